@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 use tubular::types::coordinate::Coordinate;
 use tubular::types::direction::Direction;
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use num_bigint::BigInt;
 
 pub fn bench_coordinate_creation(c: &mut Criterion) {
@@ -115,21 +115,21 @@ pub fn bench_bigint_creation(c: &mut Criterion) {
 
     group.bench_function("new_i64", |b| {
         b.iter(|| {
-            let bigint = TubularBigInt::new(black_box(42));
+            let bigint = TubularNumber::new(black_box(42));
             black_box(bigint);
         })
     });
 
     group.bench_function("zero", |b| {
         b.iter(|| {
-            let bigint = TubularBigInt::zero();
+            let bigint = TubularNumber::zero();
             black_box(bigint);
         })
     });
 
     group.bench_function("one", |b| {
         b.iter(|| {
-            let bigint = TubularBigInt::one();
+            let bigint = TubularNumber::one();
             black_box(bigint);
         })
     });
@@ -137,14 +137,14 @@ pub fn bench_bigint_creation(c: &mut Criterion) {
     group.bench_function("from_bigint_large", |b| {
         let large_bigint = BigInt::from(12345678901234567890i128);
         b.iter(|| {
-            let bigint = TubularBigInt::from_bigint(black_box(large_bigint.clone()));
+            let bigint = TubularNumber::from_bigint(black_box(large_bigint.clone()));
             black_box(bigint);
         })
     });
 
     group.bench_function("from_char", |b| {
         b.iter(|| {
-            let bigint = TubularBigInt::from_char(black_box('A'));
+            let bigint = TubularNumber::from_char(black_box('A'));
             black_box(bigint);
         })
     });
@@ -155,10 +155,10 @@ pub fn bench_bigint_creation(c: &mut Criterion) {
 pub fn bench_bigint_arithmetic(c: &mut Criterion) {
     let mut group = c.benchmark_group("bigint_arithmetic");
 
-    let a = TubularBigInt::new(1000000);
-    let b = TubularBigInt::new(123456);
-    let large_a = TubularBigInt::from_bigint(BigInt::from(12345678901234567890i128));
-    let large_b = TubularBigInt::from_bigint(BigInt::from(9876543210987654321i128));
+    let a = TubularNumber::new(1000000);
+    let b = TubularNumber::new(123456);
+    let large_a = TubularNumber::from_bigint(BigInt::from(12345678901234567890i128));
+    let large_b = TubularNumber::from_bigint(BigInt::from(9876543210987654321i128));
 
     group.bench_function("add_small", |b| {
         b.iter(|| {
@@ -203,7 +203,7 @@ pub fn bench_bigint_arithmetic(c: &mut Criterion) {
     });
 
     group.bench_function("increment", |b| {
-        let mut value = TubularBigInt::new(1000000);
+        let mut value = TubularNumber::new(1000000);
         b.iter(|| {
             value.increment();
             black_box(&value);
@@ -211,7 +211,7 @@ pub fn bench_bigint_arithmetic(c: &mut Criterion) {
     });
 
     group.bench_function("decrement", |b| {
-        let mut value = TubularBigInt::new(1000000);
+        let mut value = TubularNumber::new(1000000);
         b.iter(|| {
             value.decrement();
             black_box(&value);
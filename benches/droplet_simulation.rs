@@ -1,8 +1,9 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId, Throughput};
 use tubular::types::coordinate::Coordinate;
 use tubular::types::direction::Direction;
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use tubular::interpreter::droplet::{Droplet, DropletId};
+use tubular::interpreter::collision::CollisionIndex;
 use std::collections::HashMap;
 
 pub fn bench_droplet_creation(c: &mut Criterion) {
@@ -23,7 +24,7 @@ pub fn bench_droplet_creation(c: &mut Criterion) {
         b.iter(|| {
             let droplet = Droplet::with_value(
                 black_box(1),
-                black_box(TubularBigInt::new(42)),
+                black_box(TubularNumber::new(42)),
                 black_box(Coordinate::new(0, 0)),
                 black_box(Direction::Right)
             );
@@ -66,7 +67,7 @@ pub fn bench_droplet_movement(c: &mut Criterion) {
     group.bench_function("set_value", |b| {
         b.iter(|| {
             let mut test_droplet = droplet.clone();
-            let new_value = TubularBigInt::new(black_box(999));
+            let new_value = TubularNumber::new(black_box(999));
             test_droplet.set_value(new_value);
             black_box(test_droplet);
         })
@@ -107,6 +108,42 @@ pub fn bench_droplet_collision_detection(c: &mut Criterion) {
     group.finish();
 }
 
+pub fn bench_collision_index(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collision_index");
+
+    for count in [100, 1000, 10000].iter() {
+        group.throughput(Throughput::Elements(*count as u64));
+
+        // Every tenth droplet shares a cell with its neighbour
+        let claims: Vec<(Coordinate, DropletId)> = (0..*count as isize)
+            .map(|i| (Coordinate::new(i - (i % 10 == 1) as isize, i % 7), i as DropletId))
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("reused_index", count), &claims, |b, claims| {
+            let mut index = CollisionIndex::new();
+            b.iter(|| {
+                index.clear();
+                for &(position, id) in claims {
+                    index.claim(position, id);
+                }
+                black_box(index.collisions().count());
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("hashmap_per_tick", count), &claims, |b, claims| {
+            b.iter(|| {
+                let mut positions: HashMap<Coordinate, Vec<DropletId>> = HashMap::new();
+                for &(position, id) in claims {
+                    positions.entry(position).or_default().push(id);
+                }
+                black_box(positions.values().filter(|ids| ids.len() > 1).count());
+            })
+        });
+    }
+
+    group.finish();
+}
+
 pub fn bench_multiple_droplets(c: &mut Criterion) {
     let mut group = c.benchmark_group("multiple_droplets");
 
@@ -193,7 +230,7 @@ pub fn bench_droplet_cloning(c: &mut Criterion) {
 
     let droplet = Droplet::with_value(
         1,
-        TubularBigInt::new(123456789),
+        TubularNumber::new(123456789),
         Coordinate::new(100, 200),
         Direction::Left
     );
@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId, Throughput};
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use tubular::interpreter::memory::{Reservoir, ReservoirCoordinate};
 use tubular::types::coordinate::Coordinate;
 use std::collections::HashMap;
@@ -41,7 +41,7 @@ pub fn bench_reservoir_access_patterns(c: &mut Criterion) {
             let mut reservoir = Reservoir::new();
             for i in 0..100 {
                 let coord = ReservoirCoordinate::new(i as isize, i as isize);
-                let value = TubularBigInt::new(i as i64);
+                let value = TubularNumber::new(i as i64);
                 reservoir.put(coord, value);
             }
             black_box(reservoir);
@@ -53,7 +53,7 @@ pub fn bench_reservoir_access_patterns(c: &mut Criterion) {
             let mut reservoir = Reservoir::with_capacity(1000);
             for i in 0..1000 {
                 let coord = ReservoirCoordinate::new(i as isize, i as isize);
-                let value = TubularBigInt::new(i as i64);
+                let value = TubularNumber::new(i as i64);
                 reservoir.put(coord, value);
             }
             black_box(reservoir);
@@ -250,7 +250,7 @@ pub fn bench_reservoir_conversion(c: &mut Criterion) {
     let mut hashmap = HashMap::new();
     for i in 0..1000 {
         let coord = ReservoirCoordinate::new(i as isize, i as isize);
-        let value = TubularBigInt::new(i as i64);
+        let value = TubularNumber::new(i as i64);
         hashmap.insert(coord, value);
     }
 
@@ -280,7 +280,7 @@ fn create_test_reservoir(size: usize) -> Reservoir {
         let x = (i * 7) % 1000;
         let y = (i * 13) % 1000;
         let coord = ReservoirCoordinate::new(x as isize, y as isize);
-        let value = TubularBigInt::new(i as i64);
+        let value = TubularNumber::new(i as i64);
         reservoir.put(coord, value);
     }
     reservoir
@@ -294,9 +294,9 @@ fn create_test_reservoir_with_zeros(size: usize) -> Reservoir {
         let coord = ReservoirCoordinate::new(x as isize, y as isize);
         // Include some zero values
         let value = if i % 5 == 0 {
-            TubularBigInt::zero()
+            TubularNumber::zero()
         } else {
-            TubularBigInt::new(i as i64)
+            TubularNumber::new(i as i64)
         };
         reservoir.put(coord, value);
     }
@@ -309,7 +309,7 @@ fn create_clustered_reservoir(cell_count: usize, cluster_size: usize) -> Reservo
         let x = (i % cluster_size) as isize;
         let y = (i / cluster_size) as isize;
         let coord = ReservoirCoordinate::new(x, y);
-        let value = TubularBigInt::new(i as i64);
+        let value = TubularNumber::new(i as i64);
         reservoir.put(coord, value);
     }
     reservoir
@@ -321,7 +321,7 @@ fn create_scattered_reservoir(cell_count: usize, area_size: usize) -> Reservoir
         let x = (i * 73) % area_size;
         let y = (i * 37) % area_size;
         let coord = ReservoirCoordinate::new(x as isize, y as isize);
-        let value = TubularBigInt::new(i as i64);
+        let value = TubularNumber::new(i as i64);
         reservoir.put(coord, value);
     }
     reservoir
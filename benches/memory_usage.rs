@@ -1,6 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId, Throughput};
 use tubular::types::coordinate::Coordinate;
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use tubular::interpreter::grid::{ProgramGrid, ProgramCell};
 use tubular::interpreter::stack::DataStack;
 use tubular::interpreter::memory::{Reservoir, ReservoirCoordinate};
@@ -83,8 +83,8 @@ pub fn bench_memory_allocation_patterns(c: &mut Criterion) {
     group.bench_function("bigint_allocation", |b| {
         MEMORY_TRACKER.reset();
         b.iter(|| {
-            let bigints: Vec<TubularBigInt> = (0..1000)
-                .map(|i| TubularBigInt::new(i as i64))
+            let bigints: Vec<TubularNumber> = (0..1000)
+                .map(|i| TubularNumber::new(i as i64))
                 .collect();
             black_box(bigints);
         });
@@ -147,7 +147,7 @@ pub fn bench_memory_pressure(c: &mut Criterion) {
             b.iter(|| {
                 let mut stack = DataStack::with_capacity(size as usize);
                 for i in 0..size {
-                    stack.push(TubularBigInt::new(i as i64));
+                    stack.push(TubularNumber::new(i as i64));
                 }
                 black_box(stack);
             });
@@ -158,7 +158,7 @@ pub fn bench_memory_pressure(c: &mut Criterion) {
                 let mut reservoir = Reservoir::with_capacity(size as usize);
                 for i in 0..size {
                     let coord = ReservoirCoordinate::new(i as isize, (i * 7) % 1000);
-                    let value = TubularBigInt::new(i as i64);
+                    let value = TubularNumber::new(i as i64);
                     reservoir.put(coord, value);
                 }
                 black_box(reservoir);
@@ -178,7 +178,7 @@ pub fn bench_memory_reuse_patterns(c: &mut Criterion) {
             // Fill and empty stack multiple times
             for _ in 0..10 {
                 for i in 0..100 {
-                    stack.push(TubularBigInt::new(i));
+                    stack.push(TubularNumber::new(i));
                 }
                 for _ in 0..100 {
                     stack.pop();
@@ -198,7 +198,7 @@ pub fn bench_memory_reuse_patterns(c: &mut Criterion) {
             // Put and remove values multiple times
             for _ in 0..10 {
                 for (i, coord) in coords.iter().enumerate() {
-                    let value = TubularBigInt::new(i as i64);
+                    let value = TubularNumber::new(i as i64);
                     reservoir.put(coord.clone(), value);
                 }
                 for coord in &coords {
@@ -221,7 +221,7 @@ pub fn bench_memory_growth_patterns(c: &mut Criterion) {
             let mut size = 1;
             for _ in 0..10 {
                 for _ in 0..size {
-                    stack.push(TubularBigInt::new(1));
+                    stack.push(TubularNumber::new(1));
                 }
                 size *= 2;
             }
@@ -236,7 +236,7 @@ pub fn bench_memory_growth_patterns(c: &mut Criterion) {
             for iteration in 0..10 {
                 for i in 0..size {
                     let coord = ReservoirCoordinate::new((size * iteration + i) as isize, 0);
-                    let value = TubularBigInt::new(i as i64);
+                    let value = TubularNumber::new(i as i64);
                     reservoir.put(coord, value);
                 }
                 size *= 2;
@@ -257,7 +257,7 @@ pub fn bench_memory_fragmentation(c: &mut Criterion) {
             // Create fragmented pattern
             for i in 0..1000 {
                 if i % 3 == 0 {
-                    stack.push(TubularBigInt::new(i));
+                    stack.push(TubularNumber::new(i));
                 }
             }
             // Access random elements
@@ -275,7 +275,7 @@ pub fn bench_memory_fragmentation(c: &mut Criterion) {
             for i in 0..1000 {
                 if i % 3 == 0 {
                     let coord = ReservoirCoordinate::new(i as isize, i as isize);
-                    let value = TubularBigInt::new(i as i64);
+                    let value = TubularNumber::new(i as i64);
                     reservoir.put(coord, value);
                 }
             }
@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId, Throughput};
 use tubular::types::coordinate::Coordinate;
 use tubular::types::direction::Direction;
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use tubular::interpreter::grid::{ProgramGrid, ProgramCell};
 use tubular::interpreter::stack::DataStack;
 use tubular::interpreter::memory::{Reservoir, ReservoirCoordinate};
@@ -57,6 +57,7 @@ criterion_group!(
     bench_droplet_creation,
     bench_droplet_movement,
     bench_droplet_collision_detection,
+    bench_collision_index,
     bench_multiple_droplets,
 
     // Parser performance
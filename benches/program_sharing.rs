@@ -0,0 +1,82 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
+use tubular::parser::grid_parser::GridParser;
+use tubular::interpreter::execution::TubularInterpreter;
+use tubular::interpreter::grid::ProgramGrid;
+use tubular::interpreter::program::Program;
+
+const INSTANCES: usize = 1000;
+
+/// Compare building 1k live interpreters by cloning the grid against sharing one compiled program
+pub fn bench_concurrent_instantiation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("concurrent_instantiation");
+
+    for (name, source) in [("small", create_small_program()), ("wide", create_wide_program())] {
+        let grid = parse_program(&source);
+        let program = Program::compile(grid.clone()).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("clone_grid", name), &grid, |b, grid| {
+            b.iter(|| {
+                let instances: Vec<TubularInterpreter> = (0..INSTANCES)
+                    .map(|_| TubularInterpreter::new(black_box(grid.clone())).unwrap())
+                    .collect();
+                black_box(instances);
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("shared_program", name), &program, |b, program| {
+            b.iter(|| {
+                let instances: Vec<TubularInterpreter> = (0..INSTANCES)
+                    .map(|_| black_box(program).instantiate())
+                    .collect();
+                black_box(instances);
+            })
+        });
+
+        // Approximate heap held by the grids alone across all live instances
+        let grid_bytes = grid.size() * (std::mem::size_of::<tubular::types::coordinate::Coordinate>()
+            + std::mem::size_of::<tubular::interpreter::grid::ProgramCell>());
+        println!(
+            "{}: ~{} KiB of grid data with cloning vs ~{} KiB shared for {} instances",
+            name,
+            grid_bytes * INSTANCES / 1024,
+            grid_bytes / 1024,
+            INSTANCES
+        );
+    }
+
+    group.finish();
+}
+
+pub fn bench_shared_execution(c: &mut Criterion) {
+    let grid = parse_program(&create_small_program());
+    let program = Program::compile(grid).unwrap();
+
+    c.bench_function("shared_program_run", |b| {
+        b.iter(|| {
+            let mut interpreter = program.instantiate().with_options(false, false, Some(10000));
+            black_box(interpreter.run().unwrap());
+        })
+    });
+}
+
+fn parse_program(source: &str) -> ProgramGrid {
+    GridParser::new().parse_string(source).unwrap()
+}
+
+fn create_small_program() -> String {
+    "@\n|\n7\n:\n2\nA\nn\n!\n".to_string()
+}
+
+fn create_wide_program() -> String {
+    let mut program = String::from("@\n|\n");
+    for _ in 0..200 {
+        program.push('|');
+        program.push_str(&"-".repeat(200));
+        program.push('\n');
+    }
+    program.push_str("!\n");
+    program
+}
+
+criterion_group!(benches, bench_concurrent_instantiation, bench_shared_execution);
+criterion_main!(benches);
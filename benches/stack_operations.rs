@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId, Throughput};
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use tubular::interpreter::stack::DataStack;
 
 pub fn bench_stack_push_pop(c: &mut Criterion) {
@@ -12,7 +12,7 @@ pub fn bench_stack_push_pop(c: &mut Criterion) {
             b.iter(|| {
                 let mut stack = DataStack::new();
                 for i in 0..size {
-                    stack.push(black_box(TubularBigInt::new(i as i64)));
+                    stack.push(black_box(TubularNumber::new(i as i64)));
                 }
                 black_box(stack);
             })
@@ -21,7 +21,7 @@ pub fn bench_stack_push_pop(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("pop_only", size), size, |b, &size| {
             let mut stack = DataStack::with_capacity(size);
             for i in 0..size {
-                stack.push(TubularBigInt::new(i as i64));
+                stack.push(TubularNumber::new(i as i64));
             }
 
             b.iter(|| {
@@ -37,7 +37,7 @@ pub fn bench_stack_push_pop(c: &mut Criterion) {
             b.iter(|| {
                 let mut stack = DataStack::new();
                 for i in 0..size {
-                    stack.push(black_box(TubularBigInt::new(i as i64)));
+                    stack.push(black_box(TubularNumber::new(i as i64)));
                 }
                 for _ in 0..size {
                     let value = stack.pop();
@@ -55,7 +55,7 @@ pub fn bench_stack_operations(c: &mut Criterion) {
 
     let mut stack = DataStack::new();
     for i in 0..100 {
-        stack.push(TubularBigInt::new(i));
+        stack.push(TubularNumber::new(i));
     }
 
     group.bench_function("peek", |b| {
@@ -110,7 +110,7 @@ pub fn bench_stack_peek_depth(c: &mut Criterion) {
 
     let mut stack = DataStack::new();
     for i in 0..1000 {
-        stack.push(TubularBigInt::new(i));
+        stack.push(TubularNumber::new(i));
     }
 
     for depth in [0, 1, 5, 10, 50, 100].iter() {
@@ -128,7 +128,7 @@ pub fn bench_stack_peek_depth(c: &mut Criterion) {
 pub fn bench_stack_bulk_operations(c: &mut Criterion) {
     let mut group = c.benchmark_group("stack_bulk_operations");
 
-    let values: Vec<TubularBigInt> = (0..100).map(|i| TubularBigInt::new(i)).collect();
+    let values: Vec<TubularNumber> = (0..100).map(|i| TubularNumber::new(i)).collect();
 
     group.bench_function("push_n", |b| {
         b.iter(|| {
@@ -142,7 +142,7 @@ pub fn bench_stack_bulk_operations(c: &mut Criterion) {
         b.iter(|| {
             let mut stack = DataStack::new();
             for i in 0..100 {
-                stack.push(TubularBigInt::new(i));
+                stack.push(TubularNumber::new(i));
             }
             let popped = stack.pop_n(black_box(50));
             black_box(popped);
@@ -153,7 +153,7 @@ pub fn bench_stack_bulk_operations(c: &mut Criterion) {
         b.iter(|| {
             let mut stack = DataStack::new();
             for i in 0..100 {
-                stack.push(TubularBigInt::new(i));
+                stack.push(TubularNumber::new(i));
             }
             stack.clear();
             black_box(stack);
@@ -164,7 +164,7 @@ pub fn bench_stack_bulk_operations(c: &mut Criterion) {
         b.iter(|| {
             let mut stack = DataStack::new();
             for i in 0..100 {
-                stack.push(TubularBigInt::new(i));
+                stack.push(TubularNumber::new(i));
             }
             stack.truncate(black_box(50));
             black_box(stack);
@@ -189,7 +189,7 @@ pub fn bench_stack_capacity(c: &mut Criterion) {
             b.iter(|| {
                 let mut stack = DataStack::with_capacity(capacity);
                 for i in 0..capacity {
-                    stack.push(black_box(TubularBigInt::new(i as i64)));
+                    stack.push(black_box(TubularNumber::new(i as i64)));
                 }
                 black_box(stack);
             })
@@ -204,7 +204,7 @@ pub fn bench_stack_access_patterns(c: &mut Criterion) {
 
     let mut stack = DataStack::new();
     for i in 0..1000 {
-        stack.push(TubularBigInt::new(i));
+        stack.push(TubularNumber::new(i));
     }
 
     group.bench_function("get_index", |b| {
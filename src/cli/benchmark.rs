@@ -0,0 +1,257 @@
+use std::time::{Duration, Instant};
+
+/// Fewest samples taken when the sample count is chosen automatically
+pub const MIN_SAMPLES: usize = 10;
+/// Most samples taken when the sample count is chosen automatically
+pub const MAX_SAMPLES: usize = 10_000;
+/// Resamples used to estimate the confidence interval of the mean
+const BOOTSTRAP_RESAMPLES: usize = 1_000;
+
+/// Samples outside the Tukey fences of the interquartile range
+///
+/// Mild outliers lie more than 1.5 IQR beyond the quartiles, severe ones
+/// more than 3 IQR. They are reported rather than dropped, since a slow run
+/// is sometimes the interesting one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outliers {
+    pub low_severe: usize,
+    pub low_mild: usize,
+    pub high_mild: usize,
+    pub high_severe: usize,
+}
+
+impl Outliers {
+    pub fn total(&self) -> usize {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+}
+
+/// Summary statistics over a set of timing samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    pub samples: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub std_dev: Duration,
+    /// 95% bootstrap confidence interval of the mean
+    pub confidence_interval: (Duration, Duration),
+    pub outliers: Outliers,
+}
+
+impl Statistics {
+    /// Summarise `samples`, which must not be empty
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut sorted: Vec<f64> = samples.iter().map(|d| d.as_secs_f64()).collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let mean = mean(&sorted);
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (sorted.len() - 1) as f64
+        } else {
+            0.0
+        };
+
+        Statistics {
+            samples: sorted.len(),
+            mean: Duration::from_secs_f64(mean),
+            median: Duration::from_secs_f64(percentile(&sorted, 50.0)),
+            p95: Duration::from_secs_f64(percentile(&sorted, 95.0)),
+            min: Duration::from_secs_f64(sorted[0]),
+            max: Duration::from_secs_f64(sorted[sorted.len() - 1]),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            confidence_interval: bootstrap_mean_interval(&sorted),
+            outliers: classify_outliers(&sorted),
+        }
+    }
+
+    /// Relative change of the median from `baseline`, e.g. -0.25 for 25% faster
+    pub fn median_change(&self, baseline: &Statistics) -> f64 {
+        let base = baseline.median.as_secs_f64();
+        if base == 0.0 {
+            0.0
+        } else {
+            self.median.as_secs_f64() / base - 1.0
+        }
+    }
+
+    /// Whether the confidence intervals of the two means do not overlap
+    pub fn differs_from(&self, other: &Statistics) -> bool {
+        self.confidence_interval.1 < other.confidence_interval.0
+            || other.confidence_interval.1 < self.confidence_interval.0
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Linearly interpolated percentile of sorted values
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    let rank = percent / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+fn classify_outliers(sorted: &[f64]) -> Outliers {
+    let (q1, q3) = (percentile(sorted, 25.0), percentile(sorted, 75.0));
+    let iqr = q3 - q1;
+    let mut outliers = Outliers::default();
+    for &x in sorted {
+        if x < q1 - 3.0 * iqr {
+            outliers.low_severe += 1;
+        } else if x < q1 - 1.5 * iqr {
+            outliers.low_mild += 1;
+        } else if x > q3 + 3.0 * iqr {
+            outliers.high_severe += 1;
+        } else if x > q3 + 1.5 * iqr {
+            outliers.high_mild += 1;
+        }
+    }
+    outliers
+}
+
+/// 2.5th and 97.5th percentiles of the means of resampled data
+///
+/// Resampling uses a fixed-seed xorshift generator so repeated reports over
+/// the same samples agree.
+fn bootstrap_mean_interval(values: &[f64]) -> (Duration, Duration) {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next_index = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % values.len() as u64) as usize
+    };
+
+    let mut means: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| (0..values.len()).map(|_| values[next_index()]).sum::<f64>() / values.len() as f64)
+        .collect();
+    means.sort_by(|a, b| a.total_cmp(b));
+
+    (
+        Duration::from_secs_f64(percentile(&means, 2.5)),
+        Duration::from_secs_f64(percentile(&means, 97.5)),
+    )
+}
+
+/// Decides how many times to run a routine and times each run
+///
+/// Unless a fixed sample count is set, the warm-up runs estimate how long one
+/// run takes and enough samples are taken to fill the measurement time,
+/// between `MIN_SAMPLES` and `MAX_SAMPLES`.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    warm_up_runs: usize,
+    measurement_time: Duration,
+    sample_count: Option<usize>,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler { warm_up_runs: 3, measurement_time: Duration::from_secs(3), sample_count: None }
+    }
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_warm_up_runs(mut self, runs: usize) -> Self {
+        self.warm_up_runs = runs;
+        self
+    }
+
+    /// Time budget used to choose the sample count automatically
+    pub fn with_measurement_time(mut self, time: Duration) -> Self {
+        self.measurement_time = time;
+        self
+    }
+
+    /// Take exactly this many samples instead of choosing automatically
+    pub fn with_sample_count(mut self, count: Option<usize>) -> Self {
+        self.sample_count = count.map(|count| count.max(1));
+        self
+    }
+
+    /// Samples to take given an estimate of one run's duration
+    pub fn sample_count(&self, estimate: Duration) -> usize {
+        if let Some(count) = self.sample_count {
+            return count;
+        }
+        if estimate.is_zero() {
+            return MAX_SAMPLES;
+        }
+        let fits = self.measurement_time.as_secs_f64() / estimate.as_secs_f64();
+        (fits as usize).clamp(MIN_SAMPLES, MAX_SAMPLES)
+    }
+
+    /// Warm up, then time each run of `routine`, returning the samples and run outputs
+    ///
+    /// With no warm-up runs one untimed pilot run provides the estimate.
+    pub fn run<T, E>(&self, mut routine: impl FnMut() -> Result<T, E>) -> Result<(Vec<Duration>, Vec<T>), E> {
+        let warm_up_start = Instant::now();
+        let warm_up_runs = self.warm_up_runs.max(usize::from(self.sample_count.is_none()));
+        for _ in 0..warm_up_runs {
+            routine()?;
+        }
+        let estimate = warm_up_start.elapsed() / warm_up_runs.max(1) as u32;
+
+        let count = self.sample_count(estimate);
+        let mut samples = Vec::with_capacity(count);
+        let mut outputs = Vec::with_capacity(count);
+        for _ in 0..count {
+            let start = Instant::now();
+            let output = routine()?;
+            samples.push(start.elapsed());
+            outputs.push(output);
+        }
+        Ok((samples, outputs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn test_statistics_median_percentiles_and_outliers() {
+        let stats = Statistics::from_samples(&millis(&[10, 11, 12, 10, 11, 12, 10, 11, 12, 100]));
+
+        assert_eq!(stats.samples, 10);
+        assert_eq!(stats.median, Duration::from_millis(11));
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.outliers, Outliers { high_severe: 1, ..Outliers::default() });
+        assert!(stats.p95 > Duration::from_millis(12));
+
+        let (low, high) = stats.confidence_interval;
+        assert!(low <= stats.mean && stats.mean <= high);
+    }
+
+    #[test]
+    fn test_sample_count_fills_measurement_time() {
+        let sampler = Sampler::new().with_measurement_time(Duration::from_secs(1));
+        assert_eq!(sampler.sample_count(Duration::from_millis(10)), 100);
+        assert_eq!(sampler.sample_count(Duration::from_secs(5)), MIN_SAMPLES);
+        assert_eq!(sampler.sample_count(Duration::ZERO), MAX_SAMPLES);
+        assert_eq!(sampler.with_sample_count(Some(7)).sample_count(Duration::ZERO), 7);
+    }
+
+    #[test]
+    fn test_comparison_detects_clear_difference() {
+        let fast = Statistics::from_samples(&millis(&[10, 10, 11, 10, 11, 10, 10, 11, 10, 10]));
+        let slow = Statistics::from_samples(&millis(&[20, 21, 20, 20, 21, 20, 21, 20, 20, 20]));
+
+        assert!(fast.differs_from(&slow));
+        assert!(fast.median_change(&slow) < -0.4);
+        assert!(!fast.differs_from(&fast));
+    }
+}
@@ -3,14 +3,53 @@ use anyhow::Result;
 use std::fs;
 use std::env;
 use std::time::{Duration, Instant};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 // Import necessary modules
 use crate::parser::grid_parser::GridParser;
 use crate::parser::validator::ProgramValidator;
-use crate::interpreter::execution::TubularInterpreter;
-use crate::types::error::InterpreterError;
-use crate::cli::output::{OutputFormatter, TraceConfig, TraceLevel, TraceFormat, TraceOperation};
+use crate::parser::stack_checker::StackChecker;
+use crate::parser::formatter::GridFormatter;
+use crate::parser::lint::{LintLevel, Linter, Strictness};
+use crate::parser::flow_graph::ControlFlowGraph;
+use crate::parser::inspect::ProgramReport;
+use crate::parser::transform::GridTransform;
+use crate::parser::minify::minify;
+use crate::codegen::c::emit_c;
+use crate::interpreter::builder::InterpreterBuilder;
+use crate::interpreter::interrupt::InterruptHandle;
+use crate::interpreter::collision::CollisionPolicy;
+use crate::interpreter::execution::{ExecutionLimits, TubularInterpreter};
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::metrics::Metrics;
+use crate::interpreter::scheduler::SchedulerKind;
+use crate::interpreter::watch::{WatchAction, WatchExpr};
+use crate::operations::io::{EofPolicy, InputMode, OutputBuffering, ReaderInput, StdoutSink, StringInput};
+use crate::types::error::{ErrorSeverity, ErrorType, InterpreterError};
+use crate::cli::debugger::Debugger;
+use crate::cli::lsp::LanguageServer;
+use crate::cli::repl::Repl;
+use crate::cli::test_runner::{self, GoldenTest, TestOutcome, TestRunner};
+use crate::cli::visualizer::Visualizer;
+use crate::cli::benchmark::{Sampler, Statistics};
+use crate::cli::diagnostics::{diagnostics_json, diagnostics_sarif, Diagnostic};
+use crate::cli::exit_code::ExitCode;
+use crate::cli::gallery::{find_example, EXAMPLES};
+use crate::cli::line_editor::EditorInput;
+use crate::cli::output::{OutputFormatter, TraceConfig, TraceDocument, TraceLevel, TraceFormat, TraceOperation, TraceEvent};
+use crate::cli::replay::TraceReplay;
+use crate::cli::scaffold;
+use crate::cli::project::{find_project_file, load_symbol_map};
+use crate::parser::symbol_map::SymbolMap;
+
+/// `eprintln!` for status messages and diagnostics, silenced by `--quiet`
+macro_rules! diag {
+    ($cli:expr, $($arg:tt)*) => {
+        if !$cli.quiet {
+            eprintln!($($arg)*);
+        }
+    };
+}
 
 /// Environment variable configuration
 #[derive(Debug, Clone)]
@@ -19,7 +58,10 @@ pub struct EnvConfig {
     pub verbose: bool,
     pub trace: bool,
     pub benchmark: bool,
-    pub strict: bool,
+    /// Validation strictness when `validate` is given none
+    pub strictness: Strictness,
+    /// Lint rule levels in the order they apply
+    pub lint_levels: Vec<(String, LintLevel)>,
     pub trace_config: TraceConfig,
 }
 
@@ -30,7 +72,8 @@ impl Default for EnvConfig {
             verbose: false,
             trace: false,
             benchmark: false,
-            strict: false,
+            strictness: Strictness::Default,
+            lint_levels: Vec::new(),
             trace_config: TraceConfig::default(),
         }
     }
@@ -71,8 +114,29 @@ impl EnvConfig {
         // Load TUBULAR_BENCHMARK
         config.benchmark = parse_bool_env("TUBULAR_BENCHMARK")?;
 
-        // Load TUBULAR_STRICT
-        config.strict = parse_bool_env("TUBULAR_STRICT")?;
+        // Load TUBULAR_STRICT, then TUBULAR_STRICTNESS, which takes precedence
+        if parse_bool_env("TUBULAR_STRICT")? {
+            config.strictness = Strictness::Strict;
+        }
+        if let Ok(level) = env::var("TUBULAR_STRICTNESS") {
+            config.strictness = level.trim().parse().map_err(|e: String| {
+                eprintln!("Error: Invalid TUBULAR_STRICTNESS value '{}'. {}", level, e);
+                anyhow::anyhow!("Invalid TUBULAR_STRICTNESS value")
+            })?;
+        }
+
+        // Load TUBULAR_LINT_ALLOW, TUBULAR_LINT_WARN and TUBULAR_LINT_DENY
+        for (var_name, level) in [
+            ("TUBULAR_LINT_ALLOW", LintLevel::Allow),
+            ("TUBULAR_LINT_WARN", LintLevel::Warn),
+            ("TUBULAR_LINT_DENY", LintLevel::Deny),
+        ] {
+            if let Ok(rules) = env::var(var_name) {
+                config.lint_levels.extend(
+                    rules.split(',').map(str::trim).filter(|rule| !rule.is_empty()).map(|rule| (rule.to_string(), level)),
+                );
+            }
+        }
 
         Ok(config)
     }
@@ -83,6 +147,9 @@ impl EnvConfig {
         if cli.verbose {
             self.verbose = true;
         }
+        if cli.quiet {
+            self.verbose = false;
+        }
         if cli.trace {
             self.trace = true;
         }
@@ -106,7 +173,7 @@ impl EnvConfig {
            cli.trace_droplets.is_some() || cli.trace_operations.is_some() ||
            cli.trace_ticks.is_some() || cli.trace_max_events.is_some() ||
            cli.trace_performance || cli.trace_memory || cli.trace_stack ||
-           cli.trace_subroutines || cli.trace_output.is_some() {
+           cli.trace_subroutines || cli.trace_output.is_some() || !cli.trace_watch.is_empty() {
             trace_config.level = TraceLevel::Detailed; // Default when trace options are used
         }
 
@@ -126,13 +193,14 @@ impl EnvConfig {
                 "compact" => TraceFormat::Compact,
                 "detailed" => TraceFormat::Detailed,
                 "json" => TraceFormat::Json,
+                "chrome" => TraceFormat::Chrome,
                 _ => TraceFormat::Compact,
             };
         }
 
         // Apply droplet filter
         if let Some(ref droplets_str) = cli.trace_droplets {
-            let droplet_ids: std::collections::HashSet<u64> = droplets_str
+            let droplet_ids: std::collections::BTreeSet<u64> = droplets_str
                 .split(',')
                 .filter_map(|s| s.trim().parse().ok())
                 .collect();
@@ -143,7 +211,7 @@ impl EnvConfig {
 
         // Apply operation filter
         if let Some(ref operations_str) = cli.trace_operations {
-            let operations: std::collections::HashSet<TraceOperation> = operations_str
+            let operations: std::collections::BTreeSet<TraceOperation> = operations_str
                 .split(',')
                 .filter_map(|s| {
                     match s.trim().to_lowercase().as_str() {
@@ -152,12 +220,14 @@ impl EnvConfig {
                         "stack_op" => Some(TraceOperation::StackOp),
                         "memory_op" => Some(TraceOperation::MemoryOp),
                         "arithmetic_op" => Some(TraceOperation::ArithmeticOp),
+                        "bitwise_op" => Some(TraceOperation::BitwiseOp),
                         "io_op" => Some(TraceOperation::IoOp),
                         "subroutine_call" => Some(TraceOperation::SubroutineCall),
                         "subroutine_return" => Some(TraceOperation::SubroutineReturn),
                         "direction_change" => Some(TraceOperation::DirectionChange),
                         "collision" => Some(TraceOperation::Collision),
                         "droplet_lifecycle" => Some(TraceOperation::DropletLifecycle),
+                        "watch" => Some(TraceOperation::Watch),
                         _ => None,
                     }
                 })
@@ -205,7 +275,7 @@ impl EnvConfig {
         eprintln!("  Verbose: {}", self.verbose);
         eprintln!("  Trace: {}", self.trace);
         eprintln!("  Benchmark: {}", self.benchmark);
-        eprintln!("  Strict: {}", self.strict);
+        eprintln!("  Strictness: {}", self.strictness);
 
         // Print trace configuration if trace is enabled
         if self.trace || self.trace_config.level != TraceLevel::Basic {
@@ -232,6 +302,21 @@ impl EnvConfig {
     }
 }
 
+/// A positive, finite number of ticks per second for `--tick-rate`
+fn parse_tick_rate(text: &str) -> std::result::Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(hz) if hz.is_finite() && hz > 0.0 => Ok(hz),
+        _ => Err(format!("expected a positive number of ticks per second, got '{}'", text)),
+    }
+}
+
+/// A finite, non-negative number of seconds, fractions allowed
+fn parse_seconds(text: &str) -> std::result::Result<Duration, String> {
+    text.parse::<f64>().ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("expected a non-negative number of seconds, got '{}'", text))
+}
+
 /// Parse a boolean environment variable with support for multiple formats
 fn parse_bool_env(var_name: &str) -> Result<bool> {
     match env::var(var_name) {
@@ -266,6 +351,10 @@ ENVIRONMENT VARIABLES:
     TUBULAR_TRACE <BOOL>           Enable trace mode by default (default: false)
     TUBULAR_BENCHMARK <BOOL>       Enable benchmark mode by default (default: false)
     TUBULAR_STRICT <BOOL>          Enable strict validation by default (default: false)
+    TUBULAR_STRICTNESS <LEVEL>     Default validation strictness: default, strict, pedantic
+    TUBULAR_LINT_ALLOW <RULES>     Lint rules to silence (comma-separated)
+    TUBULAR_LINT_WARN <RULES>      Lint rules to report as warnings (comma-separated)
+    TUBULAR_LINT_DENY <RULES>      Lint rules to report as errors (comma-separated)
 
 TRACE OPTIONS:
     --trace-level <LEVEL>          Trace detail level: basic, detailed, verbose
@@ -279,10 +368,14 @@ TRACE OPTIONS:
     --trace-stack                  Include stack state changes in traces
     --trace-subroutines            Include subroutine call tracking in traces
     --trace-output <FILE>          Save trace output to specified file
+    --trace-watch <EXPR>           Trace changes to a reservoir cell or the stack (repeatable)
 
 Operation types for filtering: movement, value_change, stack_op, memory_op,
-arithmetic_op, io_op, subroutine_call, subroutine_return, direction_change,
-collision, droplet_lifecycle
+arithmetic_op, bitwise_op, io_op, subroutine_call, subroutine_return,
+direction_change, collision, droplet_lifecycle, watch
+
+Watch expressions: \"reservoir (X,Y)\", \"stack depth\" or \"stack top\", optionally
+followed by a comparison such as \"> 10\" to trace only when it becomes true
 
 Boolean values accept: true/false, 1/0, yes/no, on/off, y/n
 
@@ -290,17 +383,41 @@ All environment variables can be overridden by their corresponding command-line
 ")]
 pub struct Cli {
     /// Input file to execute
-    #[arg(help = "Input file to execute. If not provided, runs in interactive mode.")]
+    #[arg(help = "Input file to execute, or - to read the program from stdin. If not provided, runs in interactive mode.")]
     pub file: Option<String>,
 
+    /// Program source given on the command line
+    #[arg(short = 'e', long, value_name = "PROGRAM", conflicts_with = "file", help = "Run PROGRAM instead of a file. \\n starts a new row, \\t is a tab and \\\\ a backslash, e.g. -e '@\\n7\\nn\\n!'.")]
+    pub eval: Option<String>,
+
+    /// Arguments for the program, after `--`
+    #[arg(last = true, value_name = "ARGS", help = "Arguments passed to the program after `--`; the program reads them with the 'a' operation.")]
+    pub program_args: Vec<String>,
+
     /// Enable verbose output
     #[arg(short, long, help = "Enable verbose output. Overrides TUBULAR_VERBOSE environment variable.")]
     pub verbose: bool,
 
+    /// Suppress status messages and diagnostics
+    #[arg(short, long, conflicts_with = "verbose", help = "Suppress status messages and diagnostics on stderr. Program output always goes to stdout and nothing else does; the exit code still reports failures.")]
+    pub quiet: bool,
+
     /// Maximum number of ticks to execute
     #[arg(short, long, help = "Maximum number of ticks to execute. Overrides TUBULAR_TICK_LIMIT environment variable.")]
     pub ticks: Option<u64>,
 
+    /// Maximum wall-clock time in milliseconds
+    #[arg(long = "time-limit", value_name = "MS", help = "Stop the program after MS milliseconds of wall-clock time (default: 5000; 0 for no limit)")]
+    pub time_limit: Option<u64>,
+
+    /// Ticks per second to run at
+    #[arg(long = "tick-rate", value_name = "HZ", value_parser = parse_tick_rate, help = "Run at most HZ ticks a second, pausing between ticks, for clocks, games and other animated programs; fractions such as 0.5 are allowed. The pauses count toward --time-limit, so pass --time-limit 0 for programs meant to run indefinitely")]
+    pub tick_rate: Option<f64>,
+
+    /// Warn when a run nears its tick or time limit
+    #[arg(long = "soft-limits", help = "Warn on stderr when the program reaches 80% of its tick or time limit")]
+    pub soft_limits: bool,
+
     /// Enable step-by-step execution tracing
     #[arg(long, help = "Enable step-by-step execution tracing. Overrides TUBULAR_TRACE environment variable.")]
     pub trace: bool,
@@ -313,8 +430,8 @@ pub struct Cli {
     #[arg(long = "trace-level", value_parser = ["basic", "detailed", "verbose"], help = "Trace level of detail: basic, detailed, or verbose")]
     pub trace_level: Option<String>,
 
-    /// Trace output format (compact, detailed, json)
-    #[arg(long = "trace-format", value_parser = ["compact", "detailed", "json"], help = "Trace output format: compact, detailed, or json")]
+    /// Trace output format (compact, detailed, json, chrome)
+    #[arg(long = "trace-format", value_parser = ["compact", "detailed", "json", "chrome"], help = "Trace output format: compact, detailed, json, or chrome (chrome://tracing / Perfetto JSON)")]
     pub trace_format: Option<String>,
 
     /// Filter traces by specific droplet IDs (comma-separated)
@@ -322,7 +439,7 @@ pub struct Cli {
     pub trace_droplets: Option<String>,
 
     /// Filter traces by operation types (comma-separated)
-    #[arg(long = "trace-operations", help = "Filter traces by operation types (comma-separated): movement, value_change, stack_op, memory_op, arithmetic_op, io_op, subroutine_call, subroutine_return, direction_change, collision, droplet_lifecycle")]
+    #[arg(long = "trace-operations", help = "Filter traces by operation types (comma-separated): movement, value_change, stack_op, memory_op, arithmetic_op, bitwise_op, io_op, subroutine_call, subroutine_return, direction_change, collision, droplet_lifecycle, watch")]
     pub trace_operations: Option<String>,
 
     /// Filter traces by tick range (start-end)
@@ -353,10 +470,184 @@ pub struct Cli {
     #[arg(long = "trace-output", help = "Save trace output to specified file")]
     pub trace_output: Option<String>,
 
+    /// Record a trace event whenever a watched value changes
+    #[arg(long = "trace-watch", value_name = "EXPR", help = "Trace changes to a reservoir cell or the stack, e.g. \"reservoir (3,4)\" or \"stack depth > 10\" (repeatable)")]
+    pub trace_watch: Vec<WatchExpr>,
+
+    /// Seed for the deterministic pseudo-input operation (i)
+    #[arg(long = "pseudo-input-seed", default_value = "0", help = "Seed for the deterministic pseudo-input sequence read by the 'i' operation")]
+    pub pseudo_input_seed: u64,
+
+    /// Seed for the random operation (r)
+    #[arg(long = "seed", help = "Seed for the random numbers drawn by the 'r' operation, for reproducible runs (default: different every run)")]
+    pub seed: Option<u64>,
+
+    /// Compute droplet commands on all cores
+    #[cfg(feature = "parallel")]
+    #[arg(long = "parallel", help = "Compute droplet commands on all cores when many droplets are active; output is unchanged")]
+    pub parallel: bool,
+
+    /// Compile hot corridors to native code
+    #[cfg(feature = "jit")]
+    #[arg(long = "jit", help = "Compile straight pipe corridors to native code once a droplet keeps passing through them; output and tick counts are unchanged")]
+    pub jit: bool,
+
+    /// Wrap droplets around the grid edges
+    #[arg(long = "wrap", help = "Toroidal grid: droplets leaving the bounding box re-enter on the opposite side instead of being destroyed")]
+    pub wrap: bool,
+
+    /// Order droplets are processed in within a tick
+    #[arg(long = "schedule", value_name = "ORDER", value_parser = ["fifo", "id", "position"], conflicts_with = "deterministic", help = "Order in which droplets run their cells each tick, which decides whose stack operations, input and output come first: ascending droplet ID (id, the default), the order droplets entered the program (fifo) or top to bottom, then left to right (position)")]
+    pub schedule: Option<String>,
+
+    /// Give every droplet its own stack
+    #[arg(long = "local-stacks", help = "Enable the local stack extension: every droplet carries its own stack, used by { (push the value), } (pop into the value), ( (take the top of the shared stack) and ) (give the top back); without it those operations stop the program with an error")]
+    pub local_stacks: bool,
+
+    /// Read runs of digits as one number
+    #[arg(long = "multi-digit", help = "Consecutive digits on a droplet's path form one number, so 123 sets the value to 123 instead of 3; programs can ask for this themselves with a %multidigit line")]
+    pub multi_digit: bool,
+
+    /// What happens when droplets collide
+    #[arg(long = "collisions", value_name = "POLICY", value_parser = ["destroy-all", "merge-sum", "merge-product", "keep-oldest", "bounce"], help = "What happens when droplets move into the same cell: all are destroyed (destroy-all, the default), the oldest carries on with the sum or product of their values (merge-sum, merge-product), the oldest carries on alone (keep-oldest), or they stay where they were and turn around (bounce); overrides a %collisions line in the program")]
+    pub collisions: Option<String>,
+
+    /// How ? and ?? divide up their input
+    #[arg(long = "input-mode", value_name = "MODE", value_parser = ["chars", "lines", "numbers"], help = "How ? and ?? divide up their input: every character in turn, with ?? reading the rest of the line (chars, the default), a line per read, keeping only its first character for ? (lines) or whitespace-separated integers for ??, stopping the program on anything else (numbers)")]
+    pub input_mode: Option<String>,
+
+    /// What ? reads at end of input
+    #[arg(long = "eof", value_name = "POLICY", value_parser = ["newline", "zero", "minus-one", "error"], help = "What ? reads once input runs out: 10 (newline, the default), 0 (zero), -1 (minus-one), or nothing, stopping the program with an error (error); overrides an %eof line in the program")]
+    pub eof: Option<String>,
+
+    /// Make output and traces reproducible across machines
+    #[arg(long = "deterministic", help = "Reproducible runs: process droplets in ascending ID order, seed 'r' with 0 unless --seed is given, and ignore the wall-clock limit so only --ticks stops the program")]
+    pub deterministic: bool,
+
+    /// Let the file operations read and write files
+    #[arg(long = "allow-fs", help = "Allow the file operations (O, I, W, X) to open, read and write files; without it they stop the program with an error")]
+    pub allow_fs: bool,
+
+    /// Let the network operations open TCP connections
+    #[arg(long = "allow-net", help = "Allow the experimental network operations (T, E, V) to connect to TCP servers; without it they stop the program with an error")]
+    pub allow_net: bool,
+
+    /// Longest a TCP connection attempt may take
+    #[arg(long = "connect-timeout", value_name = "MS", requires = "allow_net", value_parser = clap::value_parser!(u64).range(1..), help = "Give up a TCP connection (T) after MS milliseconds instead of 5000; the program waits meanwhile, even past --time-limit")]
+    pub connect_timeout: Option<u64>,
+
+    /// Let the program rewrite its own cells
+    #[arg(long = "allow-self-modify", help = "Allow 'p' to write characters into the program's own cells while it runs; without it 'p' stops the program with an error")]
+    pub allow_self_modify: bool,
+
+    /// Warn when a droplet value grows beyond this many decimal digits
+    #[arg(long = "warn-digits", help = "Warn once when any droplet, stack or reservoir value exceeds this many decimal digits")]
+    pub warn_digits: Option<usize>,
+
+    /// Symbol aliases to parse programs with
+    #[arg(long = "symbol-map", value_name = "FILE", help = "Read symbol aliases from the [symbols] table of this TOML file instead of the nearest tubular.toml")]
+    pub symbol_map: Option<String>,
+
+    /// Fail runs that issue warnings
+    #[arg(long = "deny-warnings", help = "Exit with status 3 when a run that otherwise succeeds issues warnings, such as --warn-digits or --soft-limits")]
+    pub deny_warnings: bool,
+
+    /// Fail when more droplets than this are alive at once
+    #[arg(long = "max-droplets", value_name = "N", help = "Stop with an error when more than N droplets are alive at once")]
+    pub max_droplets: Option<usize>,
+
+    /// Fail when the data stack grows deeper than this
+    #[arg(long = "max-stack", value_name = "N", help = "Stop with an error when the data stack holds more than N values")]
+    pub max_stack: Option<usize>,
+
+    /// Fail when more reservoir cells than this are in use
+    #[arg(long = "max-reservoir", value_name = "N", help = "Stop with an error when more than N reservoir cells are in use")]
+    pub max_reservoir: Option<usize>,
+
+    /// Render program output as a hex dump instead of raw bytes
+    #[arg(long = "output-hexdump", num_args = 0..=1, require_equals = true, default_missing_value = "text", value_parser = ["text", "json"], help = "Render program output as an offset/hex/ASCII dump instead of raw bytes; use --output-hexdump=json for base64 JSON")]
+    pub output_hexdump: Option<String>,
+
+    /// Stream program output to stdout while the program runs
+    #[arg(long = "output-buffering", value_name = "MODE", conflicts_with = "output_hexdump", help = "Write output while the program runs: after each newline (line), immediately (none) or in large blocks (full); buffered output is flushed when the program stops for any reason")]
+    pub output_buffering: Option<OutputBuffering>,
+
+    /// Report the run as text or as one JSON document
+    #[arg(long = "output-format", value_name = "FORMAT", default_value = "text", value_parser = ["text", "json"], conflicts_with_all = ["output_hexdump", "output_buffering"], help = "Print program output as text, or print one JSON document with the output, status, exit code, tick count, peak droplets, warnings and timing (json), also when the program fails to parse or validate")]
+    pub output_format: String,
+
+    /// Print limits versus actual peak usage after execution
+    #[arg(long = "report-usage", num_args = 0..=1, require_equals = true, default_missing_value = "table", value_parser = ["table", "json"], conflicts_with_all = ["output_hexdump", "output_buffering"], help = "Print a table of configured limits versus actual peak usage after execution; --report-usage=json prints the --output-format json report instead, which lists the usage under \"resource_usage\"")]
+    pub report_usage: Option<String>,
+
+    /// Fold constant stack arithmetic before the run
+    #[arg(long = "optimize", help = "Fold digit pushes and the stack arithmetic that consumes them on fixed paths into precomputed values before the run; output and tick counts are unchanged")]
+    pub optimize: bool,
+
+    /// Print the folds the optimizer applied after execution
+    #[arg(long = "opt-report", help = "Print every constant fold applied to the program after execution; implies --optimize")]
+    pub opt_report: bool,
+
+    /// Print the grid annotated with collision counts after execution
+    #[arg(long = "collision-map", help = "Print the program grid annotated with per-cell collision counts after execution")]
+    pub collision_map: bool,
+
+    /// Print the hottest cells and operations after execution
+    #[arg(long = "profile", value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "10", help = "Count visits and time per cell and operation, then print the N hottest cells after execution (default 10)")]
+    pub profile: Option<usize>,
+
+    /// Write execution counters in Prometheus text format when the program stops
+    #[arg(long = "metrics-file", value_name = "PATH", help = "Write tick, droplet, collision, stack and I/O counters to PATH in Prometheus text format when execution stops")]
+    pub metrics_file: Option<String>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+/// `--allow`, `--warn` and `--deny` rules of `tubular lint`, in the order given
+#[derive(Debug, Clone, Default)]
+pub struct LintLevelArgs {
+    pub levels: Vec<(String, LintLevel)>,
+}
+
+/// Flag, level and help text of each lint level option
+const LINT_LEVEL_FLAGS: [(&str, LintLevel, &str); 3] = [
+    ("allow", LintLevel::Allow, "Silence a rule, or `all`. Overrides TUBULAR_LINT_ALLOW and earlier flags."),
+    ("warn", LintLevel::Warn, "Report a rule as a warning, or `all`. Overrides TUBULAR_LINT_WARN and earlier flags."),
+    ("deny", LintLevel::Deny, "Report a rule as an error and fail, or `all`. Overrides TUBULAR_LINT_DENY and earlier flags."),
+];
+
+impl clap::FromArgMatches for LintLevelArgs {
+    fn from_arg_matches(matches: &clap::ArgMatches) -> std::result::Result<Self, clap::Error> {
+        // The flags are separate arguments, so their positions restore the order they were given in
+        let mut levels = Vec::new();
+        for (id, level, _) in LINT_LEVEL_FLAGS {
+            if let (Some(indices), Some(rules)) = (matches.indices_of(id), matches.get_many::<String>(id)) {
+                levels.extend(indices.zip(rules).map(|(index, rule)| (index, rule.clone(), level)));
+            }
+        }
+        levels.sort_by_key(|(index, ..)| *index);
+        Ok(LintLevelArgs { levels: levels.into_iter().map(|(_, rule, level)| (rule, level)).collect() })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &clap::ArgMatches) -> std::result::Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
+
+impl clap::Args for LintLevelArgs {
+    fn augment_args(command: clap::Command) -> clap::Command {
+        LINT_LEVEL_FLAGS.into_iter().fold(command, |command, (id, _, help)| {
+            command.arg(clap::Arg::new(id).long(id).value_name("RULE").action(clap::ArgAction::Append).help(help))
+        })
+    }
+
+    fn augment_args_for_update(command: clap::Command) -> clap::Command {
+        Self::augment_args(command)
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Validate program syntax without execution
@@ -365,29 +656,227 @@ pub enum Commands {
         #[arg(help = "Input file to validate. If not provided, reads from stdin.")]
         file: Option<String>,
         /// Enable strict validation mode
-        #[arg(long, help = "Enable strict validation mode. Overrides TUBULAR_STRICT environment variable.")]
+        #[arg(long, help = "Enable strict validation mode, the same as --strictness strict. Overrides TUBULAR_STRICT environment variable.")]
         strict: bool,
+        /// Validation strictness
+        #[arg(long, value_parser = ["default", "strict", "pedantic"], conflicts_with = "strict", help = "Validation strictness: default (syntax and wiring), strict (also structural lint rules) or pedantic (every lint rule). Overrides TUBULAR_STRICTNESS.")]
+        strictness: Option<String>,
+        /// Output format (text, json, sarif)
+        #[arg(long, default_value = "text", value_parser = ["text", "json", "sarif"], help = "Output format: human-readable text, json for editors and CI, or sarif for code scanning.")]
+        format: String,
+        /// Treat warnings as errors
+        #[arg(long, help = "Report warnings, such as unreachable cells, as errors that fail validation.")]
+        deny_warnings: bool,
+        /// Report every error instead of stopping at the first
+        #[arg(long, help = "Report every parse and validation error in one pass instead of stopping at the first.")]
+        all_errors: bool,
     },
     /// Execute program with interactive input support
     Run {
         /// Input file to execute
-        #[arg(help = "Input file to execute.")]
+        #[arg(help = "Input file to execute, or - to read the program from stdin.")]
         file: String,
         /// Enable interactive input mode
-        #[arg(short, long, help = "Enable interactive input mode for programs that read from stdin.")]
+        #[arg(short, long, help = "Enable interactive input mode for programs that read from stdin. At a terminal, ? and ?? show a prompt saying whether a character or a number is expected, and lines can be edited and recalled with the arrow keys.")]
         interactive: bool,
         /// Provide input as command line argument
         #[arg(long, help = "Provide input as command line argument instead of stdin.")]
         input: Option<String>,
+        /// Read program input from a file
+        #[arg(long = "input-file", value_name = "PATH", conflicts_with_all = ["input", "interactive"], help = "Read the program's ? and ?? input from PATH instead of stdin, exactly as if it were redirected there; the program itself can then come from stdin with -.")]
+        input_file: Option<PathBuf>,
+        /// Re-run whenever the program changes
+        #[arg(long, conflicts_with = "interactive", help = "Run again whenever the file or a file it includes changes, clearing the screen first. Stop with Ctrl-C.")]
+        watch: bool,
+        /// Arguments for the program, after `--`
+        #[arg(last = true, value_name = "ARGS", help = "Arguments passed to the program after `--`.")]
+        args: Vec<String>,
+    },
+    /// Report a program's size, symbols, reachability, labels and memory use
+    Inspect {
+        /// Input file to inspect
+        #[arg(help = "Input file to inspect.")]
+        file: String,
+        /// Report format
+        #[arg(long, default_value = "text", value_parser = ["text", "json"], help = "Report format: text or json.")]
+        format: String,
+    },
+    /// Print the graph of every path a droplet can take through a program
+    Graph {
+        /// Input file to graph
+        #[arg(help = "Input file to graph.")]
+        file: String,
+        /// Graph format
+        #[arg(long, default_value = "dot", value_parser = ["dot"], help = "Graph format: dot (Graphviz).")]
+        format: String,
+    },
+    /// Run static analyses over a program without executing it
+    Analyze {
+        /// Input file to analyze
+        #[arg(help = "Input file to analyze.")]
+        file: String,
+        /// Check that no reachable stack operation can underflow
+        #[arg(long = "stack-check", help = "Track stack depth along every path from @ and report operations that can underflow.")]
+        stack_check: bool,
+    },
+    /// Check a program against named lint rules
+    Lint {
+        /// Input file to lint
+        #[arg(required_unless_present = "list", help = "Input file to lint.")]
+        file: Option<String>,
+        /// Rules to silence, warn about or deny
+        #[command(flatten)]
+        levels: LintLevelArgs,
+        /// Output format (text, json, sarif)
+        #[arg(long, default_value = "text", value_parser = ["text", "json", "sarif"], help = "Output format: human-readable text, json for editors and CI, or sarif for code scanning.")]
+        format: String,
+        /// List the available rules and their levels
+        #[arg(long, help = "List every rule with its level and description instead of linting.")]
+        list: bool,
+        /// Apply safe fixes and write the program back
+        #[arg(long, help = "Blank out invalid characters and orphaned pipes, normalise whitespace, and write the file back before linting.")]
+        fix: bool,
+    },
+    /// Rewrite programs into a canonical layout
+    Fmt {
+        /// Files to format
+        #[arg(required = true, help = "Program files to format in place.")]
+        files: Vec<String>,
+        /// Report files that need formatting instead of rewriting them
+        #[arg(long, help = "Exit with status 1 if any file is not formatted, without changing it.")]
+        check: bool,
+        /// Print the formatted program instead of writing it back
+        #[arg(long, help = "Write the formatted program to stdout instead of the file.")]
+        stdout: bool,
+        /// Pad lines with spaces to a rectangle
+        #[arg(long, help = "Pad every line with spaces to the width of the widest line.")]
+        pad: bool,
+        /// Move the program so its bounding box starts at (0, 0)
+        #[arg(long, help = "Drop leading blank rows and shared indentation. Changes cell coordinates.")]
+        reanchor: bool,
+    },
+    /// Rotate or mirror a program, turning its pipes and mirrors to match
+    #[command(group = clap::ArgGroup::new("transform").required(true).args(["rotate", "mirror_h", "mirror_v", "transpose"]))]
+    Transform {
+        /// Program to transform
+        #[arg(help = "Program file to transform, or - to read it from stdin. The result is printed to stdout.")]
+        file: String,
+        /// Clockwise rotation
+        #[arg(long, value_name = "DEGREES", value_parser = ["90", "180", "270"], help = "Rotate the program clockwise by 90, 180 or 270 degrees.")]
+        rotate: Option<String>,
+        /// Flip left to right
+        #[arg(long = "mirror-h", help = "Flip the program left to right.")]
+        mirror_h: bool,
+        /// Flip top to bottom
+        #[arg(long = "mirror-v", help = "Flip the program top to bottom.")]
+        mirror_v: bool,
+        /// Swap rows and columns
+        #[arg(long, help = "Swap the program's rows and columns.")]
+        transpose: bool,
+    },
+    /// Pack a program into the smallest grid that runs the same way
+    Minify {
+        /// Program to minify
+        #[arg(help = "Program file to minify, or - to read it from stdin. The result is printed to stdout.")]
+        file: String,
+        /// Check the result against the program's golden test
+        #[arg(long, help = "Also run the minified program against the file's .expected (and .input) files, failing if the output differs.")]
+        test: bool,
+    },
+    /// Translate a program into source code for a native compiler
+    Compile {
+        /// Program to compile
+        #[arg(help = "Program file to compile, or - to read it from stdin")]
+        file: String,
+        /// Language to emit
+        #[arg(long, value_parser = ["c"], default_value = "c", help = "Language to emit. Only c is supported: one droplet, 64-bit values, no random numbers, arguments, pseudo-input, sleeping, files, sockets or unbound calls.")]
+        emit: String,
+        /// Where to write the result
+        #[arg(short, long, value_name = "FILE", help = "Write the generated source to FILE instead of stdout")]
+        output: Option<String>,
+    },
+    /// Run golden-file tests: programs with matching .expected output files
+    Test {
+        /// Programs or directories to search
+        #[arg(default_value = ".", help = "Program files or directories to search for programs with a matching .expected file.")]
+        paths: Vec<String>,
+        /// Rewrite .expected files from the actual output
+        #[arg(long, help = "Overwrite each .expected file with the program's actual output.")]
+        update: bool,
+    },
+    /// List the bundled example programs, or print or run one
+    Examples {
+        /// Example to show
+        #[arg(help = "Example to print. If not provided, lists the examples.")]
+        name: Option<String>,
+        /// Run the example instead of printing it
+        #[arg(long, requires = "name", help = "Run the example instead of printing its source.")]
+        run: bool,
+    },
+    /// Create a new project with a starter program and tests
+    New {
+        /// Directory to create
+        #[arg(help = "Directory for the new project; its name becomes the project name.")]
+        name: String,
+    },
+    /// Animate droplets moving through the grid in the terminal
+    Watch {
+        /// Input file to visualize
+        #[arg(help = "Input file to visualize.")]
+        file: String,
+        /// Delay between ticks in milliseconds
+        #[arg(long, default_value = "200", help = "Milliseconds to wait between ticks while playing.")]
+        delay: u64,
+        /// Start paused
+        #[arg(long, help = "Start paused; press s to step or space to play.")]
+        paused: bool,
+        /// Provide input as command line argument
+        #[arg(long, help = "Provide the program's ? and ?? input as a command line argument. The terminal is busy with key presses, so without --input or --input-file the program reads no input.")]
+        input: Option<String>,
+        /// Read program input from a file
+        #[arg(long = "input-file", value_name = "PATH", conflicts_with = "input", help = "Read the program's ? and ?? input from PATH.")]
+        input_file: Option<PathBuf>,
+    },
+    /// Re-render a saved JSON trace without running the program again
+    Replay {
+        /// Trace file written with --trace-format json
+        #[arg(help = "Trace file written with --trace-format json --trace-output.")]
+        file: String,
+        /// Output format (compact, detailed, json, chrome, animate)
+        #[arg(long, default_value = "compact", value_parser = ["compact", "detailed", "json", "chrome", "animate"], help = "Trace format to print, or animate to replay the droplets on the grid.")]
+        format: String,
+        /// Delay between frames in milliseconds
+        #[arg(long, default_value = "200", help = "Milliseconds to wait between frames with --format animate.")]
+        delay: u64,
+    },
+    /// Step through a program one tick at a time
+    Debug {
+        /// Input file to debug
+        #[arg(help = "Input file to debug.")]
+        file: String,
+        /// Number of ticks that can be stepped back
+        #[arg(long, default_value_t = crate::cli::debugger::DEBUGGER_HISTORY, help = "Number of ticks kept for stepping back.")]
+        history: usize,
+    },
+    /// Run a Language Server Protocol server on stdin/stdout
+    Lsp,
+    /// Build and run a program interactively, one row at a time
+    Repl {
+        /// Tick limit for each run (0 for unlimited)
+        #[arg(long, default_value_t = crate::cli::repl::DEFAULT_REPL_TICKS, help = "Tick limit for each run of the program. 0 disables the limit.")]
+        ticks: u64,
     },
     /// Run comprehensive performance benchmarks
     Benchmark {
         /// Input file to benchmark
         #[arg(help = "Input file to benchmark.")]
         file: String,
-        /// Number of benchmark iterations
-        #[arg(long, default_value = "10", help = "Number of benchmark iterations.")]
-        iterations: usize,
+        /// Number of benchmark iterations (chosen automatically if omitted)
+        #[arg(long, help = "Number of benchmark iterations. By default enough iterations are run to fill --measurement-time.")]
+        iterations: Option<usize>,
+        /// Target time for the measured iterations (seconds)
+        #[arg(long = "measurement-time", default_value = "3", value_parser = parse_seconds, help = "Target total time in seconds for measured iterations when --iterations is not given.")]
+        measurement_time: Duration,
         /// Output format (json, csv, table)
         #[arg(long, default_value = "table", help = "Benchmark output format: json, csv, or table.")]
         output: String,
@@ -410,18 +899,32 @@ pub enum Commands {
 }
 
 impl Cli {
-    pub fn run(self) -> Result<()> {
+    /// Run the selected command, reporting any error and returning the exit code
+    pub fn run(self) -> ExitCode {
+        let quiet = self.quiet;
+        match self.dispatch() {
+            Ok(code) => code,
+            Err(e) => {
+                if !quiet {
+                    eprintln!("Error: {:?}", e);
+                }
+                ExitCode::from_error(&e)
+            }
+        }
+    }
+
+    fn dispatch(self) -> Result<ExitCode> {
         // Load environment configuration
         let env_config = match EnvConfig::load() {
             Ok(config) => {
-                if self.verbose || config.verbose {
+                if (self.verbose || config.verbose) && !self.quiet {
                     eprintln!("Environment configuration loaded successfully");
                 }
                 config
             }
             Err(e) => {
-                eprintln!("Warning: Failed to load environment configuration: {}", e);
-                eprintln!("Using default configuration values");
+                diag!(self, "Warning: Failed to load environment configuration: {}", e);
+                diag!(self, "Using default configuration values");
                 EnvConfig::default()
             }
         };
@@ -435,90 +938,921 @@ impl Cli {
         }
 
         match self.command {
-            Some(Commands::Validate { ref file, strict }) => {
-                let final_strict = strict || config.strict;
-                self.validate_program(file.as_deref(), final_strict, &config)
+            Some(Commands::Validate { ref file, strict, ref strictness, ref format, all_errors, deny_warnings }) => {
+                let strictness = match strictness {
+                    _ if strict => Strictness::Strict,
+                    Some(level) => level.parse().map_err(anyhow::Error::msg)?,
+                    None => config.strictness,
+                };
+                self.validate_program(file.as_deref(), strictness, format, all_errors, deny_warnings)
+            }
+            Some(Commands::Run { ref file, watch: true, ref input, ref input_file, .. }) => {
+                self.watch_and_rerun(file, input.clone(), input_file.as_deref(), &config)
+            }
+            Some(Commands::Run { ref file, interactive, ref input, ref input_file, .. }) => {
+                let outcome = self.execute_program_interactive(file, interactive, input.clone(), input_file.as_deref(), &config);
+                self.report_startup_error(file, outcome)
+            }
+            Some(Commands::Inspect { ref file, ref format }) => {
+                self.inspect_program(file, format)
+            }
+            Some(Commands::Transform { ref file, ref rotate, mirror_h, mirror_v, .. }) => {
+                let transform = match rotate {
+                    Some(degrees) => GridTransform::rotation(degrees.parse()?)
+                        .ok_or_else(|| anyhow::anyhow!("Cannot rotate by {} degrees", degrees))?,
+                    None if mirror_h => GridTransform::MirrorH,
+                    None if mirror_v => GridTransform::MirrorV,
+                    None => GridTransform::Transpose,
+                };
+                self.transform_program(file, transform)
+            }
+            Some(Commands::Minify { ref file, test }) => {
+                self.minify_program(file, test)
+            }
+            Some(Commands::Compile { ref file, ref output, .. }) => {
+                self.compile_program(file, output.as_deref())
+            }
+            Some(Commands::Graph { ref file, .. }) => {
+                self.graph_program(file)
+            }
+            Some(Commands::Analyze { ref file, stack_check }) => {
+                self.analyze_program(file, stack_check)
+            }
+            Some(Commands::Lint { ref file, ref levels, ref format, list, fix }) => {
+                let linter = Self::configure_linter(&config.lint_levels, &levels.levels)?;
+                match file {
+                    Some(file) if !list => self.lint_program(file, &linter, format, fix),
+                    _ => {
+                        Self::print_lint_rules(&linter);
+                        Ok(ExitCode::Success)
+                    }
+                }
+            }
+            Some(Commands::Fmt { ref files, check, stdout, pad, reanchor }) => {
+                let formatter = GridFormatter::new().with_padding(pad).with_reanchor(reanchor);
+                self.format_programs(files, &formatter, check, stdout)
+            }
+            Some(Commands::Test { ref paths, update }) => {
+                self.run_golden_tests(paths, update)
+            }
+            Some(Commands::Examples { ref name, run }) => {
+                self.show_example(name.as_deref(), run, &config)
+            }
+            Some(Commands::New { ref name }) => {
+                self.create_project(name)
+            }
+            Some(Commands::Watch { ref file, delay, paused, ref input, ref input_file }) => {
+                self.watch_program(file, delay, paused, input.as_deref(), input_file.as_deref(), &config).map(|()| ExitCode::Success)
             }
-            Some(Commands::Run { ref file, interactive, ref input }) => {
-                self.execute_program_interactive(file, interactive, input.clone(), &config)
+            Some(Commands::Replay { ref file, ref format, delay }) => {
+                self.replay_trace(file, format, delay, &config).map(|()| ExitCode::Success)
             }
-            Some(Commands::Benchmark { ref file, iterations, ref output, warmup, time_limit, ref save, verbose, ref compare }) => {
-                self.run_benchmark(file, iterations, &output, warmup, time_limit, save.as_deref(), verbose, &compare, &config)
+            Some(Commands::Debug { ref file, history }) => {
+                self.debug_program(file, history, &config).map(|()| ExitCode::Success)
+            }
+            Some(Commands::Lsp) => {
+                LanguageServer::run_stdio().map(|()| ExitCode::Success)
+            }
+            Some(Commands::Repl { ticks }) => {
+                self.run_repl(ticks).map(|()| ExitCode::Success)
+            }
+            Some(Commands::Benchmark { ref file, iterations, measurement_time, ref output, warmup, time_limit, ref save, verbose, ref compare }) => {
+                let sampler = Sampler::new()
+                    .with_warm_up_runs(warmup)
+                    .with_measurement_time(measurement_time)
+                    .with_sample_count(iterations);
+                let files: Vec<String> = std::iter::once(file.clone()).chain(compare.iter().cloned()).collect();
+                let report = self.run_benchmark(&files, &sampler, &output, time_limit, verbose, &config)?;
+                if let Some(save_path) = save {
+                    fs::write(save_path, report)?;
+                    eprintln!("Results saved to: {}", save_path);
+                }
+                Ok(ExitCode::Success)
             }
             None => {
-                if let Some(ref file) = self.file {
-                    self.execute_program(file, &config)
+                if self.eval.is_some() {
+                    let outcome = self.execute_program(EVAL_SOURCE, &config);
+                    self.report_startup_error(EVAL_SOURCE, outcome)
+                } else if let Some(ref file) = self.file {
+                    let outcome = self.execute_program(file, &config);
+                    self.report_startup_error(file, outcome)
                 } else {
-                    println!("No file specified. Use --help for usage information.");
-                    Ok(())
+                    diag!(self, "No file specified. Use --help for usage information, or `tubular repl` to experiment interactively.");
+                    Ok(ExitCode::Success)
                 }
             }
         }
     }
 
+    /// Read a program from `file_path`, from stdin when it is `-`, or from
+    /// `--eval` when it is [`EVAL_SOURCE`]
+    ///
+    /// Returns the source with the name used in diagnostics.
+    fn read_program_source(&self, file_path: &str) -> Result<(String, String)> {
+        if file_path == EVAL_SOURCE && let Some(program) = &self.eval {
+            return Ok((unescape_program(program), EVAL_SOURCE.to_string()));
+        }
+        if file_path != "-" {
+            let content = fs::read_to_string(file_path)
+                .map_err(|e| InterpreterError::System(
+                    crate::types::error::SystemError::IoError(e.to_string())
+                ))?;
+            return Ok((content, file_path.to_string()));
+        }
+
+        use std::io::Read;
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)
+            .map_err(|e| InterpreterError::System(
+                crate::types::error::SystemError::IoError(e.to_string())
+            ))?;
+        Ok((content, "<stdin>".to_string()))
+    }
+
     /// Validate a program from file or stdin
-    fn validate_program(&self, file_path: Option<&str>, strict: bool, config: &EnvConfig) -> Result<()> {
+    fn validate_program(&self, file_path: Option<&str>, strictness: Strictness, format: &str, all_errors: bool, deny_warnings: bool) -> Result<ExitCode> {
         // Read input content
-        let (content, source_name) = match file_path {
-            Some(path) => {
-                let content = fs::read_to_string(path)
-                    .map_err(|e| InterpreterError::System(
-                        crate::types::error::SystemError::IoError(e.to_string())
-                    ))?;
-                (content, path.to_string())
-            }
-            None => {
-                // Read from stdin
-                use std::io::Read;
-                let mut content = String::new();
-                let mut stdin = std::io::stdin();
-                stdin.read_to_string(&mut content)
-                    .map_err(|e| InterpreterError::System(
-                        crate::types::error::SystemError::IoError(e.to_string())
-                    ))?;
-                (content, "<stdin>".to_string())
+        let (content, source_name) = match self.read_program_source(file_path.unwrap_or("-")) {
+            Ok(source) => source,
+            Err(e) if format != "text" => {
+                let source_name = file_path.unwrap_or("<stdin>");
+                let diagnostic = match e.downcast_ref::<InterpreterError>() {
+                    Some(error) => Diagnostic::from_error(error, source_name),
+                    None => Diagnostic::from_error(&InterpreterError::enhanced(e.to_string(), ErrorType::System), source_name),
+                };
+                Self::print_diagnostics(format, source_name, &[diagnostic]);
+                return Ok(ExitCode::from_error(&e));
             }
+            Err(e) => return Err(e),
         };
 
         // If content is empty and reading from stdin, it's likely a usage error
-        if content.trim().is_empty() {
-            eprintln!("Error: No input provided");
-            eprintln!("Usage: cargo run -- validate <file>");
-            eprintln!("   or: cat <file> | cargo run -- validate");
-            std::process::exit(1);
+        if content.trim().is_empty() && format != "text" {
+            let error = InterpreterError::enhanced("No input provided".to_string(), ErrorType::Validation)
+                .with_suggestions(vec!["Pass a program file, or pipe one into tubular validate".to_string()]);
+            Self::print_diagnostics(format, &source_name, &[Diagnostic::from_error(&error, &source_name)]);
+            return Ok(ExitCode::Failure);
+        } else if content.trim().is_empty() {
+            diag!(self, "Error: No input provided");
+            diag!(self, "Usage: cargo run -- validate <file>");
+            diag!(self, "   or: cat <file> | cargo run -- validate");
+            return Ok(ExitCode::Failure);
         }
 
         // Parse the program
-        let parser = GridParser::new();
-        let grid = match parser.parse_string(&content) {
-            Ok(grid) => grid,
+        let parser = self.grid_parser(&source_name)?;
+        let validator = ProgramValidator::new()
+            .with_strictness(strictness)
+            .with_deny_warnings(deny_warnings)
+            .with_source_content(content.clone());
+        // Machine-readable output always lists every diagnostic
+        if all_errors || format != "text" {
+            return Ok(self.validate_all(&parser, &validator, &content, &source_name, format));
+        }
+        let outcome = parser.parse_named(&content, &source_name)
+            .and_then(|grid| validator.validate(&grid).map(|()| grid));
+        // A program that passed can still have warnings
+        let warnings = outcome.as_ref().map_or_else(|_| Vec::new(), |grid| validator.validate_all(grid));
+
+        match outcome {
+            Ok(grid) => {
+                self.print_validation_success(&grid, &source_name);
+                self.print_validation_warnings(&warnings, &source_name);
+                Ok(ExitCode::Success)
+            }
             Err(e) => {
                 self.print_validation_error(&e, &content, &source_name);
-                std::process::exit(1);
+                Ok(ExitCode::from_interpreter_error(&e))
             }
+        }
+    }
+
+    /// Validate a program, reporting every parse and validation error and warning found
+    fn validate_all(&self, parser: &GridParser, validator: &ProgramValidator, content: &str, source_name: &str, format: &str) -> ExitCode {
+        let (grid, mut errors) = parser.parse_all(content, source_name);
+        if let Some(grid) = &grid {
+            // Both passes check the start symbol; report each problem once
+            let key = |error: &InterpreterError| {
+                let diagnostic = Diagnostic::from_error(error, source_name);
+                (diagnostic.message, diagnostic.line, diagnostic.column)
+            };
+            for error in validator.validate_all(grid) {
+                if !errors.iter().any(|seen| key(seen) == key(&error)) {
+                    errors.push(error);
+                }
+            }
+        }
+        let (errors, warnings): (Vec<_>, Vec<_>) = errors.into_iter()
+            .partition(|error| error.severity() == ErrorSeverity::Error);
+        let exit_code = errors.first().map_or(ExitCode::Success, ExitCode::from_interpreter_error);
+
+        if format != "text" {
+            let diagnostics: Vec<Diagnostic> = errors.iter()
+                .chain(&warnings)
+                .map(|e| Diagnostic::from_error(e, source_name))
+                .collect();
+            Self::print_diagnostics(format, source_name, &diagnostics);
+            return exit_code;
+        }
+
+        match grid {
+            Some(grid) if errors.is_empty() => self.print_validation_success(&grid, source_name),
+            _ if !self.quiet => {
+                let plural = if errors.len() == 1 { "" } else { "s" };
+                eprintln!("[ERROR] Validation failed: {} ({} error{})", source_name, errors.len(), plural);
+                for (i, error) in errors.iter().enumerate() {
+                    eprintln!();
+                    eprintln!("  Error {} of {}", i + 1, errors.len());
+                    self.print_error_details(error, content, source_name);
+                }
+            }
+            _ => {}
+        }
+        self.print_validation_warnings(&warnings, source_name);
+        exit_code
+    }
+
+    /// Print `diagnostics` as a SARIF log (`sarif`) or a JSON document (`json`)
+    fn print_diagnostics(format: &str, source_name: &str, diagnostics: &[Diagnostic]) {
+        if format == "sarif" {
+            println!("{}", diagnostics_sarif(diagnostics));
+        } else {
+            println!("{}", diagnostics_json(source_name, diagnostics));
+        }
+    }
+
+    /// Parser with the aliases from `--symbol-map`, or else from the `tubular.toml` nearest `source_name`
+    ///
+    /// Programs from stdin, `--eval` and the gallery use the project file
+    /// nearest the current directory.
+    fn grid_parser(&self, source_name: &str) -> Result<GridParser> {
+        let project_file = match &self.symbol_map {
+            Some(path) => Some(PathBuf::from(path)),
+            None => {
+                let dir = Path::new(source_name).parent()
+                    .filter(|dir| !source_name.starts_with('<') && !dir.as_os_str().is_empty())
+                    .unwrap_or(Path::new("."));
+                find_project_file(dir)
+            }
+        };
+        let symbols = match project_file {
+            Some(path) => load_symbol_map(&path)?,
+            None => SymbolMap::new(),
         };
+        Ok(GridParser::new().with_symbol_map(symbols))
+    }
 
-        // Validate the program
-        let validator = if strict {
-            ProgramValidator::strict()
+    /// Arguments after `--`, given either to `tubular run` or to `tubular` itself
+    fn program_args(&self) -> &[String] {
+        match &self.command {
+            Some(Commands::Run { args, .. }) => args,
+            _ => &self.program_args,
+        }
+    }
+
+    /// Interpreter settings shared by every run mode
+    fn interpreter_builder(&self, config: &EnvConfig) -> InterpreterBuilder {
+        let mut builder = TubularInterpreter::builder()
+            .with_limits(ExecutionLimits::default()
+                .with_value_digit_warning(self.warn_digits)
+                .with_max_droplets(self.max_droplets)
+                .with_max_stack_depth(self.max_stack)
+                .with_max_reservoir_cells(self.max_reservoir))
+            .with_seed(self.pseudo_input_seed)
+            .with_wrap(self.wrap)
+            .with_allow_fs(self.allow_fs)
+            .with_allow_net(self.allow_net)
+            .with_allow_self_modify(self.allow_self_modify)
+            .with_deterministic(self.deterministic)
+            .with_optimize(self.optimize || self.opt_report)
+            .with_local_stacks(self.local_stacks)
+            .with_multi_digit_literals(self.multi_digit)
+            .with_program_args(self.program_args().to_vec())
+            .with_profiling(self.profile.is_some());
+        #[cfg(feature = "parallel")]
+        {
+            builder = builder.with_parallel(self.parallel);
+        }
+        #[cfg(feature = "jit")]
+        {
+            builder = builder.with_jit(self.jit);
+        }
+        if let Some(policy) = self.collisions.as_deref().and_then(CollisionPolicy::from_name) {
+            builder = builder.with_collision_policy(policy);
+        }
+        if let Some(scheduler) = self.schedule.as_deref().and_then(SchedulerKind::from_name) {
+            builder = builder.with_scheduler(scheduler);
+        }
+        if let Some(seed) = self.seed {
+            builder = builder.with_random_seed(seed);
+        }
+        if let Some(mode) = self.input_mode.as_deref().and_then(InputMode::from_name) {
+            builder = builder.with_input_mode(mode);
+        }
+        if let Some(policy) = self.eof.as_deref().and_then(EofPolicy::from_name) {
+            builder = builder.with_eof_policy(policy);
+        }
+        if let Some(hz) = self.tick_rate {
+            builder = builder.with_tick_rate(hz);
+        }
+        if let Some(ms) = self.connect_timeout {
+            builder = builder.with_connect_timeout(Some(Duration::from_millis(ms)));
+        }
+        // CLI overrides environment; otherwise keep the default limit
+        if let Some(ticks) = self.ticks.or(config.tick_limit) {
+            builder = builder.with_max_ticks(Some(ticks));
+        }
+        if let Some(time_limit) = self.time_limit {
+            builder = builder.with_max_time_ms(Some(time_limit).filter(|&ms| ms > 0));
+        }
+        let limits = builder.limits().clone();
+        builder.with_limits(if self.soft_limits {
+            limits.with_derived_soft_limits()
         } else {
-            ProgramValidator::new()
+            limits.with_soft_tick_limit(None).with_soft_time_limit_ms(None)
+        })
+    }
+
+    /// Build an interpreter for a normal run, with --trace*, --output-buffering and watches applied
+    fn traced_interpreter(&self, grid: ProgramGrid, config: &EnvConfig) -> Result<TubularInterpreter> {
+        let mut builder = self.interpreter_builder(config)
+            .with_verbose(config.verbose)
+            .with_interrupt_handle(Self::cancel_on_ctrl_c());
+        if Self::tracing_enabled(config) {
+            builder = builder.with_trace_config(config.trace_config.clone());
+        }
+        if let Some(buffering) = self.output_buffering {
+            builder = builder.with_output(StdoutSink::new().with_buffering(buffering));
+        }
+
+        let mut interpreter = builder.build(grid)?;
+        if Self::tracing_enabled(config) {
+            for expr in &self.trace_watch {
+                interpreter.add_watch(expr.clone(), WatchAction::Trace);
+            }
+        }
+        Ok(interpreter)
+    }
+
+    /// The handle cancelled by the first Ctrl-C; a second Ctrl-C exits immediately,
+    /// e.g. while the program is blocked reading input
+    ///
+    /// Only one handler can be installed per process, so every call shares it.
+    fn cancel_on_ctrl_c() -> InterruptHandle {
+        static TOKEN: std::sync::OnceLock<InterruptHandle> = std::sync::OnceLock::new();
+        TOKEN.get_or_init(|| {
+            let token = InterruptHandle::new();
+            let handle = token.clone();
+            let _ = ctrlc::set_handler(move || {
+                if handle.is_cancelled() {
+                    std::process::exit(ExitCode::Cancelled.code().into());
+                }
+                handle.cancel();
+            });
+            token
+        }).clone()
+    }
+
+    /// Print the output so far and a summary of an interrupted run
+    fn report_cancelled(&self, result: &crate::interpreter::execution::ExecutionResult, interpreter: &TubularInterpreter) -> ExitCode {
+        self.write_program_output(&result.final_output);
+        diag!(self, "[CANCELLED] Program interrupted after {} ticks ({}ms)", result.metrics.ticks, result.execution_time_ms);
+        diag!(self, "  Active droplets: {}", interpreter.state().droplets.len());
+        diag!(self, "  Max droplets: {}", result.max_droplets);
+        diag!(self, "  Max stack depth: {}", result.max_stack_depth);
+        ExitCode::Cancelled
+    }
+
+    /// List the bundled examples, or print or run the one called `name`
+    fn show_example(&self, name: Option<&str>, run: bool, config: &EnvConfig) -> Result<ExitCode> {
+        let Some(name) = name else {
+            let width = EXAMPLES.iter().map(|example| example.name.len()).max().unwrap_or(0);
+            for example in EXAMPLES {
+                println!("{:width$}  {}", example.name, example.description, width = width);
+            }
+            return Ok(ExitCode::Success);
         };
+        let example = find_example(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown example '{}'; run `tubular examples` to list them", name))?;
+        if run {
+            return self.execute_source(example.source, &format!("<example:{}>", example.name), config);
+        }
+        print!("{}", example.source);
+        Ok(ExitCode::Success)
+    }
 
-        match validator.validate(&grid) {
-            Ok(()) => {
-                self.print_validation_success(&grid, &source_name);
-                std::process::exit(0);
+    /// Scaffold a project in `name` and list the files created
+    fn create_project(&self, name: &str) -> Result<ExitCode> {
+        let files = scaffold::create_project(Path::new(name))
+            .map_err(|e| InterpreterError::System(
+                crate::types::error::SystemError::IoError(e.to_string())
+            ))?;
+        for file in &files {
+            diag!(self, "Created {}", file.display());
+        }
+        diag!(self, "Run it with `tubular {}` and test it with `tubular test {}`",
+            Path::new(name).join("main.tub").display(), name);
+        Ok(ExitCode::Success)
+    }
+
+    /// Discover and run golden-file tests, failing the check if any fail
+    fn run_golden_tests(&self, paths: &[String], update: bool) -> Result<ExitCode> {
+        let paths: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+        let tests = test_runner::discover(&paths)?;
+        if tests.is_empty() {
+            diag!(self, "[ERROR] No programs with .expected files found");
+            return Ok(ExitCode::Failure);
+        }
+
+        let runner = TestRunner::new(env::current_exe()?)
+            .with_args(self.golden_test_args())
+            .with_update(update);
+        let failed = runner.run_all(&tests, &mut std::io::stdout())?;
+        Ok(if failed > 0 { ExitCode::CheckFailed } else { ExitCode::Success })
+    }
+
+    /// Runtime flags given to `tubular test` or `tubular minify --test`, passed on to every program run
+    fn golden_test_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(ticks) = self.ticks {
+            args.extend(["--ticks".to_string(), ticks.to_string()]);
+        }
+        if let Some(time_limit) = self.time_limit {
+            args.extend(["--time-limit".to_string(), time_limit.to_string()]);
+        }
+        if self.pseudo_input_seed != 0 {
+            args.extend(["--pseudo-input-seed".to_string(), self.pseudo_input_seed.to_string()]);
+        }
+        if let Some(seed) = self.seed {
+            args.extend(["--seed".to_string(), seed.to_string()]);
+        }
+        for (flag, limit) in [("--max-droplets", self.max_droplets), ("--max-stack", self.max_stack), ("--max-reservoir", self.max_reservoir)] {
+            if let Some(limit) = limit {
+                args.extend([flag.to_string(), limit.to_string()]);
             }
-            Err(e) => {
-                self.print_validation_error(&e, &content, &source_name);
-                std::process::exit(1);
+        }
+        if self.deterministic {
+            args.push("--deterministic".to_string());
+        }
+        if self.allow_fs {
+            args.push("--allow-fs".to_string());
+        }
+        if self.allow_net {
+            args.push("--allow-net".to_string());
+        }
+        if let Some(ms) = self.connect_timeout {
+            args.extend(["--connect-timeout".to_string(), ms.to_string()]);
+        }
+        if self.allow_self_modify {
+            args.push("--allow-self-modify".to_string());
+        }
+        args
+    }
+
+    /// Write the program's output to stdout, honouring --output-hexdump
+    ///
+    /// Does nothing with --output-buffering, which writes output as it is produced.
+    fn write_program_output(&self, output: &str) {
+        use std::io::Write;
+
+        if self.output_buffering.is_some() {
+            return;
+        }
+        match self.output_hexdump.as_deref() {
+            Some("json") => {
+                println!("{}", OutputFormatter::format_output_json(output.as_bytes()));
+            }
+            Some(_) => {
+                print!("{}", OutputFormatter::format_hexdump(output.as_bytes()));
+            }
+            None => {
+                if output.is_empty() {
+                    return;
+                }
+                print!("{}", output);
+            }
+        }
+
+        // Flush stdout to ensure output is displayed immediately
+        std::io::stdout().flush().unwrap_or_default();
+    }
+
+    /// Print the --output-format json report for a finished run
+    fn write_execution_report(
+        &self,
+        file_path: &str,
+        outcome: &crate::types::error::Result<crate::interpreter::execution::ExecutionResult>,
+        interpreter: &TubularInterpreter,
+        config: &EnvConfig,
+    ) -> Result<ExitCode> {
+        if Self::tracing_enabled(config) {
+            self.handle_trace_output(&config.trace_config, &self.trace_output, interpreter.trace_events(), interpreter.grid())?;
+        }
+        let exit_code = match outcome {
+            Ok(result) if self.warnings_denied(result) => ExitCode::RuntimeError,
+            Ok(result) => ExitCode::from_status(&result.status),
+            Err(error) => ExitCode::from_interpreter_error(error),
+        };
+        println!("{}", OutputFormatter::format_execution_report_json(
+            file_path, outcome, interpreter.state(), interpreter.limits(), exit_code.code(),
+        ));
+        Ok(exit_code)
+    }
+
+    /// Whether the run is reported as one JSON document, by `--output-format json`
+    /// or `--report-usage=json`
+    fn json_report(&self) -> bool {
+        self.output_format == "json" || self.report_usage.as_deref() == Some("json")
+    }
+
+    /// With `--output-format json`, report an error that stopped `file_path` before it ran
+    ///
+    /// Runs that start always print their own report, so any error still
+    /// returned came from reading, parsing or validating the program.
+    fn report_startup_error(&self, file_path: &str, outcome: Result<ExitCode>) -> Result<ExitCode> {
+        match outcome {
+            Err(error) if self.json_report() => {
+                let exit_code = ExitCode::from_error(&error);
+                println!("{}", OutputFormatter::format_startup_error_report_json(
+                    file_path, &error.to_string(), exit_code.code(),
+                ));
+                Ok(exit_code)
+            }
+            outcome => outcome,
+        }
+    }
+
+    /// `error` with the source location of the cell that raised it, when known
+    fn locate_runtime_error(error: InterpreterError, interpreter: &TubularInterpreter, source_name: &str) -> anyhow::Error {
+        match interpreter.error_position() {
+            Some(position) => {
+                let file = position.file.as_deref().unwrap_or(source_name);
+                anyhow::Error::new(error)
+                    .context(format!("Runtime error at {}:{}:{}", file, position.line + 1, position.column + 1))
+            }
+            None => error.into(),
+        }
+    }
+
+    /// Whether `--deny-warnings` turns a completed run that issued warnings into a failure
+    fn warnings_denied(&self, result: &crate::interpreter::execution::ExecutionResult) -> bool {
+        self.deny_warnings
+            && matches!(result.status, crate::interpreter::execution::ExecutionStatus::Completed)
+            && !result.warnings_issued.is_empty()
+    }
+
+    /// Print runtime warnings that are reported even without --verbose
+    fn report_runtime_warnings(&self, result: &crate::interpreter::execution::ExecutionResult) {
+        for warning in &result.warnings_issued {
+            match warning {
+                crate::interpreter::execution::ExecutionWarning::LargeValue { location, digits } => {
+                    diag!(self, "[WARNING] {} reached a {}-digit value (threshold {})",
+                        location, digits, self.warn_digits.unwrap_or(0));
+                }
+                crate::interpreter::execution::ExecutionWarning::SoftTickLimit(ticks) => {
+                    diag!(self, "[WARNING] Reached tick {}, 80% of the tick limit", ticks);
+                }
+                crate::interpreter::execution::ExecutionWarning::SoftTimeLimit(ms) => {
+                    diag!(self, "[WARNING] Ran for {}ms, 80% of the time limit", ms);
+                }
+            }
+        }
+    }
+
+    /// Format each file in place, to stdout, or just check it
+    fn format_programs(&self, files: &[String], formatter: &GridFormatter, check: bool, to_stdout: bool) -> Result<ExitCode> {
+        let mut unformatted = 0;
+
+        for file_path in files {
+            let content = fs::read_to_string(file_path)
+                .map_err(|e| InterpreterError::System(
+                    crate::types::error::SystemError::IoError(e.to_string())
+                ))?;
+            let formatted = formatter.format(&content)
+                .map_err(|e| anyhow::anyhow!("{}: {}", file_path, e))?;
+
+            if to_stdout {
+                print!("{}", formatted);
+            } else if formatted != content {
+                if check {
+                    diag!(self, "Would reformat: {}", file_path);
+                    unformatted += 1;
+                } else {
+                    fs::write(file_path, &formatted)
+                        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", file_path, e))?;
+                    diag!(self, "Formatted: {}", file_path);
+                }
+            }
+        }
+
+        if unformatted > 0 {
+            diag!(self, "[ERROR] {} of {} file(s) need formatting", unformatted, files.len());
+            return Ok(ExitCode::CheckFailed);
+        }
+        Ok(ExitCode::Success)
+    }
+
+    /// Animate a program in the terminal, or print every frame when not on a TTY
+    fn watch_program(&self, file_path: &str, delay_ms: u64, paused: bool, input: Option<&str>, input_file: Option<&Path>, config: &EnvConfig) -> Result<()> {
+        use std::io::IsTerminal;
+
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| InterpreterError::System(
+                crate::types::error::SystemError::IoError(e.to_string())
+            ))?;
+        let grid = self.grid_parser(file_path)?.parse_named(&content, file_path)?;
+
+        // Key presses own the terminal, so the program never reads stdin here
+        let interpreter = self.interpreter_builder(config).build(grid)?;
+        let interpreter = match (input, input_file) {
+            (Some(input), _) => interpreter.with_input(StringInput::new(input)),
+            (None, Some(path)) => {
+                let file = fs::File::open(path)
+                    .map_err(|e| anyhow::anyhow!("Cannot open input file {}: {}", path.display(), e))?;
+                interpreter.with_input(ReaderInput::new(std::io::BufReader::new(file)))
+            }
+            (None, None) => interpreter.with_input(StringInput::default()),
+        };
+
+        let mut visualizer = Visualizer::new(interpreter)
+            .with_delay(Duration::from_millis(delay_ms))
+            .with_paused(paused);
+        if std::io::stdout().is_terminal() {
+            visualizer.run_interactive()?;
+        } else {
+            visualizer.with_color(false).run_plain(std::io::stdout())?;
+        }
+        Ok(())
+    }
+
+    /// Print or animate a trace saved with `--trace-format json`
+    fn replay_trace(&self, file_path: &str, format: &str, delay_ms: u64, config: &EnvConfig) -> Result<()> {
+        use std::io::IsTerminal;
+
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| InterpreterError::System(
+                crate::types::error::SystemError::IoError(e.to_string())
+            ))?;
+        let document = TraceDocument::parse(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid trace file '{}': {}", file_path, e))?;
+
+        let mut trace_config = config.trace_config.clone();
+        trace_config.format = match format {
+            "animate" => {
+                let replay = TraceReplay::new(document).with_delay(Duration::from_millis(delay_ms));
+                if std::io::stdout().is_terminal() {
+                    replay.run_animated(std::io::stdout())?;
+                } else {
+                    replay.with_color(false).run_plain(std::io::stdout())?;
+                }
+                return Ok(());
+            }
+            "detailed" => TraceFormat::Detailed,
+            "json" => TraceFormat::Json,
+            "chrome" => TraceFormat::Chrome,
+            _ => TraceFormat::Compact,
+        };
+        if self.trace_level.is_none() {
+            trace_config.level = TraceLevel::Detailed;
+        }
+
+        let program = document.program.as_deref()
+            .and_then(|program| GridParser::new().parse_string(program).ok());
+        let output = OutputFormatter.format_trace_events_with_program(&document.events, &trace_config, program.as_ref());
+        println!("{}", output);
+        Ok(())
+    }
+
+    /// Start a step-through debugging session on stdin/stdout
+    fn debug_program(&self, file_path: &str, history: usize, config: &EnvConfig) -> Result<()> {
+        use std::io::IsTerminal;
+
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| InterpreterError::System(
+                crate::types::error::SystemError::IoError(e.to_string())
+            ))?;
+        let grid = self.grid_parser(file_path)?.parse_named(&content, file_path)?;
+
+        let interpreter = self.interpreter_builder(config).build(grid)?;
+
+        let stdin = std::io::stdin();
+        let mut debugger = Debugger::new(interpreter)
+            .with_history_window(history)
+            .with_prompt(stdin.is_terminal());
+        // Program input is read through the debugger's reader, so stdin is never locked twice
+        debugger.run(std::io::BufReader::new(stdin), std::io::stdout())?;
+        Ok(())
+    }
+
+    /// Start an interactive session on stdin/stdout
+    fn run_repl(&self, ticks: u64) -> Result<()> {
+        use std::io::IsTerminal;
+
+        let stdin = std::io::stdin();
+        let max_ticks = if ticks == 0 { None } else { Some(ticks) };
+        let mut repl = Repl::new()
+            .with_prompt(stdin.is_terminal())
+            .with_max_ticks(max_ticks);
+        // Programs read their input through the REPL's reader, so stdin is never locked twice
+        repl.run(std::io::BufReader::new(stdin), std::io::stdout())?;
+        Ok(())
+    }
+
+    /// Run the requested static analyses (all of them when none is selected)
+    fn analyze_program(&self, file_path: &str, stack_check: bool) -> Result<ExitCode> {
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| InterpreterError::System(
+                crate::types::error::SystemError::IoError(e.to_string())
+            ))?;
+
+        let grid = self.grid_parser(file_path)?.parse_named(&content, file_path)?;
+        let run_all = !stack_check;
+        let mut findings = 0;
+
+        if stack_check || run_all {
+            for diagnostic in StackChecker::check(&grid) {
+                let location = grid.source_location(diagnostic.coordinate)
+                    .map(|loc| loc.to_string())
+                    .unwrap_or_else(|| file_path.to_string());
+                diag!(self, "{}: warning: {}", location, diagnostic);
+                findings += 1;
+            }
+        }
+
+        if findings > 0 {
+            diag!(self, "[ERROR] Analysis found {} issue(s) in {}", findings, file_path);
+            return Ok(ExitCode::InvalidProgram);
+        }
+
+        if !self.quiet {
+            println!("[OK] Analysis passed: {}", file_path);
+        }
+        Ok(ExitCode::Success)
+    }
+
+    /// Print static facts about a program
+    fn inspect_program(&self, file_path: &str, format: &str) -> Result<ExitCode> {
+        let (content, source_name) = self.read_program_source(file_path)?;
+        let grid = self.grid_parser(&source_name)?.parse_named(&content, &source_name)?;
+        let report = ProgramReport::collect(&grid);
+        if format == "json" {
+            println!("{}", OutputFormatter::format_program_report_json(&source_name, &report));
+        } else {
+            print!("{}", OutputFormatter::format_program_report(&report));
+        }
+        Ok(ExitCode::Success)
+    }
+
+    /// Print a program's control-flow graph in Graphviz format
+    fn graph_program(&self, file_path: &str) -> Result<ExitCode> {
+        let (content, source_name) = self.read_program_source(file_path)?;
+        let grid = self.grid_parser(&source_name)?.parse_named(&content, &source_name)?;
+        print!("{}", ControlFlowGraph::build(&grid).to_dot());
+        Ok(ExitCode::Success)
+    }
+
+    fn transform_program(&self, file_path: &str, transform: GridTransform) -> Result<ExitCode> {
+        let (content, source_name) = self.read_program_source(file_path)?;
+        let grid = self.grid_parser(&source_name)?.parse_named(&content, &source_name)?;
+        let transformed = transform.apply(&grid)
+            .map_err(|e| anyhow::anyhow!("{}: {}", source_name, e))?;
+        print!("{}", transformed);
+        Ok(ExitCode::Success)
+    }
+
+    /// Print the minified program once it validates and, with `test`, passes the golden test
+    fn minify_program(&self, file_path: &str, test: bool) -> Result<ExitCode> {
+        let (content, source_name) = self.read_program_source(file_path)?;
+        let grid = self.grid_parser(&source_name)?.parse_named(&content, &source_name)?;
+        ProgramValidator::new().validate(&grid)?;
+        let source = minify(&grid)
+            .map_err(|e| anyhow::anyhow!("{}: {}", source_name, e))?
+            .to_source();
+
+        // Minified programs are written with built-in symbols, so no symbol map applies
+        let minified = GridParser::new().parse_string(&source)?;
+        ProgramValidator::new().validate(&minified)
+            .map_err(|e| anyhow::anyhow!("{}: minified program does not validate: {}", source_name, e))?;
+
+        if test {
+            let Some(golden) = GoldenTest::for_program(Path::new(file_path)) else {
+                diag!(self, "[ERROR] {} has no .expected file to test against", source_name);
+                return Ok(ExitCode::Failure);
+            };
+            let program = env::temp_dir().join(format!("tubular_minify_{}.tb", std::process::id()));
+            fs::write(&program, &source)?;
+            let outcome = TestRunner::new(env::current_exe()?)
+                .with_args(self.golden_test_args())
+                .run(&GoldenTest { program: program.clone(), ..golden });
+            let _ = fs::remove_file(&program);
+            match outcome {
+                TestOutcome::Passed => {}
+                TestOutcome::Failed { expected, actual, .. } => {
+                    diag!(self, "[ERROR] Minified program fails the golden test for {}", source_name);
+                    diag!(self, "{}", test_runner::diff_lines(&expected, &actual).trim_end());
+                    return Ok(ExitCode::CheckFailed);
+                }
+                TestOutcome::Error(message) => return Err(anyhow::anyhow!(message)),
+            }
+        }
+
+        let size = |text: &str| (text.lines().map(|line| line.chars().count()).max().unwrap_or(0), text.lines().count());
+        let ((width, height), (min_width, min_height)) = (size(&grid.to_text()), size(&minified.to_text()));
+        diag!(self, "Minified {}: {}x{} -> {}x{}", source_name, width, height, min_width, min_height);
+        print!("{}", source);
+        Ok(ExitCode::Success)
+    }
+
+    /// Emit C for a program that validates, to stdout or `output`
+    fn compile_program(&self, file_path: &str, output: Option<&str>) -> Result<ExitCode> {
+        let (content, source_name) = self.read_program_source(file_path)?;
+        let grid = self.grid_parser(&source_name)?.parse_named(&content, &source_name)?;
+        ProgramValidator::new().validate(&grid)?;
+        let c = emit_c(&grid, &source_name)
+            .map_err(|e| anyhow::anyhow!("{}: {}", source_name, e))?;
+        match output {
+            Some(path) => {
+                fs::write(path, c)?;
+                diag!(self, "Compiled {} to {}", source_name, path);
+            }
+            None => print!("{}", c),
+        }
+        Ok(ExitCode::Success)
+    }
+
+    /// Built-in lint rules with `levels` applied in order, `all` entries first
+    /// Linter with the environment's levels applied, then the command line's in order
+    ///
+    /// Later settings win. The environment variables give no order, so their
+    /// `all` entries go before the rules they name.
+    fn configure_linter(env_levels: &[(String, LintLevel)], cli_levels: &[(String, LintLevel)]) -> Result<Linter> {
+        let (all, named): (Vec<_>, Vec<_>) = env_levels.iter().partition(|(rule, _)| rule == "all");
+        let mut linter = Linter::new();
+        for (rule, level) in all.into_iter().chain(named).chain(cli_levels) {
+            linter = linter.with_level(rule, *level).map_err(|e| anyhow::anyhow!(e))?;
+        }
+        Ok(linter)
+    }
+
+    fn print_lint_rules(linter: &Linter) {
+        for rule in linter.rules() {
+            let level = match linter.level(rule.id()).unwrap_or(LintLevel::Allow) {
+                LintLevel::Allow => "allow",
+                LintLevel::Warn => "warn",
+                LintLevel::Deny => "deny",
+            };
+            println!("{:<28} {:<5}  {:<8}  {}", rule.id(), level, rule.strictness(), rule.description());
+        }
+    }
+
+    /// Run the linter over a program, failing if any denied rule fires
+    fn lint_program(&self, file_path: &str, linter: &Linter, format: &str, fix: bool) -> Result<ExitCode> {
+        let mut content = fs::read_to_string(file_path)
+            .map_err(|e| InterpreterError::System(
+                crate::types::error::SystemError::IoError(e.to_string())
+            ))?;
+
+        if fix {
+            let outcome = linter.fix(&content)?;
+            if outcome.source != content {
+                fs::write(file_path, &outcome.source)?;
+                diag!(self, "Fixed {} cell(s) in {}", outcome.fixes, file_path);
+                content = outcome.source;
+            }
+        }
+
+        let grid = self.grid_parser(file_path)?.parse_named(&content, file_path)?;
+        let findings = linter.run(&grid);
+        let denied = findings.iter().filter(|finding| finding.level == LintLevel::Deny).count();
+
+        if format != "text" {
+            let diagnostics: Vec<Diagnostic> = findings.iter()
+                .map(|finding| Diagnostic::from_lint(finding, &grid, file_path))
+                .collect();
+            Self::print_diagnostics(format, file_path, &diagnostics);
+        } else {
+            for finding in &findings {
+                let location = finding.coordinate
+                    .and_then(|coord| grid.source_location(coord))
+                    .map(|loc| loc.to_string())
+                    .unwrap_or_else(|| file_path.to_string());
+                diag!(self, "{}: {}", location, finding);
+            }
+            if findings.is_empty() && !self.quiet {
+                println!("[OK] No lint findings: {}", file_path);
+            } else if !findings.is_empty() {
+                diag!(self, "{} finding(s), {} denied, in {}", findings.len(), denied, file_path);
             }
         }
+
+        Ok(if denied > 0 { ExitCode::InvalidProgram } else { ExitCode::Success })
     }
 
     /// Print successful validation result
     fn print_validation_success(&self, grid: &crate::interpreter::grid::ProgramGrid, source_name: &str) {
+        if self.quiet {
+            return;
+        }
         println!("[OK] Program validation passed: {}", source_name);
         println!("  Grid size: {}x{}", grid.dimensions().0, grid.dimensions().1);
         println!("  Program cells: {}", grid.size());
@@ -528,10 +1862,29 @@ impl Cli {
         }
     }
 
+    /// Print advisory findings as `file:line:column: warning: message`
+    fn print_validation_warnings(&self, warnings: &[InterpreterError], source_name: &str) {
+        for warning in warnings {
+            let diagnostic = Diagnostic::from_error(warning, source_name);
+            let location = match (diagnostic.line, diagnostic.column) {
+                (Some(line), Some(column)) => format!("{}:{}:{}", diagnostic.file, line, column),
+                _ => diagnostic.file.clone(),
+            };
+            diag!(self, "{}: warning: {}", location, diagnostic.message);
+        }
+    }
+
     /// Print validation error with context
     fn print_validation_error(&self, error: &InterpreterError, content: &str, source_name: &str) {
+        if self.quiet {
+            return;
+        }
         eprintln!("[ERROR] Validation failed: {}", source_name);
+        self.print_error_details(error, content, source_name);
+    }
 
+    /// Print what went wrong, with source context and suggestions
+    fn print_error_details(&self, error: &InterpreterError, content: &str, source_name: &str) {
         match error {
             InterpreterError::Initialization(init_err) => {
                 match init_err {
@@ -547,6 +1900,15 @@ impl Cli {
                     crate::types::error::InitError::GridSizeExceeded(width, height) => {
                         eprintln!("  Grid size {}x{} exceeds maximum supported size of 1000x1000", width, height);
                     }
+                    crate::types::error::InitError::SnapshotMismatch
+                    | crate::types::error::InitError::UndefinedLabel(..)
+                    | crate::types::error::InitError::InvalidCallSite(_)
+                    | crate::types::error::InitError::MissingLabelEntry(..)
+                    | crate::types::error::InitError::InvalidPortalSite(_)
+                    | crate::types::error::InitError::UnnamedPortal(_)
+                    | crate::types::error::InitError::UnpairedPortal(..) => {
+                        eprintln!("  {}", init_err);
+                    }
                 }
             }
             InterpreterError::System(sys_err) => {
@@ -562,7 +1924,10 @@ impl Cli {
                 if let Some(context) = &info.context {
                     let line_num = context.position.line + 1;
                     let col_num = context.position.column + 1;
-                    eprintln!("  Location: line {}, column {}", line_num, col_num);
+                    match &context.position.file {
+                        Some(file) => eprintln!("  Location: {}, line {}, column {}", file, line_num, col_num),
+                        None => eprintln!("  Location: line {}, column {}", line_num, col_num),
+                    }
 
                     if !context.source_line.is_empty() {
                         eprintln!("  Line {}: {}", line_num, context.source_line);
@@ -622,19 +1987,19 @@ impl Cli {
     }
 
     /// Execute a program file
-    fn execute_program(&self, file_path: &str, config: &EnvConfig) -> Result<()> {
-        // Read and parse the program
-        let content = fs::read_to_string(file_path)
-            .map_err(|e| InterpreterError::System(
-                crate::types::error::SystemError::IoError(e.to_string())
-            ))?;
+    fn execute_program(&self, file_path: &str, config: &EnvConfig) -> Result<ExitCode> {
+        let (content, source_name) = self.read_program_source(file_path)?;
+        self.execute_source(&content, &source_name, config)
+    }
 
+    /// Parse and execute program source, naming it `source_name` in diagnostics
+    fn execute_source(&self, content: &str, source_name: &str, config: &EnvConfig) -> Result<ExitCode> {
         if config.verbose {
-            eprintln!("Parsing program: {}", file_path);
+            eprintln!("Parsing program: {}", source_name);
         }
 
-        let parser = GridParser::new();
-        let grid = parser.parse_string(&content)?;
+        let parser = self.grid_parser(source_name)?;
+        let grid = parser.parse_named(content, source_name)?;
 
         if config.verbose {
             eprintln!("Program parsed successfully:");
@@ -644,22 +2009,40 @@ impl Cli {
         }
 
         // Create and run interpreter
-        let mut interpreter = TubularInterpreter::new(grid)?;
-
-        // Determine final tick limit: CLI overrides environment
-        let final_ticks = self.ticks.or(config.tick_limit);
-
-        interpreter = interpreter.with_options(config.verbose, config.trace, final_ticks);
+        let mut interpreter = self.traced_interpreter(grid, config)?;
 
         if config.verbose {
             eprintln!("Starting execution...");
         }
 
-        let result = interpreter.run()?;
+        let result = interpreter.run();
+        self.write_metrics(interpreter.metrics())?;
+        if self.json_report() {
+            return self.write_execution_report(source_name, &result, &interpreter, config);
+        }
+        let result = result.map_err(|e| Self::locate_runtime_error(e, &interpreter, source_name))?;
+        self.report_runtime_warnings(&result);
 
         // Handle trace output if trace is enabled
-        if config.trace || config.trace_config.level != TraceLevel::Basic {
-            self.handle_trace_output(&config.trace_config, &self.trace_output)?;
+        if Self::tracing_enabled(config) {
+            self.handle_trace_output(&config.trace_config, &self.trace_output, interpreter.trace_events(), interpreter.grid())?;
+        }
+
+        if self.collision_map {
+            eprint!("{}", OutputFormatter::format_collision_map(interpreter.grid(), &result.collision_hotspots));
+        }
+
+        if let (Some(top_n), Some(profile)) = (self.profile, result.profile.as_ref()) {
+            eprint!("{}", OutputFormatter::format_profile(interpreter.grid(), profile, top_n));
+        }
+
+        if self.report_usage.is_some() {
+            let usage = crate::interpreter::execution::ResourceUsage::collect(&result, interpreter.limits());
+            eprint!("{}", OutputFormatter::format_usage_report(&usage));
+        }
+
+        if self.opt_report {
+            eprint!("{}", OutputFormatter::format_opt_report(&interpreter.folds()));
         }
 
         // Print execution results
@@ -670,46 +2053,104 @@ impl Cli {
                     eprintln!("  Total ticks: {}", result.total_ticks);
                     eprintln!("  Max droplets: {}", result.max_droplets);
                     eprintln!("  Max stack depth: {}", result.max_stack_depth);
+                    eprintln!("  Peak value size: {} digits", result.peak_value_digits);
                 }
 
                 // Print program output if there is any
-                if !result.final_output.is_empty() {
-                    print!("{}", result.final_output);
-                    // Flush stdout to ensure output is displayed immediately
-                    use std::io::Write;
-                    std::io::stdout().flush().unwrap_or_default();
+                self.write_program_output(&result.final_output);
+                if self.warnings_denied(&result) {
+                    diag!(self, "[ERROR] Run issued {} warning(s) and --deny-warnings is set", result.warnings_issued.len());
+                    return Ok(ExitCode::RuntimeError);
                 }
             }
             crate::interpreter::execution::ExecutionStatus::TickTimeout(ticks) => {
-                eprintln!("[TIMEOUT] Program execution timed out after {} ticks", ticks);
+                diag!(self, "[TIMEOUT] Program execution timed out after {} ticks", ticks);
+                return Ok(ExitCode::Timeout);
             }
             crate::interpreter::execution::ExecutionStatus::WallClockTimeout(time_ms) => {
-                eprintln!("[TIMEOUT] Program execution timed out after {}ms", time_ms);
+                diag!(self, "[TIMEOUT] Program execution timed out after {}ms", time_ms);
+                return Ok(ExitCode::Timeout);
             }
             crate::interpreter::execution::ExecutionStatus::Error(err) => {
-                eprintln!("[ERROR] Program execution failed: {}", err);
+                diag!(self, "[ERROR] Program execution failed: {}", err);
                 return Err(err.into());
             }
+            crate::interpreter::execution::ExecutionStatus::Cancelled => {
+                return Ok(self.report_cancelled(&result, &interpreter));
+            }
             _ => {}
         }
 
-        Ok(())
+        Ok(ExitCode::Success)
+    }
+
+    /// Run a program again each time it or a file it includes changes, until Ctrl-C
+    fn watch_and_rerun(&self, file_path: &str, input: Option<String>, input_file: Option<&Path>, config: &EnvConfig) -> Result<ExitCode> {
+        use std::io::{IsTerminal, Write};
+
+        if file_path == "-" {
+            return Err(anyhow::anyhow!("--watch needs a program file, not stdin"));
+        }
+        let stop = Self::cancel_on_ctrl_c();
+        loop {
+            let files = self.watched_files(file_path);
+            let versions = Self::file_versions(&files);
+
+            if std::io::stdout().is_terminal() {
+                print!("\x1b[H\x1b[2J");
+            }
+            match self.execute_program_interactive(file_path, false, input.clone(), input_file, config) {
+                Ok(ExitCode::Success | ExitCode::Cancelled) => {}
+                Ok(code) => diag!(self, "[INFO] Exited with status {}", code.code()),
+                Err(e) => diag!(self, "Error: {:?}", e),
+            }
+            std::io::stdout().flush().unwrap_or_default();
+            if stop.is_cancelled() {
+                return Ok(ExitCode::Cancelled);
+            }
+
+            diag!(self, "[INFO] Watching {} for changes (Ctrl-C to stop)", files.join(", "));
+            while Self::file_versions(&files) == versions {
+                if stop.is_cancelled() {
+                    return Ok(ExitCode::Cancelled);
+                }
+                std::thread::sleep(WATCH_POLL_INTERVAL);
+            }
+        }
+    }
+
+    /// `file_path` and the files it includes, or just `file_path` if it does not parse
+    fn watched_files(&self, file_path: &str) -> Vec<String> {
+        let mut files = self.grid_parser(file_path).ok()
+            .and_then(|parser| parser.parse_file(file_path).ok())
+            .map(|grid| grid.source_map.files().to_vec())
+            .unwrap_or_default();
+        if !files.iter().any(|file| file == file_path) {
+            files.insert(0, file_path.to_string());
+        }
+        files
+    }
+
+    /// Modification time and length of each file, None for missing ones
+    fn file_versions(files: &[String]) -> Vec<Option<(Option<std::time::SystemTime>, u64)>> {
+        files.iter()
+            .map(|file| fs::metadata(file).ok().map(|metadata| (metadata.modified().ok(), metadata.len())))
+            .collect()
     }
 
     /// Execute a program file with interactive input support
-    fn execute_program_interactive(&self, file_path: &str, interactive: bool, input: Option<String>, config: &EnvConfig) -> Result<()> {
+    fn execute_program_interactive(&self, file_path: &str, interactive: bool, input: Option<String>, input_file: Option<&Path>, config: &EnvConfig) -> Result<ExitCode> {
+        use std::io::IsTerminal;
+
         // Read and parse the program
-        let content = fs::read_to_string(file_path)
-            .map_err(|e| InterpreterError::System(
-                crate::types::error::SystemError::IoError(e.to_string())
-            ))?;
+        let (content, source_name) = self.read_program_source(file_path)?;
 
         if config.verbose {
-            eprintln!("Parsing program: {}", file_path);
+            eprintln!("Parsing program: {}", source_name);
         }
 
-        let parser = GridParser::new();
-        let grid = parser.parse_string(&content)?;
+        let parser = self.grid_parser(&source_name)?;
+        let grid = parser.parse_named(&content, &source_name)?;
 
         if config.verbose {
             eprintln!("Program parsed successfully:");
@@ -726,28 +2167,57 @@ impl Cli {
 
         // Handle interactive input setup
         if interactive {
-            eprintln!("[INFO] Interactive mode enabled - program can read from stdin");
+            diag!(self, "[INFO] Interactive mode enabled - program can read from stdin");
             if input.is_some() {
-                eprintln!("[INFO] Using provided command line input instead of stdin");
+                diag!(self, "[INFO] Using provided command line input instead of stdin");
             }
         }
 
         // Create and run interpreter
-        // Determine final tick limit: CLI overrides environment
-        let final_ticks = self.ticks.or(config.tick_limit);
-
-        let mut interpreter = TubularInterpreter::new(grid)?
-            .with_options(config.verbose, config.trace, final_ticks);
+        let mut interpreter = self.traced_interpreter(grid, config)?;
+        if let Some(input) = input {
+            interpreter = interpreter.with_input(StringInput::new(&input));
+        } else if let Some(path) = input_file {
+            let file = fs::File::open(path)
+                .map_err(|e| anyhow::anyhow!("Cannot open input file {}: {}", path.display(), e))?;
+            interpreter = interpreter.with_input(ReaderInput::new(std::io::BufReader::new(file)));
+        } else if interactive && std::io::stdin().is_terminal() {
+            // Piped input is read as is; typing at a terminal gets prompts and editing
+            interpreter = interpreter.with_input(EditorInput::new(Self::cancel_on_ctrl_c())?);
+        }
 
         if config.verbose {
             eprintln!("Starting execution...");
         }
 
-        let result = interpreter.run()?;
+        let result = interpreter.run();
+        self.write_metrics(interpreter.metrics())?;
+        if self.json_report() {
+            return self.write_execution_report(file_path, &result, &interpreter, config);
+        }
+        let result = result.map_err(|e| Self::locate_runtime_error(e, &interpreter, file_path))?;
+        self.report_runtime_warnings(&result);
 
         // Handle trace output if trace is enabled
-        if config.trace || config.trace_config.level != TraceLevel::Basic {
-            self.handle_trace_output(&config.trace_config, &self.trace_output)?;
+        if Self::tracing_enabled(config) {
+            self.handle_trace_output(&config.trace_config, &self.trace_output, interpreter.trace_events(), interpreter.grid())?;
+        }
+
+        if self.collision_map {
+            eprint!("{}", OutputFormatter::format_collision_map(interpreter.grid(), &result.collision_hotspots));
+        }
+
+        if let (Some(top_n), Some(profile)) = (self.profile, result.profile.as_ref()) {
+            eprint!("{}", OutputFormatter::format_profile(interpreter.grid(), profile, top_n));
+        }
+
+        if self.report_usage.is_some() {
+            let usage = crate::interpreter::execution::ResourceUsage::collect(&result, interpreter.limits());
+            eprint!("{}", OutputFormatter::format_usage_report(&usage));
+        }
+
+        if self.opt_report {
+            eprint!("{}", OutputFormatter::format_opt_report(&interpreter.folds()));
         }
 
         // Print execution results
@@ -758,69 +2228,69 @@ impl Cli {
                     eprintln!("  Total ticks: {}", result.total_ticks);
                     eprintln!("  Max droplets: {}", result.max_droplets);
                     eprintln!("  Max stack depth: {}", result.max_stack_depth);
+                    eprintln!("  Peak value size: {} digits", result.peak_value_digits);
                 }
 
                 // Print program output if there is any
-                if !result.final_output.is_empty() {
-                    print!("{}", result.final_output);
-                    // Flush stdout to ensure output is displayed immediately
-                    use std::io::Write;
-                    std::io::stdout().flush().unwrap_or_default();
-                }
+                self.write_program_output(&result.final_output);
 
                 if interactive {
-                    eprintln!("[INFO] Interactive execution completed");
+                    diag!(self, "[INFO] Interactive execution completed");
+                }
+                if self.warnings_denied(&result) {
+                    diag!(self, "[ERROR] Run issued {} warning(s) and --deny-warnings is set", result.warnings_issued.len());
+                    return Ok(ExitCode::RuntimeError);
                 }
             }
             crate::interpreter::execution::ExecutionStatus::TickTimeout(ticks) => {
-                eprintln!("[TIMEOUT] Program execution timed out after {} ticks", ticks);
+                diag!(self, "[TIMEOUT] Program execution timed out after {} ticks", ticks);
+                return Ok(ExitCode::Timeout);
             }
             crate::interpreter::execution::ExecutionStatus::WallClockTimeout(time_ms) => {
-                eprintln!("[TIMEOUT] Program execution timed out after {}ms", time_ms);
+                diag!(self, "[TIMEOUT] Program execution timed out after {}ms", time_ms);
+                return Ok(ExitCode::Timeout);
             }
             crate::interpreter::execution::ExecutionStatus::Error(err) => {
-                eprintln!("[ERROR] Program execution failed: {}", err);
+                diag!(self, "[ERROR] Program execution failed: {}", err);
                 return Err(err.into());
             }
+            crate::interpreter::execution::ExecutionStatus::Cancelled => {
+                return Ok(self.report_cancelled(&result, &interpreter));
+            }
             _ => {}
         }
 
-        Ok(())
+        Ok(ExitCode::Success)
     }
 
     /// Run comprehensive benchmark for a Tubular program
+    ///
+    /// The first of `files_to_benchmark` is the program under test and the
+    /// rest are compared against it. Returns the printed report.
     fn run_benchmark(
         &self,
-        file_path: &str,
-        iterations: usize,
+        files_to_benchmark: &[String],
+        sampler: &Sampler,
         output_format: &str,
-        warmup_iterations: usize,
         time_limit: u64,
-        save_file: Option<&str>,
         verbose_benchmark: bool,
-        compare_files: &[String],
         config: &EnvConfig,
-    ) -> Result<()> {
+    ) -> Result<String> {
         if config.verbose {
-            eprintln!("Starting benchmark for: {}", file_path);
-            eprintln!("Iterations: {}, Warmup: {}, Time limit: {}s", iterations, warmup_iterations, time_limit);
+            eprintln!("Starting benchmark for: {}", files_to_benchmark[0]);
+            eprintln!("Sampler: {:?}, Time limit: {}s", sampler, time_limit);
         }
 
-        // Collect all files to benchmark
-        let mut files_to_benchmark = vec![file_path.to_string()];
-        files_to_benchmark.extend_from_slice(compare_files);
-
         let mut all_results = Vec::new();
 
-        for file in &files_to_benchmark {
+        for file in files_to_benchmark {
             if config.verbose {
                 eprintln!("\nBenchmarking: {}", file);
             }
 
             let result = self.benchmark_single_file(
                 file,
-                iterations,
-                warmup_iterations,
+                sampler,
                 time_limit,
                 verbose_benchmark,
                 config,
@@ -840,22 +2310,14 @@ impl Cli {
         };
 
         println!("{}", output);
-
-        // Save results to file if requested
-        if let Some(save_path) = save_file {
-            fs::write(save_path, output)?;
-            eprintln!("Results saved to: {}", save_path);
-        }
-
-        Ok(())
+        Ok(output)
     }
 
     /// Benchmark a single program file
     fn benchmark_single_file(
         &self,
         file_path: &str,
-        iterations: usize,
-        warmup_iterations: usize,
+        sampler: &Sampler,
         time_limit_seconds: u64,
         verbose_benchmark: bool,
         config: &EnvConfig,
@@ -866,8 +2328,8 @@ impl Cli {
                 crate::types::error::SystemError::IoError(e.to_string())
             ))?;
 
-        let parser = GridParser::new();
-        let grid = parser.parse_string(&content)?;
+        let parser = self.grid_parser(file_path)?;
+        let grid = parser.parse_named(&content, file_path)?;
 
         if config.verbose {
             eprintln!("Program parsed successfully:");
@@ -875,75 +2337,33 @@ impl Cli {
             eprintln!("  Program cells: {}", grid.size());
         }
 
-        // Warmup iterations
-        if warmup_iterations > 0 {
-            if config.verbose {
-                eprintln!("Running {} warmup iterations...", warmup_iterations);
-            }
-            for i in 0..warmup_iterations {
-                if verbose_benchmark {
-                    eprint!("Warmup {}/{}\r", i + 1, warmup_iterations);
-                }
-                let mut interpreter = TubularInterpreter::new(grid.clone())?
-                    .with_options(false, false, Some(time_limit_seconds));
-                let _ = interpreter.run();
-            }
-            if verbose_benchmark {
-                eprintln!("\nWarmup completed.");
-            }
-        }
-
-        // Benchmark iterations
-        let mut execution_times = Vec::new();
-        let mut tick_counts = Vec::new();
-        let mut peak_droplet_counts = Vec::new();
-        let mut memory_usage = Vec::new();
-
-        if config.verbose {
-            eprintln!("Running {} benchmark iterations...", iterations);
-        }
-
-        for i in 0..iterations {
+        let mut run = 0;
+        let (execution_times, results) = sampler.run(|| {
+            run += 1;
             if verbose_benchmark {
-                eprint!("Iteration {}/{}\r", i + 1, iterations);
-            }
-
-            let start_time = Instant::now();
-
-            // Create fresh interpreter for each iteration
-            let mut interpreter = TubularInterpreter::new(grid.clone())?
-                .with_options(false, false, Some(time_limit_seconds));
-
-            let result = interpreter.run()?;
-
-            let elapsed = start_time.elapsed();
-
-            execution_times.push(elapsed);
-            tick_counts.push(result.total_ticks);
-            peak_droplet_counts.push(result.max_droplets);
-
-            // Estimate memory usage (rough approximation)
-            let memory_mb = self.estimate_memory_usage(&result, &grid);
-            memory_usage.push(memory_mb);
-
-            if verbose_benchmark && i == iterations - 1 {
-                eprintln!("\n");
+                eprint!("Run {}\r", run);
             }
+            TubularInterpreter::new(grid.clone())?
+                .with_limits(ExecutionLimits::unlimited().with_max_time_ms(Some(time_limit_seconds.saturating_mul(1000))))
+                .run()
+        })?;
+        if verbose_benchmark {
+            eprintln!();
         }
 
-        // Calculate statistics
-        let avg_time = execution_times.iter().sum::<Duration>() / iterations as u32;
-        let min_time = execution_times.iter().min().unwrap();
-        let max_time = execution_times.iter().max().unwrap();
+        let iterations = results.len();
+        let tick_counts: Vec<u64> = results.iter().map(|result| result.total_ticks).collect();
+        let peak_droplet_counts: Vec<usize> = results.iter().map(|result| result.max_droplets).collect();
+        let memory_usage: Vec<f64> = results.iter().map(|result| self.estimate_memory_usage(result, &grid)).collect();
 
+        let timing = Statistics::from_samples(&execution_times);
         let avg_ticks = tick_counts.iter().sum::<u64>() / iterations as u64;
         let avg_droplets = peak_droplet_counts.iter().sum::<usize>() / iterations;
         let avg_memory = memory_usage.iter().sum::<f64>() / iterations as f64;
 
-        let total_instructions = tick_counts.iter().sum::<u64>();
-        let total_time_ms = avg_time.as_millis() as u64;
-        let instructions_per_sec = if total_time_ms > 0 {
-            (total_instructions as f64 * 1000.0) / total_time_ms as f64
+        let mean_secs = timing.mean.as_secs_f64();
+        let instructions_per_sec = if mean_secs > 0.0 {
+            avg_ticks as f64 / mean_secs
         } else {
             0.0
         };
@@ -951,9 +2371,7 @@ impl Cli {
         Ok(BenchmarkResult {
             program_file: file_path.to_string(),
             iterations,
-            avg_execution_time: avg_time,
-            min_execution_time: *min_time,
-            max_execution_time: *max_time,
+            timing,
             avg_total_ticks: avg_ticks,
             avg_peak_droplets: avg_droplets,
             avg_memory_usage_mb: avg_memory,
@@ -970,7 +2388,7 @@ impl Cli {
         // Rough estimation of memory usage in MB
         let grid_memory = grid.size() * std::mem::size_of::<crate::interpreter::grid::ProgramCell>();
         let droplet_memory = result.max_droplets * std::mem::size_of::<crate::interpreter::droplet::Droplet>();
-        let stack_memory = result.max_stack_depth * std::mem::size_of::<crate::types::bigint::TubularBigInt>();
+        let stack_memory = result.max_stack_depth * std::mem::size_of::<crate::types::number::TubularNumber>();
 
         (grid_memory + droplet_memory + stack_memory) as f64 / (1024.0 * 1024.0)
     }
@@ -997,7 +2415,7 @@ impl Cli {
         // Use existing OutputFormatter for basic table
         output.push_str(&OutputFormatter::format_benchmark_table(
             file_path,
-            result.avg_execution_time.as_millis() as u64,
+            result.timing.mean.as_millis() as u64,
             result.avg_total_ticks,
             result.avg_peak_droplets,
             result.avg_memory_usage_mb,
@@ -1007,10 +2425,20 @@ impl Cli {
         // Add additional statistical information
         output.push_str("\nStatistical Details:\n");
         output.push_str("===================\n");
+        let timing = &result.timing;
         output.push_str(&format!("Iterations: {}\n", result.iterations));
-        output.push_str(&format!("Min Execution Time: {:.3} ms\n", result.min_execution_time.as_millis()));
-        output.push_str(&format!("Max Execution Time: {:.3} ms\n", result.max_execution_time.as_millis()));
-        output.push_str(&format!("Time Std Dev: {:.3} ms\n", self.calculate_std_dev(&result.execution_times)));
+        output.push_str(&format!("Mean Execution Time: {:.3} ms (95% CI {:.3} - {:.3} ms)\n",
+            millis(timing.mean), millis(timing.confidence_interval.0), millis(timing.confidence_interval.1)));
+        output.push_str(&format!("Median Execution Time: {:.3} ms\n", millis(timing.median)));
+        output.push_str(&format!("p95 Execution Time: {:.3} ms\n", millis(timing.p95)));
+        output.push_str(&format!("Min Execution Time: {:.3} ms\n", millis(timing.min)));
+        output.push_str(&format!("Max Execution Time: {:.3} ms\n", millis(timing.max)));
+        output.push_str(&format!("Time Std Dev: {:.3} ms\n", millis(timing.std_dev)));
+        let outliers = &timing.outliers;
+        output.push_str(&format!(
+            "Outliers: {} ({} low severe, {} low mild, {} high mild, {} high severe)\n",
+            outliers.total(), outliers.low_severe, outliers.low_mild, outliers.high_mild, outliers.high_severe
+        ));
 
         if verbose {
             output.push_str("\nPer-Iteration Details:\n");
@@ -1019,7 +2447,7 @@ impl Cli {
                 output.push_str(&format!(
                     "Iter {}: {:.3} ms, {} ticks, {} droplets\n",
                     i + 1,
-                    time.as_millis(),
+                    millis(*time),
                     result.tick_counts[i],
                     result.peak_droplet_counts[i]
                 ));
@@ -1038,10 +2466,14 @@ impl Cli {
   "iterations": {},
   "results": {{
     "execution_time": {{
-      "average_ms": {},
-      "min_ms": {},
-      "max_ms": {},
-      "std_dev_ms": {}
+      "average_ms": {:.3},
+      "median_ms": {:.3},
+      "p95_ms": {:.3},
+      "min_ms": {:.3},
+      "max_ms": {:.3},
+      "std_dev_ms": {:.3},
+      "confidence_interval_ms": [{:.3}, {:.3}],
+      "outliers": {{"low_severe": {}, "low_mild": {}, "high_mild": {}, "high_severe": {}}}
     }},
     "total_ticks": {{
       "average": {},
@@ -1064,10 +2496,18 @@ impl Cli {
                 .unwrap_or_default()
                 .as_secs(),
             result.iterations,
-            result.avg_execution_time.as_millis(),
-            result.min_execution_time.as_millis(),
-            result.max_execution_time.as_millis(),
-            self.calculate_std_dev(&result.execution_times),
+            millis(result.timing.mean),
+            millis(result.timing.median),
+            millis(result.timing.p95),
+            millis(result.timing.min),
+            millis(result.timing.max),
+            millis(result.timing.std_dev),
+            millis(result.timing.confidence_interval.0),
+            millis(result.timing.confidence_interval.1),
+            result.timing.outliers.low_severe,
+            result.timing.outliers.low_mild,
+            result.timing.outliers.high_mild,
+            result.timing.outliers.high_severe,
             result.avg_total_ticks,
             result.tick_counts,
             result.avg_peak_droplets,
@@ -1091,7 +2531,7 @@ impl Cli {
                 "{},{},{:.3},{},{},{:.3}\n",
                 file_path,
                 i + 1,
-                result.execution_times[i].as_millis(),
+                millis(result.execution_times[i]),
                 result.tick_counts[i],
                 result.peak_droplet_counts[i],
                 result.memory_usage[i]
@@ -1124,18 +2564,21 @@ impl Cli {
 
         // Header
         output.push_str(&format!(
-            "{:<25} | {:>12} | {:>12} | {:>12} | {:>12} | {:>15}\n",
-            "Program", "Avg Time (ms)", "Avg Ticks", "Peak Droplets", "Memory (MB)", "Instructions/sec"
+            "{:<25} | {:>12} | {:>12} | {:>14} | {:>12} | {:>12} | {:>12} | {:>15}\n",
+            "Program", "Median (ms)", "p95 (ms)", "vs Baseline", "Avg Ticks", "Peak Droplets", "Memory (MB)", "Instructions/sec"
         ));
-        output.push_str(&"-".repeat(85));
+        output.push_str(&"-".repeat(140));
         output.push_str("\n");
 
-        // Results
+        // Results, with the change in median relative to the first program
+        let baseline = &all_results[0].1.timing;
         for (file, result) in all_results {
             output.push_str(&format!(
-                "{:<25} | {:>12.3} | {:>12} | {:>12} | {:>12.3} | {:>15.0}\n",
+                "{:<25} | {:>12.3} | {:>12.3} | {:>14} | {:>12} | {:>12} | {:>12.3} | {:>15.0}\n",
                 Path::new(file).file_name().unwrap_or_default().to_string_lossy(),
-                result.avg_execution_time.as_millis(),
+                millis(result.timing.median),
+                millis(result.timing.p95),
+                Self::format_change(&result.timing, baseline),
                 result.avg_total_ticks,
                 result.avg_peak_droplets,
                 result.avg_memory_usage_mb,
@@ -1164,6 +2607,11 @@ impl Cli {
       "program": "{}",
       "results": {{
         "execution_time_ms": {:.3},
+        "median_ms": {:.3},
+        "p95_ms": {:.3},
+        "confidence_interval_ms": [{:.3}, {:.3}],
+        "median_change": {:.4},
+        "significant": {},
         "total_ticks": {},
         "peak_droplets": {},
         "memory_usage_mb": {:.3},
@@ -1171,7 +2619,13 @@ impl Cli {
       }}
     }}"#,
                 file,
-                result.avg_execution_time.as_millis(),
+                millis(result.timing.mean),
+                millis(result.timing.median),
+                millis(result.timing.p95),
+                millis(result.timing.confidence_interval.0),
+                millis(result.timing.confidence_interval.1),
+                result.timing.median_change(&all_results[0].1.timing),
+                result.timing.differs_from(&all_results[0].1.timing),
                 result.avg_total_ticks,
                 result.avg_peak_droplets,
                 result.avg_memory_usage_mb,
@@ -1195,14 +2649,16 @@ impl Cli {
         let mut output = String::new();
 
         // CSV header
-        output.push_str("program,avg_execution_time_ms,avg_ticks,avg_peak_droplets,avg_memory_mb,instructions_per_second\n");
+        output.push_str("program,avg_execution_time_ms,median_ms,p95_ms,avg_ticks,avg_peak_droplets,avg_memory_mb,instructions_per_second\n");
 
         // CSV data
         for (file, result) in all_results {
             output.push_str(&format!(
-                "{},{:.3},{},{},{:.3},{:.2}\n",
+                "{},{:.3},{:.3},{:.3},{},{},{:.3},{:.2}\n",
                 file,
-                result.avg_execution_time.as_millis(),
+                millis(result.timing.mean),
+                millis(result.timing.median),
+                millis(result.timing.p95),
                 result.avg_total_ticks,
                 result.avg_peak_droplets,
                 result.avg_memory_usage_mb,
@@ -1213,70 +2669,93 @@ impl Cli {
         Ok(output)
     }
 
-    /// Calculate standard deviation for a set of durations
-    fn calculate_std_dev(&self, durations: &[Duration]) -> f64 {
-        if durations.len() <= 1 {
-            return 0.0;
+    /// Median change versus the baseline, marked with `~` when within noise
+    fn format_change(timing: &Statistics, baseline: &Statistics) -> String {
+        if std::ptr::eq(timing, baseline) {
+            return "baseline".to_string();
         }
+        let marker = if timing.differs_from(baseline) { "" } else { "~" };
+        format!("{}{:+.1}%", marker, timing.median_change(baseline) * 100.0)
+    }
 
-        let mean = durations.iter().sum::<Duration>() / durations.len() as u32;
-        let mean_ms = mean.as_millis() as f64;
-
-        let variance: f64 = durations
-            .iter()
-            .map(|d| {
-                let diff = d.as_millis() as f64 - mean_ms;
-                diff * diff
-            })
-            .sum::<f64>() / (durations.len() - 1) as f64;
+    /// Dump the execution counters to --metrics-file, if given
+    fn write_metrics(&self, metrics: &Metrics) -> Result<()> {
+        if let Some(path) = &self.metrics_file {
+            fs::write(path, metrics.to_prometheus())
+                .map_err(|e| anyhow::anyhow!("Failed to write metrics to '{}': {}", path, e))?;
+        }
+        Ok(())
+    }
 
-        variance.sqrt()
+    /// Whether any --trace* option asked for trace events
+    fn tracing_enabled(config: &EnvConfig) -> bool {
+        config.trace || config.trace_config.level != TraceLevel::Basic
     }
 
-    /// Handle trace output after execution
-    fn handle_trace_output(&self, trace_config: &TraceConfig, trace_output_file: &Option<String>) -> Result<()> {
-        // For now, this is a placeholder - the actual trace events will be generated
-        // by the execution engine in a future implementation
+    /// Format the trace events recorded during execution and write them out
+    fn handle_trace_output(
+        &self,
+        trace_config: &TraceConfig,
+        trace_output_file: &Option<String>,
+        events: &[TraceEvent],
+        program: &ProgramGrid,
+    ) -> Result<()> {
         let formatter = OutputFormatter;
-
-        // Create a placeholder trace event to show the feature works
-        let placeholder_events = vec![
-            OutputFormatter::create_movement_trace_event(
-                0,
-                0,
-                crate::types::coordinate::Coordinate::new(0, 0),
-                crate::types::coordinate::Coordinate::new(0, 1),
-                crate::types::direction::Direction::Down,
-                &crate::types::bigint::TubularBigInt::zero(),
-                Some('@'),
-            )
-        ];
-
-        let trace_output = formatter.format_trace_events(&placeholder_events, trace_config);
+        let trace_output = formatter.format_trace_events_with_program(events, trace_config, Some(program));
 
         // Output trace results
         if let Some(file_path) = trace_output_file {
             // Save to file
             fs::write(file_path, trace_output)
                 .map_err(|e| anyhow::anyhow!("Failed to write trace output to '{}': {}", file_path, e))?;
-            eprintln!("Trace output saved to: {}", file_path);
+            diag!(self, "Trace output saved to: {}", file_path);
         } else {
-            // Print to stdout
-            println!("{}", trace_output);
+            // Traces are diagnostics: stdout carries only program output
+            eprintln!("{}", trace_output);
         }
 
         Ok(())
     }
 }
 
+/// Duration in fractional milliseconds for reports
+/// How often `run --watch` checks the watched files for changes
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Source name of a program given with `--eval`
+const EVAL_SOURCE: &str = "<eval>";
+
+/// Turn the `\n`, `\t` and `\\` escapes of an `--eval` program into the
+/// characters they stand for; any other backslash is kept as a mirror
+fn unescape_program(program: &str) -> String {
+    let mut source = String::with_capacity(program.len());
+    let mut chars = program.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let escaped = match (ch, chars.peek()) {
+            ('\\', Some('n')) => '\n',
+            ('\\', Some('t')) => '\t',
+            ('\\', Some('\\')) => '\\',
+            _ => {
+                source.push(ch);
+                continue;
+            }
+        };
+        chars.next();
+        source.push(escaped);
+    }
+    source
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
 /// Benchmark result data structure
 #[derive(Debug, Clone)]
 struct BenchmarkResult {
     program_file: String,
     iterations: usize,
-    avg_execution_time: Duration,
-    min_execution_time: Duration,
-    max_execution_time: Duration,
+    timing: Statistics,
     avg_total_ticks: u64,
     avg_peak_droplets: usize,
     avg_memory_usage_mb: f64,
@@ -0,0 +1,257 @@
+use crate::interpreter::breakpoint::BreakCondition;
+use crate::interpreter::execution::{ExecutionStatus, TubularInterpreter};
+use crate::interpreter::watch::{WatchAction, WatchExpr};
+use crate::operations::io::{InputProvider, SharedReaderInput};
+use crate::types::number::TubularNumber;
+use crate::types::coordinate::Coordinate;
+use std::io::{self, BufRead, Write};
+
+/// Number of ticks the debugger can step back through
+pub const DEBUGGER_HISTORY: usize = 1_000;
+
+const DEBUGGER_HELP: &str = "\
+Commands:
+  step [N]          Execute N ticks (default 1); an empty line repeats the last command
+  back [N]          Undo N ticks (default 1)
+  history           Show how many ticks can be undone
+  continue          Run until the program stops or a breakpoint is hit
+  break X Y         Stop when a droplet reaches (X, Y)
+  break tick N      Stop before tick N
+  break droplet N   Stop when droplet N appears
+  break value V     Stop when a droplet holds V
+  breakpoints       List breakpoints
+  delete N          Remove breakpoint N
+  watch EXPR        Stop when a watch triggers, e.g. watch reservoir (3,4)
+                    or watch stack depth > 10
+  watches           List watches
+  unwatch N         Remove watch N
+  droplets          Show active droplets
+  stack             Show the data stack
+  mem               Show the reservoir
+  output            Show output produced so far
+  help              Show this help
+  quit              Stop debugging";
+
+/// Line-oriented front-end that drives an interpreter one tick at a time
+pub struct Debugger {
+    interpreter: TubularInterpreter,
+    last_command: String,
+    prompt: bool,
+}
+
+impl Debugger {
+    pub fn new(interpreter: TubularInterpreter) -> Self {
+        Debugger {
+            interpreter: interpreter.with_history(DEBUGGER_HISTORY),
+            last_command: "step".to_string(),
+            prompt: true,
+        }
+    }
+
+    /// Keep undo information for the last `window` ticks instead of the default
+    pub fn with_history_window(mut self, window: usize) -> Self {
+        self.interpreter.enable_history(window);
+        self
+    }
+
+    /// Disable the `(tdb)` prompt, e.g. when commands are piped
+    pub fn with_prompt(mut self, prompt: bool) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    pub fn interpreter(&self) -> &TubularInterpreter {
+        &self.interpreter
+    }
+
+    /// Process commands until `quit` or end of input
+    ///
+    /// The program reads `?` and `??` input from the same stream, so the
+    /// lines after a `step` or `continue` that reaches `?` feed it.
+    pub fn run<R: BufRead + Send + 'static, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        self.print_droplets(&mut output)?;
+
+        let mut input = SharedReaderInput::new(input);
+        self.interpreter.set_input(input.clone());
+        loop {
+            if self.prompt {
+                write!(output, "(tdb) ")?;
+                output.flush()?;
+            }
+
+            let line = match input.read_raw_line().map_err(|e| io::Error::other(e.to_string()))? {
+                Some(line) => line,
+                None => break,
+            };
+            let line = line.strip_suffix('\n').unwrap_or(&line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            if !self.handle_command(line, &mut output)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute one debugger command; returns false when the session should end
+    pub fn handle_command<W: Write>(&mut self, line: &str, output: &mut W) -> io::Result<bool> {
+        let line = match line.trim() {
+            "" => self.last_command.clone(),
+            line => line.to_string(),
+        };
+
+        let mut parts = line.split_whitespace();
+        let name = parts.next().unwrap_or("");
+        let count = parts.next().map(|n| n.parse::<u64>());
+
+        match name {
+            "step" | "s" | "next" | "n" => match count.unwrap_or(Ok(1)) {
+                Ok(n) => self.step(n, output)?,
+                Err(_) => writeln!(output, "Usage: step [N]")?,
+            },
+            "back" | "b" => match count.unwrap_or(Ok(1)) {
+                Ok(n) => self.back(n, output)?,
+                Err(_) => writeln!(output, "Usage: back [N]")?,
+            },
+            "history" => writeln!(output, "{} tick(s) can be stepped back", self.interpreter.history_len())?,
+            "continue" | "c" => self.step(u64::MAX, output)?,
+            "break" | "br" => {
+                let args: Vec<&str> = line.split_whitespace().skip(1).collect();
+                match parse_break_condition(&args) {
+                    Some(condition) => {
+                        writeln!(output, "Breakpoint {}: {}", self.interpreter.breakpoints().count(), condition)?;
+                        self.interpreter.add_breakpoint(condition);
+                    }
+                    None => writeln!(output, "Usage: break X Y | break tick N | break droplet N | break value V")?,
+                }
+            }
+            "breakpoints" => {
+                for (index, condition) in self.interpreter.breakpoints().enumerate() {
+                    writeln!(output, "Breakpoint {}: {}", index, condition)?;
+                }
+            }
+            "delete" => match count {
+                Some(Ok(index)) => match self.interpreter.remove_breakpoint(index as usize) {
+                    Some(condition) => writeln!(output, "Deleted breakpoint {}: {}", index, condition)?,
+                    None => writeln!(output, "No breakpoint {}", index)?,
+                },
+                _ => writeln!(output, "Usage: delete N")?,
+            },
+            "watch" | "w" => {
+                let text = line.split_once(char::is_whitespace).map_or("", |(_, rest)| rest);
+                match text.parse::<WatchExpr>() {
+                    Ok(expr) => {
+                        writeln!(output, "Watch {}: {}", self.interpreter.watches().len(), expr)?;
+                        self.interpreter.add_watch(expr, WatchAction::Pause);
+                    }
+                    Err(e) => writeln!(output, "{}", e)?,
+                }
+            }
+            "watches" => {
+                for (index, watch) in self.interpreter.watches().iter().enumerate() {
+                    writeln!(output, "Watch {}: {}", index, watch.expr)?;
+                }
+            }
+            "unwatch" => match count {
+                Some(Ok(index)) => match self.interpreter.remove_watch(index as usize) {
+                    Some(expr) => writeln!(output, "Removed watch {}: {}", index, expr)?,
+                    None => writeln!(output, "No watch {}", index)?,
+                },
+                _ => writeln!(output, "Usage: unwatch N")?,
+            },
+            "droplets" | "d" => self.print_droplets(output)?,
+            "stack" => {
+                let values: Vec<String> = self.interpreter.state().stack.as_slice()
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect();
+                writeln!(output, "Stack (bottom to top): [{}]", values.join(", "))?;
+            }
+            "mem" | "m" => write!(output, "{}", self.interpreter.state().reservoir)?,
+            "output" | "o" => writeln!(output, "Output: {:?}", self.interpreter.state().output)?,
+            "help" | "h" | "?" => writeln!(output, "{}", DEBUGGER_HELP)?,
+            "quit" | "q" | "exit" => return Ok(false),
+            _ => {
+                writeln!(output, "Unknown command '{}'; type help for commands", name)?;
+                return Ok(true);
+            }
+        }
+
+        self.last_command = line;
+        Ok(true)
+    }
+
+    fn step<W: Write>(&mut self, ticks: u64, output: &mut W) -> io::Result<()> {
+        self.interpreter.resume();
+        let mut executed = 0;
+        while executed < ticks && self.interpreter.is_running() {
+            if let Err(e) = self.interpreter.execute_tick() {
+                writeln!(output, "Error: {}", e)?;
+                break;
+            }
+            executed += 1;
+        }
+
+        if executed == 0 && !self.interpreter.is_running() {
+            writeln!(output, "Program is not running")?;
+            return self.print_status(output);
+        }
+
+        self.print_droplets(output)?;
+        if !self.interpreter.is_running() {
+            self.print_status(output)?;
+        }
+        Ok(())
+    }
+
+    fn back<W: Write>(&mut self, ticks: u64, output: &mut W) -> io::Result<()> {
+        for _ in 0..ticks {
+            if !self.interpreter.step_back() {
+                writeln!(output, "No earlier ticks in history")?;
+                break;
+            }
+        }
+        self.print_droplets(output)
+    }
+
+    fn print_droplets<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        let state = self.interpreter.state();
+        writeln!(output, "Tick {}: {} droplet(s)", state.tick, state.droplets.len())?;
+        for droplet in &state.droplets {
+            let symbol = self.interpreter.grid().get_symbol(droplet.position).unwrap_or(' ');
+            writeln!(output, "  D{} at {} on '{}' heading {} value {}",
+                droplet.id, droplet.position, symbol, droplet.direction, droplet.value)?;
+        }
+        for coord in self.interpreter.last_tick_collisions() {
+            writeln!(output, "  Collision at {}", coord)?;
+        }
+        Ok(())
+    }
+
+    fn print_status<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        match &self.interpreter.state().status {
+            ExecutionStatus::Completed => writeln!(output, "Program completed"),
+            ExecutionStatus::Error(e) => writeln!(output, "Program stopped with error: {}", e),
+            ExecutionStatus::TickTimeout(ticks) => writeln!(output, "Tick limit of {} reached", ticks),
+            ExecutionStatus::WallClockTimeout(ms) => writeln!(output, "Time limit of {}ms reached", ms),
+            ExecutionStatus::Cancelled => writeln!(output, "Program cancelled"),
+            ExecutionStatus::Paused => match (self.interpreter.hit_breakpoint(), self.interpreter.hit_watch()) {
+                (Some(condition), _) => writeln!(output, "Stopped at breakpoint: {}", condition),
+                (None, Some(hit)) => writeln!(output, "Watch triggered: {}", hit),
+                (None, None) => writeln!(output, "Paused"),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parse the arguments of a `break` command
+fn parse_break_condition(args: &[&str]) -> Option<BreakCondition> {
+    match args {
+        ["tick", n] => n.parse().ok().map(BreakCondition::Tick),
+        ["droplet", n] => n.trim_start_matches('D').parse().ok().map(BreakCondition::Droplet),
+        ["value", v] => v.parse().ok().map(|v| BreakCondition::Value(TubularNumber::from_bigint(v))),
+        [x, y] => Some(BreakCondition::At(Coordinate::new(x.parse().ok()?, y.parse().ok()?))),
+        _ => None,
+    }
+}
@@ -0,0 +1,260 @@
+use crate::cli::output::OutputFormatter;
+use crate::interpreter::grid::ProgramGrid;
+use crate::parser::lint::LintFinding;
+use crate::types::error::{ErrorSeverity, ErrorType, InitError, InterpreterError};
+use serde_json::json;
+
+/// Version of the JSON emitted by `validate` and `lint` with `--format json`
+///
+/// Bump when a field is renamed or removed; adding fields is not a breaking change.
+pub const DIAGNOSTICS_SCHEMA_VERSION: u32 = 1;
+
+/// A validation finding in a form editors and CI tools can consume
+///
+/// Lines and columns are 1-based, matching the human-readable output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub code: &'static str,
+    /// Lint rule that produced the finding, if any
+    pub rule: Option<&'static str>,
+    pub error_type: ErrorType,
+    pub severity: ErrorSeverity,
+    pub message: String,
+    pub file: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Column just past the end of the highlighted span
+    pub end_column: Option<usize>,
+    pub suggestions: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn from_error(error: &InterpreterError, file: &str) -> Self {
+        // Positions in an included file name that file
+        let file = error.context().and_then(|context| context.position.file.as_deref()).unwrap_or(file);
+        let (line, column, end_column) = match error {
+            InterpreterError::Initialization(InitError::InvalidCharacter(_, coord)) => {
+                let column = coord.x.max(0) as usize + 1;
+                (Some(coord.y.max(0) as usize + 1), Some(column), Some(column + 1))
+            }
+            _ => match error.context() {
+                Some(context) => {
+                    let (start, end) = context.error_span;
+                    (Some(context.position.line + 1), Some(context.position.column + 1), Some(end.max(start + 1) + 1))
+                }
+                None => (None, None, None),
+            },
+        };
+
+        let message = match error {
+            InterpreterError::Initialization(e) => e.to_string(),
+            InterpreterError::Execution(e) => e.to_string(),
+            InterpreterError::System(e) => e.to_string(),
+            InterpreterError::Enhanced { info, .. } => info.message.clone(),
+        };
+
+        Diagnostic {
+            code: OutputFormatter::extract_error_code(error),
+            rule: None,
+            error_type: error.error_type(),
+            severity: error.severity(),
+            message,
+            file: file.to_string(),
+            line,
+            column,
+            end_column,
+            suggestions: error.suggestions().to_vec(),
+            help: error.help_text().cloned(),
+        }
+    }
+
+    /// A lint finding, positioned through the grid's source map
+    pub fn from_lint(finding: &LintFinding, grid: &ProgramGrid, file: &str) -> Self {
+        let location = finding.coordinate.and_then(|coord| grid.source_location(coord));
+        let column = location.as_ref().map(|loc| loc.column + 1);
+        Diagnostic {
+            rule: Some(finding.rule),
+            message: finding.message.clone(),
+            file: location.as_ref().map_or_else(|| file.to_string(), |loc| loc.file.clone()),
+            line: location.as_ref().map(|loc| loc.line + 1),
+            column,
+            end_column: column.map(|column| column + 1),
+            ..Self::from_error(&finding.to_error(), file)
+        }
+    }
+
+    /// Rule id for SARIF: the lint rule if there is one, otherwise the error code
+    pub fn rule_id(&self) -> &'static str {
+        self.rule.unwrap_or(self.code)
+    }
+
+    pub fn severity_name(&self) -> &'static str {
+        match self.severity {
+            ErrorSeverity::Error => "error",
+            ErrorSeverity::Warning => "warning",
+            ErrorSeverity::Info => "info",
+            ErrorSeverity::Hint => "hint",
+        }
+    }
+
+    /// SARIF result level; SARIF has no separate info or hint level
+    pub fn sarif_level(&self) -> &'static str {
+        match self.severity {
+            ErrorSeverity::Error => "error",
+            ErrorSeverity::Warning => "warning",
+            ErrorSeverity::Info | ErrorSeverity::Hint => "note",
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "code": self.code,
+            "rule": self.rule,
+            "severity": self.severity_name(),
+            "message": self.message,
+            "file": self.file,
+            "line": self.line,
+            "column": self.column,
+            "end_column": self.end_column,
+            "suggestions": self.suggestions,
+            "help": self.help,
+        })
+    }
+}
+
+/// Validation result as a JSON document with a `diagnostics` array
+pub fn diagnostics_json(file: &str, diagnostics: &[Diagnostic]) -> String {
+    let document = json!({
+        "version": DIAGNOSTICS_SCHEMA_VERSION,
+        "file": file,
+        "valid": !diagnostics.iter().any(|d| d.severity == ErrorSeverity::Error),
+        "diagnostics": diagnostics.iter().map(Diagnostic::to_json).collect::<Vec<_>>(),
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// Rule name for a diagnostic category, e.g. `syntax-error`
+fn sarif_rule_name(error_type: &ErrorType) -> String {
+    error_type.to_string().to_lowercase().replace(' ', "-")
+}
+
+/// Diagnostics as a SARIF 2.1.0 log for GitHub code scanning and other consumers
+///
+/// Each error code or lint rule becomes a rule; a diagnostic without a position is
+/// reported against the whole file.
+pub fn diagnostics_sarif(diagnostics: &[Diagnostic]) -> String {
+    let mut rules: Vec<serde_json::Value> = Vec::new();
+    let mut rule_ids: Vec<&str> = Vec::new();
+    for diagnostic in diagnostics {
+        if !rule_ids.contains(&diagnostic.rule_id()) {
+            rule_ids.push(diagnostic.rule_id());
+            rules.push(json!({
+                "id": diagnostic.rule_id(),
+                "name": diagnostic.rule.map_or_else(|| sarif_rule_name(&diagnostic.error_type), str::to_string),
+                "shortDescription": {"text": diagnostic.error_type.to_string()},
+                "defaultConfiguration": {"level": diagnostic.sarif_level()},
+            }));
+        }
+    }
+
+    let results: Vec<serde_json::Value> = diagnostics.iter().map(|diagnostic| {
+        let mut physical_location = json!({"artifactLocation": {"uri": diagnostic.file}});
+        if let Some(line) = diagnostic.line {
+            let mut region = json!({"startLine": line});
+            if let Some(column) = diagnostic.column {
+                region["startColumn"] = json!(column);
+            }
+            if let Some(end_column) = diagnostic.end_column {
+                region["endColumn"] = json!(end_column);
+            }
+            physical_location["region"] = region;
+        }
+
+        let mut text = diagnostic.message.clone();
+        for suggestion in &diagnostic.suggestions {
+            text.push_str(&format!("\n- {}", suggestion));
+        }
+
+        json!({
+            "ruleId": diagnostic.rule_id(),
+            "ruleIndex": rule_ids.iter().position(|id| *id == diagnostic.rule_id()),
+            "level": diagnostic.sarif_level(),
+            "message": {"text": text},
+            "locations": [{"physicalLocation": physical_location}],
+        })
+    }).collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "tubular",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&log).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::coordinate::Coordinate;
+    use crate::types::error::{ErrorContext, ErrorType, Position};
+
+    #[test]
+    fn test_diagnostic_from_errors_uses_one_based_positions() {
+        let invalid = InterpreterError::Initialization(InitError::InvalidCharacter('?', Coordinate::new(2, 0)));
+        let diagnostic = Diagnostic::from_error(&invalid, "p.tb");
+        assert_eq!((diagnostic.code, diagnostic.line, diagnostic.column), ("E003", Some(1), Some(3)));
+
+        let enhanced = InterpreterError::enhanced("Unreachable cell".to_string(), ErrorType::Validation)
+            .with_context(ErrorContext::new(Position::new(3, 4, Coordinate::new(4, 3)), "  |".to_string()))
+            .with_suggestions(vec!["Remove it".to_string()]);
+        let json = Diagnostic::from_error(&enhanced, "p.tb").to_json();
+        assert_eq!(json["code"], "E002");
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["message"], "Unreachable cell");
+        assert_eq!((json["line"].as_u64(), json["column"].as_u64()), (Some(4), Some(5)));
+        assert_eq!(json["suggestions"][0], "Remove it");
+    }
+
+    #[test]
+    fn test_sarif_maps_codes_to_rules_and_spans_to_regions() {
+        let invalid = InterpreterError::Initialization(InitError::InvalidCharacter('?', Coordinate::new(2, 0)));
+        let missing_start = InterpreterError::Initialization(InitError::NoStartSymbol);
+        let diagnostics = vec![
+            Diagnostic::from_error(&invalid, "p.tb"),
+            Diagnostic::from_error(&missing_start, "p.tb"),
+        ];
+
+        let sarif: serde_json::Value = serde_json::from_str(&diagnostics_sarif(&diagnostics)).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+        assert_eq!(run["tool"]["driver"]["rules"][0]["name"], "initialization-error");
+
+        let region = &run["results"][0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region, &serde_json::json!({"startLine": 1, "startColumn": 3, "endColumn": 4}));
+        assert_eq!(run["results"][1]["ruleIndex"], 0);
+        assert!(run["results"][1]["locations"][0]["physicalLocation"]["region"].is_null());
+    }
+
+    #[test]
+    fn test_lint_findings_report_rule_ids() {
+        let grid = crate::parser::grid_parser::GridParser::new().parse_named("@\n!\n\n1", "p.tb").unwrap();
+        let findings = crate::parser::lint::Linter::new().run(&grid);
+        let diagnostic = Diagnostic::from_lint(&findings[0], &grid, "p.tb");
+        assert_eq!((diagnostic.rule, diagnostic.line, diagnostic.column), (Some("unreachable-cell"), Some(4), Some(1)));
+
+        let sarif: serde_json::Value = serde_json::from_str(&diagnostics_sarif(&[diagnostic])).unwrap();
+        assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], "unreachable-cell");
+        assert_eq!(sarif["runs"][0]["tool"]["driver"]["rules"][0]["name"], "unreachable-cell");
+    }
+}
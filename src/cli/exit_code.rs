@@ -0,0 +1,94 @@
+use crate::interpreter::execution::ExecutionStatus;
+use crate::types::error::{ErrorType, ExecError, InterpreterError};
+
+/// Process exit status returned by `Cli::run`
+///
+/// | Code | Meaning                                                        |
+/// |------|----------------------------------------------------------------|
+/// | 0    | Success                                                        |
+/// | 1    | General failure: I/O, internal errors                          |
+/// | 2    | The program failed to parse, validate or pass analysis         |
+/// | 3    | The program raised a runtime error                             |
+/// | 4    | The program hit its tick or wall-clock limit                   |
+/// | 5    | A check failed: `fmt --check` or `test` found problems         |
+/// | 130  | Execution was interrupted with Ctrl-C                          |
+///
+/// Invalid command-line arguments are reported by clap, which also exits with 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    Failure = 1,
+    InvalidProgram = 2,
+    RuntimeError = 3,
+    Timeout = 4,
+    CheckFailed = 5,
+    Cancelled = 130,
+}
+
+impl ExitCode {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Classify an error that escaped a command
+    #[cfg(feature = "cli")]
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<InterpreterError>() {
+            Some(error) => Self::from_interpreter_error(error),
+            None => ExitCode::Failure,
+        }
+    }
+
+    /// Exit code for a run that stopped with `status`
+    pub fn from_status(status: &ExecutionStatus) -> Self {
+        match status {
+            ExecutionStatus::TickTimeout(_) | ExecutionStatus::WallClockTimeout(_) => ExitCode::Timeout,
+            ExecutionStatus::Cancelled => ExitCode::Cancelled,
+            ExecutionStatus::Error(error) => Self::from_interpreter_error(error),
+            _ => ExitCode::Success,
+        }
+    }
+
+    pub fn from_interpreter_error(error: &InterpreterError) -> Self {
+        match error {
+            InterpreterError::Initialization(_) => ExitCode::InvalidProgram,
+            InterpreterError::Execution(ExecError::ExecutionTimeout(_) | ExecError::WallClockTimeout(_)) => ExitCode::Timeout,
+            InterpreterError::Execution(_) => ExitCode::RuntimeError,
+            InterpreterError::System(_) => ExitCode::Failure,
+            InterpreterError::Enhanced { info, .. } => match info.error_type {
+                ErrorType::Syntax | ErrorType::Validation | ErrorType::Initialization | ErrorType::Semantic => {
+                    ExitCode::InvalidProgram
+                }
+                ErrorType::Execution | ErrorType::Runtime => ExitCode::RuntimeError,
+                ErrorType::System => ExitCode::Failure,
+            },
+        }
+    }
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        std::process::ExitCode::from(code.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::error::{InitError, SystemError};
+
+    #[test]
+    fn test_errors_map_to_documented_codes() {
+        let cases = [
+            (InterpreterError::Initialization(InitError::NoStartSymbol), 2),
+            (InterpreterError::Execution(ExecError::DivisionByZero), 3),
+            (InterpreterError::Execution(ExecError::ExecutionTimeout(10)), 4),
+            (InterpreterError::System(SystemError::IoError("missing".to_string())), 1),
+            (InterpreterError::enhanced("bad".to_string(), ErrorType::Syntax), 2),
+        ];
+        for (error, code) in cases {
+            assert_eq!(ExitCode::from_error(&error.into()).code(), code);
+        }
+        assert_eq!(ExitCode::from_error(&anyhow::anyhow!("other")), ExitCode::Failure);
+    }
+}
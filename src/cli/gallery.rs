@@ -0,0 +1,63 @@
+/// A program bundled with the interpreter, shown by `tubular examples`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub source: &'static str,
+}
+
+/// The bundled examples, in the order they are listed
+///
+/// Sources are embedded from `examples/gallery/`, where each sits next to an
+/// `.expected` file that `tubular test examples/gallery` checks.
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "hello",
+        description: "Print \"Hello!\" from characters built on the stack",
+        source: include_str!("../../examples/gallery/hello.tb"),
+    },
+    Example {
+        name: "counter",
+        description: "Count from 1 to 5 with a subroutine loop",
+        source: include_str!("../../examples/gallery/counter.tb"),
+    },
+    Example {
+        name: "fibonacci",
+        description: "Print the Fibonacci numbers below 100",
+        source: include_str!("../../examples/gallery/fibonacci.tb"),
+    },
+    Example {
+        name: "collision",
+        description: "Add two droplets' values by letting them collide",
+        source: include_str!("../../examples/gallery/collision.tb"),
+    },
+];
+
+/// The bundled example called `name`
+pub fn find_example(name: &str) -> Option<&'static Example> {
+    EXAMPLES.iter().find(|example| example.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::execution::TubularInterpreter;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_examples_run_to_their_expected_output() {
+        let expected = [
+            include_str!("../../examples/gallery/hello.expected"),
+            include_str!("../../examples/gallery/counter.expected"),
+            include_str!("../../examples/gallery/fibonacci.expected"),
+            include_str!("../../examples/gallery/collision.expected"),
+        ];
+        for (example, expected) in EXAMPLES.iter().zip(expected) {
+            let grid = GridParser::new().parse_string(example.source).unwrap();
+            let result = TubularInterpreter::new(grid).unwrap().run().unwrap();
+            assert_eq!(result.final_output, expected, "{}", example.name);
+        }
+        assert_eq!(find_example("counter").map(|example| example.name), Some("counter"));
+        assert!(find_example("missing").is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::interpreter::interrupt::InterruptHandle;
+use crate::operations::io::{InputProvider, InputRequest};
+use crate::types::error::{Result, SystemError};
+use rustyline::config::{Behavior, Config};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+/// Prompt shown while `?` waits for a character
+pub const CHAR_PROMPT: &str = "? char> ";
+
+/// Prompt shown while `??` waits for a number
+pub const NUMBER_PROMPT: &str = "?? number> ";
+
+/// Prompt shown when a line is read without saying which operator wants it
+pub const INPUT_PROMPT: &str = "input> ";
+
+/// Reads one line after showing the given prompt
+type Readline = Box<dyn FnMut(&str) -> std::result::Result<String, ReadlineError> + Send>;
+
+/// Reads `?` and `??` input at the terminal with line editing and history,
+/// for `tubular run --interactive`
+///
+/// Prompts and editing use the terminal directly, so stdout still carries
+/// only program output. Lines entered stay in history until the run ends.
+/// Ctrl-D ends input, as end of file does for piped input, and Ctrl-C cancels the run.
+pub struct EditorInput {
+    readline: Readline,
+    cancellation: InterruptHandle,
+}
+
+impl EditorInput {
+    pub fn new(cancellation: InterruptHandle) -> Result<Self> {
+        let config = Config::builder()
+            .behavior(Behavior::PreferTerm)
+            .auto_add_history(true)
+            .build();
+        let mut editor = DefaultEditor::with_config(config)
+            .map_err(|e| SystemError::IoError(format!("Failed to open the terminal for input: {}", e)))?;
+        Ok(Self::with_readline(move |prompt| editor.readline(prompt), cancellation))
+    }
+
+    /// Input read by `readline`, which is given the prompt to show
+    pub(crate) fn with_readline(
+        readline: impl FnMut(&str) -> std::result::Result<String, ReadlineError> + Send + 'static,
+        cancellation: InterruptHandle,
+    ) -> Self {
+        EditorInput { readline: Box::new(readline), cancellation }
+    }
+
+    /// Line typed after `prompt`; None at end of input or when cancelled
+    fn prompt_line(&mut self, prompt: &str) -> Result<Option<String>> {
+        match (self.readline)(prompt) {
+            Ok(line) => Ok(Some(line)),
+            Err(ReadlineError::Eof) => Ok(None),
+            Err(ReadlineError::Interrupted) => {
+                self.cancellation.cancel();
+                Ok(None)
+            }
+            Err(e) => Err(SystemError::IoError(format!("Failed to read input: {}", e)).into()),
+        }
+    }
+}
+
+impl InputProvider for EditorInput {
+    fn read_char(&mut self) -> Result<Option<char>> {
+        Ok(self.prompt_line(CHAR_PROMPT)?.map(|line| line.chars().next().unwrap_or('\n')))
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        Ok(self.prompt_line(NUMBER_PROMPT)?.unwrap_or_default().trim().to_string())
+    }
+
+    fn read_raw_line(&mut self) -> Result<Option<String>> {
+        Ok(self.prompt_line(INPUT_PROMPT)?.map(|line| line + "\n"))
+    }
+
+    fn read_raw_line_for(&mut self, request: InputRequest) -> Result<Option<String>> {
+        let prompt = match request {
+            InputRequest::Char => CHAR_PROMPT,
+            InputRequest::Number => NUMBER_PROMPT,
+        };
+        Ok(self.prompt_line(prompt)?.map(|line| line + "\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scripted(lines: Vec<std::result::Result<&'static str, ReadlineError>>, cancellation: InterruptHandle) -> EditorInput {
+        let mut lines = lines.into_iter();
+        EditorInput::with_readline(
+            move |_| lines.next().unwrap_or(Err(ReadlineError::Eof)).map(str::to_string),
+            cancellation,
+        )
+    }
+
+    #[test]
+    fn test_end_of_input_ends_reads_without_cancelling() {
+        let cancellation = InterruptHandle::new();
+        let mut input = scripted(vec![Ok("abc"), Ok("42"), Err(ReadlineError::Eof)], cancellation.clone());
+
+        assert_eq!(input.read_char().unwrap(), Some('a'));
+        assert_eq!(input.read_line().unwrap(), "42");
+        assert_eq!(input.read_char().unwrap(), None);
+        assert_eq!(input.read_raw_line().unwrap(), None);
+        assert!(!cancellation.is_cancelled());
+    }
+
+    #[test]
+    fn test_interrupt_cancels_the_run() {
+        let cancellation = InterruptHandle::new();
+        let mut input = scripted(vec![Err(ReadlineError::Interrupted)], cancellation.clone());
+
+        assert_eq!(input.read_raw_line_for(InputRequest::Number).unwrap(), None);
+        assert!(cancellation.is_cancelled());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::interpreter::grid::ProgramCell;
+use crate::parser::grid_parser::GridParser;
+use crate::parser::stack_checker::StackChecker;
+use crate::parser::validator::ProgramValidator;
+use crate::types::error::{ErrorSeverity, InitError, InterpreterError};
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use std::collections::HashMap;
+
+/// Language server state: the latest text of every open document
+#[derive(Debug, Default)]
+pub struct LanguageServer {
+    documents: HashMap<Url, String>,
+}
+
+impl LanguageServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            definition_provider: Some(OneOf::Left(true)),
+            ..ServerCapabilities::default()
+        }
+    }
+
+    /// Serve requests over stdin/stdout until the client shuts down
+    pub fn run_stdio() -> anyhow::Result<()> {
+        let (connection, io_threads) = Connection::stdio();
+        connection.initialize(serde_json::to_value(Self::capabilities())?)?;
+
+        let mut server = LanguageServer::new();
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        break;
+                    }
+                    connection.sender.send(Message::Response(server.handle_request(request)))?;
+                }
+                Message::Notification(notification) => {
+                    if let Some(reply) = server.handle_notification(notification) {
+                        connection.sender.send(Message::Notification(reply))?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+
+        // The writer thread only exits once every sender is gone
+        drop(connection);
+        io_threads.join()?;
+        Ok(())
+    }
+
+    /// Answer a client request; unknown methods get a MethodNotFound error
+    pub fn handle_request(&mut self, request: Request) -> Response {
+        let id = request.id.clone();
+        match request.method.as_str() {
+            HoverRequest::METHOD => match serde_json::from_value::<HoverParams>(request.params) {
+                Ok(params) => {
+                    let document = &params.text_document_position_params;
+                    let hover = self.documents.get(&document.text_document.uri)
+                        .and_then(|text| hover(text, document.position));
+                    Response::new_ok(id, hover)
+                }
+                Err(e) => Self::invalid_params(id, e),
+            },
+            GotoDefinition::METHOD => match serde_json::from_value::<GotoDefinitionParams>(request.params) {
+                Ok(params) => {
+                    let uri = params.text_document_position_params.text_document.uri;
+                    let location = self.documents.get(&uri)
+                        .and_then(|text| start_symbol_range(text))
+                        .map(|range| GotoDefinitionResponse::Scalar(Location { uri, range }));
+                    Response::new_ok(id, location)
+                }
+                Err(e) => Self::invalid_params(id, e),
+            },
+            method => Response::new_err(
+                id,
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request: {}", method),
+            ),
+        }
+    }
+
+    /// Track document changes, returning the diagnostics to publish (if any)
+    pub fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        let (uri, text) = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                (params.text_document.uri, params.text_document.text)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                // Full sync: the last change holds the whole document
+                let text = params.content_changes.into_iter().last()?.text;
+                (params.text_document.uri, text)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                self.documents.remove(&params.text_document.uri);
+                return Some(Self::publish(params.text_document.uri, Vec::new()));
+            }
+            _ => return None,
+        };
+
+        let diagnostics = diagnostics(&text, uri.path());
+        self.documents.insert(uri.clone(), text);
+        Some(Self::publish(uri, diagnostics))
+    }
+
+    fn publish(uri: Url, diagnostics: Vec<Diagnostic>) -> Notification {
+        Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            PublishDiagnosticsParams { uri, diagnostics, version: None },
+        )
+    }
+
+    fn invalid_params(id: RequestId, error: serde_json::Error) -> Response {
+        Response::new_err(id, lsp_server::ErrorCode::InvalidParams as i32, error.to_string())
+    }
+}
+
+/// Parser, validator and stack-check findings for a document
+pub fn diagnostics(text: &str, source_name: &str) -> Vec<Diagnostic> {
+    let grid = match GridParser::new().parse_named(text, source_name) {
+        Ok(grid) => grid,
+        Err(e) => return vec![error_diagnostic(&e)],
+    };
+
+    let mut diagnostics = Vec::new();
+    let validator = ProgramValidator::new().with_source_content(text.to_string());
+    if let Err(e) = validator.validate(&grid) {
+        diagnostics.push(error_diagnostic(&e));
+    }
+
+    for finding in StackChecker::check(&grid) {
+        let position = grid.source_location(finding.coordinate)
+            .map(|loc| Position::new(loc.line as u32, loc.column as u32))
+            .unwrap_or_default();
+        diagnostics.push(Diagnostic {
+            range: cell_range(position),
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some("tubular".to_string()),
+            message: finding.to_string(),
+            ..Diagnostic::default()
+        });
+    }
+
+    diagnostics
+}
+
+/// Description of the symbol under `position`
+pub fn hover(text: &str, position: Position) -> Option<Hover> {
+    let symbol = text.lines().nth(position.line as usize)?.chars().nth(position.character as usize)?;
+    let description = ProgramCell::description(symbol)?;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("`{}` — {}", symbol, description),
+        }),
+        range: Some(cell_range(position)),
+    })
+}
+
+/// Location of the `@` start symbol
+pub fn start_symbol_range(text: &str) -> Option<Range> {
+    text.lines().enumerate().find_map(|(line, row)| {
+        row.chars()
+            .position(|ch| ch == '@')
+            .map(|column| cell_range(Position::new(line as u32, column as u32)))
+    })
+}
+
+fn error_diagnostic(error: &InterpreterError) -> Diagnostic {
+    let position = match error {
+        InterpreterError::Initialization(InitError::InvalidCharacter(_, coord)) => {
+            Position::new(coord.y.max(0) as u32, coord.x.max(0) as u32)
+        }
+        _ => error.context()
+            .map(|context| Position::new(context.position.line as u32, context.position.column as u32))
+            .unwrap_or_default(),
+    };
+    let severity = match error.severity() {
+        ErrorSeverity::Error => DiagnosticSeverity::ERROR,
+        ErrorSeverity::Warning => DiagnosticSeverity::WARNING,
+        ErrorSeverity::Info => DiagnosticSeverity::INFORMATION,
+        ErrorSeverity::Hint => DiagnosticSeverity::HINT,
+    };
+
+    let mut message = error.to_string();
+    for suggestion in error.suggestions() {
+        message.push_str(&format!("\n• {}", suggestion));
+    }
+
+    Diagnostic {
+        range: cell_range(position),
+        severity: Some(severity),
+        source: Some("tubular".to_string()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+/// Range covering the single grid cell starting at `position`
+fn cell_range(position: Position) -> Range {
+    Range::new(position, Position::new(position.line, position.character + 1))
+}
@@ -1,5 +1,51 @@
+#[cfg(feature = "cli")]
+pub mod benchmark;
+#[cfg(feature = "cli")]
 pub mod commands;
+#[cfg(feature = "cli")]
+pub mod debugger;
+#[cfg(feature = "cli")]
+pub mod diagnostics;
+pub mod exit_code;
+pub mod gallery;
+#[cfg(feature = "cli")]
+pub mod line_editor;
+#[cfg(feature = "cli")]
+pub mod lsp;
+#[cfg(feature = "cli")]
 pub mod output;
+#[cfg(feature = "cli")]
+pub mod project;
+pub mod repl;
+#[cfg(feature = "cli")]
+pub mod replay;
+pub mod scaffold;
+pub mod test_runner;
+#[cfg(feature = "cli")]
+pub mod visualizer;
 
+#[cfg(feature = "cli")]
+pub use benchmark::*;
+#[cfg(feature = "cli")]
 pub use commands::*;
-pub use output::*;
\ No newline at end of file
+#[cfg(feature = "cli")]
+pub use debugger::*;
+#[cfg(feature = "cli")]
+pub use diagnostics::*;
+pub use exit_code::*;
+pub use gallery::*;
+#[cfg(feature = "cli")]
+pub use line_editor::*;
+#[cfg(feature = "cli")]
+pub use lsp::*;
+#[cfg(feature = "cli")]
+pub use output::*;
+#[cfg(feature = "cli")]
+pub use project::*;
+pub use repl::*;
+#[cfg(feature = "cli")]
+pub use replay::*;
+pub use scaffold::*;
+pub use test_runner::*;
+#[cfg(feature = "cli")]
+pub use visualizer::*;
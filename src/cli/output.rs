@@ -1,291 +1,20 @@
-use crate::interpreter::execution::{ExecutionResult, TickResult};
+use crate::interpreter::execution::{ExecutionResult, ResourceUsage, TickResult};
 use crate::interpreter::droplet::Droplet;
 use crate::interpreter::stack::DataStack;
 use crate::interpreter::memory::Reservoir;
 use crate::interpreter::subroutines::CallStack;
+use crate::interpreter::grid::{ProgramCell, ProgramGrid};
+use crate::interpreter::profiler::ExecutionProfile;
+use crate::interpreter::optimizer::Fold;
+use crate::parser::inspect::ProgramReport;
 use crate::types::coordinate::Coordinate;
-use crate::types::direction::Direction;
-use crate::types::bigint::TubularBigInt;
 use std::io::{self, Write};
-use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
-/// Trace configuration for execution debugging
-#[derive(Debug, Clone)]
-pub struct TraceConfig {
-    /// Trace level of detail
-    pub level: TraceLevel,
-    /// Output format for traces
-    pub format: TraceFormat,
-    /// Filter traces by droplet IDs (None = all droplets)
-    pub droplet_filter: Option<HashSet<u64>>,
-    /// Filter traces by operation types (None = all operations)
-    pub operation_filter: Option<HashSet<TraceOperation>>,
-    /// Filter traces by tick range (None = all ticks)
-    pub tick_range: Option<(u64, u64)>,
-    /// Maximum number of trace events to capture (None = unlimited)
-    pub max_events: Option<usize>,
-    /// Whether to include performance metrics
-    pub include_performance: bool,
-    /// Whether to include memory state changes
-    pub include_memory: bool,
-    /// Whether to include stack state changes
-    pub include_stack: bool,
-    /// Whether to include subroutine call tracking
-    pub include_subroutines: bool,
-}
-
-/// Level of detail for execution tracing
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum TraceLevel {
-    /// Basic tick-level information only
-    Basic,
-    /// Detailed operation-level information
-    Detailed,
-    /// Verbose execution with all state changes
-    Verbose,
-}
-
-/// Output format for trace information
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum TraceFormat {
-    /// Compact single-line format
-    Compact,
-    /// Detailed multi-line format
-    Detailed,
-    /// JSON structured format
-    Json,
-}
-
-/// Types of operations that can be traced
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum TraceOperation {
-    /// Droplet movement
-    Movement,
-    /// Value changes
-    ValueChange,
-    /// Stack operations (push, pop, etc.)
-    StackOp,
-    /// Memory operations (get, put)
-    MemoryOp,
-    /// Arithmetic operations
-    ArithmeticOp,
-    /// Input/output operations
-    IoOp,
-    /// Subroutine calls
-    SubroutineCall,
-    /// Subroutine returns
-    SubroutineReturn,
-    /// Direction changes
-    DirectionChange,
-    /// Collision detection
-    Collision,
-    /// Droplet creation/destruction
-    DropletLifecycle,
-}
-
-/// Detailed trace event for comprehensive execution tracking
-#[derive(Debug, Clone)]
-pub struct TraceEvent {
-    /// Tick number when this event occurred
-    pub tick: u64,
-    /// Timestamp for performance tracking
-    pub timestamp: Duration,
-    /// ID of the droplet involved (if applicable)
-    pub droplet_id: Option<u64>,
-    /// Type of operation
-    pub operation: TraceOperation,
-    /// Position in grid (if applicable)
-    pub position: Option<Coordinate>,
-    /// Cell symbol being processed (if applicable)
-    pub cell_symbol: Option<char>,
-    /// Detailed description of the event
-    pub description: String,
-    /// Previous state (for state changes)
-    pub before_state: Option<TraceState>,
-    /// New state (for state changes)
-    pub after_state: Option<TraceState>,
-    /// Additional metadata
-    pub metadata: TraceMetadata,
-}
-
-/// State information for tracing
-#[derive(Debug, Clone)]
-pub struct TraceState {
-    /// Droplet value
-    pub droplet_value: Option<TubularBigInt>,
-    /// Droplet direction
-    pub droplet_direction: Option<Direction>,
-    /// Stack contents (if enabled)
-    pub stack_contents: Option<Vec<TubularBigInt>>,
-    /// Call stack depth
-    pub call_stack_depth: Option<usize>,
-    /// Memory coordinates accessed (if applicable)
-    pub memory_coord: Option<Coordinate>,
-}
-
-/// Additional metadata for trace events
-#[derive(Debug, Clone)]
-pub struct TraceMetadata {
-    /// Execution time for this operation
-    pub execution_time_us: Option<u64>,
-    /// Number of active droplets at this point
-    pub active_droplets: usize,
-    /// Total memory usage estimate
-    pub memory_usage_bytes: usize,
-    /// Collision count for this tick
-    pub collision_count: usize,
-    /// Additional key-value data
-    pub extra: std::collections::HashMap<String, String>,
-}
-
-/// Performance metrics collected during execution
-#[derive(Debug, Clone)]
-pub struct PerformanceMetrics {
-    /// Time taken for this tick
-    pub tick_time_us: u64,
-    /// Number of droplet operations
-    pub droplet_operations: usize,
-    /// Stack operations count
-    pub stack_operations: usize,
-    /// Memory operations count
-    pub memory_operations: usize,
-    /// Collision detection time
-    pub collision_time_us: u64,
-    /// Grid access count
-    pub grid_accesses: usize,
-}
-
-impl Default for TraceConfig {
-    fn default() -> Self {
-        Self {
-            level: TraceLevel::Basic,
-            format: TraceFormat::Compact,
-            droplet_filter: None,
-            operation_filter: None,
-            tick_range: None,
-            max_events: None,
-            include_performance: false,
-            include_memory: false,
-            include_stack: false,
-            include_subroutines: false,
-        }
-    }
-}
-
-impl TraceConfig {
-    /// Create a new trace configuration with basic settings
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// Set trace level
-    pub fn with_level(mut self, level: TraceLevel) -> Self {
-        self.level = level;
-        self
-    }
-
-    /// Set trace format
-    pub fn with_format(mut self, format: TraceFormat) -> Self {
-        self.format = format;
-        self
-    }
-
-    /// Filter by specific droplet IDs
-    pub fn with_droplet_filter(mut self, droplet_ids: HashSet<u64>) -> Self {
-        self.droplet_filter = Some(droplet_ids);
-        self
-    }
-
-    /// Filter by specific operation types
-    pub fn with_operation_filter(mut self, operations: HashSet<TraceOperation>) -> Self {
-        self.operation_filter = Some(operations);
-        self
-    }
-
-    /// Filter by tick range
-    pub fn with_tick_range(mut self, start: u64, end: u64) -> Self {
-        self.tick_range = Some((start, end));
-        self
-    }
-
-    /// Set maximum number of events to capture
-    pub fn with_max_events(mut self, max_events: usize) -> Self {
-        self.max_events = Some(max_events);
-        self
-    }
-
-    /// Enable performance metrics collection
-    pub fn with_performance(mut self, include: bool) -> Self {
-        self.include_performance = include;
-        self
-    }
-
-    /// Enable memory state tracking
-    pub fn with_memory(mut self, include: bool) -> Self {
-        self.include_memory = include;
-        self
-    }
-
-    /// Enable stack state tracking
-    pub fn with_stack(mut self, include: bool) -> Self {
-        self.include_stack = include;
-        self
-    }
-
-    /// Enable subroutine tracking
-    pub fn with_subroutines(mut self, include: bool) -> Self {
-        self.include_subroutines = include;
-        self
-    }
-
-    /// Create a comprehensive trace configuration for debugging
-    pub fn comprehensive() -> Self {
-        Self {
-            level: TraceLevel::Verbose,
-            format: TraceFormat::Detailed,
-            droplet_filter: None,
-            operation_filter: None,
-            tick_range: None,
-            max_events: None,
-            include_performance: true,
-            include_memory: true,
-            include_stack: true,
-            include_subroutines: true,
-        }
-    }
-
-    /// Check if an event should be included based on filters
-    pub fn should_include_event(&self, event: &TraceEvent) -> bool {
-        // Check droplet filter
-        if let Some(ref droplet_filter) = self.droplet_filter {
-            if let Some(droplet_id) = event.droplet_id {
-                if !droplet_filter.contains(&droplet_id) {
-                    return false;
-                }
-            } else {
-                // Event has no droplet ID, skip if droplet filter is active
-                return false;
-            }
-        }
-
-        // Check operation filter
-        if let Some(ref operation_filter) = self.operation_filter {
-            if !operation_filter.contains(&event.operation) {
-                return false;
-            }
-        }
-
-        // Check tick range filter
-        if let Some((start, end)) = self.tick_range {
-            if event.tick < start || event.tick > end {
-                return false;
-            }
-        }
-
-        true
-    }
-}
+pub use crate::interpreter::trace::{
+    CHROME_TICK_US, PerformanceMetrics, TRACE_SCHEMA_VERSION, TraceConfig, TraceDocument, TraceEvent,
+    TraceFormat, TraceLevel, TraceMetadata, TraceOperation, TraceState,
+};
 
 /// CLI output formatting for Tubular interpreter
 pub struct OutputFormatter;
@@ -363,337 +92,14 @@ impl OutputFormatter {
         output
     }
 
-    // === COMPREHENSIVE TRACING METHODS ===
-
-    /// Create a basic trace event for droplet movement
-    pub fn create_movement_trace_event(
-        tick: u64,
-        droplet_id: u64,
-        from_pos: Coordinate,
-        to_pos: Coordinate,
-        direction: Direction,
-        value: &TubularBigInt,
-        cell_symbol: Option<char>,
-    ) -> TraceEvent {
-        let timestamp = Duration::from_millis(tick); // Simplified timestamp
-
-        TraceEvent {
-            tick,
-            timestamp,
-            droplet_id: Some(droplet_id),
-            operation: TraceOperation::Movement,
-            position: Some(to_pos),
-            cell_symbol,
-            description: format!(
-                "Droplet {} moves from {} to {} heading {:?} with value {}",
-                droplet_id, from_pos, to_pos, direction, value
-            ),
-            before_state: Some(TraceState {
-                droplet_value: Some(value.clone()),
-                droplet_direction: Some(direction),
-                stack_contents: None,
-                call_stack_depth: None,
-                memory_coord: Some(from_pos),
-            }),
-            after_state: Some(TraceState {
-                droplet_value: Some(value.clone()),
-                droplet_direction: Some(direction),
-                stack_contents: None,
-                call_stack_depth: None,
-                memory_coord: Some(to_pos),
-            }),
-            metadata: TraceMetadata {
-                execution_time_us: None,
-                active_droplets: 1,
-                memory_usage_bytes: 0,
-                collision_count: 0,
-                extra: std::collections::HashMap::new(),
-            },
-        }
-    }
-
-    /// Create a trace event for stack operations
-    pub fn create_stack_trace_event(
-        tick: u64,
-        droplet_id: u64,
-        operation: char,
-        position: Coordinate,
-        stack_before: &[TubularBigInt],
-        stack_after: &[TubularBigInt],
-        droplet_value: &TubularBigInt,
-    ) -> TraceEvent {
-        let operation_type = match operation {
-            ':' => TraceOperation::StackOp,
-            ';' => TraceOperation::StackOp,
-            'd' => TraceOperation::StackOp,
-            'A' | 'S' | 'M' | 'D' | '=' | '<' | '>' | '%' => TraceOperation::ArithmeticOp,
-            '+' | '~' => TraceOperation::ValueChange,
-            _ => TraceOperation::StackOp,
-        };
-
-        let operation_desc = match operation {
-            ':' => "push",
-            ';' => "pop",
-            'd' => "duplicate",
-            'A' => "add",
-            'S' => "subtract",
-            'M' => "multiply",
-            'D' => "divide",
-            '=' => "equals",
-            '<' => "less_than",
-            '>' => "greater_than",
-            '%' => "modulo",
-            '+' => "increment",
-            '~' => "decrement",
-            _ => "unknown_stack_op",
-        };
-
-        TraceEvent {
-            tick,
-            timestamp: Duration::from_millis(tick),
-            droplet_id: Some(droplet_id),
-            operation: operation_type,
-            position: Some(position),
-            cell_symbol: Some(operation),
-            description: format!(
-                "Droplet {} performs {} operation at {} with value {}",
-                droplet_id, operation_desc, position, droplet_value
-            ),
-            before_state: Some(TraceState {
-                droplet_value: Some(droplet_value.clone()),
-                droplet_direction: None,
-                stack_contents: Some(stack_before.to_vec()),
-                call_stack_depth: None,
-                memory_coord: Some(position),
-            }),
-            after_state: Some(TraceState {
-                droplet_value: Some(droplet_value.clone()),
-                droplet_direction: None,
-                stack_contents: Some(stack_after.to_vec()),
-                call_stack_depth: None,
-                memory_coord: Some(position),
-            }),
-            metadata: TraceMetadata {
-                execution_time_us: None,
-                active_droplets: 1,
-                memory_usage_bytes: stack_before.len() * std::mem::size_of::<TubularBigInt>(),
-                collision_count: 0,
-                extra: {
-                    let mut extra = std::collections::HashMap::new();
-                    extra.insert("stack_depth_before".to_string(), stack_before.len().to_string());
-                    extra.insert("stack_depth_after".to_string(), stack_after.len().to_string());
-                    extra
-                },
-            },
-        }
-    }
-
-    /// Create a trace event for memory operations
-    pub fn create_memory_trace_event(
-        tick: u64,
-        droplet_id: u64,
-        operation: char, // 'G' for get, 'P' for put
-        position: Coordinate,
-        memory_coord: Coordinate,
-        memory_value: &TubularBigInt,
-        droplet_value: &TubularBigInt,
-    ) -> TraceEvent {
-        let operation_type = match operation {
-            'G' => TraceOperation::MemoryOp,
-            'P' => TraceOperation::MemoryOp,
-            _ => TraceOperation::MemoryOp,
-        };
-
-        let operation_desc = match operation {
-            'G' => "get",
-            'P' => "put",
-            _ => "unknown_memory_op",
-        };
-
-        TraceEvent {
-            tick,
-            timestamp: Duration::from_millis(tick),
-            droplet_id: Some(droplet_id),
-            operation: operation_type,
-            position: Some(position),
-            cell_symbol: Some(operation),
-            description: format!(
-                "Droplet {} performs memory {} at coordinate {} with value {}",
-                droplet_id, operation_desc, memory_coord, memory_value
-            ),
-            before_state: Some(TraceState {
-                droplet_value: Some(droplet_value.clone()),
-                droplet_direction: None,
-                stack_contents: None,
-                call_stack_depth: None,
-                memory_coord: Some(memory_coord),
-            }),
-            after_state: Some(TraceState {
-                droplet_value: Some(match operation {
-                    'G' => memory_value.clone(), // After get, droplet has the memory value
-                    'P' => droplet_value.clone(), // After put, droplet value unchanged
-                    _ => droplet_value.clone(),
-                }),
-                droplet_direction: None,
-                stack_contents: None,
-                call_stack_depth: None,
-                memory_coord: Some(memory_coord),
-            }),
-            metadata: TraceMetadata {
-                execution_time_us: None,
-                active_droplets: 1,
-                memory_usage_bytes: std::mem::size_of::<TubularBigInt>(),
-                collision_count: 0,
-                extra: {
-                    let mut extra = std::collections::HashMap::new();
-                    extra.insert("memory_operation".to_string(), operation.to_string());
-                    extra.insert("memory_coord_x".to_string(), memory_coord.x.to_string());
-                    extra.insert("memory_coord_y".to_string(), memory_coord.y.to_string());
-                    extra
-                },
-            },
-        }
-    }
-
-    /// Create a trace event for I/O operations
-    pub fn create_io_trace_event(
-        tick: u64,
-        droplet_id: u64,
-        operation: char, // ',' for char output, 'n' for numeric output, '?' for input
-        position: Coordinate,
-        io_value: &str,
-        droplet_value: &TubularBigInt,
-    ) -> TraceEvent {
-        let operation_type = match operation {
-            ',' | 'n' => TraceOperation::IoOp,
-            '?' => TraceOperation::IoOp,
-            _ => TraceOperation::IoOp,
-        };
-
-        let operation_desc = match operation {
-            ',' => "char_output",
-            'n' => "numeric_output",
-            '?' => "input",
-            _ => "unknown_io_op",
-        };
-
-        TraceEvent {
-            tick,
-            timestamp: Duration::from_millis(tick),
-            droplet_id: Some(droplet_id),
-            operation: operation_type,
-            position: Some(position),
-            cell_symbol: Some(operation),
-            description: format!(
-                "Droplet {} performs {} at {} with value '{}'",
-                droplet_id, operation_desc, position, io_value
-            ),
-            before_state: Some(TraceState {
-                droplet_value: Some(droplet_value.clone()),
-                droplet_direction: None,
-                stack_contents: None,
-                call_stack_depth: None,
-                memory_coord: Some(position),
-            }),
-            after_state: Some(TraceState {
-                droplet_value: Some(droplet_value.clone()),
-                droplet_direction: None,
-                stack_contents: None,
-                call_stack_depth: None,
-                memory_coord: Some(position),
-            }),
-            metadata: TraceMetadata {
-                execution_time_us: None,
-                active_droplets: 1,
-                memory_usage_bytes: 0,
-                collision_count: 0,
-                extra: {
-                    let mut extra = std::collections::HashMap::new();
-                    extra.insert("io_value".to_string(), io_value.to_string());
-                    extra.insert("io_operation".to_string(), operation.to_string());
-                    extra
-                },
-            },
-        }
-    }
-
-    /// Create a trace event for collision detection
-    pub fn create_collision_trace_event(
-        tick: u64,
-        collision_position: Coordinate,
-        colliding_droplet_ids: &[u64],
-        active_droplet_count: usize,
-    ) -> TraceEvent {
-        TraceEvent {
-            tick,
-            timestamp: Duration::from_millis(tick),
-            droplet_id: None, // Multiple droplets involved
-            operation: TraceOperation::Collision,
-            position: Some(collision_position),
-            cell_symbol: None,
-            description: format!(
-                "Collision at {} involving {} droplets: {:?}",
-                collision_position, colliding_droplet_ids.len(), colliding_droplet_ids
-            ),
-            before_state: None,
-            after_state: None,
-            metadata: TraceMetadata {
-                execution_time_us: None,
-                active_droplets: active_droplet_count,
-                memory_usage_bytes: 0,
-                collision_count: colliding_droplet_ids.len(),
-                extra: {
-                    let mut extra = std::collections::HashMap::new();
-                    extra.insert("colliding_droplets".to_string(),
-                        format!("[{}]", colliding_droplet_ids.iter()
-                            .map(|id| id.to_string())
-                            .collect::<Vec<_>>()
-                            .join(", ")));
-                    extra
-                },
-            },
-        }
-    }
-
-    /// Create a trace event for droplet lifecycle (creation/destruction)
-    pub fn create_lifecycle_trace_event(
-        tick: u64,
-        droplet_id: u64,
-        event_type: &str, // "created" or "destroyed"
-        position: Coordinate,
-        value: &TubularBigInt,
-        direction: Direction,
-    ) -> TraceEvent {
-        TraceEvent {
-            tick,
-            timestamp: Duration::from_millis(tick),
-            droplet_id: Some(droplet_id),
-            operation: TraceOperation::DropletLifecycle,
-            position: Some(position),
-            cell_symbol: None,
-            description: format!(
-                "Droplet {} {} at {} with value {} heading {:?}",
-                droplet_id, event_type, position, value, direction
-            ),
-            before_state: None,
-            after_state: Some(TraceState {
-                droplet_value: Some(value.clone()),
-                droplet_direction: Some(direction),
-                stack_contents: None,
-                call_stack_depth: None,
-                memory_coord: Some(position),
-            }),
-            metadata: TraceMetadata {
-                execution_time_us: None,
-                active_droplets: 1,
-                memory_usage_bytes: std::mem::size_of::<TubularBigInt>(),
-                collision_count: 0,
-                extra: {
-                    let mut extra = std::collections::HashMap::new();
-                    extra.insert("lifecycle_event".to_string(), event_type.to_string());
-                    extra
-                },
-            },
+    /// Fill in the original source location of each event from the grid's source map
+    pub fn attach_source_locations(events: &mut [TraceEvent], grid: &ProgramGrid) {
+        for event in events.iter_mut() {
+            if event.source_location.is_none() {
+                if let Some(pos) = event.position {
+                    event.source_location = grid.source_location(pos);
+                }
+            }
         }
     }
 
@@ -702,7 +108,8 @@ impl OutputFormatter {
         match config.format {
             TraceFormat::Compact => self.format_trace_event_compact(event, config),
             TraceFormat::Detailed => self.format_trace_event_detailed(event, config),
-            TraceFormat::Json => self.format_trace_event_json(event, config),
+            TraceFormat::Json => serde_json::to_string_pretty(&event.to_json()).unwrap_or_default(),
+            TraceFormat::Chrome => Self::chrome_event(event, event.tick * CHROME_TICK_US, CHROME_TICK_US).to_string(),
         }
     }
 
@@ -726,6 +133,11 @@ impl OutputFormatter {
             output.push_str(&format!(" @{}", pos));
         }
 
+        // Add original source location if known
+        if let Some(location) = &event.source_location {
+            output.push_str(&format!(" [{}]", location));
+        }
+
         // Add cell symbol if available
         if let Some(symbol) = event.cell_symbol {
             output.push_str(&format!(" '{}'", symbol));
@@ -774,6 +186,9 @@ impl OutputFormatter {
         if let Some(pos) = event.position {
             output.push_str(&format!("║ Position: {}\n", pos));
         }
+        if let Some(location) = &event.source_location {
+            output.push_str(&format!("║ Source: {}\n", location));
+        }
         if let Some(symbol) = event.cell_symbol {
             output.push_str(&format!("║ Cell Symbol: '{}'\n", symbol));
         }
@@ -833,95 +248,112 @@ impl OutputFormatter {
         output
     }
 
-    /// Format trace event in JSON format
-    fn format_trace_event_json(&self, event: &TraceEvent, _config: &TraceConfig) -> String {
-        let mut json_parts = Vec::new();
-
-        // Basic fields
-        json_parts.push(format!("\"tick\": {}", event.tick));
-        json_parts.push(format!("\"operation\": \"{:?}\"", event.operation));
-        json_parts.push(format!("\"timestamp_us\": {}", event.timestamp.as_micros()));
-
-        if let Some(droplet_id) = event.droplet_id {
-            json_parts.push(format!("\"droplet_id\": {}", droplet_id));
-        }
+    /// Track ID for a droplet's events; track 0 holds events with no droplet
+    fn chrome_track(droplet_id: Option<u64>) -> u64 {
+        droplet_id.map_or(0, |id| id + 1)
+    }
 
+    /// One complete ("X") event placed at `ts` microseconds on its droplet's track
+    fn chrome_event(event: &TraceEvent, ts: u64, dur: u64) -> serde_json::Value {
+        let mut args = serde_json::Map::new();
+        args.insert("tick".to_string(), event.tick.into());
+        args.insert("description".to_string(), event.description.clone().into());
         if let Some(pos) = event.position {
-            json_parts.push(format!("\"position\": {{\"x\": {}, \"y\": {}}}", pos.x, pos.y));
-        }
-
-        if let Some(symbol) = event.cell_symbol {
-            json_parts.push(format!("\"cell_symbol\": \"{}\"", symbol));
+            args.insert("position".to_string(), pos.to_string().into());
         }
-
-        json_parts.push(format!("\"description\": \"{}\"",
-            event.description.replace('"', "\\\"")));
-
-        // State information
-        if let (Some(before), Some(after)) = (&event.before_state, &event.after_state) {
-            let before_json = self.format_trace_state_json(before);
-            let after_json = self.format_trace_state_json(after);
-            json_parts.push(format!("\"before_state\": {}", before_json));
-            json_parts.push(format!("\"after_state\": {}", after_json));
+        if let Some(location) = &event.source_location {
+            args.insert("source".to_string(),
+                format!("{}:{}:{}", location.file, location.line + 1, location.column + 1).into());
         }
-
-        // Metadata
-        json_parts.push(format!("\"active_droplets\": {}", event.metadata.active_droplets));
-        json_parts.push(format!("\"memory_usage_bytes\": {}", event.metadata.memory_usage_bytes));
-        json_parts.push(format!("\"collision_count\": {}", event.metadata.collision_count));
-
-        if let Some(exec_time) = event.metadata.execution_time_us {
-            json_parts.push(format!("\"execution_time_us\": {}", exec_time));
+        if let Some(symbol) = event.cell_symbol {
+            args.insert("cell_symbol".to_string(), symbol.to_string().into());
         }
-
-        if !event.metadata.extra.is_empty() {
-            let extra_json = event.metadata.extra.iter()
-                .map(|(k, v)| format!("\"{}\": \"{}\"", k, v))
-                .collect::<Vec<_>>()
-                .join(", ");
-            json_parts.push(format!("\"extra\": {{{}}}", extra_json));
+        if let Some(value) = event.after_state.as_ref().and_then(|state| state.droplet_value.as_ref()) {
+            args.insert("value".to_string(), value.to_string().into());
         }
 
-        format!("{{\n  {}\n}}", json_parts.join(",\n  "))
+        serde_json::json!({
+            "name": format!("{:?}", event.operation),
+            "cat": "tubular",
+            "ph": "X",
+            "ts": ts,
+            "dur": dur,
+            "pid": 1,
+            "tid": Self::chrome_track(event.droplet_id),
+            "args": args,
+        })
     }
 
-    /// Helper to format TraceState as JSON
-    fn format_trace_state_json(&self, state: &TraceState) -> String {
-        let mut parts = Vec::new();
-
-        if let Some(ref value) = state.droplet_value {
-            parts.push(format!("\"droplet_value\": \"{}\"", value));
+    /// Chrome trace event file with one track per droplet and one span per tick
+    ///
+    /// The timeline is measured in ticks rather than wall-clock time: each tick
+    /// spans `CHROME_TICK_US` microseconds, shown as 1ms in the viewer, and the
+    /// events a droplet produced during that tick split the span evenly.
+    pub fn format_trace_events_chrome(events: &[&TraceEvent]) -> String {
+        let mut trace_events = Vec::new();
+
+        let mut tracks: Vec<Option<u64>> = events.iter().map(|event| event.droplet_id).collect();
+        tracks.sort();
+        tracks.dedup();
+        for droplet_id in tracks {
+            let name = droplet_id.map_or("Engine".to_string(), |id| format!("Droplet D{}", id));
+            trace_events.push(serde_json::json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": 1,
+                "tid": Self::chrome_track(droplet_id),
+                "args": {"name": name},
+            }));
         }
 
-        if let Some(ref direction) = state.droplet_direction {
-            parts.push(format!("\"droplet_direction\": \"{:?}\"", direction));
-        }
-
-        if let Some(ref stack) = state.stack_contents {
-            let stack_json = stack.iter()
-                .map(|v| format!("\"{}\"", v))
-                .collect::<Vec<_>>()
-                .join(", ");
-            parts.push(format!("\"stack_contents\": [{}]", stack_json));
-        }
-
-        if let Some(depth) = state.call_stack_depth {
-            parts.push(format!("\"call_stack_depth\": {}", depth));
+        // Events are recorded in tick order, so each (tick, droplet) group is contiguous
+        let mut groups: Vec<Vec<&TraceEvent>> = Vec::new();
+        for event in events {
+            match groups.last_mut() {
+                Some(group) if group[0].tick == event.tick && group[0].droplet_id == event.droplet_id => group.push(event),
+                _ => groups.push(vec![event]),
+            }
         }
 
-        if let Some(ref coord) = state.memory_coord {
-            parts.push(format!("\"memory_coord\": {{\"x\": {}, \"y\": {}}}", coord.x, coord.y));
+        for group in groups {
+            let (tick, droplet_id) = (group[0].tick, group[0].droplet_id);
+            let start = tick * CHROME_TICK_US;
+            trace_events.push(serde_json::json!({
+                "name": format!("Tick {}", tick),
+                "cat": "tick",
+                "ph": "X",
+                "ts": start,
+                "dur": CHROME_TICK_US,
+                "pid": 1,
+                "tid": Self::chrome_track(droplet_id),
+                "args": {"tick": tick},
+            }));
+
+            let slice = CHROME_TICK_US / group.len() as u64;
+            for (i, event) in group.iter().enumerate() {
+                trace_events.push(Self::chrome_event(event, start + i as u64 * slice, slice));
+            }
         }
 
-        if parts.is_empty() {
-            "{{}}".to_string()
-        } else {
-            format!("{{\n    {} }}", parts.join(",\n    "))
-        }
+        let trace = serde_json::json!({
+            "traceEvents": trace_events,
+            "displayTimeUnit": "ms",
+        });
+        serde_json::to_string(&trace).unwrap_or_default()
     }
 
     /// Format multiple trace events with filtering
     pub fn format_trace_events(&self, events: &[TraceEvent], config: &TraceConfig) -> String {
+        self.format_trace_events_with_program(events, config, None)
+    }
+
+    /// Format trace events, embedding the program in JSON traces so they can be replayed
+    pub fn format_trace_events_with_program(
+        &self,
+        events: &[TraceEvent],
+        config: &TraceConfig,
+        program: Option<&ProgramGrid>,
+    ) -> String {
         let mut output = String::new();
 
         // Apply filters
@@ -930,6 +362,18 @@ impl OutputFormatter {
             .take(config.max_events.unwrap_or(events.len()))
             .collect();
 
+        match config.format {
+            TraceFormat::Chrome => return Self::format_trace_events_chrome(&filtered_events),
+            TraceFormat::Json => {
+                let document = TraceDocument {
+                    program: program.map(ProgramGrid::to_text),
+                    events: filtered_events.into_iter().cloned().collect(),
+                };
+                return document.to_json(config);
+            }
+            _ => {}
+        }
+
         // Add header
         match config.format {
             TraceFormat::Compact => {
@@ -953,45 +397,16 @@ impl OutputFormatter {
                     "╚════════════════════════════════════════════════════════════╝\n"
                 ));
             }
-            TraceFormat::Json => {
-                output.push_str("{\n");
-                output.push_str(&format!("  \"trace_info\": {{\n"));
-                output.push_str(&format!("    \"total_events\": {},\n", filtered_events.len()));
-                output.push_str(&format!("    \"level\": \"{:?}\",\n", config.level));
-                output.push_str(&format!("    \"format\": \"{:?}\"\n", config.format));
-                output.push_str(&format!("  }},\n"));
-                output.push_str(&format!("  \"events\": [\n"));
-            }
+            TraceFormat::Json | TraceFormat::Chrome => {}
         }
 
         // Format each event
-        for (i, event) in filtered_events.iter().enumerate() {
-            let formatted = self.format_trace_event(event, config);
-
-            match config.format {
-                TraceFormat::Json => {
-                    output.push_str("    ");
-                    // Add comma between events except last one
-                    if i < filtered_events.len() - 1 {
-                        output.push_str(&formatted.replace('\n', "\n    "));
-                        output.push_str(",");
-                    } else {
-                        output.push_str(&formatted.replace('\n', "\n    "));
-                    }
-                    output.push('\n');
-                }
-                _ => {
-                    output.push_str(&formatted);
-                }
-            }
+        for event in &filtered_events {
+            output.push_str(&self.format_trace_event(event, config));
         }
 
         // Add footer
         match config.format {
-            TraceFormat::Json => {
-                output.push_str("  ]\n");
-                output.push_str("}\n");
-            }
             TraceFormat::Detailed => {
                 output.push_str("╔════════════════════════════════════════════════════════════╗\n");
                 output.push_str("║                     END OF TRACE                          ║\n");
@@ -1040,6 +455,7 @@ impl OutputFormatter {
         output.push_str(&format!("Execution Time: {}ms\n", result.execution_time_ms));
         output.push_str(&format!("Peak Droplets: {}\n", result.max_droplets));
         output.push_str(&format!("Peak Stack Depth: {}\n", result.max_stack_depth));
+        output.push_str(&format!("Peak Value Size: {} digits\n", result.peak_value_digits));
         output.push_str(&format!("Final Status: {:?}\n", result.status));
 
         // Display timeout information
@@ -1050,6 +466,9 @@ impl OutputFormatter {
             crate::interpreter::execution::ExecutionStatus::WallClockTimeout(limit) => {
                 output.push_str(&format!("⏹️  Stopped: Time limit of {}ms reached\n", limit));
             }
+            crate::interpreter::execution::ExecutionStatus::Cancelled => {
+                output.push_str("⏹️  Stopped: Cancelled\n");
+            }
             _ => {}
         }
 
@@ -1064,6 +483,9 @@ impl OutputFormatter {
                     crate::interpreter::execution::ExecutionWarning::SoftTimeLimit(limit) => {
                         output.push_str(&format!("  ⚠️  Approaching time limit of {}ms\n", limit));
                     }
+                    crate::interpreter::execution::ExecutionWarning::LargeValue { location, digits } => {
+                        output.push_str(&format!("  ⚠️  {} reached a {}-digit value\n", location, digits));
+                    }
                 }
             }
         }
@@ -1076,6 +498,13 @@ impl OutputFormatter {
                 last_report.tick, last_report.elapsed_time_ms, last_report.active_droplets));
         }
 
+        if !result.collision_hotspots.is_empty() {
+            output.push_str("Collision Hotspots:\n");
+            for (coord, count) in result.collision_hotspots.iter().take(5) {
+                output.push_str(&format!("  {}: {} collisions\n", coord, count));
+            }
+        }
+
         if !result.final_output.is_empty() {
             output.push_str(&format!("Program Output: {}\n", result.final_output));
         }
@@ -1083,6 +512,320 @@ impl OutputFormatter {
         output
     }
 
+    /// Format the program grid with collision hotspots annotated
+    ///
+    /// Cells where collisions happened show the count (1-9, `*` for ten or more),
+    /// followed by a list of hotspots with their original symbols.
+    pub fn format_collision_map(grid: &ProgramGrid, hotspots: &[(Coordinate, usize)]) -> String {
+        let mut output = String::new();
+
+        output.push_str("Collision Map:\n");
+        output.push_str("==============\n");
+
+        if hotspots.is_empty() {
+            output.push_str("No collisions recorded\n");
+            return output;
+        }
+
+        let counts: std::collections::HashMap<Coordinate, usize> = hotspots.iter().cloned().collect();
+        let (width, height) = grid.dimensions();
+        for y in 0..height {
+            let mut line = String::new();
+            for x in 0..width {
+                let coord = Coordinate::new(grid.bounds.min_x + x as isize, grid.bounds.min_y + y as isize);
+                match counts.get(&coord) {
+                    Some(&count) if count < 10 => line.push(char::from_digit(count as u32, 10).unwrap_or('*')),
+                    Some(_) => line.push('*'),
+                    None => line.push(grid.get_symbol(coord).unwrap_or(' ')),
+                }
+            }
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+
+        output.push_str("Hotspots:\n");
+        for (coord, count) in hotspots {
+            let symbol = grid.get_symbol(*coord).unwrap_or(' ');
+            output.push_str(&format!("  {} '{}': {} collision{}\n",
+                coord, symbol, count, if *count == 1 { "" } else { "s" }));
+        }
+
+        output
+    }
+
+    /// The `top_n` hottest cells followed by totals per operation
+    pub fn format_profile(grid: &ProgramGrid, profile: &ExecutionProfile, top_n: usize) -> String {
+        let mut output = String::new();
+
+        output.push_str("Profile:\n");
+        output.push_str("========\n");
+
+        if profile.total_visits() == 0 {
+            output.push_str("No cells visited\n");
+            return output;
+        }
+
+        let total_time = profile.total_time().as_secs_f64();
+        let share = |time: Duration| if total_time > 0.0 { time.as_secs_f64() * 100.0 / total_time } else { 0.0 };
+        let operation = |symbol: char| ProgramCell::description(symbol)
+            .and_then(|text| text.split(':').next())
+            .unwrap_or("Unknown");
+
+        output.push_str(&format!("Hottest cells (top {}):\n", top_n));
+        output.push_str(&format!("  {:<12} {:<6} {:>10} {:>12} {:>7}\n", "Cell", "Symbol", "Visits", "Time (us)", "Time %"));
+        for (coord, entry) in profile.hottest_cells(top_n) {
+            let symbol = grid.get_symbol(coord).unwrap_or(entry.symbol);
+            output.push_str(&format!("  {:<12} {:<6} {:>10} {:>12} {:>6.1}%\n",
+                coord.to_string(), format!("'{}'", symbol), entry.visits, entry.time.as_micros(), share(entry.time)));
+        }
+
+        output.push_str("By operation:\n");
+        for entry in profile.by_operation() {
+            output.push_str(&format!("  '{}' {:<24} {:>10} {:>12} {:>6.1}%\n",
+                entry.symbol, operation(entry.symbol), entry.visits, entry.time.as_micros(), share(entry.time)));
+        }
+
+        output
+    }
+
+    /// Table comparing configured limits with actual peak usage
+    pub fn format_usage_report(usage: &[ResourceUsage]) -> String {
+        let mut output = String::new();
+
+        output.push_str("Resource Usage:\n");
+        output.push_str(&format!("{:<24} {:>12} {:>12} {:>8}\n", "Resource", "Actual", "Limit", "Used"));
+        output.push_str(&format!("{:-<24} {:->12} {:->12} {:->8}\n", "", "", "", ""));
+
+        for row in usage {
+            let limit = row.limit.map_or("unlimited".to_string(), |limit| limit.to_string());
+            let used = row.percent_used().map_or("-".to_string(), |percent| format!("{:.1}%", percent));
+            output.push_str(&format!("{:<24} {:>12} {:>12} {:>8}\n",
+                format!("{} ({})", row.resource, row.unit), row.actual, limit, used));
+        }
+
+        output
+    }
+
+    /// Constant folds applied by the optimizer, one per line
+    pub fn format_opt_report(folds: &[&Fold]) -> String {
+        let mut output = format!("Optimizations: {} constant fold{}\n", folds.len(), if folds.len() == 1 { "" } else { "s" });
+        for fold in folds {
+            output.push_str(&format!("  {}\n", fold));
+        }
+        output
+    }
+
+    /// Text report for `tubular inspect`
+    pub fn format_program_report(report: &ProgramReport) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("Grid size: {}x{}\n", report.width, report.height));
+        output.push_str(&format!("Cells: {}\n", report.cells));
+        let start = report.start.map_or("none".to_string(), |start| start.to_string());
+        output.push_str(&format!("Start position: {}\n", start));
+        output.push_str(&format!("Reachable cells: {} of {} ({:.1}%)\n",
+            report.reachable, report.cells, report.reachable_percent()));
+
+        output.push_str("Symbols:\n");
+        for (symbol, count) in &report.histogram {
+            output.push_str(&format!("  {:<4} {:>6}\n", format!("'{}'", symbol), count));
+        }
+
+        output.push_str("Subroutine labels:");
+        if report.labels.is_empty() {
+            output.push_str(" none");
+        }
+        output.push('\n');
+        for (name, label) in &report.labels {
+            output.push_str(&format!("  {} at {} heading {}\n", name, label.entry, label.direction.name()));
+        }
+
+        output.push_str("Memory operations:");
+        if report.memory_operations.is_empty() {
+            output.push_str(" none");
+        }
+        output.push('\n');
+        for (coord, symbol) in &report.memory_operations {
+            output.push_str(&format!("  '{}' at {}\n", symbol, coord));
+        }
+
+        output
+    }
+
+    /// `tubular inspect` report as a JSON document
+    pub fn format_program_report_json(program: &str, report: &ProgramReport) -> String {
+        use serde_json::json;
+
+        let point = |coord: Coordinate| json!({"x": coord.x, "y": coord.y});
+        let histogram: serde_json::Map<String, serde_json::Value> = report.histogram.iter()
+            .map(|(symbol, count)| (symbol.to_string(), json!(count)))
+            .collect();
+        json!({
+            "program": program,
+            "width": report.width,
+            "height": report.height,
+            "cells": report.cells,
+            "start": report.start.map(point),
+            "reachable_cells": report.reachable,
+            "reachable_percent": report.reachable_percent(),
+            "symbols": histogram,
+            "labels": report.labels.iter().map(|(name, label)| json!({
+                "name": name,
+                "entry": point(label.entry),
+                "heading": label.direction.name(),
+            })).collect::<Vec<_>>(),
+            "memory_operations": report.memory_operations.iter().map(|(coord, symbol)| json!({
+                "symbol": symbol.to_string(),
+                "at": point(*coord),
+            })).collect::<Vec<_>>(),
+        }).to_string()
+    }
+
+    /// Render bytes as a classic hex dump: offset, 16 hex bytes, then printable ASCII
+    pub fn format_hexdump(bytes: &[u8]) -> String {
+        let mut output = String::new();
+
+        for (row, chunk) in bytes.chunks(16).enumerate() {
+            output.push_str(&format!("{:08x}  ", row * 16));
+
+            for i in 0..16 {
+                match chunk.get(i) {
+                    Some(byte) => output.push_str(&format!("{:02x} ", byte)),
+                    None => output.push_str("   "),
+                }
+                if i == 7 {
+                    output.push(' ');
+                }
+            }
+
+            output.push_str(" |");
+            for &byte in chunk {
+                output.push(if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' });
+            }
+            output.push_str("|\n");
+        }
+
+        output.push_str(&format!("{:08x}\n", bytes.len()));
+        output
+    }
+
+    /// Standard (RFC 4648) base64 encoding with padding
+    pub fn encode_base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+        for chunk in bytes.chunks(3) {
+            let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+            let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+
+            encoded.push(ALPHABET[(n >> 18) as usize & 63] as char);
+            encoded.push(ALPHABET[(n >> 12) as usize & 63] as char);
+            encoded.push(if chunk.len() > 1 { ALPHABET[(n >> 6) as usize & 63] as char } else { '=' });
+            encoded.push(if chunk.len() > 2 { ALPHABET[n as usize & 63] as char } else { '=' });
+        }
+
+        encoded
+    }
+
+    /// Program output as JSON with the raw bytes base64-encoded
+    pub fn format_output_json(bytes: &[u8]) -> String {
+        format!("{{\"length\": {}, \"base64\": \"{}\"}}", bytes.len(), Self::encode_base64(bytes))
+    }
+
+    /// Single JSON document describing a finished run, for `--output-format json`
+    ///
+    /// `state` supplies the tick count and output when the run failed with an
+    /// error and there is no result.
+    pub fn format_execution_report_json(
+        program: &str,
+        outcome: &crate::types::error::Result<ExecutionResult>,
+        state: &crate::interpreter::execution::ExecutionState,
+        limits: &crate::interpreter::execution::ExecutionLimits,
+        exit_code: u8,
+    ) -> String {
+        use crate::interpreter::execution::{ExecutionStatus, ExecutionWarning};
+        use serde_json::json;
+
+        let report = match outcome {
+            Ok(result) => {
+                let (status, error) = match &result.status {
+                    ExecutionStatus::Completed => ("completed", None),
+                    ExecutionStatus::TickTimeout(_) => ("tick_limit", None),
+                    ExecutionStatus::WallClockTimeout(_) => ("time_limit", None),
+                    ExecutionStatus::Cancelled => ("cancelled", None),
+                    ExecutionStatus::Paused => ("paused", None),
+                    ExecutionStatus::Error(error) => ("error", Some(error.to_string())),
+                    ExecutionStatus::Running | ExecutionStatus::Warning(_) => ("running", None),
+                };
+                let warnings: Vec<String> = result.warnings_issued.iter().map(|warning| match warning {
+                    ExecutionWarning::SoftTickLimit(ticks) => format!("Reached the soft tick limit of {}", ticks),
+                    ExecutionWarning::SoftTimeLimit(ms) => format!("Reached the soft time limit of {}ms", ms),
+                    ExecutionWarning::LargeValue { location, digits } => {
+                        format!("{} reached a {}-digit value", location, digits)
+                    }
+                }).collect();
+                let usage: Vec<_> = ResourceUsage::collect(result, limits).iter().map(|row| json!({
+                    "resource": row.resource,
+                    "unit": row.unit,
+                    "actual": row.actual,
+                    "limit": row.limit,
+                    "percent_used": row.percent_used().map(|percent| (percent * 100.0).round() / 100.0),
+                })).collect();
+                json!({
+                    "program": program,
+                    "status": status,
+                    "exit_code": exit_code,
+                    "error": error,
+                    "output": result.final_output,
+                    "ticks": result.total_ticks,
+                    "peak_droplets": result.max_droplets,
+                    "max_stack_depth": result.max_stack_depth,
+                    "peak_reservoir_cells": result.peak_reservoir_cells,
+                    "execution_time_ms": result.execution_time_ms,
+                    "warnings": warnings,
+                    "resource_usage": usage,
+                })
+            }
+            Err(error) => json!({
+                "program": program,
+                "status": "error",
+                "exit_code": exit_code,
+                "error": error.to_string(),
+                "output": state.output,
+                "ticks": state.tick,
+                "peak_droplets": null,
+                "max_stack_depth": state.stack.max_depth_reached(),
+                "peak_reservoir_cells": null,
+                "execution_time_ms": null,
+                "warnings": [],
+                "resource_usage": [],
+            }),
+        };
+        serde_json::to_string_pretty(&report).unwrap_or_default()
+    }
+
+    /// The `--output-format json` report for a program that stopped before its first tick,
+    /// because it could not be read, parsed or validated
+    ///
+    /// The fields are those of [`format_execution_report_json`](Self::format_execution_report_json).
+    pub fn format_startup_error_report_json(program: &str, error: &str, exit_code: u8) -> String {
+        let report = serde_json::json!({
+            "program": program,
+            "status": "error",
+            "exit_code": exit_code,
+            "error": error,
+            "output": "",
+            "ticks": 0,
+            "peak_droplets": null,
+            "max_stack_depth": 0,
+            "peak_reservoir_cells": null,
+            "execution_time_ms": null,
+            "warnings": [],
+            "resource_usage": [],
+        });
+        serde_json::to_string_pretty(&report).unwrap_or_default()
+    }
+
     /// Format benchmark results (table format)
     pub fn format_benchmark_table(
         program_file: &str,
@@ -1214,7 +957,7 @@ impl OutputFormatter {
     }
 
     /// Extract error code from InterpreterError
-    fn extract_error_code(error: &crate::types::error::InterpreterError) -> &'static str {
+    pub fn extract_error_code(error: &crate::types::error::InterpreterError) -> &'static str {
         use crate::types::error::{InterpreterError, ExecError, SystemError};
 
         match error {
@@ -1232,11 +975,24 @@ impl OutputFormatter {
                 ExecError::SoftTimeLimitWarning(_) => "E018",
                 ExecError::InternalError(_) => "E011",
                 ExecError::InvalidOperation(_) => "E015",
+                ExecError::CapabilityDenied(..) => "E020",
+                ExecError::DropletLimitExceeded(_) => "E021",
+                ExecError::StackLimitExceeded(_) => "E022",
+                ExecError::ReservoirLimitExceeded(_) => "E023",
+                ExecError::LocalStackOverflow(..) => "E024",
+                ExecError::ShiftTooLarge(_) => "E025",
+                ExecError::NegativeExponent => "E026",
+                ExecError::PowerTooLarge(_) => "E027",
+                ExecError::RegionTooLarge(_) => "E028",
+                ExecError::InvalidCellWrite(..) => "E029",
+                ExecError::InvalidNumericInput(_) => "E030",
+                ExecError::EndOfInput => "E031",
             },
             InterpreterError::System(sys_error) => match sys_error {
                 SystemError::OutOfMemory => "E012",
                 SystemError::IoError(_) => "E013",
                 SystemError::InternalError(_) => "E014",
+                SystemError::InvalidSnapshot(_) => "E019",
             },
             InterpreterError::Enhanced { info, .. } => {
                 use crate::types::error::ErrorType;
@@ -1315,16 +1071,3 @@ pub struct BenchmarkResults {
     pub peak_memory_usage_mb: f64,
     pub instructions_per_second: f64,
 }
-
-// Include serde JSON support for benchmarking
-#[cfg(feature = "json")]
-mod json {
-    use super::*;
-    use serde_json;
-
-    impl BenchmarkJson {
-        pub fn to_json(&self) -> String {
-            serde_json::to_string_pretty(self).unwrap_or_default()
-        }
-    }
-}
\ No newline at end of file
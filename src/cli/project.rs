@@ -0,0 +1,76 @@
+use crate::parser::symbol_map::SymbolMap;
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project file written by `tubular new`
+pub const PROJECT_FILE: &str = "tubular.toml";
+
+/// The `tubular.toml` in `dir` or the nearest directory above it
+pub fn find_project_file(dir: &Path) -> Option<PathBuf> {
+    let dir = dir.canonicalize().ok()?;
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(PROJECT_FILE))
+        .find(|file| file.is_file())
+}
+
+/// The `[symbols]` table of the TOML file at `path`, or an empty map if it has none
+///
+/// Each entry maps a one-character alias to the built-in symbol it stands for:
+///
+/// ```toml
+/// [symbols]
+/// "Ø" = "!"
+/// ```
+pub fn load_symbol_map(path: &Path) -> Result<SymbolMap> {
+    let text = fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    let table: toml::Table = text.parse().map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let Some(entries) = table.get("symbols") else {
+        return Ok(SymbolMap::new());
+    };
+    let entries = entries.as_table()
+        .ok_or_else(|| anyhow!("{}: [symbols] must be a table", path.display()))?;
+
+    let mut symbols = SymbolMap::new();
+    for (alias, symbol) in entries {
+        let (Some(alias), Some(symbol)) = (single_char(alias), symbol.as_str().and_then(single_char)) else {
+            return Err(anyhow!("{}: [symbols] entries map one character to one character, as in \"Ø\" = \"!\"", path.display()));
+        };
+        symbols.insert(alias, symbol).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    }
+    Ok(symbols)
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_table_is_read_from_the_nearest_project_file() {
+        let dir = std::env::temp_dir().join(format!("tubular_project_{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join(PROJECT_FILE), "[project]\nname = \"demo\"\n\n[symbols]\n\"Ø\" = \"!\"\n").unwrap();
+
+        let file = find_project_file(&dir.join("src")).unwrap();
+        assert_eq!(file, dir.join(PROJECT_FILE).canonicalize().unwrap());
+        let symbols = load_symbol_map(&file).unwrap();
+        assert_eq!(symbols.resolve('Ø'), '!');
+
+        fs::write(dir.join(PROJECT_FILE), "[symbols]\n\"n\" = \"!\"\n").unwrap();
+        assert!(load_symbol_map(&file).unwrap_err().to_string().contains("already a Tubular symbol"));
+        fs::write(dir.join(PROJECT_FILE), "[symbols]\n\"Ø\" = \"!!\"\n").unwrap();
+        assert!(load_symbol_map(&file).is_err());
+        fs::write(dir.join(PROJECT_FILE), "[project]\nname = \"demo\"\n").unwrap();
+        assert!(load_symbol_map(&file).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use crate::interpreter::execution::{ExecutionLimits, ExecutionStatus, TubularInterpreter};
+use crate::operations::io::{InputProvider, SharedReaderInput, StringInput};
+use crate::parser::grid_parser::GridParser;
+use std::io::{self, BufRead, Write};
+
+/// Tick limit applied to REPL runs so an accidental loop does not hang the session
+pub const DEFAULT_REPL_TICKS: u64 = 10_000;
+
+const REPL_HELP: &str = "\
+Lines are appended to the program as grid rows. Commands start with '.':
+  .run              Run the current program from a fresh state
+  .list             Show the program with row numbers
+  .edit N ROW       Replace row N with ROW
+  .delete N         Remove row N
+  .undo             Remove the last row
+  .clear            Start a new, empty program
+  .stack            Show the data stack after the last run
+  .reservoir        Show the reservoir after the last run
+  .ticks N          Set the tick limit for runs (0 for unlimited)
+  .help             Show this help
+  .quit             Leave the REPL";
+
+/// Interactive session that builds a program row by row
+///
+/// The interpreter from the last `.run` is kept so its stack and reservoir
+/// can be inspected until the next run. Programs read `?` and `??` input from
+/// the lines after `.run`, so piped sessions can supply it in line.
+pub struct Repl {
+    rows: Vec<String>,
+    last_run: Option<TubularInterpreter>,
+    /// Session input, shared with running programs; None outside `run`
+    input: Option<SharedReaderInput>,
+    max_ticks: Option<u64>,
+    prompt: bool,
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl {
+            rows: Vec::new(),
+            last_run: None,
+            input: None,
+            max_ticks: Some(DEFAULT_REPL_TICKS),
+            prompt: true,
+        }
+    }
+
+    /// Disable the `tb>` prompt, e.g. when input is piped
+    pub fn with_prompt(mut self, prompt: bool) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    pub fn with_max_ticks(mut self, max_ticks: Option<u64>) -> Self {
+        self.max_ticks = max_ticks;
+        self
+    }
+
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+
+    /// Run the read-eval-print loop until `.quit` or end of input
+    pub fn run<R: BufRead + Send + 'static, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        if self.prompt {
+            writeln!(output, "Tubular REPL - type .help for commands")?;
+        }
+
+        let input = SharedReaderInput::new(input);
+        self.input = Some(input.clone());
+        let result = self.read_eval_loop(input, &mut output);
+        self.input = None;
+        result
+    }
+
+    fn read_eval_loop<W: Write>(&mut self, mut input: SharedReaderInput, output: &mut W) -> io::Result<()> {
+        loop {
+            if self.prompt {
+                write!(output, "tb> ")?;
+                output.flush()?;
+            }
+
+            let line = match input.read_raw_line().map_err(|e| io::Error::other(e.to_string()))? {
+                Some(line) => line,
+                None => break,
+            };
+            let line = line.strip_suffix('\n').unwrap_or(&line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            if !self.handle_line(line, output)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Process one line of input; returns false when the session should end
+    pub fn handle_line<W: Write>(&mut self, line: &str, output: &mut W) -> io::Result<bool> {
+        let command = match line.strip_prefix('.') {
+            Some(command) => command.trim(),
+            None => {
+                self.rows.push(line.to_string());
+                return Ok(true);
+            }
+        };
+
+        let (name, args) = match command.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim_start()),
+            None => (command, ""),
+        };
+
+        match name {
+            "run" | "r" => self.run_program(output)?,
+            "list" | "l" => {
+                if self.rows.is_empty() {
+                    writeln!(output, "(empty program)")?;
+                }
+                for (i, row) in self.rows.iter().enumerate() {
+                    writeln!(output, "{:3} | {}", i + 1, row)?;
+                }
+            }
+            "edit" | "e" => match args.split_once(' ') {
+                Some((n, row)) => match self.row_index(n) {
+                    Some(index) => self.rows[index] = row.to_string(),
+                    None => writeln!(output, "No row {}", n)?,
+                },
+                None => writeln!(output, "Usage: .edit N ROW")?,
+            },
+            "delete" | "d" => match self.row_index(args) {
+                Some(index) => {
+                    self.rows.remove(index);
+                }
+                None => writeln!(output, "No row {}", args)?,
+            },
+            "undo" | "u" => {
+                if self.rows.pop().is_none() {
+                    writeln!(output, "Nothing to undo")?;
+                }
+            }
+            "clear" | "c" => {
+                self.rows.clear();
+                self.last_run = None;
+            }
+            "stack" | "s" => match &self.last_run {
+                Some(interpreter) => {
+                    let values: Vec<String> = interpreter.state().stack.as_slice()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect();
+                    writeln!(output, "Stack (bottom to top): [{}]", values.join(", "))?;
+                }
+                None => writeln!(output, "No run yet; use .run first")?,
+            },
+            "reservoir" | "m" => match &self.last_run {
+                Some(interpreter) => write!(output, "{}", interpreter.state().reservoir)?,
+                None => writeln!(output, "No run yet; use .run first")?,
+            },
+            "ticks" | "t" => match args.parse::<u64>() {
+                Ok(0) => self.max_ticks = None,
+                Ok(ticks) => self.max_ticks = Some(ticks),
+                Err(_) => writeln!(output, "Usage: .ticks N")?,
+            },
+            "help" | "h" | "?" => writeln!(output, "{}", REPL_HELP)?,
+            "quit" | "q" | "exit" => return Ok(false),
+            _ => writeln!(output, "Unknown command '.{}'; type .help for commands", name)?,
+        }
+
+        Ok(true)
+    }
+
+    /// Parse and run the current rows, reporting output and final status
+    fn run_program<W: Write>(&mut self, output: &mut W) -> io::Result<()> {
+        let source = self.rows.join("\n");
+        let grid = match GridParser::new().parse_named(&source, "<repl>") {
+            Ok(grid) => grid,
+            Err(e) => {
+                writeln!(output, "Parse error: {}", e)?;
+                return Ok(());
+            }
+        };
+
+        let limits = ExecutionLimits::new().with_max_ticks(self.max_ticks);
+        // Outside a session there is nothing to read, so input is empty
+        let input: Box<dyn InputProvider> = match &self.input {
+            Some(input) => Box::new(input.clone()),
+            None => Box::new(StringInput::default()),
+        };
+        let mut interpreter = match TubularInterpreter::new(grid) {
+            Ok(interpreter) => interpreter.with_limits(limits).with_boxed_input(input),
+            Err(e) => {
+                writeln!(output, "Error: {}", e)?;
+                return Ok(());
+            }
+        };
+
+        match interpreter.run() {
+            Ok(result) => {
+                if !result.final_output.is_empty() {
+                    writeln!(output, "{}", result.final_output)?;
+                }
+                match result.status {
+                    ExecutionStatus::TickTimeout(ticks) => {
+                        writeln!(output, "[TIMEOUT] Stopped after {} ticks", ticks)?;
+                    }
+                    ExecutionStatus::Error(e) => writeln!(output, "Error: {}", e)?,
+                    _ => writeln!(output, "[OK] {} ticks", result.total_ticks)?,
+                }
+            }
+            Err(e) => writeln!(output, "Error: {}", e)?,
+        }
+
+        self.last_run = Some(interpreter);
+        Ok(())
+    }
+
+    /// Convert a 1-based row number into an index into `rows`
+    fn row_index(&self, n: &str) -> Option<usize> {
+        n.trim().parse::<usize>().ok()
+            .filter(|&n| n >= 1 && n <= self.rows.len())
+            .map(|n| n - 1)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::cli::output::{TraceDocument, TraceEvent, TraceOperation};
+use crate::cli::visualizer::{render_grid, OUTPUT_STYLE, RESET};
+use crate::interpreter::grid::ProgramGrid;
+use crate::parser::grid_parser::GridParser;
+use crate::types::number::TubularNumber;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// A droplet as reconstructed from trace events
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayDroplet {
+    pub position: Coordinate,
+    pub direction: Option<Direction>,
+    pub value: Option<TubularNumber>,
+}
+
+/// Everything a replay frame shows, rebuilt from the events before it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayState {
+    pub droplets: BTreeMap<u64, ReplayDroplet>,
+    pub collisions: HashSet<Coordinate>,
+    pub output: String,
+}
+
+/// Animates a saved trace without running the program again
+///
+/// Frame 0 shows the droplets created before the first tick, and frame N the
+/// state after tick N - 1, matching `tubular watch`. The grid comes from the
+/// program embedded in the trace, or from the cells the events visited when
+/// the trace has none.
+pub struct TraceReplay {
+    grid: ProgramGrid,
+    events: Vec<TraceEvent>,
+    delay: Duration,
+    color: bool,
+}
+
+impl TraceReplay {
+    pub fn new(document: TraceDocument) -> Self {
+        let grid = document.program
+            .and_then(|program| GridParser::new().parse_string(&program).ok())
+            .unwrap_or_else(|| Self::grid_from_events(&document.events));
+        TraceReplay {
+            grid,
+            events: document.events,
+            delay: Duration::from_millis(200),
+            color: std::env::var("NO_COLOR").is_err(),
+        }
+    }
+
+    /// Time to wait between frames when animating
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn grid_from_events(events: &[TraceEvent]) -> ProgramGrid {
+        let mut grid = ProgramGrid::new();
+        for event in events {
+            if let (Some(position), Some(symbol)) = (event.position, event.cell_symbol)
+                && grid.get(position).is_none()
+            {
+                let _ = grid.add_cell(position, symbol);
+            }
+        }
+        grid
+    }
+
+    /// Number of frames: one before the first tick and one after each traced tick
+    pub fn frame_count(&self) -> u64 {
+        self.events.iter().map(|event| event.tick + 2).max().unwrap_or(1)
+    }
+
+    /// State shown in `frame`
+    pub fn state_at(&self, frame: u64) -> ReplayState {
+        let mut state = ReplayState::default();
+        for event in &self.events {
+            let created = event.metadata.extra.get("lifecycle_event").is_some_and(|e| e == "created");
+            if event.tick >= frame && !(created && event.tick == frame) {
+                continue;
+            }
+
+            let after = event.after_state.as_ref();
+            match (event.operation.clone(), event.droplet_id, event.position) {
+                (TraceOperation::DropletLifecycle, Some(id), Some(position)) => {
+                    if created {
+                        state.droplets.insert(id, ReplayDroplet {
+                            position,
+                            direction: after.and_then(|s| s.droplet_direction),
+                            value: after.and_then(|s| s.droplet_value.clone()),
+                        });
+                    } else {
+                        state.droplets.remove(&id);
+                    }
+                }
+                (TraceOperation::Collision, _, Some(position)) if event.tick + 1 == frame => {
+                    state.collisions.insert(position);
+                }
+                (_, Some(id), Some(position)) => {
+                    let droplet = state.droplets.entry(id).or_insert(ReplayDroplet {
+                        position,
+                        direction: None,
+                        value: None,
+                    });
+                    if event.operation == TraceOperation::Movement {
+                        droplet.position = position;
+                    }
+                    if let Some(direction) = after.and_then(|s| s.droplet_direction) {
+                        droplet.direction = Some(direction);
+                    }
+                    if let Some(value) = after.and_then(|s| s.droplet_value.clone()) {
+                        droplet.value = Some(value);
+                    }
+                }
+                _ => {}
+            }
+
+            let extra = &event.metadata.extra;
+            if matches!(extra.get("io_operation").map(String::as_str), Some(",") | Some("n"))
+                && let Some(value) = extra.get("io_value")
+            {
+                state.output.push_str(value);
+            }
+        }
+        state
+    }
+
+    pub fn render_frame(&self, frame: u64) -> String {
+        let state = self.state_at(frame);
+        let droplets: HashSet<Coordinate> = state.droplets.values().map(|d| d.position).collect();
+        let status = if frame + 1 == self.frame_count() { "end of trace" } else { "replay" };
+
+        let mut text = format!("Tick {}  Droplets {}  [{}]\n\n", frame, state.droplets.len(), status);
+        text.push_str(&render_grid(&self.grid, &droplets, &state.collisions, self.color));
+        text.push('\n');
+        for (id, droplet) in &state.droplets {
+            let direction = droplet.direction.map_or("?".to_string(), |d| d.to_string());
+            let value = droplet.value.as_ref().map_or("?".to_string(), |v| v.to_string());
+            text.push_str(&format!("  D{} at {} heading {} value {}\n", id, droplet.position, direction, value));
+        }
+        if self.color {
+            text.push_str(&format!("Output: {}{}{}\n", OUTPUT_STYLE, state.output, RESET));
+        } else {
+            text.push_str(&format!("Output: {}\n", state.output));
+        }
+        text
+    }
+
+    /// Print every frame in sequence without terminal control, e.g. when piped
+    pub fn run_plain<W: Write>(&self, mut output: W) -> io::Result<()> {
+        for frame in 0..self.frame_count() {
+            writeln!(output, "{}", self.render_frame(frame))?;
+        }
+        Ok(())
+    }
+
+    /// Redraw each frame in place, waiting `delay` between them
+    pub fn run_animated<W: Write>(&self, mut output: W) -> io::Result<()> {
+        for frame in 0..self.frame_count() {
+            if frame > 0 {
+                std::thread::sleep(self.delay);
+            }
+            write!(output, "\x1b[2J\x1b[H{}", self.render_frame(frame))?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::output::TraceConfig;
+    use crate::interpreter::execution::TubularInterpreter;
+
+    fn replay(source: &str) -> TraceReplay {
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let mut interpreter = TubularInterpreter::new(grid.clone()).unwrap()
+            .with_trace_config(TraceConfig::new());
+        interpreter.run().unwrap();
+        let document = TraceDocument { program: Some(grid.to_text()), events: interpreter.trace_events().to_vec() };
+        let saved = document.to_json(&TraceConfig::new());
+        TraceReplay::new(TraceDocument::parse(&saved).unwrap()).with_color(false)
+    }
+
+    #[test]
+    fn test_replay_follows_droplet_and_output() {
+        let replay = replay("@\n7\nn\n!\n");
+
+        let start = replay.state_at(0);
+        assert_eq!(start.droplets[&0].position, Coordinate::new(0, 0));
+
+        let end = replay.state_at(replay.frame_count() - 1);
+        assert!(end.droplets.is_empty());
+        assert_eq!(end.output, "7");
+        assert!(replay.render_frame(1).contains("@\no\nn\n!"));
+    }
+}
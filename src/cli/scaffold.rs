@@ -0,0 +1,70 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Starter program written to `main.tub`; it prints 7
+const MAIN_PROGRAM: &str = "# Run with `tubular main.tub`; `tubular test` checks the programs in tests/\n@\n7\nn\n!\n";
+
+/// Golden test running the starter program, paired with [`MAIN_EXPECTED`]
+const MAIN_TEST: &str = "%include \"../main.tub\" at (0, 0)\n";
+
+const MAIN_EXPECTED: &str = "7\n";
+
+/// Create a new project in `dir` and return the files written
+///
+/// The project has a starter `main.tub`, a `tubular.toml` naming it, and a
+/// `tests/` folder with a golden test that `tubular test` picks up from the
+/// project root. `dir` may exist but must be empty.
+pub fn create_project(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    if dir.exists() && fs::read_dir(dir)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("'{}' already exists and is not empty", dir.display()),
+        ));
+    }
+    let name = dir.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("tubular-project");
+    let manifest = format!("[project]\nname = \"{}\"\nentry = \"main.tub\"\n\n[test]\npaths = [\"tests\"]\n", name);
+
+    fs::create_dir_all(dir.join("tests"))?;
+    let files = [
+        ("tubular.toml", manifest.as_str()),
+        ("main.tub", MAIN_PROGRAM),
+        ("tests/main.tub", MAIN_TEST),
+        ("tests/main.expected", MAIN_EXPECTED),
+    ];
+    let mut written = Vec::new();
+    for (file, contents) in files {
+        let path = dir.join(file);
+        fs::write(&path, contents)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::test_runner::discover;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_project_has_program_manifest_and_golden_test() {
+        let dir = std::env::temp_dir().join(format!("tubular_scaffold_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let files = create_project(&dir.join("demo")).unwrap();
+        assert_eq!(files.len(), 4);
+        let manifest = fs::read_to_string(dir.join("demo/tubular.toml")).unwrap();
+        assert!(manifest.contains("name = \"demo\""));
+        assert!(GridParser::new().parse_file(dir.join("demo/tests/main.tub").to_str().unwrap()).is_ok());
+
+        let tests = discover(&[dir.join("demo")]).unwrap();
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].program, dir.join("demo/tests/main.tub"));
+
+        assert!(create_project(&dir.join("demo")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
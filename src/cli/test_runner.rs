@@ -0,0 +1,208 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// File extensions recognised as Tubular programs
+pub const PROGRAM_EXTENSIONS: [&str; 2] = ["tb", "tub"];
+
+/// A program with its expected output and optional stdin contents
+///
+/// `countdown.tb` is paired with `countdown.expected` and, if present,
+/// `countdown.input` in the same directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenTest {
+    pub program: PathBuf,
+    pub expected: PathBuf,
+    pub input: Option<PathBuf>,
+}
+
+impl GoldenTest {
+    /// Pair `program` with its sibling files, if it has an `.expected` file
+    pub fn for_program(program: &Path) -> Option<Self> {
+        let extension = program.extension()?.to_str()?;
+        if !PROGRAM_EXTENSIONS.contains(&extension) {
+            return None;
+        }
+        let expected = program.with_extension("expected");
+        if !expected.is_file() {
+            return None;
+        }
+        let input = Some(program.with_extension("input")).filter(|path| path.is_file());
+        Some(GoldenTest { program: program.to_path_buf(), expected, input })
+    }
+}
+
+/// Find golden tests under `paths`, descending into directories, in path order
+pub fn discover(paths: &[PathBuf]) -> io::Result<Vec<GoldenTest>> {
+    let mut tests = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<io::Result<_>>()?;
+            entries.sort();
+            tests.extend(discover(&entries)?);
+        } else if let Some(test) = GoldenTest::for_program(path) {
+            tests.push(test);
+        }
+    }
+    Ok(tests)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed { expected: String, actual: String, stderr: String },
+    /// The test could not be run at all
+    Error(String),
+}
+
+/// Runs golden tests by executing each program in a separate interpreter process
+///
+/// A child process keeps each program's stdin separate, so `.input` files
+/// reach the program exactly as they would from a shell pipe.
+pub struct TestRunner {
+    executable: PathBuf,
+    args: Vec<String>,
+    update: bool,
+}
+
+impl TestRunner {
+    pub fn new(executable: PathBuf) -> Self {
+        TestRunner { executable, args: Vec::new(), update: false }
+    }
+
+    /// Extra interpreter arguments passed before the program path
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Overwrite `.expected` files with the actual output instead of comparing
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    pub fn run(&self, test: &GoldenTest) -> TestOutcome {
+        let stdin = match &test.input {
+            Some(path) => match fs::File::open(path) {
+                Ok(file) => Stdio::from(file),
+                Err(e) => return TestOutcome::Error(format!("{}: {}", path.display(), e)),
+            },
+            None => Stdio::null(),
+        };
+
+        let output = match Command::new(&self.executable)
+            .args(&self.args)
+            .arg(&test.program)
+            .stdin(stdin)
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => return TestOutcome::Error(format!("failed to start {}: {}", self.executable.display(), e)),
+        };
+        let actual = String::from_utf8_lossy(&output.stdout).into_owned();
+
+        if self.update {
+            return match fs::write(&test.expected, &actual) {
+                Ok(()) => TestOutcome::Passed,
+                Err(e) => TestOutcome::Error(format!("{}: {}", test.expected.display(), e)),
+            };
+        }
+
+        let expected = match fs::read_to_string(&test.expected) {
+            Ok(expected) => expected,
+            Err(e) => return TestOutcome::Error(format!("{}: {}", test.expected.display(), e)),
+        };
+
+        // Editors usually add a final newline that the program never printed
+        if output.status.success() && actual.trim_end_matches('\n') == expected.trim_end_matches('\n') {
+            TestOutcome::Passed
+        } else {
+            TestOutcome::Failed {
+                expected,
+                actual,
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            }
+        }
+    }
+
+    /// Run every test, printing a line per test plus diffs for failures
+    ///
+    /// Returns the number of tests that did not pass.
+    pub fn run_all<W: Write>(&self, tests: &[GoldenTest], output: &mut W) -> io::Result<usize> {
+        let mut failed = 0;
+        for test in tests {
+            match self.run(test) {
+                TestOutcome::Passed if self.update => writeln!(output, "UPDATED {}", test.program.display())?,
+                TestOutcome::Passed => writeln!(output, "PASS {}", test.program.display())?,
+                TestOutcome::Failed { expected, actual, stderr } => {
+                    failed += 1;
+                    writeln!(output, "FAIL {}", test.program.display())?;
+                    write!(output, "{}", diff_lines(&expected, &actual))?;
+                    for line in stderr.lines() {
+                        writeln!(output, "    stderr: {}", line)?;
+                    }
+                }
+                TestOutcome::Error(message) => {
+                    failed += 1;
+                    writeln!(output, "ERROR {}: {}", test.program.display(), message)?;
+                }
+            }
+        }
+
+        writeln!(output, "\n{} passed, {} failed", tests.len() - failed, failed)?;
+        Ok(failed)
+    }
+}
+
+/// Line-by-line comparison showing only the lines that differ
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.trim_end_matches('\n').split('\n').collect();
+    let actual: Vec<&str> = actual.trim_end_matches('\n').split('\n').collect();
+
+    let mut diff = String::new();
+    for line in 0..expected.len().max(actual.len()) {
+        let (want, got) = (expected.get(line), actual.get(line));
+        if want == got {
+            continue;
+        }
+        if let Some(want) = want {
+            diff.push_str(&format!("    {:>4} - {}\n", line + 1, want));
+        }
+        if let Some(got) = got {
+            diff.push_str(&format!("    {:>4} + {}\n", line + 1, got));
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines_shows_changed_and_missing_lines() {
+        assert_eq!(diff_lines("a\nb\nc\n", "a\nb\nc"), "");
+        assert_eq!(diff_lines("a\nb\nc", "a\nx"), "       2 - b\n       2 + x\n       3 - c\n");
+    }
+
+    #[test]
+    fn test_discover_pairs_programs_with_expected_output() {
+        let dir = std::env::temp_dir().join(format!("tubular_golden_{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for file in ["a.tb", "a.expected", "a.input", "b.tub", "nested/c.tb", "nested/c.expected", "nested/c.txt"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let tests = discover(std::slice::from_ref(&dir)).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(tests, vec![
+            GoldenTest { program: dir.join("a.tb"), expected: dir.join("a.expected"), input: Some(dir.join("a.input")) },
+            GoldenTest { program: dir.join("nested/c.tb"), expected: dir.join("nested/c.expected"), input: None },
+        ]);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::interpreter::execution::{ExecutionStatus, TubularInterpreter};
+use crate::interpreter::grid::ProgramGrid;
+use crate::types::coordinate::Coordinate;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const DROPLET_STYLE: &str = "\x1b[1;36m";
+const COLLISION_STYLE: &str = "\x1b[1;41m";
+pub(crate) const OUTPUT_STYLE: &str = "\x1b[33m";
+pub(crate) const RESET: &str = "\x1b[0m";
+
+/// Draw the grid with occupied cells and collisions highlighted
+///
+/// Without color, occupied cells show `o` and collisions `*` in place of their symbol.
+pub(crate) fn render_grid(
+    grid: &ProgramGrid,
+    droplets: &HashSet<Coordinate>,
+    collisions: &HashSet<Coordinate>,
+    color: bool,
+) -> String {
+    let mut text = String::new();
+    let bounds = &grid.bounds;
+    for y in bounds.min_y.min(0)..=bounds.max_y {
+        for x in bounds.min_x.min(0)..=bounds.max_x {
+            let coord = Coordinate::new(x, y);
+            let symbol = grid.get_symbol(coord).unwrap_or(' ');
+            let style = if collisions.contains(&coord) {
+                Some(COLLISION_STYLE)
+            } else if droplets.contains(&coord) {
+                Some(DROPLET_STYLE)
+            } else {
+                None
+            };
+
+            match style {
+                Some(style) if color => text.push_str(&format!("{}{}{}", style, symbol, RESET)),
+                Some(_) => text.push(if collisions.contains(&coord) { '*' } else { 'o' }),
+                None => text.push(symbol),
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+const KEY_HELP: &str = "space: pause/resume  s: step  +/-: speed  q: quit";
+
+/// Animates droplets moving through the grid, one frame per tick
+pub struct Visualizer {
+    interpreter: TubularInterpreter,
+    delay: Duration,
+    paused: bool,
+    color: bool,
+}
+
+impl Visualizer {
+    pub fn new(interpreter: TubularInterpreter) -> Self {
+        Visualizer {
+            interpreter,
+            delay: Duration::from_millis(200),
+            paused: false,
+            color: std::env::var("NO_COLOR").is_err(),
+        }
+    }
+
+    /// Time to wait between ticks while playing
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Start paused so the first tick needs a key press
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Render the grid with droplets and this tick's collisions highlighted
+    pub fn render_frame(&self) -> String {
+        let interpreter = &self.interpreter;
+        let state = interpreter.state();
+        let grid = interpreter.grid();
+        let droplets: HashSet<Coordinate> = state.droplets.iter().map(|d| d.position).collect();
+        let collisions: HashSet<Coordinate> = interpreter.last_tick_collisions().iter().copied().collect();
+
+        let mut frame = String::new();
+        let status = match &state.status {
+            ExecutionStatus::Running if self.paused => "paused",
+            ExecutionStatus::Running => "running",
+            ExecutionStatus::Completed => "completed",
+            ExecutionStatus::TickTimeout(_) => "tick limit reached",
+            ExecutionStatus::WallClockTimeout(_) => "time limit reached",
+            ExecutionStatus::Error(_) => "error",
+            ExecutionStatus::Warning(_) => "warning",
+            ExecutionStatus::Paused if interpreter.hit_breakpoint().is_some() || interpreter.hit_watch().is_some() => "breakpoint",
+            ExecutionStatus::Paused => "paused",
+            ExecutionStatus::Cancelled => "cancelled",
+        };
+        frame.push_str(&format!("Tick {}  Droplets {}  [{}]\n\n", state.tick, state.droplets.len(), status));
+
+        frame.push_str(&render_grid(grid, &droplets, &collisions, self.color));
+        frame.push('\n');
+        for droplet in &state.droplets {
+            frame.push_str(&format!("  D{} at {} heading {} value {}\n",
+                droplet.id, droplet.position, droplet.direction, droplet.value));
+        }
+        if self.color {
+            frame.push_str(&format!("Output: {}{}{}\n", OUTPUT_STYLE, state.output, RESET));
+        } else {
+            frame.push_str(&format!("Output: {}\n", state.output));
+        }
+
+        frame
+    }
+
+    /// Print every frame in sequence without terminal control, e.g. when piped
+    pub fn run_plain<W: Write>(&mut self, mut output: W) -> io::Result<()> {
+        writeln!(output, "{}", self.render_frame())?;
+        while self.interpreter.is_running() {
+            if let Err(e) = self.interpreter.execute_tick() {
+                writeln!(output, "Error: {}", e)?;
+                break;
+            }
+            writeln!(output, "{}", self.render_frame())?;
+        }
+        Ok(())
+    }
+
+    /// Animate in the terminal's alternate screen until the user quits
+    pub fn run_interactive(&mut self) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        write!(stdout, "\x1b[?1049h\x1b[?25l")?;
+
+        let result = self.event_loop(&mut stdout);
+
+        write!(stdout, "\x1b[?25h\x1b[?1049l")?;
+        stdout.flush()?;
+        terminal::disable_raw_mode()?;
+
+        // Leave the final state visible after the alternate screen is gone
+        if result.is_ok() {
+            println!("{}", self.render_frame());
+        }
+        result
+    }
+
+    fn event_loop<W: Write>(&mut self, stdout: &mut W) -> io::Result<()> {
+        let mut next_tick = Instant::now() + self.delay;
+        loop {
+            self.draw(stdout)?;
+
+            let wait = if self.paused || !self.interpreter.is_running() {
+                None
+            } else {
+                Some(next_tick.saturating_duration_since(Instant::now()))
+            };
+
+            let key = match wait {
+                Some(timeout) if !event::poll(timeout)? => None,
+                _ => match event::read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => Some(key),
+                    _ => continue,
+                },
+            };
+
+            match key {
+                None => {
+                    self.tick();
+                    next_tick = Instant::now() + self.delay;
+                }
+                Some(KeyEvent { code: KeyCode::Char('c'), modifiers, .. }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(());
+                }
+                Some(key) => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char(' ') | KeyCode::Char('p') => self.paused = !self.paused,
+                    KeyCode::Char('s') | KeyCode::Char('n') | KeyCode::Right => {
+                        self.paused = true;
+                        self.tick();
+                    }
+                    KeyCode::Char('+') | KeyCode::Char('=') => self.delay /= 2,
+                    KeyCode::Char('-') => self.delay = (self.delay * 2).max(Duration::from_millis(1)),
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.interpreter.is_running() {
+            // Errors end the run; the status line reports them
+            let _ = self.interpreter.execute_tick();
+        }
+    }
+
+    fn draw<W: Write>(&self, stdout: &mut W) -> io::Result<()> {
+        // Raw mode needs explicit carriage returns
+        let frame = self.render_frame().replace('\n', "\r\n");
+        write!(stdout, "\x1b[H\x1b[2J{}\r\n{}  delay {}ms", frame, KEY_HELP, self.delay.as_millis())?;
+        stdout.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::io::StringInput;
+    use crate::parser::grid_parser::GridParser;
+
+    fn visualizer(source: &str, input: &str) -> Visualizer {
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let interpreter = TubularInterpreter::new(grid).unwrap()
+            .with_input(StringInput::new(input));
+        Visualizer::new(interpreter).with_delay(Duration::ZERO).with_color(false)
+    }
+
+    fn frames(visualizer: &mut Visualizer) -> String {
+        let mut output = Vec::new();
+        visualizer.run_plain(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_plain_frames_show_each_tick() {
+        let text = frames(&mut visualizer("@\n7\nn\n!", ""));
+
+        assert!(text.starts_with("Tick 0  Droplets 1  [running]\n\no\n7\nn\n!\n"));
+        assert!(text.contains("  D0 at (0, 2) heading v value 7\n"));
+        assert!(text.ends_with("Tick 4  Droplets 0  [completed]\n\n@\n7\nn\n!\n\nOutput: 7\n\n"));
+    }
+
+    #[test]
+    fn test_plain_frames_read_injected_input() {
+        let text = frames(&mut visualizer("@\n?\nn\n!", "A"));
+
+        assert!(text.contains("  D0 at (0, 2) heading v value 65\n"));
+        assert!(text.contains("[completed]"));
+        assert!(text.ends_with("Output: 65\n\n"));
+    }
+}
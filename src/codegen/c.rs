@@ -0,0 +1,457 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::operations::io::{EofPolicy, IoOperations};
+use crate::operations::net::NetOperations;
+use crate::operations::local_stack::LocalStackOperations;
+use crate::operations::memory::MemoryOperations;
+use crate::operations::metadata::MetadataOperations;
+use crate::operations::reflection::ReflectionOperations;
+use crate::parser::flow::StaticFlow;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// C source for `grid`, emitted by `tubular compile --emit c`
+///
+/// Every cell and heading the droplet can reach becomes a labelled block of
+/// straight-line C, so the program runs without a grid or a tick loop. The
+/// droplet value and the stack are 64-bit; a value that does not fit stops
+/// the program with exit code 3 instead of growing as it would in the
+/// interpreter. Droplets leaving the grid are destroyed, as they are without
+/// `--wrap`. Fails, naming every offending cell, if a reachable cell needs
+/// something only the interpreter provides: random numbers, arguments,
+/// pseudo-input, sleeping, files, sockets, local stacks, multi-digit literals,
+/// an `%eof` policy other than newline, or a `C` not bound by `%call`. Programs
+/// starting more than one droplet with `%droplet` are refused as well.
+pub fn emit_c(grid: &ProgramGrid, source_name: &str) -> Result<String, String> {
+    let Some(entry) = StaticFlow::entry(grid) else {
+        return Err("cannot compile a program without a start symbol (@)".to_string());
+    };
+    if !grid.droplets.is_empty() {
+        return Err("cannot compile a program with more than one droplet (%droplet) to C".to_string());
+    }
+    let states = reachable_states(grid, entry);
+
+    let mut dynamic: Vec<_> = states.iter()
+        .filter_map(|&(coord, _)| {
+            let symbol = grid.get_symbol(coord)?;
+            let feature = dynamic_feature(grid, coord, symbol)?;
+            Some((coord, format!("'{}' ({}) at {}", symbol, feature, coord)))
+        })
+        .collect();
+    if !dynamic.is_empty() {
+        dynamic.sort_by_key(|(coord, _)| (coord.y, coord.x));
+        dynamic.dedup();
+        let cells: Vec<_> = dynamic.into_iter().map(|(_, cell)| cell).collect();
+        return Err(format!("cannot compile to C, the program uses features only the interpreter supports: {}", cells.join(", ")));
+    }
+
+    let index: HashMap<_, _> = states.iter().enumerate().map(|(i, state)| (*state, i)).collect();
+    // Continuations after a bound `C`, numbered in the order of its blocks
+    let returns: Vec<(Coordinate, Direction)> = states.iter()
+        .filter(|&&(coord, _)| grid.get_symbol(coord) == Some('C'))
+        .copied()
+        .collect();
+    let goto = |coord: Coordinate, direction: Direction| match index.get(&(coord, direction)) {
+        Some(i) => format!("goto s{};", i),
+        None => "goto halt;".to_string(),
+    };
+
+    let mut c = String::new();
+    let _ = writeln!(c, "/* {} compiled by tubular compile --emit c */", source_name.replace("*/", "* /"));
+    c.push_str(RUNTIME);
+    c.push_str("\nint main(void) {\n    int64_t v = 0;\n\n");
+    let _ = writeln!(c, "    {}", goto(entry.0, entry.1));
+    for (i, &(coord, direction)) in states.iter().enumerate() {
+        let symbol = grid.get_symbol(coord).unwrap_or(' ');
+        let at = format!("\"{}\"", coord);
+        let next = goto(coord + direction, direction);
+        let heading = format!("{:?}", direction).to_lowercase();
+        let _ = writeln!(c, "s{}: /* {} at {} heading {} */", i, escape(symbol), coord, heading);
+        let body = match symbol {
+            '|' | '-' | '@' => next,
+            '^' => goto(coord + Direction::Up, Direction::Up),
+            '/' => {
+                let turned = match direction {
+                    Direction::Right => Direction::Up,
+                    Direction::Up => Direction::Right,
+                    Direction::Left => Direction::Down,
+                    Direction::Down => Direction::Left,
+                };
+                goto(coord + turned, turned)
+            }
+            '\\' => {
+                let turned = match direction {
+                    Direction::Right => Direction::Down,
+                    Direction::Down => Direction::Right,
+                    Direction::Left => Direction::Up,
+                    Direction::Up => Direction::Left,
+                };
+                let reversed = direction.opposite();
+                format!("if (v != 0) {}\n    {}", goto(coord + turned, turned), goto(coord + reversed, reversed))
+            }
+            '0'..='9' => format!("v = {};\n    {}", symbol, next),
+            '\'' => {
+                let quoted = grid.get_symbol(coord + direction).unwrap_or(' ');
+                format!("v = {};\n    {}", quoted as u32, goto(coord + direction + direction, direction))
+            }
+            ':' => format!("push(v);\n    {}", next),
+            ';' => format!("v = pop();\n    {}", next),
+            'd' => format!("push(sp > 0 ? stack[sp - 1] : 0);\n    {}", next),
+            'A' | 'S' | 'M' | 'D' => {
+                let operation = match symbol {
+                    'A' => "add",
+                    'S' => "sub",
+                    'M' => "mul",
+                    _ => "quot",
+                };
+                format!("{{ int64_t b = pop(), a = pop(); v = {}(a, b, {}); }}\n    {}", operation, at, next)
+            }
+            '%' => format!("{{ int64_t b = pop(), a = pop(); v = rem(a, b); }}\n    {}", next),
+            '*' => format!("{{ int64_t b = pop(), a = pop(); v = power(a, b, {}); }}\n    {}", at, next),
+            '_' => format!("{{ int64_t a = pop(); v = a < 0 ? sub(0, a, {}) : a; }}\n    {}", at, next),
+            '=' | '<' | '>' => {
+                let operator = match symbol {
+                    '=' => "==",
+                    '<' => "<",
+                    _ => ">",
+                };
+                format!("{{ int64_t b = pop(), a = pop(); v = a {} b; }}\n    {}", operator, next)
+            }
+            '&' | 'o' | 'x' => {
+                let operator = match symbol {
+                    '&' => "&",
+                    'o' => "|",
+                    _ => "^",
+                };
+                format!("{{ int64_t b = pop(), a = pop(); v = a {} b; }}\n    {}", operator, next)
+            }
+            'N' => format!("v = ~v;\n    {}", next),
+            '[' | ']' => {
+                let operation = if symbol == '[' { "shl" } else { "shr" };
+                format!("{{ int64_t b = pop(), a = pop(); v = {}(a, b, {}); }}\n    {}", operation, at, next)
+            }
+            '+' => format!("v = add(v, 1, {});\n    {}", at, next),
+            '~' => format!("v = sub(v, 1, {});\n    {}", at, next),
+            // Compiled values are always whole
+            't' => next,
+            'J' => match grid.portals.exit(coord) {
+                Some(exit) => goto(exit + direction, direction),
+                None => "goto halt;".to_string(),
+            },
+            'K' => format!("if (pop() == 0) goto halt;\n    {}", next),
+            'n' => format!("out_number(v);\n    {}", next),
+            ',' => format!("out_char(v);\n    {}", next),
+            // `??` reads a number; the second `?` then reads as usual
+            '?' if grid.get_symbol(coord + direction) == Some('?') => format!("v = in_number();\n    {}", next),
+            '?' => format!("v = in_char();\n    {}", next),
+            'C' => {
+                let label = grid.subroutines.call_label(coord)
+                    .and_then(|name| grid.subroutines.label(name))
+                    .ok_or_else(|| format!("'C' at {} has no label to call", coord))?;
+                let id = returns.iter().position(|&state| state == (coord, direction)).unwrap_or_default();
+                format!("call({});\n    {}", id, goto(label.entry, label.direction))
+            }
+            'R' => {
+                let mut body = String::from("if (csp > 0) {\n        switch (calls[--csp]) {\n");
+                for (id, &(site, heading)) in returns.iter().enumerate() {
+                    let _ = writeln!(body, "        case {}: {}", id, goto(site + heading, heading));
+                }
+                let _ = write!(body, "        }}\n    }}\n    {}", next);
+                body
+            }
+            // `!`, blank and unknown cells destroy the droplet
+            _ => "goto halt;".to_string(),
+        };
+        let _ = writeln!(c, "    {}", body);
+    }
+    c.push_str("halt:\n    fflush(stdout);\n    return 0;\n}\n");
+    Ok(c)
+}
+
+/// Why a cell can only run in the interpreter, if it cannot be compiled
+fn dynamic_feature(grid: &ProgramGrid, coord: Coordinate, symbol: char) -> Option<&'static str> {
+    match symbol {
+        'i' => Some("pseudo-input"),
+        'r' => Some("random numbers"),
+        'a' => Some("program arguments"),
+        'z' => Some("sleep"),
+        'C' if grid.subroutines.call_label(coord).is_none() => Some("unbound call"),
+        '0'..='9' if grid.multi_digit_literals => Some("multi-digit literals"),
+        'D' if grid.rational_numbers => Some("exact division"),
+        '?' if grid.eof_policy.is_some_and(|policy| policy != EofPolicy::Newline) => Some("end-of-input policy"),
+        _ if IoOperations::is_file_operation(symbol) => Some("file access"),
+        _ if NetOperations::is_network_operation(symbol) => Some("network access"),
+        _ if LocalStackOperations::is_local_stack_operation(symbol) => Some("local stacks"),
+        _ if MemoryOperations::is_memory_operation(symbol) => Some("the reservoir"),
+        _ if ReflectionOperations::is_reflection_operation(symbol) => Some("grid reflection"),
+        _ if MetadataOperations::is_metadata_operation(symbol) => Some("droplet metadata"),
+        _ => None,
+    }
+}
+
+/// Non-empty cells and headings the droplet can be on, in grid order
+///
+/// Every continuation after a bound `C` is included, since an `R` may
+/// return to any of them.
+fn reachable_states(grid: &ProgramGrid, entry: (Coordinate, Direction)) -> Vec<(Coordinate, Direction)> {
+    let mut states = Vec::new();
+    let mut visited = HashSet::new();
+    let mut worklist = vec![entry];
+    while let Some((coord, direction)) = worklist.pop() {
+        if grid.get(coord).is_none() || !visited.insert((coord, direction)) {
+            continue;
+        }
+        states.push((coord, direction));
+        worklist.extend(StaticFlow::successors(grid, coord, direction));
+        if grid.get_symbol(coord) == Some('C') {
+            worklist.push((coord + direction, direction));
+        }
+    }
+    states.sort_by_key(|&(coord, direction)| (coord.y, coord.x, direction as u8));
+    states
+}
+
+/// A symbol as it can appear inside a C comment
+fn escape(symbol: char) -> String {
+    match symbol {
+        '\\' => "'\\\\'".to_string(),
+        _ => format!("'{}'", symbol),
+    }
+}
+
+/// Helpers shared by every compiled program
+const RUNTIME: &str = r#"#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+static int64_t *stack;
+static size_t sp, stack_size;
+static size_t *calls;
+static size_t csp, calls_size;
+
+static void fail(const char *message, const char *at) {
+    fflush(stdout);
+    fprintf(stderr, "Runtime error at %s: %s\n", at, message);
+    exit(3);
+}
+
+static void *grow(void *items, size_t *size, size_t item) {
+    *size = *size ? *size * 2 : 64;
+    items = realloc(items, *size * item);
+    if (!items) fail("out of memory", "start");
+    return items;
+}
+
+static inline void push(int64_t value) {
+    if (sp == stack_size) stack = grow(stack, &stack_size, sizeof *stack);
+    stack[sp++] = value;
+}
+
+static inline int64_t pop(void) {
+    return sp > 0 ? stack[--sp] : 0;
+}
+
+static inline void call(size_t id) {
+    if (csp == calls_size) calls = grow(calls, &calls_size, sizeof *calls);
+    calls[csp++] = id;
+}
+
+#define OVERFLOW "value does not fit in 64 bits"
+
+static inline int64_t add(int64_t a, int64_t b, const char *at) {
+    int64_t r;
+    if (__builtin_add_overflow(a, b, &r)) fail(OVERFLOW, at);
+    return r;
+}
+
+static inline int64_t sub(int64_t a, int64_t b, const char *at) {
+    int64_t r;
+    if (__builtin_sub_overflow(a, b, &r)) fail(OVERFLOW, at);
+    return r;
+}
+
+static inline int64_t mul(int64_t a, int64_t b, const char *at) {
+    int64_t r;
+    if (__builtin_mul_overflow(a, b, &r)) fail(OVERFLOW, at);
+    return r;
+}
+
+/* Division and remainder by zero give 0 */
+static inline int64_t quot(int64_t a, int64_t b, const char *at) {
+    if (b == 0) return 0;
+    if (b == -1) return sub(0, a, at);
+    return a / b;
+}
+
+static inline int64_t rem(int64_t a, int64_t b) {
+    return b == 0 || b == -1 ? 0 : a % b;
+}
+
+/* 0 to the power 0 is 1 */
+static inline int64_t power(int64_t a, int64_t b, const char *at) {
+    if (b < 0) fail("negative exponent", at);
+    int64_t r = 1;
+    for (; b > 0; b >>= 1) {
+        if (b & 1) r = mul(r, a, at);
+        if (b > 1) a = mul(a, a, at);
+    }
+    return r;
+}
+
+/* Negative counts shift the other way; right shifts round down */
+static inline int64_t shl(int64_t a, int64_t b, const char *at) {
+    if (b < 0) return b < -63 ? (a < 0 ? -1 : 0) : a >> -b;
+    if (a == 0) return 0;
+    if (b > 63 || a > (INT64_MAX >> b) || a < (INT64_MIN >> b)) fail(OVERFLOW, at);
+    return (int64_t)((uint64_t)a << b);
+}
+
+static inline int64_t shr(int64_t a, int64_t b, const char *at) {
+    return shl(a, b == INT64_MIN ? INT64_MAX : -b, at);
+}
+
+static inline void out_number(int64_t v) {
+    printf("%lld", (long long)v);
+}
+
+/* Values that are not Unicode scalar values print nothing */
+static inline void out_char(int64_t v) {
+    uint32_t c = (uint32_t)v;
+    if (v < 0 || v > 0x10FFFF || (c >= 0xD800 && c <= 0xDFFF)) return;
+    if (c < 0x80) {
+        putchar((int)c);
+    } else if (c < 0x800) {
+        putchar(0xC0 | (c >> 6));
+        putchar(0x80 | (c & 0x3F));
+    } else if (c < 0x10000) {
+        putchar(0xE0 | (c >> 12));
+        putchar(0x80 | ((c >> 6) & 0x3F));
+        putchar(0x80 | (c & 0x3F));
+    } else {
+        putchar(0xF0 | (c >> 18));
+        putchar(0x80 | ((c >> 12) & 0x3F));
+        putchar(0x80 | ((c >> 6) & 0x3F));
+        putchar(0x80 | (c & 0x3F));
+    }
+}
+
+static inline int next_byte(void) {
+    int c = getchar();
+    return c == EOF ? '\n' : c;
+}
+
+static inline void skip_line(int c) {
+    while (c != '\n' && c != EOF) c = getchar();
+}
+
+/* Next character, newlines included, truncated to a byte; a newline at the end of input */
+static inline int64_t in_char(void) {
+    int c, extra, i;
+    uint32_t code;
+    fflush(stdout);
+    c = next_byte();
+    extra = c >= 0xF0 ? 3 : c >= 0xE0 ? 2 : c >= 0xC0 ? 1 : 0;
+    code = extra ? (uint32_t)c & (0x3F >> extra) : (uint32_t)c;
+    for (i = 0; i < extra; i++) {
+        c = getchar();
+        if ((c & 0xC0) != 0x80) {
+            /* Not a continuation byte: leave it for the next read */
+            if (c != EOF) ungetc(c, stdin);
+            return code & 0xFF;
+        }
+        code = (code << 6) | ((uint32_t)c & 0x3F);
+    }
+    return code & 0xFF;
+}
+
+/* First integer on the rest of the line, or 0 if there is none or it does not fit */
+static inline int64_t in_number(void) {
+    int c, negative = 0, found = 0, fits = 1;
+    int64_t n = 0;
+    fflush(stdout);
+    for (c = getchar(); c != '\n' && c != EOF; c = getchar()) {
+        if (c == '-' && !negative && !found) {
+            negative = 1;
+        } else if (c >= '0' && c <= '9') {
+            found = 1;
+            if (__builtin_mul_overflow(n, 10, &n) || __builtin_sub_overflow(n, c - '0', &n)) fits = 0;
+        } else if (found) {
+            break;
+        } else {
+            negative = 0;
+        }
+    }
+    skip_line(c);
+    if (!found || !fits || (!negative && n == INT64_MIN)) return 0;
+    return negative ? n : -n;
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_reachable_cells_become_labelled_blocks() {
+        let grid = GridParser::new().parse_string("@\n7\n:\nA\nn\n!").unwrap();
+        let c = emit_c(&grid, "sum.tb").unwrap();
+        assert!(c.starts_with("/* sum.tb compiled by tubular compile --emit c */"));
+        assert!(c.contains("s1: /* '7' at (0, 1) heading"));
+        assert!(c.contains("v = 7;\n    goto s2;"));
+        assert!(c.contains("{ int64_t b = pop(), a = pop(); v = add(a, b, \"(0, 3)\"); }"));
+        assert!(c.contains("s5: /* '!' at (0, 5) heading down */\n    goto halt;"));
+    }
+
+    #[test]
+    fn test_bitwise_operations_compile() {
+        let grid = GridParser::new().parse_string("@\n&\nN\n[\nn\n!").unwrap();
+        let c = emit_c(&grid, "bits.tb").unwrap();
+        assert!(c.contains("{ int64_t b = pop(), a = pop(); v = a & b; }"));
+        assert!(c.contains("v = ~v;"));
+        assert!(c.contains("v = shl(a, b, \"(0, 3)\");"));
+    }
+
+    #[test]
+    fn test_power_and_absolute_value_compile() {
+        let grid = GridParser::new().parse_string("@\n*\n_\nn\n!").unwrap();
+        let c = emit_c(&grid, "pow.tb").unwrap();
+        assert!(c.contains("v = power(a, b, \"(0, 1)\");"));
+        assert!(c.contains("{ int64_t a = pop(); v = a < 0 ? sub(0, a, \"(0, 2)\") : a; }"));
+    }
+
+    #[test]
+    fn test_quotes_compile_to_character_codes() {
+        let grid = GridParser::new().parse_string("@\n'\nH\n,\n!").unwrap();
+        let c = emit_c(&grid, "h.tb").unwrap();
+        assert!(c.contains("s1: /* ''' at (0, 1) heading down */\n    v = 72;\n    goto s2;"), "{}", c);
+        assert!(c.contains("s2: /* ',' at (0, 3) heading down */"), "{}", c);
+    }
+
+    #[test]
+    fn test_portals_compile_to_jumps_past_their_pair() {
+        let grid = GridParser::new().parse_string("@   J\n7   n\nJ\n%portal a at (0, 2)\n%portal a at (4, 0)").unwrap();
+        let c = emit_c(&grid, "portal.tb").unwrap();
+        assert!(c.contains("s2: /* 'n' at (4, 1) heading down */"), "{}", c);
+        assert!(c.contains("s3: /* 'J' at (0, 2) heading down */\n    goto s2;"), "{}", c);
+    }
+
+    #[test]
+    fn test_dynamic_features_are_refused() {
+        let grid = GridParser::new().parse_string("@\nr\nC\nn\n!\n\n   z").unwrap();
+        let error = emit_c(&grid, "dice.tb").unwrap_err();
+        assert!(error.contains("'r' (random numbers) at (0, 1)"), "{}", error);
+        assert!(error.contains("'C' (unbound call) at (0, 2)"), "{}", error);
+        // Unreachable cells are never compiled, so they do not matter
+        assert!(!error.contains("'z'"), "{}", error);
+
+        let grid = GridParser::new().parse_string("%multidigit\n@\n4\n2\nn").unwrap();
+        let error = emit_c(&grid, "answer.tb").unwrap_err();
+        assert!(error.contains("'4' (multi-digit literals) at (0, 2)"), "{}", error);
+
+        let grid = GridParser::new().parse_string("%eof minus-one\n@\n?\nn").unwrap();
+        let error = emit_c(&grid, "cat.tb").unwrap_err();
+        assert!(error.contains("'?' (end-of-input policy) at (0, 2)"), "{}", error);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod c;
+
+pub use c::*;
@@ -0,0 +1,202 @@
+use crate::interpreter::droplet::{Droplet, DropletId};
+use crate::types::prelude::*;
+use core::ops::{Deref, Index, IndexMut};
+
+/// Handle to a droplet in a [`DropletArena`]
+///
+/// Stays valid while its droplet is stored; once the droplet is removed the
+/// slot may be reused, and the generation makes the old key resolve to None.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DropletKey {
+    slot: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    generation: u32,
+    /// Position in the dense droplet list (None = free)
+    index: Option<u32>,
+}
+
+/// Generational slab holding the live droplets
+///
+/// Droplets are kept densely in insertion order, so the arena derefs to a
+/// `[Droplet]` slice for iteration and schedulers, while keys give O(1)
+/// access for the commands issued during a tick.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<Droplet>", into = "Vec<Droplet>"))]
+pub struct DropletArena {
+    droplets: Vec<Droplet>,
+    /// Slot of each entry in `droplets`
+    owners: Vec<u32>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl DropletArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a droplet after the existing ones
+    pub fn push(&mut self, droplet: Droplet) -> DropletKey {
+        let index = Some(self.droplets.len() as u32);
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot as usize].index = index;
+                slot
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, index });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        self.droplets.push(droplet);
+        self.owners.push(slot);
+        DropletKey { slot, generation: self.slots[slot as usize].generation }
+    }
+
+    /// Key of the droplet at position `index` of the slice
+    pub fn key_at(&self, index: usize) -> DropletKey {
+        let slot = self.owners[index];
+        DropletKey { slot, generation: self.slots[slot as usize].generation }
+    }
+
+    fn index_of(&self, key: DropletKey) -> Option<usize> {
+        self.slots.get(key.slot as usize)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.index)
+            .map(|index| index as usize)
+    }
+
+    pub fn get(&self, key: DropletKey) -> Option<&Droplet> {
+        self.index_of(key).map(|index| &self.droplets[index])
+    }
+
+    pub fn get_mut(&mut self, key: DropletKey) -> Option<&mut Droplet> {
+        self.index_of(key).map(|index| &mut self.droplets[index])
+    }
+
+    /// Look a droplet up by its ID; a linear scan, unlike [`get`](Self::get)
+    pub fn find(&self, id: DropletId) -> Option<&Droplet> {
+        self.droplets.iter().find(|droplet| droplet.id == id)
+    }
+
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, Droplet> {
+        self.droplets.iter_mut()
+    }
+
+    /// Keep only droplets for which `keep` returns true, preserving order
+    ///
+    /// Keys of removed droplets stop resolving; keys of kept ones stay valid.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Droplet) -> bool) {
+        let mut kept = 0;
+        for index in 0..self.droplets.len() {
+            let slot = self.owners[index];
+            if keep(&self.droplets[index]) {
+                self.droplets.swap(kept, index);
+                self.owners.swap(kept, index);
+                self.slots[slot as usize].index = Some(kept as u32);
+                kept += 1;
+            } else {
+                self.release(slot);
+            }
+        }
+        self.droplets.truncate(kept);
+        self.owners.truncate(kept);
+    }
+
+    pub fn clear(&mut self) {
+        for slot in core::mem::take(&mut self.owners) {
+            self.release(slot);
+        }
+        self.droplets.clear();
+    }
+
+    fn release(&mut self, slot: u32) {
+        let entry = &mut self.slots[slot as usize];
+        entry.index = None;
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free.push(slot);
+    }
+}
+
+impl Deref for DropletArena {
+    type Target = [Droplet];
+
+    fn deref(&self) -> &[Droplet] {
+        &self.droplets
+    }
+}
+
+impl Index<usize> for DropletArena {
+    type Output = Droplet;
+
+    fn index(&self, index: usize) -> &Droplet {
+        &self.droplets[index]
+    }
+}
+
+impl IndexMut<usize> for DropletArena {
+    fn index_mut(&mut self, index: usize) -> &mut Droplet {
+        &mut self.droplets[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a DropletArena {
+    type Item = &'a Droplet;
+    type IntoIter = core::slice::Iter<'a, Droplet>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.droplets.iter()
+    }
+}
+
+impl From<Vec<Droplet>> for DropletArena {
+    fn from(droplets: Vec<Droplet>) -> Self {
+        let mut arena = DropletArena::new();
+        for droplet in droplets {
+            arena.push(droplet);
+        }
+        arena
+    }
+}
+
+impl From<DropletArena> for Vec<Droplet> {
+    fn from(arena: DropletArena) -> Self {
+        arena.droplets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::coordinate::Coordinate;
+    use crate::types::direction::Direction;
+
+    fn droplet(id: DropletId) -> Droplet {
+        Droplet::new(id, Coordinate::new(id as isize, 0), Direction::Down)
+    }
+
+    #[test]
+    fn test_retain_invalidates_removed_keys_only() {
+        let mut arena = DropletArena::new();
+        let keys: Vec<_> = (0..4).map(|id| arena.push(droplet(id))).collect();
+
+        arena.retain(|droplet| droplet.id % 2 == 1);
+        assert_eq!(arena.iter().map(|d| d.id).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(arena.get(keys[0]).is_none());
+        assert_eq!(arena.get(keys[3]).map(|d| d.id), Some(3));
+
+        // A reused slot does not answer to the key of its previous droplet
+        let reused = arena.push(droplet(4));
+        assert!(arena.get(keys[0]).is_none() && arena.get(keys[2]).is_none());
+        assert_eq!(arena.get(reused).map(|d| d.id), Some(4));
+        assert_eq!(arena.key_at(2), reused);
+
+        arena.get_mut(keys[1]).unwrap().set_value(crate::types::number::TubularNumber::new(9));
+        assert_eq!(arena.find(1).unwrap().value, crate::types::number::TubularNumber::new(9));
+    }
+}
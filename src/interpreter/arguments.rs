@@ -0,0 +1,71 @@
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+
+/// Command-line arguments read by the argument operation (`a`)
+///
+/// Arguments are numbered from 1; number 0 stands for the argument count.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgramArguments {
+    args: Vec<String>,
+}
+
+impl ProgramArguments {
+    pub fn new(args: Vec<String>) -> Self {
+        ProgramArguments { args }
+    }
+
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Value `a` reads for argument `index` at character `position`
+    ///
+    /// Index 0 gives the argument count. Position 0 gives the argument as an
+    /// integer (0 if it is not one or does not exist) and position k >= 1 the
+    /// code of its k-th character, or -1 past its end.
+    pub fn value(&self, index: &TubularNumber, position: &TubularNumber) -> TubularNumber {
+        if index.is_zero() {
+            return TubularNumber::new(self.args.len() as i64);
+        }
+        let arg = index.to_usize()
+            .and_then(|index| index.checked_sub(1))
+            .and_then(|index| self.args.get(index));
+        match position.to_usize() {
+            Some(0) => arg
+                .and_then(|arg| arg.trim().parse().ok())
+                .map(TubularNumber::from_bigint)
+                .unwrap_or_default(),
+            Some(k) => arg
+                .and_then(|arg| arg.chars().nth(k - 1))
+                .map_or(TubularNumber::new(-1), TubularNumber::from_char),
+            None => TubularNumber::new(-1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(args: &ProgramArguments, index: i64, position: i64) -> Option<i64> {
+        args.value(&TubularNumber::new(index), &TubularNumber::new(position)).to_i64()
+    }
+
+    #[test]
+    fn test_arguments_by_index_and_position() {
+        let args = ProgramArguments::new(vec!["42".to_string(), "hi".to_string()]);
+
+        assert_eq!(value(&args, 0, 5), Some(2));
+        assert_eq!(value(&args, 1, 0), Some(42));
+        assert_eq!(value(&args, 2, 0), Some(0));
+        assert_eq!(value(&args, 2, 1), Some('h' as i64));
+        assert_eq!(value(&args, 2, 3), Some(-1));
+        assert_eq!(value(&args, 3, 1), Some(-1));
+        assert_eq!(value(&args, -1, 0), Some(0));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::types::coordinate::Coordinate;
+use crate::types::number::TubularNumber;
+use crate::interpreter::droplet::DropletId;
+use crate::interpreter::execution::ExecutionState;
+use core::fmt;
+
+/// Condition that pauses execution when it becomes true
+///
+/// Conditions are checked against the state between ticks. A breakpoint
+/// fires on the tick its condition changes from false to true, so resuming
+/// does not immediately stop again at the same place.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BreakCondition {
+    /// A droplet is on this cell
+    At(Coordinate),
+    /// The next tick to execute is this one
+    Tick(u64),
+    /// The droplet with this ID exists
+    Droplet(DropletId),
+    /// A droplet holds this value
+    Value(TubularNumber),
+}
+
+impl BreakCondition {
+    pub fn is_met(&self, state: &ExecutionState) -> bool {
+        match self {
+            BreakCondition::At(coord) => state.droplets.iter().any(|d| d.position == *coord),
+            BreakCondition::Tick(tick) => state.tick == *tick,
+            BreakCondition::Droplet(id) => state.droplets.iter().any(|d| d.id == *id),
+            BreakCondition::Value(value) => state.droplets.iter().any(|d| d.value == *value),
+        }
+    }
+}
+
+impl fmt::Display for BreakCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakCondition::At(coord) => write!(f, "droplet at {}", coord),
+            BreakCondition::Tick(tick) => write!(f, "tick {}", tick),
+            BreakCondition::Droplet(id) => write!(f, "droplet D{}", id),
+            BreakCondition::Value(value) => write!(f, "value {}", value),
+        }
+    }
+}
+
+/// A registered break condition and whether it held after the last check
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    pub condition: BreakCondition,
+    met: bool,
+}
+
+impl Breakpoint {
+    pub fn new(condition: BreakCondition, state: &ExecutionState) -> Self {
+        let met = condition.is_met(state);
+        Breakpoint { condition, met }
+    }
+
+    /// Re-evaluate against `state`, returning true if the condition just became true
+    pub fn check(&mut self, state: &ExecutionState) -> bool {
+        let was_met = self.met;
+        self.met = self.condition.is_met(state);
+        self.met && !was_met
+    }
+}
@@ -0,0 +1,309 @@
+use crate::interpreter::trace::TraceConfig;
+use crate::interpreter::interrupt::InterruptHandle;
+use crate::interpreter::collision::CollisionPolicy;
+use crate::interpreter::execution::{ExecutionLimits, RuntimeOptions, TubularInterpreter};
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::scheduler::SchedulerKind;
+use crate::operations::io::{EofPolicy, InputMode, InputProvider, OutputSink};
+use crate::types::error::Result;
+use crate::types::prelude::*;
+use alloc::sync::Arc;
+use core::time::Duration;
+
+/// Makes the provider or sink for each interpreter a builder builds
+type Factory<T> = Arc<dyn Fn() -> Box<T> + Send + Sync>;
+
+/// Collects interpreter settings and builds configured interpreters
+///
+/// A builder can be kept and reused, building one interpreter per program:
+///
+/// ```
+/// use tubular::interpreter::execution::{ExecutionLimits, TubularInterpreter};
+/// use tubular::parser::grid_parser::GridParser;
+///
+/// let builder = TubularInterpreter::builder()
+///     .with_limits(ExecutionLimits::unlimited().with_max_ticks(Some(100)))
+///     .with_seed(42);
+/// let grid = GridParser::new().parse_string("@\n7\nn\n!").unwrap();
+/// let result = builder.build(grid).unwrap().run().unwrap();
+/// assert_eq!(result.final_output, "7");
+/// ```
+#[derive(Clone, Default)]
+pub struct InterpreterBuilder {
+    limits: ExecutionLimits,
+    options: RuntimeOptions,
+    trace_config: Option<TraceConfig>,
+    input: Option<Factory<dyn InputProvider>>,
+    output: Option<Factory<dyn OutputSink>>,
+    interrupt: Option<InterruptHandle>,
+    verbose: bool,
+    profiling: bool,
+    /// Ticks of undo history to keep (None = no history)
+    history: Option<usize>,
+}
+
+impl InterpreterBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace every execution limit
+    pub fn with_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Hard tick limit (None = unlimited), keeping the other limits
+    pub fn with_max_ticks(mut self, max_ticks: Option<u64>) -> Self {
+        self.limits.max_ticks = max_ticks;
+        self
+    }
+
+    /// Hard wall-clock limit in milliseconds (None = unlimited), keeping the other limits
+    pub fn with_max_time_ms(mut self, max_time_ms: Option<u64>) -> Self {
+        self.limits.max_time_ms = max_time_ms;
+        self
+    }
+
+    pub fn with_runtime_options(mut self, options: RuntimeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_scheduler(mut self, scheduler: SchedulerKind) -> Self {
+        self.options.scheduler = scheduler;
+        self
+    }
+
+    /// Seed for the deterministic pseudo-input operation (`i`)
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.options.pseudo_input_seed = seed;
+        self
+    }
+
+    /// Let the file operations (`O`, `I`, `W`, `X`) read and write files
+    pub fn with_allow_fs(mut self, allow_fs: bool) -> Self {
+        self.options = self.options.with_allow_fs(allow_fs);
+        self
+    }
+
+    /// Let the network operations (`T`, `E`, `V`) open TCP connections
+    pub fn with_allow_net(mut self, allow_net: bool) -> Self {
+        self.options = self.options.with_allow_net(allow_net);
+        self
+    }
+
+    /// Longest `T` waits for a TCP connection (see [`RuntimeOptions::connect_timeout`])
+    pub fn with_connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.options = self.options.with_connect_timeout(timeout);
+        self
+    }
+
+    /// Let `p` rewrite the program's cells while it runs
+    pub fn with_allow_self_modify(mut self, allow_self_modify: bool) -> Self {
+        self.options = self.options.with_allow_self_modify(allow_self_modify);
+        self
+    }
+
+    /// Seed for the random operation (`r`), for reproducible runs
+    pub fn with_random_seed(mut self, seed: u64) -> Self {
+        self.options.random_seed = Some(seed);
+        self
+    }
+
+    /// Make runs reproducible across machines (see [`RuntimeOptions::deterministic`])
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.options.deterministic = deterministic;
+        self
+    }
+
+    /// Arguments the program reads with the argument operation (`a`)
+    pub fn with_program_args(mut self, args: Vec<String>) -> Self {
+        self.options.program_args = args;
+        self
+    }
+
+    /// Compute droplet commands in parallel when many droplets are active
+    /// (needs the `parallel` feature)
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.options.parallel = parallel;
+        self
+    }
+
+    /// Compile hot pipe corridors to native code (needs the `jit` feature)
+    pub fn with_jit(mut self, jit: bool) -> Self {
+        self.options.jit = jit;
+        self
+    }
+
+    /// Fold constant stack arithmetic on fixed paths before the run
+    pub fn with_optimize(mut self, optimize: bool) -> Self {
+        self.options.optimize = optimize;
+        self
+    }
+
+    /// Give every droplet its own stack for `{`, `}`, `(` and `)`
+    pub fn with_local_stacks(mut self, local_stacks: bool) -> Self {
+        self.options.local_stacks = local_stacks;
+        self
+    }
+
+    /// Read consecutive digits on a droplet's path as one number
+    pub fn with_multi_digit_literals(mut self, multi_digit_literals: bool) -> Self {
+        self.options.multi_digit_literals = multi_digit_literals;
+        self
+    }
+
+    /// Decide what happens when droplets collide, whatever the program's `%collisions` line says
+    pub fn with_collision_policy(mut self, policy: CollisionPolicy) -> Self {
+        self.options.collision_policy = Some(policy);
+        self
+    }
+
+    /// Decide how `?` and `??` divide up their input
+    pub fn with_input_mode(mut self, mode: InputMode) -> Self {
+        self.options.input_mode = Some(mode);
+        self
+    }
+
+    /// Decide what `?` gives at end of input, whatever the program's `%eof` line says
+    pub fn with_eof_policy(mut self, policy: EofPolicy) -> Self {
+        self.options.eof_policy = Some(policy);
+        self
+    }
+
+    /// Pause between ticks so `run` does `hz` ticks a second
+    pub fn with_tick_rate(mut self, hz: f64) -> Self {
+        self.options.tick_rate = Some(hz);
+        self
+    }
+
+    /// Let droplets leaving the grid re-enter on the opposite side
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.options.wrap = wrap;
+        self
+    }
+
+    /// Record trace events matching `config`
+    pub fn with_trace_config(mut self, config: TraceConfig) -> Self {
+        self.trace_config = Some(config);
+        self
+    }
+
+    /// Read `?` and `??` input from `input` instead of stdin
+    ///
+    /// Each interpreter built gets its own clone of `input`.
+    pub fn with_input<P: InputProvider + Clone + Sync + 'static>(mut self, input: P) -> Self {
+        self.input = Some(Arc::new(move || Box::new(input.clone())));
+        self
+    }
+
+    /// Send output to `output` as it is produced
+    ///
+    /// Each interpreter built gets its own clone of `output`; use a
+    /// [`SharedOutput`](crate::operations::io::SharedOutput) to collect it in one place.
+    pub fn with_output<S: OutputSink + Clone + Sync + 'static>(mut self, output: S) -> Self {
+        self.output = Some(Arc::new(move || Box::new(output.clone())));
+        self
+    }
+
+    /// Let `handle` cancel every interpreter built from now on
+    ///
+    /// The interpreters share the handle, so a pause requested through it
+    /// stops whichever of them reaches its next tick first.
+    pub fn with_interrupt_handle(mut self, handle: InterruptHandle) -> Self {
+        self.interrupt = Some(handle);
+        self
+    }
+
+    /// Log ticks, warnings and the final status to stderr
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    pub fn with_profiling(mut self, enabled: bool) -> Self {
+        self.profiling = enabled;
+        self
+    }
+
+    /// Keep undo journals for the last `window` ticks
+    pub fn with_history(mut self, window: usize) -> Self {
+        self.history = Some(window);
+        self
+    }
+
+    pub fn limits(&self) -> &ExecutionLimits {
+        &self.limits
+    }
+
+    /// Validate `grid` and build an interpreter with these settings
+    pub fn build(&self, grid: impl Into<Arc<ProgramGrid>>) -> Result<TubularInterpreter> {
+        let mut interpreter = TubularInterpreter::from_shared(grid.into())?
+            .with_options(self.verbose, false, None)
+            .with_limits(self.limits.clone())
+            .with_runtime_options(&self.options)
+            .with_profiling(self.profiling);
+        if let Some(config) = &self.trace_config {
+            interpreter = interpreter.with_trace_config(config.clone());
+        }
+        if let Some(input) = &self.input {
+            interpreter = interpreter.with_boxed_input(input());
+        }
+        if let Some(output) = &self.output {
+            interpreter = interpreter.with_boxed_output(output());
+        }
+        if let Some(handle) = &self.interrupt {
+            interpreter = interpreter.with_interrupt_handle(handle.clone());
+        }
+        if let Some(window) = self.history {
+            interpreter.enable_history(window);
+        }
+        Ok(interpreter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::trace::TraceLevel;
+    use crate::operations::io::{SharedOutput, StringInput};
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_builder_configures_each_interpreter() {
+        let builder = TubularInterpreter::builder()
+            .with_max_ticks(Some(3))
+            .with_seed(9)
+            .with_trace_config(TraceConfig::new().with_level(TraceLevel::Detailed))
+            .with_history(4);
+        let grid = GridParser::new().parse_string("@\n|\n|\n|\n|\n!").unwrap();
+
+        let mut interpreter = builder.build(grid.clone()).unwrap();
+        assert_eq!(interpreter.limits().max_ticks, Some(3));
+        assert_eq!(interpreter.limits().max_time_ms, ExecutionLimits::default().max_time_ms);
+        let result = interpreter.run().unwrap();
+        assert_eq!(result.status, crate::interpreter::execution::ExecutionStatus::TickTimeout(3));
+        assert!(!interpreter.trace_events().is_empty());
+        assert!(interpreter.step_back());
+
+        let other = builder.build(Arc::new(grid)).unwrap();
+        assert_eq!(other.state().pseudo_input, crate::interpreter::pseudo_input::PseudoInput::new(9));
+        assert!(builder.build(ProgramGrid::new()).is_err());
+    }
+
+    #[test]
+    fn test_built_interpreters_read_input_and_stream_output() {
+        let output = SharedOutput::new();
+        let builder = TubularInterpreter::builder()
+            .with_input(StringInput::new("A"))
+            .with_output(output.clone());
+        let grid = GridParser::new().parse_string("@\n?\n,\n!").unwrap();
+
+        for _ in 0..2 {
+            let result = builder.build(grid.clone()).unwrap().run().unwrap();
+            assert_eq!(result.final_output, "A");
+        }
+        assert_eq!(output.contents(), "AA");
+    }
+}
@@ -0,0 +1,234 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::operations::arithmetic::ArithmeticOperations;
+use crate::operations::io::IoOperations;
+use crate::operations::local_stack::LocalStackOperations;
+use crate::operations::memory::MemoryOperations;
+use crate::operations::metadata::MetadataOperations;
+use crate::operations::net::NetOperations;
+use crate::operations::reflection::ReflectionOperations;
+use crate::types::coordinate::Coordinate;
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+
+/// What a cell does to a droplet, decoded once before the program runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `!`, unknown symbols, blank cells and everything outside the grid
+    Destroy,
+    /// `|`, `-` and `@`
+    Pass,
+    /// `/`
+    Mirror,
+    /// `\`: turn non-zero droplets, reverse zero ones
+    Branch,
+    /// `^`
+    Up,
+    /// `0` to `9`
+    Digit(u8),
+    /// Stack and arithmetic operations, handled by [`ArithmeticOperations`]
+    Arithmetic(char),
+    /// `i`
+    PseudoInput,
+    /// `r`
+    Random,
+    /// `a`
+    Argument,
+    /// `C`
+    Call,
+    /// `R`
+    Return,
+    /// File operations, handled by [`IoOperations`]
+    File(char),
+    /// Socket operations, handled by [`NetOperations`]
+    Network(char),
+    /// Droplet stack operations, handled by [`LocalStackOperations`]
+    Local(char),
+    /// Reservoir operations, handled by [`MemoryOperations`]
+    Memory(char),
+    /// `g` and `p`, handled by [`ReflectionOperations`]
+    Reflect(char),
+    /// Droplet ID, age and heading, handled by [`MetadataOperations`]
+    Metadata(char),
+    /// `'`: read the next cell's character and jump over it
+    Quote,
+    /// `J`: leave from the other end of the portal pair
+    Portal,
+    /// `?`, or `??` when the next cell is also `?`
+    Input,
+    /// `z`
+    Sleep,
+    /// `K`: pop a value and let the droplet through only if it is non-zero
+    Gate,
+    /// `,`
+    OutputChar,
+    /// `n`
+    OutputNumber,
+}
+
+impl Op {
+    /// Operation of a cell holding `symbol`
+    pub fn decode(symbol: char) -> Self {
+        match symbol {
+            '|' | '-' | '@' => Op::Pass,
+            '/' => Op::Mirror,
+            '\\' => Op::Branch,
+            '^' => Op::Up,
+            '0'..='9' => Op::Digit(symbol as u8 - b'0'),
+            _ if ArithmeticOperations::is_arithmetic_operation(symbol) => Op::Arithmetic(symbol),
+            'i' => Op::PseudoInput,
+            'r' => Op::Random,
+            'a' => Op::Argument,
+            'C' => Op::Call,
+            'R' => Op::Return,
+            _ if IoOperations::is_file_operation(symbol) => Op::File(symbol),
+            _ if NetOperations::is_network_operation(symbol) => Op::Network(symbol),
+            _ if LocalStackOperations::is_local_stack_operation(symbol) => Op::Local(symbol),
+            _ if MemoryOperations::is_memory_operation(symbol) => Op::Memory(symbol),
+            _ if ReflectionOperations::is_reflection_operation(symbol) => Op::Reflect(symbol),
+            _ if MetadataOperations::is_metadata_operation(symbol) => Op::Metadata(symbol),
+            '?' => Op::Input,
+            'z' => Op::Sleep,
+            'K' => Op::Gate,
+            ',' => Op::OutputChar,
+            'n' => Op::OutputNumber,
+            '\'' => Op::Quote,
+            'J' => Op::Portal,
+            _ => Op::Destroy,
+        }
+    }
+
+    /// Whether the droplet stays on its cell for the operation to run after movement is resolved
+    pub fn stays(self) -> bool {
+        matches!(self,
+            Op::Arithmetic(_) | Op::PseudoInput | Op::Random | Op::Argument | Op::Call | Op::Return
+            | Op::File(_) | Op::Network(_) | Op::Local(_) | Op::Memory(_) | Op::Reflect(_) | Op::Metadata(_) | Op::Quote
+        )
+    }
+}
+
+/// Largest dense table built for a grid, in cells; sparser grids are hashed instead
+const MAX_DENSE_CELLS: usize = 1 << 24;
+
+/// Every cell of a grid decoded to an [`Op`], so the run loop looks up one
+/// table entry per droplet instead of hashing coordinates and matching characters
+///
+/// Grids that fill at least a sixteenth of their bounding box get a flat,
+/// row-major table; very sparse ones keep a map.
+#[derive(Debug, Clone)]
+pub struct Bytecode {
+    origin: Coordinate,
+    width: usize,
+    height: usize,
+    table: Table,
+}
+
+#[derive(Debug, Clone)]
+enum Table {
+    Dense(Vec<Op>),
+    Sparse(BTreeMap<Coordinate, Op>),
+}
+
+impl Bytecode {
+    pub fn compile(grid: &ProgramGrid) -> Self {
+        let (width, height) = (grid.bounds.width(), grid.bounds.height());
+        let origin = Coordinate::new(grid.bounds.min_x, grid.bounds.min_y);
+        let area = width.checked_mul(height).filter(|&area| {
+            area <= MAX_DENSE_CELLS && area <= grid.cells.len().saturating_mul(16).max(4096)
+        });
+        let table = match area {
+            Some(area) => {
+                let mut ops = vec![Op::Destroy; area];
+                for (coord, cell) in grid.iter() {
+                    let index = (coord.y - origin.y) as usize * width + (coord.x - origin.x) as usize;
+                    ops[index] = Op::decode(cell.symbol);
+                }
+                Table::Dense(ops)
+            }
+            None => Table::Sparse(grid.iter().map(|(coord, cell)| (*coord, Op::decode(cell.symbol))).collect()),
+        };
+        Bytecode { origin, width, height, table }
+    }
+
+    /// Operation of the cell at `coord`; [`Op::Destroy`] where there is none
+    #[inline]
+    pub fn op(&self, coord: Coordinate) -> Op {
+        match &self.table {
+            Table::Dense(ops) => match self.index(coord) {
+                Some(index) => ops[index],
+                None => Op::Destroy,
+            },
+            Table::Sparse(ops) => ops.get(&coord).copied().unwrap_or(Op::Destroy),
+        }
+    }
+
+    /// Decode the cell at `coord` again after the running program rewrote it
+    ///
+    /// A cell outside a dense table rebuilds the table for the grid's new bounds.
+    pub fn update(&mut self, grid: &ProgramGrid, coord: Coordinate) {
+        let op = grid.get_symbol(coord).map_or(Op::Destroy, Op::decode);
+        let index = self.index(coord);
+        match (&mut self.table, index) {
+            (Table::Dense(ops), Some(index)) => ops[index] = op,
+            (Table::Dense(_), None) => *self = Self::compile(grid),
+            (Table::Sparse(ops), _) => {
+                ops.insert(coord, op);
+            }
+        }
+    }
+
+    #[inline]
+    fn index(&self, coord: Coordinate) -> Option<usize> {
+        let (x, y) = (coord.x.wrapping_sub(self.origin.x) as usize, coord.y.wrapping_sub(self.origin.y) as usize);
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_cells_decode_once_and_blanks_destroy() {
+        let grid = GridParser::new().parse_string("  @\n  7\n  : \\\n  n/\n  !").unwrap();
+        let code = Bytecode::compile(&grid);
+        assert!(matches!(code.table, Table::Dense(_)));
+        assert_eq!(code.op(Coordinate::new(2, 0)), Op::Pass);
+        assert_eq!(code.op(Coordinate::new(2, 1)), Op::Digit(7));
+        assert_eq!(code.op(Coordinate::new(2, 2)), Op::Arithmetic(':'));
+        assert_eq!(code.op(Coordinate::new(4, 2)), Op::Branch);
+        assert_eq!(code.op(Coordinate::new(3, 3)), Op::Mirror);
+        assert_eq!(code.op(Coordinate::new(2, 3)), Op::OutputNumber);
+        assert_eq!(code.op(Coordinate::new(3, 2)), Op::Destroy);
+        assert_eq!(code.op(Coordinate::new(-1, 0)), Op::Destroy);
+        assert_eq!(code.op(Coordinate::new(2, 99)), Op::Destroy);
+    }
+
+    #[test]
+    fn test_sparse_grids_are_hashed() {
+        let mut grid = ProgramGrid::new();
+        grid.add_cell(Coordinate::new(0, 0), '@').unwrap();
+        grid.add_cell(Coordinate::new(100_000, 100_000), 'n').unwrap();
+        let code = Bytecode::compile(&grid);
+        assert!(matches!(code.table, Table::Sparse(_)));
+        assert_eq!(code.op(Coordinate::new(100_000, 100_000)), Op::OutputNumber);
+        assert_eq!(code.op(Coordinate::new(1, 0)), Op::Destroy);
+    }
+
+    #[test]
+    fn test_rewritten_cells_are_decoded_again() {
+        let mut grid = GridParser::new().parse_string("@\n7\nn").unwrap();
+        let mut code = Bytecode::compile(&grid);
+        grid.set_cell(Coordinate::new(0, 1), ':').unwrap();
+        code.update(&grid, Coordinate::new(0, 1));
+        assert_eq!(code.op(Coordinate::new(0, 1)), Op::Arithmetic(':'));
+
+        // Outside the table, which grows to fit
+        grid.set_cell(Coordinate::new(3, 4), 'n').unwrap();
+        code.update(&grid, Coordinate::new(3, 4));
+        assert_eq!(code.op(Coordinate::new(3, 4)), Op::OutputNumber);
+        grid.set_cell(Coordinate::new(3, 4), ' ').unwrap();
+        code.update(&grid, Coordinate::new(3, 4));
+        assert_eq!(code.op(Coordinate::new(3, 4)), Op::Destroy);
+    }
+}
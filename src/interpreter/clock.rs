@@ -0,0 +1,58 @@
+//! Wall-clock time for time limits, tick pacing and profiling
+//!
+//! Builds without std have no clock: [`now`] is always None there, so those
+//! features are skipped, as they are on `wasm32-unknown-unknown`.
+
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+pub(crate) use std::time::Instant;
+
+/// Stand-in for a moment in time on builds without std, where [`now`] never
+/// returns one
+#[cfg(not(feature = "std"))]
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Instant;
+
+#[cfg(not(feature = "std"))]
+impl Instant {
+    pub(crate) fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::ops::Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, _duration: Duration) -> Instant {
+        self
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::ops::Sub for Instant {
+    type Output = Duration;
+
+    fn sub(self, _earlier: Instant) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// The current time, or None on targets without a clock
+pub(crate) fn now() -> Option<Instant> {
+    #[cfg(feature = "std")]
+    if !cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        return Some(Instant::now());
+    }
+    None
+}
+
+/// Block the current thread for `duration`; a no-op without std
+pub(crate) fn sleep(duration: Duration) {
+    #[cfg(feature = "std")]
+    std::thread::sleep(duration);
+    #[cfg(not(feature = "std"))]
+    let _ = duration;
+}
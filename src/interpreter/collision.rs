@@ -1,6 +1,8 @@
-use crate::interpreter::droplet::Droplet;
+use crate::interpreter::droplet::{Droplet, DropletId};
 use crate::types::coordinate::Coordinate;
-use std::collections::{HashMap, HashSet};
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+use alloc::collections::{BTreeMap, BTreeSet};
 
 /// Collision detection and handling for droplets
 pub struct CollisionDetector;
@@ -12,9 +14,9 @@ impl CollisionDetector {
     }
 
     /// Detect collisions between droplets moving to the same position
-    pub fn detect_collisions(&self, droplets: &[Droplet]) -> HashSet<Coordinate> {
-        let mut position_map: HashMap<Coordinate, Vec<usize>> = HashMap::new();
-        let mut collision_positions = HashSet::new();
+    pub fn detect_collisions(&self, droplets: &[Droplet]) -> BTreeSet<Coordinate> {
+        let mut position_map: BTreeMap<Coordinate, Vec<usize>> = BTreeMap::new();
+        let mut collision_positions = BTreeSet::new();
 
         // Map next positions to droplet indices
         for (index, droplet) in droplets.iter().enumerate() {
@@ -37,7 +39,7 @@ impl CollisionDetector {
     }
 
     /// Get all droplets that would collide at the given positions
-    pub fn get_colliding_droplets(&self, droplets: &[Droplet], collision_positions: &HashSet<Coordinate>) -> Vec<usize> {
+    pub fn get_colliding_droplets(&self, droplets: &[Droplet], collision_positions: &BTreeSet<Coordinate>) -> Vec<usize> {
         let mut colliding_droplets = Vec::new();
 
         for (index, droplet) in droplets.iter().enumerate() {
@@ -79,4 +81,339 @@ impl Default for CollisionDetector {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// What happens to droplets that claim the same cell in one tick
+///
+/// Chosen with `--collisions` or a `%collisions` line in the program; the
+/// command line wins when both are given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CollisionPolicy {
+    /// Every droplet in the collision is destroyed
+    #[default]
+    DestroyAll,
+    /// The oldest droplet carries on with the sum of everyone's values
+    MergeSum,
+    /// The oldest droplet carries on with the product of everyone's values
+    MergeProduct,
+    /// The oldest droplet carries on and the others are destroyed
+    KeepOldest,
+    /// Moving droplets that meet stay where they were and turn around
+    /// (see [`resolve_bounces`])
+    Bounce,
+}
+
+impl CollisionPolicy {
+    /// Every policy, in the order `--collisions` lists them
+    pub const ALL: [CollisionPolicy; 5] = [
+        CollisionPolicy::DestroyAll,
+        CollisionPolicy::MergeSum,
+        CollisionPolicy::MergeProduct,
+        CollisionPolicy::KeepOldest,
+        CollisionPolicy::Bounce,
+    ];
+
+    /// Name used by `--collisions`, `%collisions` and trace events
+    pub fn name(self) -> &'static str {
+        match self {
+            CollisionPolicy::DestroyAll => "destroy-all",
+            CollisionPolicy::MergeSum => "merge-sum",
+            CollisionPolicy::MergeProduct => "merge-product",
+            CollisionPolicy::KeepOldest => "keep-oldest",
+            CollisionPolicy::Bounce => "bounce",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|policy| policy.name() == name)
+    }
+
+    /// Decide what happens to the droplets `ids`, which all claimed the same cell
+    ///
+    /// `created_at` gives the tick each droplet was created on. The oldest
+    /// droplet is the one created first, with the lowest ID breaking ties, so
+    /// the outcome never depends on the order droplets ran in.
+    pub fn resolve(self, ids: &[DropletId], created_at: impl Fn(DropletId) -> u64) -> CollisionOutcome {
+        match self {
+            CollisionPolicy::DestroyAll => CollisionOutcome {
+                destroyed: ids.to_vec(),
+                ..CollisionOutcome::default()
+            },
+            CollisionPolicy::MergeSum | CollisionPolicy::MergeProduct | CollisionPolicy::KeepOldest => {
+                let survivor = ids.iter().copied().min_by_key(|&id| (created_at(id), id));
+                CollisionOutcome {
+                    survivor,
+                    destroyed: ids.iter().copied().filter(|&id| Some(id) != survivor).collect(),
+                    ..CollisionOutcome::default()
+                }
+            }
+            CollisionPolicy::Bounce => CollisionOutcome {
+                bounced: ids.to_vec(),
+                ..CollisionOutcome::default()
+            },
+        }
+    }
+
+    /// Value a merging policy gives the survivor from the colliding droplets'
+    /// `values`; None for policies that do not merge
+    pub fn merge(self, values: impl IntoIterator<Item = TubularNumber>) -> Option<TubularNumber> {
+        match self {
+            CollisionPolicy::MergeSum => Some(values.into_iter().fold(TubularNumber::zero(), |sum, value| sum + value)),
+            CollisionPolicy::MergeProduct => Some(values.into_iter().fold(TubularNumber::one(), |product, value| product * value)),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for CollisionPolicy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl core::str::FromStr for CollisionPolicy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::from_name(name).ok_or_else(|| format!(
+            "unknown collision policy '{}' (expected one of: {})",
+            name, Self::ALL.map(Self::name).join(", "),
+        ))
+    }
+}
+
+/// How a [`CollisionPolicy`] settled one collision
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CollisionOutcome {
+    /// Droplet that carries on into the contested cell
+    pub survivor: Option<DropletId>,
+    pub destroyed: Vec<DropletId>,
+    /// Droplets that stay put and reverse instead of moving
+    pub bounced: Vec<DropletId>,
+}
+
+impl core::fmt::Display for CollisionOutcome {
+    /// What became of the droplets, e.g. "droplet 0 carries on, 1 destroyed"
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(survivor) = self.survivor {
+            parts.push(format!("droplet {} carries on", survivor));
+        }
+        if !self.destroyed.is_empty() || self.bounced.is_empty() {
+            parts.push(format!("{} destroyed", self.destroyed.len()));
+        }
+        if !self.bounced.is_empty() {
+            parts.push(format!("{} bounced", self.bounced.len()));
+        }
+        f.write_str(&parts.join(", "))
+    }
+}
+
+/// A droplet's step this tick, from its cell to the one it is heading for
+///
+/// Droplets holding their cell, such as sleeping ones, have `to == from`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub id: DropletId,
+    pub from: Coordinate,
+    pub to: Coordinate,
+}
+
+/// Droplets the bounce policy turns around this tick, in ID order
+///
+/// Droplets heading for the same cell bounce, and so do two droplets meeting
+/// head-on by swapping cells. A bounced droplet keeps its cell, so a droplet
+/// stepping into it bounces too, and so on down the line until nothing else
+/// is turned back. Every droplet is checked against the whole tick at once,
+/// so the outcome does not depend on the order droplets ran in.
+pub fn resolve_bounces(steps: &[Step]) -> Vec<DropletId> {
+    let mut claims: BTreeMap<Coordinate, usize> = BTreeMap::new();
+    for step in steps {
+        *claims.entry(step.to).or_default() += 1;
+    }
+    let starts: BTreeMap<Coordinate, &Step> = steps.iter()
+        .filter(|step| step.from != step.to)
+        .map(|step| (step.from, step))
+        .collect();
+
+    let mut bounced: BTreeSet<DropletId> = steps.iter()
+        .filter(|step| step.from != step.to)
+        .filter(|step| {
+            let shared = claims[&step.to] > 1;
+            let head_on = starts.get(&step.to).is_some_and(|other| other.to == step.from);
+            shared || head_on
+        })
+        .map(|step| step.id)
+        .collect();
+
+    // Cells kept by bounced droplets push back on whoever steps into them
+    loop {
+        let kept: BTreeSet<Coordinate> = steps.iter()
+            .filter(|step| bounced.contains(&step.id))
+            .map(|step| step.from)
+            .collect();
+        let before = bounced.len();
+        bounced.extend(steps.iter()
+            .filter(|step| step.from != step.to && kept.contains(&step.to))
+            .map(|step| step.id));
+        if bounced.len() == before {
+            break;
+        }
+    }
+
+    let mut bounced: Vec<DropletId> = bounced.into_iter().collect();
+    bounced.sort_unstable();
+    bounced
+}
+
+/// Where each droplet will be after the current tick, reused across ticks
+///
+/// Claims go into one flat buffer that is sorted once per tick, so after the
+/// first few ticks detecting collisions allocates nothing. Collisions come
+/// out in coordinate order, with the droplets at each cell in id order.
+#[derive(Debug, Clone, Default)]
+pub struct CollisionIndex {
+    claims: Vec<(Coordinate, DropletId)>,
+    /// Droplet ids of `claims` once sorted, so each cell's droplets form a slice
+    ids: Vec<DropletId>,
+}
+
+impl CollisionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every claim, keeping the buffers for the next tick
+    pub fn clear(&mut self) {
+        self.claims.clear();
+        self.ids.clear();
+    }
+
+    /// Record that droplet `id` will occupy `position`
+    pub fn claim(&mut self, position: Coordinate, id: DropletId) {
+        self.claims.push((position, id));
+    }
+
+    pub fn len(&self) -> usize {
+        self.claims.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+    }
+
+    /// Cells claimed by more than one droplet, with the droplets claiming each
+    pub fn collisions(&mut self) -> Collisions<'_> {
+        self.claims.sort_unstable();
+        self.ids.clear();
+        self.ids.extend(self.claims.iter().map(|&(_, id)| id));
+        Collisions { claims: &self.claims, ids: &self.ids, start: 0 }
+    }
+}
+
+/// Iterator over the collisions in a [`CollisionIndex`]
+pub struct Collisions<'a> {
+    claims: &'a [(Coordinate, DropletId)],
+    ids: &'a [DropletId],
+    start: usize,
+}
+
+impl<'a> Iterator for Collisions<'a> {
+    type Item = (Coordinate, &'a [DropletId]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(position, _)) = self.claims.get(self.start) {
+            let count = self.claims[self.start..].iter().take_while(|(p, _)| *p == position).count();
+            let droplets = &self.ids[self.start..self.start + count];
+            self.start += count;
+            if count > 1 {
+                return Some((position, droplets));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_reports_shared_cells_in_order() {
+        let mut index = CollisionIndex::new();
+        index.claim(Coordinate::new(2, 0), 4);
+        index.claim(Coordinate::new(0, 1), 1);
+        index.claim(Coordinate::new(2, 0), 3);
+        index.claim(Coordinate::new(5, 5), 2);
+        index.claim(Coordinate::new(0, 1), 0);
+
+        let collisions: Vec<_> = index.collisions().collect();
+        assert_eq!(collisions, vec![
+            (Coordinate::new(0, 1), &[0, 1][..]),
+            (Coordinate::new(2, 0), &[3, 4][..]),
+        ]);
+
+        index.clear();
+        assert!(index.is_empty());
+        index.claim(Coordinate::new(0, 1), 0);
+        assert_eq!(index.collisions().count(), 0);
+    }
+
+    #[test]
+    fn test_policies_pick_the_oldest_droplet_to_keep() {
+        // Droplet 4 was created on tick 2, droplets 1 and 6 on tick 5
+        let created_at = |id| if id == 4 { 2 } else { 5 };
+        let ids = [1, 4, 6];
+
+        assert_eq!(CollisionPolicy::DestroyAll.resolve(&ids, created_at).destroyed, vec![1, 4, 6]);
+        let kept = CollisionPolicy::KeepOldest.resolve(&ids, created_at);
+        assert_eq!((kept.survivor, kept.destroyed), (Some(4), vec![1, 6]));
+        assert_eq!(CollisionPolicy::MergeSum.resolve(&[1, 6], created_at).survivor, Some(1));
+        let bounced = CollisionPolicy::Bounce.resolve(&ids, created_at);
+        assert_eq!((bounced.bounced, bounced.destroyed.len()), (vec![1, 4, 6], 0));
+
+        assert_eq!(CollisionPolicy::MergeProduct.resolve(&ids, created_at).survivor, Some(4));
+
+        assert_eq!(CollisionPolicy::DestroyAll.resolve(&ids, created_at).to_string(), "3 destroyed");
+        assert_eq!(CollisionPolicy::KeepOldest.resolve(&ids, created_at).to_string(), "droplet 4 carries on, 2 destroyed");
+        assert_eq!(CollisionPolicy::Bounce.resolve(&ids, created_at).to_string(), "3 bounced");
+
+        assert_eq!("merge-sum".parse(), Ok(CollisionPolicy::MergeSum));
+        assert!("merge".parse::<CollisionPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_bounces_cover_head_on_swaps_and_chains() {
+        let step = |id, from: (isize, isize), to: (isize, isize)| Step {
+            id,
+            from: Coordinate::new(from.0, from.1),
+            to: Coordinate::new(to.0, to.1),
+        };
+
+        // 0 and 1 swap cells head-on; 2 passes by
+        let swap = [step(0, (0, 0), (0, 1)), step(1, (0, 1), (0, 0)), step(2, (1, 0), (1, 1))];
+        assert_eq!(resolve_bounces(&swap), vec![0, 1]);
+
+        // 3 and 4 meet at (0, 1); 5 follows 4 into its kept cell, then 6 into 5's
+        let chain = [
+            step(6, (0, 4), (0, 3)),
+            step(3, (0, 0), (0, 1)),
+            step(5, (0, 3), (0, 2)),
+            step(4, (0, 2), (0, 1)),
+        ];
+        assert_eq!(resolve_bounces(&chain), vec![3, 4, 5, 6]);
+
+        // A sleeping droplet turns back whoever steps onto it but stays as it is
+        let asleep = [step(7, (2, 2), (2, 2)), step(8, (1, 2), (2, 2))];
+        assert_eq!(resolve_bounces(&asleep), vec![8]);
+    }
+
+    #[test]
+    fn test_merging_policies_combine_values() {
+        let values = || [3, -4, 5].map(TubularNumber::new);
+        assert_eq!(CollisionPolicy::MergeSum.merge(values()), Some(TubularNumber::new(4)));
+        assert_eq!(CollisionPolicy::MergeProduct.merge(values()), Some(TubularNumber::new(-60)));
+        assert_eq!(CollisionPolicy::KeepOldest.merge(values()), None);
+    }
+}
@@ -1,20 +1,34 @@
 use crate::types::coordinate::Coordinate;
 use crate::types::direction::Direction;
-use crate::types::bigint::TubularBigInt;
-use std::fmt;
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+use core::fmt;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Droplet {
     /// Unique identifier for tracking
     pub id: DropletId,
     /// Current integer value (arbitrary precision)
-    pub value: TubularBigInt,
+    pub value: TubularNumber,
     /// Current position in the grid
     pub position: Coordinate,
     /// Current direction of movement
     pub direction: Direction,
     /// Whether this droplet is active (will move next tick)
     pub active: bool,
+    /// Tick at which a sleeping droplet resumes movement (None = awake)
+    pub sleep_until: Option<u64>,
+    /// The droplet's own stack, top last (local stack extension)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub locals: Vec<TubularNumber>,
+    /// Whether the droplet just left a digit, so a digit next extends the
+    /// number instead of replacing it (multi-digit literals)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub in_literal: bool,
+    /// Tick the droplet was created on, from which its age is counted
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub created_at: u64,
 }
 
 pub type DropletId = u64;
@@ -23,23 +37,42 @@ impl Droplet {
     pub fn new(id: DropletId, position: Coordinate, direction: Direction) -> Self {
         Droplet {
             id,
-            value: TubularBigInt::zero(),
+            value: TubularNumber::zero(),
             position,
             direction,
             active: true,
+            sleep_until: None,
+            locals: Vec::new(),
+            in_literal: false,
+            created_at: 0,
         }
     }
 
-    pub fn with_value(id: DropletId, value: TubularBigInt, position: Coordinate, direction: Direction) -> Self {
+    pub fn with_value(id: DropletId, value: TubularNumber, position: Coordinate, direction: Direction) -> Self {
         Droplet {
             id,
             value,
             position,
             direction,
             active: true,
+            sleep_until: None,
+            locals: Vec::new(),
+            in_literal: false,
+            created_at: 0,
         }
     }
 
+    /// The same droplet, created on `tick`
+    pub fn with_creation_tick(mut self, tick: u64) -> Self {
+        self.created_at = tick;
+        self
+    }
+
+    /// Ticks since the droplet was created, as of `tick`
+    pub fn age(&self, tick: u64) -> u64 {
+        tick.saturating_sub(self.created_at)
+    }
+
     pub fn is_active(&self) -> bool {
         self.active
     }
@@ -48,6 +81,16 @@ impl Droplet {
         self.active = false;
     }
 
+    /// Suspend the droplet in place until the given tick
+    pub fn suspend_until(&mut self, tick: u64) {
+        self.sleep_until = Some(tick);
+    }
+
+    /// Whether the droplet is still sleeping at the given tick
+    pub fn is_sleeping(&self, tick: u64) -> bool {
+        matches!(self.sleep_until, Some(wake_tick) if tick < wake_tick)
+    }
+
     pub fn move_to(&mut self, new_position: Coordinate) {
         self.position = new_position;
     }
@@ -56,7 +99,7 @@ impl Droplet {
         self.direction = direction;
     }
 
-    pub fn set_value(&mut self, value: TubularBigInt) {
+    pub fn set_value(&mut self, value: TubularNumber) {
         self.value = value;
     }
 
@@ -91,8 +134,8 @@ impl PartialEq for Droplet {
 
 impl Eq for Droplet {}
 
-impl std::hash::Hash for Droplet {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl core::hash::Hash for Droplet {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.id.hash(state);
     }
 }
\ No newline at end of file
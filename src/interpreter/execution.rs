@@ -1,17 +1,58 @@
 use crate::types::coordinate::Coordinate;
 use crate::types::direction::Direction;
-use crate::types::bigint::TubularBigInt;
-use crate::types::error::{Result, InterpreterError, ExecError};
+use crate::types::number::TubularNumber;
+use crate::types::error::{Result, InterpreterError, ExecError, InitError, Position};
 use crate::interpreter::droplet::{Droplet, DropletId};
+use crate::interpreter::arena::{DropletArena, DropletKey};
 use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::bytecode::{Bytecode, Op};
+use crate::interpreter::optimizer::{fold_constants, Fold};
+#[cfg(feature = "jit")]
+use crate::interpreter::jit::CorridorJit;
 use crate::interpreter::stack::DataStack;
 use crate::interpreter::memory::Reservoir;
+use crate::interpreter::collision::{resolve_bounces, CollisionIndex, CollisionPolicy, Step};
 use crate::interpreter::subroutines::CallStack;
+use crate::interpreter::history::{ExecutionHistory, TickJournal};
+use crate::interpreter::scheduler::{Scheduler, SchedulerKind};
+use crate::interpreter::pseudo_input::PseudoInput;
+use crate::interpreter::arguments::ProgramArguments;
+use crate::interpreter::random::RandomSource;
+use crate::interpreter::snapshot::InterpreterSnapshot;
+use crate::interpreter::breakpoint::{BreakCondition, Breakpoint};
+use crate::interpreter::watch::{WatchAction, WatchExpr, WatchHit, Watchpoint};
+use crate::interpreter::profiler::ExecutionProfile;
+use crate::interpreter::metrics::Metrics;
+use crate::interpreter::builder::InterpreterBuilder;
+use crate::interpreter::interrupt::InterruptHandle;
+use crate::interpreter::ticks::Ticks;
 use crate::operations::arithmetic::ArithmeticOperations;
-use crate::operations::io::IoOperations;
+use crate::operations::net::{NetOperations, SocketTable, CONNECT_TIMEOUT};
+use crate::operations::local_stack::{LocalStackOperations, LOCAL_STACKS_FLAG};
+use crate::operations::memory::MemoryOperations;
+use crate::operations::metadata::MetadataOperations;
+use crate::operations::reflection::ReflectionOperations;
+use crate::operations::io::{Capabilities, Capability, EofPolicy, FileTable, InputMode, InputProvider, InputReader, IoOperations, NullOutput, OutputSink, DefaultInput};
 use crate::operations::flow_control::FlowControlOperations;
-use std::collections::{HashMap, HashSet};
-use std::time::Instant;
+use crate::operations::subroutines::SubroutineOperations;
+use crate::interpreter::trace::{TraceConfig, TraceEvent};
+use crate::interpreter::clock::{self, Instant};
+use crate::types::prelude::*;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt;
+use alloc::sync::Arc;
+use core::time::Duration;
+
+/// `eprintln!` for warnings and verbose logging; builds without std have no
+/// stderr, so there the message is dropped
+macro_rules! stderr {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "std")]
+        eprintln!($($arg)*);
+        #[cfg(not(feature = "std"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
 
 /// Configuration for execution limits and timeouts
 #[derive(Debug, Clone)]
@@ -26,6 +67,14 @@ pub struct ExecutionLimits {
     pub soft_time_limit_ms: Option<u64>,
     /// Progress reporting interval in ticks (None = no progress reports)
     pub progress_interval: Option<u64>,
+    /// Warn once when a droplet value exceeds this many decimal digits (None = no watch)
+    pub value_digit_warning: Option<usize>,
+    /// Most droplets alive at once before the run fails (None = no limit)
+    pub max_droplets: Option<usize>,
+    /// Deepest the data stack may grow before the run fails (None = no limit)
+    pub max_stack_depth: Option<usize>,
+    /// Most reservoir cells in use before the run fails (None = no limit)
+    pub max_reservoir_cells: Option<usize>,
 }
 
 impl Default for ExecutionLimits {
@@ -36,6 +85,10 @@ impl Default for ExecutionLimits {
             soft_tick_limit: Some(800), // Warn at 80% of hard limit
             soft_time_limit_ms: Some(4000), // Warn at 80% of hard limit
             progress_interval: Some(100), // Report every 100 ticks
+            value_digit_warning: None,
+            max_droplets: None,
+            max_stack_depth: None,
+            max_reservoir_cells: None,
         }
     }
 }
@@ -70,6 +123,34 @@ impl ExecutionLimits {
         self
     }
 
+    pub fn with_value_digit_warning(mut self, digits: Option<usize>) -> Self {
+        self.value_digit_warning = digits;
+        self
+    }
+
+    /// Place the soft tick and time limits at 80% of the hard ones, or
+    /// remove them where there is no hard limit
+    pub fn with_derived_soft_limits(mut self) -> Self {
+        self.soft_tick_limit = self.max_ticks.map(|ticks| ticks - ticks / 5);
+        self.soft_time_limit_ms = self.max_time_ms.map(|ms| ms - ms / 5);
+        self
+    }
+
+    pub fn with_max_droplets(mut self, max_droplets: Option<usize>) -> Self {
+        self.max_droplets = max_droplets;
+        self
+    }
+
+    pub fn with_max_stack_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_stack_depth = max_depth;
+        self
+    }
+
+    pub fn with_max_reservoir_cells(mut self, max_cells: Option<usize>) -> Self {
+        self.max_reservoir_cells = max_cells;
+        self
+    }
+
     pub fn unlimited() -> Self {
         Self {
             max_ticks: None,
@@ -77,16 +158,191 @@ impl ExecutionLimits {
             soft_tick_limit: None,
             soft_time_limit_ms: None,
             progress_interval: None,
+            value_digit_warning: None,
+            max_droplets: None,
+            max_stack_depth: None,
+            max_reservoir_cells: None,
         }
     }
 }
 
+/// Runtime behaviour options that do not affect limits
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeOptions {
+    /// Order in which droplets are processed each tick (see [`Scheduler`]);
+    /// ascending ID by default
+    pub scheduler: SchedulerKind,
+    /// Seed for the deterministic pseudo-input operation (`i`)
+    pub pseudo_input_seed: u64,
+    /// Seed for the random operation (`r`); None seeds differently each run,
+    /// or with 0 on builds without std
+    pub random_seed: Option<u64>,
+    /// Host resources the program may use (files, network); none by default
+    pub capabilities: Capabilities,
+    /// Arguments read by the argument operation (`a`)
+    pub program_args: Vec<String>,
+    /// Compute commands for large droplet populations on all cores
+    /// (needs the `parallel` feature; otherwise ignored)
+    pub parallel: bool,
+    /// Droplets leaving the grid's bounding box re-enter on the opposite side
+    /// instead of being destroyed
+    pub wrap: bool,
+    /// Compile hot pipe corridors to native code (needs the `jit` feature;
+    /// otherwise ignored)
+    pub jit: bool,
+    /// Fold digit-and-stack arithmetic on fixed paths into precomputed values
+    /// before the run (see [`fold_constants`])
+    pub optimize: bool,
+    /// Give every droplet its own stack, used by `{`, `}`, `(` and `)`;
+    /// without it those operations stop the program with an error
+    pub local_stacks: bool,
+    /// Consecutive digits on a droplet's path form one number, as in programs
+    /// with a `%multidigit` line; constant folding and the JIT stay off
+    pub multi_digit_literals: bool,
+    /// What happens when droplets collide; None uses the program's
+    /// `%collisions` line, or destroys them all if it has none
+    pub collision_policy: Option<CollisionPolicy>,
+    /// Ticks per second `run` keeps to by pausing between ticks; None runs
+    /// as fast as possible. The wall-clock limit counts the pauses. Ignored
+    /// on builds without std, which have no clock.
+    pub tick_rate: Option<f64>,
+    /// How `?` and `??` divide up their input; None reads characters in
+    /// turn, so several `?` can take theirs from one line
+    pub input_mode: Option<InputMode>,
+    /// What `?` gives at end of input; None uses the program's `%eof` line,
+    /// or a newline if it has none
+    pub eof_policy: Option<EofPolicy>,
+    /// Longest `T` waits for a TCP connection; None waits [`CONNECT_TIMEOUT`].
+    /// The tick, and so the wall-clock limit, waits with it.
+    pub connect_timeout: Option<Duration>,
+    /// Reproducible runs: droplets in ascending ID order whatever `scheduler`
+    /// says, `r` seeded with `random_seed` or [`DETERMINISTIC_SEED`], and the
+    /// wall-clock limits ignored so only ticks decide when a run stops
+    pub deterministic: bool,
+}
+
+/// Seed for the random operation in deterministic runs without an explicit seed
+pub const DETERMINISTIC_SEED: u64 = 0;
+
+impl RuntimeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_scheduler(mut self, scheduler: SchedulerKind) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    pub fn with_pseudo_input_seed(mut self, seed: u64) -> Self {
+        self.pseudo_input_seed = seed;
+        self
+    }
+
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    pub fn with_program_args(mut self, args: Vec<String>) -> Self {
+        self.program_args = args;
+        self
+    }
+
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Let the file operations (`O`, `I`, `W`, `X`) touch the filesystem
+    pub fn with_allow_fs(mut self, allow_fs: bool) -> Self {
+        self.capabilities = self.capabilities.with(Capability::Filesystem, allow_fs);
+        self
+    }
+
+    /// Let the network operations (`T`, `E`, `V`) open TCP connections
+    pub fn with_allow_net(mut self, allow_net: bool) -> Self {
+        self.capabilities = self.capabilities.with(Capability::Network, allow_net);
+        self
+    }
+
+    /// Let `p` rewrite the program's cells while it runs
+    pub fn with_allow_self_modify(mut self, allow_self_modify: bool) -> Self {
+        self.capabilities = self.capabilities.with(Capability::SelfModify, allow_self_modify);
+        self
+    }
+
+    pub fn with_random_seed(mut self, seed: Option<u64>) -> Self {
+        self.random_seed = seed;
+        self
+    }
+
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_jit(mut self, jit: bool) -> Self {
+        self.jit = jit;
+        self
+    }
+
+    pub fn with_optimize(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    pub fn with_local_stacks(mut self, local_stacks: bool) -> Self {
+        self.local_stacks = local_stacks;
+        self
+    }
+
+    pub fn with_multi_digit_literals(mut self, multi_digit_literals: bool) -> Self {
+        self.multi_digit_literals = multi_digit_literals;
+        self
+    }
+
+    pub fn with_collision_policy(mut self, policy: Option<CollisionPolicy>) -> Self {
+        self.collision_policy = policy;
+        self
+    }
+
+    pub fn with_tick_rate(mut self, tick_rate: Option<f64>) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    pub fn with_input_mode(mut self, input_mode: Option<InputMode>) -> Self {
+        self.input_mode = input_mode;
+        self
+    }
+
+    pub fn with_eof_policy(mut self, policy: Option<EofPolicy>) -> Self {
+        self.eof_policy = policy;
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+}
+
+/// Longest single pause while pacing, so cancellation is noticed promptly
+const PACE_SLICE: Duration = Duration::from_millis(50);
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExecutionState {
     /// Current tick number
     pub tick: u64,
     /// Active droplets
-    pub droplets: Vec<Droplet>,
+    pub droplets: DropletArena,
     /// Data stack
     pub stack: DataStack,
     /// Reservoir memory
@@ -99,9 +355,25 @@ pub struct ExecutionState {
     pub output: String,
     /// Next droplet ID
     pub next_droplet_id: DropletId,
+    /// Deterministic sequence consumed by the pseudo-input operation
+    pub pseudo_input: PseudoInput,
+    /// Random numbers drawn by the `r` operation; missing from snapshots saved
+    /// before it existed
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub random: RandomSource,
+    /// Arguments given to the program on the command line
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub arguments: ProgramArguments,
+    /// Files opened by the program; not saved in snapshots
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub files: FileTable,
+    /// TCP connections opened by the program; not saved in snapshots
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub sockets: SocketTable,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExecutionStatus {
     Running,
     Completed,
@@ -109,12 +381,41 @@ pub enum ExecutionStatus {
     TickTimeout(u64), // tick limit reached
     WallClockTimeout(u64), // wall-clock time limit reached in ms
     Warning(ExecutionWarning), // soft limit warning
+    Paused, // paused by a breakpoint, watch or `pause`; `resume` continues
+    Cancelled, // the interrupt handle was cancelled
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExecutionWarning {
     SoftTickLimit(u64),
     SoftTimeLimit(u64),
+    /// A droplet, stack or reservoir value grew past the configured digit threshold
+    LargeValue {
+        location: ValueLocation,
+        digits: usize,
+    },
+}
+
+/// Where a value watched by [`ExecutionLimits::value_digit_warning`] was held
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ValueLocation {
+    Droplet { droplet: DropletId, position: Coordinate },
+    /// Entry counted from the top of the stack (0 = top)
+    Stack { depth: usize },
+    Reservoir(Coordinate),
+}
+
+impl fmt::Display for ValueLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueLocation::Droplet { droplet, position } => write!(f, "Droplet {} at {}", droplet, position),
+            ValueLocation::Stack { depth: 0 } => write!(f, "The stack top"),
+            ValueLocation::Stack { depth } => write!(f, "Stack entry {} below the top", depth),
+            ValueLocation::Reservoir(coord) => write!(f, "Reservoir cell {}", coord),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -135,6 +436,45 @@ pub struct ExecutionResult {
     pub execution_time_ms: u64,
     pub warnings_issued: Vec<ExecutionWarning>,
     pub progress_reports: Vec<ProgressReport>,
+    /// Coordinates where collisions happened with their counts, most frequent first
+    pub collision_hotspots: Vec<(Coordinate, usize)>,
+    /// Largest droplet value seen, in decimal digits
+    pub peak_value_digits: usize,
+    /// Most reservoir cells in use at once
+    pub peak_reservoir_cells: usize,
+    /// Per-cell visit and time counters (None unless profiling was enabled)
+    pub profile: Option<ExecutionProfile>,
+    pub metrics: Metrics,
+}
+
+/// One row of the limits-versus-actuals usage report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceUsage {
+    pub resource: &'static str,
+    pub unit: &'static str,
+    /// Configured limit (None = unlimited)
+    pub limit: Option<u64>,
+    /// Peak usage observed during the run
+    pub actual: u64,
+}
+
+impl ResourceUsage {
+    /// Compare every tracked resource in `result` against `limits`
+    pub fn collect(result: &ExecutionResult, limits: &ExecutionLimits) -> Vec<ResourceUsage> {
+        vec![
+            ResourceUsage { resource: "ticks", unit: "ticks", limit: limits.max_ticks, actual: result.total_ticks },
+            ResourceUsage { resource: "wall time", unit: "ms", limit: limits.max_time_ms, actual: result.execution_time_ms },
+            ResourceUsage { resource: "stack depth", unit: "values", limit: limits.max_stack_depth.map(|n| n as u64), actual: result.max_stack_depth as u64 },
+            ResourceUsage { resource: "droplets", unit: "droplets", limit: limits.max_droplets.map(|n| n as u64), actual: result.max_droplets as u64 },
+            ResourceUsage { resource: "reservoir cells", unit: "cells", limit: limits.max_reservoir_cells.map(|n| n as u64), actual: result.peak_reservoir_cells as u64 },
+            ResourceUsage { resource: "output", unit: "bytes", limit: None, actual: result.final_output.len() as u64 },
+        ]
+    }
+
+    /// Share of the limit consumed, if there is a limit
+    pub fn percent_used(&self) -> Option<f64> {
+        self.limit.map(|limit| if limit == 0 { 100.0 } else { self.actual as f64 * 100.0 / limit as f64 })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -149,7 +489,9 @@ pub struct ProgressReport {
 /// Main interpreter that executes Tubular programs
 pub struct TubularInterpreter {
     state: ExecutionState,
-    grid: ProgramGrid,
+    grid: Arc<ProgramGrid>,
+    /// The grid's cells decoded for the run loop
+    code: Bytecode,
     verbose: bool,
     trace: bool,
     limits: ExecutionLimits,
@@ -157,11 +499,80 @@ pub struct TubularInterpreter {
     warnings_issued: Vec<ExecutionWarning>,
     progress_reports: Vec<ProgressReport>,
     total_collisions: usize,
+    collision_counts: BTreeMap<Coordinate, usize>,
+    /// Coordinates of collisions in the most recent tick
+    tick_collisions: Vec<Coordinate>,
+    peak_value_digits: usize,
+    peak_reservoir_cells: usize,
+    /// Undo journals for reverse stepping (None = disabled)
+    history: Option<ExecutionHistory>,
+    /// Cells rewritten by `p` this tick and what they held, while recording history
+    grid_writes: Option<Vec<(Coordinate, Option<char>)>>,
+    scheduler: Box<dyn Scheduler>,
+    /// Compute side-effect-free commands in parallel (see `RuntimeOptions::parallel`)
+    parallel: bool,
+    /// Wrap movement around the grid edges (see `RuntimeOptions::wrap`)
+    wrap: bool,
+    /// Native code for hot corridors (see `RuntimeOptions::jit`; None = interpret every tick)
+    #[cfg(feature = "jit")]
+    jit: Option<CorridorJit>,
+    /// Constant folds by head (see `RuntimeOptions::optimize`; empty = not optimizing)
+    folds: BTreeMap<(Coordinate, Direction), Fold>,
+    /// Droplet stack operations are enabled (see `RuntimeOptions::local_stacks`)
+    local_stacks: bool,
+    /// Digits extend the number a droplet is reading (see `RuntimeOptions::multi_digit_literals`)
+    multi_digit: bool,
+    /// What happens when droplets collide (see `RuntimeOptions::collision_policy`)
+    collision_policy: CollisionPolicy,
+    /// Host resources granted (see `RuntimeOptions::capabilities`)
+    capabilities: Capabilities,
+    /// Wall-clock limits are ignored (see `RuntimeOptions::deterministic`)
+    deterministic: bool,
+    /// Time between tick starts in `run` (see `RuntimeOptions::tick_rate`; None = unpaced)
+    tick_interval: Option<Duration>,
+    /// Cells claimed by droplets this tick; kept to reuse its buffers
+    next_positions: CollisionIndex,
+    /// Trace event filters (None = tracing disabled)
+    trace_config: Option<TraceConfig>,
+    trace_events: Vec<TraceEvent>,
+    breakpoints: Vec<Breakpoint>,
+    /// Index of the breakpoint that caused the current pause
+    hit_breakpoint: Option<usize>,
+    watches: Vec<Watchpoint>,
+    /// Watch that caused the current pause
+    hit_watch: Option<WatchHit>,
+    /// Hotspot counters (None = profiling disabled)
+    profile: Option<ExecutionProfile>,
+    metrics: Metrics,
+    /// Source for `?` and `??` input
+    input: Box<dyn InputProvider>,
+    /// Splits `input` for `?` and `??` (see `RuntimeOptions::input_mode`)
+    input_reader: InputReader,
+    /// What `?` gives at end of input (see `RuntimeOptions::eof_policy`)
+    eof_policy: EofPolicy,
+    /// Longest `T` waits for a TCP connection (see `RuntimeOptions::connect_timeout`)
+    connect_timeout: Duration,
+    /// Receives each tick's output as it is produced
+    output: Box<dyn OutputSink>,
+    /// Checked before each tick; set from other threads through `interrupt_handle`
+    interrupt: InterruptHandle,
+    /// Cell of the droplet being processed, so a failing operation can be located
+    error_cell: Option<Coordinate>,
 }
 
 impl TubularInterpreter {
     /// Create a new interpreter with the given program grid
     pub fn new(grid: ProgramGrid) -> Result<Self> {
+        Self::from_shared(Arc::new(grid))
+    }
+
+    /// Start configuring an interpreter; see [`InterpreterBuilder`]
+    pub fn builder() -> InterpreterBuilder {
+        InterpreterBuilder::new()
+    }
+
+    /// Create a new interpreter over a grid shared with other interpreters
+    pub fn from_shared(grid: Arc<ProgramGrid>) -> Result<Self> {
         // Validate the grid
         grid.validate()?;
 
@@ -170,22 +581,42 @@ impl TubularInterpreter {
             crate::types::error::InitError::NoStartSymbol
         ))?;
 
-        // Create initial droplet
-        let initial_droplet = Droplet::new(0, start_pos, Direction::Down);
+        Ok(Self::with_start(grid, start_pos))
+    }
+
+    /// Build an interpreter for an already validated grid and start position
+    pub(crate) fn with_start(grid: Arc<ProgramGrid>, start_pos: Coordinate) -> Self {
+        // The start droplet comes first, then any `%droplet` ones in order
+        let droplets: Vec<_> = core::iter::once((start_pos, grid.start_direction))
+            .chain(grid.droplets.iter().copied())
+            .enumerate()
+            .map(|(id, (position, direction))| Droplet::new(id as DropletId, position, direction))
+            .collect();
+        let created = droplets.len();
 
         let state = ExecutionState {
             tick: 0,
-            droplets: vec![initial_droplet],
+            droplets: DropletArena::from(droplets),
             stack: DataStack::new(),
             reservoir: Reservoir::new(),
             call_stack: CallStack::new(),
             status: ExecutionStatus::Running,
             output: String::new(),
-            next_droplet_id: 1,
+            next_droplet_id: created as DropletId,
+            pseudo_input: PseudoInput::default(),
+            random: RandomSource::from_entropy(),
+            arguments: ProgramArguments::default(),
+            files: FileTable::new(),
+            sockets: SocketTable::new(),
         };
 
-        Ok(TubularInterpreter {
+        TubularInterpreter {
             state,
+            code: Bytecode::compile(&grid),
+            multi_digit: grid.multi_digit_literals,
+            collision_policy: grid.collision_policy.unwrap_or_default(),
+            eof_policy: grid.eof_policy.unwrap_or_default(),
+            connect_timeout: CONNECT_TIMEOUT,
             grid,
             verbose: false,
             trace: false,
@@ -194,7 +625,37 @@ impl TubularInterpreter {
             warnings_issued: Vec::new(),
             progress_reports: Vec::new(),
             total_collisions: 0,
-        })
+            collision_counts: BTreeMap::new(),
+            tick_collisions: Vec::new(),
+            peak_value_digits: 1,
+            peak_reservoir_cells: 0,
+            history: None,
+            grid_writes: None,
+            scheduler: SchedulerKind::default().build(),
+            parallel: false,
+            wrap: false,
+            #[cfg(feature = "jit")]
+            jit: None,
+            folds: BTreeMap::new(),
+            local_stacks: false,
+            capabilities: Capabilities::new(),
+            deterministic: false,
+            tick_interval: None,
+            next_positions: CollisionIndex::new(),
+            trace_config: None,
+            trace_events: Vec::new(),
+            breakpoints: Vec::new(),
+            hit_breakpoint: None,
+            watches: Vec::new(),
+            hit_watch: None,
+            profile: None,
+            metrics: Metrics { droplets_created: created as u64, ..Metrics::new() },
+            input: Box::new(DefaultInput::default()),
+            input_reader: InputReader::default(),
+            output: Box::new(NullOutput),
+            interrupt: InterruptHandle::new(),
+            error_cell: None,
+        }
     }
 
     /// Set execution options (maintains backward compatibility)
@@ -213,6 +674,120 @@ impl TubularInterpreter {
         self
     }
 
+    /// Apply runtime options
+    pub fn with_runtime_options(mut self, options: &RuntimeOptions) -> Self {
+        self.state.pseudo_input = PseudoInput::new(options.pseudo_input_seed);
+        if options.deterministic {
+            self.scheduler = SchedulerKind::Id.build();
+            self.state.random = RandomSource::new(options.random_seed.unwrap_or(DETERMINISTIC_SEED));
+        } else {
+            self.scheduler = options.scheduler.build();
+            if let Some(seed) = options.random_seed {
+                self.state.random = RandomSource::new(seed);
+            }
+        }
+        self.deterministic = options.deterministic;
+        // A cell rewritten mid-tick must be seen by the droplets after it
+        self.parallel = options.parallel && !options.capabilities.allows(Capability::SelfModify);
+        self.wrap = options.wrap;
+        self.multi_digit = options.multi_digit_literals || self.grid.multi_digit_literals;
+        self.collision_policy = options.collision_policy.or(self.grid.collision_policy).unwrap_or_default();
+        #[cfg(feature = "jit")]
+        {
+            self.jit = if options.jit && !self.multi_digit { CorridorJit::new() } else { None };
+        }
+        // Folds divide the way integers do, so `%rational` programs run unfolded
+        self.folds = if options.optimize && !self.multi_digit && !self.grid.rational_numbers {
+            let (grid, wrap) = (&self.grid, self.wrap);
+            fold_constants(grid, &self.code, |position, direction| step(grid, wrap, position, direction))
+                .into_iter()
+                .map(|fold| ((fold.head, fold.heading), fold))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        self.local_stacks = options.local_stacks;
+        self.capabilities = options.capabilities;
+        self.tick_interval = options.tick_rate
+            .filter(|hz| hz.is_finite() && *hz > 0.0)
+            .and_then(|hz| Duration::try_from_secs_f64(hz.recip()).ok());
+        self.state.arguments = ProgramArguments::new(options.program_args.clone());
+        self.input_reader = InputReader::new(options.input_mode.unwrap_or_default());
+        self.eof_policy = options.eof_policy.or(self.grid.eof_policy).unwrap_or_default();
+        self.connect_timeout = options.connect_timeout.unwrap_or(CONNECT_TIMEOUT);
+        self
+    }
+
+    /// Read `?` and `??` input from `input` instead of stdin
+    pub fn with_input(self, input: impl InputProvider + 'static) -> Self {
+        self.with_boxed_input(Box::new(input))
+    }
+
+    pub fn with_boxed_input(mut self, input: Box<dyn InputProvider>) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Read `?` and `??` input from `input` from now on
+    pub fn set_input(&mut self, input: impl InputProvider + 'static) {
+        self.input = Box::new(input);
+    }
+
+    /// Send output to `output` as each tick produces it
+    ///
+    /// Output already sent is not taken back by `step_back`.
+    pub fn with_output(self, output: impl OutputSink + 'static) -> Self {
+        self.with_boxed_output(Box::new(output))
+    }
+
+    pub fn with_boxed_output(mut self, output: Box<dyn OutputSink>) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Deliver output a buffering sink is holding; `run` does this when it returns
+    pub fn flush_output(&mut self) -> Result<()> {
+        self.output.flush()
+    }
+
+    /// Take pause and cancel requests from `handle` instead of a handle of its own
+    ///
+    /// Once cancelled, the run stops with `ExecutionStatus::Cancelled`, leaving
+    /// droplets, stack and output as they were after the last full tick.
+    pub fn with_interrupt_handle(mut self, handle: InterruptHandle) -> Self {
+        self.interrupt = handle;
+        self
+    }
+
+    /// Use a custom droplet scheduler
+    pub fn with_scheduler(mut self, scheduler: Box<dyn Scheduler>) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Get the active droplet scheduler
+    pub fn scheduler(&self) -> &dyn Scheduler {
+        self.scheduler.as_ref()
+    }
+
+    /// Count visits and time per cell while executing
+    ///
+    /// Counters are not rewound by `step_back`.
+    pub fn with_profiling(mut self, enabled: bool) -> Self {
+        self.profile = enabled.then(ExecutionProfile::new);
+        self
+    }
+
+    /// Hotspot counters collected so far, if profiling
+    pub fn profile(&self) -> Option<&ExecutionProfile> {
+        self.profile.as_ref()
+    }
+
+    /// Counters for the execution so far
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
     /// Get current execution limits
     pub fn limits(&self) -> &ExecutionLimits {
         &self.limits
@@ -228,8 +803,366 @@ impl TubularInterpreter {
         &self.state
     }
 
+    /// Coordinates where droplets collided during the most recent tick
+    pub fn last_tick_collisions(&self) -> &[Coordinate] {
+        &self.tick_collisions
+    }
+
+    /// Record trace events matching `config` while executing
+    pub fn with_trace_config(mut self, config: TraceConfig) -> Self {
+        self.trace_config = Some(config);
+        self
+    }
+
+    /// Trace events recorded so far
+    pub fn trace_events(&self) -> &[TraceEvent] {
+        &self.trace_events
+    }
+
+    /// Remove and return the recorded trace events
+    pub fn take_trace_events(&mut self) -> Vec<TraceEvent> {
+        core::mem::take(&mut self.trace_events)
+    }
+
+    /// Whether another call to `execute_tick` will make progress
+    pub fn is_running(&self) -> bool {
+        self.state.status == ExecutionStatus::Running
+    }
+
+    /// Constant folds applied to the run, top to bottom; empty unless optimizing
+    pub fn folds(&self) -> Vec<&Fold> {
+        let mut folds: Vec<_> = self.folds.values().collect();
+        folds.sort_by_key(|fold| (fold.head.y, fold.head.x, fold.heading as u8));
+        folds
+    }
+
+    /// Get the program grid being executed
+    pub fn grid(&self) -> &ProgramGrid {
+        &self.grid
+    }
+
+    /// Where the cell whose operation failed the last tick was written
+    ///
+    /// Failures that are not raised by a cell, such as exceeded limits, have
+    /// no position.
+    pub fn error_position(&self) -> Option<Position> {
+        self.error_cell.map(|cell| self.grid.error_position(cell))
+    }
+
+    /// Get a shared handle to the program grid
+    pub fn shared_grid(&self) -> Arc<ProgramGrid> {
+        Arc::clone(&self.grid)
+    }
+
+    /// Pause execution whenever `condition` becomes true, returning the breakpoint's index
+    pub fn add_breakpoint(&mut self, condition: BreakCondition) -> usize {
+        self.breakpoints.push(Breakpoint::new(condition, &self.state));
+        self.breakpoints.len() - 1
+    }
+
+    /// Remove a breakpoint; later breakpoints shift down by one index
+    pub fn remove_breakpoint(&mut self, index: usize) -> Option<BreakCondition> {
+        if index >= self.breakpoints.len() {
+            return None;
+        }
+        self.hit_breakpoint = None;
+        Some(self.breakpoints.remove(index).condition)
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+        self.hit_breakpoint = None;
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = &BreakCondition> {
+        self.breakpoints.iter().map(|breakpoint| &breakpoint.condition)
+    }
+
+    /// The condition that caused the current pause, if paused
+    pub fn hit_breakpoint(&self) -> Option<&BreakCondition> {
+        self.hit_breakpoint
+            .filter(|_| self.state.status == ExecutionStatus::Paused)
+            .and_then(|index| self.breakpoints.get(index))
+            .map(|breakpoint| &breakpoint.condition)
+    }
+
+    /// Watch a reservoir cell or the stack, pausing or tracing when it triggers
+    pub fn add_watch(&mut self, expr: WatchExpr, action: WatchAction) -> usize {
+        self.watches.push(Watchpoint::new(expr, action, &self.state));
+        self.watches.len() - 1
+    }
+
+    /// Remove a watch; later watches shift down by one index
+    pub fn remove_watch(&mut self, index: usize) -> Option<WatchExpr> {
+        if index >= self.watches.len() {
+            return None;
+        }
+        Some(self.watches.remove(index).expr)
+    }
+
+    pub fn watches(&self) -> &[Watchpoint] {
+        &self.watches
+    }
+
+    /// The watch that caused the current pause, if paused by one
+    pub fn hit_watch(&self) -> Option<&WatchHit> {
+        self.hit_watch.as_ref().filter(|_| self.state.status == ExecutionStatus::Paused)
+    }
+
+    /// Suspend execution until `resume`; does nothing unless running
+    ///
+    /// Droplets, stack and output are kept, and `run`, `execute_tick` and
+    /// `ticks` make no progress while paused.
+    pub fn pause(&mut self) {
+        if self.state.status == ExecutionStatus::Running {
+            self.state.status = ExecutionStatus::Paused;
+            self.hit_breakpoint = None;
+            self.hit_watch = None;
+        }
+    }
+
+    /// Handle that pauses or cancels this interpreter from another thread
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.status == ExecutionStatus::Paused
+    }
+
+    /// Continue after `pause`, a breakpoint or a watch; does nothing unless paused
+    pub fn resume(&mut self) {
+        if self.state.status == ExecutionStatus::Paused {
+            self.state.status = ExecutionStatus::Running;
+            self.hit_breakpoint = None;
+            self.hit_watch = None;
+        }
+    }
+
+    /// Capture the current state so execution can be resumed later
+    pub fn snapshot(&self) -> InterpreterSnapshot {
+        InterpreterSnapshot {
+            program: self.grid.to_text(),
+            state: self.state.clone(),
+            warnings_issued: self.warnings_issued.clone(),
+            total_collisions: self.total_collisions,
+            collision_counts: self.collision_hotspots(),
+            peak_value_digits: self.peak_value_digits,
+            peak_reservoir_cells: self.peak_reservoir_cells,
+            metrics: self.metrics,
+        }
+    }
+
+    /// Resume from a snapshot taken on the same program
+    ///
+    /// Undo history and recorded trace events are discarded, and the
+    /// wall-clock limit starts counting again from the next `run`.
+    pub fn restore(&mut self, snapshot: InterpreterSnapshot) -> Result<()> {
+        if snapshot.program != self.grid.to_text() {
+            return Err(InterpreterError::Initialization(InitError::SnapshotMismatch));
+        }
+
+        self.state = snapshot.state;
+        self.warnings_issued = snapshot.warnings_issued;
+        self.total_collisions = snapshot.total_collisions;
+        self.collision_counts = snapshot.collision_counts.into_iter().collect();
+        self.tick_collisions.clear();
+        self.peak_value_digits = snapshot.peak_value_digits;
+        self.peak_reservoir_cells = snapshot.peak_reservoir_cells;
+        self.metrics = snapshot.metrics;
+        self.progress_reports.clear();
+        self.trace_events.clear();
+        self.start_time = None;
+        self.hit_breakpoint = None;
+        self.hit_watch = None;
+        for breakpoint in &mut self.breakpoints {
+            breakpoint.check(&self.state);
+        }
+        for watch in &mut self.watches {
+            watch.check(&self.state);
+        }
+        if let Some(history) = &self.history {
+            self.history = Some(ExecutionHistory::new(history.window()));
+        }
+        Ok(())
+    }
+
+    /// Record undo journals for the last `window` ticks so they can be stepped back
+    pub fn with_history(mut self, window: usize) -> Self {
+        self.enable_history(window);
+        self
+    }
+
+    /// Start recording undo journals for the last `window` ticks
+    pub fn enable_history(&mut self, window: usize) {
+        self.history = Some(ExecutionHistory::new(window));
+    }
+
+    /// Number of ticks that can currently be stepped back
+    pub fn history_len(&self) -> usize {
+        self.history.as_ref().map_or(0, |history| history.len())
+    }
+
+    /// Restore the state from before the most recent tick
+    ///
+    /// Returns false when history is disabled or exhausted.
+    pub fn step_back(&mut self) -> bool {
+        let journal = match self.history.as_mut().and_then(|history| history.pop()) {
+            Some(journal) => journal,
+            None => return false,
+        };
+
+        self.state.stack.undo(journal.stack_changes);
+        self.state.stack.max_depth = journal.stack_max_depth;
+        self.state.reservoir.undo(journal.reservoir_writes);
+        for (coord, previous) in journal.grid_writes.into_iter().rev() {
+            let grid = Arc::make_mut(&mut self.grid);
+            match previous {
+                Some(symbol) => {
+                    let _ = grid.set_cell(coord, symbol);
+                }
+                None => {
+                    grid.remove_cell(coord);
+                }
+            }
+            self.code.update(&self.grid, coord);
+        }
+        self.state.tick = journal.tick;
+        self.state.droplets = journal.droplets;
+        self.state.call_stack = journal.call_stack;
+        self.state.output.truncate(journal.output_len);
+        self.state.status = journal.status;
+        self.state.next_droplet_id = journal.next_droplet_id;
+        self.state.pseudo_input = journal.pseudo_input;
+        self.state.random = journal.random;
+        self.total_collisions = journal.total_collisions;
+        for coord in journal.collisions {
+            if let Some(count) = self.collision_counts.get_mut(&coord) {
+                *count -= 1;
+                if *count == 0 {
+                    self.collision_counts.remove(&coord);
+                }
+            }
+        }
+        self.tick_collisions = journal.previous_collisions;
+        for breakpoint in &mut self.breakpoints {
+            breakpoint.check(&self.state);
+        }
+        for watch in &mut self.watches {
+            watch.check(&self.state);
+        }
+        self.warnings_issued.truncate(journal.warnings_len);
+        self.progress_reports.truncate(journal.progress_len);
+        self.trace_events.truncate(journal.trace_len);
+        self.metrics = journal.metrics;
+        true
+    }
+
     /// Execute a single tick
+    ///
+    /// Once execution has stopped this is a no-op, so front-ends can step freely
+    /// and check `is_running` afterwards.
     pub fn execute_tick(&mut self) -> Result<TickResult> {
+        if self.history.is_none() {
+            return self.advance_tick();
+        }
+
+        let journal = TickJournal {
+            tick: self.state.tick,
+            droplets: self.state.droplets.clone(),
+            stack_changes: Vec::new(),
+            stack_max_depth: self.state.stack.max_depth,
+            reservoir_writes: Vec::new(),
+            grid_writes: Vec::new(),
+            call_stack: self.state.call_stack.clone(),
+            output_len: self.state.output.len(),
+            status: self.state.status.clone(),
+            next_droplet_id: self.state.next_droplet_id,
+            pseudo_input: self.state.pseudo_input.clone(),
+            random: self.state.random.clone(),
+            total_collisions: self.total_collisions,
+            collisions: Vec::new(),
+            previous_collisions: self.tick_collisions.clone(),
+            warnings_len: self.warnings_issued.len(),
+            progress_len: self.progress_reports.len(),
+            trace_len: self.trace_events.len(),
+            metrics: self.metrics,
+        };
+        let was_running = self.state.status == ExecutionStatus::Running;
+        self.state.stack.start_journal();
+        self.state.reservoir.start_journal();
+        self.grid_writes = Some(Vec::new());
+
+        let result = self.advance_tick();
+
+        let journal = TickJournal {
+            stack_changes: self.state.stack.take_journal(),
+            reservoir_writes: self.state.reservoir.take_journal(),
+            grid_writes: self.grid_writes.take().unwrap_or_default(),
+            collisions: self.tick_collisions.clone(),
+            ..journal
+        };
+        if was_running && let Some(history) = self.history.as_mut() {
+            history.push(journal);
+        }
+
+        result
+    }
+
+    /// Iterate over ticks, executing one per item until execution stops
+    ///
+    /// ```
+    /// use tubular::interpreter::execution::TubularInterpreter;
+    /// use tubular::parser::grid_parser::GridParser;
+    ///
+    /// let grid = GridParser::new().parse_string("@\n7\nn\n!").unwrap();
+    /// let mut interpreter = TubularInterpreter::new(grid).unwrap();
+    /// let output: String = interpreter.ticks()
+    ///     .filter_map(|tick| tick.unwrap().output)
+    ///     .collect();
+    /// assert_eq!(output, "7");
+    /// ```
+    pub fn ticks(&mut self) -> Ticks<'_> {
+        Ticks::new(self)
+    }
+
+    /// Commands for droplets on side-effect-free cells, indexed like `order`
+    ///
+    /// Empty unless parallel evaluation is on and enough droplets are queued to
+    /// repay the threading overhead. Profiled runs stay sequential so cell
+    /// timings remain meaningful.
+    #[cfg(feature = "parallel")]
+    fn precompute_commands(&self, order: &[usize]) -> Vec<Option<DropletCommand>> {
+        use rayon::prelude::*;
+
+        if !self.parallel || self.profile.is_some() || order.len() < PARALLEL_MIN_DROPLETS {
+            return Vec::new();
+        }
+        let (code, multi_digit) = (&self.code, self.multi_digit);
+        let droplets = &self.state.droplets;
+        order.par_iter()
+            .map(|&i| droplets[i].active.then(|| pure_command(code, &droplets[i], multi_digit)).flatten())
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn precompute_commands(&self, _order: &[usize]) -> Vec<Option<DropletCommand>> {
+        Vec::new()
+    }
+
+    /// Cancel or pause if the interrupt handle asks to, returning whether execution stopped
+    pub(crate) fn take_interrupt(&mut self) -> bool {
+        if self.interrupt.is_cancelled() {
+            self.state.status = ExecutionStatus::Cancelled;
+        } else if self.interrupt.take_pause() {
+            self.pause();
+        } else {
+            return false;
+        }
+        true
+    }
+
+    fn advance_tick(&mut self) -> Result<TickResult> {
         if self.state.status != ExecutionStatus::Running {
             return Ok(TickResult {
                 tick: self.state.tick,
@@ -238,10 +1171,20 @@ impl TubularInterpreter {
                 output: None,
             });
         }
+        self.tick_collisions.clear();
+
+        if self.take_interrupt() {
+            return Ok(TickResult {
+                tick: self.state.tick,
+                droplets_active: self.state.droplets.len(),
+                collisions: 0,
+                output: None,
+            });
+        }
 
         // Initialize start time if this is the first tick
         if self.start_time.is_none() {
-            self.start_time = Some(Instant::now());
+            self.start_time = clock::now();
         }
 
         let elapsed_ms = self.elapsed_time_ms().unwrap_or(0);
@@ -260,7 +1203,7 @@ impl TubularInterpreter {
             }
         }
 
-        if let Some(max_time_ms) = self.limits.max_time_ms {
+        if let Some(max_time_ms) = self.limits.max_time_ms.filter(|_| !self.deterministic) {
             if elapsed_ms >= max_time_ms {
                 self.state.status = ExecutionStatus::WallClockTimeout(max_time_ms);
                 self.cleanup();
@@ -280,184 +1223,326 @@ impl TubularInterpreter {
                 self.warnings_issued.push(warning.clone());
 
                 if self.verbose {
-                    eprintln!("⚠️  Warning: Approaching tick limit ({} ticks)", soft_tick_limit);
+                    stderr!("⚠️  Warning: Approaching tick limit ({} ticks)", soft_tick_limit);
                 }
             }
         }
 
-        if let Some(soft_time_limit_ms) = self.limits.soft_time_limit_ms {
+        if let Some(soft_time_limit_ms) = self.limits.soft_time_limit_ms.filter(|_| !self.deterministic) {
             if elapsed_ms >= soft_time_limit_ms && !self.warnings_issued.iter().any(|w| matches!(w, ExecutionWarning::SoftTimeLimit(_))) {
                 let warning = ExecutionWarning::SoftTimeLimit(soft_time_limit_ms);
                 self.warnings_issued.push(warning.clone());
 
                 if self.verbose {
-                    eprintln!("⚠️  Warning: Approaching time limit ({}ms)", soft_time_limit_ms);
+                    stderr!("⚠️  Warning: Approaching time limit ({}ms)", soft_time_limit_ms);
                 }
             }
         }
 
-        let mut next_positions: HashMap<Coordinate, Vec<DropletId>> = HashMap::new();
-        let mut commands: Vec<DropletCommand> = Vec::new();
-        let mut output_this_tick = String::new();
+        if let Some(result) = self.run_folded_path() {
+            return Ok(result);
+        }
+        #[cfg(feature = "jit")]
+        if let Some(result) = self.run_compiled_corridor() {
+            return Ok(result);
+        }
+
+        self.next_positions.clear();
+        let mut commands: Vec<(DropletKey, DropletCommand)> = Vec::new();
+        let mut output_this_tick = String::new();
+        let tick = self.state.tick;
+        let tracing = self.trace_config.is_some();
+        let mut pending_trace: Vec<TraceEvent> = Vec::new();
+
+        if tracing && tick == 0 {
+            for droplet in &self.state.droplets {
+                pending_trace.push(TraceEvent::lifecycle(
+                    tick, droplet.id, "created", droplet.position, &droplet.value, droplet.direction,
+                ));
+            }
+        }
 
         // Phase 1: Calculate movements and generate commands
-        let mut i = 0;
-        while i < self.state.droplets.len() {
+        let order = self.scheduler.order(&self.state.droplets, self.state.tick);
+        let mut precomputed = self.precompute_commands(&order);
+        // Cell of the droplet being processed and when processing started
+        let mut profile_mark: Option<(Coordinate, Instant)> = None;
+        for (slot, i) in order.into_iter().enumerate() {
+            if let Some(profile) = self.profile.as_mut() {
+                let now = clock::now();
+                if let Some((cell, start)) = profile_mark.take()
+                    && let Some(now) = now
+                {
+                    profile.record_time(cell, now - start);
+                }
+                let droplet = &self.state.droplets[i];
+                if droplet.active && let Some(symbol) = self.grid.get_symbol(droplet.position) {
+                    profile.record_visit(droplet.position, symbol);
+                    profile_mark = now.map(|now| (droplet.position, now));
+                }
+            }
+
+            let key = self.state.droplets.key_at(i);
             let droplet_id = self.state.droplets[i].id;
             let droplet = &mut self.state.droplets[i];
 
             if !droplet.active {
-                i += 1;
+                continue;
+            }
+            self.error_cell = Some(droplet.position);
+
+            // Sleeping droplets hold their cell without executing anything
+            if droplet.sleep_until.is_some() {
+                let command = if droplet.is_sleeping(self.state.tick) {
+                    DropletCommand { id: droplet_id, action: Action::Wait }
+                } else {
+                    droplet.sleep_until = None;
+                    DropletCommand::move_action(droplet_id, droplet.direction)
+                };
+                let target = match command.action {
+                    Action::Move(direction) => step(&self.grid, self.wrap, droplet.position, direction),
+                    _ => droplet.position,
+                };
+                self.next_positions.claim(target, droplet_id);
+                commands.push((key, command));
                 continue;
             }
 
-            let current_cell = match self.grid.get(droplet.position) {
-                Some(cell) => cell,
-                None => {
-                    // Droplet moved out of bounds - destroy it
-                    commands.push(DropletCommand {
-                        id: droplet.id,
-                        action: Action::Destroy,
-                    });
-                    i += 1;
-                    continue;
-                }
-            };
-
-            // Process the cell and handle input operations inline to avoid borrow conflicts
-            let command = match current_cell.symbol {
-                '?' => {
-                    // Input operations - need to handle inline
-                    let next_pos = droplet.position + droplet.direction;
-                    if let Some(next_cell) = self.grid.get(next_pos) {
-                        if next_cell.symbol == '?' {
+            // Cells without side effects may already have been computed in parallel
+            let ready = precomputed.get_mut(slot).and_then(Option::take);
+            let command = match ready.or_else(|| pure_command(&self.code, droplet, self.multi_digit)) {
+                Some(command) => command,
+                None => match self.code.op(droplet.position) {
+                    Op::Input => {
+                        // Input operations - need to handle inline
+                        let next_pos = step(&self.grid, self.wrap, droplet.position, droplet.direction);
+                        let input_str = if self.code.op(next_pos) == Op::Input {
                             // This is ?? (numeric input)
-                            let input_str = IoOperations::process_numeric_input()?;
+                            let input_str = self.input_reader.read_number(self.input.as_mut())?;
                             if let Ok(value) = input_str.parse::<i64>() {
-                                droplet.set_value(TubularBigInt::new(value));
+                                droplet.set_value(TubularNumber::new(value));
                             } else {
-                                droplet.set_value(TubularBigInt::zero());
+                                droplet.set_value(TubularNumber::zero());
                             }
-                            DropletCommand::move_action(droplet_id, droplet.direction)
+                            input_str
                         } else {
-                            // Single ? (character input)
-                            let input_str = IoOperations::process_character_input()?;
-                            if input_str.len() >= 1 {
-                                let char_value = input_str.chars().next().unwrap_or('\0') as u8;
-                                droplet.set_value(TubularBigInt::new(char_value as i64));
+                            // Single ? (character input), also at the grid boundary
+                            match self.input_reader.read_char(self.input.as_mut())? {
+                                Some(ch) => {
+                                    droplet.set_value(TubularNumber::new(ch as u8 as i64));
+                                    ch.to_string()
+                                }
+                                None => {
+                                    let value = self.eof_policy.value()?;
+                                    droplet.set_value(TubularNumber::new(value));
+                                    String::new()
+                                }
                             }
-                            DropletCommand::move_action(droplet_id, droplet.direction)
-                        }
-                    } else {
-                        // Single ? at boundary (character input)
-                        let input_str = IoOperations::process_character_input()?;
-                        if input_str.len() >= 1 {
-                            let char_value = input_str.chars().next().unwrap_or('\0') as u8;
-                            droplet.set_value(TubularBigInt::new(char_value as i64));
+                        };
+                        self.metrics.io_ops += 1;
+                        if tracing {
+                            pending_trace.push(TraceEvent::io(
+                                tick, droplet_id, '?', droplet.position, &input_str, &droplet.value,
+                            ));
                         }
                         DropletCommand::move_action(droplet_id, droplet.direction)
                     }
-                }
-                _ => {
-                    // Process all other symbols using a simplified inline version
-                    match current_cell.symbol {
-                        // Flow control pipes
-                        '|' | '-' => DropletCommand::move_action(droplet_id, droplet.direction),
-                        '/' => {
-                            let new_dir = match droplet.direction {
-                                Direction::Right => Direction::Up,
-                                Direction::Down => Direction::Left,
-                                Direction::Left => Direction::Down,
-                                Direction::Up => Direction::Right,
-                            };
-                            DropletCommand::move_action(droplet_id, new_dir)
-                        }
-                        '\\' => {
-                            // Handle conditional branching for backslash
-                            let new_dir = FlowControlOperations::process_conditional_branch(droplet, droplet.direction);
-                            DropletCommand::move_action(droplet_id, new_dir)
-                        }
-                        '^' => DropletCommand::move_action(droplet_id, Direction::Up),
-                        '@' => DropletCommand::move_action(droplet_id, droplet.direction),
-                        '!' => DropletCommand::destroy_action(droplet_id),
-                        '0'..='9' => {
-                            let value = current_cell.symbol.to_digit(10).unwrap() as i64;
-                            DropletCommand::set_value_action(droplet_id, TubularBigInt::new(value), droplet.direction)
-                        }
-                        _ if ArithmeticOperations::is_arithmetic_operation(current_cell.symbol) => {
-                            DropletCommand { id: droplet_id, action: Action::Stay }
-                        }
-                        ',' => {
-                            // Character output
-                            let output_str = IoOperations::process_character_output(droplet)?;
-                            output_this_tick.push_str(&output_str);
+                    Op::Sleep => {
+                        // Sleep: pop N and stay on this cell for N ticks
+                        let ticks = self.state.stack.pop();
+                        self.metrics.stack_ops += 1;
+                        let ticks = if ticks.is_negative() {
+                            0
+                        } else {
+                            ticks.to_i64().map_or(u64::MAX, |n| n as u64)
+                        };
+                        if ticks == 0 {
                             DropletCommand::move_action(droplet_id, droplet.direction)
+                        } else {
+                            droplet.suspend_until(self.state.tick.saturating_add(ticks));
+                            DropletCommand { id: droplet_id, action: Action::Wait }
                         }
-                        'n' => {
-                            // Numeric output
-                            let output_str = IoOperations::process_numeric_output(droplet)?;
-                            output_this_tick.push_str(&output_str);
+                    }
+                    Op::Gate => {
+                        // Gate: pop a value; zero, or an empty stack, closes it
+                        let open = !self.state.stack.pop().is_zero();
+                        self.metrics.stack_ops += 1;
+                        if open {
                             DropletCommand::move_action(droplet_id, droplet.direction)
+                        } else {
+                            DropletCommand::destroy_action(droplet_id)
                         }
-                        _ => DropletCommand::destroy_action(droplet_id),
                     }
-                }
+                    op @ (Op::OutputChar | Op::OutputNumber) => {
+                        let (symbol, output_str) = if op == Op::OutputChar {
+                            (',', IoOperations::process_character_output(droplet)?)
+                        } else {
+                            ('n', IoOperations::process_numeric_output(droplet)?)
+                        };
+                        output_this_tick.push_str(&output_str);
+                        self.metrics.io_ops += 1;
+                        if tracing {
+                            pending_trace.push(TraceEvent::io(
+                                tick, droplet_id, symbol, droplet.position, &output_str, &droplet.value,
+                            ));
+                        }
+                        DropletCommand::move_action(droplet_id, droplet.direction)
+                    }
+                    // Unpaired portals are rejected before the run, but `p` can write new ones
+                    Op::Portal => match self.grid.portals.exit(droplet.position) {
+                        Some(exit) => DropletCommand { id: droplet_id, action: Action::Teleport(exit, droplet.direction) },
+                        None => DropletCommand::destroy_action(droplet_id),
+                    },
+                    _ => DropletCommand::destroy_action(droplet_id),
+                },
             };
-
             match command.action {
                 Action::Move(direction) => {
-                    let next_pos = droplet.position + direction;
-                    next_positions.entry(next_pos).or_default().push(droplet_id);
-                    commands.push(command);
+                    let next_pos = step(&self.grid, self.wrap, droplet.position, direction);
+                    self.next_positions.claim(next_pos, droplet_id);
+                    commands.push((key, command));
                 }
                 Action::SetValue(_) => {
                     // SetValue operations don't move, just set the value
-                    commands.push(command);
+                    commands.push((key, command));
                 }
                 Action::SetValueAndMove(_, direction) => {
-                    let next_pos = droplet.position + direction;
-                    next_positions.entry(next_pos).or_default().push(droplet_id);
-                    commands.push(command);
+                    let next_pos = step(&self.grid, self.wrap, droplet.position, direction);
+                    self.next_positions.claim(next_pos, droplet_id);
+                    commands.push((key, command));
+                }
+                Action::Teleport(exit, direction) => {
+                    let next_pos = step(&self.grid, self.wrap, exit, direction);
+                    self.next_positions.claim(next_pos, droplet_id);
+                    commands.push((key, command));
                 }
                 Action::Destroy => {
-                    commands.push(DropletCommand {
-                        id: droplet_id,
-                        action: Action::Destroy,
-                    });
+                    commands.push((key, command));
+                }
+                Action::Stay | Action::Bounce(..) => {
+                    commands.push((key, command));
                 }
-                Action::Stay => {
-                    commands.push(command);
+                Action::Wait => {
+                    // A waiting droplet still occupies its cell
+                    self.next_positions.claim(droplet.position, droplet_id);
+                    commands.push((key, command));
                 }
             }
-            i += 1;
         }
 
-        // Phase 2: Detect collisions
-        let mut destroyed_droplets: HashSet<DropletId> = HashSet::new();
-        for (position, droplet_ids) in &next_positions {
-            if droplet_ids.len() > 1 {
-                // Collision detected - destroy all droplets
-                for id in droplet_ids {
-                    destroyed_droplets.insert(*id);
+        if let (Some(profile), Some((cell, start))) = (self.profile.as_mut(), profile_mark) {
+            profile.record_time(cell, start.elapsed());
+        }
+
+        // Phase 2: Detect collisions and resolve them with the collision policy
+        let policy = self.collision_policy;
+        let mut destroyed_droplets: BTreeSet<DropletId> = BTreeSet::new();
+        let mut bounced_droplets: BTreeSet<DropletId> = BTreeSet::new();
+        let mut merged_values: Vec<(DropletId, TubularNumber)> = Vec::new();
+        for (position, droplet_ids) in self.next_positions.collisions() {
+            self.metrics.collisions += 1;
+            let droplets = &self.state.droplets;
+            let outcome = policy.resolve(droplet_ids, |id| droplets.find(id).map_or(u64::MAX, |d| d.created_at));
+            // Merged values are what each droplet would hold once its cell has run
+            let merged = outcome.survivor.and_then(|survivor| {
+                let values = droplet_ids.iter().map(|&id| outgoing_value(&commands, droplets, id));
+                policy.merge(values).map(|value| (survivor, value))
+            });
+            merged_values.extend(merged);
+            destroyed_droplets.extend(&outcome.destroyed);
+            bounced_droplets.extend(&outcome.bounced);
+            self.total_collisions += droplet_ids.len();
+            *self.collision_counts.entry(position).or_insert(0) += 1;
+            self.tick_collisions.push(position);
+            if tracing {
+                pending_trace.push(TraceEvent::collision(
+                    tick, position, droplet_ids, policy, &outcome, droplets.len(),
+                ));
+                for droplet in droplets.iter().filter(|d| droplet_ids.contains(&d.id)) {
+                    let event = if outcome.destroyed.contains(&droplet.id) {
+                        "destroyed"
+                    } else if outcome.bounced.contains(&droplet.id) {
+                        "bounced"
+                    } else {
+                        continue;
+                    };
+                    pending_trace.push(TraceEvent::lifecycle(
+                        tick, droplet.id, event, position, &droplet.value, droplet.direction,
+                    ));
                 }
-                self.total_collisions += droplet_ids.len();
-                if self.verbose {
-                    eprintln!("[TICK {:05}] Collision at {} - {} droplets destroyed",
-                        self.state.tick, position, droplet_ids.len());
+            }
+            if self.verbose {
+                stderr!("[TICK {:05}] Collision at {} ({}) - {}",
+                    self.state.tick, position, policy, outcome);
+            }
+        }
+        if policy == CollisionPolicy::Bounce {
+            // Droplets meeting head-on, or stepping into a bounced droplet's cell, turn back too
+            let steps: Vec<Step> = commands.iter().filter_map(|(key, command)| {
+                let droplet = self.state.droplets.get(*key)?;
+                let to = match command.action {
+                    Action::Move(direction) | Action::SetValueAndMove(_, direction) => {
+                        step(&self.grid, self.wrap, droplet.position, direction)
+                    }
+                    Action::Teleport(exit, direction) => step(&self.grid, self.wrap, exit, direction),
+                    Action::Wait => droplet.position,
+                    _ => return None,
+                };
+                Some(Step { id: command.id, from: droplet.position, to })
+            }).collect();
+            for id in resolve_bounces(&steps) {
+                if bounced_droplets.insert(id) && tracing
+                    && let Some(droplet) = self.state.droplets.find(id) {
+                    pending_trace.push(TraceEvent::lifecycle(
+                        tick, id, "bounced", droplet.position, &droplet.value, droplet.direction,
+                    ));
                 }
             }
         }
 
+        for event in pending_trace {
+            self.record_trace(event);
+        }
+
         // Phase 3: Execute commands (except destroyed droplets)
-        for command in commands {
+        for (key, command) in commands {
             if destroyed_droplets.contains(&command.id) {
                 continue;
             }
-            self.execute_command(command)?;
+            let command = if bounced_droplets.contains(&command.id) { command.bounced() } else { command };
+            self.error_cell = self.state.droplets.get(key).map(|droplet| droplet.position);
+            if self.profile.is_some() {
+                let cell = self.state.droplets.get(key).map(|d| d.position);
+                let start = clock::now();
+                self.execute_command(key, command)?;
+                if let (Some(profile), Some(cell), Some(start)) = (self.profile.as_mut(), cell, start) {
+                    profile.record_time(cell, start.elapsed());
+                }
+            } else {
+                self.execute_command(key, command)?;
+            }
+            // Only commands write the stack and reservoir, so limits and watches
+            // see every write, even one undone by another droplet later in the same tick
+            self.check_resource_limits()?;
+            self.check_watches();
         }
 
+        self.error_cell = None;
+
+        // Merged droplets take the combined value once their own command has run
+        for (id, value) in merged_values {
+            if let Some(droplet) = self.state.droplets.iter_mut().find(|d| d.id == id) {
+                droplet.set_value(value);
+            }
+        }
+
+        self.watch_value_sizes();
+        self.peak_reservoir_cells = self.peak_reservoir_cells.max(self.state.reservoir.len());
+
         // Phase 4: Remove destroyed and inactive droplets
+        let droplets_before = self.state.droplets.len();
         self.state.droplets.retain(|d| !destroyed_droplets.contains(&d.id) && d.active);
+        self.metrics.droplets_destroyed += (droplets_before - self.state.droplets.len()) as u64;
 
         // Phase 5: Check if execution is complete
         if self.state.droplets.is_empty() {
@@ -477,7 +1562,7 @@ impl TubularInterpreter {
                 self.progress_reports.push(progress_report.clone());
 
                 if self.verbose {
-                    eprintln!("[PROGRESS] Tick: {}, Time: {}ms, Droplets: {}, Collisions: {}, Stack: {}",
+                    stderr!("[PROGRESS] Tick: {}, Time: {}ms, Droplets: {}, Collisions: {}, Stack: {}",
                         progress_report.tick, progress_report.elapsed_time_ms, progress_report.active_droplets,
                         progress_report.total_collisions, progress_report.stack_depth);
                 }
@@ -487,7 +1572,9 @@ impl TubularInterpreter {
         // Add output from this tick
         if !output_this_tick.is_empty() {
             self.state.output.push_str(&output_this_tick);
+            self.output.write_str(&output_this_tick)?;
         }
+        self.check_resource_limits()?;
 
         let result = TickResult {
             tick: self.state.tick,
@@ -497,25 +1584,194 @@ impl TubularInterpreter {
         };
 
         self.state.tick += 1;
+        self.metrics.ticks += 1;
+        self.check_breakpoints();
         Ok(result)
     }
 
-    /// Execute until completion or timeout
+    /// Whether the run is down to one awake droplet and nothing has to see
+    /// every tick (verbose logging, tracing, breakpoints, watches, history,
+    /// profiling, value size warnings, a tick rate), so several ticks can pass
+    /// at once
+    fn lone_unobserved_droplet(&self) -> bool {
+        let observed = self.verbose || self.trace_config.is_some() || !self.breakpoints.is_empty()
+            || !self.watches.is_empty() || self.history.is_some() || self.profile.is_some()
+            || self.limits.value_digit_warning.is_some() || self.tick_interval.is_some();
+        !observed && self.state.droplets.len() == 1
+            && self.state.droplets[0].active && self.state.droplets[0].sleep_until.is_none()
+    }
+
+    /// Whether `ticks` more ticks end before the tick limit and the next progress report
+    fn fits_before_limits(&self, ticks: u64) -> bool {
+        let end = self.state.tick + ticks;
+        let report_due = self.limits.progress_interval
+            .is_some_and(|interval| interval > 0 && self.state.tick.div_ceil(interval) * interval < end);
+        !report_due && self.limits.max_ticks.is_none_or(|max| end <= max)
+    }
+
+    /// Move a lone droplet starting a constant fold to its exit at once
+    ///
+    /// The fold's values are pushed and ticks and metrics advance as if every
+    /// cell had been interpreted. None, leaving the tick to the interpreter,
+    /// when several ticks cannot pass at once (see
+    /// [`Self::lone_unobserved_droplet`] and [`Self::fits_before_limits`]) or
+    /// the fold's pushes would outgrow the stack limit on the way.
+    fn run_folded_path(&mut self) -> Option<TickResult> {
+        if self.folds.is_empty() || !self.lone_unobserved_droplet() {
+            return None;
+        }
+        let droplet = &self.state.droplets[0];
+        let fold = self.folds.get(&(droplet.position, droplet.direction))?;
+        let peak_depth = self.state.stack.depth() + fold.peak_depth;
+        if !self.fits_before_limits(fold.ticks) || self.limits.max_stack_depth.is_some_and(|max| peak_depth > max) {
+            return None;
+        }
+
+        let droplet = &mut self.state.droplets[0];
+        droplet.set_value(fold.value.clone());
+        droplet.move_to(fold.exit);
+        droplet.set_direction(fold.direction);
+        for value in &fold.pushes {
+            self.state.stack.push(value.clone());
+        }
+        self.state.stack.max_depth = self.state.stack.max_depth.max(peak_depth);
+        self.peak_value_digits = self.peak_value_digits.max(fold.peak_digits);
+
+        let tick = self.state.tick + fold.ticks - 1;
+        self.state.tick += fold.ticks;
+        self.metrics.ticks += fold.ticks;
+        self.metrics.stack_ops += fold.stack_ops;
+        Some(TickResult { tick, droplets_active: 1, collisions: 0, output: None })
+    }
+
+    /// Move a lone droplet through a whole compiled corridor at once
+    ///
+    /// Ticks and metrics advance as if every cell had been interpreted. None,
+    /// leaving the tick to the interpreter, when several ticks cannot pass at
+    /// once (see [`Self::run_folded_path`]), the corridor is not compiled yet
+    /// or a value outgrows 64 bits.
+    #[cfg(feature = "jit")]
+    fn run_compiled_corridor(&mut self) -> Option<TickResult> {
+        if self.jit.is_none() || !self.lone_unobserved_droplet() {
+            return None;
+        }
+        let droplet = &self.state.droplets[0];
+        let value = droplet.value.to_i64()?;
+        let (grid, wrap) = (&self.grid, self.wrap);
+        let corridor = self.jit.as_mut()?.corridor(&self.code, droplet.position, droplet.direction, |position, direction| {
+            step(grid, wrap, position, direction)
+        })?;
+        if !self.fits_before_limits(corridor.ticks) {
+            return None;
+        }
+        let run = corridor.run(value)?;
+        let droplet = &mut self.state.droplets[0];
+
+        if run.value != value {
+            droplet.set_value(TubularNumber::new(run.value));
+        }
+        droplet.move_to(corridor.exit);
+        droplet.set_direction(corridor.direction);
+        let digits = TubularNumber::new(run.min).decimal_digits().max(TubularNumber::new(run.max).decimal_digits());
+        self.peak_value_digits = self.peak_value_digits.max(digits);
+
+        let tick = self.state.tick + corridor.ticks - 1;
+        self.state.tick += corridor.ticks;
+        self.metrics.ticks += corridor.ticks;
+        self.metrics.stack_ops += corridor.stack_ops;
+        Some(TickResult { tick, droplets_active: 1, collisions: 0, output: None })
+    }
+
+    /// Fail once the droplets, stack or reservoir outgrow their limits
+    fn check_resource_limits(&self) -> Result<()> {
+        let limits = &self.limits;
+        if let Some(max) = limits.max_droplets && self.state.droplets.len() > max {
+            return Err(ExecError::DropletLimitExceeded(max).into());
+        }
+        if let Some(max) = limits.max_stack_depth && self.state.stack.depth() > max {
+            return Err(ExecError::StackLimitExceeded(max).into());
+        }
+        if let Some(max) = limits.max_reservoir_cells && self.state.reservoir.len() > max {
+            return Err(ExecError::ReservoirLimitExceeded(max).into());
+        }
+        Ok(())
+    }
+
+    /// Re-read every watched value, tracing or pausing for those that triggered
+    ///
+    /// Runs after each droplet's command; a pause takes effect once the tick ends.
+    fn check_watches(&mut self) {
+        let mut hits = Vec::new();
+        for watch in &mut self.watches {
+            if let Some(hit) = watch.check(&self.state) {
+                hits.push((hit, watch.action));
+            }
+        }
+
+        for (hit, action) in hits {
+            match action {
+                WatchAction::Trace => {
+                    if self.trace_config.is_some() {
+                        let event = TraceEvent::watch(self.state.tick, &hit.to_string());
+                        self.record_trace(event);
+                    }
+                }
+                WatchAction::Pause => {
+                    if self.state.status == ExecutionStatus::Running {
+                        self.state.status = ExecutionStatus::Paused;
+                        self.hit_watch = Some(hit);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pause if any breakpoint condition became true during the last tick
+    fn check_breakpoints(&mut self) {
+        let mut hit = None;
+        for (index, breakpoint) in self.breakpoints.iter_mut().enumerate() {
+            // Check every breakpoint so each one tracks the current state
+            if breakpoint.check(&self.state) && hit.is_none() {
+                hit = Some(index);
+            }
+        }
+
+        if hit.is_some() && self.state.status == ExecutionStatus::Running {
+            self.state.status = ExecutionStatus::Paused;
+            self.hit_breakpoint = hit;
+        }
+    }
+
+    /// Execute until completion, timeout or a breakpoint
     pub fn run(&mut self) -> Result<ExecutionResult> {
         // Initialize start time
-        self.start_time = Some(Instant::now());
+        self.start_time = clock::now();
 
         let mut max_droplets = self.state.droplets.len();
         let mut total_ticks = 0;
+        let mut next_tick_at = self.start_time;
 
         if self.verbose {
-            eprintln!("Starting execution with limits: {:?}", self.limits);
+            stderr!("Starting execution with limits: {:?}", self.limits);
         }
 
         while self.state.status == ExecutionStatus::Running {
             max_droplets = max_droplets.max(self.state.droplets.len());
 
-            let tick_result = self.execute_tick()?;
+            if let (Some(interval), Some(due)) = (self.tick_interval, next_tick_at) {
+                self.pace(due)?;
+                // A late tick pushes back the ones after it rather than bunching them up
+                next_tick_at = Some(clock::now().map_or(due, |now| due.max(now)) + interval);
+            }
+
+            let tick_result = match self.execute_tick() {
+                Ok(tick_result) => tick_result,
+                Err(error) => {
+                    // Output from before the error still reaches the sink
+                    let _ = self.output.flush();
+                    return Err(error);
+                }
+            };
             total_ticks = tick_result.tick;
 
             // Note: Output is collected and will be printed once at the end
@@ -523,11 +1779,13 @@ impl TubularInterpreter {
 
             // Verbose logging
             if self.verbose {
-                eprintln!("[TICK {:05}] Active droplets: {}, Collisions: {}",
+                stderr!("[TICK {:05}] Active droplets: {}, Collisions: {}",
                     tick_result.tick, tick_result.droplets_active, tick_result.collisions);
             }
         }
 
+        self.output.flush()?;
+
         // Handle timeout states with graceful shutdown
         let execution_time_ms = self.elapsed_time_ms().unwrap_or(0);
 
@@ -535,16 +1793,19 @@ impl TubularInterpreter {
         if self.verbose {
             match &self.state.status {
                 ExecutionStatus::TickTimeout(limit) => {
-                    eprintln!("⏹️  Execution stopped: Tick limit of {} reached", limit);
+                    stderr!("⏹️  Execution stopped: Tick limit of {} reached", limit);
                 }
                 ExecutionStatus::WallClockTimeout(limit) => {
-                    eprintln!("⏹️  Execution stopped: Time limit of {}ms reached", limit);
+                    stderr!("⏹️  Execution stopped: Time limit of {}ms reached", limit);
                 }
                 ExecutionStatus::Completed => {
-                    eprintln!("✅ Execution completed successfully");
+                    stderr!("✅ Execution completed successfully");
                 }
                 ExecutionStatus::Error(error) => {
-                    eprintln!("❌ Execution failed: {}", error);
+                    stderr!("❌ Execution failed: {}", error);
+                }
+                ExecutionStatus::Cancelled => {
+                    stderr!("⏹️  Execution cancelled at tick {}", self.state.tick);
                 }
                 _ => {}
             }
@@ -553,7 +1814,7 @@ impl TubularInterpreter {
         // Final progress report if we have any
         if let Some(_last_progress) = self.progress_reports.last() {
             if self.verbose {
-                eprintln!("Final stats: {} ticks, {}ms, {} max droplets, {} total collisions",
+                stderr!("Final stats: {} ticks, {}ms, {} max droplets, {} total collisions",
                     total_ticks, execution_time_ms, max_droplets, self.total_collisions);
             }
         }
@@ -567,13 +1828,101 @@ impl TubularInterpreter {
             execution_time_ms,
             warnings_issued: self.warnings_issued.clone(),
             progress_reports: self.progress_reports.clone(),
+            collision_hotspots: self.collision_hotspots(),
+            peak_value_digits: self.peak_value_digits,
+            peak_reservoir_cells: self.peak_reservoir_cells,
+            profile: self.profile.clone(),
+            metrics: self.metrics,
         })
     }
 
+    /// Wait until `due`, the start of the next tick when pacing to a tick rate
+    ///
+    /// Output so far is delivered first, so animations show each frame. The
+    /// wait ends early at the wall-clock limit, which the next tick then
+    /// reports, or when the run is cancelled.
+    fn pace(&mut self, due: Instant) -> Result<()> {
+        let deadline = self.start_time
+            .zip(self.limits.max_time_ms.filter(|_| !self.deterministic))
+            .map(|(start, ms)| start + Duration::from_millis(ms));
+        let wake = deadline.map_or(due, |deadline| due.min(deadline));
+        if clock::now().is_none_or(|now| wake <= now) {
+            return Ok(());
+        }
+        self.output.flush()?;
+        loop {
+            let Some(now) = clock::now().filter(|&now| now < wake) else {
+                return Ok(());
+            };
+            if self.interrupt.is_cancelled() {
+                return Ok(());
+            }
+            clock::sleep((wake - now).min(PACE_SLICE));
+        }
+    }
+
+    /// Track the largest droplet value and, when `value_digit_warning` is set, warn once
+    /// if a droplet, stack or reservoir value crosses it
+    ///
+    /// The stack and reservoir are only scanned while the warning is enabled, so
+    /// without it the peak value size reflects droplet values alone.
+    fn watch_value_sizes(&mut self) {
+        let droplet_digits = self.state.droplets.iter()
+            .filter(|d| d.active)
+            .map(|d| d.value.decimal_digits())
+            .max()
+            .unwrap_or(0);
+        self.peak_value_digits = self.peak_value_digits.max(droplet_digits);
+
+        let Some(threshold) = self.limits.value_digit_warning else {
+            return;
+        };
+        if self.warnings_issued.iter().any(|w| matches!(w, ExecutionWarning::LargeValue { .. })) {
+            return;
+        }
+
+        let droplets = self.state.droplets.iter()
+            .filter(|d| d.active)
+            .map(|d| (ValueLocation::Droplet { droplet: d.id, position: d.position }, &d.value));
+        let stack = self.state.stack.data.iter().rev().enumerate()
+            .map(|(depth, value)| (ValueLocation::Stack { depth }, value));
+        let reservoir = self.state.reservoir.iter()
+            .map(|(coord, value)| (ValueLocation::Reservoir(coord.to_program_coordinate()), value));
+
+        let mut largest: Option<(ValueLocation, usize)> = None;
+        for (location, value) in droplets.chain(stack).chain(reservoir) {
+            let digits = value.decimal_digits();
+            if largest.is_none_or(|(_, most)| digits > most) {
+                largest = Some((location, digits));
+            }
+        }
+        let Some((location, digits)) = largest else {
+            return;
+        };
+        self.peak_value_digits = self.peak_value_digits.max(digits);
+
+        if digits > threshold {
+            self.warnings_issued.push(ExecutionWarning::LargeValue { location, digits });
+            if self.verbose {
+                stderr!("⚠️  Warning: {} holds a {}-digit value (threshold {})", location, digits, threshold);
+            }
+        }
+    }
+
+    /// Collision counts per coordinate, sorted by count (descending) then row and column
+    pub fn collision_hotspots(&self) -> Vec<(Coordinate, usize)> {
+        let mut hotspots: Vec<(Coordinate, usize)> = self.collision_counts
+            .iter()
+            .map(|(coord, count)| (*coord, *count))
+            .collect();
+        hotspots.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.y.cmp(&b.0.y)).then(a.0.x.cmp(&b.0.x)));
+        hotspots
+    }
+
     /// Perform graceful cleanup when execution is terminated
     fn cleanup(&mut self) {
         if self.verbose {
-            eprintln!("Performing graceful cleanup...");
+            stderr!("Performing graceful cleanup...");
         }
 
         // Clear all active droplets
@@ -590,23 +1939,34 @@ impl TubularInterpreter {
         }
 
         if self.verbose {
-            eprintln!("Cleanup completed");
+            stderr!("Cleanup completed");
         }
     }
 
     
     /// Execute a droplet command
-    fn execute_command(&mut self, command: DropletCommand) -> Result<()> {
-        let droplet = self.state.droplets.iter_mut()
-            .find(|d| d.id == command.id)
+    fn execute_command(&mut self, key: DropletKey, command: DropletCommand) -> Result<()> {
+        let droplet = self.state.droplets.get_mut(key)
             .ok_or_else(|| InterpreterError::Execution(ExecError::InternalError(
                 format!("Droplet {} not found", command.id)
             )))?;
 
+        let tracing = self.trace_config.is_some();
+        let tick = self.state.tick;
+        let from = droplet.position;
+        let mut event = None;
+        droplet.in_literal = matches!(self.code.op(from), Op::Digit(_));
+
         match command.action {
             Action::Move(direction) => {
                 droplet.set_direction(direction);
-                droplet.move_to(droplet.next_position());
+                droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                if tracing {
+                    event = Some(TraceEvent::movement(
+                        tick, droplet.id, from, droplet.position, direction, &droplet.value,
+                        self.grid.get_symbol(from),
+                    ));
+                }
             }
             Action::SetValue(value) => {
                 droplet.set_value(value);
@@ -614,34 +1974,276 @@ impl TubularInterpreter {
             Action::SetValueAndMove(value, direction) => {
                 droplet.set_value(value);
                 droplet.set_direction(direction);
-                droplet.move_to(droplet.next_position());
+                droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                if tracing {
+                    event = Some(TraceEvent::movement(
+                        tick, droplet.id, from, droplet.position, direction, &droplet.value,
+                        self.grid.get_symbol(from),
+                    ));
+                }
             }
             Action::Destroy => {
                 droplet.deactivate();
+                if tracing {
+                    event = Some(TraceEvent::lifecycle(
+                        tick, droplet.id, "destroyed", from, &droplet.value, droplet.direction,
+                    ));
+                }
+            }
+            Action::Wait => {
+                // Sleeping droplets do nothing until they wake
+            }
+            Action::Bounce(value, direction) => {
+                if let Some(value) = value {
+                    droplet.set_value(value);
+                }
+                droplet.set_direction(direction);
+            }
+            Action::Teleport(exit, direction) => {
+                droplet.move_to(step(&self.grid, self.wrap, exit, direction));
+                if tracing {
+                    event = Some(TraceEvent::movement(
+                        tick, droplet.id, from, droplet.position, direction, &droplet.value,
+                        self.grid.get_symbol(from),
+                    ));
+                }
             }
             Action::Stay => {
                 // Process operations when droplet stays in place
-                let current_cell = self.grid.get(droplet.position)
-                    .ok_or_else(|| InterpreterError::Execution(ExecError::InternalError(
-                        format!("No cell found at position {}", droplet.position)
-                    )))?;
+                let stack_before = if tracing { self.state.stack.as_slice().to_vec() } else { Vec::new() };
+
+                match self.code.op(droplet.position) {
+                    #[cfg(feature = "rational")]
+                    Op::Arithmetic('D') if self.grid.rational_numbers => {
+                        ArithmeticOperations::process_exact_division(droplet, &mut self.state.stack)?;
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Arithmetic(symbol) => {
+                        ArithmeticOperations::process_stack_operation(symbol, droplet, &mut self.state.stack)?;
 
-                // Handle stack and arithmetic operations
-                if ArithmeticOperations::is_arithmetic_operation(current_cell.symbol) {
-                    ArithmeticOperations::process_stack_operation(
-                        current_cell.symbol,
-                        droplet,
-                        &mut self.state.stack,
-                    )?;
+                        // After processing the operation, move the droplet forward
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::PseudoInput => {
+                        // Pseudo-input: push the next value of the deterministic sequence
+                        self.state.stack.push(self.state.pseudo_input.next_value());
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Random => {
+                        // Random: pops b then a; the droplet value becomes a random integer in a..=b
+                        let high = self.state.stack.pop();
+                        let low = self.state.stack.pop();
+                        droplet.set_value(self.state.random.next_in_range(&low, &high));
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 2;
+                    }
+                    Op::Argument => {
+                        // Argument: pops a character position, then an argument index
+                        let position = self.state.stack.pop();
+                        let index = self.state.stack.pop();
+                        droplet.set_value(self.state.arguments.value(&index, &position));
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Call => {
+                        // Call: jump to the bound label, or to the target given by the value and stack
+                        let subroutines = &self.grid.subroutines;
+                        match subroutines.call_label(droplet.position).and_then(|name| subroutines.label(name)) {
+                            Some(label) => {
+                                self.state.call_stack.push_return(droplet.position, droplet.direction);
+                                droplet.move_to(label.entry);
+                                droplet.set_direction(label.direction);
+                            }
+                            None => {
+                                let depth = self.state.call_stack.depth();
+                                SubroutineOperations::process_call_operation(
+                                    droplet, &mut self.state.stack, &mut self.state.call_stack, &self.grid,
+                                )?;
+                                if self.state.call_stack.depth() == depth {
+                                    // No cell at the target, so the droplet passes through
+                                    droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                                }
+                            }
+                        }
+                    }
+                    Op::Return => {
+                        // Return: resume just past the most recent call site, or pass through
+                        SubroutineOperations::process_return_operation(droplet, &mut self.state.call_stack)?;
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                    }
+                    Op::File(symbol) => {
+                        self.capabilities.check(symbol)?;
+                        IoOperations::process_file_operation(
+                            symbol, droplet, &mut self.state.stack, &mut self.state.files,
+                        );
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.io_ops += 1;
+                    }
+                    Op::Network(symbol) => {
+                        // A socket that is not ready keeps the droplet here until a later tick
+                        self.capabilities.check(symbol)?;
+                        let done = NetOperations::process_network_operation(
+                            symbol, droplet, &mut self.state.stack, &mut self.state.sockets, self.connect_timeout,
+                        );
+                        if done {
+                            droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                            self.metrics.io_ops += 1;
+                        }
+                    }
+                    Op::Local(symbol) => {
+                        if !self.local_stacks {
+                            return Err(ExecError::CapabilityDenied(symbol, LOCAL_STACKS_FLAG.to_string()).into());
+                        }
+                        LocalStackOperations::process_local_stack_operation(symbol, droplet, &mut self.state.stack)?;
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Memory(symbol) => {
+                        MemoryOperations::process_memory_operation(
+                            symbol, droplet, &mut self.state.stack, &mut self.state.reservoir, self.limits.max_reservoir_cells,
+                        )?;
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Reflect('g') => {
+                        ReflectionOperations::process_grid_get(droplet, &mut self.state.stack, &self.grid);
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Reflect(symbol) => {
+                        // Grid put: later ticks see the new cell, so cached folds and corridors are dropped
+                        self.capabilities.check(symbol)?;
+                        let grid = Arc::make_mut(&mut self.grid);
+                        let (coord, previous) = ReflectionOperations::process_grid_put(droplet, &mut self.state.stack, grid)?;
+                        self.code.update(&self.grid, coord);
+                        self.folds.clear();
+                        #[cfg(feature = "jit")]
+                        {
+                            self.jit = None;
+                        }
+                        if let Some(writes) = self.grid_writes.as_mut() {
+                            writes.push((coord, previous));
+                        }
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Metadata(symbol) => {
+                        MetadataOperations::process_metadata_operation(symbol, droplet, &mut self.state.stack, tick)?;
+                        droplet.move_to(step(&self.grid, self.wrap, droplet.position, droplet.direction));
+                        self.metrics.stack_ops += 1;
+                    }
+                    Op::Quote => {
+                        // Character literal: read the next cell, blank reading as a space, and skip it
+                        let quoted = step(&self.grid, self.wrap, droplet.position, droplet.direction);
+                        droplet.set_value(TubularNumber::from_char(self.grid.get_symbol(quoted).unwrap_or(' ')));
+                        droplet.move_to(step(&self.grid, self.wrap, quoted, droplet.direction));
+                    }
+                    op => {
+                        return Err(InterpreterError::Execution(ExecError::InternalError(
+                            format!("No operation to run for {:?} at position {}", op, from)
+                        )));
+                    }
+                }
 
-                    // After processing the operation, move the droplet forward
-                    droplet.move_to(droplet.next_position());
+                if tracing {
+                    event = Some(TraceEvent::stack(
+                        tick, droplet.id, self.grid.get_symbol(from).unwrap_or(' '), from,
+                        &stack_before, self.state.stack.as_slice(), &droplet.value,
+                    ));
                 }
             }
         }
 
+        if let Some(event) = event {
+            self.record_trace(event);
+        }
         Ok(())
     }
+
+    /// Keep `event` if tracing is enabled and it passes the configured filters
+    fn record_trace(&mut self, mut event: TraceEvent) {
+        let config = match &self.trace_config {
+            Some(config) => config,
+            None => return,
+        };
+        if config.max_events.is_some_and(|max| self.trace_events.len() >= max) {
+            return;
+        }
+        if !config.should_include_event(&event) {
+            return;
+        }
+
+        if let Some(start) = self.start_time {
+            event.timestamp = start.elapsed();
+        }
+        event.metadata.active_droplets = self.state.droplets.iter().filter(|d| d.active).count();
+        if let Some(position) = event.position {
+            event.source_location = self.grid.source_location(position);
+        }
+        self.trace_events.push(event);
+    }
+}
+
+/// Cell a droplet at `position` moves to when heading in `direction`
+///
+/// With `wrap` set, leaving the grid's bounding box re-enters it on the
+/// opposite side; empty cells inside the box still destroy the droplet.
+fn step(grid: &ProgramGrid, wrap: bool, position: Coordinate, direction: Direction) -> Coordinate {
+    let target = position + direction;
+    if wrap { grid.bounds.wrap(target) } else { target }
+}
+
+/// Fewest droplets in a tick for parallel command computation to be used
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_DROPLETS: usize = 256;
+
+/// Command for a droplet whose cell has no side effects
+///
+/// Returns None for sleeping droplets and for cells that read input, write
+/// output or pop the stack; those are handled in scheduler order. Depends only
+/// on the program and the droplet itself, so it can run for all droplets at once.
+/// With `multi_digit`, a digit right after another appends to the droplet value.
+fn pure_command(code: &Bytecode, droplet: &Droplet, multi_digit: bool) -> Option<DropletCommand> {
+    if droplet.sleep_until.is_some() {
+        return None;
+    }
+    let id = droplet.id;
+    let op = code.op(droplet.position);
+    let command = match op {
+        Op::Pass => DropletCommand::move_action(id, droplet.direction),
+        Op::Mirror => {
+            let new_dir = match droplet.direction {
+                Direction::Right => Direction::Up,
+                Direction::Down => Direction::Left,
+                Direction::Left => Direction::Down,
+                Direction::Up => Direction::Right,
+            };
+            DropletCommand::move_action(id, new_dir)
+        }
+        Op::Branch => {
+            // Handle conditional branching for backslash
+            let new_dir = FlowControlOperations::process_conditional_branch(droplet, droplet.direction);
+            DropletCommand::move_action(id, new_dir)
+        }
+        Op::Up => DropletCommand::move_action(id, Direction::Up),
+        Op::Digit(digit) => {
+            let digit = TubularNumber::new(digit as i64);
+            let value = if multi_digit && droplet.in_literal {
+                droplet.value.clone() * TubularNumber::new(10) + digit
+            } else {
+                digit
+            };
+            DropletCommand::set_value_action(id, value, droplet.direction)
+        }
+        _ if op.stays() => DropletCommand { id, action: Action::Stay },
+        Op::Input | Op::Sleep | Op::Gate | Op::OutputChar | Op::OutputNumber | Op::Portal => return None,
+        // Also droplets that moved out of bounds or onto a blank cell
+        _ => DropletCommand::destroy_action(id),
+    };
+    Some(command)
 }
 
 #[derive(Debug, Clone)]
@@ -658,7 +2260,7 @@ impl DropletCommand {
         }
     }
 
-    fn set_value_action(id: DropletId, value: TubularBigInt, direction: Direction) -> Self {
+    fn set_value_action(id: DropletId, value: TubularNumber, direction: Direction) -> Self {
         DropletCommand {
             id,
             action: Action::SetValueAndMove(value, direction),
@@ -671,13 +2273,1010 @@ impl DropletCommand {
             action: Action::Destroy,
         }
     }
+
+    /// This command for a droplet bounced by a collision: it keeps any new
+    /// value but stays on its cell, facing the way it came
+    fn bounced(self) -> Self {
+        let action = match self.action {
+            Action::Move(direction) => Action::Bounce(None, direction.opposite()),
+            Action::SetValueAndMove(value, direction) => Action::Bounce(Some(value), direction.opposite()),
+            Action::Teleport(_, direction) => Action::Bounce(None, direction.opposite()),
+            action => action,
+        };
+        DropletCommand { id: self.id, action }
+    }
+}
+
+/// Value droplet `id` holds once its command for this tick has run
+fn outgoing_value(commands: &[(DropletKey, DropletCommand)], droplets: &DropletArena, id: DropletId) -> TubularNumber {
+    match commands.iter().find(|(_, command)| command.id == id) {
+        Some((_, DropletCommand { action: Action::SetValueAndMove(value, _), .. })) => value.clone(),
+        Some((key, _)) => droplets.get(*key).map(|droplet| droplet.value.clone()).unwrap_or_default(),
+        None => TubularNumber::zero(),
+    }
 }
 
 #[derive(Debug, Clone)]
 enum Action {
     Move(Direction),
-    SetValue(TubularBigInt),
-    SetValueAndMove(TubularBigInt, Direction),
+    SetValue(TubularNumber),
+    SetValueAndMove(TubularNumber, Direction),
     Destroy,
     Stay,
-}
\ No newline at end of file
+    Wait,
+    /// Stay on the cell facing the direction, taking the value if there is one
+    Bounce(Option<TubularNumber>, Direction),
+    /// Move to the cell past the portal exit in the direction
+    Teleport(Coordinate, Direction),
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+    use crate::interpreter::memory::ReservoirCoordinate;
+
+    fn run_program(source: &str) -> ExecutionResult {
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        interpreter.run().unwrap()
+    }
+
+    #[test]
+    fn test_sleep_delays_droplet_by_popped_ticks() {
+        let awake = run_program("@\n3\n:\n|\nn\n!");
+        let asleep = run_program("@\n3\n:\nz\nn\n!");
+
+        assert_eq!(asleep.final_output, "3");
+        assert_eq!(asleep.total_ticks, awake.total_ticks + 3);
+    }
+
+    #[test]
+    fn test_sleeping_droplets_hold_their_cell() {
+        let grid = GridParser::new().parse_string("@\n9\n:\nz\n|\n|").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        for _ in 0..6 {
+            interpreter.execute_tick().unwrap();
+        }
+        // Waiting on its own cell is not a collision
+        let sleeper = &interpreter.state().droplets[0];
+        assert_eq!(sleeper.position, Coordinate::new(0, 3));
+        assert!(sleeper.sleep_until.is_some());
+
+        // A droplet arriving meanwhile meets it there
+        interpreter.state.droplets.push(Droplet::new(1, Coordinate::new(0, 4), Direction::Up));
+        interpreter.execute_tick().unwrap();
+        assert_eq!(interpreter.state().droplets.len(), 0);
+        assert_eq!(interpreter.metrics().collisions, 1);
+    }
+
+    #[test]
+    fn test_metrics_count_engine_events() {
+        let result = run_program("@\n3\n:\n:\nA\nn\n!");
+        let metrics = result.metrics;
+        assert_eq!(metrics.ticks, result.total_ticks + 1);
+        assert_eq!((metrics.droplets_created, metrics.droplets_destroyed), (1, 1));
+        assert_eq!((metrics.stack_ops, metrics.io_ops, metrics.collisions), (3, 1, 0));
+
+        let grid = GridParser::new().parse_string("@\n|\n|\n|").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_history(8);
+        interpreter.state.droplets.push(Droplet::new(1, Coordinate::new(0, 2), Direction::Up));
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.metrics().collisions, 1);
+        assert_eq!(interpreter.metrics().droplets_destroyed, 2);
+
+        interpreter.step_back();
+        assert_eq!(interpreter.metrics().collisions, 0);
+    }
+
+    #[test]
+    fn test_cancellation_stops_before_next_tick() {
+        let token = InterruptHandle::new();
+        let grid = GridParser::new().parse_string("\\\n@\n|\n\\").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap()
+            .with_limits(ExecutionLimits::unlimited())
+            .with_interrupt_handle(token.clone());
+
+        for _ in 0..5 {
+            interpreter.execute_tick().unwrap();
+        }
+        token.cancel();
+        let result = interpreter.run().unwrap();
+
+        assert_eq!(result.status, ExecutionStatus::Cancelled);
+        assert_eq!(result.metrics.ticks, 5);
+        assert_eq!(interpreter.state().droplets.len(), 1);
+    }
+
+    #[test]
+    fn test_collision_hotspots_recorded_per_coordinate() {
+        let grid = GridParser::new().parse_string("@\n|\n|\n|").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        // A second droplet heading up meets the start droplet at (0, 1)
+        interpreter.state.droplets.push(Droplet::new(1, Coordinate::new(0, 2), Direction::Up));
+
+        let result = interpreter.run().unwrap();
+        assert_eq!(result.collision_hotspots, vec![(Coordinate::new(0, 1), 1)]);
+    }
+
+    #[test]
+    fn test_profile_counts_visits_per_cell() {
+        let grid = GridParser::new().parse_string("@\n|\n|\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_profiling(true);
+
+        let result = interpreter.run().unwrap();
+        let profile = result.profile.expect("profiling was enabled");
+        assert_eq!(profile.total_visits(), 4);
+        assert_eq!(profile.cell(Coordinate::new(0, 1)).map(|e| (e.symbol, e.visits)), Some(('|', 1)));
+        assert_eq!(profile.by_operation().iter().find(|e| e.symbol == '|').map(|e| e.visits), Some(2));
+        assert_eq!(profile.hottest_cells(2).len(), 2);
+
+        assert!(run_program("@\n!").profile.is_none());
+    }
+
+    #[test]
+    fn test_sleep_with_empty_stack_passes_through() {
+        let awake = run_program("@\n5\n|\nn\n!");
+        let asleep = run_program("@\n5\nz\nn\n!");
+
+        assert_eq!(asleep.final_output, "5");
+        assert_eq!(asleep.total_ticks, awake.total_ticks);
+    }
+
+    #[test]
+    fn test_gate_passes_droplets_only_on_non_zero() {
+        assert_eq!(run_program("@\n5\n:\nK\nn\n!").final_output, "5");
+        // The popped value decides, not the droplet's own
+        assert_eq!(run_program("@\n0\n:\n5\nK\nn\n!").final_output, "");
+        assert_eq!(run_program("@\n5\nK\nn\n!").final_output, "");
+    }
+
+    fn snapshot(interpreter: &TubularInterpreter) -> (u64, Vec<(Coordinate, String)>, Vec<String>, String) {
+        let state = interpreter.state();
+        (
+            state.tick,
+            state.droplets.iter().map(|d| (d.position, d.value.to_string())).collect(),
+            state.stack.data.iter().map(|v| v.to_string()).collect(),
+            state.output.clone(),
+        )
+    }
+
+    #[test]
+    fn test_step_back_restores_previous_ticks() {
+        let grid = GridParser::new().parse_string("@\n7\n:\n2\n:\nA\nn\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_history(100);
+
+        let mut snapshots = vec![snapshot(&interpreter)];
+        while interpreter.state().status == ExecutionStatus::Running {
+            interpreter.execute_tick().unwrap();
+            snapshots.push(snapshot(&interpreter));
+        }
+        assert_eq!(interpreter.state().output, "9");
+
+        snapshots.pop();
+        while let Some(expected) = snapshots.pop() {
+            assert!(interpreter.step_back());
+            assert_eq!(snapshot(&interpreter), expected);
+        }
+        assert!(!interpreter.step_back());
+        assert_eq!(interpreter.state().status, ExecutionStatus::Running);
+    }
+
+    #[test]
+    fn test_history_window_limits_step_back() {
+        let grid = GridParser::new().parse_string("@\n|\n|\n|\n|\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_history(2);
+
+        for _ in 0..4 {
+            interpreter.execute_tick().unwrap();
+        }
+        assert_eq!(interpreter.history_len(), 2);
+        assert!(interpreter.step_back());
+        assert!(interpreter.step_back());
+        assert!(!interpreter.step_back());
+        assert_eq!(interpreter.state().tick, 2);
+    }
+
+    #[test]
+    fn test_wrap_reenters_on_opposite_side() {
+        // '/' turns the droplet left, off the grid's left edge and onto the 'n'
+        let grid = GridParser::new().parse_string("@\n7\nn\n/ n").unwrap();
+        let run = |wrap| {
+            TubularInterpreter::new(grid.clone()).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_wrap(wrap))
+                .run().unwrap()
+        };
+
+        assert_eq!(run(false).final_output, "7");
+        let wrapped = run(true);
+        assert_eq!(wrapped.final_output, "77");
+        assert_eq!(wrapped.status, ExecutionStatus::Completed);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_commands_match_sequential() {
+        let width = 400;
+        let source = [
+            format!("@{}", "7".repeat(width - 1)),
+            (0..width).map(|x| if x % 3 == 0 { '\\' } else { '|' }).collect(),
+            "|".repeat(width),
+            (0..width).map(|x| if x % 2 == 0 { 'n' } else { ',' }).collect(),
+            "!".repeat(width),
+        ].join("\n");
+
+        let run = |parallel| {
+            let grid = GridParser::new().parse_string(&source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_parallel(parallel));
+            for x in 1..width {
+                interpreter.state.droplets.push(Droplet::new(x as DropletId, Coordinate::new(x as isize, 0), Direction::Down));
+            }
+            // Droplets heading up meet those heading down in every fifth column
+            for (n, x) in (1..width).step_by(5).enumerate() {
+                interpreter.state.droplets.push(Droplet::new((width + n) as DropletId, Coordinate::new(x as isize, 2), Direction::Up));
+            }
+            interpreter.state.next_droplet_id = (2 * width) as DropletId;
+            let result = interpreter.run().unwrap();
+            (result.final_output, result.status, result.total_ticks, result.collision_hotspots)
+        };
+
+        let sequential = run(false);
+        assert!(!sequential.3.is_empty());
+        assert_eq!(run(true), sequential);
+    }
+
+    #[test]
+    fn test_constant_folds_match_interpreted_runs() {
+        // Each lap computes 5 * 3, leaves it on the stack and prints it
+        let source = "/-------\\\n|       5\n|       :\n|       3\n|       :\n|       M\n|       :\n|       n\n^-------/\n@\n1\n^";
+
+        let run = |optimize, limits: ExecutionLimits| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_limits(limits)
+                .with_runtime_options(&RuntimeOptions::new().with_optimize(optimize));
+            assert_eq!(interpreter.folds().is_empty(), !optimize);
+            let result = interpreter.run().map(|result| {
+                let reports: Vec<_> = result.progress_reports.iter().map(|report| report.tick).collect();
+                (result.final_output, result.status, result.total_ticks, result.metrics, result.peak_value_digits, result.max_stack_depth, reports)
+            });
+            (result.map_err(|e| e.to_string()), interpreter.state.tick, interpreter.state.stack.data.clone())
+        };
+
+        let limits = ExecutionLimits::default().with_max_ticks(Some(400));
+        let interpreted = run(false, limits.clone());
+        let (output, status, ..) = interpreted.0.clone().unwrap();
+        assert!(output.starts_with("151515"));
+        assert_eq!(status, ExecutionStatus::TickTimeout(400));
+        assert_eq!(run(true, limits), interpreted);
+        // Tick limits inside a folded path and stack limits it would cross stop the run at the same point
+        for limits in [
+            ExecutionLimits::default().with_max_ticks(Some(47)),
+            ExecutionLimits::default().with_max_stack_depth(Some(4)),
+        ] {
+            assert_eq!(run(true, limits.clone()), run(false, limits));
+        }
+    }
+
+    #[cfg(feature = "jit")]
+    #[test]
+    fn test_jit_corridors_match_interpreted_runs() {
+        // Counts down from 9, then bounces between the `\\` and the `^` until the tick limit
+        let source = "/-------\\\n|       n\n|       ~\n|       |\n^-------/\n@\n9\n^";
+
+        let run = |jit, max_ticks| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_limits(ExecutionLimits::default().with_max_ticks(Some(max_ticks)))
+                .with_runtime_options(&RuntimeOptions::new().with_jit(jit));
+            let result = interpreter.run().unwrap();
+            let reports: Vec<_> = result.progress_reports.iter().map(|report| report.tick).collect();
+            (result.final_output, result.status, result.total_ticks, result.metrics, result.peak_value_digits, reports)
+        };
+
+        let interpreted = run(false, 400);
+        assert_eq!(interpreted.0, "987654321");
+        assert_eq!(interpreted.1, ExecutionStatus::TickTimeout(400));
+        assert_eq!(run(true, 400), interpreted);
+        // A limit inside a corridor stops the run on the same tick
+        assert_eq!(run(true, 47), run(false, 47));
+    }
+
+    #[test]
+    fn test_portals_move_droplets_to_their_pair() {
+        // The droplet carries 7 into the portal at (0, 2) and leaves below (4, 0)
+        let source = "@   J\n7   n\nJ\n%portal a at (0, 2)\n%portal a at (4, 0)";
+        assert_eq!(run_program(source).final_output, "7");
+
+        // Leaving sideways keeps the heading too
+        let source = "@\n\\-J\n\n J-n\n%portal side at (2, 1)\n%portal side at (1, 3)";
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        interpreter.state.droplets.iter_mut().for_each(|droplet| droplet.set_value(TubularNumber::new(4)));
+        assert_eq!(interpreter.run().unwrap().final_output, "4");
+    }
+
+    #[test]
+    fn test_collision_policies_decide_who_carries_on() {
+        use crate::interpreter::trace::TraceOperation;
+
+        let run = |source: &str, policy: Option<CollisionPolicy>| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_collision_policy(policy))
+                .with_trace_config(TraceConfig::new());
+            // Droplet 1 carries 5 up into the start droplet at (0, 1)
+            interpreter.state.droplets.push(Droplet::with_value(1, TubularNumber::new(5), Coordinate::new(0, 2), Direction::Up));
+            let output = interpreter.run().unwrap().final_output;
+            let collision = interpreter.trace_events().iter()
+                .find(|event| event.operation == TraceOperation::Collision)
+                .map(|event| event.metadata.extra["policy"].clone());
+            (output, collision.unwrap())
+        };
+
+        let program = "@\n|\n|\nn";
+        assert_eq!(run(program, None), ("".to_string(), "destroy-all".to_string()));
+        assert_eq!(run(program, Some(CollisionPolicy::KeepOldest)).0, "0");
+        assert_eq!(run(program, Some(CollisionPolicy::MergeSum)), ("5".to_string(), "merge-sum".to_string()));
+        // The start droplet turns back off the top edge; droplet 1 heads down to n
+        assert_eq!(run(program, Some(CollisionPolicy::Bounce)).0, "5");
+        // The command line wins over the program's own policy
+        let merging = format!("{}\n%collisions merge-sum", program);
+        assert_eq!(run(&merging, None).0, "5");
+        assert_eq!(run(&merging, Some(CollisionPolicy::KeepOldest)).0, "0");
+    }
+
+    #[test]
+    fn test_merged_droplets_combine_values_left_by_their_cells() {
+        let run = |policy| {
+            let grid = GridParser::new().parse_string("@\n|4\n|\nn").unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_collision_policy(Some(policy)));
+            interpreter.state.droplets.iter_mut().for_each(|droplet| droplet.set_value(TubularNumber::new(2)));
+            // Droplet 1 sits on the 4, so it enters (0, 1) holding 4 rather than 5
+            interpreter.state.droplets.push(Droplet::with_value(1, TubularNumber::new(5), Coordinate::new(1, 1), Direction::Left));
+            interpreter.run().unwrap().final_output
+        };
+
+        assert_eq!(run(CollisionPolicy::MergeSum), "6");
+        assert_eq!(run(CollisionPolicy::MergeProduct), "8");
+        assert_eq!(run(CollisionPolicy::KeepOldest), "2");
+    }
+
+    #[test]
+    fn test_bouncing_droplets_turn_back_head_on() {
+        let bouncing = RuntimeOptions::new().with_collision_policy(Some(CollisionPolicy::Bounce));
+        let run = |options: &RuntimeOptions| {
+            let grid = GridParser::new().parse_string("@\n|\n|\nn").unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap().with_runtime_options(options);
+            // Droplet 1 swaps cells with the start droplet unless they bounce
+            interpreter.state.droplets.push(Droplet::with_value(1, TubularNumber::new(5), Coordinate::new(0, 1), Direction::Up));
+            interpreter.run().unwrap().final_output
+        };
+        assert_eq!(run(&RuntimeOptions::new()), "0");
+        assert_eq!(run(&bouncing), "5");
+
+        // Droplets 2 and 3 follow droplet 1 up the pipe and are turned back in turn
+        let grid = GridParser::new().parse_string("@\n|\n|\n|\n|\n|").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_runtime_options(&bouncing);
+        for (id, y) in [(1, 2), (2, 3), (3, 4)] {
+            interpreter.state.droplets.push(Droplet::new(id, Coordinate::new(0, y), Direction::Up));
+        }
+        interpreter.execute_tick().unwrap();
+        let droplets: Vec<_> = interpreter.state.droplets.iter().map(|d| (d.position.y, d.direction)).collect();
+        assert_eq!(droplets, vec![(0, Direction::Up), (2, Direction::Down), (3, Direction::Down), (4, Direction::Down)]);
+    }
+
+    #[test]
+    fn test_step_back_restores_tick_collisions() {
+        let grid = GridParser::new().parse_string("@\n|\n|\n|\n|\n|\n|").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_history(10);
+        interpreter.state.droplets.push(Droplet::new(1, Coordinate::new(0, 2), Direction::Up));
+        // A third droplet keeps the program running after the first two collide
+        interpreter.state.droplets.push(Droplet::new(2, Coordinate::new(0, 6), Direction::Up));
+
+        interpreter.execute_tick().unwrap();
+        assert_eq!(interpreter.last_tick_collisions(), &[Coordinate::new(0, 1)]);
+        interpreter.execute_tick().unwrap();
+        assert!(interpreter.last_tick_collisions().is_empty());
+
+        assert!(interpreter.step_back());
+        assert_eq!(interpreter.last_tick_collisions(), &[Coordinate::new(0, 1)]);
+        assert!(interpreter.step_back());
+        assert!(interpreter.last_tick_collisions().is_empty());
+        assert!(interpreter.collision_hotspots().is_empty());
+    }
+
+    #[test]
+    fn test_breakpoints_pause_run_until_resumed() {
+        let grid = GridParser::new().parse_string("@\n7\n:\n2\n:\nA\nn\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        let at_add = interpreter.add_breakpoint(BreakCondition::At(Coordinate::new(0, 5)));
+        interpreter.add_breakpoint(BreakCondition::Value(TubularNumber::new(9)));
+
+        let paused = interpreter.run().unwrap();
+        assert_eq!(paused.status, ExecutionStatus::Paused);
+        assert_eq!(interpreter.state().tick, 5);
+        assert_eq!(interpreter.hit_breakpoint(), Some(&BreakCondition::At(Coordinate::new(0, 5))));
+
+        // The value breakpoint fires once the droplet leaves the add cell holding 9
+        interpreter.resume();
+        assert_eq!(interpreter.run().unwrap().status, ExecutionStatus::Paused);
+        assert_eq!(interpreter.hit_breakpoint(), Some(&BreakCondition::Value(TubularNumber::new(9))));
+
+        assert!(interpreter.remove_breakpoint(at_add).is_some());
+        interpreter.resume();
+        let finished = interpreter.run().unwrap();
+        assert_eq!(finished.status, ExecutionStatus::Completed);
+        assert_eq!(finished.final_output, "9");
+    }
+
+    #[test]
+    fn test_pause_and_resume_keep_state() {
+        let source = "@\n7\n:\n2\n:\nA\nn\n!";
+        let expected = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap()
+            .run().unwrap().final_output;
+        let mut interpreter = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap();
+        interpreter.execute_tick().unwrap();
+        interpreter.execute_tick().unwrap();
+
+        interpreter.pause();
+        assert!(interpreter.is_paused());
+        assert_eq!(interpreter.hit_breakpoint(), None);
+        assert_eq!(interpreter.run().unwrap().status, ExecutionStatus::Paused);
+        assert_eq!(interpreter.ticks().count(), 0);
+        assert_eq!(interpreter.state().tick, 2);
+
+        interpreter.resume();
+        let finished = interpreter.run().unwrap();
+        assert_eq!(finished.status, ExecutionStatus::Completed);
+        assert_eq!(finished.final_output, expected);
+
+        // Pausing a finished program leaves its status alone
+        interpreter.pause();
+        assert_eq!(interpreter.state().status, ExecutionStatus::Completed);
+    }
+
+    #[test]
+    fn test_interrupt_handle_pauses_run_from_another_thread() {
+        let grid = GridParser::new().parse_string("\\\n@\n|\n\\\n").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap()
+            .with_limits(ExecutionLimits::unlimited());
+        let handle = interpreter.interrupt_handle();
+
+        let paused = std::thread::spawn(move || {
+            let status = interpreter.run().unwrap().status;
+            (status, interpreter)
+        });
+        std::thread::sleep(core::time::Duration::from_millis(20));
+        handle.pause();
+        let (status, mut interpreter) = paused.join().unwrap();
+        assert_eq!(status, ExecutionStatus::Paused);
+        assert!(!handle.is_pause_requested());
+
+        let tick = interpreter.state().tick;
+        interpreter.resume();
+        interpreter.execute_tick().unwrap();
+        assert_eq!(interpreter.state().tick, tick + 1);
+        assert!(interpreter.is_running());
+    }
+
+    #[test]
+    fn test_tick_and_droplet_breakpoints() {
+        let grid = GridParser::new().parse_string("@\n|\n|\n|\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        interpreter.add_breakpoint(BreakCondition::Tick(2));
+        // Droplet 0 exists from the start, so this never changes from false to true
+        interpreter.add_breakpoint(BreakCondition::Droplet(0));
+
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().tick, 2);
+        assert_eq!(interpreter.hit_breakpoint(), Some(&BreakCondition::Tick(2)));
+
+        interpreter.resume();
+        assert_eq!(interpreter.run().unwrap().status, ExecutionStatus::Completed);
+    }
+
+    #[test]
+    fn test_watches_pause_and_trace() {
+        use crate::interpreter::trace::TraceOperation;
+
+        let source = "@\n7\n:\n2\n:\nA\nn\n!";
+        let mut interpreter = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap();
+        interpreter.add_watch("stack depth > 1".parse().unwrap(), WatchAction::Pause);
+
+        assert_eq!(interpreter.run().unwrap().status, ExecutionStatus::Paused);
+        let hit = interpreter.hit_watch().unwrap();
+        assert_eq!(hit.to_string(), "stack depth > 1: 1 -> 2");
+        assert_eq!(interpreter.state().stack.depth(), 2);
+
+        interpreter.resume();
+        assert_eq!(interpreter.run().unwrap().final_output, "9");
+
+        let mut traced = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap()
+            .with_trace_config(TraceConfig::new());
+        traced.add_watch("stack top".parse().unwrap(), WatchAction::Trace);
+        traced.run().unwrap();
+        let watch_events: Vec<&str> = traced.trace_events().iter()
+            .filter(|event| event.operation == TraceOperation::Watch)
+            .map(|event| event.description.as_str())
+            .collect();
+        assert_eq!(watch_events, vec![
+            "Watch stack top: empty -> 7",
+            "Watch stack top: 7 -> 2",
+            "Watch stack top: 2 -> empty",
+        ]);
+    }
+
+    #[test]
+    fn test_large_value_warning_issued_once() {
+        let grid = GridParser::new().parse_string("@\n9\n:\n:\nM\n:\n:\nM\nn\n!").unwrap();
+        let limits = ExecutionLimits::default().with_value_digit_warning(Some(1));
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_limits(limits);
+
+        let result = interpreter.run().unwrap();
+        assert_eq!(result.final_output, "6561");
+        assert_eq!(result.peak_value_digits, 4);
+        let large: Vec<_> = result.warnings_issued.iter()
+            .filter(|w| matches!(w, ExecutionWarning::LargeValue { .. }))
+            .collect();
+        assert_eq!(large.len(), 1);
+        assert!(matches!(large[0], ExecutionWarning::LargeValue {
+            location: ValueLocation::Droplet { droplet: 0, .. },
+            digits: 2,
+        }));
+    }
+
+    #[test]
+    fn test_watches_see_writes_undone_within_a_tick() {
+        let source = "@ |\n: ;\n! !\n%droplet at (2, 0) heading down";
+        let mut interpreter = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap()
+            .with_trace_config(TraceConfig::new());
+        interpreter.add_watch("stack depth".parse().unwrap(), WatchAction::Trace);
+
+        let result = interpreter.run().unwrap();
+        assert_eq!(result.status, ExecutionStatus::Completed);
+        assert_eq!(interpreter.state().stack.depth(), 0);
+        let watch_events: Vec<&str> = interpreter.trace_events().iter()
+            .filter(|event| event.operation == crate::interpreter::trace::TraceOperation::Watch)
+            .map(|event| event.description.as_str())
+            .collect();
+        assert_eq!(watch_events, vec!["Watch stack depth: 0 -> 1", "Watch stack depth: 1 -> 0"]);
+    }
+
+    #[test]
+    fn test_large_value_warning_watches_the_stack_and_reservoir() {
+        let grid = GridParser::new().parse_string("@\n!").unwrap();
+        let limits = ExecutionLimits::default().with_value_digit_warning(Some(2));
+        let mut interpreter = TubularInterpreter::new(grid.clone()).unwrap().with_limits(limits.clone());
+        interpreter.state.stack.push(TubularNumber::new(12345));
+        interpreter.state.stack.push(TubularNumber::new(7));
+        interpreter.watch_value_sizes();
+        assert_eq!(interpreter.warnings_issued, vec![ExecutionWarning::LargeValue {
+            location: ValueLocation::Stack { depth: 1 },
+            digits: 5,
+        }]);
+        assert_eq!(interpreter.peak_value_digits, 5);
+
+        let mut interpreter = TubularInterpreter::new(grid.clone()).unwrap().with_limits(limits);
+        interpreter.state.reservoir.put(ReservoirCoordinate::new(3, -1), TubularNumber::new(-999));
+        interpreter.watch_value_sizes();
+        assert_eq!(interpreter.warnings_issued, vec![ExecutionWarning::LargeValue {
+            location: ValueLocation::Reservoir(Coordinate::new(3, -1)),
+            digits: 3,
+        }]);
+
+        let mut unwatched = TubularInterpreter::new(grid).unwrap();
+        unwatched.state.stack.push(TubularNumber::new(12345));
+        unwatched.watch_value_sizes();
+        assert!(unwatched.warnings_issued.is_empty());
+        assert_eq!(unwatched.peak_value_digits, 1);
+    }
+
+    #[test]
+    fn test_resource_usage_compares_limits() {
+        let grid = GridParser::new().parse_string("@\n7\n:\nn\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_options(false, false, Some(50));
+        let result = interpreter.run().unwrap();
+
+        let usage = ResourceUsage::collect(&result, interpreter.limits());
+        let ticks = usage.iter().find(|row| row.resource == "ticks").unwrap();
+        assert_eq!(ticks.limit, Some(50));
+        assert_eq!(ticks.percent_used(), Some(result.total_ticks as f64 * 2.0));
+
+        let output = usage.iter().find(|row| row.resource == "output").unwrap();
+        assert_eq!((output.actual, output.limit), (1, None));
+        assert_eq!(usage.iter().find(|row| row.resource == "stack depth").unwrap().actual, 1);
+    }
+
+    #[test]
+    fn test_tick_rate_paces_runs_within_the_time_limit() {
+        let grid = GridParser::new().parse_string("@\n|\n|\n|\n!").unwrap();
+        let paced = |hz: f64, limits: ExecutionLimits| TubularInterpreter::new(grid.clone()).unwrap()
+            .with_limits(limits)
+            .with_runtime_options(&RuntimeOptions::new().with_tick_rate(Some(hz)))
+            .run()
+            .unwrap();
+
+        // Five ticks at 50 Hz leave four 20 ms gaps
+        let result = paced(50.0, ExecutionLimits::default());
+        assert_eq!(result.status, ExecutionStatus::Completed);
+        assert!(result.execution_time_ms >= 75, "{}ms", result.execution_time_ms);
+
+        // A pause is cut short by the wall-clock limit, which then stops the run
+        let result = paced(0.5, ExecutionLimits::default().with_max_time_ms(Some(100)));
+        assert_eq!(result.status, ExecutionStatus::WallClockTimeout(100));
+        assert!(result.execution_time_ms < 1000, "{}ms", result.execution_time_ms);
+    }
+
+    #[test]
+    fn test_tick_rate_paces_every_tick_of_an_optimized_run() {
+        let grid = GridParser::new().parse_string("@\n7\n:\n3\n:\nA\n:\n2\n:\nM\nn\n!").unwrap();
+        let result = TubularInterpreter::new(grid).unwrap()
+            .with_runtime_options(&RuntimeOptions::new().with_optimize(true).with_tick_rate(Some(50.0)))
+            .run()
+            .unwrap();
+
+        // Eleven ticks at 50 Hz leave ten 20 ms gaps, folded or not
+        assert_eq!(result.final_output, "20");
+        assert_eq!(result.total_ticks, 11);
+        assert!(result.execution_time_ms >= 190, "{}ms", result.execution_time_ms);
+    }
+
+    #[test]
+    fn test_eof_policy_decides_what_question_mark_reads_at_end_of_input() {
+        let run = |program: &str, policy: Option<EofPolicy>| {
+            let grid = GridParser::new().parse_string(program).unwrap();
+            TubularInterpreter::new(grid).unwrap()
+                .with_input(crate::operations::io::StringInput::new("A"))
+                .with_runtime_options(&RuntimeOptions::new().with_eof_policy(policy))
+                .run()
+        };
+        let echo = "@\n?\nn\n?\nn\n!";
+        assert_eq!(run(echo, None).unwrap().final_output, "6510");
+        assert_eq!(run(echo, Some(EofPolicy::Zero)).unwrap().final_output, "650");
+        assert_eq!(run(echo, Some(EofPolicy::MinusOne)).unwrap().final_output, "65-1");
+        assert!(matches!(
+            run(echo, Some(EofPolicy::Error)),
+            Err(InterpreterError::Execution(ExecError::EndOfInput))
+        ));
+
+        // The program's `%eof` line applies unless the run picks a policy
+        let program = format!("{}\n%eof minus-one", echo);
+        assert_eq!(run(&program, None).unwrap().final_output, "65-1");
+        assert_eq!(run(&program, Some(EofPolicy::Zero)).unwrap().final_output, "650");
+    }
+
+    #[test]
+    fn test_derived_soft_limits_follow_hard_limits() {
+        let limits = ExecutionLimits::unlimited()
+            .with_max_ticks(Some(5000))
+            .with_max_time_ms(Some(250))
+            .with_derived_soft_limits();
+        assert_eq!((limits.soft_tick_limit, limits.soft_time_limit_ms), (Some(4000), Some(200)));
+        assert_eq!(ExecutionLimits::unlimited().with_derived_soft_limits().soft_tick_limit, None);
+        assert_eq!(ExecutionLimits::default().with_derived_soft_limits().soft_tick_limit, ExecutionLimits::default().soft_tick_limit);
+    }
+
+    #[test]
+    fn test_resource_limits_stop_runaway_programs() {
+        let grid = GridParser::new().parse_string("@\n:\n:\n:\n!").unwrap();
+        let run = |limits: ExecutionLimits| TubularInterpreter::new(grid.clone()).unwrap().with_limits(limits).run();
+
+        let error = run(ExecutionLimits::default().with_max_stack_depth(Some(2))).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::StackLimitExceeded(2))));
+        assert!(run(ExecutionLimits::default().with_max_stack_depth(Some(3)).with_max_droplets(Some(1))).is_ok());
+        assert!(matches!(
+            run(ExecutionLimits::default().with_max_droplets(Some(0))).unwrap_err(),
+            InterpreterError::Execution(ExecError::DropletLimitExceeded(0))
+        ));
+
+        // Three droplets push in the same tick; the second push already fails
+        let source = "@ | |\n: : :\n! ! !\n%droplet at (2, 0) heading down\n%droplet at (4, 0) heading down";
+        let mut interpreter = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap()
+            .with_limits(ExecutionLimits::default().with_max_stack_depth(Some(1)));
+        let error = interpreter.run().unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::StackLimitExceeded(1))));
+        assert_eq!(interpreter.state().stack.depth(), 2);
+    }
+
+    #[test]
+    fn test_labeled_call_returns_past_the_call_site() {
+        let source = "@  7\nC  R\nn\n!\n%label seven at (3, 0)\n%call seven at (0, 1)";
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+
+        let result = interpreter.run().unwrap();
+        assert_eq!(result.final_output, "7");
+        assert_eq!(result.status, ExecutionStatus::Completed);
+        assert_eq!(interpreter.state().call_stack.max_depth_reached(), 1);
+        assert!(interpreter.state().call_stack.is_empty());
+    }
+
+    #[test]
+    fn test_file_operations_need_the_filesystem_capability() {
+        let grid = GridParser::new().parse_string("@\nX\n!").unwrap();
+
+        let mut denied = TubularInterpreter::new(grid.clone()).unwrap();
+        let error = denied.run().unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::CapabilityDenied('X', ref flag)) if flag == "--allow-fs"));
+        assert_eq!(denied.error_position().map(|position| position.line), Some(1));
+
+        let options = RuntimeOptions::new().with_allow_fs(true);
+        let mut interpreter = TubularInterpreter::new(grid).unwrap().with_runtime_options(&options);
+        assert_eq!(interpreter.run().unwrap().status, ExecutionStatus::Completed);
+
+        // Granting the network does not grant the filesystem
+        let grid = GridParser::new().parse_string("@\nX\n!").unwrap();
+        let options = RuntimeOptions::new().with_allow_net(true);
+        assert!(TubularInterpreter::new(grid).unwrap().with_runtime_options(&options).run().is_err());
+    }
+
+    #[test]
+    fn test_random_operation_is_reproducible_per_seed() {
+        let grid = GridParser::new().parse_string("@\n1\n:\n9\n:\nr\nn\n!").unwrap();
+        let run_with_seed = |seed| {
+            let options = RuntimeOptions::new().with_random_seed(Some(seed));
+            let mut interpreter = TubularInterpreter::new(grid.clone()).unwrap().with_runtime_options(&options);
+            interpreter.run().unwrap().final_output
+        };
+
+        for seed in 0..20 {
+            let expected = RandomSource::new(seed).next_in_range(&TubularNumber::new(1), &TubularNumber::new(9));
+            assert_eq!(run_with_seed(seed), expected.to_string());
+            assert_eq!(run_with_seed(seed), run_with_seed(seed));
+        }
+
+        // Two pushes, then one pop for each end of the range
+        let result = TubularInterpreter::new(grid).unwrap().run().unwrap();
+        assert_eq!(result.metrics.stack_ops, 4);
+    }
+
+    #[test]
+    fn test_schedule_orders_output_within_a_tick() {
+        let grid = GridParser::new().parse_string("@\n nnn").unwrap();
+        let run = |scheduler| {
+            let mut interpreter = TubularInterpreter::new(grid.clone()).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_scheduler(scheduler));
+            // Stored third, first and second; each prints its own ID
+            for (id, x) in [(3, 2), (1, 3), (2, 1)] {
+                let mut droplet = Droplet::new(id, Coordinate::new(x, 1), Direction::Down);
+                droplet.set_value(TubularNumber::new(id as i64));
+                interpreter.state.droplets.push(droplet);
+            }
+            interpreter.run().unwrap().final_output
+        };
+
+        assert_eq!(run(SchedulerKind::Fifo), "312");
+        assert_eq!(run(SchedulerKind::Id), "123");
+        assert_eq!(run(SchedulerKind::Position), "231");
+    }
+
+    #[test]
+    fn test_local_stacks_keep_concurrent_droplets_apart() {
+        // Two droplets save their value, overwrite it and restore it in the same ticks
+        let run = |source: &str, local_stacks| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_local_stacks(local_stacks));
+            for (id, x, value) in [(1, 1, 4), (2, 3, 6)] {
+                interpreter.state.droplets.push(Droplet::with_value(id, TubularNumber::new(value), Coordinate::new(x, 1), Direction::Down));
+            }
+            interpreter.run().map(|result| result.final_output)
+        };
+
+        assert_eq!(run("@\n { {\n 1 2\n } }\n n n", true).unwrap(), "46");
+        // The shared stack hands each droplet the other's value
+        assert_eq!(run("@\n : :\n 1 2\n ; ;\n n n", true).unwrap(), "64");
+        let error = run("@\n { {\n 1 2\n } }\n n n", false).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::CapabilityDenied('{', ref flag)) if flag == "--local-stacks"));
+    }
+
+    #[test]
+    fn test_multi_digit_literals_join_consecutive_digits() {
+        let run = |source: &str, multi_digit| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_multi_digit_literals(multi_digit).with_optimize(true));
+            interpreter.run().unwrap().final_output
+        };
+
+        assert_eq!(run("@\n1\n2\n3\nn", false), "3");
+        assert_eq!(run("@\n1\n2\n3\nn", true), "123");
+        // Any other cell ends the number, and the heading decides the order
+        assert_eq!(run("@\n1\n2\n+\n3\nn", true), "3");
+        assert_eq!(run("@\n4\n2\n:\n;\nn", true), "42");
+        assert_eq!(run("%multidigit\n   @\n   |\nn75/", false), "57");
+    }
+
+    #[cfg(feature = "rational")]
+    #[test]
+    fn test_rational_programs_divide_exactly() {
+        let run = |source: &str| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_optimize(true));
+            interpreter.run().unwrap().final_output
+        };
+
+        assert_eq!(run("%rational\n@\n1\n:\n3\n:\nD\nn"), "1/3");
+        assert_eq!(run("%rational\n@\n1\n:\n3\n:\nD\n:\n3\n:\nM\nn"), "1");
+        assert_eq!(run("%rational\n@\n7\n:\n2\n:\nD\nt\nn"), "3");
+        // Without the pragma division rounds toward zero
+        assert_eq!(run("@\n1\n:\n3\n:\nD\nn"), "0");
+    }
+
+    #[test]
+    fn test_programs_read_and_rewrite_their_own_cells() {
+        let interpreter = |source: &str, allow| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            TubularInterpreter::new(grid).unwrap()
+                .with_runtime_options(&RuntimeOptions::new().with_allow_self_modify(allow).with_optimize(true))
+        };
+
+        assert_eq!(interpreter("@\n0\n:\n0\n:\ng\nn", false).run().unwrap().final_output, "64");
+        // `p` writes an `n` into the blank row below the grid before the droplet gets there
+        let source = "@\n0\n:\n9\n:\n'\nn\np\n7\n";
+        assert_eq!(interpreter(source, true).run().unwrap().final_output, "7");
+        let error = interpreter(source, false).run().unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::CapabilityDenied('p', ref flag)) if flag == "--allow-self-modify"));
+
+        // Stepping back over the write empties the cell again
+        let mut stepped = interpreter(source, true).with_history(20);
+        while stepped.grid.get_symbol(Coordinate::new(0, 9)).is_none() {
+            stepped.execute_tick().unwrap();
+        }
+        assert!(stepped.step_back());
+        assert_eq!(stepped.grid.get_symbol(Coordinate::new(0, 9)), None);
+        assert_eq!(stepped.code.op(Coordinate::new(0, 9)), Op::Destroy);
+    }
+
+    #[test]
+    fn test_droplets_push_their_id_heading_and_age() {
+        let grid = GridParser::new().parse_string("@\nw\nh\ne\n;\nn\n;\nn\n;\nn").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        // `e` runs on tick 3, heading down, in droplet 0
+        assert_eq!(interpreter.run().unwrap().final_output, "320");
+    }
+
+    #[test]
+    fn test_quote_reads_the_next_cell_and_skips_it() {
+        let run = |source: &str| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            let mut interpreter = TubularInterpreter::new(grid).unwrap();
+            let result = interpreter.run().unwrap();
+            (result.final_output, interpreter.state().tick)
+        };
+
+        assert_eq!(run("@\n'\nH\n,\n'\n!\n,").0, "H!");
+        // Blank cells read as a space, across as well as down
+        assert_eq!(run("@\n1\n\\'a,' ,").0, "a ");
+        // The jump takes a single tick
+        assert_eq!(run("@\n'\nn").1, run("@\n7").1);
+    }
+
+    #[test]
+    fn test_deterministic_mode_seeds_random_and_ignores_the_clock() {
+        let grid = GridParser::new().parse_string("@\n1\n:\n9\n:\nr\nn\n!").unwrap();
+        let options = RuntimeOptions::new()
+            .with_deterministic(true)
+            .with_scheduler(SchedulerKind::Position);
+        let run = || {
+            TubularInterpreter::new(grid.clone()).unwrap()
+                .with_limits(ExecutionLimits::default().with_max_time_ms(Some(0)))
+                .with_runtime_options(&options)
+                .run().unwrap()
+        };
+
+        let expected = RandomSource::new(DETERMINISTIC_SEED).next_in_range(&TubularNumber::new(1), &TubularNumber::new(9));
+        let result = run();
+        assert_eq!(result.status, ExecutionStatus::Completed);
+        assert_eq!(result.final_output, expected.to_string());
+        assert_eq!(run().final_output, result.final_output);
+    }
+
+    #[test]
+    fn test_pseudo_input_is_deterministic_per_seed() {
+        let run_with_seed = |seed| {
+            let grid = GridParser::new().parse_string("@\ni\n;\nn\ni\n;\nn\n!").unwrap();
+            let options = RuntimeOptions::new().with_pseudo_input_seed(seed);
+            let mut interpreter = TubularInterpreter::new(grid).unwrap().with_runtime_options(&options);
+            interpreter.run().unwrap().final_output
+        };
+
+        assert_eq!(run_with_seed(7), run_with_seed(7));
+        assert_ne!(run_with_seed(7), run_with_seed(8));
+
+        let mut expected = PseudoInput::new(7);
+        let first = expected.next_value();
+        let second = expected.next_value();
+        assert_eq!(run_with_seed(7), format!("{}{}", first, second));
+    }
+
+    #[test]
+    fn test_trace_events_follow_execution_and_filters() {
+        use crate::interpreter::trace::TraceOperation;
+
+        let grid = GridParser::new().parse_string("@\n7\n:\nn\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap()
+            .with_trace_config(TraceConfig::new());
+        interpreter.run().unwrap();
+
+        let operations: Vec<TraceOperation> = interpreter.trace_events().iter()
+            .map(|event| event.operation.clone())
+            .collect();
+        assert_eq!(operations.first(), Some(&TraceOperation::DropletLifecycle));
+        assert_eq!(operations.last(), Some(&TraceOperation::DropletLifecycle));
+        assert!(operations.contains(&TraceOperation::StackOp));
+        assert!(operations.contains(&TraceOperation::IoOp));
+
+        let filter = [TraceOperation::IoOp].into_iter().collect();
+        let grid = GridParser::new().parse_string("@\n7\n:\nn\n!").unwrap();
+        let mut filtered = TubularInterpreter::new(grid).unwrap()
+            .with_trace_config(TraceConfig::new().with_operation_filter(filter));
+        filtered.run().unwrap();
+
+        assert_eq!(filtered.trace_events().len(), 1);
+        assert_eq!(filtered.trace_events()[0].position, Some(Coordinate::new(0, 3)));
+    }
+
+    #[test]
+    fn test_step_back_discards_trace_events() {
+        let grid = GridParser::new().parse_string("@\n7\nn\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap()
+            .with_trace_config(TraceConfig::new())
+            .with_history(10);
+        interpreter.execute_tick().unwrap();
+        let after_first = interpreter.trace_events().len();
+        interpreter.execute_tick().unwrap();
+        assert!(interpreter.trace_events().len() > after_first);
+
+        interpreter.step_back();
+        assert_eq!(interpreter.trace_events().len(), after_first);
+    }
+
+    #[test]
+    fn test_restore_resumes_from_snapshot() {
+        let source = "@\n7\n:\n2\n:\nA\nn\n!";
+        let mut interpreter = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap();
+        for _ in 0..4 {
+            interpreter.execute_tick().unwrap();
+        }
+        let saved = interpreter.snapshot();
+
+        let mut resumed = TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap();
+        resumed.restore(saved).unwrap();
+        assert_eq!(snapshot(&resumed), snapshot(&interpreter));
+        assert_eq!(resumed.run().unwrap().final_output, "9");
+
+        let mut other = TubularInterpreter::new(GridParser::new().parse_string("@\n!").unwrap()).unwrap();
+        assert_eq!(
+            other.restore(interpreter.snapshot()),
+            Err(InterpreterError::Initialization(InitError::SnapshotMismatch))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_json_round_trip() {
+        let grid = GridParser::new().parse_string("@\n9\n:\n:\nM\nn\n!").unwrap();
+        let mut interpreter = TubularInterpreter::new(grid).unwrap();
+        for _ in 0..3 {
+            interpreter.execute_tick().unwrap();
+        }
+        let huge: TubularNumber = TubularNumber::from_bigint("123456789012345678901234567890".parse().unwrap());
+        interpreter.state.reservoir.put(ReservoirCoordinate::new(-2, 5), huge.clone());
+
+        let json = interpreter.snapshot().to_json().unwrap();
+        let restored = InterpreterSnapshot::from_json(&json).unwrap();
+        assert_eq!(restored.state.reservoir.get(ReservoirCoordinate::new(-2, 5)), huge);
+        assert_eq!(restored.state.tick, interpreter.state().tick);
+
+        let mut resumed = TubularInterpreter::new(interpreter.grid().clone()).unwrap();
+        resumed.restore(restored).unwrap();
+        assert_eq!(snapshot(&resumed), snapshot(&interpreter));
+        assert_eq!(resumed.run().unwrap().final_output, interpreter.run().unwrap().final_output);
+
+        assert!(InterpreterSnapshot::from_json("{}").is_err());
+    }
+}
@@ -1,9 +1,16 @@
 use crate::types::coordinate::Coordinate;
-use crate::types::error::{Result, InitError};
-use std::collections::HashMap;
-use std::fmt;
-
-#[derive(Debug, Clone)]
+use crate::types::direction::Direction;
+use crate::types::error::{Result, InitError, Position};
+use crate::types::source_map::{SourceMap, SourceLocation};
+use crate::interpreter::collision::CollisionPolicy;
+use crate::interpreter::portals::PortalTable;
+use crate::interpreter::subroutines::SubroutineTable;
+use crate::operations::io::EofPolicy;
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProgramCell {
     /// Character at this position
     pub symbol: char,
@@ -32,7 +39,8 @@ impl ProgramCell {
     pub fn is_operator_symbol(symbol: char) -> bool {
         matches!(symbol,
             '+' | '~' | ':' | ';' | 'd' | 'A' | 'S' | 'M' | 'D' | '=' | '<' | '>' | '%' |
-            'G' | 'P' | 'C' | 'R' | '!' | ',' | 'n' | '?' | '0'..='9'
+            '&' | 'o' | 'x' | 'N' | '[' | ']' | '*' | '_' | 't' | '\'' | 'J' |
+            'G' | 'P' | 'F' | 'Z' | 'B' | 'g' | 'p' | 'w' | 'e' | 'h' | 'C' | 'R' | 'z' | 'K' | 'i' | 'r' | 'a' | 'O' | 'I' | 'W' | 'X' | 'T' | 'E' | 'V' | '{' | '}' | '(' | ')' | '!' | ',' | 'n' | '?' | '0'..='9'
         )
     }
 
@@ -52,6 +60,77 @@ impl ProgramCell {
         matches!(symbol, '!' | ',' | 'n')
     }
 
+    /// One-line explanation of what a symbol does, for hover text and listings
+    pub fn description(symbol: char) -> Option<&'static str> {
+        let text = match symbol {
+            '@' => "Start: creates the initial droplet with value 0, moving down",
+            '|' => "Vertical pipe: droplet continues up or down",
+            '-' => "Horizontal pipe: droplet continues left or right",
+            '^' => "Up pipe: droplet turns upward",
+            '/' => "Corner: reflects the droplet (right to up, down to left, left to down, up to right)",
+            '\\' => "Conditional corner: value 0 reverses the droplet, any other value reflects it",
+            '0'..='9' => "Number literal: sets the droplet value to this digit",
+            '\'' => "Character literal: the droplet value becomes the code of the next cell's character (a space if it is blank), and the droplet jumps over that cell",
+            '?' => "Input: `?` reads one character as its code, `??` reads a line as a number",
+            'i' => "Pseudo-input: pushes the next byte of a deterministic sequence",
+            'r' => "Random: pops b then a; the droplet value becomes a random integer from a to b inclusive",
+            'a' => "Argument: pops a position k, then an index n; the droplet value becomes argument n as a number (k = 0) or its k-th character code, -1 past the end; n = 0 gives the argument count",
+            'O' => "File open: pops a mode (0 read, 1 write, 2 append), a length n and n character codes; the droplet value becomes the descriptor, or -1 (needs --allow-fs)",
+            'I' => "File read: pops a descriptor; the droplet value becomes the next byte, or -1 at the end (needs --allow-fs)",
+            'W' => "File write: pops a descriptor and writes the droplet value as a byte (needs --allow-fs)",
+            'X' => "File close: pops a descriptor and closes it (needs --allow-fs)",
+            'T' => "TCP connect: pops a length n and n character codes spelling host:port; the droplet value becomes the descriptor, or -1 (needs --allow-net)",
+            'E' => "TCP send: pops a descriptor and sends the droplet value as a byte, waiting while the socket is busy (needs --allow-net)",
+            'V' => "TCP receive: pops a descriptor; the droplet value becomes the next byte, or -1 once closed, waiting until one arrives (needs --allow-net)",
+            '!' => "Sink: destroys the droplet",
+            ',' => "Character output: prints the droplet value as an ASCII character",
+            'n' => "Numeric output: prints the droplet value as a number",
+            '+' => "Increment: adds 1 to the droplet value",
+            '~' => "Decrement: subtracts 1 from the droplet value",
+            ':' => "Push: pushes the droplet value onto the stack",
+            ';' => "Pop: pops the stack into the droplet value",
+            'd' => "Duplicate: pushes a copy of the top of the stack",
+            'A' => "Add: pops b then a; the droplet value becomes a + b",
+            'S' => "Subtract: pops b then a; the droplet value becomes a - b",
+            'M' => "Multiply: pops b then a; the droplet value becomes a * b",
+            'D' => "Divide: pops b then a; the droplet value becomes a / b",
+            '=' => "Equal: pops two values; the droplet value becomes 1 if they are equal, else 0",
+            '<' => "Less than: pops b then a; the droplet value becomes 1 if a < b, else 0",
+            '>' => "Greater than: pops b then a; the droplet value becomes 1 if a > b, else 0",
+            '%' => "Modulo: pops b then a; the droplet value becomes a % b",
+            '*' => "Power: pops b then a; the droplet value becomes a to the power b (b must not be negative)",
+            '_' => "Absolute value: pops a; the droplet value becomes |a|",
+            't' => "Truncate: drops the fractional part of the droplet value, rounding toward zero (with %rational)",
+            '&' => "And: pops b then a; the droplet value becomes their bitwise AND",
+            'o' => "Or: pops b then a; the droplet value becomes their bitwise OR",
+            'x' => "Xor: pops b then a; the droplet value becomes their bitwise XOR",
+            'N' => "Not: flips every bit of the droplet value, giving -value - 1",
+            '[' => "Shift left: pops b then a; the droplet value becomes a shifted left by b bits",
+            ']' => "Shift right: pops b then a; the droplet value becomes a shifted right by b bits, rounding down",
+            'G' => "Reservoir get: pops y and loads the value stored at (droplet value, y)",
+            'P' => "Reservoir put: pops y and stores the droplet value at (droplet value, y)",
+            'F' => "Region fill: pops height, width, y and x and stores the droplet value in every cell of that rectangle",
+            'Z' => "Region clear: pops height, width, y and x and empties every cell of that rectangle",
+            'B' => "Region copy: pops a destination y and x, then height, width, y and x, and copies that rectangle there",
+            'g' => "Grid get: pops y and x; the droplet value becomes the character code of the program cell there, 32 if blank",
+            'p' => "Grid put: pops y and x and writes the character whose code is the droplet value into the program cell there (needs --allow-self-modify)",
+            'w' => "Droplet ID: pushes the droplet's ID",
+            'e' => "Droplet age: pushes the number of ticks since the droplet was created",
+            'h' => "Droplet heading: pushes the droplet's direction (0 up, 1 right, 2 down, 3 left)",
+            'J' => "Portal: the droplet leaves from the other portal with the same %portal name, keeping its heading",
+            'C' => "Call: jumps to a subroutine, saving the return position",
+            'R' => "Return: resumes at the most recent call site",
+            'z' => "Sleep: pops N and holds the droplet on this cell for N ticks",
+            'K' => "Gate: pops a value and lets the droplet through if it is non-zero, destroying it otherwise",
+            '{' => "Local push: pushes the droplet value onto the droplet's own stack (needs --local-stacks)",
+            '}' => "Local pop: pops the droplet's own stack into the droplet value (needs --local-stacks)",
+            '(' => "Take: moves the top of the shared stack onto the droplet's own stack (needs --local-stacks)",
+            ')' => "Give: moves the top of the droplet's own stack onto the shared stack (needs --local-stacks)",
+            _ => return None,
+        };
+        Some(text)
+    }
+
     pub fn is_valid_symbol(symbol: char) -> bool {
         Self::is_flow_control_symbol(symbol) ||
         Self::is_operator_symbol(symbol) ||
@@ -108,6 +187,17 @@ impl BoundingBox {
         coord.x >= self.min_x && coord.x <= self.max_x &&
         coord.y >= self.min_y && coord.y <= self.max_y
     }
+
+    /// Map `coord` into the box, re-entering on the opposite side (toroidal wrap)
+    pub fn wrap(&self, coord: Coordinate) -> Coordinate {
+        if self.width() == 0 || self.height() == 0 {
+            return coord;
+        }
+        Coordinate::new(
+            self.min_x + (coord.x - self.min_x).rem_euclid(self.width() as isize),
+            self.min_y + (coord.y - self.min_y).rem_euclid(self.height() as isize),
+        )
+    }
 }
 
 impl Default for BoundingBox {
@@ -119,19 +209,46 @@ impl Default for BoundingBox {
 #[derive(Debug, Clone)]
 pub struct ProgramGrid {
     /// Sparse representation of program cells
-    pub cells: HashMap<Coordinate, ProgramCell>,
+    pub cells: BTreeMap<Coordinate, ProgramCell>,
     /// Bounding box of active program area
     pub bounds: BoundingBox,
     /// Start symbol location (must be exactly one)
     pub start: Option<Coordinate>,
+    /// Heading of the droplet leaving the start symbol (`%start heading`)
+    pub start_direction: Direction,
+    /// Cells and headings of the droplets started alongside the start symbol's (`%droplet`)
+    pub droplets: Vec<(Coordinate, Direction)>,
+    /// Mapping from grid coordinates back to the original source text
+    pub source_map: SourceMap,
+    /// Subroutine labels and the call sites bound to them
+    pub subroutines: SubroutineTable,
+    /// Portal cells and the names pairing them (`%portal`)
+    pub portals: PortalTable,
+    /// Consecutive digits on a droplet's path form one number (`%multidigit`)
+    pub multi_digit_literals: bool,
+    /// Values are exact fractions and `D` does not round (`%rational`)
+    pub rational_numbers: bool,
+    /// What happens when droplets collide (`%collisions`), unless the run overrides it
+    pub collision_policy: Option<CollisionPolicy>,
+    /// What `?` gives at end of input (`%eof`), unless the run overrides it
+    pub eof_policy: Option<EofPolicy>,
 }
 
 impl ProgramGrid {
     pub fn new() -> Self {
         ProgramGrid {
-            cells: HashMap::new(),
+            cells: BTreeMap::new(),
             bounds: BoundingBox::new(),
             start: None,
+            start_direction: Direction::Down,
+            droplets: Vec::new(),
+            source_map: SourceMap::new(),
+            subroutines: SubroutineTable::new(),
+            portals: PortalTable::new(),
+            multi_digit_literals: false,
+            rational_numbers: false,
+            collision_policy: None,
+            eof_policy: None,
         }
     }
 
@@ -155,6 +272,27 @@ impl ProgramGrid {
         Ok(())
     }
 
+    /// Write `symbol` to a cell of a running program, a space emptying it
+    ///
+    /// Unlike [`add_cell`](Self::add_cell) this never moves the start point,
+    /// so writing `@` only adds a cell that lets droplets pass. Returns the
+    /// symbol the cell held before.
+    pub fn set_cell(&mut self, coord: Coordinate, symbol: char) -> Result<Option<char>> {
+        if !symbol.is_ascii() {
+            return Err(InitError::InvalidCharacter(symbol, coord).into());
+        }
+        if symbol == ' ' {
+            return Ok(self.remove_cell(coord));
+        }
+        self.bounds.include(coord);
+        Ok(self.cells.insert(coord, ProgramCell::new(symbol)).map(|cell| cell.symbol))
+    }
+
+    /// Empty a cell, returning the symbol it held
+    pub fn remove_cell(&mut self, coord: Coordinate) -> Option<char> {
+        self.cells.remove(&coord).map(|cell| cell.symbol)
+    }
+
     pub fn get(&self, coord: Coordinate) -> Option<&ProgramCell> {
         self.cells.get(&coord)
     }
@@ -163,6 +301,32 @@ impl ProgramGrid {
         self.cells.get(&coord).map(|cell| cell.symbol)
     }
 
+    /// Whether the cell at `coord` is right of or below a `'`, so it may hold
+    /// any character for that `'` to read
+    pub fn is_quoted(&self, coord: Coordinate) -> bool {
+        [Direction::Left, Direction::Up].into_iter().any(|direction| self.get_symbol(coord + direction) == Some('\''))
+    }
+
+    /// Original source location of the cell at `coord`, if known
+    pub fn source_location(&self, coord: Coordinate) -> Option<SourceLocation> {
+        self.source_map.lookup(coord)
+    }
+
+    /// Where to report a problem with the cell at `coord`
+    ///
+    /// Goes through the source map, so cells stamped by macros point at the
+    /// text they came from and cells from included files name their file.
+    /// Cells the map does not know are placed by their coordinates.
+    pub fn error_position(&self, coord: Coordinate) -> Position {
+        match self.source_location(coord) {
+            Some(location) if Some(location.file.as_str()) != self.source_map.program_file() => {
+                Position::new(location.line, location.column, coord).with_file(location.file)
+            }
+            Some(location) => Position::new(location.line, location.column, coord),
+            None => Position::new(coord.y.max(0) as usize, coord.x.max(0) as usize, coord),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.cells.is_empty()
     }
@@ -175,7 +339,17 @@ impl ProgramGrid {
         (self.bounds.width(), self.bounds.height())
     }
 
+    /// Check the cells and the subroutine and portal directives
     pub fn validate(&self) -> Result<()> {
+        self.validate_cells()?;
+        match self.subroutine_errors().into_iter().chain(self.portal_errors()).next() {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Check for a start symbol, the size limit and invalid symbols
+    pub fn validate_cells(&self) -> Result<()> {
         if self.start.is_none() {
             return Err(InitError::NoStartSymbol.into());
         }
@@ -185,9 +359,10 @@ impl ProgramGrid {
             return Err(InitError::GridSizeExceeded(width, height).into());
         }
 
-        // Validate all symbols are valid
+        // Validate all symbols are valid; quoted cells are data
         for (coord, cell) in &self.cells {
-            if !ProgramCell::is_flow_control_symbol(cell.symbol) &&
+            if !self.is_quoted(*coord) &&
+               !ProgramCell::is_flow_control_symbol(cell.symbol) &&
                !ProgramCell::is_operator_symbol(cell.symbol) &&
                !ProgramCell::is_start_symbol(cell.symbol) &&
                !cell.symbol.is_whitespace() {
@@ -198,6 +373,45 @@ impl ProgramGrid {
         Ok(())
     }
 
+    /// Problems with `%label` and `%call` directives, ordered by call site
+    pub fn subroutine_errors(&self) -> Vec<InitError> {
+        let mut errors = Vec::new();
+        for (site, name) in self.subroutines.calls() {
+            if self.get_symbol(site) != Some('C') {
+                errors.push(InitError::InvalidCallSite(site));
+            } else if self.subroutines.label(name).is_none() {
+                errors.push(InitError::UndefinedLabel(name.to_string(), site));
+            }
+        }
+        let mut labels: Vec<_> = self.subroutines.labels()
+            .filter(|(_, label)| self.get(label.entry).is_none())
+            .map(|(name, label)| InitError::MissingLabelEntry(name.to_string(), label.entry))
+            .collect();
+        labels.sort_by_key(|error| error.to_string());
+        errors.extend(labels);
+        errors
+    }
+
+    /// Portals that are not `J` cells, `J` cells without a name and names
+    /// without exactly two ends, ordered by position
+    pub fn portal_errors(&self) -> Vec<InitError> {
+        let mut errors = Vec::new();
+        let mut unnamed: Vec<Coordinate> = self.cells.iter()
+            .filter(|(coord, cell)| cell.symbol == 'J' && !self.is_quoted(**coord) && self.portals.name(**coord).is_none())
+            .map(|(coord, _)| *coord)
+            .collect();
+        unnamed.sort_by_key(|coord| (coord.y, coord.x));
+        for (coord, name) in self.portals.portals() {
+            if self.get_symbol(coord) != Some('J') {
+                errors.push(InitError::InvalidPortalSite(coord));
+            } else if self.portals.end_count(name) != 2 {
+                errors.push(InitError::UnpairedPortal(name.to_string(), self.portals.end_count(name) - 1, coord));
+            }
+        }
+        errors.extend(unnamed.into_iter().map(InitError::UnnamedPortal));
+        errors
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (&Coordinate, &ProgramCell)> {
         self.cells.iter()
     }
@@ -225,6 +439,82 @@ impl ProgramGrid {
 
         lines
     }
+
+    /// Canonical source text for this grid
+    ///
+    /// Rows and columns are laid out from the origin, so the text keeps the grid's
+    /// offset and `GridParser::parse_string(&grid.to_text())` yields an equal grid for
+    /// every grid whose cells all sit at non-negative coordinates (which includes every
+    /// grid produced by `parse_string`). Trailing spaces are trimmed and every row ends
+    /// with a newline. Cells at negative coordinates cannot be expressed as text and
+    /// are shifted so the bounding box starts at the origin.
+    pub fn to_text(&self) -> String {
+        if self.cells.is_empty() {
+            return String::new();
+        }
+
+        let origin_x = self.bounds.min_x.min(0);
+        let origin_y = self.bounds.min_y.min(0);
+        let mut text = String::new();
+
+        for y in origin_y..=self.bounds.max_y {
+            let mut line = String::new();
+            for x in origin_x..=self.bounds.max_x {
+                line.push(self.get_symbol(Coordinate::new(x, y)).unwrap_or(' '));
+            }
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// [`to_text`](Self::to_text) followed by `%label` and `%call` directives for
+    /// the grid's subroutines, `%portal` directives for its portals, and `%start`, `%droplet`, `%multidigit`, `%rational`,
+    /// `%collisions` and `%eof` if it uses them, so the text parses back with its calls bound
+    ///
+    /// Directives keep their coordinates, so this only round-trips grids whose
+    /// cells sit at non-negative coordinates.
+    pub fn to_source(&self) -> String {
+        let mut text = self.to_text();
+        let mut labels: Vec<_> = self.subroutines.labels().collect();
+        labels.sort_by_key(|(name, _)| *name);
+        for (name, label) in labels {
+            text.push_str(&format!("%label {} at ({}, {}) heading {}\n", name, label.entry.x, label.entry.y, label.direction.name()));
+        }
+        for (site, name) in self.subroutines.calls() {
+            text.push_str(&format!("%call {} at ({}, {})\n", name, site.x, site.y));
+        }
+        for (coord, name) in self.portals.portals() {
+            text.push_str(&format!("%portal {} at ({}, {})\n", name, coord.x, coord.y));
+        }
+        if self.start_direction != Direction::Down {
+            text.push_str(&format!("%start heading {}\n", self.start_direction.name()));
+        }
+        for (coord, direction) in &self.droplets {
+            text.push_str(&format!("%droplet at ({}, {}) heading {}\n", coord.x, coord.y, direction.name()));
+        }
+        if self.multi_digit_literals {
+            text.push_str("%multidigit\n");
+        }
+        if self.rational_numbers {
+            text.push_str("%rational\n");
+        }
+        if let Some(policy) = self.collision_policy {
+            text.push_str(&format!("%collisions {}\n", policy));
+        }
+        if let Some(policy) = self.eof_policy {
+            text.push_str(&format!("%eof {}\n", policy));
+        }
+        text
+    }
+}
+
+impl PartialEq for ProgramGrid {
+    /// Grids are equal when they hold the same cells; where they came from is ignored
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells && self.start == other.start
+    }
 }
 
 impl Default for ProgramGrid {
@@ -0,0 +1,89 @@
+use crate::types::coordinate::Coordinate;
+use crate::interpreter::arena::DropletArena;
+use crate::interpreter::droplet::DropletId;
+use crate::interpreter::stack::StackChange;
+use crate::interpreter::memory::ReservoirWrite;
+use crate::interpreter::subroutines::CallStack;
+use crate::interpreter::execution::ExecutionStatus;
+use crate::interpreter::pseudo_input::PseudoInput;
+use crate::interpreter::random::RandomSource;
+use crate::interpreter::metrics::Metrics;
+use crate::types::prelude::*;
+use alloc::collections::VecDeque;
+
+/// Everything needed to undo a single tick
+///
+/// Stack and reservoir changes are stored as journals of individual writes;
+/// the remaining state is small enough to snapshot directly.
+#[derive(Debug, Clone)]
+pub struct TickJournal {
+    pub tick: u64,
+    pub droplets: DropletArena,
+    pub stack_changes: Vec<StackChange>,
+    pub stack_max_depth: usize,
+    pub reservoir_writes: Vec<ReservoirWrite>,
+    /// Program cells rewritten by `p` and the symbols they held
+    pub grid_writes: Vec<(Coordinate, Option<char>)>,
+    pub call_stack: CallStack,
+    pub output_len: usize,
+    pub status: ExecutionStatus,
+    pub next_droplet_id: DropletId,
+    pub pseudo_input: PseudoInput,
+    pub random: RandomSource,
+    pub total_collisions: usize,
+    pub collisions: Vec<Coordinate>,
+    /// Collisions reported for the tick before this one
+    pub previous_collisions: Vec<Coordinate>,
+    pub warnings_len: usize,
+    pub progress_len: usize,
+    pub trace_len: usize,
+    pub metrics: Metrics,
+}
+
+/// Bounded history of tick journals for reverse stepping
+#[derive(Debug, Clone)]
+pub struct ExecutionHistory {
+    window: usize,
+    entries: VecDeque<TickJournal>,
+}
+
+impl ExecutionHistory {
+    /// Keep undo information for at most `window` ticks
+    pub fn new(window: usize) -> Self {
+        ExecutionHistory {
+            window,
+            entries: VecDeque::with_capacity(window.min(1024)),
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    pub fn push(&mut self, journal: TickJournal) {
+        if self.window == 0 {
+            return;
+        }
+        if self.entries.len() == self.window {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(journal);
+    }
+
+    pub fn pop(&mut self) -> Option<TickJournal> {
+        self.entries.pop_back()
+    }
+
+    /// Number of ticks that can currently be stepped back
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,69 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+const NONE: u8 = 0;
+const PAUSE: u8 = 1;
+const CANCEL: u8 = 2;
+
+/// Asks an interpreter to pause or stop at the start of its next tick
+///
+/// Clones share one request, so a handle can be given to a signal handler or
+/// another thread while the interpreter keeps its own copy. A pause is
+/// consumed when the interpreter pauses, and `resume` then continues as after
+/// a breakpoint; a cancellation stays in force and wins over a pending pause.
+/// Neither can interrupt a tick that is blocked waiting for input.
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle {
+    request: Arc<AtomicU8>,
+}
+
+impl InterruptHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pause(&self) {
+        let _ = self.request.compare_exchange(NONE, PAUSE, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    pub fn cancel(&self) {
+        self.request.store(CANCEL, Ordering::SeqCst);
+    }
+
+    /// Whether a pause has been asked for but not yet taken
+    pub fn is_pause_requested(&self) -> bool {
+        self.request.load(Ordering::SeqCst) == PAUSE
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.request.load(Ordering::SeqCst) == CANCEL
+    }
+
+    /// Clear a pending pause, returning whether there was one
+    pub(crate) fn take_pause(&self) -> bool {
+        self.request.compare_exchange(PAUSE, NONE, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_requests() {
+        let handle = InterruptHandle::new();
+        let remote = handle.clone();
+        assert!(!handle.is_cancelled());
+
+        std::thread::spawn(move || remote.pause()).join().unwrap();
+        assert!(handle.is_pause_requested());
+        assert!(handle.take_pause());
+        assert!(!handle.take_pause());
+
+        handle.pause();
+        handle.cancel();
+        handle.pause();
+        assert!(handle.is_cancelled());
+        assert!(!handle.take_pause());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::interpreter::bytecode::{Bytecode, Op};
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::ir::{types, AbiParam, InstBuilder, MemFlags, Value};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Module};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Longest corridor compiled into one block, in cells
+const MAX_CORRIDOR_CELLS: usize = 4096;
+
+/// Times a droplet has to be at the head of a corridor before it is compiled
+const HOT_VISITS: u32 = 2;
+
+/// Fewest cells worth leaving the interpreter for
+const MIN_CORRIDOR_CELLS: u64 = 2;
+
+/// What a corridor cell does to the droplet value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Effect {
+    Set(u8),
+    Increment,
+    Decrement,
+}
+
+/// A run of cells whose path does not depend on the droplet value or on
+/// anything outside the droplet: pipes, `/`, `^`, digits, `+` and `~`
+#[derive(Debug, Clone, PartialEq)]
+struct CorridorPlan {
+    effects: Vec<Effect>,
+    exit: Coordinate,
+    direction: Direction,
+    ticks: u64,
+    stack_ops: u64,
+}
+
+/// Native code for one corridor
+///
+/// `run` reads the droplet value from the first of three `i64`s, writes the
+/// value at the exit back to it, and the smallest and largest value seen on
+/// the way to the other two. It returns 0, leaving them untouched, if a
+/// value would not fit in 64 bits.
+#[derive(Clone, Copy)]
+pub struct Corridor {
+    /// Cell the droplet is on when the corridor ends
+    pub exit: Coordinate,
+    pub direction: Direction,
+    /// Ticks the interpreter would have taken
+    pub ticks: u64,
+    /// Stack operations (`+` and `~`) on the way, for the run's metrics
+    pub stack_ops: u64,
+    changes_value: bool,
+    run: extern "C" fn(*mut i64) -> u8,
+}
+
+/// Result of running a corridor natively
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CorridorRun {
+    pub value: i64,
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Corridor {
+    /// Run the corridor for a droplet holding `value`; None if a value overflows 64 bits
+    pub fn run(&self, value: i64) -> Option<CorridorRun> {
+        if !self.changes_value {
+            return Some(CorridorRun { value, min: value, max: value });
+        }
+        let mut state = [value, value, value];
+        ((self.run)(state.as_mut_ptr()) != 0).then_some(CorridorRun { value: state[0], min: state[1], max: state[2] })
+    }
+}
+
+impl fmt::Debug for Corridor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Corridor")
+            .field("exit", &self.exit)
+            .field("direction", &self.direction)
+            .field("ticks", &self.ticks)
+            .field("stack_ops", &self.stack_ops)
+            .finish()
+    }
+}
+
+/// Compiles straight pipe corridors to native code with Cranelift as the
+/// droplet keeps coming back to them
+///
+/// The interpreter hands a lone droplet to [`CorridorJit::corridor`] at the
+/// start of each tick and skips the whole corridor in one go when it gets one
+/// back; junctions (`\`), input, output, the stack and calls stay with the
+/// interpreter.
+pub struct CorridorJit {
+    module: JITModule,
+    visits: HashMap<(Coordinate, Direction), u32>,
+    /// Compiled corridors by head; None where there is nothing worth compiling
+    corridors: HashMap<(Coordinate, Direction), Option<Corridor>>,
+}
+
+// SAFETY: the module and the code it owns are only reached through `&mut self`,
+// so moving the whole compiler to another thread cannot race with its use
+unsafe impl Send for CorridorJit {}
+
+impl CorridorJit {
+    /// A compiler for the host CPU, or None if Cranelift does not support it
+    pub fn new() -> Option<Self> {
+        let mut flags = settings::builder();
+        flags.set("use_colocated_libcalls", "false").ok()?;
+        flags.set("is_pic", "false").ok()?;
+        flags.set("opt_level", "speed").ok()?;
+        let isa = cranelift_native::builder().ok()?
+            .finish(settings::Flags::new(flags))
+            .ok()?;
+        Some(CorridorJit {
+            module: JITModule::new(JITBuilder::with_isa(isa, default_libcall_names())),
+            visits: HashMap::new(),
+            corridors: HashMap::new(),
+        })
+    }
+
+    /// The compiled corridor starting at `position` heading `direction`, once it is hot
+    ///
+    /// `step` gives the cell a droplet moves to, so wrapping is the caller's.
+    pub fn corridor(
+        &mut self,
+        code: &Bytecode,
+        position: Coordinate,
+        direction: Direction,
+        step: impl Fn(Coordinate, Direction) -> Coordinate,
+    ) -> Option<Corridor> {
+        let head = (position, direction);
+        if let Some(corridor) = self.corridors.get(&head) {
+            return *corridor;
+        }
+        let visits = self.visits.entry(head).or_insert(0);
+        *visits += 1;
+        if *visits < HOT_VISITS {
+            return None;
+        }
+        self.visits.remove(&head);
+
+        let corridor = plan(code, position, direction, step)
+            .filter(|plan| plan.ticks >= MIN_CORRIDOR_CELLS)
+            .and_then(|plan| self.compile(&plan));
+        self.corridors.insert(head, corridor);
+        corridor
+    }
+
+    fn compile(&mut self, plan: &CorridorPlan) -> Option<Corridor> {
+        let mut context = self.module.make_context();
+        context.func.signature.params.push(AbiParam::new(self.module.target_config().pointer_type()));
+        context.func.signature.returns.push(AbiParam::new(types::I8));
+
+        let mut function_context = FunctionBuilderContext::new();
+        let mut builder = FunctionBuilder::new(&mut context.func, &mut function_context);
+        let entry = builder.create_block();
+        let overflow = builder.create_block();
+        builder.append_block_params_for_function_params(entry);
+        builder.switch_to_block(entry);
+        builder.seal_block(entry);
+
+        let state = builder.block_params(entry)[0];
+        let flags = MemFlags::trusted();
+        let mut value = builder.ins().load(types::I64, flags, state, 0);
+        let mut min = value;
+        let mut max = value;
+        for effect in &plan.effects {
+            value = match *effect {
+                Effect::Set(digit) => builder.ins().iconst(types::I64, digit as i64),
+                Effect::Increment => checked_step(&mut builder, value, i64::MAX, 1, overflow),
+                Effect::Decrement => checked_step(&mut builder, value, i64::MIN, -1, overflow),
+            };
+            let below = builder.ins().icmp(IntCC::SignedLessThan, value, min);
+            min = builder.ins().select(below, value, min);
+            let above = builder.ins().icmp(IntCC::SignedGreaterThan, value, max);
+            max = builder.ins().select(above, value, max);
+        }
+        builder.ins().store(flags, value, state, 0);
+        builder.ins().store(flags, min, state, 8);
+        builder.ins().store(flags, max, state, 16);
+        let done = builder.ins().iconst(types::I8, 1);
+        builder.ins().return_(&[done]);
+
+        builder.switch_to_block(overflow);
+        builder.seal_block(overflow);
+        let failed = builder.ins().iconst(types::I8, 0);
+        builder.ins().return_(&[failed]);
+        builder.finalize();
+
+        let id = self.module.declare_anonymous_function(&context.func.signature).ok()?;
+        self.module.define_function(id, &mut context).ok()?;
+        self.module.clear_context(&mut context);
+        self.module.finalize_definitions().ok()?;
+        let code = self.module.get_finalized_function(id);
+        // SAFETY: the function was just built with exactly this signature
+        let run = unsafe { std::mem::transmute::<*const u8, extern "C" fn(*mut i64) -> u8>(code) };
+        Some(Corridor {
+            exit: plan.exit,
+            direction: plan.direction,
+            ticks: plan.ticks,
+            stack_ops: plan.stack_ops,
+            changes_value: !plan.effects.is_empty(),
+            run,
+        })
+    }
+}
+
+/// `value + delta`, branching to `overflow` when `value` is already `limit`
+fn checked_step(builder: &mut FunctionBuilder, value: Value, limit: i64, delta: i64, overflow: cranelift_codegen::ir::Block) -> Value {
+    let at_limit = builder.ins().icmp_imm(IntCC::Equal, value, limit);
+    let next = builder.create_block();
+    builder.ins().brif(at_limit, overflow, &[], next, &[]);
+    builder.switch_to_block(next);
+    builder.seal_block(next);
+    builder.ins().iadd_imm(value, delta)
+}
+
+/// Cells a droplet at `position` heading `direction` crosses before reaching
+/// one whose outcome depends on more than the droplet's path
+fn plan(
+    code: &Bytecode,
+    mut position: Coordinate,
+    mut direction: Direction,
+    step: impl Fn(Coordinate, Direction) -> Coordinate,
+) -> Option<CorridorPlan> {
+    let mut effects = Vec::new();
+    let mut ticks = 0;
+    let mut stack_ops = 0;
+    let mut seen = HashSet::new();
+    while (ticks as usize) < MAX_CORRIDOR_CELLS && seen.insert((position, direction)) {
+        direction = match code.op(position) {
+            Op::Pass => direction,
+            Op::Up => Direction::Up,
+            Op::Mirror => match direction {
+                Direction::Right => Direction::Up,
+                Direction::Down => Direction::Left,
+                Direction::Left => Direction::Down,
+                Direction::Up => Direction::Right,
+            },
+            Op::Digit(digit) => {
+                effects.push(Effect::Set(digit));
+                direction
+            }
+            Op::Arithmetic(symbol @ ('+' | '~')) => {
+                effects.push(if symbol == '+' { Effect::Increment } else { Effect::Decrement });
+                stack_ops += 1;
+                direction
+            }
+            _ => break,
+        };
+        position = step(position, direction);
+        ticks += 1;
+    }
+    (ticks > 0).then_some(CorridorPlan { effects, exit: position, direction, ticks, stack_ops })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    fn bytecode(source: &str) -> Bytecode {
+        Bytecode::compile(&GridParser::new().parse_string(source).unwrap())
+    }
+
+    #[test]
+    fn test_corridors_stop_at_junctions_and_io() {
+        let code = bytecode("@\n7\n+\n/\n");
+        let corridor = plan(&code, Coordinate::new(0, 0), Direction::Down, |position, direction| position + direction).unwrap();
+        assert_eq!(corridor.effects, vec![Effect::Set(7), Effect::Increment]);
+        // `/` turns the droplet left, off the grid
+        assert_eq!((corridor.exit, corridor.direction, corridor.ticks, corridor.stack_ops), (Coordinate::new(-1, 3), Direction::Left, 4, 1));
+
+        let code = bytecode("@\n|\n\\\nn");
+        let corridor = plan(&code, Coordinate::new(0, 0), Direction::Down, |position, direction| position + direction).unwrap();
+        assert_eq!((corridor.exit, corridor.ticks), (Coordinate::new(0, 2), 2));
+    }
+
+    #[test]
+    fn test_hot_corridors_run_natively() {
+        let code = bytecode("@\n9\n+\n~\n~\nn");
+        let Some(mut jit) = CorridorJit::new() else { return };
+        let step = |position: Coordinate, direction| position + direction;
+        assert!(jit.corridor(&code, Coordinate::new(0, 0), Direction::Down, step).is_none());
+        let corridor = jit.corridor(&code, Coordinate::new(0, 0), Direction::Down, step).unwrap();
+        assert_eq!((corridor.exit, corridor.ticks), (Coordinate::new(0, 5), 5));
+        assert_eq!(corridor.run(0), Some(CorridorRun { value: 8, min: 0, max: 10 }));
+
+        let code = bytecode("@\n+\n+\nn");
+        let mut jit = CorridorJit::new().unwrap();
+        jit.corridor(&code, Coordinate::new(0, 0), Direction::Down, step);
+        let corridor = jit.corridor(&code, Coordinate::new(0, 0), Direction::Down, step).unwrap();
+        assert_eq!(corridor.run(i64::MAX - 1), None);
+        assert_eq!(corridor.run(-1).map(|run| run.value), Some(1));
+    }
+}
@@ -1,9 +1,11 @@
 use crate::types::coordinate::Coordinate;
-use crate::types::bigint::TubularBigInt;
-use std::collections::HashMap;
-use std::fmt;
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+use core::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReservoirCoordinate {
     /// X coordinate (can be negative)
     pub x: isize,
@@ -38,34 +40,63 @@ impl From<ReservoirCoordinate> for Coordinate {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Reservoir {
     /// Sparse storage for memory cells
-    pub data: HashMap<ReservoirCoordinate, TubularBigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "cell_list"))]
+    pub data: BTreeMap<ReservoirCoordinate, TubularNumber>,
+    /// Previous values of cells written since the journal was started (None = not recording)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    journal: Option<Vec<ReservoirWrite>>,
 }
 
+/// Reservoir cells as a list of pairs, since most formats only allow string map keys
+#[cfg(feature = "serde")]
+mod cell_list {
+    use super::{ReservoirCoordinate, TubularNumber};
+    use crate::types::prelude::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use alloc::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(data: &BTreeMap<ReservoirCoordinate, TubularNumber>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut cells: Vec<_> = data.iter().collect();
+        cells.sort_by_key(|(coord, _)| (coord.y, coord.x));
+        cells.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<ReservoirCoordinate, TubularNumber>, D::Error> {
+        let cells: Vec<(ReservoirCoordinate, TubularNumber)> = Vec::deserialize(deserializer)?;
+        Ok(cells.into_iter().collect())
+    }
+}
+
+/// Previous contents of a reservoir cell before a recorded write
+pub type ReservoirWrite = (ReservoirCoordinate, Option<TubularNumber>);
+
 impl Reservoir {
     pub fn new() -> Self {
         Reservoir {
-            data: HashMap::new(),
+            data: BTreeMap::new(),
+            journal: None,
         }
     }
 
-    pub fn with_capacity(capacity: usize) -> Self {
-        Reservoir {
-            data: HashMap::with_capacity(capacity),
-        }
+    /// Same as `new`: cells live in a sorted map, which has nothing to reserve
+    pub fn with_capacity(_capacity: usize) -> Self {
+        Self::new()
     }
 
-    pub fn get(&self, coord: ReservoirCoordinate) -> TubularBigInt {
-        self.data.get(&coord).cloned().unwrap_or_else(|| TubularBigInt::zero())
+    pub fn get(&self, coord: ReservoirCoordinate) -> TubularNumber {
+        self.data.get(&coord).cloned().unwrap_or_else(|| TubularNumber::zero())
     }
 
-    pub fn put(&mut self, coord: ReservoirCoordinate, value: TubularBigInt) -> TubularBigInt {
-        self.data.insert(coord, value.clone());
+    pub fn put(&mut self, coord: ReservoirCoordinate, value: TubularNumber) -> TubularNumber {
+        let previous = self.data.insert(coord.clone(), value.clone());
+        self.record(coord, previous);
         value
     }
 
-    pub fn get_or_zero(&self, coord: ReservoirCoordinate) -> TubularBigInt {
+    pub fn get_or_zero(&self, coord: ReservoirCoordinate) -> TubularNumber {
         self.get(coord)
     }
 
@@ -73,11 +104,18 @@ impl Reservoir {
         self.data.contains_key(coord)
     }
 
-    pub fn remove(&mut self, coord: &ReservoirCoordinate) -> Option<TubularBigInt> {
-        self.data.remove(coord)
+    pub fn remove(&mut self, coord: &ReservoirCoordinate) -> Option<TubularNumber> {
+        let previous = self.data.remove(coord);
+        if previous.is_some() {
+            self.record(coord.clone(), previous.clone());
+        }
+        previous
     }
 
     pub fn clear(&mut self) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.extend(core::mem::take(&mut self.data).into_iter().map(|(coord, value)| (coord, Some(value))));
+        }
         self.data.clear();
     }
 
@@ -93,11 +131,11 @@ impl Reservoir {
         self.len()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&ReservoirCoordinate, &TubularBigInt)> {
+    pub fn iter(&self) -> impl Iterator<Item = (&ReservoirCoordinate, &TubularNumber)> {
         self.data.iter()
     }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&ReservoirCoordinate, &mut TubularBigInt)> {
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&ReservoirCoordinate, &mut TubularNumber)> {
         self.data.iter_mut()
     }
 
@@ -105,15 +143,15 @@ impl Reservoir {
         self.data.keys()
     }
 
-    pub fn values(&self) -> impl Iterator<Item = &TubularBigInt> {
+    pub fn values(&self) -> impl Iterator<Item = &TubularNumber> {
         self.data.values()
     }
 
-    pub fn into_values(self) -> impl Iterator<Item = TubularBigInt> {
+    pub fn into_values(self) -> impl Iterator<Item = TubularNumber> {
         self.data.into_values()
     }
 
-    pub fn get_adjacent(&self, coord: ReservoirCoordinate) -> [(TubularBigInt, ReservoirCoordinate); 8] {
+    pub fn get_adjacent(&self, coord: ReservoirCoordinate) -> [(TubularNumber, ReservoirCoordinate); 8] {
         [
             (self.get(ReservoirCoordinate::new(coord.x - 1, coord.y - 1)), ReservoirCoordinate::new(coord.x - 1, coord.y - 1)),
             (self.get(ReservoirCoordinate::new(coord.x, coord.y - 1)), ReservoirCoordinate::new(coord.x, coord.y - 1)),
@@ -156,8 +194,110 @@ impl Reservoir {
     }
 
     pub fn filter_zero_values(&mut self) {
-        self.data.retain(|_, value| !value.is_zero());
+        let journal = &mut self.journal;
+        self.data.retain(|coord, value| {
+            if value.is_zero() {
+                if let Some(journal) = journal.as_mut() {
+                    journal.push((coord.clone(), Some(value.clone())));
+                }
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Set every cell of the `width` by `height` rectangle whose top-left corner is `origin` to `value`
+    pub fn fill_region(&mut self, origin: ReservoirCoordinate, width: usize, height: usize, value: TubularNumber) {
+        for coord in region(&origin, width, height) {
+            self.put(coord, value.clone());
+        }
+    }
+
+    /// Empty every cell of the `width` by `height` rectangle whose top-left corner is `origin`
+    pub fn clear_region(&mut self, origin: ReservoirCoordinate, width: usize, height: usize) {
+        for coord in self.cells_in_region(&origin, width, height) {
+            self.remove(&coord);
+        }
+    }
+
+    /// Copy the `width` by `height` rectangle at `from` so its top-left corner lands on `to`
+    ///
+    /// Empty source cells empty their destinations, and the source is read in
+    /// full before anything is written, so overlapping rectangles copy as if
+    /// through a buffer.
+    pub fn copy_region(&mut self, from: ReservoirCoordinate, width: usize, height: usize, to: ReservoirCoordinate) {
+        let cells: Vec<_> = self.cells_in_region(&from, width, height).into_iter()
+            .map(|coord| {
+                let value = self.get(coord.clone());
+                (coord.x.wrapping_sub(from.x), coord.y.wrapping_sub(from.y), value)
+            })
+            .collect();
+        self.clear_region(to.clone(), width, height);
+        for (dx, dy, value) in cells {
+            self.put(ReservoirCoordinate::new(to.x.wrapping_add(dx), to.y.wrapping_add(dy)), value);
+        }
+    }
+
+    /// Number of stored cells inside the `width` by `height` rectangle whose top-left corner is `origin`
+    pub fn count_in_region(&self, origin: &ReservoirCoordinate, width: usize, height: usize) -> usize {
+        self.cells_in_region(origin, width, height).len()
+    }
+
+    /// Stored cells inside a rectangle, found by whichever of the rectangle
+    /// and the stored cells is smaller
+    fn cells_in_region(&self, origin: &ReservoirCoordinate, width: usize, height: usize) -> Vec<ReservoirCoordinate> {
+        if width.saturating_mul(height) <= self.data.len() {
+            region(origin, width, height).filter(|coord| self.data.contains_key(coord)).collect()
+        } else {
+            self.data.keys()
+                .filter(|coord| {
+                    (coord.x.wrapping_sub(origin.x) as usize) < width && (coord.y.wrapping_sub(origin.y) as usize) < height
+                })
+                .cloned()
+                .collect()
+        }
+    }
+
+    /// Start recording writes so they can later be undone
+    ///
+    /// Writes made through `iter_mut` are not recorded.
+    pub fn start_journal(&mut self) {
+        self.journal = Some(Vec::new());
     }
+
+    /// Stop recording and return the writes made since `start_journal`
+    pub fn take_journal(&mut self) -> Vec<ReservoirWrite> {
+        self.journal.take().unwrap_or_default()
+    }
+
+    /// Revert writes previously returned by `take_journal`
+    pub fn undo(&mut self, writes: Vec<ReservoirWrite>) {
+        for (coord, previous) in writes.into_iter().rev() {
+            match previous {
+                Some(value) => {
+                    self.data.insert(coord, value);
+                }
+                None => {
+                    self.data.remove(&coord);
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, coord: ReservoirCoordinate, previous: Option<TubularNumber>) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push((coord, previous));
+        }
+    }
+}
+
+/// Coordinates of a rectangle, row by row
+fn region(origin: &ReservoirCoordinate, width: usize, height: usize) -> impl Iterator<Item = ReservoirCoordinate> + use<> {
+    let (x, y) = (origin.x, origin.y);
+    (0..height).flat_map(move |dy| {
+        (0..width).map(move |dx| ReservoirCoordinate::new(x.wrapping_add(dx as isize), y.wrapping_add(dy as isize)))
+    })
 }
 
 impl Default for Reservoir {
@@ -186,8 +326,51 @@ impl fmt::Display for Reservoir {
     }
 }
 
-impl From<HashMap<ReservoirCoordinate, TubularBigInt>> for Reservoir {
-    fn from(data: HashMap<ReservoirCoordinate, TubularBigInt>) -> Self {
-        Reservoir { data }
+impl From<BTreeMap<ReservoirCoordinate, TubularNumber>> for Reservoir {
+    fn from(data: BTreeMap<ReservoirCoordinate, TubularNumber>) -> Self {
+        Reservoir { data, journal: None }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(x: isize, y: isize) -> ReservoirCoordinate {
+        ReservoirCoordinate::new(x, y)
+    }
+
+    #[test]
+    fn test_regions_fill_clear_and_copy() {
+        let mut reservoir = Reservoir::new();
+        reservoir.fill_region(cell(0, 0), 3, 2, TubularNumber::new(7));
+        assert_eq!(reservoir.len(), 6);
+        assert_eq!(reservoir.get(cell(2, 1)), TubularNumber::new(7));
+        assert!(!reservoir.contains(&cell(3, 1)));
+
+        reservoir.clear_region(cell(1, 0), 1, 2);
+        assert_eq!(reservoir.len(), 4);
+        assert!(!reservoir.contains(&cell(1, 1)));
+
+        // The copy overlaps its source, and the hole at (1, y) moves with it
+        reservoir.put(cell(0, 0), TubularNumber::new(1));
+        reservoir.copy_region(cell(0, 0), 3, 2, cell(1, 0));
+        assert_eq!(reservoir.get(cell(1, 0)), TubularNumber::new(1));
+        assert!(!reservoir.contains(&cell(2, 0)));
+        assert_eq!(reservoir.get(cell(3, 1)), TubularNumber::new(7));
+        assert_eq!(reservoir.get(cell(0, 0)), TubularNumber::new(1));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_region_writes_are_journaled() {
+        let mut reservoir = Reservoir::new();
+        reservoir.put(cell(-1, -1), TubularNumber::new(5));
+        let before = reservoir.data.clone();
+        reservoir.start_journal();
+        reservoir.fill_region(cell(-2, -2), 4, 4, TubularNumber::new(9));
+        reservoir.copy_region(cell(-2, -2), 2, 2, cell(100, 100));
+        reservoir.clear_region(cell(-2, -2), 1000, 1000);
+        let journal = reservoir.take_journal();
+        reservoir.undo(journal);
+        assert_eq!(reservoir.data, before);
+    }
+}
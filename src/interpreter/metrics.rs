@@ -0,0 +1,66 @@
+use crate::types::prelude::*;
+
+/// Running counters kept by the interpreter for every execution
+///
+/// The counters are always on and cheap to update, so services embedding the
+/// interpreter can poll them between ticks or export them once a run ends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metrics {
+    /// Ticks executed
+    pub ticks: u64,
+    pub droplets_created: u64,
+    /// Droplets removed by collisions, '!' or leaving the grid
+    pub droplets_destroyed: u64,
+    /// Cells where two or more droplets collided, counted once per tick
+    pub collisions: u64,
+    /// Arithmetic, stack and pseudo-input operations executed
+    pub stack_ops: u64,
+    /// Character and numeric input and output operations executed
+    pub io_ops: u64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counters as (name, help, value), in export order
+    pub fn counters(&self) -> [(&'static str, &'static str, u64); 6] {
+        [
+            ("ticks", "Ticks executed", self.ticks),
+            ("droplets_created", "Droplets created", self.droplets_created),
+            ("droplets_destroyed", "Droplets destroyed by collisions, '!' or leaving the grid", self.droplets_destroyed),
+            ("collisions", "Collisions between droplets", self.collisions),
+            ("stack_ops", "Stack and arithmetic operations executed", self.stack_ops),
+            ("io_ops", "Input and output operations executed", self.io_ops),
+        ]
+    }
+
+    /// Counters in the Prometheus text exposition format
+    pub fn to_prometheus(self) -> String {
+        let mut text = String::new();
+        for (name, help, value) in self.counters() {
+            text.push_str(&format!("# HELP tubular_{}_total {}\n", name, help));
+            text.push_str(&format!("# TYPE tubular_{}_total counter\n", name));
+            text.push_str(&format!("tubular_{}_total {}\n", name, value));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_output_lists_every_counter() {
+        let metrics = Metrics { ticks: 7, io_ops: 2, ..Metrics::new() };
+        let text = metrics.to_prometheus();
+
+        assert!(text.starts_with("# HELP tubular_ticks_total Ticks executed\n# TYPE tubular_ticks_total counter\ntubular_ticks_total 7\n"));
+        assert!(text.contains("\ntubular_io_ops_total 2\n"));
+        assert!(text.contains("\ntubular_collisions_total 0\n"));
+        assert_eq!(text.lines().filter(|line| !line.starts_with('#')).count(), 6);
+    }
+}
@@ -1,15 +1,58 @@
 pub mod droplet;
+pub mod arena;
 pub mod grid;
+pub mod bytecode;
+pub mod optimizer;
+#[cfg(feature = "jit")]
+pub mod jit;
 pub mod stack;
 pub mod memory;
 pub mod subroutines;
+pub mod portals;
 pub mod execution;
 pub mod collision;
+pub mod program;
+pub mod history;
+pub mod scheduler;
+pub mod pseudo_input;
+pub mod random;
+pub mod arguments;
+pub mod snapshot;
+pub mod breakpoint;
+pub mod watch;
+pub mod profiler;
+pub mod metrics;
+pub mod builder;
+pub mod interrupt;
+pub(crate) mod clock;
+pub mod ticks;
+pub mod trace;
 
 pub use droplet::*;
+pub use arena::*;
 pub use grid::*;
+pub use bytecode::*;
+pub use optimizer::*;
+#[cfg(feature = "jit")]
+pub use jit::*;
 pub use stack::*;
 pub use memory::*;
 pub use subroutines::*;
+pub use portals::*;
 pub use execution::*;
-pub use collision::*;
\ No newline at end of file
+pub use collision::*;
+pub use program::*;
+pub use history::*;
+pub use scheduler::*;
+pub use pseudo_input::*;
+pub use random::*;
+pub use arguments::*;
+pub use snapshot::*;
+pub use breakpoint::*;
+pub use watch::*;
+pub use profiler::*;
+pub use metrics::*;
+pub use builder::*;
+pub use interrupt::*;
+pub use ticks::*;
+pub use trace::*;
\ No newline at end of file
@@ -0,0 +1,176 @@
+use crate::interpreter::bytecode::{Bytecode, Op};
+use crate::interpreter::droplet::Droplet;
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::stack::DataStack;
+use crate::operations::arithmetic::ArithmeticOperations;
+use crate::operations::flow_control::FlowControlOperations;
+use crate::parser::flow::StaticFlow;
+use crate::types::number::TubularNumber;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use crate::types::prelude::*;
+use alloc::collections::BTreeSet;
+use core::fmt;
+
+/// Longest run of cells folded into one step
+const MAX_FOLD_CELLS: u64 = 4096;
+
+/// A fixed path starting at a digit whose stack arithmetic only ever pops
+/// values pushed on the same path, so its outcome is known before the run
+///
+/// Running a fold leaves the droplet at `exit` holding `value` with `pushes`
+/// added to the stack, exactly as interpreting its `ticks` cells would.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fold {
+    /// Digit cell the path starts on
+    pub head: Coordinate,
+    pub heading: Direction,
+    /// Symbols of the folded cells, in path order
+    pub symbols: String,
+    /// Droplet value at the exit
+    pub value: TubularNumber,
+    /// Values left on the stack, bottom first
+    pub pushes: Vec<TubularNumber>,
+    /// Cell the droplet is on when the fold ends
+    pub exit: Coordinate,
+    pub direction: Direction,
+    /// Ticks the interpreter would have taken
+    pub ticks: u64,
+    /// Stack operations on the way, for the run's metrics
+    pub stack_ops: u64,
+    /// Most values the path has on the stack at once
+    pub peak_depth: usize,
+    /// Most decimal digits of any droplet value on the way
+    pub peak_digits: usize,
+}
+
+impl fmt::Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} heading {}: {} -> {} ({} cells", self.head, self.heading, self.symbols, self.value, self.ticks)?;
+        if !self.pushes.is_empty() {
+            let pushes: Vec<_> = self.pushes.iter().map(ToString::to_string).collect();
+            write!(f, ", leaves [{}] on the stack", pushes.join(", "))?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Constant folds for every digit a droplet can reach heading the way it can reach it
+///
+/// `step` gives the cell a droplet moves to, so wrapping is the caller's.
+/// Folds are sorted by head, top to bottom.
+pub fn fold_constants(
+    grid: &ProgramGrid,
+    code: &Bytecode,
+    step: impl Fn(Coordinate, Direction) -> Coordinate,
+) -> Vec<Fold> {
+    let mut folds: Vec<_> = StaticFlow::reachable_states(grid).into_iter()
+        .filter(|(coord, _)| matches!(code.op(*coord), Op::Digit(_)))
+        .filter_map(|(coord, direction)| fold(grid, code, coord, direction, &step))
+        .collect();
+    folds.sort_by_key(|fold| (fold.head.y, fold.head.x, fold.heading as u8));
+    folds
+}
+
+/// Values an arithmetic operation pops off the stack
+fn pops(symbol: char) -> usize {
+    match symbol {
+        ':' | '+' | '~' | 'N' | 't' => 0,
+        ';' | 'd' | '_' => 1,
+        _ => 2,
+    }
+}
+
+/// Follow the path from a digit for as long as every value involved is known
+fn fold(
+    grid: &ProgramGrid,
+    code: &Bytecode,
+    head: Coordinate,
+    heading: Direction,
+    step: impl Fn(Coordinate, Direction) -> Coordinate,
+) -> Option<Fold> {
+    let mut droplet = Droplet::new(0, head, heading);
+    let mut stack = DataStack::new();
+    let mut symbols = String::new();
+    let (mut ticks, mut stack_ops, mut peak_digits) = (0, 0, 1);
+    let mut folded = false;
+    let mut seen = BTreeSet::new();
+    while ticks < MAX_FOLD_CELLS && seen.insert((droplet.position, droplet.direction)) {
+        let direction = match code.op(droplet.position) {
+            Op::Pass => droplet.direction,
+            Op::Up => Direction::Up,
+            Op::Mirror => match droplet.direction {
+                Direction::Right => Direction::Up,
+                Direction::Down => Direction::Left,
+                Direction::Left => Direction::Down,
+                Direction::Up => Direction::Right,
+            },
+            Op::Branch => FlowControlOperations::process_conditional_branch(&droplet, droplet.direction),
+            Op::Digit(digit) => {
+                droplet.set_value(TubularNumber::new(digit as i64));
+                droplet.direction
+            }
+            Op::Arithmetic(symbol) if pops(symbol) <= stack.depth() => {
+                ArithmeticOperations::process_stack_operation(symbol, &mut droplet, &mut stack).ok()?;
+                folded |= pops(symbol) > 0;
+                stack_ops += 1;
+                droplet.direction
+            }
+            _ => break,
+        };
+        symbols.extend(grid.get_symbol(droplet.position));
+        droplet.set_direction(direction);
+        droplet.move_to(step(droplet.position, direction));
+        peak_digits = peak_digits.max(droplet.value.decimal_digits());
+        ticks += 1;
+    }
+    folded.then(|| Fold {
+        head,
+        heading,
+        symbols,
+        value: droplet.value.clone(),
+        pushes: stack.data.clone(),
+        exit: droplet.position,
+        direction: droplet.direction,
+        ticks,
+        stack_ops,
+        peak_depth: stack.max_depth,
+        peak_digits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    fn folds(source: &str) -> Vec<Fold> {
+        let grid = GridParser::new().parse_string(source).unwrap();
+        fold_constants(&grid, &Bytecode::compile(&grid), |position, direction| position + direction)
+    }
+
+    #[test]
+    fn test_digit_arithmetic_on_fixed_paths_folds() {
+        let folds = folds("@\n7\n:\n3\n:\nA\n:\n2\n:\nM\nn");
+        assert_eq!(folds.len(), 1);
+        let fold = &folds[0];
+        assert_eq!((fold.head, fold.heading), (Coordinate::new(0, 1), Direction::Down));
+        assert_eq!(fold.symbols, "7:3:A:2:M");
+        assert_eq!(fold.value, TubularNumber::new(20));
+        assert!(fold.pushes.is_empty());
+        assert_eq!((fold.exit, fold.ticks, fold.stack_ops, fold.peak_depth), (Coordinate::new(0, 10), 9, 6, 2));
+        assert_eq!(fold.to_string(), "(0, 1) heading v: 7:3:A:2:M -> 20 (9 cells)");
+    }
+
+    #[test]
+    fn test_folds_stop_at_unknown_stack_values_and_dynamic_cells() {
+        // `A` would pop a value pushed before the path started
+        assert!(folds("@\n7\n:\nA\nn").is_empty());
+        // The branch is decided by the known value; the leftover push is reported
+        let folds = folds("@\n4\n:\n:\n;\n1\n\\\nn");
+        assert_eq!(folds[0].symbols, "4::;1\\");
+        assert_eq!(folds[0].pushes, vec![TubularNumber::new(4)]);
+        assert_eq!((folds[0].exit, folds[0].direction), (Coordinate::new(1, 6), Direction::Right));
+        assert!(folds[0].to_string().ends_with("(6 cells, leaves [4] on the stack)"));
+    }
+}
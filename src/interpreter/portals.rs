@@ -0,0 +1,96 @@
+use alloc::collections::BTreeMap;
+use crate::types::coordinate::Coordinate;
+use crate::types::prelude::*;
+
+/// Portal cells (`J`) and the names that pair them, from `%portal` directives
+///
+/// A droplet entering a portal leaves from the other end of its pair, keeping
+/// its heading. Only names with exactly two ends make a working pair; the
+/// validator reports the others.
+#[derive(Debug, Clone, Default)]
+pub struct PortalTable {
+    /// Name of the portal at each cell
+    names: BTreeMap<Coordinate, String>,
+    /// Cells of each name, in the order they were named
+    ends: BTreeMap<String, Vec<Coordinate>>,
+}
+
+impl PortalTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the cell at `coord` an end of portal `name`; returns false if the
+    /// cell already belongs to a portal
+    pub fn bind(&mut self, coord: Coordinate, name: &str) -> bool {
+        if self.names.contains_key(&coord) {
+            return false;
+        }
+        self.names.insert(coord, name.to_string());
+        self.ends.entry(name.to_string()).or_default().push(coord);
+        true
+    }
+
+    /// Name of the portal at `coord`, if the cell is one
+    pub fn name(&self, coord: Coordinate) -> Option<&str> {
+        self.names.get(&coord).map(String::as_str)
+    }
+
+    /// Cell a droplet entering the portal at `coord` leaves from: the other
+    /// end of its pair, if the name has exactly two ends
+    pub fn exit(&self, coord: Coordinate) -> Option<Coordinate> {
+        match *self.ends.get(self.name(coord)?)?.as_slice() {
+            [a, b] if a == coord => Some(b),
+            [a, b] if b == coord => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Portal cells and their names, ordered by position
+    pub fn portals(&self) -> Vec<(Coordinate, &str)> {
+        let mut portals: Vec<_> = self.names.iter().map(|(coord, name)| (*coord, name.as_str())).collect();
+        portals.sort_by_key(|(coord, _)| (coord.y, coord.x));
+        portals
+    }
+
+    /// Number of cells named `name`
+    pub fn end_count(&self, name: &str) -> usize {
+        self.ends.get(name).map_or(0, Vec::len)
+    }
+
+    /// Copy every portal from `other`, shifted by `offset`
+    ///
+    /// Returns the first cell that already belonged to a portal, if any.
+    pub fn merge(&mut self, other: &PortalTable, offset: Coordinate) -> Option<Coordinate> {
+        for (coord, name) in other.portals() {
+            let coord = Coordinate::new(coord.x + offset.x, coord.y + offset.y);
+            if !self.bind(coord, name) {
+                return Some(coord);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_pairs_have_exits() {
+        let mut portals = PortalTable::new();
+        assert!(portals.bind(Coordinate::new(0, 1), "a"));
+        assert!(portals.bind(Coordinate::new(5, 3), "a"));
+        assert!(portals.bind(Coordinate::new(2, 2), "lonely"));
+        assert!(!portals.bind(Coordinate::new(2, 2), "b"));
+
+        assert_eq!(portals.exit(Coordinate::new(0, 1)), Some(Coordinate::new(5, 3)));
+        assert_eq!(portals.exit(Coordinate::new(5, 3)), Some(Coordinate::new(0, 1)));
+        assert_eq!(portals.exit(Coordinate::new(2, 2)), None);
+        assert_eq!(portals.exit(Coordinate::new(9, 9)), None);
+
+        portals.bind(Coordinate::new(7, 0), "a");
+        assert_eq!(portals.end_count("a"), 3);
+        assert_eq!(portals.exit(Coordinate::new(0, 1)), None);
+    }
+}
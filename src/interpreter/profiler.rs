@@ -0,0 +1,85 @@
+use crate::types::coordinate::Coordinate;
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+
+/// Visits and time attributed to one cell or operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileEntry {
+    pub symbol: char,
+    /// Number of times a droplet was processed on this cell
+    pub visits: u64,
+    /// Time spent computing and applying those droplets' commands
+    pub time: Duration,
+}
+
+impl ProfileEntry {
+    fn new(symbol: char) -> Self {
+        ProfileEntry { symbol, visits: 0, time: Duration::ZERO }
+    }
+}
+
+/// Per-cell execution counters collected while profiling
+///
+/// Time includes waiting for input, so interactive programs show their
+/// input cells as hot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionProfile {
+    cells: BTreeMap<Coordinate, ProfileEntry>,
+}
+
+impl ExecutionProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_visit(&mut self, coord: Coordinate, symbol: char) {
+        self.cells.entry(coord).or_insert_with(|| ProfileEntry::new(symbol)).visits += 1;
+    }
+
+    /// Add time to a cell already recorded by `record_visit`
+    pub fn record_time(&mut self, coord: Coordinate, time: Duration) {
+        if let Some(entry) = self.cells.get_mut(&coord) {
+            entry.time += time;
+        }
+    }
+
+    pub fn cell(&self, coord: Coordinate) -> Option<&ProfileEntry> {
+        self.cells.get(&coord)
+    }
+
+    pub fn total_visits(&self) -> u64 {
+        self.cells.values().map(|entry| entry.visits).sum()
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.cells.values().map(|entry| entry.time).sum()
+    }
+
+    /// The `n` cells with the most time, ties broken by visits then row and column
+    pub fn hottest_cells(&self, n: usize) -> Vec<(Coordinate, ProfileEntry)> {
+        let mut cells: Vec<(Coordinate, ProfileEntry)> = self.cells.iter().map(|(c, e)| (*c, *e)).collect();
+        cells.sort_by(|a, b| {
+            b.1.time.cmp(&a.1.time)
+                .then(b.1.visits.cmp(&a.1.visits))
+                .then(a.0.y.cmp(&b.0.y))
+                .then(a.0.x.cmp(&b.0.x))
+        });
+        cells.truncate(n);
+        cells
+    }
+
+    /// Totals per symbol, most time first
+    pub fn by_operation(&self) -> Vec<ProfileEntry> {
+        let mut operations: BTreeMap<char, ProfileEntry> = BTreeMap::new();
+        for entry in self.cells.values() {
+            let total = operations.entry(entry.symbol).or_insert_with(|| ProfileEntry::new(entry.symbol));
+            total.visits += entry.visits;
+            total.time += entry.time;
+        }
+
+        let mut operations: Vec<ProfileEntry> = operations.into_values().collect();
+        operations.sort_by(|a, b| b.time.cmp(&a.time).then(b.visits.cmp(&a.visits)).then(a.symbol.cmp(&b.symbol)));
+        operations
+    }
+}
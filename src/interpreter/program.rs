@@ -0,0 +1,91 @@
+use crate::types::coordinate::Coordinate;
+use crate::types::error::{Result, InterpreterError, InitError};
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::execution::TubularInterpreter;
+use alloc::sync::Arc;
+
+/// A validated, immutable program that can be instantiated many times
+///
+/// Compiling validates the grid and locates the start symbol once; every
+/// interpreter created by `instantiate` shares the same grid through an `Arc`
+/// instead of cloning it.
+#[derive(Debug, Clone)]
+pub struct Program {
+    grid: Arc<ProgramGrid>,
+    start: Coordinate,
+}
+
+impl Program {
+    /// Validate a parsed grid and prepare it for execution
+    pub fn compile(grid: ProgramGrid) -> Result<Self> {
+        Self::compile_shared(Arc::new(grid))
+    }
+
+    /// Validate a grid that is already shared
+    pub fn compile_shared(grid: Arc<ProgramGrid>) -> Result<Self> {
+        grid.validate()?;
+
+        let start = grid.start.ok_or(InterpreterError::Initialization(
+            InitError::NoStartSymbol
+        ))?;
+
+        Ok(Program { grid, start })
+    }
+
+    /// Create a fresh interpreter for this program
+    pub fn instantiate(&self) -> TubularInterpreter {
+        TubularInterpreter::with_start(Arc::clone(&self.grid), self.start)
+    }
+
+    pub fn grid(&self) -> &ProgramGrid {
+        &self.grid
+    }
+
+    pub fn shared_grid(&self) -> Arc<ProgramGrid> {
+        Arc::clone(&self.grid)
+    }
+
+    pub fn start(&self) -> Coordinate {
+        self.start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+    use std::thread;
+
+    #[test]
+    fn test_instances_share_grid() {
+        let grid = GridParser::new().parse_string("@\n7\nn\n!").unwrap();
+        let program = Program::compile(grid).unwrap();
+
+        let instances: Vec<TubularInterpreter> = (0..10).map(|_| program.instantiate()).collect();
+        assert_eq!(Arc::strong_count(&program.grid), 11);
+        assert!(Arc::ptr_eq(&instances[0].shared_grid(), &program.shared_grid()));
+    }
+
+    #[test]
+    fn test_concurrent_instances_run_independently() {
+        let grid = GridParser::new().parse_string("@\n7\nn\n!").unwrap();
+        let program = Program::compile(grid).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let program = program.clone();
+                thread::spawn(move || program.instantiate().run().unwrap().final_output)
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), "7");
+        }
+    }
+
+    #[test]
+    fn test_compile_requires_start() {
+        let grid = GridParser::new().parse_string("|\n!").unwrap_or_default();
+        assert!(Program::compile(grid).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::types::number::TubularNumber;
+
+/// Deterministic stand-in for program input
+///
+/// A 32-bit linear congruential generator yielding byte values (0-255), so
+/// programs that "read input" produce identical output on every run without
+/// touching stdin. It is deliberately separate from any random number source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PseudoInput {
+    seed: u64,
+    state: u32,
+}
+
+impl PseudoInput {
+    const MULTIPLIER: u32 = 1_664_525;
+    const INCREMENT: u32 = 1_013_904_223;
+
+    pub fn new(seed: u64) -> Self {
+        // Scramble the seed so that nearby seeds start far apart in the sequence
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        PseudoInput {
+            seed,
+            state: (z ^ (z >> 31)) as u32,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Advance the sequence and return the next value
+    pub fn next_value(&mut self) -> TubularNumber {
+        self.state = self.state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(Self::INCREMENT);
+        // The high bits of an LCG are far better distributed than the low ones
+        TubularNumber::new((self.state >> 24) as i64)
+    }
+
+    /// Restart the sequence from its seed
+    pub fn reset(&mut self) {
+        *self = Self::new(self.seed);
+    }
+}
+
+impl Default for PseudoInput {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
@@ -0,0 +1,241 @@
+use crate::interpreter::droplet::{Droplet, DropletId};
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+/// Decides the order in which droplets are processed within a tick
+///
+/// The order matters whenever droplets share state: stack operations, input
+/// reads and output are applied in scheduling order. Movement and collisions
+/// do not depend on it; every droplet moves at the same time once all of
+/// them have been processed.
+pub trait Scheduler: fmt::Debug + Send {
+    /// Short name used in diagnostics and CLI output
+    fn name(&self) -> &'static str;
+
+    /// Indices into `droplets` in the order they should be processed this tick
+    fn order(&mut self, droplets: &[Droplet], tick: u64) -> Vec<usize>;
+}
+
+/// Ascending droplet ID (the historical behaviour)
+#[derive(Debug, Clone, Default)]
+pub struct IdScheduler;
+
+impl Scheduler for IdScheduler {
+    fn name(&self) -> &'static str {
+        "id"
+    }
+
+    fn order(&mut self, droplets: &[Droplet], _tick: u64) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..droplets.len()).collect();
+        order.sort_by_key(|&i| droplets[i].id);
+        order
+    }
+}
+
+/// Order the droplets entered the program, oldest first
+///
+/// The droplet arena keeps insertion order as droplets are removed, so this is
+/// the order they are stored in.
+#[derive(Debug, Clone, Default)]
+pub struct FifoScheduler;
+
+impl Scheduler for FifoScheduler {
+    fn name(&self) -> &'static str {
+        "fifo"
+    }
+
+    fn order(&mut self, droplets: &[Droplet], _tick: u64) -> Vec<usize> {
+        (0..droplets.len()).collect()
+    }
+}
+
+/// Row-major grid position (top to bottom, then left to right), ties broken by ID
+#[derive(Debug, Clone, Default)]
+pub struct PositionScheduler;
+
+impl Scheduler for PositionScheduler {
+    fn name(&self) -> &'static str {
+        "position"
+    }
+
+    fn order(&mut self, droplets: &[Droplet], _tick: u64) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..droplets.len()).collect();
+        order.sort_by_key(|&i| (droplets[i].position.y, droplets[i].position.x, droplets[i].id));
+        order
+    }
+}
+
+/// Shuffled order that is reproducible for a given seed
+#[derive(Debug, Clone)]
+pub struct RandomScheduler {
+    seed: u64,
+    state: u64,
+}
+
+impl RandomScheduler {
+    pub fn new(seed: u64) -> Self {
+        RandomScheduler { seed, state: seed }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// SplitMix64 step; small, fast and good enough for shuffling
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl Scheduler for RandomScheduler {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn order(&mut self, droplets: &[Droplet], _tick: u64) -> Vec<usize> {
+        // Start from ID order so the shuffle does not depend on vector layout
+        let mut order = IdScheduler.order(droplets, 0);
+        for i in (1..order.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            order.swap(i, j);
+        }
+        order
+    }
+}
+
+/// Higher priority first, then ascending ID; unlisted droplets have priority 0
+///
+/// With `phases > 1`, droplets are additionally grouped by `id % phases`, and the
+/// group that goes first rotates every tick.
+#[derive(Debug, Clone, Default)]
+pub struct PriorityScheduler {
+    priorities: BTreeMap<DropletId, i64>,
+    phases: u64,
+}
+
+impl PriorityScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_priority(mut self, droplet: DropletId, priority: i64) -> Self {
+        self.priorities.insert(droplet, priority);
+        self
+    }
+
+    pub fn with_phases(mut self, phases: u64) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    pub fn set_priority(&mut self, droplet: DropletId, priority: i64) {
+        self.priorities.insert(droplet, priority);
+    }
+}
+
+impl Scheduler for PriorityScheduler {
+    fn name(&self) -> &'static str {
+        "priority"
+    }
+
+    fn order(&mut self, droplets: &[Droplet], tick: u64) -> Vec<usize> {
+        let phases = self.phases.max(1);
+        let mut order: Vec<usize> = (0..droplets.len()).collect();
+        order.sort_by_key(|&i| {
+            let droplet = &droplets[i];
+            let priority = self.priorities.get(&droplet.id).copied().unwrap_or(0);
+            let phase = (droplet.id + phases - tick % phases) % phases;
+            (core::cmp::Reverse(priority), phase, droplet.id)
+        });
+        order
+    }
+}
+
+/// Built-in scheduling strategies selectable through `RuntimeOptions`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum SchedulerKind {
+    #[default]
+    Id,
+    Fifo,
+    Position,
+    Random(u64),
+    Priority(BTreeMap<DropletId, i64>),
+}
+
+impl SchedulerKind {
+    /// Scheduler selected by `--schedule`: `fifo`, `id` or `position`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fifo" => Some(SchedulerKind::Fifo),
+            "id" => Some(SchedulerKind::Id),
+            "position" => Some(SchedulerKind::Position),
+            _ => None,
+        }
+    }
+
+    pub fn build(&self) -> Box<dyn Scheduler> {
+        match self {
+            SchedulerKind::Id => Box::new(IdScheduler),
+            SchedulerKind::Fifo => Box::new(FifoScheduler),
+            SchedulerKind::Position => Box::new(PositionScheduler),
+            SchedulerKind::Random(seed) => Box::new(RandomScheduler::new(*seed)),
+            SchedulerKind::Priority(priorities) => Box::new(PriorityScheduler {
+                priorities: priorities.clone(),
+                phases: 1,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::coordinate::Coordinate;
+    use crate::types::direction::Direction;
+
+    fn droplets() -> Vec<Droplet> {
+        vec![
+            Droplet::new(3, Coordinate::new(0, 0), Direction::Down),
+            Droplet::new(1, Coordinate::new(2, 1), Direction::Down),
+            Droplet::new(2, Coordinate::new(1, 0), Direction::Down),
+        ]
+    }
+
+    fn ids(droplets: &[Droplet], order: Vec<usize>) -> Vec<DropletId> {
+        order.into_iter().map(|i| droplets[i].id).collect()
+    }
+
+    #[test]
+    fn test_id_fifo_and_position_orders() {
+        let droplets = droplets();
+        assert_eq!(ids(&droplets, IdScheduler.order(&droplets, 0)), vec![1, 2, 3]);
+        assert_eq!(ids(&droplets, FifoScheduler.order(&droplets, 0)), vec![3, 1, 2]);
+        assert_eq!(ids(&droplets, PositionScheduler.order(&droplets, 0)), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_random_order_is_reproducible() {
+        let droplets = droplets();
+        let mut a = RandomScheduler::new(42);
+        let mut b = RandomScheduler::new(42);
+        for tick in 0..10 {
+            assert_eq!(a.order(&droplets, tick), b.order(&droplets, tick));
+        }
+    }
+
+    #[test]
+    fn test_priority_and_phases() {
+        let droplets = droplets();
+        let mut scheduler = PriorityScheduler::new().with_priority(3, 5);
+        assert_eq!(ids(&droplets, scheduler.order(&droplets, 0)), vec![3, 1, 2]);
+
+        let mut phased = PriorityScheduler::new().with_phases(2);
+        assert_eq!(ids(&droplets, phased.order(&droplets, 0)), vec![2, 1, 3]);
+        assert_eq!(ids(&droplets, phased.order(&droplets, 1)), vec![1, 3, 2]);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::types::coordinate::Coordinate;
+use crate::interpreter::execution::{ExecutionState, ExecutionWarning};
+use crate::interpreter::metrics::Metrics;
+use crate::types::prelude::*;
+
+/// Everything needed to resume an interpreter later
+///
+/// The program itself is not stored; a snapshot is restored onto an
+/// interpreter built from the same grid, which is checked against `program`.
+/// Undo history, trace events and progress reports are not captured.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InterpreterSnapshot {
+    /// Text of the program the snapshot was taken from
+    pub program: String,
+    pub state: ExecutionState,
+    pub warnings_issued: Vec<ExecutionWarning>,
+    pub total_collisions: usize,
+    /// Collision counts per coordinate
+    pub collision_counts: Vec<(Coordinate, usize)>,
+    pub peak_value_digits: usize,
+    pub peak_reservoir_cells: usize,
+    /// Counters so far; missing from snapshots saved before metrics existed
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metrics: Metrics,
+}
+
+#[cfg(feature = "serde")]
+impl InterpreterSnapshot {
+    pub fn to_json(&self) -> crate::types::error::Result<String> {
+        serde_json::to_string_pretty(self).map_err(Self::invalid)
+    }
+
+    pub fn from_json(json: &str) -> crate::types::error::Result<Self> {
+        serde_json::from_str(json).map_err(Self::invalid)
+    }
+
+    /// Write the snapshot to `path` as JSON
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> crate::types::error::Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Read a snapshot previously written by `save`
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> crate::types::error::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    fn invalid(error: serde_json::Error) -> crate::types::error::InterpreterError {
+        crate::types::error::SystemError::InvalidSnapshot(error.to_string()).into()
+    }
+}
@@ -1,12 +1,26 @@
-use crate::types::bigint::TubularBigInt;
-use std::fmt;
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+use core::fmt;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataStack {
     /// Stack values (arbitrary precision integers)
-    pub data: Vec<TubularBigInt>,
+    pub data: Vec<TubularNumber>,
     /// Maximum depth reached (for monitoring)
     pub max_depth: usize,
+    /// Changes recorded since the journal was started (None = not recording)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    journal: Option<Vec<StackChange>>,
+}
+
+/// A single recorded stack mutation, kept so it can be undone
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackChange {
+    Pushed,
+    Popped(TubularNumber),
+    /// Bulk edits (clear, truncate, swap) record the full previous contents
+    Replaced(Vec<TubularNumber>),
 }
 
 impl DataStack {
@@ -14,6 +28,7 @@ impl DataStack {
         DataStack {
             data: Vec::new(),
             max_depth: 0,
+            journal: None,
         }
     }
 
@@ -21,29 +36,37 @@ impl DataStack {
         DataStack {
             data: Vec::with_capacity(capacity),
             max_depth: 0,
+            journal: None,
         }
     }
 
-    pub fn push(&mut self, value: TubularBigInt) {
+    pub fn push(&mut self, value: TubularNumber) {
         self.data.push(value);
         self.max_depth = self.max_depth.max(self.data.len());
+        self.record(StackChange::Pushed);
     }
 
-    pub fn pop(&mut self) -> TubularBigInt {
-        self.data.pop().unwrap_or_else(|| TubularBigInt::zero())
+    pub fn pop(&mut self) -> TubularNumber {
+        match self.data.pop() {
+            Some(value) => {
+                self.record(StackChange::Popped(value.clone()));
+                value
+            }
+            None => TubularNumber::zero(),
+        }
     }
 
-    pub fn pop_or_zero(&mut self) -> TubularBigInt {
+    pub fn pop_or_zero(&mut self) -> TubularNumber {
         self.pop()
     }
 
-    pub fn peek(&self) -> TubularBigInt {
-        self.data.last().cloned().unwrap_or_else(|| TubularBigInt::zero())
+    pub fn peek(&self) -> TubularNumber {
+        self.data.last().cloned().unwrap_or_else(|| TubularNumber::zero())
     }
 
-    pub fn peek_depth(&self, depth: usize) -> TubularBigInt {
+    pub fn peek_depth(&self, depth: usize) -> TubularNumber {
         if depth >= self.data.len() {
-            TubularBigInt::zero()
+            TubularNumber::zero()
         } else {
             self.data[self.data.len() - 1 - depth].clone()
         }
@@ -62,11 +85,13 @@ impl DataStack {
     }
 
     pub fn clear(&mut self) {
+        self.record_replace();
         self.data.clear();
     }
 
     pub fn truncate(&mut self, new_len: usize) {
         if new_len < self.data.len() {
+            self.record_replace();
             self.data.truncate(new_len);
         }
     }
@@ -75,6 +100,7 @@ impl DataStack {
         if self.data.len() < 2 {
             false
         } else {
+            self.record_replace();
             let len = self.data.len();
             self.data.swap(len - 1, len - 2);
             true
@@ -91,7 +117,7 @@ impl DataStack {
         }
     }
 
-    pub fn pop_n(&mut self, n: usize) -> Vec<TubularBigInt> {
+    pub fn pop_n(&mut self, n: usize) -> Vec<TubularNumber> {
         let mut result = Vec::with_capacity(n);
         for _ in 0..n {
             result.push(self.pop());
@@ -99,17 +125,17 @@ impl DataStack {
         result
     }
 
-    pub fn push_n(&mut self, values: Vec<TubularBigInt>) {
+    pub fn push_n(&mut self, values: Vec<TubularNumber>) {
         for value in values {
             self.push(value);
         }
     }
 
-    pub fn get(&self, index: usize) -> Option<&TubularBigInt> {
+    pub fn get(&self, index: usize) -> Option<&TubularNumber> {
         self.data.get(index)
     }
 
-    pub fn get_from_top(&self, index_from_top: usize) -> Option<&TubularBigInt> {
+    pub fn get_from_top(&self, index_from_top: usize) -> Option<&TubularNumber> {
         if index_from_top >= self.data.len() {
             None
         } else {
@@ -117,7 +143,7 @@ impl DataStack {
         }
     }
 
-    pub fn as_slice(&self) -> &[TubularBigInt] {
+    pub fn as_slice(&self) -> &[TubularNumber] {
         &self.data
     }
 
@@ -128,6 +154,42 @@ impl DataStack {
     pub fn max_depth_reached(&self) -> usize {
         self.max_depth
     }
+
+    /// Start recording changes so they can later be undone
+    pub fn start_journal(&mut self) {
+        self.journal = Some(Vec::new());
+    }
+
+    /// Stop recording and return the changes made since `start_journal`
+    pub fn take_journal(&mut self) -> Vec<StackChange> {
+        self.journal.take().unwrap_or_default()
+    }
+
+    /// Revert changes previously returned by `take_journal`
+    pub fn undo(&mut self, changes: Vec<StackChange>) {
+        for change in changes.into_iter().rev() {
+            match change {
+                StackChange::Pushed => {
+                    self.data.pop();
+                }
+                StackChange::Popped(value) => self.data.push(value),
+                StackChange::Replaced(previous) => self.data = previous,
+            }
+        }
+    }
+
+    fn record(&mut self, change: StackChange) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(change);
+        }
+    }
+
+    fn record_replace(&mut self) {
+        if self.journal.is_some() {
+            let previous = self.data.clone();
+            self.record(StackChange::Replaced(previous));
+        }
+    }
 }
 
 impl Default for DataStack {
@@ -149,21 +211,22 @@ impl fmt::Display for DataStack {
     }
 }
 
-impl From<Vec<TubularBigInt>> for DataStack {
-    fn from(values: Vec<TubularBigInt>) -> Self {
+impl From<Vec<TubularNumber>> for DataStack {
+    fn from(values: Vec<TubularNumber>) -> Self {
         let max_depth = values.len();
         DataStack {
             data: values,
             max_depth,
+            journal: None,
         }
     }
 }
 
 impl From<Vec<i64>> for DataStack {
     fn from(values: Vec<i64>) -> Self {
-        let bigint_values: Vec<TubularBigInt> = values
+        let bigint_values: Vec<TubularNumber> = values
             .into_iter()
-            .map(TubularBigInt::new)
+            .map(TubularNumber::new)
             .collect();
         bigint_values.into()
     }
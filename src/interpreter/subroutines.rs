@@ -1,8 +1,11 @@
+use alloc::collections::BTreeMap;
 use crate::types::coordinate::Coordinate;
 use crate::types::direction::Direction;
-use std::fmt;
+use crate::types::prelude::*;
+use core::fmt;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StackFrame {
     /// Position to return to
     pub return_position: Coordinate,
@@ -20,6 +23,7 @@ impl StackFrame {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CallStack {
     /// Stack of return positions
     pub frames: Vec<StackFrame>,
@@ -176,4 +180,80 @@ impl From<Vec<(Coordinate, Direction)>> for CallStack {
             .collect();
         frames.into()
     }
-}
\ No newline at end of file
+}
+/// Named subroutine entry point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label {
+    /// Cell a called droplet jumps to
+    pub entry: Coordinate,
+    /// Heading of a called droplet at the entry
+    pub direction: Direction,
+}
+
+/// Labels and the `C` cells bound to them, from `%label` and `%call` directives
+#[derive(Debug, Clone, Default)]
+pub struct SubroutineTable {
+    labels: BTreeMap<String, Label>,
+    /// Label called by the `C` cell at each coordinate
+    calls: BTreeMap<Coordinate, String>,
+}
+
+impl SubroutineTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define `name`; returns false if it was already defined
+    pub fn define_label(&mut self, name: &str, label: Label) -> bool {
+        if self.labels.contains_key(name) {
+            return false;
+        }
+        self.labels.insert(name.to_string(), label);
+        true
+    }
+
+    /// Make the `C` cell at `call_site` call `name`
+    pub fn bind_call(&mut self, call_site: Coordinate, name: &str) {
+        self.calls.insert(call_site, name.to_string());
+    }
+
+    pub fn label(&self, name: &str) -> Option<&Label> {
+        self.labels.get(name)
+    }
+
+    /// Label called from `call_site`, if the cell is bound to one
+    pub fn call_label(&self, call_site: Coordinate) -> Option<&str> {
+        self.calls.get(&call_site).map(String::as_str)
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = (&str, &Label)> {
+        self.labels.iter().map(|(name, label)| (name.as_str(), label))
+    }
+
+    /// Call sites and the labels they call, ordered by position
+    pub fn calls(&self) -> Vec<(Coordinate, &str)> {
+        let mut calls: Vec<_> = self.calls.iter().map(|(site, name)| (*site, name.as_str())).collect();
+        calls.sort_by_key(|(site, _)| (site.y, site.x));
+        calls
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.calls.is_empty()
+    }
+
+    /// Copy every label and call from `other`, shifted by `offset`
+    ///
+    /// Returns the name of the first label defined in both, if any.
+    pub fn merge(&mut self, other: &SubroutineTable, offset: Coordinate) -> Option<String> {
+        let shift = |coord: Coordinate| Coordinate::new(coord.x + offset.x, coord.y + offset.y);
+        for (name, label) in &other.labels {
+            if !self.define_label(name, Label { entry: shift(label.entry), ..*label }) {
+                return Some(name.clone());
+            }
+        }
+        for (site, name) in &other.calls {
+            self.bind_call(shift(*site), name);
+        }
+        None
+    }
+}
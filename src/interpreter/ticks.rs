@@ -0,0 +1,96 @@
+use crate::interpreter::execution::{TickResult, TubularInterpreter};
+use crate::types::error::Result;
+
+/// Iterator that executes one tick per `next`, from [`TubularInterpreter::ticks`]
+///
+/// Iteration ends once the interpreter stops running: the program completed,
+/// hit a limit, paused at a breakpoint or was cancelled. A pause or
+/// cancellation requested through the interrupt handle ends iteration without
+/// yielding anything for the tick it prevented. An error is yielded once and
+/// ends iteration. Dropping the iterator leaves the interpreter where
+/// it was, so execution can continue with `run` or another `ticks`.
+pub struct Ticks<'a> {
+    interpreter: &'a mut TubularInterpreter,
+    failed: bool,
+}
+
+impl<'a> Ticks<'a> {
+    pub(crate) fn new(interpreter: &'a mut TubularInterpreter) -> Self {
+        Ticks { interpreter, failed: false }
+    }
+
+    /// The interpreter being driven, e.g. to inspect state between ticks
+    pub fn interpreter(&self) -> &TubularInterpreter {
+        self.interpreter
+    }
+}
+
+impl Iterator for Ticks<'_> {
+    type Item = Result<TickResult>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // A pending pause or cancellation stops here, before any tick is reported
+        if self.failed || !self.interpreter.is_running() || self.interpreter.take_interrupt() {
+            return None;
+        }
+
+        let result = self.interpreter.execute_tick();
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+impl core::iter::FusedIterator for Ticks<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::execution::{ExecutionStatus, TubularInterpreter};
+    use crate::parser::grid_parser::GridParser;
+
+    fn interpreter(source: &str) -> TubularInterpreter {
+        TubularInterpreter::new(GridParser::new().parse_string(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_ticks_run_to_completion() {
+        let mut interpreter = interpreter("@\n7\nn\n!");
+        let results: Vec<_> = interpreter.ticks().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(results.iter().map(|tick| tick.tick).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(results.iter().filter_map(|tick| tick.output.clone()).collect::<String>(), "7");
+        assert_eq!(interpreter.state().status, ExecutionStatus::Completed);
+        assert_eq!(interpreter.ticks().count(), 0);
+    }
+
+    #[test]
+    fn test_ticks_can_stop_early_and_continue() {
+        let mut interpreter = interpreter("@\n7\nn\n!");
+        let first_output = interpreter.ticks()
+            .take_while(|tick| tick.as_ref().is_ok_and(|tick| tick.output.is_none()))
+            .count();
+        assert_eq!(first_output, 2);
+        assert!(interpreter.is_running());
+
+        let result = interpreter.run().unwrap();
+        assert_eq!(result.final_output, "7");
+    }
+
+    #[test]
+    fn test_ticks_end_when_paused_or_cancelled() {
+        let mut interpreter = interpreter("@\n7\nn\n!");
+        let handle = interpreter.interrupt_handle();
+
+        handle.pause();
+        assert_eq!(interpreter.ticks().count(), 0);
+        assert_eq!(interpreter.state().status, ExecutionStatus::Paused);
+        assert_eq!(interpreter.state().tick, 0);
+
+        interpreter.resume();
+        let mut ticks = interpreter.ticks();
+        assert_eq!(ticks.next().unwrap().unwrap().tick, 0);
+        handle.cancel();
+        assert!(ticks.next().is_none());
+        assert_eq!(interpreter.state().status, ExecutionStatus::Cancelled);
+        assert_eq!(interpreter.state().tick, 1);
+    }
+}
@@ -0,0 +1,936 @@
+use crate::interpreter::collision::{CollisionOutcome, CollisionPolicy};
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use crate::types::number::TubularNumber;
+use crate::types::source_map::SourceLocation;
+use crate::types::prelude::*;
+use alloc::collections::BTreeSet;
+use core::time::Duration;
+
+/// Length of one tick on the Chrome trace timeline, in microseconds
+pub const CHROME_TICK_US: u64 = 1000;
+
+/// Version of the JSON trace written by `--trace-format json` and read by `tubular replay`
+///
+/// Bump when a field is renamed or removed; adding fields is not a breaking change.
+pub const TRACE_SCHEMA_VERSION: u32 = 1;
+
+/// Trace configuration for execution debugging
+#[derive(Debug, Clone)]
+pub struct TraceConfig {
+    /// Trace level of detail
+    pub level: TraceLevel,
+    /// Output format for traces
+    pub format: TraceFormat,
+    /// Filter traces by droplet IDs (None = all droplets)
+    pub droplet_filter: Option<BTreeSet<u64>>,
+    /// Filter traces by operation types (None = all operations)
+    pub operation_filter: Option<BTreeSet<TraceOperation>>,
+    /// Filter traces by tick range (None = all ticks)
+    pub tick_range: Option<(u64, u64)>,
+    /// Maximum number of trace events to capture (None = unlimited)
+    pub max_events: Option<usize>,
+    /// Whether to include performance metrics
+    pub include_performance: bool,
+    /// Whether to include memory state changes
+    pub include_memory: bool,
+    /// Whether to include stack state changes
+    pub include_stack: bool,
+    /// Whether to include subroutine call tracking
+    pub include_subroutines: bool,
+}
+
+/// Level of detail for execution tracing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceLevel {
+    /// Basic tick-level information only
+    Basic,
+    /// Detailed operation-level information
+    Detailed,
+    /// Verbose execution with all state changes
+    Verbose,
+}
+
+/// Output format for trace information
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Compact single-line format
+    Compact,
+    /// Detailed multi-line format
+    Detailed,
+    /// JSON structured format
+    Json,
+    /// Chrome trace event JSON for `chrome://tracing` and Perfetto
+    Chrome,
+}
+
+/// Types of operations that can be traced
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TraceOperation {
+    /// Droplet movement
+    Movement,
+    /// Value changes
+    ValueChange,
+    /// Stack operations (push, pop, etc.)
+    StackOp,
+    /// Memory operations (get, put)
+    MemoryOp,
+    /// Arithmetic operations
+    ArithmeticOp,
+    /// Bitwise operations (and, or, xor, not, shifts)
+    BitwiseOp,
+    /// Input/output operations
+    IoOp,
+    /// Subroutine calls
+    SubroutineCall,
+    /// Subroutine returns
+    SubroutineReturn,
+    /// Direction changes
+    DirectionChange,
+    /// Collision detection
+    Collision,
+    /// Droplet creation/destruction
+    DropletLifecycle,
+    /// A watched reservoir cell or stack property triggered
+    Watch,
+}
+
+/// Detailed trace event for comprehensive execution tracking
+#[derive(Debug, Clone)]
+pub struct TraceEvent {
+    /// Tick number when this event occurred
+    pub tick: u64,
+    /// Timestamp for performance tracking
+    pub timestamp: Duration,
+    /// ID of the droplet involved (if applicable)
+    pub droplet_id: Option<u64>,
+    /// Type of operation
+    pub operation: TraceOperation,
+    /// Position in grid (if applicable)
+    pub position: Option<Coordinate>,
+    /// Original file/line/column of `position` (if known)
+    pub source_location: Option<SourceLocation>,
+    /// Cell symbol being processed (if applicable)
+    pub cell_symbol: Option<char>,
+    /// Detailed description of the event
+    pub description: String,
+    /// Previous state (for state changes)
+    pub before_state: Option<TraceState>,
+    /// New state (for state changes)
+    pub after_state: Option<TraceState>,
+    /// Additional metadata
+    pub metadata: TraceMetadata,
+}
+
+impl TraceOperation {
+    pub const ALL: [TraceOperation; 13] = [
+        TraceOperation::Movement,
+        TraceOperation::ValueChange,
+        TraceOperation::StackOp,
+        TraceOperation::MemoryOp,
+        TraceOperation::ArithmeticOp,
+        TraceOperation::BitwiseOp,
+        TraceOperation::IoOp,
+        TraceOperation::SubroutineCall,
+        TraceOperation::SubroutineReturn,
+        TraceOperation::DirectionChange,
+        TraceOperation::Collision,
+        TraceOperation::DropletLifecycle,
+        TraceOperation::Watch,
+    ];
+
+    /// Parse the name used in JSON traces, e.g. `StackOp`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|operation| format!("{:?}", operation) == name)
+    }
+}
+
+/// State information for tracing
+#[derive(Debug, Clone)]
+pub struct TraceState {
+    /// Droplet value
+    pub droplet_value: Option<TubularNumber>,
+    /// Droplet direction
+    pub droplet_direction: Option<Direction>,
+    /// Stack contents (if enabled)
+    pub stack_contents: Option<Vec<TubularNumber>>,
+    /// Call stack depth
+    pub call_stack_depth: Option<usize>,
+    /// Memory coordinates accessed (if applicable)
+    pub memory_coord: Option<Coordinate>,
+}
+
+/// Additional metadata for trace events
+#[derive(Debug, Clone)]
+pub struct TraceMetadata {
+    /// Execution time for this operation
+    pub execution_time_us: Option<u64>,
+    /// Number of active droplets at this point
+    pub active_droplets: usize,
+    /// Total memory usage estimate
+    pub memory_usage_bytes: usize,
+    /// Collision count for this tick
+    pub collision_count: usize,
+    /// Additional key-value data
+    pub extra: alloc::collections::BTreeMap<String, String>,
+}
+
+/// Performance metrics collected during execution
+#[derive(Debug, Clone)]
+pub struct PerformanceMetrics {
+    /// Time taken for this tick
+    pub tick_time_us: u64,
+    /// Number of droplet operations
+    pub droplet_operations: usize,
+    /// Stack operations count
+    pub stack_operations: usize,
+    /// Memory operations count
+    pub memory_operations: usize,
+    /// Collision detection time
+    pub collision_time_us: u64,
+    /// Grid access count
+    pub grid_accesses: usize,
+}
+
+fn coordinate_json(coord: Coordinate) -> serde_json::Value {
+    serde_json::json!({"x": coord.x, "y": coord.y})
+}
+
+fn coordinate_from_json(value: &serde_json::Value) -> Option<Coordinate> {
+    Some(Coordinate::new(value["x"].as_i64()? as isize, value["y"].as_i64()? as isize))
+}
+
+fn bigint_from_json(value: &serde_json::Value) -> Result<TubularNumber, String> {
+    let text = value.as_str().ok_or_else(|| format!("Expected a number as a string, found {}", value))?;
+    text.parse()
+        .map(TubularNumber::from_bigint)
+        .map_err(|_| format!("'{}' is not an integer", text))
+}
+
+impl TraceState {
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::Map::new();
+        if let Some(value) = &self.droplet_value {
+            json.insert("droplet_value".to_string(), value.to_string().into());
+        }
+        if let Some(direction) = &self.droplet_direction {
+            json.insert("droplet_direction".to_string(), format!("{:?}", direction).into());
+        }
+        if let Some(stack) = &self.stack_contents {
+            json.insert("stack_contents".to_string(), stack.iter().map(|v| v.to_string()).collect());
+        }
+        if let Some(depth) = self.call_stack_depth {
+            json.insert("call_stack_depth".to_string(), depth.into());
+        }
+        if let Some(coord) = self.memory_coord {
+            json.insert("memory_coord".to_string(), coordinate_json(coord));
+        }
+        json.into()
+    }
+
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, String> {
+        let droplet_direction = match json["droplet_direction"].as_str() {
+            Some(name) => Some(
+                [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+                    .into_iter()
+                    .find(|direction| format!("{:?}", direction) == name)
+                    .ok_or_else(|| format!("Unknown direction '{}'", name))?,
+            ),
+            None => None,
+        };
+        let stack_contents = match json["stack_contents"].as_array() {
+            Some(values) => Some(values.iter().map(bigint_from_json).collect::<Result<Vec<_>, _>>()?),
+            None => None,
+        };
+
+        Ok(TraceState {
+            droplet_value: json.get("droplet_value").map(bigint_from_json).transpose()?,
+            droplet_direction,
+            stack_contents,
+            call_stack_depth: json["call_stack_depth"].as_u64().map(|depth| depth as usize),
+            memory_coord: coordinate_from_json(&json["memory_coord"]),
+        })
+    }
+}
+
+impl TraceEvent {
+    /// The event as an object in the versioned JSON trace
+    ///
+    /// Source lines and columns are 1-based; absent optional fields are omitted.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::Map::new();
+        json.insert("tick".to_string(), self.tick.into());
+        json.insert("operation".to_string(), format!("{:?}", self.operation).into());
+        json.insert("timestamp_us".to_string(), (self.timestamp.as_micros() as u64).into());
+        if let Some(droplet_id) = self.droplet_id {
+            json.insert("droplet_id".to_string(), droplet_id.into());
+        }
+        if let Some(pos) = self.position {
+            json.insert("position".to_string(), coordinate_json(pos));
+        }
+        if let Some(location) = &self.source_location {
+            json.insert("source".to_string(), serde_json::json!({
+                "file": location.file,
+                "line": location.line + 1,
+                "column": location.column + 1,
+            }));
+        }
+        if let Some(symbol) = self.cell_symbol {
+            json.insert("cell_symbol".to_string(), symbol.to_string().into());
+        }
+        json.insert("description".to_string(), self.description.clone().into());
+        if let Some(state) = &self.before_state {
+            json.insert("before_state".to_string(), state.to_json());
+        }
+        if let Some(state) = &self.after_state {
+            json.insert("after_state".to_string(), state.to_json());
+        }
+        json.insert("active_droplets".to_string(), self.metadata.active_droplets.into());
+        json.insert("memory_usage_bytes".to_string(), self.metadata.memory_usage_bytes.into());
+        json.insert("collision_count".to_string(), self.metadata.collision_count.into());
+        if let Some(exec_time) = self.metadata.execution_time_us {
+            json.insert("execution_time_us".to_string(), exec_time.into());
+        }
+        if !self.metadata.extra.is_empty() {
+            let extra: serde_json::Map<String, serde_json::Value> = self.metadata.extra.iter()
+                .map(|(k, v)| (k.clone(), v.clone().into()))
+                .collect();
+            json.insert("extra".to_string(), extra.into());
+        }
+        json.into()
+    }
+
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, String> {
+        let operation_name = json["operation"].as_str().ok_or("Trace event has no operation")?;
+        let operation = TraceOperation::from_name(operation_name)
+            .ok_or_else(|| format!("Unknown trace operation '{}'", operation_name))?;
+        let source_location = match &json["source"] {
+            serde_json::Value::Null => None,
+            source => Some(SourceLocation::new(
+                source["file"].as_str().unwrap_or_default().to_string(),
+                source["line"].as_u64().unwrap_or(1).saturating_sub(1) as usize,
+                source["column"].as_u64().unwrap_or(1).saturating_sub(1) as usize,
+            )),
+        };
+        let state = |key: &str| match &json[key] {
+            serde_json::Value::Null => Ok(None),
+            state => TraceState::from_json(state).map(Some),
+        };
+        let extra = json["extra"].as_object()
+            .map(|extra| {
+                extra.iter()
+                    .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(TraceEvent {
+            tick: json["tick"].as_u64().ok_or("Trace event has no tick")?,
+            timestamp: Duration::from_micros(json["timestamp_us"].as_u64().unwrap_or(0)),
+            droplet_id: json["droplet_id"].as_u64(),
+            operation,
+            position: coordinate_from_json(&json["position"]),
+            source_location,
+            cell_symbol: json["cell_symbol"].as_str().and_then(|symbol| symbol.chars().next()),
+            description: json["description"].as_str().unwrap_or_default().to_string(),
+            before_state: state("before_state")?,
+            after_state: state("after_state")?,
+            metadata: TraceMetadata {
+                execution_time_us: json["execution_time_us"].as_u64(),
+                active_droplets: json["active_droplets"].as_u64().unwrap_or(0) as usize,
+                memory_usage_bytes: json["memory_usage_bytes"].as_u64().unwrap_or(0) as usize,
+                collision_count: json["collision_count"].as_u64().unwrap_or(0) as usize,
+                extra,
+            },
+        })
+    }
+}
+
+/// A saved JSON trace: its events and, when known, the program that produced them
+#[derive(Debug, Clone)]
+pub struct TraceDocument {
+    /// Program source, so a replay can draw the grid
+    pub program: Option<String>,
+    pub events: Vec<TraceEvent>,
+}
+
+impl TraceDocument {
+    pub fn to_json(&self, config: &TraceConfig) -> String {
+        let mut document = serde_json::json!({
+            "version": TRACE_SCHEMA_VERSION,
+            "trace_info": {
+                "total_events": self.events.len(),
+                "level": format!("{:?}", config.level),
+                "format": format!("{:?}", config.format),
+            },
+        });
+        if let Some(program) = &self.program {
+            document["program"] = program.clone().into();
+        }
+        document["events"] = self.events.iter().map(TraceEvent::to_json).collect();
+        serde_json::to_string_pretty(&document).unwrap_or_default()
+    }
+
+    /// Read a trace written with `--trace-format json`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let document: serde_json::Value = serde_json::from_str(text)
+            .map_err(|e| format!("Trace is not valid JSON: {}", e))?;
+        match document["version"].as_u64() {
+            Some(version) if version == TRACE_SCHEMA_VERSION as u64 => {}
+            Some(version) => {
+                return Err(format!(
+                    "Trace schema version {} is not supported (expected {})", version, TRACE_SCHEMA_VERSION
+                ));
+            }
+            None => return Err("Trace has no schema version; record it again with --trace-format json".to_string()),
+        }
+
+        let events = document["events"].as_array()
+            .ok_or("Trace has no events array")?
+            .iter()
+            .enumerate()
+            .map(|(i, event)| TraceEvent::from_json(event).map_err(|e| format!("Event {}: {}", i, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TraceDocument {
+            program: document["program"].as_str().map(str::to_string),
+            events,
+        })
+    }
+}
+
+impl Default for TraceConfig {
+    fn default() -> Self {
+        Self {
+            level: TraceLevel::Basic,
+            format: TraceFormat::Compact,
+            droplet_filter: None,
+            operation_filter: None,
+            tick_range: None,
+            max_events: None,
+            include_performance: false,
+            include_memory: false,
+            include_stack: false,
+            include_subroutines: false,
+        }
+    }
+}
+
+impl TraceConfig {
+    /// Create a new trace configuration with basic settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set trace level
+    pub fn with_level(mut self, level: TraceLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set trace format
+    pub fn with_format(mut self, format: TraceFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Filter by specific droplet IDs
+    pub fn with_droplet_filter(mut self, droplet_ids: BTreeSet<u64>) -> Self {
+        self.droplet_filter = Some(droplet_ids);
+        self
+    }
+
+    /// Filter by specific operation types
+    pub fn with_operation_filter(mut self, operations: BTreeSet<TraceOperation>) -> Self {
+        self.operation_filter = Some(operations);
+        self
+    }
+
+    /// Filter by tick range
+    pub fn with_tick_range(mut self, start: u64, end: u64) -> Self {
+        self.tick_range = Some((start, end));
+        self
+    }
+
+    /// Set maximum number of events to capture
+    pub fn with_max_events(mut self, max_events: usize) -> Self {
+        self.max_events = Some(max_events);
+        self
+    }
+
+    /// Enable performance metrics collection
+    pub fn with_performance(mut self, include: bool) -> Self {
+        self.include_performance = include;
+        self
+    }
+
+    /// Enable memory state tracking
+    pub fn with_memory(mut self, include: bool) -> Self {
+        self.include_memory = include;
+        self
+    }
+
+    /// Enable stack state tracking
+    pub fn with_stack(mut self, include: bool) -> Self {
+        self.include_stack = include;
+        self
+    }
+
+    /// Enable subroutine tracking
+    pub fn with_subroutines(mut self, include: bool) -> Self {
+        self.include_subroutines = include;
+        self
+    }
+
+    /// Create a comprehensive trace configuration for debugging
+    pub fn comprehensive() -> Self {
+        Self {
+            level: TraceLevel::Verbose,
+            format: TraceFormat::Detailed,
+            droplet_filter: None,
+            operation_filter: None,
+            tick_range: None,
+            max_events: None,
+            include_performance: true,
+            include_memory: true,
+            include_stack: true,
+            include_subroutines: true,
+        }
+    }
+
+    /// Check if an event should be included based on filters
+    pub fn should_include_event(&self, event: &TraceEvent) -> bool {
+        // Check droplet filter
+        if let Some(ref droplet_filter) = self.droplet_filter {
+            if let Some(droplet_id) = event.droplet_id {
+                if !droplet_filter.contains(&droplet_id) {
+                    return false;
+                }
+            } else {
+                // Event has no droplet ID, skip if droplet filter is active
+                return false;
+            }
+        }
+
+        // Check operation filter
+        if let Some(ref operation_filter) = self.operation_filter {
+            if !operation_filter.contains(&event.operation) {
+                return false;
+            }
+        }
+
+        // Check tick range filter
+        if let Some((start, end)) = self.tick_range {
+            if event.tick < start || event.tick > end {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl TraceEvent {
+    /// Create a basic trace event for droplet movement
+    pub fn movement(
+        tick: u64,
+        droplet_id: u64,
+        from_pos: Coordinate,
+        to_pos: Coordinate,
+        direction: Direction,
+        value: &TubularNumber,
+        cell_symbol: Option<char>,
+    ) -> TraceEvent {
+        let timestamp = Duration::from_millis(tick); // Simplified timestamp
+
+        TraceEvent {
+            tick,
+            timestamp,
+            droplet_id: Some(droplet_id),
+            operation: TraceOperation::Movement,
+            position: Some(to_pos),
+            source_location: None,
+            cell_symbol,
+            description: format!(
+                "Droplet {} moves from {} to {} heading {:?} with value {}",
+                droplet_id, from_pos, to_pos, direction, value
+            ),
+            before_state: Some(TraceState {
+                droplet_value: Some(value.clone()),
+                droplet_direction: Some(direction),
+                stack_contents: None,
+                call_stack_depth: None,
+                memory_coord: Some(from_pos),
+            }),
+            after_state: Some(TraceState {
+                droplet_value: Some(value.clone()),
+                droplet_direction: Some(direction),
+                stack_contents: None,
+                call_stack_depth: None,
+                memory_coord: Some(to_pos),
+            }),
+            metadata: TraceMetadata {
+                execution_time_us: None,
+                active_droplets: 1,
+                memory_usage_bytes: 0,
+                collision_count: 0,
+                extra: alloc::collections::BTreeMap::new(),
+            },
+        }
+    }
+
+    /// Create a trace event for stack operations
+    pub fn stack(
+        tick: u64,
+        droplet_id: u64,
+        operation: char,
+        position: Coordinate,
+        stack_before: &[TubularNumber],
+        stack_after: &[TubularNumber],
+        droplet_value: &TubularNumber,
+    ) -> TraceEvent {
+        let operation_type = match operation {
+            ':' => TraceOperation::StackOp,
+            ';' => TraceOperation::StackOp,
+            'd' => TraceOperation::StackOp,
+            'A' | 'S' | 'M' | 'D' | '=' | '<' | '>' | '%' | '*' | '_' => TraceOperation::ArithmeticOp,
+            '&' | 'o' | 'x' | 'N' | '[' | ']' => TraceOperation::BitwiseOp,
+            'G' | 'P' | 'F' | 'Z' | 'B' | 'g' | 'p' => TraceOperation::MemoryOp,
+            '+' | '~' | 't' | '\'' => TraceOperation::ValueChange,
+            _ => TraceOperation::StackOp,
+        };
+
+        let operation_desc = match operation {
+            ':' => "push",
+            ';' => "pop",
+            'd' => "duplicate",
+            'A' => "add",
+            'S' => "subtract",
+            'M' => "multiply",
+            'D' => "divide",
+            '=' => "equals",
+            '<' => "less_than",
+            '>' => "greater_than",
+            '%' => "modulo",
+            '*' => "power",
+            '_' => "absolute",
+            '+' => "increment",
+            '~' => "decrement",
+            't' => "truncate",
+            '\'' => "char_literal",
+            '&' => "and",
+            'o' => "or",
+            'x' => "xor",
+            'N' => "not",
+            '[' => "shift_left",
+            ']' => "shift_right",
+            'G' => "get",
+            'P' => "put",
+            'F' => "fill_region",
+            'Z' => "clear_region",
+            'B' => "copy_region",
+            'w' => "droplet_id",
+            'e' => "droplet_age",
+            'h' => "droplet_heading",
+            'g' => "grid_get",
+            'p' => "grid_put",
+            '{' => "local_push",
+            '}' => "local_pop",
+            '(' => "local_take",
+            ')' => "local_give",
+            'i' => "pseudo_input",
+            'r' => "random",
+            'a' => "argument",
+            'O' => "file_open",
+            'I' => "file_read",
+            'W' => "file_write",
+            'X' => "file_close",
+            'T' => "tcp_connect",
+            'E' => "tcp_send",
+            'V' => "tcp_receive",
+            _ => "unknown_stack_op",
+        };
+
+        TraceEvent {
+            tick,
+            timestamp: Duration::from_millis(tick),
+            droplet_id: Some(droplet_id),
+            operation: operation_type,
+            position: Some(position),
+            source_location: None,
+            cell_symbol: Some(operation),
+            description: format!(
+                "Droplet {} performs {} operation at {} with value {}",
+                droplet_id, operation_desc, position, droplet_value
+            ),
+            before_state: Some(TraceState {
+                droplet_value: Some(droplet_value.clone()),
+                droplet_direction: None,
+                stack_contents: Some(stack_before.to_vec()),
+                call_stack_depth: None,
+                memory_coord: Some(position),
+            }),
+            after_state: Some(TraceState {
+                droplet_value: Some(droplet_value.clone()),
+                droplet_direction: None,
+                stack_contents: Some(stack_after.to_vec()),
+                call_stack_depth: None,
+                memory_coord: Some(position),
+            }),
+            metadata: TraceMetadata {
+                execution_time_us: None,
+                active_droplets: 1,
+                memory_usage_bytes: core::mem::size_of_val(stack_before),
+                collision_count: 0,
+                extra: {
+                    let mut extra = alloc::collections::BTreeMap::new();
+                    extra.insert("stack_depth_before".to_string(), stack_before.len().to_string());
+                    extra.insert("stack_depth_after".to_string(), stack_after.len().to_string());
+                    extra
+                },
+            },
+        }
+    }
+
+    /// Create a trace event for memory operations
+    pub fn memory(
+        tick: u64,
+        droplet_id: u64,
+        operation: char, // 'G' for get, 'P' for put
+        position: Coordinate,
+        memory_coord: Coordinate,
+        memory_value: &TubularNumber,
+        droplet_value: &TubularNumber,
+    ) -> TraceEvent {
+        let operation_type = match operation {
+            'G' => TraceOperation::MemoryOp,
+            'P' => TraceOperation::MemoryOp,
+            _ => TraceOperation::MemoryOp,
+        };
+
+        let operation_desc = match operation {
+            'G' => "get",
+            'P' => "put",
+            _ => "unknown_memory_op",
+        };
+
+        TraceEvent {
+            tick,
+            timestamp: Duration::from_millis(tick),
+            droplet_id: Some(droplet_id),
+            operation: operation_type,
+            position: Some(position),
+            source_location: None,
+            cell_symbol: Some(operation),
+            description: format!(
+                "Droplet {} performs memory {} at coordinate {} with value {}",
+                droplet_id, operation_desc, memory_coord, memory_value
+            ),
+            before_state: Some(TraceState {
+                droplet_value: Some(droplet_value.clone()),
+                droplet_direction: None,
+                stack_contents: None,
+                call_stack_depth: None,
+                memory_coord: Some(memory_coord),
+            }),
+            after_state: Some(TraceState {
+                droplet_value: Some(match operation {
+                    'G' => memory_value.clone(), // After get, droplet has the memory value
+                    'P' => droplet_value.clone(), // After put, droplet value unchanged
+                    _ => droplet_value.clone(),
+                }),
+                droplet_direction: None,
+                stack_contents: None,
+                call_stack_depth: None,
+                memory_coord: Some(memory_coord),
+            }),
+            metadata: TraceMetadata {
+                execution_time_us: None,
+                active_droplets: 1,
+                memory_usage_bytes: core::mem::size_of::<TubularNumber>(),
+                collision_count: 0,
+                extra: {
+                    let mut extra = alloc::collections::BTreeMap::new();
+                    extra.insert("memory_operation".to_string(), operation.to_string());
+                    extra.insert("memory_coord_x".to_string(), memory_coord.x.to_string());
+                    extra.insert("memory_coord_y".to_string(), memory_coord.y.to_string());
+                    extra
+                },
+            },
+        }
+    }
+
+    /// Create a trace event for I/O operations
+    pub fn io(
+        tick: u64,
+        droplet_id: u64,
+        operation: char, // ',' for char output, 'n' for numeric output, '?' for input
+        position: Coordinate,
+        io_value: &str,
+        droplet_value: &TubularNumber,
+    ) -> TraceEvent {
+        let operation_type = match operation {
+            ',' | 'n' => TraceOperation::IoOp,
+            '?' => TraceOperation::IoOp,
+            _ => TraceOperation::IoOp,
+        };
+
+        let operation_desc = match operation {
+            ',' => "char_output",
+            'n' => "numeric_output",
+            '?' => "input",
+            _ => "unknown_io_op",
+        };
+
+        TraceEvent {
+            tick,
+            timestamp: Duration::from_millis(tick),
+            droplet_id: Some(droplet_id),
+            operation: operation_type,
+            position: Some(position),
+            source_location: None,
+            cell_symbol: Some(operation),
+            description: format!(
+                "Droplet {} performs {} at {} with value '{}'",
+                droplet_id, operation_desc, position, io_value
+            ),
+            before_state: Some(TraceState {
+                droplet_value: Some(droplet_value.clone()),
+                droplet_direction: None,
+                stack_contents: None,
+                call_stack_depth: None,
+                memory_coord: Some(position),
+            }),
+            after_state: Some(TraceState {
+                droplet_value: Some(droplet_value.clone()),
+                droplet_direction: None,
+                stack_contents: None,
+                call_stack_depth: None,
+                memory_coord: Some(position),
+            }),
+            metadata: TraceMetadata {
+                execution_time_us: None,
+                active_droplets: 1,
+                memory_usage_bytes: 0,
+                collision_count: 0,
+                extra: {
+                    let mut extra = alloc::collections::BTreeMap::new();
+                    extra.insert("io_value".to_string(), io_value.to_string());
+                    extra.insert("io_operation".to_string(), operation.to_string());
+                    extra
+                },
+            },
+        }
+    }
+
+    /// Create a trace event for a collision and how `policy` resolved it
+    pub fn collision(
+        tick: u64,
+        collision_position: Coordinate,
+        colliding_droplet_ids: &[u64],
+        policy: CollisionPolicy,
+        outcome: &CollisionOutcome,
+        active_droplet_count: usize,
+    ) -> TraceEvent {
+        TraceEvent {
+            tick,
+            timestamp: Duration::from_millis(tick),
+            droplet_id: None, // Multiple droplets involved
+            operation: TraceOperation::Collision,
+            position: Some(collision_position),
+            source_location: None,
+            cell_symbol: None,
+            description: format!(
+                "Collision at {} involving {} droplets: {:?} ({})",
+                collision_position, colliding_droplet_ids.len(), colliding_droplet_ids, policy
+            ),
+            before_state: None,
+            after_state: None,
+            metadata: TraceMetadata {
+                execution_time_us: None,
+                active_droplets: active_droplet_count,
+                memory_usage_bytes: 0,
+                collision_count: colliding_droplet_ids.len(),
+                extra: {
+                    let mut extra = alloc::collections::BTreeMap::new();
+                    extra.insert("colliding_droplets".to_string(),
+                        format!("[{}]", colliding_droplet_ids.iter()
+                            .map(|id| id.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")));
+                    extra.insert("policy".to_string(), policy.to_string());
+                    if let Some(survivor) = outcome.survivor {
+                        extra.insert("survivor".to_string(), survivor.to_string());
+                    }
+                    extra
+                },
+            },
+        }
+    }
+
+    /// Create a trace event for a triggered watch
+    pub fn watch(tick: u64, description: &str) -> TraceEvent {
+        TraceEvent {
+            tick,
+            timestamp: Duration::from_millis(tick),
+            droplet_id: None,
+            operation: TraceOperation::Watch,
+            position: None,
+            source_location: None,
+            cell_symbol: None,
+            description: format!("Watch {}", description),
+            before_state: None,
+            after_state: None,
+            metadata: TraceMetadata {
+                execution_time_us: None,
+                active_droplets: 0,
+                memory_usage_bytes: 0,
+                collision_count: 0,
+                extra: alloc::collections::BTreeMap::new(),
+            },
+        }
+    }
+
+    /// Create a trace event for droplet lifecycle (creation/destruction)
+    pub fn lifecycle(
+        tick: u64,
+        droplet_id: u64,
+        event_type: &str, // "created" or "destroyed"
+        position: Coordinate,
+        value: &TubularNumber,
+        direction: Direction,
+    ) -> TraceEvent {
+        TraceEvent {
+            tick,
+            timestamp: Duration::from_millis(tick),
+            droplet_id: Some(droplet_id),
+            operation: TraceOperation::DropletLifecycle,
+            position: Some(position),
+            source_location: None,
+            cell_symbol: None,
+            description: format!(
+                "Droplet {} {} at {} with value {} heading {:?}",
+                droplet_id, event_type, position, value, direction
+            ),
+            before_state: None,
+            after_state: Some(TraceState {
+                droplet_value: Some(value.clone()),
+                droplet_direction: Some(direction),
+                stack_contents: None,
+                call_stack_depth: None,
+                memory_coord: Some(position),
+            }),
+            metadata: TraceMetadata {
+                execution_time_us: None,
+                active_droplets: 1,
+                memory_usage_bytes: core::mem::size_of::<TubularNumber>(),
+                collision_count: 0,
+                extra: {
+                    let mut extra = alloc::collections::BTreeMap::new();
+                    extra.insert("lifecycle_event".to_string(), event_type.to_string());
+                    extra
+                },
+            },
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::types::number::TubularNumber;
+use crate::interpreter::memory::ReservoirCoordinate;
+use crate::interpreter::execution::ExecutionState;
+use crate::types::prelude::*;
+use core::fmt;
+use core::str::FromStr;
+
+/// Quantity observed by a watch
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchTarget {
+    /// Value stored in a reservoir cell
+    Reservoir(ReservoirCoordinate),
+    /// Number of values on the data stack
+    StackDepth,
+    /// Top of the data stack (absent when empty)
+    StackTop,
+}
+
+impl WatchTarget {
+    pub fn value(&self, state: &ExecutionState) -> Option<TubularNumber> {
+        match self {
+            WatchTarget::Reservoir(coord) => Some(state.reservoir.get(coord.clone())),
+            WatchTarget::StackDepth => Some(TubularNumber::new(state.stack.depth() as i64)),
+            WatchTarget::StackTop => state.stack.as_slice().last().cloned(),
+        }
+    }
+}
+
+impl fmt::Display for WatchTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchTarget::Reservoir(coord) => write!(f, "reservoir ({}, {})", coord.x, coord.y),
+            WatchTarget::StackDepth => write!(f, "stack depth"),
+            WatchTarget::StackTop => write!(f, "stack top"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    /// Operators in the order they must be tried when parsing
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    pub fn holds(&self, left: &TubularNumber, right: &TubularNumber) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        Self::OPERATORS.iter().find(|(_, op)| op == self).map(|(symbol, _)| *symbol).unwrap_or("?")
+    }
+}
+
+/// A watched quantity, optionally compared against a constant
+///
+/// Written as e.g. `reservoir (3,4)`, `stack top` or `stack depth > 10`.
+/// Without a comparison the watch triggers whenever the value changes; with
+/// one it triggers when the comparison becomes true.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchExpr {
+    pub target: WatchTarget,
+    pub condition: Option<(Comparison, TubularNumber)>,
+}
+
+impl WatchExpr {
+    pub fn new(target: WatchTarget) -> Self {
+        WatchExpr { target, condition: None }
+    }
+
+    pub fn with_condition(mut self, comparison: Comparison, value: TubularNumber) -> Self {
+        self.condition = Some((comparison, value));
+        self
+    }
+
+    /// Whether the comparison holds for `value` (always false without a comparison)
+    fn holds(&self, value: Option<&TubularNumber>) -> bool {
+        match (&self.condition, value) {
+            (Some((comparison, constant)), Some(value)) => comparison.holds(value, constant),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for WatchExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.target)?;
+        if let Some((comparison, value)) = &self.condition {
+            write!(f, " {} {}", comparison.symbol(), value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for WatchExpr {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (target_text, condition) = match Comparison::OPERATORS.iter().find_map(|(symbol, op)| {
+            text.find(symbol).map(|index| (index, *symbol, *op))
+        }) {
+            Some((index, symbol, op)) => {
+                let constant = text[index + symbol.len()..].trim();
+                let value = constant.parse()
+                    .map_err(|_| format!("'{}' is not an integer", constant))?;
+                (&text[..index], Some((op, TubularNumber::from_bigint(value))))
+            }
+            None => (text, None),
+        };
+
+        let words: Vec<&str> = target_text
+            .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | ','))
+            .filter(|word| !word.is_empty())
+            .collect();
+        let target = match words.as_slice() {
+            ["reservoir" | "mem", x, y] => {
+                let x = x.parse().map_err(|_| format!("'{}' is not a coordinate", x))?;
+                let y = y.parse().map_err(|_| format!("'{}' is not a coordinate", y))?;
+                WatchTarget::Reservoir(ReservoirCoordinate::new(x, y))
+            }
+            ["stack", "depth"] => WatchTarget::StackDepth,
+            ["stack", "top"] | ["stack"] => WatchTarget::StackTop,
+            _ => return Err(format!(
+                "Unknown watch '{}'; expected 'reservoir (X,Y)', 'stack depth' or 'stack top'",
+                text.trim()
+            )),
+        };
+
+        Ok(WatchExpr { target, condition })
+    }
+}
+
+/// What happens when a watch triggers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchAction {
+    /// Stop execution with `ExecutionStatus::Paused`
+    Pause,
+    /// Record a trace event (requires tracing to be enabled)
+    Trace,
+}
+
+/// A registered watch and the value it saw at the last check
+#[derive(Debug, Clone)]
+pub struct Watchpoint {
+    pub expr: WatchExpr,
+    pub action: WatchAction,
+    last_value: Option<TubularNumber>,
+}
+
+/// A watch that triggered, with the watched value before and after
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchHit {
+    pub expr: WatchExpr,
+    pub before: Option<TubularNumber>,
+    pub after: Option<TubularNumber>,
+}
+
+impl fmt::Display for WatchHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<TubularNumber>| value.as_ref().map_or("empty".to_string(), |v| v.to_string());
+        write!(f, "{}: {} -> {}", self.expr, show(&self.before), show(&self.after))
+    }
+}
+
+impl Watchpoint {
+    pub fn new(expr: WatchExpr, action: WatchAction, state: &ExecutionState) -> Self {
+        let last_value = expr.target.value(state);
+        Watchpoint { expr, action, last_value }
+    }
+
+    /// Re-read the watched value, reporting a hit if the watch triggered
+    pub fn check(&mut self, state: &ExecutionState) -> Option<WatchHit> {
+        let value = self.expr.target.value(state);
+        if value == self.last_value {
+            return None;
+        }
+
+        let before = core::mem::replace(&mut self.last_value, value);
+        let triggered = match self.expr.condition {
+            None => true,
+            Some(_) => self.expr.holds(self.last_value.as_ref()) && !self.expr.holds(before.as_ref()),
+        };
+        triggered.then(|| WatchHit {
+            expr: self.expr.clone(),
+            before,
+            after: self.last_value.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_watch_expressions() {
+        let reservoir: WatchExpr = "reservoir (3,4)".parse().unwrap();
+        assert_eq!(reservoir, WatchExpr::new(WatchTarget::Reservoir(ReservoirCoordinate::new(3, 4))));
+        assert_eq!(reservoir.to_string(), "reservoir (3, 4)");
+
+        let depth: WatchExpr = "stack depth > 10".parse().unwrap();
+        assert_eq!(depth, WatchExpr::new(WatchTarget::StackDepth)
+            .with_condition(Comparison::Greater, TubularNumber::new(10)));
+        assert_eq!(depth.to_string(), "stack depth > 10");
+
+        let top: WatchExpr = "stack top>=-2".parse().unwrap();
+        assert_eq!(top.condition, Some((Comparison::GreaterOrEqual, TubularNumber::new(-2))));
+
+        assert!("heap 1".parse::<WatchExpr>().is_err());
+        assert!("stack depth > lots".parse::<WatchExpr>().is_err());
+    }
+}
@@ -1,11 +1,25 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod interpreter;
 pub mod operations;
 pub mod parser;
 pub mod types;
+#[cfg(feature = "std")]
+pub mod codegen;
+#[cfg(feature = "std")]
 pub mod cli;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use interpreter::*;
 pub use operations::*;
 pub use parser::*;
 pub use types::*;
-pub use cli::*;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub use codegen::*;
+#[cfg(feature = "std")]
+pub use cli::*;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
@@ -1,15 +1,17 @@
+extern crate alloc;
+
 use clap::Parser;
-use anyhow::Result;
 
 mod interpreter;
 mod operations;
 mod parser;
 mod types;
+mod codegen;
 mod cli;
 
 use cli::Cli;
 
-fn main() -> Result<()> {
+fn main() -> std::process::ExitCode {
     let cli = Cli::parse();
-    cli.run()
+    cli.run().into()
 }
@@ -1,8 +1,15 @@
-use crate::types::bigint::TubularBigInt;
+use crate::types::number::TubularNumber;
 use crate::types::error::{Result, ExecError, InitError};
 use crate::interpreter::droplet::Droplet;
 use crate::interpreter::stack::DataStack;
 
+/// Largest left shift, in bits; shifting a non-zero value further is an error
+/// rather than an attempt to allocate an enormous number
+pub const MAX_SHIFT_BITS: usize = 1 << 20;
+
+/// Largest power, in bits of the result; bigger powers are an error
+pub const MAX_POWER_BITS: u64 = 1 << 20;
+
 /// Arithmetic and stack operations for Tubular programs
 pub struct ArithmeticOperations;
 
@@ -14,7 +21,7 @@ impl ArithmeticOperations {
         }
 
         let value = literal.to_digit(10).unwrap() as i64;
-        droplet.set_value(TubularBigInt::new(value));
+        droplet.set_value(TubularNumber::new(value));
         Ok(())
     }
 
@@ -38,6 +45,15 @@ impl ArithmeticOperations {
             '%' => Self::modulo(droplet, stack),
             '+' => Self::increment(droplet),
             '~' => Self::decrement(droplet),
+            '&' => Self::bitwise(droplet, stack, |a, b| a & b),
+            'o' => Self::bitwise(droplet, stack, |a, b| a | b),
+            'x' => Self::bitwise(droplet, stack, |a, b| a ^ b),
+            'N' => Self::not(droplet),
+            '[' => Self::shift(droplet, stack, true),
+            ']' => Self::shift(droplet, stack, false),
+            '*' => Self::power(droplet, stack),
+            '_' => Self::absolute(droplet, stack),
+            't' => Self::truncate(droplet),
             _ => Err(ExecError::InvalidOperation(operation).into()),
         }
     }
@@ -57,7 +73,7 @@ impl ArithmeticOperations {
     /// Duplicate (d) - Duplicate top stack value
     fn duplicate(stack: &mut DataStack) -> Result<()> {
         if stack.is_empty() {
-            stack.push(TubularBigInt::zero());
+            stack.push(TubularNumber::zero());
         } else {
             let top = stack.peek();
             stack.push(top);
@@ -97,7 +113,7 @@ impl ArithmeticOperations {
         let b = stack.pop_or_zero();
         let a = stack.pop_or_zero();
         let result = if b.is_zero() {
-            TubularBigInt::zero()
+            TubularNumber::zero()
         } else {
             a / b
         };
@@ -110,9 +126,9 @@ impl ArithmeticOperations {
         let b = stack.pop_or_zero();
         let a = stack.pop_or_zero();
         let result = if a == b {
-            TubularBigInt::one()
+            TubularNumber::one()
         } else {
-            TubularBigInt::zero()
+            TubularNumber::zero()
         };
         droplet.set_value(result);
         Ok(())
@@ -123,9 +139,9 @@ impl ArithmeticOperations {
         let b = stack.pop_or_zero();
         let a = stack.pop_or_zero();
         let result = if a < b {
-            TubularBigInt::one()
+            TubularNumber::one()
         } else {
-            TubularBigInt::zero()
+            TubularNumber::zero()
         };
         droplet.set_value(result);
         Ok(())
@@ -136,9 +152,9 @@ impl ArithmeticOperations {
         let b = stack.pop_or_zero();
         let a = stack.pop_or_zero();
         let result = if a > b {
-            TubularBigInt::one()
+            TubularNumber::one()
         } else {
-            TubularBigInt::zero()
+            TubularNumber::zero()
         };
         droplet.set_value(result);
         Ok(())
@@ -149,7 +165,7 @@ impl ArithmeticOperations {
         let b = stack.pop_or_zero();
         let a = stack.pop_or_zero();
         let result = if b.is_zero() {
-            TubularBigInt::zero()
+            TubularNumber::zero()
         } else {
             a % b
         };
@@ -159,13 +175,106 @@ impl ArithmeticOperations {
 
     /// Increment (+) - Increment droplet value by 1
     fn increment(droplet: &mut Droplet) -> Result<()> {
-        droplet.set_value(droplet.value.clone() + TubularBigInt::new(1));
+        droplet.set_value(droplet.value.clone() + TubularNumber::new(1));
         Ok(())
     }
 
     /// Decrement (~) - Decrement droplet value by 1
     fn decrement(droplet: &mut Droplet) -> Result<()> {
-        droplet.set_value(droplet.value.clone() - TubularBigInt::new(1));
+        droplet.set_value(droplet.value.clone() - TubularNumber::new(1));
+        Ok(())
+    }
+
+    /// And (&), Or (o), Xor (x) - Pop b then a, combine their bits into the droplet value
+    fn bitwise(
+        droplet: &mut Droplet,
+        stack: &mut DataStack,
+        operation: fn(TubularNumber, TubularNumber) -> TubularNumber,
+    ) -> Result<()> {
+        let b = stack.pop_or_zero();
+        let a = stack.pop_or_zero();
+        droplet.set_value(operation(a, b));
+        Ok(())
+    }
+
+    /// Not (N) - Flip every bit of the droplet value, giving -value - 1
+    fn not(droplet: &mut Droplet) -> Result<()> {
+        droplet.set_value(!droplet.value.clone());
+        Ok(())
+    }
+
+    /// Shift left ([) / right (]) - Pop a bit count b then a value a; the
+    /// droplet value becomes a shifted by b bits, the other way if b is negative
+    ///
+    /// Right shifts round toward negative infinity, so -1 stays -1.
+    fn shift(droplet: &mut Droplet, stack: &mut DataStack, left: bool) -> Result<()> {
+        let bits = stack.pop_or_zero();
+        let a = stack.pop_or_zero();
+        let left = left != bits.is_negative();
+        let result = match bits.abs().to_usize() {
+            _ if a.is_zero() => TubularNumber::zero(),
+            Some(bits) if !left => a.shr(bits),
+            Some(bits) if bits <= MAX_SHIFT_BITS => a.shl(bits),
+            // Shifting right past every bit leaves only the sign
+            None if !left => if a.is_negative() { TubularNumber::new(-1) } else { TubularNumber::zero() },
+            _ => return Err(ExecError::ShiftTooLarge(MAX_SHIFT_BITS).into()),
+        };
+        droplet.set_value(result);
+        Ok(())
+    }
+
+    /// Power (*) - Pop an exponent b then a base a; the droplet value becomes a to the power b
+    ///
+    /// A negative exponent is an error, as is a result of more than
+    /// [`MAX_POWER_BITS`] bits; 0 to the power 0 is 1.
+    fn power(droplet: &mut Droplet, stack: &mut DataStack) -> Result<()> {
+        let b = stack.pop_or_zero();
+        let a = stack.pop_or_zero();
+        if b.is_negative() {
+            return Err(ExecError::NegativeExponent.into());
+        }
+        let result = if b.is_zero() || a == TubularNumber::one() {
+            TubularNumber::one()
+        } else if a.is_zero() {
+            TubularNumber::zero()
+        } else if a == TubularNumber::new(-1) {
+            // Only the exponent's parity matters, however large it is
+            if b.safe_mod(&TubularNumber::new(2)).is_zero() { TubularNumber::one() } else { a }
+        } else {
+            // |a| >= 2, so the result has between (bits(a) - 1) * b + 1 and bits(a) * b bits
+            let exponent = b.to_usize()
+                .filter(|&b| (a.bits() - 1).saturating_mul(b as u64) < MAX_POWER_BITS)
+                .ok_or(ExecError::PowerTooLarge(MAX_POWER_BITS))?;
+            let result = a.pow(exponent as u32);
+            // Only the upper bound can be wrong, and by less than a factor of two
+            if result.bits() > MAX_POWER_BITS {
+                return Err(ExecError::PowerTooLarge(MAX_POWER_BITS).into());
+            }
+            result
+        };
+        droplet.set_value(result);
+        Ok(())
+    }
+
+    /// Absolute value (_) - Pop a; the droplet value becomes |a|
+    fn absolute(droplet: &mut Droplet, stack: &mut DataStack) -> Result<()> {
+        droplet.set_value(stack.pop_or_zero().abs());
+        Ok(())
+    }
+
+    /// Truncate (t) - Drop the fractional part of the droplet value, rounding toward zero
+    fn truncate(droplet: &mut Droplet) -> Result<()> {
+        droplet.set_value(droplet.value.truncate());
+        Ok(())
+    }
+
+    /// Divide (D) for `%rational` programs - Pop two values; the droplet value
+    /// becomes the exact fraction a / b (division by zero = 0)
+    #[cfg(feature = "rational")]
+    pub fn process_exact_division(droplet: &mut Droplet, stack: &mut DataStack) -> Result<()> {
+        let b = stack.pop_or_zero();
+        let a = stack.pop_or_zero();
+        droplet.set_value(a.exact_div(&b));
         Ok(())
     }
 
@@ -173,6 +282,7 @@ impl ArithmeticOperations {
     pub fn is_arithmetic_operation(symbol: char) -> bool {
         matches!(symbol,
             ':' | ';' | 'd' | 'A' | 'S' | 'M' | 'D' | '=' | '<' | '>' | '%' | '+' | '~'
+            | '&' | 'o' | 'x' | 'N' | '[' | ']' | '*' | '_' | 't'
         )
     }
 
@@ -187,10 +297,11 @@ mod tests {
     use super::*;
     use crate::types::coordinate::Coordinate;
     use crate::types::direction::Direction;
+    use crate::types::error::InterpreterError;
 
     fn create_test_droplet(id: u64, value: i64) -> Droplet {
         let mut droplet = Droplet::new(id, Coordinate::new(0, 0), Direction::Down);
-        droplet.set_value(TubularBigInt::new(value));
+        droplet.set_value(TubularNumber::new(value));
         droplet
     }
 
@@ -199,10 +310,10 @@ mod tests {
         let mut droplet = create_test_droplet(0, 0);
 
         ArithmeticOperations::process_numeric_literal(&mut droplet, '5').unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(5));
+        assert_eq!(droplet.value, TubularNumber::new(5));
 
         ArithmeticOperations::process_numeric_literal(&mut droplet, '9').unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(9));
+        assert_eq!(droplet.value, TubularNumber::new(9));
     }
 
     #[test]
@@ -213,12 +324,12 @@ mod tests {
         // Push value
         ArithmeticOperations::push(&droplet, &mut stack).unwrap();
         assert_eq!(stack.depth(), 1);
-        assert_eq!(stack.peek(), TubularBigInt::new(42));
+        assert_eq!(stack.peek(), TubularNumber::new(42));
 
         // Pop value
-        droplet.set_value(TubularBigInt::zero());
+        droplet.set_value(TubularNumber::zero());
         ArithmeticOperations::pop(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(42));
+        assert_eq!(droplet.value, TubularNumber::new(42));
         assert_eq!(stack.depth(), 0);
     }
 
@@ -229,12 +340,12 @@ mod tests {
 
         // Pop from empty stack should give 0
         ArithmeticOperations::pop(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::zero());
+        assert_eq!(droplet.value, TubularNumber::zero());
 
         // Duplicate on empty stack should push 0
         ArithmeticOperations::duplicate(&mut stack).unwrap();
         assert_eq!(stack.depth(), 1);
-        assert_eq!(stack.peek(), TubularBigInt::zero());
+        assert_eq!(stack.peek(), TubularNumber::zero());
     }
 
     #[test]
@@ -243,22 +354,22 @@ mod tests {
         let mut stack = DataStack::new();
 
         // Test addition: 5 + 3 = 8
-        stack.push(TubularBigInt::new(5));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(5));
+        stack.push(TubularNumber::new(3));
         ArithmeticOperations::add(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(8));
+        assert_eq!(droplet.value, TubularNumber::new(8));
 
         // Test subtraction: 10 - 4 = 6
-        stack.push(TubularBigInt::new(10));
-        stack.push(TubularBigInt::new(4));
+        stack.push(TubularNumber::new(10));
+        stack.push(TubularNumber::new(4));
         ArithmeticOperations::subtract(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(6));
+        assert_eq!(droplet.value, TubularNumber::new(6));
 
         // Test multiplication: 3 * 7 = 21
-        stack.push(TubularBigInt::new(3));
-        stack.push(TubularBigInt::new(7));
+        stack.push(TubularNumber::new(3));
+        stack.push(TubularNumber::new(7));
         ArithmeticOperations::multiply(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(21));
+        assert_eq!(droplet.value, TubularNumber::new(21));
     }
 
     #[test]
@@ -267,10 +378,10 @@ mod tests {
         let mut stack = DataStack::new();
 
         // 5 / 0 = 0 (division by zero protection)
-        stack.push(TubularBigInt::new(5));
-        stack.push(TubularBigInt::zero());
+        stack.push(TubularNumber::new(5));
+        stack.push(TubularNumber::zero());
         ArithmeticOperations::divide(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::zero());
+        assert_eq!(droplet.value, TubularNumber::zero());
     }
 
     #[test]
@@ -279,22 +390,22 @@ mod tests {
         let mut stack = DataStack::new();
 
         // 5 < 10 = 1 (true)
-        stack.push(TubularBigInt::new(5));
-        stack.push(TubularBigInt::new(10));
+        stack.push(TubularNumber::new(5));
+        stack.push(TubularNumber::new(10));
         ArithmeticOperations::less_than(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::one());
+        assert_eq!(droplet.value, TubularNumber::one());
 
         // 10 > 5 = 1 (true)
-        stack.push(TubularBigInt::new(10));
-        stack.push(TubularBigInt::new(5));
+        stack.push(TubularNumber::new(10));
+        stack.push(TubularNumber::new(5));
         ArithmeticOperations::greater_than(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::one());
+        assert_eq!(droplet.value, TubularNumber::one());
 
         // 7 = 7 = 1 (true)
-        stack.push(TubularBigInt::new(7));
-        stack.push(TubularBigInt::new(7));
+        stack.push(TubularNumber::new(7));
+        stack.push(TubularNumber::new(7));
         ArithmeticOperations::equals(&mut droplet, &mut stack).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::one());
+        assert_eq!(droplet.value, TubularNumber::one());
     }
 
     #[test]
@@ -302,9 +413,96 @@ mod tests {
         let mut droplet = create_test_droplet(0, 5);
 
         ArithmeticOperations::increment(&mut droplet).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(6));
+        assert_eq!(droplet.value, TubularNumber::new(6));
 
         ArithmeticOperations::decrement(&mut droplet).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(5));
+        assert_eq!(droplet.value, TubularNumber::new(5));
     }
-}
\ No newline at end of file
+
+    fn run(operation: char, a: i64, b: i64) -> Result<TubularNumber> {
+        let mut droplet = create_test_droplet(0, 0);
+        let mut stack = DataStack::new();
+        stack.push(TubularNumber::new(a));
+        stack.push(TubularNumber::new(b));
+        ArithmeticOperations::process_stack_operation(operation, &mut droplet, &mut stack)?;
+        Ok(droplet.value)
+    }
+
+    #[test]
+    fn test_bitwise_operations() {
+        assert_eq!(run('&', 12, 10).unwrap(), TubularNumber::new(8));
+        assert_eq!(run('o', 12, 10).unwrap(), TubularNumber::new(14));
+        assert_eq!(run('x', 12, 10).unwrap(), TubularNumber::new(6));
+        // Negative values behave as two's complement
+        assert_eq!(run('&', -1, 10).unwrap(), TubularNumber::new(10));
+
+        let mut droplet = create_test_droplet(0, 5);
+        ArithmeticOperations::process_stack_operation('N', &mut droplet, &mut DataStack::new()).unwrap();
+        assert_eq!(droplet.value, TubularNumber::new(-6));
+    }
+
+    #[test]
+    fn test_shifts() {
+        assert_eq!(run('[', 3, 4).unwrap(), TubularNumber::new(48));
+        assert_eq!(run(']', 48, 4).unwrap(), TubularNumber::new(3));
+        assert_eq!(run(']', -5, 1).unwrap(), TubularNumber::new(-3));
+        // A negative count shifts the other way
+        assert_eq!(run('[', 48, -4).unwrap(), TubularNumber::new(3));
+        assert_eq!(run('[', 1, 100).unwrap().to_string(), "1267650600228229401496703205376");
+        assert_eq!(run(']', -7, i64::MAX).unwrap(), TubularNumber::new(-1));
+        assert_eq!(run('[', 0, i64::MAX).unwrap(), TubularNumber::zero());
+
+        let error = run('[', 1, MAX_SHIFT_BITS as i64 + 1).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::ShiftTooLarge(MAX_SHIFT_BITS))));
+    }
+
+    #[test]
+    fn test_power_and_absolute_value() {
+        assert_eq!(run('*', 3, 4).unwrap(), TubularNumber::new(81));
+        assert_eq!(run('*', -2, 3).unwrap(), TubularNumber::new(-8));
+        assert_eq!(run('*', 0, 0).unwrap(), TubularNumber::one());
+        assert_eq!(run('*', 2, 100).unwrap().to_string(), "1267650600228229401496703205376");
+        // Bases of 0, 1 and -1 take any exponent
+        assert_eq!(run('*', -1, i64::MAX).unwrap(), TubularNumber::new(-1));
+        assert_eq!(run('*', 0, i64::MAX).unwrap(), TubularNumber::zero());
+
+        let error = run('*', 2, -1).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::NegativeExponent)));
+        let error = run('*', 2, MAX_POWER_BITS as i64 + 1).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::PowerTooLarge(MAX_POWER_BITS))));
+        assert_eq!(run('*', 2, MAX_POWER_BITS as i64 - 1).unwrap().bits(), MAX_POWER_BITS);
+        let error = run('*', 2, MAX_POWER_BITS as i64).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::PowerTooLarge(MAX_POWER_BITS))));
+        // 3^b has about 1.58 * b bits, more than (bits(3) - 1) * b
+        let error = run('*', 3, MAX_POWER_BITS as i64).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::PowerTooLarge(MAX_POWER_BITS))));
+        let error = run('*', 3, 700_000).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::PowerTooLarge(MAX_POWER_BITS))));
+
+        // `_` pops only a; the value below it stays on the stack
+        let mut droplet = create_test_droplet(0, 0);
+        let mut stack = DataStack::new();
+        stack.push(TubularNumber::new(4));
+        stack.push(TubularNumber::new(-12));
+        ArithmeticOperations::process_stack_operation('_', &mut droplet, &mut stack).unwrap();
+        assert_eq!(droplet.value, TubularNumber::new(12));
+        assert_eq!(stack.as_slice(), &[TubularNumber::new(4)]);
+    }
+
+    #[cfg(feature = "rational")]
+    #[test]
+    fn test_exact_division_and_truncate() {
+        let mut droplet = create_test_droplet(0, 0);
+        let mut stack = DataStack::new();
+        stack.push(TubularNumber::new(-7));
+        stack.push(TubularNumber::new(2));
+        ArithmeticOperations::process_exact_division(&mut droplet, &mut stack).unwrap();
+        assert_eq!(droplet.value.to_string(), "-7/2");
+
+        ArithmeticOperations::process_stack_operation('t', &mut droplet, &mut stack).unwrap();
+        assert_eq!(droplet.value, TubularNumber::new(-3));
+        // Integers are already whole
+        ArithmeticOperations::process_stack_operation('t', &mut droplet, &mut stack).unwrap();
+        assert_eq!(droplet.value, TubularNumber::new(-3));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::types::direction::Direction;
 use crate::interpreter::grid::ProgramCell;
 use crate::interpreter::droplet::Droplet;
-use crate::types::bigint::TubularBigInt;
+use crate::types::prelude::*;
 
 /// Flow control operations for pipe symbols
 pub struct FlowControlOperations;
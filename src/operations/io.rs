@@ -1,10 +1,601 @@
 use crate::interpreter::droplet::Droplet;
-use crate::types::error::{Result, SystemError};
-use crate::types::bigint::TubularBigInt;
-use std::io::{self, BufRead, Read};
+use crate::interpreter::stack::DataStack;
+use crate::types::error::{ExecError, Result, SystemError};
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, Write};
+#[cfg(feature = "std")]
 use std::sync::{Arc, Mutex};
 
+/// Source of the values read by `?` and `??`
+pub trait InputProvider: Send {
+    /// Next character for `?`; None once input is exhausted
+    fn read_char(&mut self) -> Result<Option<char>>;
+
+    /// Next line for `??` without its line ending; empty once input is exhausted
+    fn read_line(&mut self) -> Result<String>;
+
+    /// Next line exactly as given, line ending included; None once input is
+    /// exhausted. Used by [`InputReader`] to split input itself.
+    ///
+    /// Defaults to gathering characters from [`read_char`](Self::read_char)
+    /// up to and including a newline.
+    fn read_raw_line(&mut self) -> Result<Option<String>> {
+        let mut line = String::new();
+        while let Some(ch) = self.read_char()? {
+            line.push(ch);
+            if ch == '\n' {
+                break;
+            }
+        }
+        Ok(if line.is_empty() { None } else { Some(line) })
+    }
+
+    /// [`read_raw_line`](Self::read_raw_line) for a line `request` is waiting
+    /// on, so providers that prompt can say what is wanted
+    fn read_raw_line_for(&mut self, _request: InputRequest) -> Result<Option<String>> {
+        self.read_raw_line()
+    }
+}
+
+/// Operator waiting on input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputRequest {
+    /// `?`
+    Char,
+    /// `??`
+    Number,
+}
+
+/// Destination for program output as each tick produces it
+///
+/// The interpreter also keeps the full output in `ExecutionResult::final_output`,
+/// so a sink is only needed to see output while the program runs.
+pub trait OutputSink: Send {
+    fn write_str(&mut self, text: &str) -> Result<()>;
+
+    /// Deliver anything held back; called when execution stops
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads from the process's stdin, one line per read
+///
+/// Read directly, `?` takes the first character of the line and discards the
+/// rest; the interpreter reads whole lines and splits them with [`InputReader`].
+#[cfg(feature = "stdin")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdinInput;
+
+/// Input used when the host supplies none: stdin with the `stdin` feature,
+/// otherwise nothing, so every read is end of input
+#[cfg(feature = "stdin")]
+pub type DefaultInput = StdinInput;
+
+/// Input used when the host supplies none: stdin with the `stdin` feature,
+/// otherwise nothing, so every read is end of input
+#[cfg(not(feature = "stdin"))]
+pub type DefaultInput = StringInput;
+
+#[cfg(feature = "stdin")]
+impl InputProvider for StdinInput {
+    fn read_char(&mut self) -> Result<Option<char>> {
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(input.chars().next()),
+            Err(e) => Err(SystemError::IoError(format!("Failed to read character from stdin: {}", e)).into()),
+        }
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(_) => Ok(input.trim().to_string()),
+            Err(e) => Err(SystemError::IoError(format!("Failed to read line from stdin: {}", e)).into()),
+        }
+    }
+
+    fn read_raw_line(&mut self) -> Result<Option<String>> {
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(input)),
+            Err(e) => Err(SystemError::IoError(format!("Failed to read line from stdin: {}", e)).into()),
+        }
+    }
+}
+
+/// Reads from a buffered reader, such as an open file, one line per read
+///
+/// Input arrives exactly as it would from `StdinInput` with the reader
+/// redirected to stdin.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ReaderInput<R> {
+    reader: R,
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead + Send> ReaderInput<R> {
+    pub fn new(reader: R) -> Self {
+        ReaderInput { reader }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead + Send> InputProvider for ReaderInput<R> {
+    fn read_char(&mut self) -> Result<Option<char>> {
+        let mut input = String::new();
+        match self.reader.read_line(&mut input) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(input.chars().next()),
+            Err(e) => Err(SystemError::IoError(format!("Failed to read character input: {}", e)).into()),
+        }
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        let mut input = String::new();
+        match self.reader.read_line(&mut input) {
+            Ok(_) => Ok(input.trim().to_string()),
+            Err(e) => Err(SystemError::IoError(format!("Failed to read line input: {}", e)).into()),
+        }
+    }
+
+    fn read_raw_line(&mut self) -> Result<Option<String>> {
+        let mut input = String::new();
+        match self.reader.read_line(&mut input) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(input)),
+            Err(e) => Err(SystemError::IoError(format!("Failed to read line input: {}", e)).into()),
+        }
+    }
+}
+
+/// A [`ReaderInput`] whose clones all read from the same reader, in turn
+///
+/// Lets a front-end such as the REPL read its own commands from the stream
+/// that also carries the program's input, without holding a lock on it
+/// while the program runs.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct SharedReaderInput {
+    inner: Arc<Mutex<ReaderInput<Box<dyn BufRead + Send>>>>,
+}
+
+#[cfg(feature = "std")]
+impl SharedReaderInput {
+    pub fn new(reader: impl BufRead + Send + 'static) -> Self {
+        SharedReaderInput { inner: Arc::new(Mutex::new(ReaderInput::new(Box::new(reader)))) }
+    }
+
+    fn reader(&self) -> std::sync::MutexGuard<'_, ReaderInput<Box<dyn BufRead + Send>>> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(feature = "std")]
+impl InputProvider for SharedReaderInput {
+    fn read_char(&mut self) -> Result<Option<char>> {
+        self.reader().read_char()
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        self.reader().read_line()
+    }
+
+    fn read_raw_line(&mut self) -> Result<Option<String>> {
+        self.reader().read_raw_line()
+    }
+}
+
+/// Reads from an in-memory string, one character at a time
+///
+/// Each clone starts reading from the beginning.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StringInput {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl StringInput {
+    pub fn new(input: &str) -> Self {
+        StringInput { chars: input.chars().collect(), position: 0 }
+    }
+
+    /// Characters not read yet
+    pub fn remaining(&self) -> usize {
+        self.chars.len() - self.position
+    }
+}
+
+impl InputProvider for StringInput {
+    fn read_char(&mut self) -> Result<Option<char>> {
+        let ch = self.chars.get(self.position).copied();
+        self.position = (self.position + 1).min(self.chars.len());
+        Ok(ch)
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        let rest = &self.chars[self.position..];
+        let end = rest.iter().position(|&ch| ch == '\n');
+        let line: String = rest[..end.unwrap_or(rest.len())].iter().collect();
+        self.position += end.map_or(rest.len(), |end| end + 1);
+        Ok(line.trim_end_matches('\r').to_string())
+    }
+
+    fn read_raw_line(&mut self) -> Result<Option<String>> {
+        let rest = &self.chars[self.position..];
+        if rest.is_empty() {
+            return Ok(None);
+        }
+        let end = rest.iter().position(|&ch| ch == '\n').map_or(rest.len(), |end| end + 1);
+        self.position += end;
+        Ok(Some(rest[..end].iter().collect()))
+    }
+}
+
+/// Discards output; the default, since the CLI prints `final_output` when the run ends
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullOutput;
+
+impl OutputSink for NullOutput {
+    fn write_str(&mut self, _text: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// When a [`StdoutSink`] passes output on to stdout
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputBuffering {
+    /// Write every tick's output immediately
+    None,
+    /// Write whenever a newline is output
+    #[default]
+    Line,
+    /// Write when the buffer fills and when execution stops
+    Full,
+}
+
+#[cfg(feature = "std")]
+impl FromStr for OutputBuffering {
+    type Err = String;
+
+    fn from_str(text: &str) -> core::result::Result<Self, Self::Err> {
+        match text {
+            "none" => Ok(OutputBuffering::None),
+            "line" => Ok(OutputBuffering::Line),
+            "full" => Ok(OutputBuffering::Full),
+            _ => Err(format!("Unknown output buffering '{}', expected line, none or full", text)),
+        }
+    }
+}
+
+/// Bytes a fully buffered [`StdoutSink`] holds before writing
+#[cfg(feature = "std")]
+const FULL_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Writes output to the process's stdout while the program runs
+///
+/// Held-back output is written by `flush` and when the sink is dropped.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct StdoutSink {
+    buffering: OutputBuffering,
+    pending: String,
+}
+
+#[cfg(feature = "std")]
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_buffering(mut self, buffering: OutputBuffering) -> Self {
+        self.buffering = buffering;
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl OutputSink for StdoutSink {
+    fn write_str(&mut self, text: &str) -> Result<()> {
+        self.pending.push_str(text);
+        let ready = match self.buffering {
+            OutputBuffering::None => true,
+            OutputBuffering::Line => text.contains('\n'),
+            OutputBuffering::Full => self.pending.len() >= FULL_BUFFER_BYTES,
+        };
+        if ready { self.flush() } else { Ok(()) }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(self.pending.as_bytes())
+            .and_then(|()| stdout.flush())
+            .map_err(|e| SystemError::IoError(format!("Failed to write output: {}", e)))?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for StdoutSink {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Collects output in a buffer shared by all its clones
+///
+/// Hand one clone to the interpreter and keep another to read the output.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct SharedOutput {
+    buffer: Arc<Mutex<String>>,
+}
+
+#[cfg(feature = "std")]
+impl SharedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contents(&self) -> String {
+        self.buffer.lock().unwrap().clone()
+    }
+}
+
+#[cfg(feature = "std")]
+impl OutputSink for SharedOutput {
+    fn write_str(&mut self, text: &str) -> Result<()> {
+        self.buffer.lock().unwrap().push_str(text);
+        Ok(())
+    }
+}
+
+/// Host resource that an operation needs permission to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Opening, reading and writing files (`O`, `I`, `W`, `X`)
+    Filesystem,
+    /// Connecting to and talking over TCP sockets (`T`, `E`, `V`)
+    Network,
+    /// Rewriting the program's own cells (`p`)
+    SelfModify,
+}
+
+impl Capability {
+    /// Command-line flag that grants this capability
+    pub fn flag(self) -> &'static str {
+        match self {
+            Capability::Filesystem => "--allow-fs",
+            Capability::Network => "--allow-net",
+            Capability::SelfModify => "--allow-self-modify",
+        }
+    }
+
+    /// Capability the operation `symbol` needs, if any
+    pub fn required_by(symbol: char) -> Option<Self> {
+        match symbol {
+            'O' | 'I' | 'W' | 'X' => Some(Capability::Filesystem),
+            'T' | 'E' | 'V' => Some(Capability::Network),
+            'p' => Some(Capability::SelfModify),
+            _ => None,
+        }
+    }
+}
+
+/// Capabilities granted to a run; everything is denied by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    filesystem: bool,
+    network: bool,
+    self_modify: bool,
+}
+
+impl Capabilities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grant or revoke `capability`
+    pub fn with(mut self, capability: Capability, granted: bool) -> Self {
+        match capability {
+            Capability::Filesystem => self.filesystem = granted,
+            Capability::Network => self.network = granted,
+            Capability::SelfModify => self.self_modify = granted,
+        }
+        self
+    }
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Filesystem => self.filesystem,
+            Capability::Network => self.network,
+            Capability::SelfModify => self.self_modify,
+        }
+    }
+
+    /// Fail unless every capability the operation `symbol` needs is granted
+    pub fn check(&self, symbol: char) -> Result<()> {
+        match Capability::required_by(symbol) {
+            Some(capability) if !self.allows(capability) => {
+                Err(ExecError::CapabilityDenied(symbol, capability.flag().to_string()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How `O` opens a file, from the mode popped off the stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileMode {
+    /// 0: read from the start
+    Read,
+    /// 1: create or truncate, then write
+    Write,
+    /// 2: create if needed, then write at the end
+    Append,
+}
+
+impl FileMode {
+    pub fn from_value(value: &TubularNumber) -> Option<Self> {
+        match value.to_i64()? {
+            0 => Some(FileMode::Read),
+            1 => Some(FileMode::Write),
+            2 => Some(FileMode::Append),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+enum OpenFile {
+    Reader(io::BufReader<std::fs::File>),
+    Writer(io::BufWriter<std::fs::File>),
+}
+
+/// Files opened by a program, indexed by descriptor
+///
+/// Descriptors are small non-negative integers; a closed descriptor is reused
+/// by the next `open`. Failures are reported as -1 to the program rather than
+/// as errors. Open files are not part of snapshots or undo history: a clone
+/// starts with no files open. Writers are flushed on close and when dropped.
+#[derive(Debug, Default)]
+pub struct FileTable {
+    #[cfg(feature = "std")]
+    files: Vec<Option<OpenFile>>,
+}
+
+impl FileTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileTable {
+
+    /// Open `path` and return its descriptor, or -1 if it cannot be opened
+    pub fn open(&mut self, path: &str, mode: FileMode) -> i64 {
+        let mut options = std::fs::OpenOptions::new();
+        match mode {
+            FileMode::Read => options.read(true),
+            FileMode::Write => options.write(true).create(true).truncate(true),
+            FileMode::Append => options.append(true).create(true),
+        };
+        let file = match options.open(path) {
+            Ok(file) => file,
+            Err(_) => return -1,
+        };
+        let file = match mode {
+            FileMode::Read => OpenFile::Reader(io::BufReader::new(file)),
+            FileMode::Write | FileMode::Append => OpenFile::Writer(io::BufWriter::new(file)),
+        };
+
+        match self.files.iter().position(Option::is_none) {
+            Some(descriptor) => {
+                self.files[descriptor] = Some(file);
+                descriptor as i64
+            }
+            None => {
+                self.files.push(Some(file));
+                (self.files.len() - 1) as i64
+            }
+        }
+    }
+
+    fn get(&mut self, descriptor: &TubularNumber) -> Option<&mut OpenFile> {
+        let index = descriptor.to_usize()?;
+        self.files.get_mut(index)?.as_mut()
+    }
+
+    /// Next byte from a file opened for reading; -1 at the end or on error
+    pub fn read_byte(&mut self, descriptor: &TubularNumber) -> i64 {
+        use std::io::Read;
+        let Some(OpenFile::Reader(reader)) = self.get(descriptor) else {
+            return -1;
+        };
+        let mut byte = [0u8];
+        match reader.read(&mut byte) {
+            Ok(1) => byte[0] as i64,
+            _ => -1,
+        }
+    }
+
+    /// Write the low byte of `value` to a file opened for writing
+    ///
+    /// Returns false if the descriptor is not writable or the write failed.
+    pub fn write_byte(&mut self, descriptor: &TubularNumber, value: &TubularNumber) -> bool {
+        let Some(OpenFile::Writer(writer)) = self.get(descriptor) else {
+            return false;
+        };
+        let byte = value.to_integer().as_bigint().to_signed_bytes_le().first().copied().unwrap_or(0);
+        writer.write_all(&[byte]).is_ok()
+    }
+
+    /// Close a descriptor, flushing it if it was written; false if it was not open
+    pub fn close(&mut self, descriptor: &TubularNumber) -> bool {
+        let Some(slot) = descriptor.to_usize().and_then(|index| self.files.get_mut(index)) else {
+            return false;
+        };
+        match slot.take() {
+            Some(OpenFile::Writer(mut writer)) => writer.flush().is_ok(),
+            Some(OpenFile::Reader(_)) => true,
+            None => false,
+        }
+    }
+
+    /// Number of descriptors currently open
+    pub fn open_count(&self) -> usize {
+        self.files.iter().filter(|file| file.is_some()).count()
+    }
+}
+
+/// Builds without std have no filesystem, so every file fails to open
+#[cfg(not(feature = "std"))]
+impl FileTable {
+    pub fn open(&mut self, _path: &str, _mode: FileMode) -> i64 {
+        -1
+    }
+
+    pub fn read_byte(&mut self, _descriptor: &TubularNumber) -> i64 {
+        -1
+    }
+
+    pub fn write_byte(&mut self, _descriptor: &TubularNumber, _value: &TubularNumber) -> bool {
+        false
+    }
+
+    pub fn close(&mut self, _descriptor: &TubularNumber) -> bool {
+        false
+    }
+
+    pub fn open_count(&self) -> usize {
+        0
+    }
+}
+
+impl Clone for FileTable {
+    /// File handles cannot be shared, so a clone has no files open
+    fn clone(&self) -> Self {
+        FileTable::new()
+    }
+}
+
 /// Thread-safe input buffer for managing program input
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub struct InputBuffer {
     buffer: Arc<Mutex<Vec<String>>>,
@@ -12,6 +603,7 @@ pub struct InputBuffer {
     position: Arc<Mutex<usize>>,
 }
 
+#[cfg(feature = "std")]
 impl InputBuffer {
     /// Create a new input buffer
     pub fn new() -> Self {
@@ -45,11 +637,11 @@ impl InputBuffer {
                 *current_line = Some(buffer[*position].clone());
                 *position += 1;
             } else {
-                // No more buffered input, read from stdin
+                // No more buffered input, read from the default input
                 drop(current_line);
                 drop(buffer);
                 drop(position);
-                return self.read_char_from_stdin();
+                return self.read_char_fallback();
             }
         }
 
@@ -76,9 +668,9 @@ impl InputBuffer {
             }
         }
 
-        // Fallback: try stdin
+        // Fallback: try the default input
         drop(current_line);
-        self.read_char_from_stdin()
+        self.read_char_fallback()
     }
 
     /// Read a line of text from input
@@ -91,40 +683,219 @@ impl InputBuffer {
             *position += 1;
             Ok(line)
         } else {
-            // No more buffered input, read from stdin
+            // No more buffered input, read from the default input
             drop(buffer);
             drop(position);
-            self.read_line_from_stdin()
+            self.read_line_fallback()
         }
     }
 
-    /// Read a single character from stdin
-    fn read_char_from_stdin(&self) -> Result<char> {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                if let Some(ch) = input.chars().next() {
-                    Ok(ch)
+    /// Read a single character from the default input
+    fn read_char_fallback(&self) -> Result<char> {
+        Ok(DefaultInput::default().read_char()?.unwrap_or('\n'))
+    }
+
+    /// Read a line from the default input
+    fn read_line_fallback(&self) -> Result<String> {
+        DefaultInput::default().read_line()
+    }
+
+    /// Check if input validation should be strict
+    pub fn validation_mode(&self) -> ValidationMode {
+        ValidationMode::Lenient
+    }
+}
+
+/// Buffered lines first, then the default input; clones share the same position
+#[cfg(feature = "std")]
+impl InputProvider for InputBuffer {
+    fn read_char(&mut self) -> Result<Option<char>> {
+        InputBuffer::read_char(self).map(Some)
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        InputBuffer::read_line(self)
+    }
+
+    fn read_raw_line(&mut self) -> Result<Option<String>> {
+        let buffer = self.buffer.lock().unwrap();
+        let mut position = self.position.lock().unwrap();
+        if *position < buffer.len() {
+            let line = format!("{}\n", buffer[*position]);
+            *position += 1;
+            Ok(Some(line))
+        } else {
+            drop(buffer);
+            drop(position);
+            DefaultInput::default().read_raw_line()
+        }
+    }
+}
+
+/// How `?` and `??` divide up their input, chosen with `--input-mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputMode {
+    /// `?` reads every character in turn, newlines included, and `??` the
+    /// rest of the line as a number
+    #[default]
+    Chars,
+    /// `?` reads the first character of the next line and `??` the next line
+    /// as a number
+    Lines,
+    /// `??` reads the next whitespace-separated integer and stops the program
+    /// if it is not one; `?` reads every character in turn
+    Numbers,
+}
+
+impl InputMode {
+    /// Every mode, in the order `--input-mode` lists them
+    pub const ALL: [InputMode; 3] = [InputMode::Chars, InputMode::Lines, InputMode::Numbers];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InputMode::Chars => "chars",
+            InputMode::Lines => "lines",
+            InputMode::Numbers => "numbers",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+/// What `?` gives once input is exhausted
+///
+/// Chosen with `--eof` or an `%eof` line in the program; the command line
+/// wins when both are given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EofPolicy {
+    /// 10, as if input ended with an empty line
+    #[default]
+    Newline,
+    /// 0
+    Zero,
+    /// -1, as C's `getchar` gives
+    MinusOne,
+    /// The program stops with an error
+    Error,
+}
+
+impl EofPolicy {
+    /// Every policy, in the order `--eof` lists them
+    pub const ALL: [EofPolicy; 4] = [EofPolicy::Newline, EofPolicy::Zero, EofPolicy::MinusOne, EofPolicy::Error];
+
+    /// Name used by `--eof` and `%eof`
+    pub fn name(self) -> &'static str {
+        match self {
+            EofPolicy::Newline => "newline",
+            EofPolicy::Zero => "zero",
+            EofPolicy::MinusOne => "minus-one",
+            EofPolicy::Error => "error",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|policy| policy.name() == name)
+    }
+
+    /// Value `?` gives at end of input, or the error that stops the program
+    pub fn value(self) -> Result<i64> {
+        match self {
+            EofPolicy::Newline => Ok('\n' as i64),
+            EofPolicy::Zero => Ok(0),
+            EofPolicy::MinusOne => Ok(-1),
+            EofPolicy::Error => Err(ExecError::EndOfInput.into()),
+        }
+    }
+}
+
+impl core::fmt::Display for EofPolicy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Splits what an [`InputProvider`] supplies into what `?` and `??` read,
+/// as an [`InputMode`] says
+///
+/// The interpreter owns one for the whole run, so every `?` and `??` shares
+/// it. Characters of a line that were read but not used yet wait here for
+/// the next read, which lets several `?` take their characters from one
+/// typed line.
+#[derive(Debug, Clone, Default)]
+pub struct InputReader {
+    mode: InputMode,
+    pending: VecDeque<char>,
+}
+
+impl InputReader {
+    pub fn new(mode: InputMode) -> Self {
+        InputReader { mode, pending: VecDeque::new() }
+    }
+
+    /// Character for `?`; None once input is exhausted
+    pub fn read_char(&mut self, input: &mut dyn InputProvider) -> Result<Option<char>> {
+        match self.mode {
+            InputMode::Lines => Ok(input.read_raw_line_for(InputRequest::Char)?.map(|line| {
+                line.trim_end_matches(['\r', '\n']).chars().next().unwrap_or('\n')
+            })),
+            InputMode::Chars | InputMode::Numbers => self.next_char(input, InputRequest::Char),
+        }
+    }
+
+    /// Number for `??`, as text; "0" once input is exhausted
+    pub fn read_number(&mut self, input: &mut dyn InputProvider) -> Result<String> {
+        let request = InputRequest::Number;
+        match self.mode {
+            InputMode::Lines => {
+                let line = input.read_raw_line_for(request)?.unwrap_or_default();
+                IoOperations::validate_and_parse_numeric(&line, ValidationMode::Lenient)
+            }
+            InputMode::Chars => {
+                // A line `?` has read all but its ending is used up; take the next one
+                if self.pending.iter().all(|ch| ch.is_whitespace()) {
+                    self.pending.clear();
+                }
+                let mut line = String::new();
+                while let Some(ch) = self.next_char(input, request)? && ch != '\n' {
+                    line.push(ch);
+                }
+                IoOperations::validate_and_parse_numeric(&line, ValidationMode::Lenient)
+            }
+            InputMode::Numbers => {
+                while self.peek_char(input, request)?.is_some_and(char::is_whitespace) {
+                    self.pending.pop_front();
+                }
+                let mut token = String::new();
+                while let Some(ch) = self.peek_char(input, request)? && !ch.is_whitespace() {
+                    token.push(ch);
+                    self.pending.pop_front();
+                }
+                if token.is_empty() {
+                    Ok("0".to_string())
+                } else if IoOperations::is_valid_integer(&token) {
+                    Ok(token)
                 } else {
-                    Ok('\n') // End of input
+                    Err(ExecError::InvalidNumericInput(token).into())
                 }
             }
-            Err(e) => Err(SystemError::IoError(format!("Failed to read character from stdin: {}", e)).into()),
         }
     }
 
-    /// Read a line from stdin
-    fn read_line_from_stdin(&self) -> Result<String> {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => Ok(input.trim().to_string()),
-            Err(e) => Err(SystemError::IoError(format!("Failed to read line from stdin: {}", e)).into()),
+    /// Next character without using it up, reading another line if none are waiting
+    fn peek_char(&mut self, input: &mut dyn InputProvider, request: InputRequest) -> Result<Option<char>> {
+        if self.pending.is_empty() && let Some(line) = input.read_raw_line_for(request)? {
+            self.pending.extend(line.chars());
         }
+        Ok(self.pending.front().copied())
     }
 
-    /// Check if input validation should be strict
-    pub fn validation_mode(&self) -> ValidationMode {
-        ValidationMode::Lenient
+    fn next_char(&mut self, input: &mut dyn InputProvider, request: InputRequest) -> Result<Option<char>> {
+        let ch = self.peek_char(input, request)?;
+        self.pending.pop_front();
+        Ok(ch)
     }
 }
 
@@ -163,29 +934,39 @@ impl IoOperations {
         Ok(String::new())
     }
 
-    /// Process character input (?) - read single character from stdin with buffering
+    /// Process character input (?) - read single character from the default input
     pub fn process_character_input() -> Result<String> {
-        let buffer = InputBuffer::new();
-        Self::process_character_input_with_buffer(&buffer)
+        Self::process_character_input_from(&mut DefaultInput::default())
     }
 
     /// Process character input with a specific buffer
+    #[cfg(feature = "std")]
     pub fn process_character_input_with_buffer(buffer: &InputBuffer) -> Result<String> {
-        match buffer.read_char() {
-            Ok(ch) => Ok(ch.to_string()),
+        Self::process_character_input_from(&mut buffer.clone())
+    }
+
+    /// Process character input (?) from any input provider
+    pub fn process_character_input_from(input: &mut dyn InputProvider) -> Result<String> {
+        match input.read_char() {
+            Ok(ch) => Ok(ch.unwrap_or('\n').to_string()),
             Err(e) => Err(SystemError::IoError(format!("Failed to read character input: {}", e)).into()),
         }
     }
 
-    /// Process numeric input (??) - read number from stdin with enhanced validation
+    /// Process numeric input (??) - read number from the default input with enhanced validation
     pub fn process_numeric_input() -> Result<String> {
-        let buffer = InputBuffer::new();
-        Self::process_numeric_input_with_buffer(&buffer, ValidationMode::Lenient)
+        Self::process_numeric_input_from(&mut DefaultInput::default(), ValidationMode::Lenient)
     }
 
     /// Process numeric input with a specific buffer and validation mode
+    #[cfg(feature = "std")]
     pub fn process_numeric_input_with_buffer(buffer: &InputBuffer, mode: ValidationMode) -> Result<String> {
-        match buffer.read_line() {
+        Self::process_numeric_input_from(&mut buffer.clone(), mode)
+    }
+
+    /// Process numeric input (??) from any input provider
+    pub fn process_numeric_input_from(input: &mut dyn InputProvider, mode: ValidationMode) -> Result<String> {
+        match input.read_line() {
             Ok(input_str) => {
                 Self::validate_and_parse_numeric(&input_str, mode)
             }
@@ -307,6 +1088,47 @@ impl IoOperations {
         }
     }
 
+    /// Process a file operation (O, I, W, X) against `files`
+    ///
+    /// `O` pops a mode, a length n and n character codes (pushed first
+    /// character first) and leaves the descriptor, or -1, in the droplet.
+    /// `I` pops a descriptor and leaves the next byte, or -1 at the end;
+    /// `W` pops a descriptor and writes the droplet value as a byte; `X` pops
+    /// a descriptor and closes it. Failures never stop the program.
+    pub fn process_file_operation(symbol: char, droplet: &mut Droplet, stack: &mut DataStack, files: &mut FileTable) {
+        match symbol {
+            'O' => {
+                let mode = FileMode::from_value(&stack.pop());
+                let length = stack.pop().to_i64().unwrap_or(0).max(0);
+                let mut path: Vec<char> = (0..length).map(|_| stack.pop().to_char().unwrap_or('\u{FFFD}')).collect();
+                path.reverse();
+                let descriptor = match mode {
+                    Some(mode) => files.open(&path.into_iter().collect::<String>(), mode),
+                    None => -1,
+                };
+                droplet.set_value(TubularNumber::new(descriptor));
+            }
+            'I' => {
+                let descriptor = stack.pop();
+                droplet.set_value(TubularNumber::new(files.read_byte(&descriptor)));
+            }
+            'W' => {
+                let descriptor = stack.pop();
+                files.write_byte(&descriptor, &droplet.value);
+            }
+            'X' => {
+                let descriptor = stack.pop();
+                files.close(&descriptor);
+            }
+            _ => {}
+        }
+    }
+
+    /// Check if a character is a file operation (needs --allow-fs)
+    pub fn is_file_operation(symbol: char) -> bool {
+        Capability::required_by(symbol) == Some(Capability::Filesystem)
+    }
+
     /// Check if a character is an I/O operation
     pub fn is_io_operation(symbol: char) -> bool {
         matches!(symbol, ',' | 'n' | '!' | '?')
@@ -331,7 +1153,7 @@ mod tests {
 
     fn create_test_droplet(id: u64, value: i64) -> Droplet {
         let mut droplet = Droplet::new(id, Coordinate::new(0, 0), Direction::Down);
-        droplet.set_value(TubularBigInt::new(value));
+        droplet.set_value(TubularNumber::new(value));
         droplet
     }
 
@@ -404,6 +1226,104 @@ mod tests {
         assert!(!IoOperations::is_data_source('|'));
     }
 
+    #[test]
+    fn test_string_input_reads_characters_and_lines() {
+        let mut input = StringInput::new("ab\n42\r\nx");
+        assert_eq!(IoOperations::process_character_input_from(&mut input).unwrap(), "a");
+        assert_eq!(input.read_line().unwrap(), "b");
+        assert_eq!(IoOperations::process_numeric_input_from(&mut input, ValidationMode::Lenient).unwrap(), "42");
+        assert_eq!(input.read_char().unwrap(), Some('x'));
+
+        // Exhausted input reads like end of file on stdin
+        assert_eq!(input.remaining(), 0);
+        assert_eq!(input.read_char().unwrap(), None);
+        assert_eq!(IoOperations::process_character_input_from(&mut input).unwrap(), "\n");
+        assert_eq!(IoOperations::process_numeric_input_from(&mut input, ValidationMode::Lenient).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_reader_input_reads_a_line_per_read() {
+        let mut input = ReaderInput::new(io::Cursor::new("ab\n 42 \n"));
+        // Read directly, `?` keeps the first character and drops the rest of the line
+        assert_eq!(input.read_char().unwrap(), Some('a'));
+        assert_eq!(input.read_line().unwrap(), "42");
+        assert_eq!(input.read_char().unwrap(), None);
+    }
+
+    #[test]
+    fn test_reader_holds_back_the_rest_of_a_line_for_later_reads() {
+        let mut reader = InputReader::default();
+        let mut input = ReaderInput::new(io::Cursor::new("ab\n12 x\nc"));
+        assert_eq!(reader.read_char(&mut input).unwrap(), Some('a'));
+        assert_eq!(reader.read_char(&mut input).unwrap(), Some('b'));
+        assert_eq!(reader.read_char(&mut input).unwrap(), Some('\n'));
+        assert_eq!(reader.read_char(&mut input).unwrap(), Some('1'));
+        // `??` takes what is left of the line
+        assert_eq!(reader.read_number(&mut input).unwrap(), "2");
+        assert_eq!(reader.read_char(&mut input).unwrap(), Some('c'));
+        assert_eq!(reader.read_char(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_raw_lines_default_to_gathering_characters() {
+        struct Chars(std::vec::IntoIter<char>);
+        impl InputProvider for Chars {
+            fn read_char(&mut self) -> Result<Option<char>> {
+                Ok(self.0.next())
+            }
+            fn read_line(&mut self) -> Result<String> {
+                Ok(String::new())
+            }
+        }
+
+        let mut input = Chars("ab\nc".chars().collect::<Vec<_>>().into_iter());
+        assert_eq!(input.read_raw_line().unwrap().as_deref(), Some("ab\n"));
+        assert_eq!(input.read_raw_line().unwrap().as_deref(), Some("c"));
+        assert_eq!(input.read_raw_line().unwrap(), None);
+    }
+
+    #[test]
+    fn test_number_after_a_char_reads_the_next_line() {
+        let mut reader = InputReader::new(InputMode::Chars);
+        let mut input = StringInput::new("Q\n42\nz");
+        assert_eq!(reader.read_char(&mut input).unwrap(), Some('Q'));
+        assert_eq!(reader.read_number(&mut input).unwrap(), "42");
+        assert_eq!(reader.read_char(&mut input).unwrap(), Some('z'));
+    }
+
+    #[test]
+    fn test_input_modes_split_the_same_input_differently() {
+        let read = |mode| {
+            let mut reader = InputReader::new(mode);
+            let mut input = StringInput::new("ab 12\n 7 x\n");
+            let first = reader.read_char(&mut input).unwrap();
+            (first, reader.read_number(&mut input).unwrap())
+        };
+        assert_eq!(read(InputMode::Lines), (Some('a'), "7".to_string()));
+        assert_eq!(read(InputMode::Chars), (Some('a'), "12".to_string()));
+
+        let mut reader = InputReader::new(InputMode::Numbers);
+        let mut input = StringInput::new("12\n\n  -3 x");
+        assert_eq!(reader.read_number(&mut input).unwrap(), "12");
+        assert_eq!(reader.read_number(&mut input).unwrap(), "-3");
+        assert!(matches!(
+            reader.read_number(&mut input),
+            Err(crate::types::error::InterpreterError::Execution(ExecError::InvalidNumericInput(token))) if token == "x"
+        ));
+        assert_eq!(reader.read_number(&mut input).unwrap(), "0");
+        assert_eq!(reader.read_char(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_shared_output_collects_from_every_clone() {
+        let output = SharedOutput::new();
+        let mut first = output.clone();
+        let mut second = output.clone();
+        first.write_str("4").unwrap();
+        second.write_str("2").unwrap();
+        assert_eq!(output.contents(), "42");
+    }
+
     #[test]
     fn test_character_input_operations() {
         // These tests would require mocking stdin, so we just test the detection
@@ -517,4 +1437,45 @@ mod tests {
         let result = IoOperations::validate_and_parse_numeric("xyz", ValidationMode::Permissive).unwrap();
         assert_eq!(result, "0"); // Falls back to 0
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_file_operations_write_then_read_back() {
+        let path = std::env::temp_dir().join(format!("tubular-io-{}.txt", std::process::id()));
+        let path_text = path.to_string_lossy().to_string();
+        let mut files = FileTable::new();
+        let mut stack = DataStack::new();
+        let mut droplet = create_test_droplet(1, 0);
+
+        let mut open = |stack: &mut DataStack, droplet: &mut Droplet, files: &mut FileTable, mode: i64| {
+            for ch in path_text.chars() {
+                stack.push(TubularNumber::new(ch as i64));
+            }
+            stack.push(TubularNumber::new(path_text.chars().count() as i64));
+            stack.push(TubularNumber::new(mode));
+            IoOperations::process_file_operation('O', droplet, stack, files);
+            droplet.value.clone()
+        };
+
+        let descriptor = open(&mut stack, &mut droplet, &mut files, 1);
+        assert_eq!(descriptor.to_i64(), Some(0));
+        droplet.set_value(TubularNumber::new(b'A' as i64));
+        stack.push(descriptor.clone());
+        IoOperations::process_file_operation('W', &mut droplet, &mut stack, &mut files);
+        stack.push(descriptor);
+        IoOperations::process_file_operation('X', &mut droplet, &mut stack, &mut files);
+        assert_eq!(files.open_count(), 0);
+
+        let descriptor = open(&mut stack, &mut droplet, &mut files, 0);
+        for expected in [b'A' as i64, -1] {
+            stack.push(descriptor.clone());
+            IoOperations::process_file_operation('I', &mut droplet, &mut stack, &mut files);
+            assert_eq!(droplet.value.to_i64(), Some(expected));
+        }
+        assert!(stack.is_empty());
+        std::fs::remove_file(&path).unwrap();
+
+        // Unknown modes and unopened descriptors report -1
+        assert_eq!(open(&mut stack, &mut droplet, &mut files, 7).to_i64(), Some(-1));
+        assert_eq!(files.read_byte(&TubularNumber::new(5)), -1);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::interpreter::droplet::Droplet;
+use crate::interpreter::stack::DataStack;
+use crate::types::number::TubularNumber;
+use crate::types::error::{ExecError, Result};
+
+/// Most values a droplet's own stack holds
+pub const LOCAL_STACK_DEPTH: usize = 64;
+
+/// Flag that enables the local stack extension
+pub const LOCAL_STACKS_FLAG: &str = "--local-stacks";
+
+/// Operations on the stack each droplet carries with it (the local stack extension)
+///
+/// The local stack travels with the droplet and disappears with it, so
+/// droplets running the same subroutine at once keep their own values. Popping
+/// an empty stack, local or shared, gives 0.
+pub struct LocalStackOperations;
+
+impl LocalStackOperations {
+    /// Check if a character is a local stack operation
+    pub fn is_local_stack_operation(symbol: char) -> bool {
+        matches!(symbol, '{' | '}' | '(' | ')')
+    }
+
+    /// Run a local stack operation
+    ///
+    /// - `{` pushes the droplet value onto its local stack
+    /// - `}` pops the local stack into the droplet value
+    /// - `(` moves the top of the shared stack onto the local stack
+    /// - `)` moves the top of the local stack onto the shared stack
+    pub fn process_local_stack_operation(symbol: char, droplet: &mut Droplet, stack: &mut DataStack) -> Result<()> {
+        match symbol {
+            '{' => Self::push(droplet, droplet.value.clone()),
+            '}' => {
+                let value = droplet.locals.pop().unwrap_or_else(TubularNumber::zero);
+                droplet.set_value(value);
+                Ok(())
+            }
+            '(' => Self::push(droplet, stack.pop_or_zero()),
+            ')' => {
+                stack.push(droplet.locals.pop().unwrap_or_else(TubularNumber::zero));
+                Ok(())
+            }
+            _ => Err(ExecError::InvalidOperation(symbol).into()),
+        }
+    }
+
+    fn push(droplet: &mut Droplet, value: TubularNumber) -> Result<()> {
+        if droplet.locals.len() >= LOCAL_STACK_DEPTH {
+            return Err(ExecError::LocalStackOverflow(droplet.id, LOCAL_STACK_DEPTH).into());
+        }
+        droplet.locals.push(value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::coordinate::Coordinate;
+    use crate::types::direction::Direction;
+    use crate::types::error::InterpreterError;
+
+    #[test]
+    fn test_values_move_between_droplet_local_and_shared_stacks() {
+        let mut droplet = Droplet::with_value(1, TubularNumber::new(7), Coordinate::new(0, 0), Direction::Down);
+        let mut stack = DataStack::new();
+        stack.push(TubularNumber::new(3));
+
+        LocalStackOperations::process_local_stack_operation('{', &mut droplet, &mut stack).unwrap();
+        LocalStackOperations::process_local_stack_operation('(', &mut droplet, &mut stack).unwrap();
+        assert_eq!(droplet.locals, vec![TubularNumber::new(7), TubularNumber::new(3)]);
+        assert!(stack.is_empty());
+
+        LocalStackOperations::process_local_stack_operation('}', &mut droplet, &mut stack).unwrap();
+        LocalStackOperations::process_local_stack_operation(')', &mut droplet, &mut stack).unwrap();
+        assert_eq!(droplet.value, TubularNumber::new(3));
+        assert_eq!(stack.as_slice(), &[TubularNumber::new(7)]);
+
+        // Empty stacks give 0
+        LocalStackOperations::process_local_stack_operation('}', &mut droplet, &mut stack).unwrap();
+        assert_eq!(droplet.value, TubularNumber::zero());
+    }
+
+    #[test]
+    fn test_local_stack_depth_is_bounded() {
+        let mut droplet = Droplet::new(4, Coordinate::new(0, 0), Direction::Down);
+        let mut stack = DataStack::new();
+        for _ in 0..LOCAL_STACK_DEPTH {
+            LocalStackOperations::process_local_stack_operation('{', &mut droplet, &mut stack).unwrap();
+        }
+        let error = LocalStackOperations::process_local_stack_operation('{', &mut droplet, &mut stack).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::LocalStackOverflow(4, LOCAL_STACK_DEPTH))));
+    }
+}
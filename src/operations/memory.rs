@@ -2,13 +2,88 @@ use crate::interpreter::droplet::Droplet;
 use crate::interpreter::stack::DataStack;
 use crate::interpreter::memory::{Reservoir, ReservoirCoordinate};
 use crate::types::coordinate::Coordinate;
-use crate::types::bigint::TubularBigInt;
-use crate::types::error::{Result, SystemError};
+use crate::types::number::TubularNumber;
+use crate::types::error::{ExecError, Result, SystemError};
+
+/// Most cells one region operation may touch
+pub const MAX_REGION_CELLS: u64 = 1 << 20;
 
 /// Memory operations for reservoir access
 pub struct MemoryOperations;
 
 impl MemoryOperations {
+    /// Run a reservoir operation
+    pub fn process_memory_operation(
+        symbol: char,
+        droplet: &mut Droplet,
+        stack: &mut DataStack,
+        reservoir: &mut Reservoir,
+        max_cells: Option<usize>,
+    ) -> Result<()> {
+        match symbol {
+            'G' => Self::process_get_operation(droplet, stack, reservoir),
+            'P' => Self::process_put_operation(droplet, stack, reservoir),
+            _ => Self::process_region_operation(symbol, droplet, stack, reservoir, max_cells),
+        }
+    }
+
+    /// Process a region operation on the rectangle described by the stack
+    ///
+    /// Each pops a height, a width, y and x, in that order, for the rectangle
+    /// whose top-left corner is (x, y); an empty or negative size touches nothing.
+    /// - `F` sets every cell of the rectangle to the droplet value
+    /// - `Z` empties every cell of the rectangle
+    /// - `B` first pops a destination y and x, then copies the rectangle there
+    ///
+    /// The droplet value is left as it is. A rectangle of more than
+    /// [`MAX_REGION_CELLS`] cells is an error, as is leaving more than
+    /// `max_cells` cells in the reservoir; either is reported before anything
+    /// is written.
+    pub fn process_region_operation(
+        symbol: char,
+        droplet: &Droplet,
+        stack: &mut DataStack,
+        reservoir: &mut Reservoir,
+        max_cells: Option<usize>,
+    ) -> Result<()> {
+        let destination = (symbol == 'B').then(|| {
+            let y = Self::pop_coordinate(stack);
+            ReservoirCoordinate::new(Self::pop_coordinate(stack), y)
+        });
+        let height = stack.pop().to_i64().unwrap_or(0).max(0) as u64;
+        let width = stack.pop().to_i64().unwrap_or(0).max(0) as u64;
+        let y = Self::pop_coordinate(stack);
+        let origin = ReservoirCoordinate::new(Self::pop_coordinate(stack), y);
+        if width.saturating_mul(height) > MAX_REGION_CELLS {
+            return Err(ExecError::RegionTooLarge(MAX_REGION_CELLS).into());
+        }
+        let (width, height) = (width as usize, height as usize);
+
+        if let Some(max) = max_cells {
+            let cells_after = match (symbol, &destination) {
+                ('F', _) => reservoir.len() - reservoir.count_in_region(&origin, width, height) + width * height,
+                ('B', Some(to)) => reservoir.len() - reservoir.count_in_region(to, width, height)
+                    + reservoir.count_in_region(&origin, width, height),
+                _ => 0,
+            };
+            if cells_after > max {
+                return Err(ExecError::ReservoirLimitExceeded(max).into());
+            }
+        }
+
+        match (symbol, destination) {
+            ('F', _) => reservoir.fill_region(origin, width, height, droplet.value.clone()),
+            ('Z', _) => reservoir.clear_region(origin, width, height),
+            ('B', Some(to)) => reservoir.copy_region(origin, width, height, to),
+            _ => return Err(ExecError::InvalidOperation(symbol).into()),
+        }
+        Ok(())
+    }
+
+    fn pop_coordinate(stack: &mut DataStack) -> isize {
+        stack.pop().to_i64().unwrap_or(0) as isize
+    }
+
     /// Process reservoir Get operation (G)
     /// Reads value from coordinate (droplet.value, stack.pop()) and sets droplet value
     ///
@@ -82,7 +157,7 @@ impl MemoryOperations {
     /// Calculate reservoir coordinate from droplet value and stack
     /// Helper method for coordinate calculation used by both Get and Put
     pub fn calculate_coordinate(
-        droplet_value: &TubularBigInt,
+        droplet_value: &TubularNumber,
         stack: &mut DataStack,
     ) -> Result<ReservoirCoordinate> {
         let y_value = stack.pop();
@@ -95,7 +170,12 @@ impl MemoryOperations {
 
     /// Check if a symbol is a memory operation
     pub fn is_memory_operation(symbol: char) -> bool {
-        matches!(symbol, 'G' | 'P')
+        matches!(symbol, 'G' | 'P') || Self::is_region_operation(symbol)
+    }
+
+    /// Check if a symbol acts on a rectangle of the reservoir
+    pub fn is_region_operation(symbol: char) -> bool {
+        matches!(symbol, 'F' | 'Z' | 'B')
     }
 
     /// Get the type of memory operation
@@ -103,6 +183,9 @@ impl MemoryOperations {
         match symbol {
             'G' => Some(MemoryOperationType::Get),
             'P' => Some(MemoryOperationType::Put),
+            'F' => Some(MemoryOperationType::Fill),
+            'Z' => Some(MemoryOperationType::Clear),
+            'B' => Some(MemoryOperationType::Copy),
             _ => None,
         }
     }
@@ -115,6 +198,12 @@ pub enum MemoryOperationType {
     Get,
     /// Put operation - write to reservoir
     Put,
+    /// Fill operation - write one value to a rectangle
+    Fill,
+    /// Clear operation - empty a rectangle
+    Clear,
+    /// Copy operation - copy a rectangle elsewhere
+    Copy,
 }
 
 #[cfg(test)]
@@ -126,7 +215,7 @@ mod tests {
 
     fn create_test_droplet(value: i64, x: isize, y: isize) -> Droplet {
         let mut droplet = Droplet::new(0, Coordinate::new(x, y), Direction::Down);
-        droplet.set_value(TubularBigInt::new(value));
+        droplet.set_value(TubularNumber::new(value));
         droplet
     }
 
@@ -137,16 +226,16 @@ mod tests {
         let mut reservoir = Reservoir::new();
 
         // Setup: put value 42 at coordinate (5, 10)
-        reservoir.put(ReservoirCoordinate::new(5, 10), TubularBigInt::new(42));
+        reservoir.put(ReservoirCoordinate::new(5, 10), TubularNumber::new(42));
 
         // Setup stack with y-coordinate
-        stack.push(TubularBigInt::new(10));
+        stack.push(TubularNumber::new(10));
 
         // Execute get operation
         MemoryOperations::process_get_operation(&mut droplet, &mut stack, &reservoir).unwrap();
 
         // Verify droplet now has the value from reservoir
-        assert_eq!(droplet.value, TubularBigInt::new(42));
+        assert_eq!(droplet.value, TubularNumber::new(42));
 
         // Verify stack lost the y-coordinate
         assert_eq!(stack.depth(), 0);
@@ -159,13 +248,13 @@ mod tests {
         let reservoir = Reservoir::new(); // Empty reservoir
 
         // Setup stack with y-coordinate
-        stack.push(TubularBigInt::new(10));
+        stack.push(TubularNumber::new(10));
 
         // Execute get operation
         MemoryOperations::process_get_operation(&mut droplet, &mut stack, &reservoir).unwrap();
 
         // Verify droplet gets zero value
-        assert_eq!(droplet.value, TubularBigInt::zero());
+        assert_eq!(droplet.value, TubularNumber::zero());
 
         // Verify stack lost the y-coordinate
         assert_eq!(stack.depth(), 0);
@@ -178,20 +267,20 @@ mod tests {
         let mut reservoir = Reservoir::new();
 
         // Setup stack with y-coordinate
-        stack.push(TubularBigInt::new(10));
+        stack.push(TubularNumber::new(10));
 
         // Execute put operation
         MemoryOperations::process_put_operation(&droplet, &mut stack, &mut reservoir).unwrap();
 
         // Verify reservoir now contains the droplet's value
         let retrieved = reservoir.get(ReservoirCoordinate::new(5, 10));
-        assert_eq!(retrieved, TubularBigInt::new(5));
+        assert_eq!(retrieved, TubularNumber::new(5));
 
         // Verify stack lost the y-coordinate
         assert_eq!(stack.depth(), 0);
 
         // Verify droplet value unchanged
-        assert_eq!(droplet.value, TubularBigInt::new(5));
+        assert_eq!(droplet.value, TubularNumber::new(5));
     }
 
     #[test]
@@ -201,17 +290,17 @@ mod tests {
         let mut reservoir = Reservoir::new();
 
         // Pre-populate reservoir with existing value
-        reservoir.put(ReservoirCoordinate::new(5, 10), TubularBigInt::new(99));
+        reservoir.put(ReservoirCoordinate::new(5, 10), TubularNumber::new(99));
 
         // Setup stack with y-coordinate
-        stack.push(TubularBigInt::new(10));
+        stack.push(TubularNumber::new(10));
 
         // Execute put operation
         MemoryOperations::process_put_operation(&droplet, &mut stack, &mut reservoir).unwrap();
 
         // Verify existing value was overwritten
         let retrieved = reservoir.get(ReservoirCoordinate::new(5, 10));
-        assert_eq!(retrieved, TubularBigInt::new(5)); // New value, not 99
+        assert_eq!(retrieved, TubularNumber::new(5)); // New value, not 99
     }
 
     #[test]
@@ -221,23 +310,23 @@ mod tests {
         let mut reservoir = Reservoir::new();
 
         // Setup: put value 42 at negative coordinate (-5, -10)
-        reservoir.put(ReservoirCoordinate::new(-5, -10), TubularBigInt::new(42));
+        reservoir.put(ReservoirCoordinate::new(-5, -10), TubularNumber::new(42));
 
         // Setup stack with negative y-coordinate
-        stack.push(TubularBigInt::new(-10));
+        stack.push(TubularNumber::new(-10));
 
         // Execute get operation
         MemoryOperations::process_get_operation(&mut droplet, &mut stack, &reservoir).unwrap();
 
         // Verify negative coordinates work
-        assert_eq!(droplet.value, TubularBigInt::new(42));
+        assert_eq!(droplet.value, TubularNumber::new(42));
     }
 
     #[test]
     fn test_coordinate_calculation() {
-        let droplet_value = TubularBigInt::new(5);
+        let droplet_value = TubularNumber::new(5);
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(10));
+        stack.push(TubularNumber::new(10));
 
         let coord = MemoryOperations::calculate_coordinate(&droplet_value, &mut stack).unwrap();
 
@@ -278,14 +367,14 @@ mod tests {
         // Test very large coordinates
         reservoir.put(
             ReservoirCoordinate::new(1000000, 2000000),
-            TubularBigInt::new(999)
+            TubularNumber::new(999)
         );
 
-        stack.push(TubularBigInt::new(2000000));
+        stack.push(TubularNumber::new(2000000));
 
         MemoryOperations::process_get_operation(&mut droplet, &mut stack, &reservoir).unwrap();
 
-        assert_eq!(droplet.value, TubularBigInt::new(999));
+        assert_eq!(droplet.value, TubularNumber::new(999));
     }
 
     #[test]
@@ -299,10 +388,65 @@ mod tests {
 
         // Verify the value was stored at (5, 0)
         let retrieved = reservoir.get(ReservoirCoordinate::new(5, 0));
-        assert_eq!(retrieved, TubularBigInt::new(5));
+        assert_eq!(retrieved, TubularNumber::new(5));
 
         // Get operation with empty stack should use y=0
         MemoryOperations::process_get_operation(&mut droplet, &mut stack, &reservoir).unwrap();
-        assert_eq!(droplet.value, TubularBigInt::new(5));
+        assert_eq!(droplet.value, TubularNumber::new(5));
+    }
+
+    #[test]
+    fn test_region_operations_take_rectangles_from_the_stack() {
+        let droplet = create_test_droplet(4, 0, 0);
+        let mut stack = DataStack::new();
+        let mut reservoir = Reservoir::new();
+        let mut run = |symbol, values: &[i64], reservoir: &mut Reservoir| {
+            for &value in values {
+                stack.push(TubularNumber::new(value));
+            }
+            let result = MemoryOperations::process_region_operation(symbol, &droplet, &mut stack, reservoir, None);
+            assert!(stack.is_empty());
+            result
+        };
+
+        // x, y, width, height
+        run('F', &[-1, 2, 3, 2], &mut reservoir).unwrap();
+        assert_eq!(reservoir.len(), 6);
+        assert_eq!(reservoir.get(ReservoirCoordinate::new(1, 3)), TubularNumber::new(4));
+        // ...then the destination x and y
+        run('B', &[-1, 2, 2, 1, 10, 10], &mut reservoir).unwrap();
+        assert_eq!(reservoir.get(ReservoirCoordinate::new(11, 10)), TubularNumber::new(4));
+        run('Z', &[-1, 2, 3, 2], &mut reservoir).unwrap();
+        assert_eq!(reservoir.len(), 2);
+        run('F', &[0, 0, -5, 2], &mut reservoir).unwrap();
+        assert_eq!(reservoir.len(), 2);
+
+        let error = run('F', &[0, 0, 1 << 11, 1 << 11], &mut reservoir).unwrap_err();
+        assert!(matches!(error, crate::types::error::InterpreterError::Execution(ExecError::RegionTooLarge(MAX_REGION_CELLS))));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_region_operations_stay_within_the_cell_limit() {
+        let droplet = create_test_droplet(4, 0, 0);
+        let mut stack = DataStack::new();
+        let mut reservoir = Reservoir::new();
+        let mut run = |symbol, values: &[i64], reservoir: &mut Reservoir| {
+            for &value in values {
+                stack.push(TubularNumber::new(value));
+            }
+            MemoryOperations::process_region_operation(symbol, &droplet, &mut stack, reservoir, Some(6))
+        };
+
+        run('F', &[0, 0, 3, 2], &mut reservoir).unwrap();
+        // Refilling cells that are already stored adds none
+        run('F', &[1, 0, 2, 2], &mut reservoir).unwrap();
+        let error = run('F', &[0, 2, 1, 1], &mut reservoir).unwrap_err();
+        assert!(matches!(error, crate::types::error::InterpreterError::Execution(ExecError::ReservoirLimitExceeded(6))));
+        let error = run('B', &[0, 0, 1, 2, 5, 5], &mut reservoir).unwrap_err();
+        assert!(matches!(error, crate::types::error::InterpreterError::Execution(ExecError::ReservoirLimitExceeded(6))));
+        assert_eq!(reservoir.len(), 6);
+        // Copying onto stored cells replaces them
+        run('B', &[0, 0, 1, 2, 2, 0], &mut reservoir).unwrap();
+        assert_eq!(reservoir.len(), 6);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::interpreter::droplet::Droplet;
+use crate::interpreter::stack::DataStack;
+use crate::operations::subroutines::SubroutineOperations;
+use crate::types::error::{ExecError, Result};
+use crate::types::number::TubularNumber;
+
+/// Operations that push facts about the droplet running them
+///
+/// The droplet value is left alone, so a program can branch on which flow it
+/// is in without losing what it carries.
+pub struct MetadataOperations;
+
+impl MetadataOperations {
+    /// Check if a character is a droplet metadata operation
+    pub fn is_metadata_operation(symbol: char) -> bool {
+        matches!(symbol, 'w' | 'e' | 'h')
+    }
+
+    /// Run a metadata operation on `tick`
+    ///
+    /// - `w` pushes the droplet's ID
+    /// - `e` pushes its age, the ticks since it was created
+    /// - `h` pushes its heading: 0 up, 1 right, 2 down, 3 left, as `C` reads directions
+    pub fn process_metadata_operation(symbol: char, droplet: &Droplet, stack: &mut DataStack, tick: u64) -> Result<()> {
+        let value = match symbol {
+            'w' => TubularNumber::from_bigint(droplet.id.into()),
+            'e' => TubularNumber::from_bigint(droplet.age(tick).into()),
+            'h' => SubroutineOperations::direction_to_value(droplet.direction),
+            _ => return Err(ExecError::InvalidOperation(symbol).into()),
+        };
+        stack.push(value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::coordinate::Coordinate;
+    use crate::types::direction::Direction;
+
+    #[test]
+    fn test_id_age_and_heading_are_pushed() {
+        let droplet = Droplet::with_value(7, TubularNumber::new(42), Coordinate::new(0, 0), Direction::Left)
+            .with_creation_tick(10);
+        let mut stack = DataStack::new();
+        for symbol in ['w', 'e', 'h'] {
+            MetadataOperations::process_metadata_operation(symbol, &droplet, &mut stack, 25).unwrap();
+        }
+        assert_eq!(stack.as_slice(), &[TubularNumber::new(7), TubularNumber::new(15), TubularNumber::new(3)]);
+        assert_eq!(droplet.value, TubularNumber::new(42));
+    }
+}
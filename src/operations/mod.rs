@@ -2,10 +2,18 @@ pub mod flow_control;
 pub mod arithmetic;
 pub mod memory;
 pub mod io;
+pub mod net;
+pub mod local_stack;
+pub mod reflection;
+pub mod metadata;
 pub mod subroutines;
 
 pub use flow_control::*;
 pub use arithmetic::*;
 pub use memory::*;
 pub use io::*;
+pub use net::*;
+pub use local_stack::*;
+pub use reflection::*;
+pub use metadata::*;
 pub use subroutines::*;
\ No newline at end of file
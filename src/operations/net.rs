@@ -0,0 +1,270 @@
+use crate::interpreter::droplet::Droplet;
+use crate::interpreter::stack::DataStack;
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "std")]
+use std::net::{TcpStream, ToSocketAddrs};
+
+/// Longest a connection attempt may take before `T` gives up, unless
+/// [`RuntimeOptions::connect_timeout`](crate::interpreter::execution::RuntimeOptions::connect_timeout) says otherwise
+///
+/// The whole tick waits for the connection, so a run can overshoot its
+/// wall-clock limit by up to this long.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest a send or receive blocks within one tick
+///
+/// A droplet whose socket is not ready after this long stays on its cell and
+/// retries next tick, so a slow peer delays that droplet but never stalls
+/// the interpreter for more than this per tick.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// TCP connections opened by a program, indexed by descriptor
+///
+/// Descriptors are handed out like [`FileTable`](crate::operations::io::FileTable)
+/// ones but are separate from them. Connections are closed when the table is
+/// dropped; like open files, they are not part of snapshots, and a clone
+/// starts with no connections.
+#[derive(Debug, Default)]
+pub struct SocketTable {
+    #[cfg(feature = "std")]
+    sockets: Vec<Option<TcpStream>>,
+}
+
+impl SocketTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "std")]
+impl SocketTable {
+    /// Connect to `address` (`host:port`) and return its descriptor, or -1
+    /// if no address it resolves to accepts the connection within `timeout`
+    pub fn connect(&mut self, address: &str, timeout: Duration) -> i64 {
+        let Ok(addresses) = address.to_socket_addrs() else {
+            return -1;
+        };
+        let Some(stream) = addresses
+            .filter_map(|address| TcpStream::connect_timeout(&address, timeout).ok())
+            .next()
+        else {
+            return -1;
+        };
+        if stream.set_read_timeout(Some(POLL_INTERVAL)).is_err()
+            || stream.set_write_timeout(Some(POLL_INTERVAL)).is_err()
+        {
+            return -1;
+        }
+        let _ = stream.set_nodelay(true);
+
+        match self.sockets.iter().position(Option::is_none) {
+            Some(descriptor) => {
+                self.sockets[descriptor] = Some(stream);
+                descriptor as i64
+            }
+            None => {
+                self.sockets.push(Some(stream));
+                (self.sockets.len() - 1) as i64
+            }
+        }
+    }
+
+    fn get(&mut self, descriptor: &TubularNumber) -> Option<&mut TcpStream> {
+        let index = descriptor.to_usize()?;
+        self.sockets.get_mut(index)?.as_mut()
+    }
+
+    /// Next byte from a connection; -1 once the peer has closed it or on error
+    ///
+    /// None if no byte arrived within [`POLL_INTERVAL`].
+    pub fn receive_byte(&mut self, descriptor: &TubularNumber) -> Option<i64> {
+        let Some(stream) = self.get(descriptor) else {
+            return Some(-1);
+        };
+        let mut byte = [0u8];
+        match stream.read(&mut byte) {
+            Ok(1) => Some(byte[0] as i64),
+            Err(error) if is_pending(&error) => None,
+            _ => Some(-1),
+        }
+    }
+
+    /// Send the low byte of `value`; false if the connection is not usable
+    ///
+    /// None if the byte could not be sent within [`POLL_INTERVAL`].
+    pub fn send_byte(&mut self, descriptor: &TubularNumber, value: &TubularNumber) -> Option<bool> {
+        let Some(stream) = self.get(descriptor) else {
+            return Some(false);
+        };
+        let byte = value.to_integer().as_bigint().to_signed_bytes_le().first().copied().unwrap_or(0);
+        match stream.write(&[byte]) {
+            Ok(1) => Some(true),
+            Ok(_) => None,
+            Err(error) if is_pending(&error) => None,
+            Err(_) => Some(false),
+        }
+    }
+
+    /// Number of connections currently open
+    pub fn open_count(&self) -> usize {
+        self.sockets.iter().filter(|socket| socket.is_some()).count()
+    }
+}
+
+/// Builds without std have no network, so every connection attempt fails
+#[cfg(not(feature = "std"))]
+impl SocketTable {
+    pub fn connect(&mut self, _address: &str, _timeout: Duration) -> i64 {
+        -1
+    }
+
+    pub fn receive_byte(&mut self, _descriptor: &TubularNumber) -> Option<i64> {
+        Some(-1)
+    }
+
+    pub fn send_byte(&mut self, _descriptor: &TubularNumber, _value: &TubularNumber) -> Option<bool> {
+        Some(false)
+    }
+
+    pub fn open_count(&self) -> usize {
+        0
+    }
+}
+
+impl Clone for SocketTable {
+    /// Connections cannot be shared, so a clone has none open
+    fn clone(&self) -> Self {
+        SocketTable::new()
+    }
+}
+
+/// Whether a socket error only means "not ready yet"
+#[cfg(feature = "std")]
+fn is_pending(error: &io::Error) -> bool {
+    matches!(error.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted)
+}
+
+/// Network operations for Tubular programs
+pub struct NetOperations;
+
+impl NetOperations {
+    /// Process a network operation (T, E, V) against `sockets`
+    ///
+    /// `T` pops a length n and n character codes spelling `host:port` and
+    /// leaves the descriptor, or -1, in the droplet. `E` sends the droplet
+    /// value as a byte and `V` leaves the next byte, or -1 once the peer has
+    /// closed, in the droplet; both pop a descriptor. `T` waits up to
+    /// `connect_timeout` for each address. Returns false while the
+    /// socket is not ready, in which case the descriptor stays on the stack
+    /// and the droplet should try again next tick.
+    pub fn process_network_operation(
+        symbol: char,
+        droplet: &mut Droplet,
+        stack: &mut DataStack,
+        sockets: &mut SocketTable,
+        connect_timeout: Duration,
+    ) -> bool {
+        match symbol {
+            'T' => {
+                let length = stack.pop().to_i64().unwrap_or(0).max(0);
+                let mut address: Vec<char> = (0..length).map(|_| stack.pop().to_char().unwrap_or('\u{FFFD}')).collect();
+                address.reverse();
+                let descriptor = sockets.connect(&address.into_iter().collect::<String>(), connect_timeout);
+                droplet.set_value(TubularNumber::new(descriptor));
+                true
+            }
+            'E' => match sockets.send_byte(&stack.peek(), &droplet.value) {
+                Some(_) => {
+                    stack.pop();
+                    true
+                }
+                None => false,
+            },
+            'V' => match sockets.receive_byte(&stack.peek()) {
+                Some(byte) => {
+                    stack.pop();
+                    droplet.set_value(TubularNumber::new(byte));
+                    true
+                }
+                None => false,
+            },
+            _ => true,
+        }
+    }
+
+    /// Check if a character is a network operation (needs --allow-net)
+    pub fn is_network_operation(symbol: char) -> bool {
+        matches!(symbol, 'T' | 'E' | 'V')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::coordinate::Coordinate;
+    use crate::types::direction::Direction;
+    use std::net::TcpListener;
+
+    fn push_text(stack: &mut DataStack, text: &str) {
+        for ch in text.chars() {
+            stack.push(TubularNumber::new(ch as i64));
+        }
+        stack.push(TubularNumber::new(text.chars().count() as i64));
+    }
+
+    #[test]
+    fn test_connect_send_and_receive() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut peer, _) = listener.accept().unwrap();
+            let mut byte = [0u8];
+            peer.read_exact(&mut byte).unwrap();
+            peer.write_all(&[byte[0] + 1]).unwrap();
+        });
+
+        let mut sockets = SocketTable::new();
+        let mut stack = DataStack::new();
+        let mut droplet = Droplet::new(1, Coordinate::new(0, 0), Direction::Down);
+        push_text(&mut stack, &address);
+        assert!(NetOperations::process_network_operation('T', &mut droplet, &mut stack, &mut sockets, CONNECT_TIMEOUT));
+        let descriptor = droplet.value.clone();
+        assert_eq!(descriptor.to_i64(), Some(0));
+
+        droplet.set_value(TubularNumber::new(b'A' as i64));
+        stack.push(descriptor.clone());
+        assert!(NetOperations::process_network_operation('E', &mut droplet, &mut stack, &mut sockets, CONNECT_TIMEOUT));
+        server.join().unwrap();
+
+        // Retry until the reply arrives, as the interpreter would tick by tick
+        stack.push(descriptor.clone());
+        while !NetOperations::process_network_operation('V', &mut droplet, &mut stack, &mut sockets, CONNECT_TIMEOUT) {}
+        assert_eq!(droplet.value.to_i64(), Some(b'B' as i64));
+        stack.push(descriptor);
+        while !NetOperations::process_network_operation('V', &mut droplet, &mut stack, &mut sockets, CONNECT_TIMEOUT) {}
+        assert_eq!(droplet.value.to_i64(), Some(-1));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_connect_gives_up_after_timeout() {
+        let mut sockets = SocketTable::new();
+        let started = std::time::Instant::now();
+        // TEST-NET-1 is never routed, so the attempt can only time out or fail
+        assert_eq!(sockets.connect("192.0.2.1:9", Duration::from_millis(100)), -1);
+        assert!(started.elapsed() < CONNECT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_unusable_addresses_and_descriptors() {
+        let mut sockets = SocketTable::new();
+        assert_eq!(sockets.connect("not an address", CONNECT_TIMEOUT), -1);
+        assert_eq!(sockets.receive_byte(&TubularNumber::new(3)), Some(-1));
+        assert_eq!(sockets.send_byte(&TubularNumber::new(3), &TubularNumber::new(1)), Some(false));
+        assert_eq!(sockets.open_count(), 0);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::interpreter::droplet::Droplet;
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::stack::DataStack;
+use crate::types::coordinate::Coordinate;
+use crate::types::error::{ExecError, Result};
+use crate::types::number::TubularNumber;
+use crate::types::prelude::*;
+
+/// Operations that read and rewrite the program's own cells
+///
+/// Both pop y and then x. Blank cells read as a space, and writing a space
+/// empties a cell. Writing needs `--allow-self-modify`.
+pub struct ReflectionOperations;
+
+impl ReflectionOperations {
+    /// Check if a character reads or writes the program grid
+    pub fn is_reflection_operation(symbol: char) -> bool {
+        matches!(symbol, 'g' | 'p')
+    }
+
+    /// Grid get (g) - the droplet value becomes the character code of the cell at (x, y)
+    pub fn process_grid_get(droplet: &mut Droplet, stack: &mut DataStack, grid: &ProgramGrid) {
+        let coord = Self::pop_coordinate(stack);
+        droplet.set_value(TubularNumber::from_char(grid.get_symbol(coord).unwrap_or(' ')));
+    }
+
+    /// Grid put (p) - write the character whose code is the droplet value to the cell at (x, y)
+    ///
+    /// Returns the cell written and the symbol it held before; only printable
+    /// ASCII codes (32 to 126) can be written.
+    pub fn process_grid_put(droplet: &Droplet, stack: &mut DataStack, grid: &mut ProgramGrid) -> Result<(Coordinate, Option<char>)> {
+        let coord = Self::pop_coordinate(stack);
+        let symbol = droplet.value.to_char()
+            .filter(|symbol| (' '..='~').contains(symbol))
+            .ok_or_else(|| ExecError::InvalidCellWrite(droplet.value.to_string(), coord))?;
+        let previous = grid.set_cell(coord, symbol)?;
+        Ok((coord, previous))
+    }
+
+    fn pop_coordinate(stack: &mut DataStack) -> Coordinate {
+        let y = stack.pop().to_i64().unwrap_or(0) as isize;
+        let x = stack.pop().to_i64().unwrap_or(0) as isize;
+        Coordinate::new(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+    use crate::types::direction::Direction;
+    use crate::types::error::InterpreterError;
+
+    fn stack_of(values: &[i64]) -> DataStack {
+        let mut stack = DataStack::new();
+        for &value in values {
+            stack.push(TubularNumber::new(value));
+        }
+        stack
+    }
+
+    #[test]
+    fn test_cells_are_read_and_rewritten_as_character_codes() {
+        let mut grid = GridParser::new().parse_string("@\n7\nn").unwrap();
+        let mut droplet = Droplet::new(0, Coordinate::new(0, 0), Direction::Down);
+
+        ReflectionOperations::process_grid_get(&mut droplet, &mut stack_of(&[0, 1]), &grid);
+        assert_eq!(droplet.value, TubularNumber::from_char('7'));
+        ReflectionOperations::process_grid_get(&mut droplet, &mut stack_of(&[5, 5]), &grid);
+        assert_eq!(droplet.value, TubularNumber::from_char(' '));
+
+        droplet.set_value(TubularNumber::from_char('+'));
+        let written = ReflectionOperations::process_grid_put(&droplet, &mut stack_of(&[0, 1]), &mut grid).unwrap();
+        assert_eq!(written, (Coordinate::new(0, 1), Some('7')));
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 1)), Some('+'));
+
+        droplet.set_value(TubularNumber::from_char(' '));
+        ReflectionOperations::process_grid_put(&droplet, &mut stack_of(&[0, 1]), &mut grid).unwrap();
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 1)), None);
+
+        droplet.set_value(TubularNumber::new(10));
+        let error = ReflectionOperations::process_grid_put(&droplet, &mut stack_of(&[0, 1]), &mut grid).unwrap_err();
+        assert!(matches!(error, InterpreterError::Execution(ExecError::InvalidCellWrite(ref value, _)) if value == "10"));
+    }
+}
@@ -4,8 +4,9 @@ use crate::interpreter::subroutines::{CallStack, StackFrame};
 use crate::interpreter::grid::ProgramGrid;
 use crate::types::coordinate::Coordinate;
 use crate::types::direction::Direction;
-use crate::types::bigint::TubularBigInt;
+use crate::types::number::TubularNumber;
 use crate::types::error::{Result, SystemError};
+use crate::types::prelude::*;
 
 /// Subroutine operations for call/return functionality
 pub struct SubroutineOperations;
@@ -88,7 +89,7 @@ impl SubroutineOperations {
     /// Convert a numeric value to Direction
     /// 0 = Up, 1 = Right, 2 = Down, 3 = Left (clockwise from up)
     /// Invalid values default to Down
-    fn value_to_direction(value: &TubularBigInt) -> Direction {
+    fn value_to_direction(value: &TubularNumber) -> Direction {
         let val = value.to_i64().unwrap_or(2) as isize % 4; // Default to Down (2)
         match val {
             0 => Direction::Up,
@@ -101,12 +102,12 @@ impl SubroutineOperations {
 
     /// Convert Direction to numeric value (inverse of value_to_direction)
     /// Up = 0, Right = 1, Down = 2, Left = 3
-    pub fn direction_to_value(direction: Direction) -> TubularBigInt {
+    pub fn direction_to_value(direction: Direction) -> TubularNumber {
         match direction {
-            Direction::Up => TubularBigInt::new(0),
-            Direction::Right => TubularBigInt::new(1),
-            Direction::Down => TubularBigInt::new(2),
-            Direction::Left => TubularBigInt::new(3),
+            Direction::Up => TubularNumber::new(0),
+            Direction::Right => TubularNumber::new(1),
+            Direction::Down => TubularNumber::new(2),
+            Direction::Left => TubularNumber::new(3),
         }
     }
 
@@ -155,7 +156,7 @@ mod tests {
 
     fn create_test_droplet(value: i64, x: isize, y: isize, direction: Direction) -> Droplet {
         let mut droplet = Droplet::new(0, Coordinate::new(x, y), direction);
-        droplet.set_value(TubularBigInt::new(value));
+        droplet.set_value(TubularNumber::new(value));
         droplet
     }
 
@@ -178,8 +179,8 @@ mod tests {
         let grid = create_test_grid();
 
         // Setup stack with direction and y-coordinate
-        stack.push(TubularBigInt::new(1)); // Right direction
-        stack.push(TubularBigInt::new(10)); // y-coordinate
+        stack.push(TubularNumber::new(1)); // Right direction
+        stack.push(TubularNumber::new(10)); // y-coordinate
 
         // Execute call operation
         SubroutineOperations::process_call_operation(&mut droplet, &mut stack, &mut call_stack, &grid).unwrap();
@@ -206,8 +207,8 @@ mod tests {
         let grid = create_test_grid();
 
         // Setup stack with direction and y-coordinate
-        stack.push(TubularBigInt::new(1)); // Right direction
-        stack.push(TubularBigInt::new(10)); // y-coordinate
+        stack.push(TubularNumber::new(1)); // Right direction
+        stack.push(TubularNumber::new(10)); // y-coordinate
 
         let original_position = droplet.position;
         let original_direction = droplet.direction;
@@ -268,15 +269,15 @@ mod tests {
         let grid = create_test_grid();
 
         // First call: (0,0,Down) -> (5,10,Right)
-        stack.push(TubularBigInt::new(1)); // Right
-        stack.push(TubularBigInt::new(10)); // y=10
+        stack.push(TubularNumber::new(1)); // Right
+        stack.push(TubularNumber::new(10)); // y=10
 
         SubroutineOperations::process_call_operation(&mut droplet, &mut stack, &mut call_stack, &grid).unwrap();
 
         // Second call: (5,10,Right) -> (-3,7,Up)
-        droplet.set_value(TubularBigInt::new(-3));
-        stack.push(TubularBigInt::new(0)); // Up
-        stack.push(TubularBigInt::new(7)); // y=7
+        droplet.set_value(TubularNumber::new(-3));
+        stack.push(TubularNumber::new(0)); // Up
+        stack.push(TubularNumber::new(7)); // y=7
 
         SubroutineOperations::process_call_operation(&mut droplet, &mut stack, &mut call_stack, &grid).unwrap();
 
@@ -298,25 +299,25 @@ mod tests {
 
     #[test]
     fn test_value_to_direction() {
-        assert_eq!(SubroutineOperations::value_to_direction(&TubularBigInt::new(0)), Direction::Up);
-        assert_eq!(SubroutineOperations::value_to_direction(&TubularBigInt::new(1)), Direction::Right);
-        assert_eq!(SubroutineOperations::value_to_direction(&TubularBigInt::new(2)), Direction::Down);
-        assert_eq!(SubroutineOperations::value_to_direction(&TubularBigInt::new(3)), Direction::Left);
+        assert_eq!(SubroutineOperations::value_to_direction(&TubularNumber::new(0)), Direction::Up);
+        assert_eq!(SubroutineOperations::value_to_direction(&TubularNumber::new(1)), Direction::Right);
+        assert_eq!(SubroutineOperations::value_to_direction(&TubularNumber::new(2)), Direction::Down);
+        assert_eq!(SubroutineOperations::value_to_direction(&TubularNumber::new(3)), Direction::Left);
 
         // Test wraparound
-        assert_eq!(SubroutineOperations::value_to_direction(&TubularBigInt::new(4)), Direction::Up);
-        assert_eq!(SubroutineOperations::value_to_direction(&TubularBigInt::new(-1)), Direction::Left);
+        assert_eq!(SubroutineOperations::value_to_direction(&TubularNumber::new(4)), Direction::Up);
+        assert_eq!(SubroutineOperations::value_to_direction(&TubularNumber::new(-1)), Direction::Left);
 
         // Test invalid/zero value defaults
-        assert_eq!(SubroutineOperations::value_to_direction(&TubularBigInt::new(999)), Direction::Right);
+        assert_eq!(SubroutineOperations::value_to_direction(&TubularNumber::new(999)), Direction::Right);
     }
 
     #[test]
     fn test_direction_to_value() {
-        assert_eq!(SubroutineOperations::direction_to_value(Direction::Up), TubularBigInt::new(0));
-        assert_eq!(SubroutineOperations::direction_to_value(Direction::Right), TubularBigInt::new(1));
-        assert_eq!(SubroutineOperations::direction_to_value(Direction::Down), TubularBigInt::new(2));
-        assert_eq!(SubroutineOperations::direction_to_value(Direction::Left), TubularBigInt::new(3));
+        assert_eq!(SubroutineOperations::direction_to_value(Direction::Up), TubularNumber::new(0));
+        assert_eq!(SubroutineOperations::direction_to_value(Direction::Right), TubularNumber::new(1));
+        assert_eq!(SubroutineOperations::direction_to_value(Direction::Down), TubularNumber::new(2));
+        assert_eq!(SubroutineOperations::direction_to_value(Direction::Left), TubularNumber::new(3));
     }
 
     #[test]
@@ -401,8 +402,8 @@ mod tests {
         let grid = create_test_grid();
 
         // Setup stack for negative coordinate jump
-        stack.push(TubularBigInt::new(0)); // Up direction
-        stack.push(TubularBigInt::new(7)); // y=7
+        stack.push(TubularNumber::new(0)); // Up direction
+        stack.push(TubularNumber::new(7)); // y=7
 
         // Execute call to negative coordinates
         SubroutineOperations::process_call_operation(&mut droplet, &mut stack, &mut call_stack, &grid).unwrap();
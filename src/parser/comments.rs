@@ -0,0 +1,104 @@
+use crate::types::coordinate::Coordinate;
+use crate::types::error::{ErrorContext, ErrorType, InterpreterError, Position, Result};
+use crate::types::prelude::*;
+
+/// Replace every comment in `source` with spaces
+///
+/// `#` starts a comment that runs to the end of the line and `#[ ... ]#`
+/// comments out a block, which may span lines and does not nest. A `#` right
+/// of or below a `'` is the character that `'` reads and starts no comment.
+/// Each comment character becomes one space and line breaks are kept, so every
+/// cell keeps the line and column it has in `source`.
+pub fn strip_comments(source: &str) -> Result<String> {
+    let mut stripped = String::with_capacity(source.len());
+    // Where the open block comment started
+    let mut block: Option<Coordinate> = None;
+    // The previous line after stripping, to find `'` above a `#`
+    let mut above: Vec<char> = Vec::new();
+
+    for (y, line) in source.split('\n').enumerate() {
+        if y > 0 {
+            stripped.push('\n');
+        }
+        let chars: Vec<char> = line.chars().collect();
+        let mut row: Vec<char> = Vec::with_capacity(chars.len());
+        let mut x = 0;
+        while x < chars.len() {
+            let pair = (chars[x], chars.get(x + 1).copied());
+            let quoted = row.last() == Some(&'\'') || above.get(x) == Some(&'\'');
+            if block.is_some() {
+                if pair == (']', Some('#')) {
+                    block = None;
+                    row.extend([' ', ' ']);
+                    x += 2;
+                } else {
+                    row.push(blank(chars[x]));
+                    x += 1;
+                }
+            } else if chars[x] == '#' && quoted {
+                // A `#` read by `'` is a character, not a comment
+                row.push('#');
+                x += 1;
+            } else if pair == ('#', Some('[')) {
+                block = Some(Coordinate::new(x as isize, y as isize));
+                row.extend([' ', ' ']);
+                x += 2;
+            } else if chars[x] == '#' {
+                row.extend(chars[x..].iter().map(|&ch| blank(ch)));
+                break;
+            } else {
+                row.push(chars[x]);
+                x += 1;
+            }
+        }
+        stripped.extend(row.iter());
+        above = row;
+    }
+
+    if let Some(start) = block {
+        return Err(InterpreterError::enhanced(
+            format!("Unterminated comment block starting at line {}, column {}", start.y + 1, start.x + 1),
+            ErrorType::Syntax,
+        )
+        .with_context(ErrorContext::new(
+            Position::new(start.y as usize, start.x as usize, start),
+            source.lines().nth(start.y as usize).unwrap_or("").to_string(),
+        ))
+        .with_suggestions(vec!["Close the block with ']#'".to_string()]));
+    }
+
+    Ok(stripped)
+}
+
+/// Space standing in for a commented-out character; `\r` is kept so CRLF
+/// sources still split into the same lines
+fn blank(ch: char) -> char {
+    if ch == '\r' { ch } else { ' ' }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_become_spaces_in_place() {
+        let source = "@  # start\n7 #[ set\nto seven ]# n\n!";
+        let stripped = strip_comments(source).unwrap();
+        assert_eq!(stripped, "@         \n7       \n            n\n!");
+        assert_eq!(strip_comments("@\n|\n!").unwrap(), "@\n|\n!");
+    }
+
+    #[test]
+    fn test_quoted_hash_is_not_a_comment() {
+        assert_eq!(strip_comments("@'# n\n!").unwrap(), "@'# n\n!");
+        assert_eq!(strip_comments("@\n'\n#\nn\n!").unwrap(), "@\n'\n#\nn\n!");
+        // The quote inside a comment reads nothing
+        assert_eq!(strip_comments("@ # '\n    #x").unwrap(), "@    \n      ");
+    }
+
+    #[test]
+    fn test_unterminated_block_is_an_error() {
+        let error = strip_comments("@\n #[ never closed\n!").unwrap_err();
+        assert!(error.to_string().contains("line 2, column 2"));
+    }
+}
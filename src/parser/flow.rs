@@ -0,0 +1,92 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::operations::arithmetic::ArithmeticOperations;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use crate::types::prelude::*;
+use alloc::collections::BTreeSet;
+
+/// Static model of how droplets move through a grid
+///
+/// Mirrors the interpreter's per-cell movement rules without any runtime values,
+/// so value-dependent cells (the conditional `\` corner) report every possible exit.
+pub struct StaticFlow;
+
+impl StaticFlow {
+    /// Directions a droplet can leave `symbol` in after arriving with `direction`
+    ///
+    /// An empty result means the droplet is destroyed on this cell.
+    pub fn exits(symbol: char, direction: Direction) -> Vec<Direction> {
+        match symbol {
+            '|' | '-' | '@' | '\'' | ',' | 'n' | '?' | 'z' | 'K' | 'i' | 'r' | 'a' | 'O' | 'I' | 'W' | 'X' | 'T' | 'E' | 'V' | '{' | '}' | '(' | ')' | 'G' | 'P' | 'F' | 'Z' | 'B' | 'g' | 'p' | 'w' | 'e' | 'h' | '0'..='9' => vec![direction],
+            // A call resumes past its cell on return; a return with no caller passes through
+            'C' | 'R' => vec![direction],
+            '/' => vec![match direction {
+                Direction::Right => Direction::Up,
+                Direction::Down => Direction::Left,
+                Direction::Left => Direction::Down,
+                Direction::Up => Direction::Right,
+            }],
+            '\\' => {
+                // Zero reverses, non-zero reflects like a plain corner
+                let reflected = match direction {
+                    Direction::Right => Direction::Down,
+                    Direction::Up => Direction::Left,
+                    Direction::Left => Direction::Up,
+                    Direction::Down => Direction::Right,
+                };
+                vec![direction.opposite(), reflected]
+            }
+            '^' => vec![Direction::Up],
+            _ if ArithmeticOperations::is_arithmetic_operation(symbol) => vec![direction],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Cells (with headings) a droplet at `coord` moving `direction` can reach next tick
+    ///
+    /// A `C` bound to a label also reaches the label's entry, a `'` jumps
+    /// over the cell it reads, and a paired portal leaves from its other end.
+    pub fn successors(grid: &ProgramGrid, coord: Coordinate, direction: Direction) -> Vec<(Coordinate, Direction)> {
+        let mut successors: Vec<_> = match grid.get_symbol(coord) {
+            Some('\'') => vec![(coord + direction + direction, direction)],
+            Some('J') => grid.portals.exit(coord).map(|exit| (exit + direction, direction)).into_iter().collect(),
+            Some(symbol) => Self::exits(symbol, direction)
+                .into_iter()
+                .map(|exit| (coord + exit, exit))
+                .collect(),
+            None => Vec::new(),
+        };
+        if let Some(label) = grid.subroutines.call_label(coord).and_then(|name| grid.subroutines.label(name)) {
+            successors.push((label.entry, label.direction));
+        }
+        successors
+    }
+
+    /// Cells a droplet can reach from the start along any possible path
+    pub fn reachable(grid: &ProgramGrid) -> BTreeSet<Coordinate> {
+        Self::reachable_states(grid).into_iter().map(|(coord, _)| coord).collect()
+    }
+
+    /// Non-empty cells a droplet can reach from the start, with every heading it can have there
+    pub fn reachable_states(grid: &ProgramGrid) -> BTreeSet<(Coordinate, Direction)> {
+        let mut visited: BTreeSet<(Coordinate, Direction)> = BTreeSet::new();
+        let mut worklist = Self::entries(grid);
+        while let Some((coord, direction)) = worklist.pop() {
+            if grid.get(coord).is_none() || !visited.insert((coord, direction)) {
+                continue;
+            }
+            worklist.extend(Self::successors(grid, coord, direction));
+        }
+        visited
+    }
+
+    /// Initial droplet state, if the grid has a start symbol
+    pub fn entry(grid: &ProgramGrid) -> Option<(Coordinate, Direction)> {
+        grid.start.map(|start| (start, grid.start_direction))
+    }
+
+    /// Every droplet's initial state: the start droplet's, then the `%droplet` ones
+    pub fn entries(grid: &ProgramGrid) -> Vec<(Coordinate, Direction)> {
+        Self::entry(grid).into_iter().chain(grid.droplets.iter().copied()).collect()
+    }
+}
@@ -0,0 +1,147 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::parser::flow::StaticFlow;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use std::collections::{BTreeSet, HashSet};
+
+/// Why a droplet can move along a [`FlowEdge`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EdgeKind {
+    /// Ordinary movement
+    Flow,
+    /// A `\` reversing a droplet whose value is zero
+    Zero,
+    /// A `\` turning a droplet whose value is non-zero
+    NonZero,
+    /// A bound `C` jumping to its label
+    Call,
+}
+
+/// Possible move from one cell to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlowEdge {
+    pub from: Coordinate,
+    pub to: Coordinate,
+    pub kind: EdgeKind,
+}
+
+/// Every path a droplet can take through a program, cell to cell
+///
+/// Built from [`StaticFlow`], so value-dependent cells contribute all their
+/// exits. Cells no path reaches are kept, without edges, so they can be shown
+/// as unreachable.
+#[derive(Debug, Clone, Default)]
+pub struct ControlFlowGraph {
+    /// Every cell with its symbol, in reading order
+    pub cells: Vec<(Coordinate, char)>,
+    pub reachable: HashSet<Coordinate>,
+    /// Edges between cells, sorted and without duplicates
+    pub edges: Vec<FlowEdge>,
+}
+
+impl ControlFlowGraph {
+    pub fn build(grid: &ProgramGrid) -> Self {
+        let mut cells: Vec<_> = grid.iter().map(|(coord, cell)| (*coord, cell.symbol)).collect();
+        cells.sort_by_key(|(coord, _)| (coord.y, coord.x));
+
+        let mut reachable = HashSet::new();
+        let mut edges = BTreeSet::new();
+        let mut visited: HashSet<(Coordinate, Direction)> = HashSet::new();
+        let mut worklist = StaticFlow::entries(grid);
+        while let Some((coord, direction)) = worklist.pop() {
+            let Some(symbol) = grid.get_symbol(coord) else {
+                continue;
+            };
+            if !visited.insert((coord, direction)) {
+                continue;
+            }
+            reachable.insert(coord);
+
+            let call = grid.subroutines.call_label(coord)
+                .and_then(|name| grid.subroutines.label(name))
+                .map(|label| (label.entry, label.direction));
+            for (next, heading) in StaticFlow::successors(grid, coord, direction) {
+                let kind = if Some((next, heading)) == call {
+                    EdgeKind::Call
+                } else if symbol == '\\' && heading == direction.opposite() {
+                    EdgeKind::Zero
+                } else if symbol == '\\' {
+                    EdgeKind::NonZero
+                } else {
+                    EdgeKind::Flow
+                };
+                if grid.get(next).is_some() {
+                    edges.insert(FlowEdge { from: coord, to: next, kind });
+                }
+                worklist.push((next, heading));
+            }
+        }
+
+        ControlFlowGraph { cells, reachable, edges: edges.into_iter().collect() }
+    }
+
+    /// Graphviz rendering; unreachable cells are grey and dashed
+    ///
+    /// Nodes carry `pos` attributes, so `neato -n` lays them out as in the grid.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph program {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for (coord, symbol) in &self.cells {
+            let style = if self.reachable.contains(coord) { "" } else { ", style=dashed, color=gray, fontcolor=gray" };
+            dot.push_str(&format!(
+                "    {} [label=\"{} {}\", pos=\"{},{}\"{}];\n",
+                node_id(*coord), escape(*symbol), coord, coord.x * 72, -coord.y * 72, style,
+            ));
+        }
+        for edge in &self.edges {
+            let attributes = match edge.kind {
+                EdgeKind::Flow => "",
+                EdgeKind::Zero => " [label=\"= 0\"]",
+                EdgeKind::NonZero => " [label=\"!= 0\"]",
+                EdgeKind::Call => " [label=\"call\", style=dashed]",
+            };
+            dot.push_str(&format!("    {} -> {}{};\n", node_id(edge.from), node_id(edge.to), attributes));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn node_id(coord: Coordinate) -> String {
+    format!("\"{},{}\"", coord.x, coord.y)
+}
+
+/// A symbol as it must appear inside a quoted DOT string
+fn escape(symbol: char) -> String {
+    match symbol {
+        '"' | '\\' => format!("\\{}", symbol),
+        _ => symbol.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_graph_marks_branches_calls_and_unreachable_cells() {
+        let source = "@ ;\nC +\n  \\n\n   !\n\n G\n%label top at (2, 0)\n%call top at (0, 1)";
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let graph = ControlFlowGraph::build(&grid);
+
+        let edge = |from: (isize, isize), to: (isize, isize), kind| FlowEdge {
+            from: Coordinate::new(from.0, from.1), to: Coordinate::new(to.0, to.1), kind,
+        };
+        assert!(graph.edges.contains(&edge((0, 0), (0, 1), EdgeKind::Flow)));
+        assert!(graph.edges.contains(&edge((0, 1), (2, 0), EdgeKind::Call)));
+        assert!(graph.edges.contains(&edge((2, 2), (3, 2), EdgeKind::NonZero)));
+        assert!(graph.edges.contains(&edge((2, 2), (2, 1), EdgeKind::Zero)));
+        assert!(!graph.reachable.contains(&Coordinate::new(1, 5)));
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph program {"));
+        assert!(dot.contains("\"2,2\" [label=\"\\\\ (2, 2)\""));
+        assert!(dot.contains("\"1,5\" [label=\"G (1, 5)\", pos=\"72,-360\", style=dashed"));
+        assert!(dot.contains("\"0,1\" -> \"2,0\" [label=\"call\", style=dashed];"));
+    }
+}
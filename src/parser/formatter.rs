@@ -0,0 +1,145 @@
+use crate::parser::comments::strip_comments;
+use crate::parser::grid_parser::GridParser;
+use crate::parser::preprocessor::is_directive;
+use crate::types::error::Result;
+
+/// Rewrites program source into a canonical layout without changing its meaning
+///
+/// Every non-space character keeps its grid coordinate: tabs become single
+/// spaces (the parser counts a tab as one column), trailing whitespace and
+/// trailing blank lines are dropped, and the file ends with a newline.
+/// Comments and directives are kept where they are.
+#[derive(Debug, Clone, Default)]
+pub struct GridFormatter {
+    pad: bool,
+    reanchor: bool,
+}
+
+impl GridFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pad every line with spaces to the width of the widest line
+    pub fn with_padding(mut self, pad: bool) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    /// Drop leading blank rows and shared indentation so the program starts at (0, 0)
+    ///
+    /// This moves every cell, so programs that call subroutines by absolute
+    /// coordinate must not be re-anchored.
+    pub fn with_reanchor(mut self, reanchor: bool) -> Self {
+        self.reanchor = reanchor;
+        self
+    }
+
+    /// Format `content`, failing if it does not parse
+    pub fn format(&self, content: &str) -> Result<String> {
+        let grid = GridParser::new().parse_string(content)?;
+
+        let keep_text = strip_comments(content)? != content || content.lines().any(is_directive);
+        let mut lines: Vec<String> = if keep_text {
+            self.layout_text(content)
+        } else if self.reanchor {
+            if grid.cells.is_empty() {
+                Vec::new()
+            } else {
+                grid.symbols_in_bounds()
+            }
+        } else {
+            grid.to_text().lines().map(str::to_string).collect()
+        };
+
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let mut text = String::new();
+        for line in lines.iter_mut() {
+            if self.pad {
+                let padding = width - line.chars().count();
+                line.extend(std::iter::repeat_n(' ', padding));
+            } else {
+                line.truncate(line.trim_end().len());
+            }
+            text.push_str(line);
+            text.push('\n');
+        }
+
+        Ok(text)
+    }
+
+    /// Lines of `content` laid out from the text itself, so comments and
+    /// directives survive
+    ///
+    /// Re-anchoring removes the indentation shared by all non-blank lines,
+    /// comments and directives included.
+    fn layout_text(&self, content: &str) -> Vec<String> {
+        let mut lines: Vec<String> = content.lines()
+            .map(|line| line.chars().map(|ch| if ch.is_whitespace() { ' ' } else { ch }).collect::<String>())
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        while lines.last().is_some_and(String::is_empty) {
+            lines.pop();
+        }
+
+        if self.reanchor {
+            let leading = lines.iter().take_while(|line| line.is_empty()).count();
+            lines.drain(..leading);
+            let indent = lines.iter()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.len() - line.trim_start().len())
+                .min()
+                .unwrap_or(0);
+            for line in lines.iter_mut() {
+                line.drain(..indent.min(line.len()));
+            }
+        }
+        lines
+    }
+
+    /// Whether `content` is already in the canonical layout
+    pub fn is_formatted(&self, content: &str) -> Result<bool> {
+        Ok(self.format(content)? == content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailing_whitespace_and_tabs_are_normalized() {
+        let formatted = GridFormatter::new().format("@  \n\t7\n\tn \n\t!\n\n\n").unwrap();
+        assert_eq!(formatted, "@\n 7\n n\n !\n");
+        assert!(GridFormatter::new().is_formatted(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_padding_produces_a_rectangle() {
+        let formatted = GridFormatter::new().with_padding(true).format("@\n7--\nn\n!").unwrap();
+        assert_eq!(formatted, "@  \n7--\nn  \n!  \n");
+    }
+
+    #[test]
+    fn test_reanchor_moves_program_to_origin() {
+        let source = "\n\n   @\n   7\n   n\n   !";
+        let formatted = GridFormatter::new().with_reanchor(true).format(source).unwrap();
+        assert_eq!(formatted, "@\n7\nn\n!\n");
+
+        let kept = GridFormatter::new().format(source).unwrap();
+        assert_eq!(kept, "\n\n   @\n   7\n   n\n   !\n");
+    }
+
+    #[test]
+    fn test_comments_are_kept() {
+        let source = "\n  @   # start \n  7\t#[ seven\n ]#\n  n\n  !\n\n";
+        let formatted = GridFormatter::new().format(source).unwrap();
+        assert_eq!(formatted, "\n  @   # start\n  7 #[ seven\n ]#\n  n\n  !\n");
+
+        let reanchored = GridFormatter::new().with_reanchor(true).format(source).unwrap();
+        assert_eq!(reanchored, " @   # start\n 7 #[ seven\n]#\n n\n !\n");
+
+        let templated = GridFormatter::new().format("@\n%use out at (0, 1)  \n%def out\nn\n%end\n").unwrap();
+        assert_eq!(templated, "@\n%use out at (0, 1)\n%def out\nn\n%end\n");
+    }
+}
@@ -1,8 +1,19 @@
+use crate::interpreter::collision::CollisionPolicy;
 use crate::interpreter::grid::{ProgramGrid, ProgramCell};
+use crate::interpreter::subroutines::Label;
+use crate::operations::io::EofPolicy;
+use crate::parser::comments::strip_comments;
+use crate::parser::preprocessor::{parse_offset, preprocess};
+use crate::parser::symbol_map::SymbolMap;
 use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
 use crate::types::error::{Result, InitError, InterpreterError, ErrorType, ErrorSeverity, Position, ErrorContext};
-use std::io::{self, Read};
-use std::collections::HashMap;
+use crate::types::prelude::*;
+#[cfg(feature = "std")]
+use std::io::Read;
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
 
 /// Parsing context for tracking source information
 #[derive(Debug, Clone)]
@@ -36,7 +47,7 @@ impl ParseContext {
 
     pub fn get_surrounding_lines(&self, line_index: usize, context_size: usize) -> Vec<(usize, String)> {
         let start = line_index.saturating_sub(context_size);
-        let end = std::cmp::min(line_index + context_size + 1, self.lines.len());
+        let end = core::cmp::min(line_index + context_size + 1, self.lines.len());
 
         self.lines[start..end]
             .iter()
@@ -54,17 +65,19 @@ impl ParseContext {
     }
 
     pub fn create_error_context(&self, position: Position, span: Option<(usize, usize)>) -> ErrorContext {
+        let error_span = span.unwrap_or((position.column, position.column + 1));
+        // Positions in an included file have no text here to show
+        if position.file.is_some() {
+            return ErrorContext::new(position, String::new()).with_span(error_span.0, error_span.1);
+        }
+
         let source_line = self.get_line(position.line)
             .unwrap_or("")
             .to_string();
 
-        let error_span = span.unwrap_or((position.column, position.column + 1));
-
-        let context = ErrorContext::new(position.clone(), source_line)
+        ErrorContext::new(position.clone(), source_line)
             .with_span(error_span.0, error_span.1)
-            .with_surrounding_lines(self.get_surrounding_lines(position.line, 2));
-
-        context
+            .with_surrounding_lines(self.get_surrounding_lines(position.line, 2))
     }
 }
 
@@ -73,6 +86,10 @@ pub struct GridParser {
     parse_context: Option<ParseContext>,
     collect_errors: bool,
     errors: Vec<InterpreterError>,
+    /// Files being included, outermost first (empty when parsing a program)
+    #[cfg(feature = "std")]
+    include_stack: Vec<PathBuf>,
+    symbols: SymbolMap,
 }
 
 impl GridParser {
@@ -81,6 +98,9 @@ impl GridParser {
             parse_context: None,
             collect_errors: false,
             errors: Vec::new(),
+            #[cfg(feature = "std")]
+            include_stack: Vec::new(),
+            symbols: SymbolMap::new(),
         }
     }
 
@@ -89,6 +109,12 @@ impl GridParser {
         self
     }
 
+    /// Read the aliases in `symbols` as the symbols they stand for, here and in included files
+    pub fn with_symbol_map(mut self, symbols: SymbolMap) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
     pub fn get_errors(&self) -> &[InterpreterError] {
         &self.errors
     }
@@ -97,6 +123,7 @@ impl GridParser {
         self.errors.clear();
     }
 
+    #[cfg(feature = "std")]
     pub fn parse_file(&self, file_path: &str) -> Result<ProgramGrid> {
         let content = std::fs::read_to_string(file_path)?;
         let mut parser = self.clone();
@@ -108,6 +135,30 @@ impl GridParser {
         parser.parse_string_with_context(content, "<string>".to_string())
     }
 
+    /// Parse a program from a string, recording `source_name` in the grid's source map
+    pub fn parse_named(&self, content: &str, source_name: &str) -> Result<ProgramGrid> {
+        let mut parser = self.clone();
+        parser.parse_string_with_context(content, source_name.to_string())
+    }
+
+    /// Parse a program, collecting every error instead of stopping at the first
+    ///
+    /// Returns the grid built from the valid cells, unless an error such as an
+    /// unterminated comment stopped parsing, with every error found.
+    pub fn parse_all(&self, content: &str, source_name: &str) -> (Option<ProgramGrid>, Vec<InterpreterError>) {
+        let mut parser = self.clone().with_error_collection();
+        parser.parse_context = Some(ParseContext::new(source_name.to_string(), content));
+        parser.errors.clear();
+        let lines: Vec<&str> = content.lines().collect();
+        match parser.build_grid(&lines) {
+            Ok(grid) => (Some(grid), parser.errors),
+            Err(e) => {
+                parser.errors.push(e);
+                (None, parser.errors)
+            }
+        }
+    }
+
     fn parse_string_with_context(&mut self, content: &str, source_name: String) -> Result<ProgramGrid> {
         self.parse_context = Some(ParseContext::new(source_name.clone(), content));
         self.errors.clear();
@@ -122,8 +173,162 @@ impl GridParser {
     }
 
     fn parse_lines_with_context(&mut self, lines: &[&str]) -> Result<ProgramGrid> {
+        let grid = self.build_grid(lines)?;
+
+        // If we're collecting errors, fail with a summary; they are in `errors`
+        if self.collect_errors && !self.errors.is_empty() {
+            return Err(InterpreterError::enhanced(
+                format!("Found {} parsing errors", self.errors.len()),
+                ErrorType::Syntax
+            ).with_severity(ErrorSeverity::Error));
+        }
+
+        Ok(grid)
+    }
+
+    /// Build the grid, keeping errors in `errors` when collecting them
+    fn build_grid(&mut self, lines: &[&str]) -> Result<ProgramGrid> {
+        // Comments become spaces, so cells keep their source columns
+        let code = preprocess(&strip_comments(&lines.join("\n"))?)?;
+        let mut lines: Vec<&str> = code.text.lines().collect();
         let mut grid = ProgramGrid::new();
         let mut invalid_chars = Vec::new();
+        let source_name = self.parse_context
+            .as_ref()
+            .map(|ctx| ctx.source_name.clone())
+            .unwrap_or_else(|| "<lines>".to_string());
+        grid.source_map.set_program_file(&source_name);
+
+        // Directive lines hold no cells; the files they name are stitched in below
+        let mut includes = Vec::new();
+        let mut droplets = Vec::new();
+        for (y, line) in lines.iter_mut().enumerate() {
+            if line.trim_start().starts_with(INCLUDE_DIRECTIVE) {
+                match parse_include(line) {
+                    Some(include) => includes.push((y, include)),
+                    None => self.report(self.directive_error(
+                        format!("Invalid include directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write includes as %include \"file.tub\" at (x, y)".to_string(),
+                    ]))?,
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(LABEL_DIRECTIVE) {
+                match parse_label(line) {
+                    Some((name, label)) => {
+                        if !grid.subroutines.define_label(&name, label) {
+                            self.report(self.directive_error(format!("Label '{}' is defined twice", name), y))?;
+                        }
+                    }
+                    None => self.report(self.directive_error(
+                        format!("Invalid label directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write labels as %label name at (x, y), optionally followed by heading up|down|left|right".to_string(),
+                    ]))?,
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(CALL_DIRECTIVE) {
+                match parse_call(line) {
+                    Some((name, site)) => grid.subroutines.bind_call(site, &name),
+                    None => self.report(self.directive_error(
+                        format!("Invalid call directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write calls as %call name at (x, y), where (x, y) is a 'C' cell".to_string(),
+                    ]))?,
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(PORTAL_DIRECTIVE) {
+                match parse_portal(line) {
+                    Some((name, coord)) => {
+                        if !grid.portals.bind(coord, &name) {
+                            self.report(self.directive_error(format!("The cell at {} is named as a portal twice", coord), y))?;
+                        }
+                    }
+                    None => self.report(self.directive_error(
+                        format!("Invalid portal directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write portals as %portal name at (x, y), where (x, y) is a 'J' cell".to_string(),
+                    ]))?,
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(START_DIRECTIVE) {
+                match parse_start(line) {
+                    Some(direction) => grid.start_direction = direction,
+                    None => self.report(self.directive_error(
+                        format!("Invalid start directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write %start heading up|down|left|right".to_string(),
+                    ]))?,
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(DROPLET_DIRECTIVE) {
+                match parse_droplet(line) {
+                    Some(droplet) => droplets.push((y, droplet)),
+                    None => self.report(self.directive_error(
+                        format!("Invalid droplet directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write droplets as %droplet at (x, y), optionally followed by heading up|down|left|right".to_string(),
+                    ]))?,
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(MULTI_DIGIT_DIRECTIVE) {
+                if line.trim() == MULTI_DIGIT_DIRECTIVE {
+                    grid.multi_digit_literals = true;
+                } else {
+                    self.report(self.directive_error(
+                        format!("Invalid multidigit directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write %multidigit alone on its line".to_string(),
+                    ]))?;
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(RATIONAL_DIRECTIVE) {
+                if line.trim() != RATIONAL_DIRECTIVE {
+                    self.report(self.directive_error(
+                        format!("Invalid rational directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![
+                        "Write %rational alone on its line".to_string(),
+                    ]))?;
+                } else if cfg!(feature = "rational") {
+                    grid.rational_numbers = true;
+                } else {
+                    self.report(self.directive_error(
+                        "%rational needs tubular built with the rational feature".to_string(), y,
+                    ).with_suggestions(vec![
+                        "Rebuild with --features rational".to_string(),
+                    ]))?;
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(COLLISIONS_DIRECTIVE) {
+                let policy = line.trim().strip_prefix(COLLISIONS_DIRECTIVE)
+                    .and_then(|rest| rest.strip_prefix(char::is_whitespace))
+                    .and_then(|name| CollisionPolicy::from_name(name.trim()));
+                match policy {
+                    Some(policy) => grid.collision_policy = Some(policy),
+                    None => self.report(self.directive_error(
+                        format!("Invalid collisions directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![format!(
+                        "Write %collisions followed by one of: {}",
+                        CollisionPolicy::ALL.map(CollisionPolicy::name).join(", "),
+                    )]))?,
+                }
+                *line = "";
+            } else if line.trim_start().starts_with(EOF_DIRECTIVE) {
+                let policy = line.trim().strip_prefix(EOF_DIRECTIVE)
+                    .and_then(|rest| rest.strip_prefix(char::is_whitespace))
+                    .and_then(|name| EofPolicy::from_name(name.trim()));
+                match policy {
+                    Some(policy) => grid.eof_policy = Some(policy),
+                    None => self.report(self.directive_error(
+                        format!("Invalid eof directive: {}", line.trim()), y,
+                    ).with_suggestions(vec![format!(
+                        "Write %eof followed by one of: {}",
+                        EofPolicy::ALL.map(EofPolicy::name).join(", "),
+                    )]))?,
+                }
+                *line = "";
+            }
+        }
 
         for (y, line) in lines.iter().enumerate() {
             for (x, ch) in line.chars().enumerate() {
@@ -132,13 +337,21 @@ impl GridParser {
                 }
 
                 let coord = Coordinate::new(x as isize, y as isize);
+                // Cells after a `'` are read as characters, not symbols
+                let quoted = grid.is_quoted(coord);
+                let ch = if quoted { ch } else { self.symbols.resolve(ch) };
+                // Macros move cells, so errors point at where the cell was written
+                let (line, column) = code.origin(coord);
+                let position = Position::new(line, column, coord);
 
                 // Validate character before adding to grid
-                match self.validate_character(ch, coord) {
+                let valid = if quoted { Ok(()) } else { self.validate_character(ch, position.clone()) };
+                match valid {
                     Ok(()) => {
+                        grid.source_map.insert(coord, &source_name, line, column);
                         if let Err(e) = grid.add_cell(coord, ch) {
                             if self.collect_errors {
-                                let context = self.create_error_context_for_coord(coord);
+                                let context = self.create_error_context_at(position);
                                 let enhanced_error = self.enhance_error_for_interpreter_error(e, context);
                                 self.errors.push(enhanced_error);
                             } else {
@@ -148,7 +361,7 @@ impl GridParser {
                     }
                     Err(e) => {
                         if self.collect_errors {
-                            let context = self.create_error_context_for_coord(coord);
+                            let context = self.create_error_context_at(position);
                             let enhanced_error = e.with_context(context);
                             self.errors.push(enhanced_error);
                             invalid_chars.push((coord, ch));
@@ -160,23 +373,137 @@ impl GridParser {
             }
         }
 
-        // Check for multiple start symbols
-        self.validate_start_symbols(&grid)?;
+        // Droplets must start on a cell of this file, like the start symbol
+        for (y, (coord, direction)) in droplets {
+            if grid.get(coord).is_none() {
+                self.report(self.directive_error(format!("Droplet at {} does not start on a cell", coord), y))?;
+            } else {
+                grid.droplets.push((coord, direction));
+            }
+        }
 
-        // If we're collecting errors, return the grid anyway with all errors collected
-        if self.collect_errors && !self.errors.is_empty() {
-            return Err(InterpreterError::enhanced(
-                format!("Found {} parsing errors", self.errors.len()),
-                ErrorType::Syntax
-            ).with_severity(ErrorSeverity::Error));
+        for (y, (path, offset)) in includes {
+            self.include_file(&mut grid, &source_name, y, &path, offset)?;
+        }
+
+        // Included files are usually libraries without a start symbol; the
+        // program that includes them is checked as a whole
+        #[cfg(feature = "std")]
+        let included = !self.include_stack.is_empty();
+        #[cfg(not(feature = "std"))]
+        let included = false;
+        if !included {
+            self.validate_start_symbols(&grid)?;
         }
 
         Ok(grid)
     }
 
-    fn validate_character(&self, ch: char, coord: Coordinate) -> Result<()> {
+    /// Parse the file at `path` and add its cells to `grid`, shifted by `offset`
+    ///
+    /// `path` is resolved against the directory of `source_name`. Cells keep
+    /// their location in the included file in the grid's source map.
+    #[cfg(feature = "std")]
+    fn include_file(&mut self, grid: &mut ProgramGrid, source_name: &str, line: usize, path: &str, offset: Coordinate) -> Result<()> {
+        let resolved = Path::new(source_name).parent().unwrap_or(Path::new("")).join(path);
+        let canonical = resolved.canonicalize().unwrap_or_else(|_| resolved.clone());
+
+        let mut chain = self.include_stack.clone();
+        if let Ok(current) = Path::new(source_name).canonicalize()
+            && !chain.contains(&current)
+        {
+            chain.push(current);
+        }
+        if chain.contains(&canonical) {
+            let cycle: Vec<String> = chain.iter()
+                .chain([&canonical])
+                .map(|file| file.display().to_string())
+                .collect();
+            return self.report(self.directive_error(format!("Include cycle: {}", cycle.join(" -> ")), line));
+        }
+
+        let content = match std::fs::read_to_string(&resolved) {
+            Ok(content) => content,
+            Err(e) => {
+                return self.report(self.directive_error(
+                    format!("Cannot read included file '{}': {}", resolved.display(), e), line,
+                ));
+            }
+        };
+        chain.push(canonical);
+        let parser = GridParser { include_stack: chain, symbols: self.symbols.clone(), ..GridParser::new() };
+        let included = match parser.parse_named(&content, &resolved.display().to_string()) {
+            Ok(included) => included,
+            Err(e) => {
+                let error = match self.directive_error(format!("Error in included file '{}'", resolved.display()), line) {
+                    InterpreterError::Enhanced { info, .. } => InterpreterError::Enhanced { info, source: Some(Box::new(e)) },
+                    other => other,
+                };
+                return self.report(error);
+            }
+        };
+
+        if let Some(name) = grid.subroutines.merge(&included.subroutines, offset) {
+            return self.report(self.directive_error(
+                format!("Label '{}' from included file '{}' is already defined", name, resolved.display()), line,
+            ));
+        }
+        if let Some(coord) = grid.portals.merge(&included.portals, offset) {
+            return self.report(self.directive_error(
+                format!("Portal at {} from included file '{}' is already named", coord, resolved.display()), line,
+            ));
+        }
+        grid.droplets.extend(included.droplets.iter()
+            .map(|(coord, direction)| (Coordinate::new(coord.x + offset.x, coord.y + offset.y), *direction)));
+        for (coord, cell) in included.iter() {
+            let target = Coordinate::new(coord.x + offset.x, coord.y + offset.y);
+            if grid.get(target).is_some() {
+                return self.report(self.directive_error(
+                    format!("Included file '{}' overlaps the program at {}", resolved.display(), target), line,
+                ));
+            }
+            if let Err(e) = grid.add_cell(target, cell.symbol) {
+                return self.report(self.enhance_error_for_interpreter_error(e, self.directive_context(line)));
+            }
+            if let Some(location) = included.source_location(*coord) {
+                grid.source_map.insert(target, &location.file, location.line, location.column);
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds without std have no filesystem, so every include fails
+    #[cfg(not(feature = "std"))]
+    fn include_file(&mut self, _grid: &mut ProgramGrid, _source_name: &str, line: usize, path: &str, _offset: Coordinate) -> Result<()> {
+        self.report(self.directive_error(
+            format!("Cannot read included file '{}': files can only be included with the std feature", path), line,
+        ))
+    }
+
+    fn directive_context(&self, line: usize) -> ErrorContext {
+        let indent = self.parse_context.as_ref()
+            .and_then(|ctx| ctx.get_line(line))
+            .map_or(0, |text| text.len() - text.trim_start().len());
+        self.create_error_context_at(Position::new(line, indent, Coordinate::new(indent as isize, line as isize)))
+    }
+
+    fn directive_error(&self, message: String, line: usize) -> InterpreterError {
+        InterpreterError::enhanced(message, ErrorType::Initialization)
+            .with_context(self.directive_context(line))
+    }
+
+    /// Keep `error` when collecting errors, otherwise fail with it
+    fn report(&mut self, error: InterpreterError) -> Result<()> {
+        if self.collect_errors {
+            self.errors.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn validate_character(&self, ch: char, position: Position) -> Result<()> {
         if !ProgramCell::is_valid_symbol(ch) {
-            let position = Position::new(coord.y as usize, coord.x as usize, coord);
             let mut error = InterpreterError::enhanced(
                 format!("Invalid character '{}' found in program", ch),
                 ErrorType::Syntax
@@ -238,7 +565,7 @@ impl GridParser {
                 // Add context for each extra start symbol
                 if let Some(ref context) = self.parse_context {
                     for (i, &coord) in start_positions.iter().enumerate() {
-                        let position = context.position_from_coordinate(coord);
+                        let position = grid.error_position(coord);
                         let error_context = context.create_error_context(position, None);
 
                         let extra_start_error = InterpreterError::enhanced(
@@ -262,8 +589,7 @@ impl GridParser {
         }
     }
 
-    fn create_error_context_for_coord(&self, coord: Coordinate) -> ErrorContext {
-        let position = Position::new(coord.y as usize, coord.x as usize, coord);
+    fn create_error_context_at(&self, position: Position) -> ErrorContext {
         self.parse_context
             .as_ref()
             .map(|ctx| ctx.create_error_context(position.clone(), None))
@@ -308,20 +634,23 @@ impl GridParser {
         }
     }
 
+    #[cfg(feature = "std")]
     pub fn parse_reader<R: Read>(&self, reader: &mut R) -> Result<ProgramGrid> {
         let mut content = String::new();
         reader.read_to_string(&mut content)?;
         self.parse_string(&content)
     }
 
+    #[cfg(feature = "stdin")]
     pub fn parse_from_stdin() -> Result<ProgramGrid> {
         let parser = GridParser::new();
-        let mut stdin = io::stdin();
+        let mut stdin = std::io::stdin();
         parser.parse_reader(&mut stdin)
     }
 
     pub fn parse_with_origin(&self, content: &str, origin_x: isize, origin_y: isize) -> Result<ProgramGrid> {
-        let lines: Vec<&str> = content.lines().collect();
+        let code = preprocess(&strip_comments(content)?)?;
+        let lines: Vec<&str> = code.text.lines().collect();
         let mut grid = ProgramGrid::new();
 
         for (y_offset, line) in lines.iter().enumerate() {
@@ -361,8 +690,8 @@ impl GridParser {
         symbols
     }
 
-    pub fn count_symbols(&self, content: &str) -> HashMap<char, usize> {
-        let mut counts = HashMap::new();
+    pub fn count_symbols(&self, content: &str) -> BTreeMap<char, usize> {
+        let mut counts = BTreeMap::new();
 
         for line in content.lines() {
             for ch in line.chars() {
@@ -432,6 +761,93 @@ impl GridParser {
     }
 }
 
+/// Line prefix that stitches another grid file into the program
+const INCLUDE_DIRECTIVE: &str = "%include";
+
+/// Path and offset of `%include "file" at (x, y)`
+fn parse_include(line: &str) -> Option<(String, Coordinate)> {
+    let rest = line.trim().strip_prefix(INCLUDE_DIRECTIVE)?;
+    let rest = rest.trim_start().strip_prefix('"')?;
+    let (path, rest) = rest.split_once('"')?;
+    if path.is_empty() {
+        return None;
+    }
+    Some((path.to_string(), parse_offset(rest)?))
+}
+
+/// Line prefix that names a subroutine entry point
+const LABEL_DIRECTIVE: &str = "%label";
+/// Line prefix that binds a `C` cell to a label
+const CALL_DIRECTIVE: &str = "%call";
+
+/// Line prefix that names one end of a portal pair
+const PORTAL_DIRECTIVE: &str = "%portal";
+
+/// Line that sets the heading droplets leave the start symbol with
+const START_DIRECTIVE: &str = "%start";
+
+/// Line prefix that starts another droplet alongside the start symbol's
+const DROPLET_DIRECTIVE: &str = "%droplet";
+
+/// Line that makes consecutive digits on a droplet's path one literal
+const MULTI_DIGIT_DIRECTIVE: &str = "%multidigit";
+/// Line that makes values exact fractions (feature `rational`)
+const RATIONAL_DIRECTIVE: &str = "%rational";
+/// Line that picks what happens when droplets collide
+const COLLISIONS_DIRECTIVE: &str = "%collisions";
+/// Line that picks what `?` gives at end of input
+const EOF_DIRECTIVE: &str = "%eof";
+
+/// Name and entry of `%label name at (x, y) [heading direction]`
+fn parse_label(line: &str) -> Option<(String, Label)> {
+    let rest = line.trim().strip_prefix(LABEL_DIRECTIVE)?;
+    let (name, rest) = directive_name(rest)?;
+    let (entry, direction) = parse_site(rest)?;
+    Some((name, Label { entry, direction }))
+}
+
+/// Cell and heading of `%droplet at (x, y) [heading direction]`
+fn parse_droplet(line: &str) -> Option<(Coordinate, Direction)> {
+    parse_site(line.trim().strip_prefix(DROPLET_DIRECTIVE)?)
+}
+
+/// Cell and heading of `at (x, y) [heading direction]`, heading down by default
+fn parse_site(text: &str) -> Option<(Coordinate, Direction)> {
+    let (offset, heading) = match text.split_once("heading") {
+        Some((offset, heading)) => (offset, heading.trim()),
+        None => (text, "down"),
+    };
+    Some((parse_offset(offset)?, Direction::from_name(heading)?))
+}
+
+/// Heading of `%start heading direction`
+fn parse_start(line: &str) -> Option<Direction> {
+    let rest = line.trim().strip_prefix(START_DIRECTIVE)?;
+    let heading = rest.trim_start().strip_prefix("heading")?;
+    Direction::from_name(heading.trim())
+}
+
+/// Label and call site of `%call name at (x, y)`
+fn parse_call(line: &str) -> Option<(String, Coordinate)> {
+    let rest = line.trim().strip_prefix(CALL_DIRECTIVE)?;
+    let (name, rest) = directive_name(rest)?;
+    Some((name, parse_offset(rest)?))
+}
+
+/// Name and cell of `%portal name at (x, y)`
+fn parse_portal(line: &str) -> Option<(String, Coordinate)> {
+    let rest = line.trim().strip_prefix(PORTAL_DIRECTIVE)?;
+    let (name, rest) = directive_name(rest)?;
+    Some((name, parse_offset(rest)?))
+}
+
+/// Leading name of a directive's arguments and the text after it
+fn directive_name(text: &str) -> Option<(String, &str)> {
+    let (name, rest) = text.trim_start().split_once(char::is_whitespace)?;
+    let valid = name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    valid.then(|| (name.to_string(), rest))
+}
+
 impl Default for GridParser {
     fn default() -> Self {
         Self::new()
@@ -461,4 +877,217 @@ mod tests {
         assert!(grid.start.is_some());
         assert_eq!(grid.size(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_source_map_records_file_positions() {
+        let parser = GridParser::new();
+        let content = "@\n  |\n  !";
+        let grid = parser.parse_named(content, "prog.tb").unwrap();
+
+        let location = grid.source_location(Coordinate::new(2, 1)).unwrap();
+        assert_eq!(location.file, "prog.tb");
+        assert_eq!((location.line, location.column), (1, 2));
+        assert_eq!(location.to_string(), "prog.tb:2:3");
+        assert!(grid.source_location(Coordinate::new(1, 1)).is_none());
+    }
+
+    #[test]
+    fn test_to_text_round_trip_keeps_offset() {
+        let parser = GridParser::new();
+        let content = "\n   @\n   |  \n  /-\\\n  n !\n";
+        let grid = parser.parse_string(content).unwrap();
+
+        let text = grid.to_text();
+        assert_eq!(text, "\n   @\n   |\n  /-\\\n  n !\n");
+        assert_eq!(parser.parse_string(&text).unwrap(), grid);
+    }
+
+    #[test]
+    fn test_multidigit_directive_sets_literal_mode() {
+        let parser = GridParser::new();
+        assert!(!parser.parse_string("@\n12\nn").unwrap().multi_digit_literals);
+        let grid = parser.parse_string("%multidigit\n@\n1\n2\nn").unwrap();
+        assert!(grid.multi_digit_literals);
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 1)), Some('@'));
+        assert!(grid.to_source().ends_with("%multidigit\n"));
+        assert!(parser.parse_string("%multidigit on\n@\n!").is_err());
+    }
+
+    #[test]
+    fn test_portal_directives_pair_cells() {
+        let parser = GridParser::new();
+        let grid = parser.parse_string("@  J\nJ\n%portal there at (3, 0)\n%portal there at (0, 1)").unwrap();
+        assert_eq!(grid.portals.exit(Coordinate::new(0, 1)), Some(Coordinate::new(3, 0)));
+        assert!(grid.to_source().ends_with("%portal there at (3, 0)\n%portal there at (0, 1)\n"));
+        assert_eq!(parser.parse_string(&grid.to_source()).unwrap().portals.portals(), grid.portals.portals());
+
+        assert!(parser.parse_string("@\nJ\n%portal at (0, 1)").is_err());
+        assert!(parser.parse_string("@\nJ\n%portal a at (0, 1)\n%portal b at (0, 1)").is_err());
+    }
+
+    #[test]
+    fn test_collisions_directive_sets_the_policy() {
+        let parser = GridParser::new();
+        assert_eq!(parser.parse_string("@\n!").unwrap().collision_policy, None);
+        let grid = parser.parse_string("%collisions keep-oldest\n@\n!").unwrap();
+        assert_eq!(grid.collision_policy, Some(CollisionPolicy::KeepOldest));
+        assert!(grid.to_source().ends_with("%collisions keep-oldest\n"));
+        assert!(parser.parse_string("%collisions\n@\n!").is_err());
+        assert!(parser.parse_string("%collisions sideways\n@\n!").is_err());
+    }
+
+    #[test]
+    fn test_eof_directive_sets_the_policy() {
+        let parser = GridParser::new();
+        assert_eq!(parser.parse_string("@\n!").unwrap().eof_policy, None);
+        let grid = parser.parse_string("%eof minus-one\n@\n?\nn").unwrap();
+        assert_eq!(grid.eof_policy, Some(EofPolicy::MinusOne));
+        assert!(grid.to_source().ends_with("%eof minus-one\n"));
+        assert!(parser.parse_string("%eof\n@\n!").is_err());
+        assert!(parser.parse_string("%eof -1\n@\n!").is_err());
+    }
+
+    #[test]
+    fn test_start_directive_sets_the_start_heading() {
+        let parser = GridParser::new();
+        assert_eq!(parser.parse_string("@\n!").unwrap().start_direction, Direction::Down);
+        let grid = parser.parse_string("%start heading right\n@7n!").unwrap();
+        assert_eq!(grid.start_direction, Direction::Right);
+        assert!(grid.to_source().ends_with("%start heading right\n"));
+        assert!(parser.parse_string("%start right\n@\n!").is_err());
+        assert!(parser.parse_string("%start heading sideways\n@\n!").is_err());
+    }
+
+    #[test]
+    fn test_droplet_directives_start_more_droplets() {
+        let parser = GridParser::new();
+        let grid = parser.parse_string("@-5\n%droplet at (2, 0) heading left\n%droplet at (1, 0)").unwrap();
+        assert_eq!(grid.droplets, vec![(Coordinate::new(2, 0), Direction::Left), (Coordinate::new(1, 0), Direction::Down)]);
+        assert!(grid.to_source().ends_with("%droplet at (2, 0) heading left\n%droplet at (1, 0) heading down\n"));
+        assert!(parser.parse_string("@\n%droplet at (3, 3)").is_err());
+        assert!(parser.parse_string("@5\n%droplet (1, 0)").is_err());
+    }
+
+    #[test]
+    fn test_rational_directive_needs_the_rational_feature() {
+        let parser = GridParser::new();
+        let parsed = parser.parse_string("%rational\n@\nn");
+        #[cfg(feature = "rational")]
+        {
+            let grid = parsed.unwrap();
+            assert!(grid.rational_numbers);
+            assert!(grid.to_source().ends_with("%rational\n"));
+        }
+        #[cfg(not(feature = "rational"))]
+        assert!(parsed.is_err());
+        assert!(parser.parse_string("%rational please\n@\n!").is_err());
+    }
+
+    #[test]
+    fn test_cells_after_a_quote_may_hold_any_character() {
+        let parser = GridParser::new();
+        let grid = parser.parse_string("@'H\n'\ne").unwrap();
+        assert_eq!(grid.get_symbol(Coordinate::new(2, 0)), Some('H'));
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 2)), Some('e'));
+        assert!(grid.validate().is_ok());
+        // Only right of or below the quote
+        assert!(parser.parse_string("@\nH'").is_err());
+    }
+
+    #[test]
+    fn test_comments_are_ignored_and_keep_columns() {
+        let parser = GridParser::new();
+        let content = "# prints 7\n@ #[ the\nstart ]#\n7\nn   # number\n!";
+        let grid = parser.parse_string(content).unwrap();
+
+        assert_eq!(grid.size(), 4);
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 1)), Some('@'));
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 5)), Some('!'));
+        assert!(parser.parse_string("@\n#[\n!").is_err());
+    }
+
+    #[test]
+    fn test_include_stitches_file_at_offset() {
+        let dir = std::env::temp_dir().join(format!("tubular_include_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.tub"), "n\n!").unwrap();
+        std::fs::write(dir.join("main.tb"), "@\n7\n%include \"lib.tub\" at (0, 2)").unwrap();
+        std::fs::write(dir.join("loop.tub"), "%include \"loop.tub\" at (1, 1)\n|").unwrap();
+
+        let main = dir.join("main.tb");
+        let grid = GridParser::new().parse_file(main.to_str().unwrap()).unwrap();
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 2)), Some('n'));
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 3)), Some('!'));
+        let location = grid.source_location(Coordinate::new(0, 3)).unwrap();
+        assert!(location.file.ends_with("lib.tub"));
+        assert_eq!((location.line, location.column), (1, 0));
+        let position = grid.error_position(Coordinate::new(0, 3));
+        assert!(position.file.unwrap().ends_with("lib.tub"));
+        assert_eq!(grid.error_position(Coordinate::new(0, 1)).file, None);
+
+        let parser = GridParser::new();
+        let cycle = parser.parse_named("@\n%include \"loop.tub\" at (0, 1)", dir.join("p.tb").to_str().unwrap());
+        assert!(format!("{:?}", cycle.unwrap_err()).contains("Include cycle"));
+        let overlap = parser.parse_named("@\n%include \"lib.tub\" at (0, 0)", dir.join("p.tb").to_str().unwrap());
+        assert!(overlap.unwrap_err().to_string().contains("overlaps"));
+        assert!(parser.parse_string("@\n%include lib.tub\n!").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_templates_map_cells_to_their_definition() {
+        let content = "@\n%use out at (0, 2)\n\n\n%def out\nn\n!\n%end";
+        let grid = GridParser::new().parse_named(content, "prog.tb").unwrap();
+
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 2)), Some('n'));
+        assert_eq!(grid.get_symbol(Coordinate::new(0, 3)), Some('!'));
+        assert_eq!(grid.size(), 3);
+        assert_eq!(grid.source_location(Coordinate::new(0, 3)).unwrap().to_string(), "prog.tb:7:1");
+
+        // Errors in stamped cells point at the template, not the stamp
+        let error = GridParser::new().parse_named("@\n%use out at (0, 2)\n\n\n%def out\nQ\n%end", "prog.tb").unwrap_err();
+        assert_eq!(error.context().unwrap().position.line, 5);
+    }
+
+    #[test]
+    fn test_parse_all_reports_every_error_and_keeps_valid_cells() {
+        let (grid, errors) = GridParser::new().parse_all("|Q\nn\n%label at\n`!", "prog.tb");
+        let grid = grid.unwrap();
+        assert_eq!(grid.size(), 3);
+
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 4, "{:?}", messages);
+        assert!(messages[0].contains("Invalid label directive"));
+        assert!(messages[1].contains("'Q'"));
+        assert!(messages[2].contains("'`'"));
+        assert!(messages[3].contains("No start symbol"));
+        assert_eq!(errors[2].context().unwrap().position.coordinate, Coordinate::new(0, 3));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_to_text_round_trip_is_identity(
+            rows in proptest::collection::vec("[ |/\\^:;dASMD=%n,+~!0-9-]{0,12}", 0..10),
+            start_x in 0usize..12,
+            start_y in 0usize..10,
+        ) {
+            let mut rows = rows;
+            while rows.len() <= start_y {
+                rows.push(String::new());
+            }
+            let mut row: Vec<char> = rows[start_y].chars().collect();
+            while row.len() <= start_x {
+                row.push(' ');
+            }
+            row[start_x] = '@';
+            rows[start_y] = row.into_iter().collect();
+
+            let parser = GridParser::new();
+            let grid = parser.parse_string(&rows.join("\n")).unwrap();
+            let reparsed = parser.parse_string(&grid.to_text()).unwrap();
+            proptest::prop_assert_eq!(reparsed.to_text(), grid.to_text());
+            proptest::prop_assert!(reparsed == grid);
+        }
+    }
+}
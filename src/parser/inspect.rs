@@ -0,0 +1,85 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::subroutines::Label;
+use crate::parser::flow::StaticFlow;
+use crate::types::coordinate::Coordinate;
+use std::collections::BTreeMap;
+
+/// Static facts about a program, shown by `tubular inspect`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramReport {
+    pub width: usize,
+    pub height: usize,
+    /// Non-blank cells
+    pub cells: usize,
+    pub start: Option<Coordinate>,
+    /// Cells a droplet can reach from the start (see [`StaticFlow::reachable`])
+    pub reachable: usize,
+    /// Number of cells holding each symbol
+    pub histogram: BTreeMap<char, usize>,
+    /// Subroutine labels, by name
+    pub labels: Vec<(String, Label)>,
+    /// Reservoir operations (`G`, `P` and the region operations `F`, `Z`, `B`) in reading order
+    pub memory_operations: Vec<(Coordinate, char)>,
+}
+
+impl ProgramReport {
+    pub fn collect(grid: &ProgramGrid) -> Self {
+        let (width, height) = grid.dimensions();
+        let mut histogram = BTreeMap::new();
+        let mut memory_operations = Vec::new();
+        for (coord, cell) in grid.iter() {
+            *histogram.entry(cell.symbol).or_insert(0) += 1;
+            if matches!(cell.symbol, 'G' | 'P' | 'F' | 'Z' | 'B') {
+                memory_operations.push((*coord, cell.symbol));
+            }
+        }
+        memory_operations.sort_by_key(|(coord, _)| (coord.y, coord.x));
+        let mut labels: Vec<_> = grid.subroutines.labels()
+            .map(|(name, label)| (name.to_string(), *label))
+            .collect();
+        labels.sort_by(|a, b| a.0.cmp(&b.0));
+
+        ProgramReport {
+            width,
+            height,
+            cells: grid.size(),
+            start: grid.start,
+            reachable: StaticFlow::reachable(grid).len(),
+            histogram,
+            labels,
+            memory_operations,
+        }
+    }
+
+    /// Share of the cells that are reachable, as a percentage
+    pub fn reachable_percent(&self) -> f64 {
+        if self.cells == 0 {
+            0.0
+        } else {
+            self.reachable as f64 * 100.0 / self.cells as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_report_counts_symbols_reachability_and_labels() {
+        let source = "@  7\nC  R\nn\n!\n\n  G Z\n%label seven at (3, 0)\n%call seven at (0, 1)";
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let report = ProgramReport::collect(&grid);
+
+        assert_eq!((report.width, report.height), (5, 6));
+        assert_eq!(report.cells, 8);
+        assert_eq!(report.start, Some(Coordinate::new(0, 0)));
+        assert_eq!(report.histogram[&'C'], 1);
+        // Everything but the stray G and Z
+        assert_eq!(report.reachable, 6);
+        assert_eq!(report.labels[0].0, "seven");
+        assert_eq!(report.labels[0].1.entry, Coordinate::new(3, 0));
+        assert_eq!(report.memory_operations, vec![(Coordinate::new(2, 5), 'G'), (Coordinate::new(4, 5), 'Z')]);
+    }
+}
@@ -0,0 +1,947 @@
+use crate::interpreter::grid::{ProgramCell, ProgramGrid};
+use crate::parser::comments::strip_comments;
+use crate::parser::flow::StaticFlow;
+use crate::parser::formatter::GridFormatter;
+use crate::parser::grid_parser::GridParser;
+use crate::parser::preprocessor::is_directive;
+use crate::parser::stack_checker::StackChecker;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use crate::types::error::{ErrorSeverity, ErrorType, InterpreterError, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// How much `validate` checks beyond what a program needs to run
+///
+/// Each level includes the checks of the levels before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strictness {
+    /// Syntax, start symbol and subroutine wiring only
+    #[default]
+    Default,
+    /// Also deny lint rules for structural mistakes
+    Strict,
+    /// Also deny lint rules for patterns that are only suspicious
+    Pedantic,
+}
+
+impl FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        match text {
+            "default" => Ok(Strictness::Default),
+            "strict" => Ok(Strictness::Strict),
+            "pedantic" => Ok(Strictness::Pedantic),
+            _ => Err(format!("Unknown strictness '{}', expected default, strict or pedantic", text)),
+        }
+    }
+}
+
+impl fmt::Display for Strictness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Strictness::Default => "default",
+            Strictness::Strict => "strict",
+            Strictness::Pedantic => "pedantic",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What to do with a rule's findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintLevel {
+    /// Don't run the rule
+    Allow,
+    /// Report findings without failing
+    Warn,
+    /// Report findings as errors
+    Deny,
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        match text {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            _ => Err(format!("Unknown lint level '{}', expected allow, warn or deny", text)),
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warning",
+            LintLevel::Deny => "error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A mechanical edit that resolves a finding without changing what the program does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintFix {
+    /// Replace the finding's cell with a space
+    RemoveCell,
+}
+
+/// Source rewritten by `Linter::fix`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixOutcome {
+    pub source: String,
+    /// Cells removed or replaced; layout changes are not counted
+    pub fixes: usize,
+}
+
+/// One problem reported by a lint rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    pub rule: &'static str,
+    /// Level the rule ran at; set by the `Linter`
+    pub level: LintLevel,
+    /// Cell the finding points at, if it concerns a single cell
+    pub coordinate: Option<Coordinate>,
+    pub message: String,
+    pub suggestions: Vec<String>,
+    pub help: Option<String>,
+    /// Edit `Linter::fix` may apply at `coordinate`
+    pub fix: Option<LintFix>,
+}
+
+impl LintFinding {
+    pub fn new(rule: &'static str, coordinate: Option<Coordinate>, message: String) -> Self {
+        LintFinding { rule, level: LintLevel::Warn, coordinate, message, suggestions: Vec::new(), help: None, fix: None }
+    }
+
+    pub fn with_fix(mut self, fix: LintFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    /// The finding as a validation error, with severity taken from its level
+    pub fn to_error(&self) -> InterpreterError {
+        let severity = match self.level {
+            LintLevel::Deny => ErrorSeverity::Error,
+            _ => ErrorSeverity::Warning,
+        };
+        let error = InterpreterError::enhanced(self.message.clone(), ErrorType::Validation)
+            .with_severity(severity)
+            .with_suggestions(self.suggestions.clone());
+        match &self.help {
+            Some(help) => error.with_help(help.clone()),
+            None => error,
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.level, self.rule, self.message)
+    }
+}
+
+/// A named check over a program grid
+///
+/// Rules report every finding; the `Linter` decides whether they are
+/// silenced, reported as warnings or treated as errors.
+pub trait LintRule: Send + Sync {
+    /// Stable kebab-case name used on the command line and in reports
+    fn id(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    /// Lowest validation strictness that denies this rule
+    fn strictness(&self) -> Strictness {
+        Strictness::Pedantic
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding>;
+}
+
+/// Runs a set of lint rules, each at its configured level
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    levels: HashMap<&'static str, LintLevel>,
+}
+
+impl Linter {
+    /// All built-in rules at their default levels
+    pub fn new() -> Self {
+        let rules = builtin_rules();
+        let levels = rules.iter().map(|rule| (rule.id(), rule.default_level())).collect();
+        Linter { rules, levels }
+    }
+
+    /// The rules behind `validate --strict`
+    pub fn strict() -> Self {
+        Self::for_strictness(Strictness::Strict)
+    }
+
+    /// Rules assigned to `strictness` or a lower level denied, everything else allowed
+    pub fn for_strictness(strictness: Strictness) -> Self {
+        let rules = builtin_rules();
+        let levels = rules
+            .iter()
+            .map(|rule| {
+                let level = if rule.strictness() <= strictness { LintLevel::Deny } else { LintLevel::Allow };
+                (rule.id(), level)
+            })
+            .collect();
+        Linter { rules, levels }
+    }
+
+    /// Add a rule alongside the built-in ones, at its default level
+    pub fn with_rule(mut self, rule: Box<dyn LintRule>) -> Self {
+        self.levels.insert(rule.id(), rule.default_level());
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set the level of the rule named `id`, or of every rule when `id` is `all`
+    pub fn with_level(mut self, id: &str, level: LintLevel) -> std::result::Result<Self, String> {
+        if id == "all" {
+            for value in self.levels.values_mut() {
+                *value = level;
+            }
+            return Ok(self);
+        }
+        match self.rules.iter().find(|rule| rule.id() == id) {
+            Some(rule) => {
+                self.levels.insert(rule.id(), level);
+                Ok(self)
+            }
+            None => Err(format!("Unknown lint rule '{}'", id)),
+        }
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    pub fn level(&self, id: &str) -> Option<LintLevel> {
+        self.levels.get(id).copied()
+    }
+
+    /// Findings of every rule not set to allow, in rule order then by row and column
+    pub fn run(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+        for rule in &self.rules {
+            let level = self.level(rule.id()).unwrap_or(LintLevel::Allow);
+            if level == LintLevel::Allow {
+                continue;
+            }
+            let mut rule_findings = rule.check(grid);
+            rule_findings.sort_by_key(|finding| finding.coordinate.map(|c| (c.y, c.x)));
+            findings.extend(rule_findings.into_iter().map(|finding| LintFinding { level, ..finding }));
+        }
+        findings
+    }
+}
+
+impl Linter {
+    /// Apply every safe fix to `source` until none are left
+    ///
+    /// Invalid characters outside comments and directives become spaces, cells
+    /// whose findings carry a fix are edited, and the result is laid out by
+    /// `GridFormatter`. Rules set to allow are not fixed. Every remaining cell
+    /// keeps its coordinate.
+    pub fn fix(&self, source: &str) -> Result<FixOutcome> {
+        let mut lines: Vec<Vec<char>> = source.lines().map(|line| line.chars().collect()).collect();
+        // Comments are blank here, so only characters in code are fixed
+        let code = strip_comments(source)?;
+        let code: Vec<Vec<char>> = code.lines().map(|line| line.chars().collect()).collect();
+        // Characters right of or below a `'` are data
+        let quoted = |x: usize, y: usize| {
+            (x > 0 && code[y][x - 1] == '\'') || (y > 0 && code[y - 1].get(x) == Some(&'\''))
+        };
+        let mut fixes = 0;
+        for (y, (line, code_line)) in lines.iter_mut().zip(&code).enumerate() {
+            if is_directive(&code_line.iter().collect::<String>()) {
+                continue;
+            }
+            for (x, (ch, &code_ch)) in line.iter_mut().zip(code_line).enumerate() {
+                if !code_ch.is_whitespace() && !ProgramCell::is_valid_symbol(code_ch) && !quoted(x, y) {
+                    *ch = ' ';
+                    fixes += 1;
+                }
+            }
+        }
+
+        loop {
+            let text = join_lines(&lines);
+            let grid = GridParser::new().parse_string(&text)?;
+            let edits: Vec<Coordinate> = self
+                .run(&grid)
+                .into_iter()
+                .filter(|finding| finding.fix == Some(LintFix::RemoveCell))
+                .filter_map(|finding| finding.coordinate)
+                // Cells stamped from templates or included files are not at their coordinate in `text`
+                .filter(|&coord| grid.source_location(coord).is_some_and(|location| {
+                    location.file == "<string>" && (location.column, location.line) == (coord.x as usize, coord.y as usize)
+                }))
+                .collect();
+
+            let mut edited = false;
+            for coord in edits {
+                if let Some(ch) = lines.get_mut(coord.y as usize).and_then(|line| line.get_mut(coord.x as usize)) {
+                    *ch = ' ';
+                    fixes += 1;
+                    edited = true;
+                }
+            }
+            if !edited {
+                return Ok(FixOutcome { source: GridFormatter::new().format(&text)?, fixes });
+            }
+        }
+    }
+}
+
+fn join_lines(lines: &[Vec<char>]) -> String {
+    lines.iter().map(|line| line.iter().collect::<String>() + "\n").collect()
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn builtin_rules() -> Vec<Box<dyn LintRule>> {
+    vec![
+        Box::new(StartAtEdge),
+        Box::new(PipeConnection),
+        Box::new(StackOpWithoutData),
+        Box::new(ArithmeticWithoutOperands),
+        Box::new(UnreachableCell),
+        Box::new(OrphanedPipe),
+        Box::new(OutputWithoutUpstream),
+        Box::new(DeadEndInput),
+        Box::new(MemoryWithoutCoordinates),
+        Box::new(StackUnderflow),
+        Box::new(SuspiciousCollision),
+        Box::new(UnusedMemoryWrite),
+    ]
+}
+
+fn neighbours(coord: Coordinate) -> [Coordinate; 4] {
+    [
+        Coordinate::new(coord.x - 1, coord.y),
+        Coordinate::new(coord.x + 1, coord.y),
+        Coordinate::new(coord.x, coord.y - 1),
+        Coordinate::new(coord.x, coord.y + 1),
+    ]
+}
+
+fn has_neighbour(grid: &ProgramGrid, coord: Coordinate, accepts: impl Fn(char) -> bool) -> bool {
+    neighbours(coord).into_iter().any(|n| grid.get_symbol(n).is_some_and(&accepts))
+}
+
+/// Reachable cells and the neighbours flow arrives from and leaves to, following `StaticFlow`
+#[derive(Default)]
+struct FlowGraph {
+    predecessors: HashMap<Coordinate, HashSet<Coordinate>>,
+    successors: HashMap<Coordinate, HashSet<Coordinate>>,
+}
+
+impl FlowGraph {
+    fn build(grid: &ProgramGrid) -> Self {
+        let mut graph = FlowGraph::default();
+        let mut visited: HashSet<(Coordinate, Direction)> = HashSet::new();
+        let mut worklist: VecDeque<(Coordinate, Direction)> = StaticFlow::entries(grid).into_iter().collect();
+
+        while let Some((coord, direction)) = worklist.pop_front() {
+            if grid.get(coord).is_none() || !visited.insert((coord, direction)) {
+                continue;
+            }
+            graph.predecessors.entry(coord).or_default();
+            for (next, heading) in StaticFlow::successors(grid, coord, direction) {
+                graph.successors.entry(coord).or_default().insert(next);
+                graph.predecessors.entry(next).or_default().insert(coord);
+                worklist.push_back((next, heading));
+            }
+        }
+
+        graph.predecessors.retain(|coord, _| grid.get(*coord).is_some());
+        graph
+    }
+
+    fn is_reachable(&self, coord: Coordinate) -> bool {
+        self.predecessors.contains_key(&coord)
+    }
+
+    /// Whether flow enters `coord` from a neighbour it never flows back to
+    ///
+    /// A path walked in both directions, as after a `\` reverses a droplet,
+    /// enters each cell only from cells it also leaves to.
+    fn is_merge(&self, coord: Coordinate) -> bool {
+        let predecessors = &self.predecessors[&coord];
+        let successors = self.successors.get(&coord);
+        predecessors.len() > 1
+            && predecessors.iter().any(|p| !successors.is_some_and(|s| s.contains(p)))
+    }
+}
+
+/// The start symbol sits on the top row or left column
+pub struct StartAtEdge;
+
+impl LintRule for StartAtEdge {
+    fn id(&self) -> &'static str {
+        "start-at-edge"
+    }
+
+    fn description(&self) -> &'static str {
+        "start symbol on the top row or left column"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Allow
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        match grid.start {
+            Some(start) if start.x == 0 || start.y == 0 => vec![
+                LintFinding::new(self.id(), Some(start), "Start symbol '@' is placed at the edge".to_string())
+                    .with_suggestions(vec![
+                        "Move the start symbol away from the edge".to_string(),
+                        "Add at least one space margin around the start symbol".to_string(),
+                    ])
+                    .with_help("The start symbol should have at least one cell of space around it to ensure proper flow.".to_string()),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Directional pipes and corners with nothing on the sides they connect
+pub struct PipeConnection;
+
+impl PipeConnection {
+    fn is_unconnected(grid: &ProgramGrid, coord: Coordinate, symbol: char) -> bool {
+        let present = |direction: Direction| grid.get(coord + direction).is_some();
+        match symbol {
+            '^' | 'v' => !present(Direction::Up) && !present(Direction::Down),
+            '<' | '>' => !present(Direction::Left) && !present(Direction::Right),
+            '/' => ![(Direction::Right, Direction::Up), (Direction::Down, Direction::Left)]
+                .iter()
+                .any(|(from, to)| grid.get(coord - *from).is_some() || present(*to)),
+            '\\' => ![(Direction::Right, Direction::Down), (Direction::Up, Direction::Left)]
+                .iter()
+                .any(|(from, to)| grid.get(coord - *from).is_some() || present(*to)),
+            _ => false,
+        }
+    }
+
+    fn suggestions(grid: &ProgramGrid, coord: Coordinate, symbol: char) -> Vec<String> {
+        let present = |direction: Direction| grid.get(coord + direction).is_some();
+        match symbol {
+            '^' | 'v' => match (present(Direction::Up), present(Direction::Down)) {
+                (false, false) => vec![
+                    "Add a cell above or below this vertical pipe".to_string(),
+                    "Use '|' for bidirectional vertical flow".to_string(),
+                ],
+                (false, true) => vec!["Add a cell above to complete the vertical connection".to_string()],
+                (true, false) => vec!["Add a cell below to complete the vertical connection".to_string()],
+                (true, true) => Vec::new(),
+            },
+            '<' | '>' => match (present(Direction::Left), present(Direction::Right)) {
+                (false, false) => vec![
+                    "Add a cell to the left or right of this horizontal pipe".to_string(),
+                    "Use '-' for bidirectional horizontal flow".to_string(),
+                ],
+                (false, true) => vec!["Add a cell to the left to complete the horizontal connection".to_string()],
+                (true, false) => vec!["Add a cell to the right to complete the horizontal connection".to_string()],
+                (true, true) => Vec::new(),
+            },
+            _ => vec![
+                "Ensure corner pipes have proper connections on their valid sides".to_string(),
+                "Check that adjacent cells align with the corner's direction".to_string(),
+            ],
+        }
+    }
+
+    fn help(symbol: char) -> String {
+        match symbol {
+            '^' => "This pipe directs flow upward. It should have at least one connection (above or below).",
+            'v' => "This pipe directs flow downward. It should have at least one connection (above or below).",
+            '<' => "This pipe directs flow leftward. It should have at least one connection (left or right).",
+            '>' => "This pipe directs flow rightward. It should have at least one connection (left or right).",
+            '/' => "This corner pipe redirects flow from right to up or from down to left.",
+            _ => "This corner pipe redirects flow from left to down or from up to right.",
+        }
+        .to_string()
+    }
+}
+
+impl LintRule for PipeConnection {
+    fn id(&self) -> &'static str {
+        "pipe-connection"
+    }
+
+    fn description(&self) -> &'static str {
+        "directional pipe or corner with no cell on the sides it connects"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        grid.iter()
+            .filter(|(coord, cell)| Self::is_unconnected(grid, **coord, cell.symbol))
+            .map(|(coord, cell)| {
+                LintFinding::new(self.id(), Some(*coord), format!("Invalid flow control pipe '{}' at this position", cell.symbol))
+                    .with_suggestions(Self::suggestions(grid, *coord, cell.symbol))
+                    .with_help(Self::help(cell.symbol))
+            })
+            .collect()
+    }
+}
+
+/// `:`, `;` and `d` with no data source beside them
+pub struct StackOpWithoutData;
+
+impl LintRule for StackOpWithoutData {
+    fn id(&self) -> &'static str {
+        "stack-op-without-data"
+    }
+
+    fn description(&self) -> &'static str {
+        "stack operation with no data source next to it"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        grid.iter()
+            .filter(|(coord, cell)| {
+                matches!(cell.symbol, ':' | ';' | 'd')
+                    && !has_neighbour(grid, **coord, |s| ProgramCell::is_data_source(s) || s == ':')
+            })
+            .map(|(coord, cell)| {
+                LintFinding::new(self.id(), Some(*coord), format!("Stack operation '{}' has no access to data", cell.symbol))
+                    .with_suggestions(vec![
+                        "Add a data source nearby (numbers, input symbol '?', or other stack operations)".to_string(),
+                        "Move the operation closer to data flow".to_string(),
+                    ])
+                    .with_help("Stack operations need access to data to function properly. Place them near data sources or in the flow of operations.".to_string())
+            })
+            .collect()
+    }
+}
+
+/// `A`, `S`, `M` and `D` with no operand source beside them
+pub struct ArithmeticWithoutOperands;
+
+impl LintRule for ArithmeticWithoutOperands {
+    fn id(&self) -> &'static str {
+        "arithmetic-without-operands"
+    }
+
+    fn description(&self) -> &'static str {
+        "arithmetic operation with no operand source next to it"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        grid.iter()
+            .filter(|(coord, cell)| {
+                matches!(cell.symbol, 'A' | 'S' | 'M' | 'D')
+                    && !has_neighbour(grid, **coord, |s| ProgramCell::is_data_source(s) || matches!(s, ':' | ';'))
+            })
+            .map(|(coord, cell)| {
+                let operation_name = match cell.symbol {
+                    'A' => "Addition",
+                    'S' => "Subtraction",
+                    'M' => "Multiplication",
+                    _ => "Division",
+                };
+                LintFinding::new(
+                    self.id(),
+                    Some(*coord),
+                    format!("{} operation '{}' has no operands available", operation_name, cell.symbol),
+                )
+                .with_suggestions(vec![
+                    "Add numeric literals (0-9) nearby".to_string(),
+                    "Ensure data flow reaches this operation".to_string(),
+                    "Add input operations or other data sources upstream".to_string(),
+                ])
+                .with_help("Arithmetic operations need at least one operand to work with. Make sure there's data flowing into this operation.".to_string())
+            })
+            .collect()
+    }
+}
+
+/// Cells no droplet can reach from the start symbol
+pub struct UnreachableCell;
+
+impl LintRule for UnreachableCell {
+    fn id(&self) -> &'static str {
+        "unreachable-cell"
+    }
+
+    fn description(&self) -> &'static str {
+        "cell no droplet can reach from the start symbol"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        if grid.start.is_none() {
+            return Vec::new();
+        }
+        let flow = FlowGraph::build(grid);
+        grid.iter()
+            .filter(|(coord, _)| !flow.is_reachable(**coord) && !grid.is_quoted(**coord))
+            .map(|(coord, cell)| {
+                LintFinding::new(self.id(), Some(*coord), format!("'{}' is unreachable", cell.symbol))
+                    .with_suggestions(vec![
+                        "Remove unused code cells".to_string(),
+                        "Connect unreachable code to the main flow".to_string(),
+                        "Add flow control to reach the isolated cells".to_string(),
+                    ])
+                    .with_help("Unreachable code cannot be executed and may indicate a bug in your program logic or missing flow connections.".to_string())
+            })
+            .collect()
+    }
+}
+
+/// Flow control pipes with no neighbouring cell at all
+pub struct OrphanedPipe;
+
+impl LintRule for OrphanedPipe {
+    fn id(&self) -> &'static str {
+        "orphaned-pipe"
+    }
+
+    fn description(&self) -> &'static str {
+        "flow control pipe with no connected neighbours"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        let connects = |s: char| {
+            ProgramCell::is_flow_control_symbol(s) || ProgramCell::is_operator_symbol(s) || ProgramCell::is_start_symbol(s)
+        };
+        grid.iter()
+            .filter(|(coord, cell)| ProgramCell::is_flow_control_symbol(cell.symbol) && !has_neighbour(grid, **coord, connects))
+            .map(|(coord, cell)| {
+                LintFinding::new(self.id(), Some(*coord), format!("Orphaned flow control pipe '{}' has no connections", cell.symbol))
+                    .with_fix(LintFix::RemoveCell)
+                    .with_suggestions(vec![
+                        "Add adjacent cells to connect this pipe".to_string(),
+                        "Remove the orphaned pipe if not needed".to_string(),
+                        "Check for gaps in your flow control network".to_string(),
+                    ])
+                    .with_help("Flow control pipes need to be connected to other cells to be useful. An orphaned pipe cannot guide droplets anywhere.".to_string())
+            })
+            .collect()
+    }
+}
+
+/// Output operations with no cell above them
+pub struct OutputWithoutUpstream;
+
+impl LintRule for OutputWithoutUpstream {
+    fn id(&self) -> &'static str {
+        "output-without-upstream"
+    }
+
+    fn description(&self) -> &'static str {
+        "output operation with no cell above it"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        grid.iter()
+            .filter(|(coord, cell)| matches!(cell.symbol, ',' | 'n') && grid.get(**coord + Direction::Up).is_none())
+            .map(|(coord, cell)| {
+                LintFinding::new(self.id(), Some(*coord), format!("Output operation '{}' has no upstream connection", cell.symbol))
+                    .with_suggestions(vec![
+                        "Add flow control leading to this output operation".to_string(),
+                        "Place the output operation in the main flow path".to_string(),
+                        "Connect this operation to upstream cells".to_string(),
+                    ])
+                    .with_help("Output operations need to be reachable by droplets to function. Ensure there's a path for droplets to reach this cell.".to_string())
+            })
+            .collect()
+    }
+}
+
+/// Input operations with no neighbouring cell to pass the value on to
+pub struct DeadEndInput;
+
+impl LintRule for DeadEndInput {
+    fn id(&self) -> &'static str {
+        "dead-end-input"
+    }
+
+    fn description(&self) -> &'static str {
+        "input operation with nowhere for the value to go"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        grid.iter()
+            .filter(|(coord, cell)| cell.symbol == '?' && !has_neighbour(grid, **coord, |_| true))
+            .map(|(coord, _)| {
+                LintFinding::new(self.id(), Some(*coord), "Input operation '?' is at a dead end".to_string())
+                    .with_suggestions(vec![
+                        "Add flow control after the input operation".to_string(),
+                        "Connect the input operation to downstream cells".to_string(),
+                        "Ensure input can flow to the rest of the program".to_string(),
+                    ])
+                    .with_help("Input operations should provide data to the rest of the program. If it's at a dead end, the input data won't be used.".to_string())
+            })
+            .collect()
+    }
+}
+
+/// `G` and `P` with nothing beside them that could supply coordinates
+pub struct MemoryWithoutCoordinates;
+
+impl LintRule for MemoryWithoutCoordinates {
+    fn id(&self) -> &'static str {
+        "memory-without-coordinates"
+    }
+
+    fn description(&self) -> &'static str {
+        "reservoir operation with no coordinate source next to it"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        grid.iter()
+            .filter(|(coord, cell)| {
+                matches!(cell.symbol, 'G' | 'P') && !has_neighbour(grid, **coord, |s| matches!(s, ':' | ';' | 'd' | '0'..='9'))
+            })
+            .map(|(coord, cell)| {
+                let operation_name = if cell.symbol == 'G' { "Get (read)" } else { "Put (write)" };
+                LintFinding::new(
+                    self.id(),
+                    Some(*coord),
+                    format!("{} memory operation '{}' cannot access stack coordinates", operation_name, cell.symbol),
+                )
+                .with_suggestions(vec![
+                    "Add stack operations nearby to provide coordinates".to_string(),
+                    "Place numeric literals for direct addressing".to_string(),
+                    "Move the operation closer to coordinate sources".to_string(),
+                ])
+                .with_help("Memory operations need coordinates from the stack or direct addressing. Ensure there's a way to provide coordinates to this operation.".to_string())
+            })
+            .collect()
+    }
+}
+
+/// Reachable stack operations that can pop more values than were pushed
+pub struct StackUnderflow;
+
+impl LintRule for StackUnderflow {
+    fn id(&self) -> &'static str {
+        "stack-underflow"
+    }
+
+    fn description(&self) -> &'static str {
+        "stack operation reachable with too few values on the stack"
+    }
+
+    fn strictness(&self) -> Strictness {
+        Strictness::Strict
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        StackChecker::check(grid)
+            .into_iter()
+            .map(|diagnostic| {
+                let error = diagnostic.to_error();
+                let finding = LintFinding::new(self.id(), Some(diagnostic.coordinate), diagnostic.to_string())
+                    .with_suggestions(error.suggestions().to_vec());
+                match error.help_text() {
+                    Some(help) => finding.with_help(help.clone()),
+                    None => finding,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Cells where flows from different sides meet
+///
+/// Droplets arriving from two sides on the same tick collide and are
+/// destroyed. A path doubling back on itself through a `\` is not reported.
+pub struct SuspiciousCollision;
+
+impl LintRule for SuspiciousCollision {
+    fn id(&self) -> &'static str {
+        "suspicious-collision"
+    }
+
+    fn description(&self) -> &'static str {
+        "cell entered from two different sides, where droplets can collide"
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        let flow = FlowGraph::build(grid);
+        flow.predecessors
+            .keys()
+            .filter(|coord| flow.is_merge(**coord))
+            .filter_map(|coord| {
+                let symbol = grid.get_symbol(*coord)?;
+                let mut sources: Vec<Coordinate> = flow.predecessors[coord].iter().copied().collect();
+                sources.sort_by_key(|c| (c.y, c.x));
+                let sources: Vec<String> = sources.iter().map(|c| c.to_string()).collect();
+                Some(
+                    LintFinding::new(
+                        self.id(),
+                        Some(*coord),
+                        format!("Flows from {} meet at '{}'", sources.join(", "), symbol),
+                    )
+                    .with_suggestions(vec![
+                        "Make sure droplets on these paths can never arrive on the same tick".to_string(),
+                        "Route one path around the shared cell".to_string(),
+                    ])
+                    .with_help("Droplets that move onto the same cell in one tick collide and are both destroyed.".to_string()),
+                )
+            })
+            .collect()
+    }
+}
+
+/// `P` in a program with no `G`, so stored values are never read back
+pub struct UnusedMemoryWrite;
+
+impl LintRule for UnusedMemoryWrite {
+    fn id(&self) -> &'static str {
+        "unused-memory-write"
+    }
+
+    fn description(&self) -> &'static str {
+        "reservoir write in a program that never reads the reservoir"
+    }
+
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+        if grid.iter().any(|(_, cell)| cell.symbol == 'G') {
+            return Vec::new();
+        }
+        grid.iter()
+            .filter(|(_, cell)| cell.symbol == 'P')
+            .map(|(coord, _)| {
+                LintFinding::new(self.id(), Some(*coord), "Reservoir write 'P' is never read".to_string())
+                    .with_suggestions(vec![
+                        "Read the value back with 'G'".to_string(),
+                        "Remove the write if the value is not needed".to_string(),
+                    ])
+                    .with_help("The program has no 'G', so values stored in the reservoir can never affect its output.".to_string())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    fn lint(linter: &Linter, source: &str) -> Vec<(&'static str, Option<Coordinate>)> {
+        let grid = GridParser::new().parse_string(source).unwrap();
+        linter.run(&grid).into_iter().map(|finding| (finding.rule, finding.coordinate)).collect()
+    }
+
+    #[test]
+    fn test_default_rules_report_unreachable_cells_and_unused_writes() {
+        let findings = lint(&Linter::new(), "@\n1\n!\n\n2 P");
+        assert!(findings.contains(&("unreachable-cell", Some(Coordinate::new(0, 4)))));
+        assert!(findings.contains(&("unused-memory-write", Some(Coordinate::new(2, 4)))));
+        assert!(!findings.iter().any(|(rule, _)| *rule == "start-at-edge"));
+    }
+
+    #[test]
+    fn test_levels_can_be_overridden() {
+        let linter = Linter::new()
+            .with_level("all", LintLevel::Allow).unwrap()
+            .with_level("start-at-edge", LintLevel::Deny).unwrap();
+        let grid = GridParser::new().parse_string("@\n!").unwrap();
+        let findings = linter.run(&grid);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].level, LintLevel::Deny);
+        assert_eq!(findings[0].to_error().severity(), ErrorSeverity::Error);
+
+        assert!(Linter::new().with_level("no-such-rule", LintLevel::Deny).is_err());
+    }
+
+    #[test]
+    fn test_merging_flows_are_suspicious_but_reversals_are_not() {
+        let linter = Linter::new().with_level("all", LintLevel::Allow).unwrap()
+            .with_level("suspicious-collision", LintLevel::Warn).unwrap();
+        assert!(lint(&linter, "@\n|\n\\\n").is_empty());
+
+        // The branch taken on non-zero loops back into the main line from the right
+        let loop_back = "@\n|\n|\\\n\\/\n";
+        assert_eq!(lint(&linter, loop_back), vec![("suspicious-collision", Some(Coordinate::new(0, 2)))]);
+    }
+
+    #[test]
+    fn test_fix_blanks_invalid_characters_and_orphaned_pipes() {
+        let outcome = Linter::new().fix("@\t Q\n1\n!   \n\n  |\n").unwrap();
+        assert_eq!(outcome.source, "@\n1\n!\n");
+        assert_eq!(outcome.fixes, 2);
+
+        let allowed = Linter::new().with_level("orphaned-pipe", LintLevel::Allow).unwrap();
+        assert_eq!(allowed.fix("@\n!\n\n|\n").unwrap().source, "@\n!\n\n|\n");
+
+        let commented = Linter::new().fix("@ # Q marks
+Q
+!
+").unwrap();
+        assert_eq!(commented.source, "@ # Q marks
+
+!
+");
+        assert_eq!(commented.fixes, 1);
+        let templated = Linter::new().fix("@\n%use out at (0, 1)\n%def out\nn\n!\n%end\n").unwrap();
+        assert_eq!(templated.fixes, 0);
+    }
+}
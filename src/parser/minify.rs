@@ -0,0 +1,110 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::subroutines::Label;
+use crate::parser::flow::StaticFlow;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use std::collections::HashSet;
+
+/// Smallest layout of `grid` that runs the same way, shown by `tubular minify`
+///
+/// The program is anchored at (0, 0) and every blank row and column no
+/// droplet path enters is dropped; a droplet that does enter one is destroyed
+/// there, so those stay. Droplets leaving the grid are assumed to be
+/// destroyed, as they are without `--wrap`. Labels, calls and `%droplet` starts move with their cells. Fails if a
+/// `C` is not bound by `%call`, since unbound calls jump to coordinates
+/// computed at run time.
+pub fn minify(grid: &ProgramGrid) -> Result<ProgramGrid, String> {
+    let mut unbound: Vec<_> = grid.iter()
+        .filter(|(coord, cell)| cell.symbol == 'C' && grid.subroutines.call_label(**coord).is_none())
+        .map(|(coord, _)| *coord)
+        .collect();
+    if !unbound.is_empty() {
+        unbound.sort_by_key(|coord| (coord.y, coord.x));
+        let sites: Vec<_> = unbound.iter().map(ToString::to_string).collect();
+        return Err(format!("cannot move cells of a program with unbound calls: 'C' at {}", sites.join(", ")));
+    }
+    if grid.cells.is_empty() {
+        return Ok(ProgramGrid::new());
+    }
+
+    let entered = entered_cells(grid);
+    let rows: Vec<isize> = (grid.bounds.min_y..=grid.bounds.max_y)
+        .filter(|&y| grid.iter().any(|(coord, _)| coord.y == y) || entered.iter().any(|coord| coord.y == y))
+        .collect();
+    let columns: Vec<isize> = (grid.bounds.min_x..=grid.bounds.max_x)
+        .filter(|&x| grid.iter().any(|(coord, _)| coord.x == x) || entered.iter().any(|coord| coord.x == x))
+        .collect();
+    // Kept rows and columns are sorted, so a cell's index in them is its new position
+    let place = |coord: Coordinate| Coordinate::new(
+        columns.binary_search(&coord.x).unwrap_or_default() as isize,
+        rows.binary_search(&coord.y).unwrap_or_default() as isize,
+    );
+
+    let mut minified = ProgramGrid::new();
+    for (coord, cell) in grid.iter() {
+        minified.add_cell(place(*coord), cell.symbol).map_err(|e| e.to_string())?;
+    }
+    for (name, label) in grid.subroutines.labels() {
+        minified.subroutines.define_label(name, Label { entry: place(label.entry), ..*label });
+    }
+    for (site, name) in grid.subroutines.calls() {
+        minified.subroutines.bind_call(place(site), name);
+    }
+    for (coord, name) in grid.portals.portals() {
+        minified.portals.bind(place(coord), name);
+    }
+    minified.start_direction = grid.start_direction;
+    minified.droplets = grid.droplets.iter().map(|(coord, direction)| (place(*coord), *direction)).collect();
+    minified.multi_digit_literals = grid.multi_digit_literals;
+    minified.rational_numbers = grid.rational_numbers;
+    minified.collision_policy = grid.collision_policy;
+    minified.eof_policy = grid.eof_policy;
+    Ok(minified)
+}
+
+/// Coordinates inside the grid's bounds that a droplet can move onto, blank ones included
+fn entered_cells(grid: &ProgramGrid) -> HashSet<Coordinate> {
+    let mut entered = HashSet::new();
+    let mut visited: HashSet<(Coordinate, Direction)> = HashSet::new();
+    let mut worklist = StaticFlow::entries(grid);
+    while let Some((coord, direction)) = worklist.pop() {
+        if grid.bounds.contains(coord) {
+            entered.insert(coord);
+        }
+        if grid.get(coord).is_none() || !visited.insert((coord, direction)) {
+            continue;
+        }
+        // The cell a `'` reads has to stay next to it, even when blank
+        if grid.get_symbol(coord) == Some('\'') && grid.bounds.contains(coord + direction) {
+            entered.insert(coord + direction);
+        }
+        worklist.extend(StaticFlow::successors(grid, coord, direction));
+    }
+    entered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_blank_rows_and_columns_no_droplet_enters_are_dropped() {
+        let source = "\n\n   @    7\n   C    R\n   n\n   !\n\n\n   G\n%label seven at (8, 2)\n%call seven at (3, 3)";
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let minified = minify(&grid).unwrap();
+
+        assert_eq!(minified.to_source(), "@7\nCR\nn\n!\nG\n%label seven at (1, 0) heading down\n%call seven at (0, 1)\n");
+        assert_eq!(minified.size(), grid.size());
+    }
+
+    #[test]
+    fn test_blank_cells_that_destroy_droplets_are_kept() {
+        // The droplet dies on the blank cell below the 7; closing the gap would reach the n
+        let grid = GridParser::new().parse_string("@\n7\n\nn\n!").unwrap();
+        assert_eq!(minify(&grid).unwrap().to_text(), "@\n7\n\nn\n!\n");
+
+        let dynamic = GridParser::new().parse_string("@\nC\n!").unwrap();
+        assert!(minify(&dynamic).unwrap_err().contains("'C' at (0, 1)"));
+    }
+}
@@ -1,5 +1,43 @@
 pub mod grid_parser;
+pub mod comments;
+pub mod preprocessor;
+#[cfg(feature = "std")]
 pub mod validator;
+pub mod flow;
+#[cfg(feature = "std")]
+pub mod flow_graph;
+#[cfg(feature = "std")]
+pub mod stack_checker;
+#[cfg(feature = "std")]
+pub mod formatter;
+#[cfg(feature = "std")]
+pub mod lint;
+#[cfg(feature = "std")]
+pub mod inspect;
+pub mod symbol_map;
+#[cfg(feature = "std")]
+pub mod transform;
+#[cfg(feature = "std")]
+pub mod minify;
 
 pub use grid_parser::*;
-pub use validator::*;
\ No newline at end of file
+pub use comments::*;
+pub use preprocessor::*;
+#[cfg(feature = "std")]
+pub use validator::*;
+pub use flow::*;
+#[cfg(feature = "std")]
+pub use flow_graph::*;
+#[cfg(feature = "std")]
+pub use stack_checker::*;
+#[cfg(feature = "std")]
+pub use formatter::*;
+#[cfg(feature = "std")]
+pub use lint::*;
+#[cfg(feature = "std")]
+pub use inspect::*;
+pub use symbol_map::*;
+#[cfg(feature = "std")]
+pub use transform::*;
+#[cfg(feature = "std")]
+pub use minify::*;
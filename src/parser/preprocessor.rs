@@ -0,0 +1,175 @@
+use crate::types::coordinate::Coordinate;
+use crate::types::error::{ErrorContext, ErrorType, InterpreterError, Position, Result};
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+
+/// Program text after `%def`/`%use` templates have been expanded
+///
+/// Directive lines and definitions are blanked rather than removed, so every
+/// cell outside a template keeps its line and column; stamped cells remember
+/// where in the definition they came from.
+#[derive(Debug, Clone, Default)]
+pub struct Preprocessed {
+    pub text: String,
+    /// Source (line, column) of each stamped cell, by grid coordinate
+    origins: BTreeMap<Coordinate, (usize, usize)>,
+}
+
+impl Preprocessed {
+    /// Zero-based source (line, column) of the cell at `coord`
+    pub fn origin(&self, coord: Coordinate) -> (usize, usize) {
+        self.origins.get(&coord).copied().unwrap_or((coord.y as usize, coord.x as usize))
+    }
+}
+
+/// Named grid snippet from a `%def` block
+#[derive(Debug, Clone)]
+struct Template {
+    /// Line of the first body row in the source
+    first_line: usize,
+    rows: Vec<Vec<char>>,
+}
+
+/// Expand templates in `source`
+///
+/// `%def name` starts a snippet that runs until `%end`; `%use name at (x, y)`
+/// stamps it with its top-left corner at column `x`, row `y`. A snippet may
+/// be used before its definition and any number of times, but stamped cells
+/// must not land on other cells. Sources without directives come back unchanged.
+pub fn preprocess(source: &str) -> Result<Preprocessed> {
+    let mut rows: Vec<Vec<char>> = source.lines().map(|line| line.chars().collect()).collect();
+    let mut templates: BTreeMap<String, Template> = BTreeMap::new();
+    let mut uses: Vec<(usize, String, Coordinate)> = Vec::new();
+    // Name and line of the open `%def`
+    let mut open: Option<(String, usize)> = None;
+
+    for (y, line) in source.lines().enumerate() {
+        let directive = line.trim();
+        if let Some((name, start)) = &open {
+            if directive == "%end" {
+                let start = *start;
+                let body = rows[start + 1..y].to_vec();
+                templates.insert(name.clone(), Template { first_line: start + 1, rows: body });
+                open = None;
+                blank(&mut rows[start + 1..=y]);
+            } else if is_directive(directive) {
+                return Err(directive_error(source, y, format!("'{}' is not allowed inside %def {}", directive, name)));
+            }
+            continue;
+        }
+
+        if let Some(name) = directive.strip_prefix("%def") {
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(directive_error(source, y, format!("Invalid template definition: {}", directive)));
+            }
+            if templates.contains_key(name) {
+                return Err(directive_error(source, y, format!("Template '{}' is defined twice", name)));
+            }
+            open = Some((name.to_string(), y));
+            blank(&mut rows[y..=y]);
+        } else if let Some(rest) = directive.strip_prefix("%use") {
+            let parsed = rest.trim_start().split_once(char::is_whitespace)
+                .and_then(|(name, at)| Some((name.to_string(), parse_offset(at)?)));
+            let Some((name, offset)) = parsed else {
+                return Err(directive_error(source, y, format!("Invalid template use: {}", directive)));
+            };
+            uses.push((y, name, offset));
+            blank(&mut rows[y..=y]);
+        } else if directive == "%end" {
+            return Err(directive_error(source, y, "%end without a matching %def".to_string()));
+        }
+    }
+    if let Some((name, start)) = open {
+        return Err(directive_error(source, start, format!("Template '{}' is missing %end", name)));
+    }
+    if uses.is_empty() && templates.is_empty() {
+        return Ok(Preprocessed { text: source.to_string(), origins: BTreeMap::new() });
+    }
+
+    let mut origins = BTreeMap::new();
+    for (line, name, offset) in uses {
+        let Some(template) = templates.get(&name) else {
+            return Err(directive_error(source, line, format!("Unknown template '{}'", name)));
+        };
+        for (dy, row) in template.rows.iter().enumerate() {
+            for (dx, &ch) in row.iter().enumerate() {
+                if ch.is_whitespace() {
+                    continue;
+                }
+                let target = Coordinate::new(offset.x + dx as isize, offset.y + dy as isize);
+                if target.x < 0 || target.y < 0 {
+                    return Err(directive_error(source, line, format!("Template '{}' at {} leaves the grid", name, offset)));
+                }
+                let (x, y) = (target.x as usize, target.y as usize);
+                if rows.len() <= y {
+                    rows.resize(y + 1, Vec::new());
+                }
+                if rows[y].len() <= x {
+                    rows[y].resize(x + 1, ' ');
+                }
+                if !rows[y][x].is_whitespace() {
+                    return Err(directive_error(source, line, format!("Template '{}' overlaps the program at {}", name, target)));
+                }
+                rows[y][x] = ch;
+                origins.insert(target, (template.first_line + dy, dx));
+            }
+        }
+    }
+
+    let text = rows.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n");
+    Ok(Preprocessed { text, origins })
+}
+
+/// Offset written as `at (x, y)`
+pub(crate) fn parse_offset(text: &str) -> Option<Coordinate> {
+    let rest = text.trim().strip_prefix("at")?;
+    let rest = rest.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let (x, y) = rest.split_once(',')?;
+    Some(Coordinate::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Whether `line` is a preprocessor or include directive
+pub(crate) fn is_directive(line: &str) -> bool {
+    ["%def", "%use", "%end", "%include", "%label", "%call", "%portal", "%start", "%droplet", "%multidigit", "%rational", "%collisions", "%eof"].iter().any(|prefix| line.trim_start().starts_with(prefix))
+}
+
+/// Empty directive and definition lines, keeping the line count
+fn blank(rows: &mut [Vec<char>]) {
+    for row in rows {
+        row.clear();
+    }
+}
+
+fn directive_error(source: &str, line: usize, message: String) -> InterpreterError {
+    let text = source.lines().nth(line).unwrap_or("");
+    let column = text.len() - text.trim_start().len();
+    InterpreterError::enhanced(message, ErrorType::Syntax).with_context(ErrorContext::new(
+        Position::new(line, column, Coordinate::new(column as isize, line as isize)),
+        text.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_are_stamped_with_origins() {
+        let source = "@\n%use out at (0, 4)\n7\n%def out\nn\n%end\n";
+        let expanded = preprocess(source).unwrap();
+
+        assert_eq!(expanded.text, "@\n\n7\n\nn\n");
+        assert_eq!(expanded.origin(Coordinate::new(0, 4)), (4, 0));
+        assert_eq!(expanded.origin(Coordinate::new(0, 2)), (2, 0));
+        assert_eq!(preprocess("@\n7\nn").unwrap().text, "@\n7\nn");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert!(preprocess("@\n%use missing at (1, 1)").unwrap_err().to_string().contains("Unknown template"));
+        assert!(preprocess("%def a\n|\n").unwrap_err().to_string().contains("missing %end"));
+        assert!(preprocess("@\n%def a\n|\n%end\n%use a at (0, 0)").unwrap_err().to_string().contains("overlaps"));
+        assert!(preprocess("%use a (1, 1)").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::parser::flow::StaticFlow;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use crate::types::error::{InterpreterError, ErrorType, ErrorSeverity};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Depths at or above this are tracked as "unknown but large"
+const SATURATED_DEPTH: usize = 64;
+
+/// A stack operation reachable with fewer values on the stack than it consumes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackDiagnostic {
+    pub coordinate: Coordinate,
+    pub symbol: char,
+    /// Number of values the operation pops
+    pub required: usize,
+    /// Smallest stack depth the operation can be reached with
+    pub min_depth: usize,
+}
+
+impl StackDiagnostic {
+    pub fn to_error(&self) -> InterpreterError {
+        InterpreterError::enhanced(self.to_string(), ErrorType::Validation)
+            .with_severity(ErrorSeverity::Warning)
+            .with_suggestions(vec![
+                format!("Push at least {} value(s) with ':' before reaching '{}'", self.required, self.symbol),
+                "Check every branch leading here, including loop entries".to_string(),
+            ])
+            .with_help("Popping an empty stack silently yields 0, which usually hides a logic error.".to_string())
+    }
+}
+
+impl fmt::Display for StackDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Stack underflow: '{}' at {} pops {} value(s) but can be reached with stack depth {}",
+               self.symbol, self.coordinate, self.required, self.min_depth)
+    }
+}
+
+/// Abstract interpreter that tracks data stack depth along every path from `@`
+///
+/// Runtime values are ignored, so both exits of a conditional corner are explored.
+/// Each (cell, heading, depth) state is visited once, which bounds the search.
+/// Programs with `%droplet` starts are not checked, since another droplet may
+/// push what one pops.
+pub struct StackChecker;
+
+impl StackChecker {
+    /// Values popped and net depth change for a symbol, if it touches the stack
+    pub fn stack_effect(symbol: char) -> Option<(usize, isize)> {
+        match symbol {
+            // `d` on an empty stack pushes 0, so it never underflows
+            ':' | 'd' | 'i' | ')' | 'w' | 'e' | 'h' => Some((0, 1)),
+            ';' | '_' | '(' | 'z' | 'K' | 'I' | 'W' | 'X' | 'T' | 'E' | 'V' | 'G' | 'P' => Some((1, -1)),
+            // Pops a mode and a length, then as many characters as the length says
+            'O' => Some((2, -2)),
+            'A' | 'S' | 'M' | 'D' | '=' | '<' | '>' | '%' | '*' | 'r' | 'a' | 'g' | 'p' => Some((2, -2)),
+            '&' | 'o' | 'x' | '[' | ']' => Some((2, -2)),
+            // A rectangle's x, y, width and height, and for `B` where to copy it
+            'F' | 'Z' => Some((4, -4)),
+            'B' => Some((6, -6)),
+            _ => None,
+        }
+    }
+
+    /// Run the analysis and return every reachable underflow, ordered by position
+    pub fn check(grid: &ProgramGrid) -> Vec<StackDiagnostic> {
+        if !grid.droplets.is_empty() {
+            return Vec::new();
+        }
+        let (start, direction) = match StaticFlow::entry(grid) {
+            Some(entry) => entry,
+            None => return Vec::new(),
+        };
+
+        let mut findings: BTreeMap<(isize, isize), StackDiagnostic> = BTreeMap::new();
+        let mut visited: HashSet<(Coordinate, Direction, usize)> = HashSet::new();
+        let mut worklist = VecDeque::new();
+        worklist.push_back((start, direction, 0usize));
+
+        while let Some((coord, direction, depth)) = worklist.pop_front() {
+            if !visited.insert((coord, direction, depth)) {
+                continue;
+            }
+
+            let symbol = match grid.get_symbol(coord) {
+                Some(symbol) => symbol,
+                None => continue,
+            };
+
+            let mut next_depth = depth;
+            if let Some((required, delta)) = Self::stack_effect(symbol) {
+                // 'z' on an empty stack is a documented pass-through, not an error
+                if depth < required && symbol != 'z' {
+                    findings
+                        .entry((coord.y, coord.x))
+                        .and_modify(|d| d.min_depth = d.min_depth.min(depth))
+                        .or_insert(StackDiagnostic { coordinate: coord, symbol, required, min_depth: depth });
+                }
+                next_depth = Self::apply(depth, delta);
+            }
+
+            for (next, heading) in StaticFlow::successors(grid, coord, direction) {
+                worklist.push_back((next, heading, next_depth));
+            }
+        }
+
+        findings.into_values().collect()
+    }
+
+    fn apply(depth: usize, delta: isize) -> usize {
+        if depth >= SATURATED_DEPTH {
+            // Never report on paths where the exact depth has been lost
+            return SATURATED_DEPTH;
+        }
+        let result = depth as isize + delta;
+        (result.max(0) as usize).min(SATURATED_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    fn check(source: &str) -> Vec<StackDiagnostic> {
+        let grid = GridParser::new().parse_string(source).unwrap();
+        StackChecker::check(&grid)
+    }
+
+    #[test]
+    fn test_balanced_program_has_no_findings() {
+        assert!(check("@\n7\n:\n2\n:\nA\nn\n!").is_empty());
+    }
+
+    #[test]
+    fn test_binary_op_with_one_value_is_flagged() {
+        let findings = check("@\n7\n:\nA\nn\n!");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].coordinate, Coordinate::new(0, 3));
+        assert_eq!((findings[0].required, findings[0].min_depth), (2, 1));
+    }
+
+    #[test]
+    fn test_duplicate_on_an_empty_stack_is_not_flagged() {
+        assert!(check("@\nd\n;\nn\n!").is_empty());
+        assert_eq!(StackChecker::stack_effect('d'), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_unreachable_ops_are_ignored() {
+        assert!(check("@\n!\n\nA").is_empty());
+    }
+}
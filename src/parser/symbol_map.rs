@@ -0,0 +1,89 @@
+use crate::interpreter::grid::ProgramCell;
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+
+/// Characters a dialect uses in place of built-in symbols
+///
+/// The parser replaces each alias with its symbol before validating the cell,
+/// so `Ø = !` lets a program end droplets with `Ø`. Comments and directives
+/// are read before aliases apply and keep their usual syntax.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolMap {
+    aliases: BTreeMap<char, char>,
+}
+
+impl SymbolMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let `alias` stand for the built-in `symbol`
+    ///
+    /// Fails if `alias` is whitespace, a comment or directive marker, already
+    /// a built-in symbol, or already an alias for a different symbol, or if
+    /// `symbol` is not a built-in symbol.
+    pub fn insert(&mut self, alias: char, symbol: char) -> Result<(), String> {
+        if !ProgramCell::is_valid_symbol(symbol) {
+            return Err(format!("'{}' is mapped to '{}', which is not a Tubular symbol", alias, symbol));
+        }
+        if alias.is_whitespace() || alias == '#' {
+            return Err(format!("'{}' cannot be used as a symbol", alias.escape_default()));
+        }
+        if ProgramCell::is_valid_symbol(alias) {
+            return Err(format!("'{}' is already a Tubular symbol and cannot be remapped to '{}'", alias, symbol));
+        }
+        if let Some(&previous) = self.aliases.get(&alias)
+            && previous != symbol
+        {
+            return Err(format!("'{}' is mapped to both '{}' and '{}'", alias, previous, symbol));
+        }
+        self.aliases.insert(alias, symbol);
+        Ok(())
+    }
+
+    pub fn with_alias(mut self, alias: char, symbol: char) -> Result<Self, String> {
+        self.insert(alias, symbol)?;
+        Ok(self)
+    }
+
+    /// The symbol `ch` stands for: its mapping, or `ch` itself
+    pub fn resolve(&self, ch: char) -> char {
+        self.aliases.get(&ch).copied().unwrap_or(ch)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Aliases and their symbols, ordered by alias
+    pub fn iter(&self) -> impl Iterator<Item = (char, char)> + '_ {
+        self.aliases.iter().map(|(alias, symbol)| (*alias, *symbol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_aliases_are_parsed_as_their_symbols() {
+        let symbols = SymbolMap::new().with_alias('Ø', '!').unwrap().with_alias('↓', '|').unwrap();
+        let grid = GridParser::new().with_symbol_map(symbols.clone()).parse_string("@\n7\n↓\nn\nØ # Ø ends here").unwrap();
+        assert_eq!(grid.to_text(), "@\n7\n|\nn\n!\n");
+        assert!(GridParser::new().parse_string("@\nØ").is_err());
+        assert_eq!(symbols.iter().collect::<Vec<_>>(), [('Ø', '!'), ('↓', '|')]);
+    }
+
+    #[test]
+    fn test_conflicting_aliases_are_rejected() {
+        let mut symbols = SymbolMap::new();
+        assert!(symbols.insert('Ø', '!').is_ok());
+        assert!(symbols.insert('Ø', '!').is_ok());
+        assert!(symbols.insert('Ø', 'n').unwrap_err().contains("both '!' and 'n'"));
+        assert!(symbols.insert('n', '!').unwrap_err().contains("already a Tubular symbol"));
+        assert!(symbols.insert('x', 'Q').unwrap_err().contains("not a Tubular symbol"));
+        assert!(symbols.insert(' ', '!').is_err());
+        assert!(symbols.insert('#', '!').is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::interpreter::grid::ProgramGrid;
+use crate::interpreter::subroutines::Label;
+use crate::types::coordinate::Coordinate;
+use crate::types::direction::Direction;
+use std::fmt;
+
+const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+/// A rotation or reflection of a whole program, shown by `tubular transform`
+///
+/// Rotations are clockwise. `|` and `-` swap when the axes do, and the start
+/// heading turns with the grid through `%start`. `/`, `\` and `^` have no
+/// counterpart for every transform, because Tubular has no unconditional `\`
+/// mirror and no other fixed heading than `^`. [`GridTransform::apply`]
+/// refuses programs that use one of them where it would change meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridTransform {
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Flip left to right
+    MirrorH,
+    /// Flip top to bottom
+    MirrorV,
+    /// Swap rows and columns
+    Transpose,
+}
+
+impl GridTransform {
+    /// Clockwise rotation by `degrees`, if it is a quarter turn
+    pub fn rotation(degrees: u32) -> Option<Self> {
+        match degrees % 360 {
+            90 => Some(GridTransform::Rotate90),
+            180 => Some(GridTransform::Rotate180),
+            270 => Some(GridTransform::Rotate270),
+            _ => None,
+        }
+    }
+
+    /// Heading a droplet moving in `direction` has once the grid is transformed
+    pub fn direction(self, direction: Direction) -> Direction {
+        match self {
+            GridTransform::Rotate90 => direction.turn_right(),
+            GridTransform::Rotate180 => direction.opposite(),
+            GridTransform::Rotate270 => direction.turn_left(),
+            GridTransform::MirrorH => match direction {
+                Direction::Left | Direction::Right => direction.opposite(),
+                _ => direction,
+            },
+            GridTransform::MirrorV => match direction {
+                Direction::Up | Direction::Down => direction.opposite(),
+                _ => direction,
+            },
+            GridTransform::Transpose => match direction {
+                Direction::Up => Direction::Left,
+                Direction::Left => Direction::Up,
+                Direction::Down => Direction::Right,
+                Direction::Right => Direction::Down,
+            },
+        }
+    }
+
+    fn inverse(self) -> Self {
+        match self {
+            GridTransform::Rotate90 => GridTransform::Rotate270,
+            GridTransform::Rotate270 => GridTransform::Rotate90,
+            other => other,
+        }
+    }
+
+    /// The transform as it reads after "the program cannot be"
+    fn participle(self) -> &'static str {
+        match self {
+            GridTransform::Rotate90 => "rotated by 90 degrees",
+            GridTransform::Rotate180 => "rotated by 180 degrees",
+            GridTransform::Rotate270 => "rotated by 270 degrees",
+            GridTransform::MirrorH => "mirrored horizontally",
+            GridTransform::MirrorV => "mirrored vertically",
+            GridTransform::Transpose => "transposed",
+        }
+    }
+
+    fn swaps_axes(self) -> bool {
+        matches!(self, GridTransform::Rotate90 | GridTransform::Rotate270 | GridTransform::Transpose)
+    }
+
+    /// Where the cell at `coord` of a `width` by `height` grid anchored at (0, 0) lands
+    pub fn coordinate(self, coord: Coordinate, width: isize, height: isize) -> Coordinate {
+        let (x, y) = (coord.x, coord.y);
+        match self {
+            GridTransform::Rotate90 => Coordinate::new(height - 1 - y, x),
+            GridTransform::Rotate180 => Coordinate::new(width - 1 - x, height - 1 - y),
+            GridTransform::Rotate270 => Coordinate::new(y, width - 1 - x),
+            GridTransform::MirrorH => Coordinate::new(width - 1 - x, y),
+            GridTransform::MirrorV => Coordinate::new(x, height - 1 - y),
+            GridTransform::Transpose => Coordinate::new(y, x),
+        }
+    }
+
+    /// Symbol that does in the transformed grid what `symbol` did in the
+    /// original, or `None` if Tubular has no such symbol
+    pub fn symbol(self, symbol: char) -> Option<char> {
+        match symbol {
+            '|' | '-' if self.swaps_axes() => Some(if symbol == '|' { '-' } else { '|' }),
+            '/' | '\\' | '^' => {
+                // A `\` reverses zero-valued droplets whatever the transform,
+                // so only its turn for non-zero values has to survive
+                let unchanged = DIRECTIONS.iter().all(|&direction| {
+                    let before = turn(symbol, self.inverse().direction(direction));
+                    self.direction(before) == turn(symbol, direction)
+                });
+                unchanged.then_some(symbol)
+            }
+            _ => Some(symbol),
+        }
+    }
+
+    /// Source of the transformed program: its cells, then its directives
+    ///
+    /// The program is anchored at (0, 0) first, includes arrive already
+    /// merged, and comments are dropped. Fails, naming every offending cell,
+    /// if a symbol has no counterpart (see [`GridTransform::symbol`]) or a
+    /// `C` is not bound by `%call`, since unbound calls jump to coordinates
+    /// computed at run time.
+    pub fn apply(self, grid: &ProgramGrid) -> Result<String, String> {
+        if grid.cells.is_empty() {
+            return Ok(String::new());
+        }
+
+        let origin = Coordinate::new(grid.bounds.min_x.min(0), grid.bounds.min_y.min(0));
+        let width = grid.bounds.max_x - origin.x + 1;
+        let height = grid.bounds.max_y - origin.y + 1;
+        let place = |coord: Coordinate| {
+            self.coordinate(Coordinate::new(coord.x - origin.x, coord.y - origin.y), width, height)
+        };
+
+        let mut cells: Vec<_> = grid.iter().map(|(coord, cell)| (*coord, cell.symbol)).collect();
+        cells.sort_by_key(|(coord, _)| (coord.y, coord.x));
+
+        let mut transformed = ProgramGrid::new();
+        let mut untranslatable = Vec::new();
+        for (coord, symbol) in cells {
+            let unbound_call = symbol == 'C' && grid.subroutines.call_label(coord).is_none();
+            match self.symbol(symbol) {
+                Some(symbol) if !unbound_call => {
+                    transformed.add_cell(place(coord), symbol).map_err(|e| e.to_string())?;
+                }
+                _ => untranslatable.push(format!("'{}' at {}", symbol, coord)),
+            }
+        }
+        if !untranslatable.is_empty() {
+            return Err(format!("this program cannot be {} without changing what it does: {}", self.participle(), untranslatable.join(", ")));
+        }
+
+        for (name, label) in grid.subroutines.labels() {
+            let label = Label { entry: place(label.entry), direction: self.direction(label.direction) };
+            transformed.subroutines.define_label(name, label);
+        }
+        for (site, name) in grid.subroutines.calls() {
+            transformed.subroutines.bind_call(place(site), name);
+        }
+        for (coord, name) in grid.portals.portals() {
+            transformed.portals.bind(place(coord), name);
+        }
+        if grid.start.is_some() {
+            transformed.start_direction = self.direction(grid.start_direction);
+        }
+        transformed.droplets = grid.droplets.iter()
+            .map(|(coord, direction)| (place(*coord), self.direction(*direction)))
+            .collect();
+        transformed.multi_digit_literals = grid.multi_digit_literals;
+        transformed.rational_numbers = grid.rational_numbers;
+        transformed.collision_policy = grid.collision_policy;
+        transformed.eof_policy = grid.eof_policy;
+        Ok(transformed.to_source())
+    }
+}
+
+impl fmt::Display for GridTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            GridTransform::Rotate90 => "rotate by 90 degrees",
+            GridTransform::Rotate180 => "rotate by 180 degrees",
+            GridTransform::Rotate270 => "rotate by 270 degrees",
+            GridTransform::MirrorH => "mirror horizontally",
+            GridTransform::MirrorV => "mirror vertically",
+            GridTransform::Transpose => "transpose",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Heading a droplet leaves a turning symbol with; `\` as for non-zero values
+fn turn(symbol: char, direction: Direction) -> Direction {
+    match (symbol, direction) {
+        ('^', _) => Direction::Up,
+        ('/', Direction::Right) | ('\\', Direction::Left) => Direction::Up,
+        ('/', Direction::Up) | ('\\', Direction::Down) => Direction::Right,
+        ('/', Direction::Left) | ('\\', Direction::Right) => Direction::Down,
+        ('/', Direction::Down) | ('\\', Direction::Up) => Direction::Left,
+        _ => direction,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_turning_symbols_survive_only_symmetric_transforms() {
+        assert_eq!(GridTransform::Transpose.symbol('/'), Some('/'));
+        assert_eq!(GridTransform::Transpose.symbol('\\'), Some('\\'));
+        assert_eq!(GridTransform::Transpose.symbol('|'), Some('-'));
+        assert_eq!(GridTransform::Rotate180.symbol('/'), Some('/'));
+        assert_eq!(GridTransform::Rotate90.symbol('/'), None);
+        assert_eq!(GridTransform::MirrorH.symbol('\\'), None);
+        assert_eq!(GridTransform::MirrorH.symbol('^'), Some('^'));
+        assert_eq!(GridTransform::Rotate180.symbol('@'), Some('@'));
+        assert_eq!(GridTransform::Rotate90.symbol('>'), Some('>'));
+    }
+
+    #[test]
+    fn test_transform_moves_cells_and_directives() {
+        let source = "@ 7\nC R\nn\n!\n%label seven at (2, 0)\n%call seven at (0, 1)";
+        let grid = GridParser::new().parse_string(source).unwrap();
+        let mirrored = GridTransform::MirrorH.apply(&grid).unwrap();
+        assert_eq!(mirrored, "7 @\nR C\n  n\n  !\n%label seven at (0, 0) heading down\n%call seven at (2, 1)\n");
+        let reparsed = GridParser::new().parse_string(&mirrored).unwrap();
+        assert_eq!(reparsed.subroutines.label("seven").unwrap().entry, Coordinate::new(0, 0));
+
+        let mut fragment = ProgramGrid::new();
+        for (x, y, symbol) in [(0, 0, '1'), (1, 0, '-'), (1, 1, ';')] {
+            fragment.add_cell(Coordinate::new(x, y), symbol).unwrap();
+        }
+        assert_eq!(GridTransform::Rotate90.apply(&fragment).unwrap(), " 1\n;|\n");
+
+        let error = GridTransform::Rotate90.apply(&GridParser::new().parse_string("@\n/").unwrap()).unwrap_err();
+        assert!(error.starts_with("this program cannot be rotated by 90 degrees"), "{}", error);
+        assert!(error.contains("'/' at (0, 1)"), "{}", error);
+    }
+
+    #[test]
+    fn test_every_transform_keeps_the_start_heading() {
+        use crate::interpreter::execution::TubularInterpreter;
+
+        let source = "@\n7\n:\n3\n:\nA\nn\n!";
+        let run = |source: &str| {
+            let grid = GridParser::new().parse_string(source).unwrap();
+            TubularInterpreter::new(grid).unwrap().run().unwrap().final_output
+        };
+        let grid = GridParser::new().parse_string(source).unwrap();
+        for transform in [GridTransform::Rotate90, GridTransform::Rotate180, GridTransform::Rotate270,
+                          GridTransform::MirrorH, GridTransform::MirrorV, GridTransform::Transpose] {
+            let transformed = transform.apply(&grid).unwrap();
+            assert_eq!(run(&transformed), "10", "{}:\n{}", transform, transformed);
+        }
+        assert_eq!(GridTransform::Rotate90.apply(&grid).unwrap(), "!nA:3:7@\n%start heading left\n");
+    }
+}
@@ -1,34 +1,66 @@
-use crate::interpreter::grid::{ProgramGrid, ProgramCell};
+use crate::interpreter::grid::ProgramGrid;
 use crate::types::coordinate::Coordinate;
-use crate::types::direction::Direction;
-use crate::types::error::{Result, InitError, InterpreterError, ErrorType, ErrorSeverity, Position, ErrorContext};
-use std::collections::{HashMap, HashSet};
+use crate::types::error::{Result, InterpreterError, InitError, ErrorType, ErrorSeverity, ErrorContext};
+use crate::parser::lint::{LintFinding, LintLevel, Linter, Strictness};
+use std::sync::Arc;
+
+/// A check library users add to [`ProgramValidator`] with `with_rule`
+///
+/// Rules run after the built-in checks. Their findings take the rule's name
+/// and severity; only `Error` findings fail validation, the rest are kept
+/// alongside the errors by `validate_all` (see `with_deny_warnings`).
+pub trait ValidationRule: Send + Sync {
+    /// Stable kebab-case name reported with each finding
+    fn name(&self) -> &'static str;
+
+    fn severity(&self) -> ErrorSeverity {
+        ErrorSeverity::Error
+    }
+
+    /// Findings for `grid`, each reported under this rule's `name()`
+    fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding>;
+}
 
 #[derive(Clone)]
 pub struct ProgramValidator {
-    strict_mode: bool,
+    strictness: Strictness,
+    deny_warnings: bool,
     collect_errors: bool,
     errors: Vec<InterpreterError>,
     source_content: Option<String>,
+    rules: Vec<Arc<dyn ValidationRule>>,
 }
 
 impl ProgramValidator {
     pub fn new() -> Self {
         ProgramValidator {
-            strict_mode: false,
+            strictness: Strictness::Default,
+            deny_warnings: false,
             collect_errors: false,
             errors: Vec::new(),
             source_content: None,
+            rules: Vec::new(),
         }
     }
 
     pub fn strict() -> Self {
-        ProgramValidator {
-            strict_mode: true,
-            collect_errors: false,
-            errors: Vec::new(),
-            source_content: None,
-        }
+        Self::new().with_strictness(Strictness::Strict)
+    }
+
+    /// Also deny the lint rules assigned to `strictness` or a lower level
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    pub fn strictness(&self) -> Strictness {
+        self.strictness
+    }
+
+    /// Report warnings as errors, so they fail validation
+    pub fn with_deny_warnings(mut self, deny_warnings: bool) -> Self {
+        self.deny_warnings = deny_warnings;
+        self
     }
 
     pub fn with_error_collection(mut self) -> Self {
@@ -41,6 +73,16 @@ impl ProgramValidator {
         self
     }
 
+    /// Run `rule` after the built-in checks
+    pub fn with_rule(mut self, rule: Box<dyn ValidationRule>) -> Self {
+        self.rules.push(Arc::from(rule));
+        self
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn ValidationRule> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
     pub fn get_errors(&self) -> &[InterpreterError] {
         &self.errors
     }
@@ -54,13 +96,20 @@ impl ProgramValidator {
         validator.validate_with_collection(grid)
     }
 
+    /// Every problem in `grid`, in the order the checks run
+    pub fn validate_all(&self, grid: &ProgramGrid) -> Vec<InterpreterError> {
+        let mut validator = self.clone().with_error_collection();
+        let _ = validator.validate_with_collection(grid);
+        validator.errors
+    }
+
     fn validate_with_collection(&mut self, grid: &ProgramGrid) -> Result<()> {
         self.errors.clear();
 
         // Basic validation
-        if let Err(e) = grid.validate() {
+        if let Err(e) = grid.validate_cells() {
             if self.collect_errors {
-                self.add_enhanced_error(e, None);
+                self.add_enhanced_error(grid, e, None);
             } else {
                 return Err(e);
             }
@@ -75,7 +124,7 @@ impl ProgramValidator {
             }
         }
 
-        if let Err(e) = self.validate_flow_control_with_context(grid) {
+        if let Err(e) = self.validate_symbols_with_context(grid) {
             if self.collect_errors {
                 self.errors.push(e);
             } else {
@@ -83,36 +132,79 @@ impl ProgramValidator {
             }
         }
 
-        if let Err(e) = self.validate_symbols_with_context(grid) {
+        // Every subroutine problem is reported at its own call site or label
+        for error in grid.subroutine_errors() {
+            let coord = match &error {
+                InitError::UndefinedLabel(_, coord) | InitError::InvalidCallSite(coord) | InitError::MissingLabelEntry(_, coord) => Some(*coord),
+                _ => None,
+            };
+            let error = InterpreterError::enhanced(error.to_string(), ErrorType::Validation)
+                .with_suggestions(vec![
+                    "Define every called label with %label name at (x, y)".to_string(),
+                    "Point %call directives at 'C' cells and %label directives at existing cells".to_string(),
+                ]);
+            let error = match coord {
+                Some(coord) => error.with_context(self.create_error_context_for_coord(grid, coord)),
+                None => error,
+            };
             if self.collect_errors {
-                self.errors.push(e);
+                self.errors.push(error);
             } else {
-                return Err(e);
+                return Err(error);
             }
         }
 
-        if let Err(e) = self.validate_reachable_code_with_context(grid) {
+        // Likewise every portal that is not one end of a pair
+        for error in grid.portal_errors() {
+            let coord = match &error {
+                InitError::InvalidPortalSite(coord) | InitError::UnnamedPortal(coord) | InitError::UnpairedPortal(_, _, coord) => Some(*coord),
+                _ => None,
+            };
+            let error = InterpreterError::enhanced(error.to_string(), ErrorType::Validation)
+                .with_suggestions(vec![
+                    "Name each 'J' cell with %portal name at (x, y)".to_string(),
+                    "Give every portal name exactly two 'J' cells".to_string(),
+                ]);
+            let error = match coord {
+                Some(coord) => error.with_context(self.create_error_context_for_coord(grid, coord)),
+                None => error,
+            };
             if self.collect_errors {
-                self.errors.push(e);
+                self.errors.push(error);
             } else {
-                return Err(e);
+                return Err(error);
             }
         }
 
-        if self.strict_mode {
-            if let Err(e) = self.validate_strict_rules_with_context(grid) {
-                if self.collect_errors {
-                    self.errors.push(e);
-                } else {
-                    return Err(e);
+        if self.strictness > Strictness::Default {
+            for finding in Linter::for_strictness(self.strictness).run(grid) {
+                self.report_finding(grid, &finding, ErrorSeverity::Error)?;
+            }
+        }
+
+        // Rules that warn by default and are not denied at this strictness
+        // are advisory; skip them when they could neither fail nor be kept
+        if self.collect_errors || self.deny_warnings {
+            let denied = Linter::for_strictness(self.strictness);
+            for finding in Linter::new().run(grid) {
+                if denied.level(finding.rule) != Some(LintLevel::Deny) {
+                    self.report_finding(grid, &finding, ErrorSeverity::Warning)?;
                 }
             }
         }
 
-        // If we're collecting errors, return an error if any were collected
-        if self.collect_errors && !self.errors.is_empty() {
+        for rule in self.rules.clone() {
+            let level = if rule.severity() == ErrorSeverity::Error { LintLevel::Deny } else { LintLevel::Warn };
+            for finding in rule.check(grid) {
+                self.report_finding(grid, &LintFinding { rule: rule.name(), level, ..finding }, rule.severity())?;
+            }
+        }
+
+        // If we're collecting errors, fail if any of them is an error
+        let failures = self.errors.iter().filter(|e| e.severity() == ErrorSeverity::Error).count();
+        if self.collect_errors && failures > 0 {
             return Err(InterpreterError::enhanced(
-                format!("Found {} validation errors", self.errors.len()),
+                format!("Found {} validation errors", failures),
                 ErrorType::Validation
             ).with_severity(ErrorSeverity::Error));
         }
@@ -120,9 +212,29 @@ impl ProgramValidator {
         Ok(())
     }
 
-    fn add_enhanced_error(&mut self, error: InterpreterError, coord: Option<Coordinate>) {
+    /// Keep `finding` at `severity` when collecting errors, otherwise fail if it is an error
+    fn report_finding(&mut self, grid: &ProgramGrid, finding: &LintFinding, severity: ErrorSeverity) -> Result<()> {
+        let severity = match severity {
+            ErrorSeverity::Warning if self.deny_warnings => ErrorSeverity::Error,
+            severity => severity,
+        };
+        let fails = severity == ErrorSeverity::Error;
+        let error = finding.to_error().with_severity(severity);
+        let error = match finding.coordinate {
+            Some(coord) => error.with_context(self.create_error_context_for_coord(grid, coord)),
+            None => error,
+        };
+        if self.collect_errors {
+            self.errors.push(error);
+        } else if fails {
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    fn add_enhanced_error(&mut self, grid: &ProgramGrid, error: InterpreterError, coord: Option<Coordinate>) {
         let enhanced_error = if let Some(coord) = coord {
-            let context = self.create_error_context_for_coord(coord);
+            let context = self.create_error_context_for_coord(grid, coord);
             error.with_context(context)
         } else {
             error
@@ -130,10 +242,13 @@ impl ProgramValidator {
         self.errors.push(enhanced_error);
     }
 
-    fn create_error_context_for_coord(&self, coord: Coordinate) -> ErrorContext {
-        let position = Position::new(coord.y as usize, coord.x as usize, coord);
+    fn create_error_context_for_coord(&self, grid: &ProgramGrid, coord: Coordinate) -> ErrorContext {
+        let position = grid.error_position(coord);
 
-        if let Some(ref content) = self.source_content {
+        // Source content is the program's own file, so it has no lines for included cells
+        if let Some(ref content) = self.source_content
+            && position.file.is_none()
+        {
             let lines: Vec<&str> = content.lines().collect();
 
             let source_line = lines.get(position.line)
@@ -158,24 +273,7 @@ impl ProgramValidator {
         }
     }
 
-    fn validate_start_symbol(&self, grid: &ProgramGrid) -> Result<()> {
-        if grid.start.is_none() {
-            return Err(InitError::NoStartSymbol.into());
-        }
-
-        // Ensure start symbol is not at an edge if strict mode
-        if self.strict_mode {
-            if let Some(start_pos) = grid.start {
-                if start_pos.x == 0 || start_pos.y == 0 {
-                    return Err(InitError::InvalidCharacter('@', start_pos).into());
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    fn validate_start_symbol_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
+    fn validate_start_symbol_with_context(&self, grid: &ProgramGrid) -> Result<()> {
         if grid.start.is_none() {
             let error = InterpreterError::enhanced(
                 "No start symbol (@) found in program".to_string(),
@@ -188,750 +286,167 @@ impl ProgramValidator {
             return Err(error);
         }
 
-        // Ensure start symbol is not at an edge if strict mode
-        if self.strict_mode {
-            if let Some(start_pos) = grid.start {
-                if start_pos.x == 0 || start_pos.y == 0 {
-                    let context = self.create_error_context_for_coord(start_pos);
-                    let error = InterpreterError::enhanced(
-                        "Start symbol '@' cannot be placed at the edge in strict mode".to_string(),
-                        ErrorType::Validation
-                    ).with_context(context)
-                    .with_suggestions(vec![
-                        "Move the start symbol away from the edge".to_string(),
-                        "Add at least one space margin around the start symbol".to_string(),
-                    ]).with_help("In strict mode, the start symbol should have at least one cell of space around it to ensure proper flow.".to_string());
-
-                    return Err(error);
-                }
-            }
-        }
-
-        Ok(InterpreterError::enhanced("Validation passed".to_string(), ErrorType::Validation))
-    }
-
-    fn validate_flow_control(&self, grid: &ProgramGrid) -> Result<()> {
-        let mut invalid_pipes = Vec::new();
-
-        for (coord, cell) in grid.iter() {
-            if !ProgramCell::is_flow_control_symbol(cell.symbol) {
-                continue;
-            }
-
-            if self.is_invalid_pipe_placement(grid, *coord, cell.symbol) {
-                invalid_pipes.push((*coord, cell.symbol));
-            }
-        }
-
-        if !invalid_pipes.is_empty() {
-            let (coord, symbol) = invalid_pipes[0];
-            return Err(InitError::InvalidCharacter(symbol, coord).into());
-        }
-
-        Ok(())
-    }
-
-    fn validate_flow_control_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        let mut invalid_pipes = Vec::new();
-
-        for (coord, cell) in grid.iter() {
-            if !ProgramCell::is_flow_control_symbol(cell.symbol) {
-                continue;
-            }
-
-            if self.is_invalid_pipe_placement(grid, *coord, cell.symbol) {
-                invalid_pipes.push((*coord, cell.symbol));
-            }
-        }
-
-        if !invalid_pipes.is_empty() {
-            let errors: Vec<InterpreterError> = invalid_pipes.into_iter().map(|(coord, symbol)| {
-                let context = self.create_error_context_for_coord(coord);
-                let suggestions = self.get_pipe_placement_suggestions(symbol, coord, grid);
-
-                InterpreterError::enhanced(
-                    format!("Invalid flow control pipe '{}' at this position", symbol),
-                    ErrorType::Validation
-                ).with_context(context)
-                .with_suggestions(suggestions)
-                .with_help(self.get_pipe_help_text(symbol))
-            }).collect();
-
-            // Return the first error, or create a combined error if multiple
-            if let Some(first_error) = errors.into_iter().next() {
-                return Err(first_error);
-            }
-        }
-
-        Ok(InterpreterError::enhanced("Flow control validation passed".to_string(), ErrorType::Validation))
-    }
-
-    fn get_pipe_placement_suggestions(&self, symbol: char, coord: Coordinate, grid: &ProgramGrid) -> Vec<String> {
-        let mut suggestions = Vec::new();
-
-        match symbol {
-            '^' | 'v' => {
-                let has_above = grid.get(Coordinate::new(coord.x, coord.y - 1)).is_some();
-                let has_below = grid.get(Coordinate::new(coord.x, coord.y + 1)).is_some();
-
-                if !has_above && !has_below {
-                    suggestions.push("Add a cell above or below this vertical pipe".to_string());
-                    suggestions.push("Use '|' for bidirectional vertical flow".to_string());
-                } else if !has_above {
-                    suggestions.push("Add a cell above to complete the vertical connection".to_string());
-                } else if !has_below {
-                    suggestions.push("Add a cell below to complete the vertical connection".to_string());
-                }
-            }
-            '<' | '>' => {
-                let has_left = grid.get(Coordinate::new(coord.x - 1, coord.y)).is_some();
-                let has_right = grid.get(Coordinate::new(coord.x + 1, coord.y)).is_some();
-
-                if !has_left && !has_right {
-                    suggestions.push("Add a cell to the left or right of this horizontal pipe".to_string());
-                    suggestions.push("Use '-' for bidirectional horizontal flow".to_string());
-                } else if !has_left {
-                    suggestions.push("Add a cell to the left to complete the horizontal connection".to_string());
-                } else if !has_right {
-                    suggestions.push("Add a cell to the right to complete the horizontal connection".to_string());
-                }
-            }
-            '/' | '\\' => {
-                suggestions.push("Ensure corner pipes have proper connections on their valid sides".to_string());
-                suggestions.push("Check that adjacent cells align with the corner's direction".to_string());
-            }
-            _ => {
-                suggestions.push("Ensure the pipe symbol has appropriate connections".to_string());
-            }
-        }
-
-        suggestions
-    }
-
-    fn get_pipe_help_text(&self, symbol: char) -> String {
-        match symbol {
-            '^' => "This pipe directs flow upward. In strict mode, it should have at least one connection (above or below).".to_string(),
-            'v' => "This pipe directs flow downward. In strict mode, it should have at least one connection (above or below).".to_string(),
-            '<' => "This pipe directs flow leftward. In strict mode, it should have at least one connection (left or right).".to_string(),
-            '>' => "This pipe directs flow rightward. In strict mode, it should have at least one connection (left or right).".to_string(),
-            '|' => "This pipe allows bidirectional vertical flow and connects cells above and below.".to_string(),
-            '-' => "This pipe allows bidirectional horizontal flow and connects cells left and right.".to_string(),
-            '/' => "This corner pipe redirects flow from right to up or from down to left.".to_string(),
-            '\\' => "This corner pipe redirects flow from left to down or from up to right.".to_string(),
-            _ => "This is a flow control symbol that directs droplet movement.".to_string(),
-        }
-    }
-
-    fn is_invalid_pipe_placement(&self, grid: &ProgramGrid, coord: Coordinate, symbol: char) -> bool {
-        match symbol {
-            '|' | '-' => false, // Straight pipes are always valid
-            '^' => self.has_invalid_vertical_connection(grid, coord),
-            'v' => self.has_invalid_vertical_connection(grid, coord),
-            '<' => self.has_invalid_horizontal_connection(grid, coord),
-            '>' => self.has_invalid_horizontal_connection(grid, coord),
-            '/' | '\\' => self.has_invalid_corner_placement(grid, coord, symbol),
-            _ => false,
-        }
-    }
-
-    fn has_invalid_vertical_connection(&self, grid: &ProgramGrid, coord: Coordinate) -> bool {
-        let above = Coordinate::new(coord.x, coord.y - 1);
-        let below = Coordinate::new(coord.x, coord.y + 1);
-
-        // In strict mode, directional pipes need connections
-        if self.strict_mode {
-            let has_above = grid.get(above).is_some();
-            let has_below = grid.get(below).is_some();
-            !has_above && !has_below
-        } else {
-            false
-        }
-    }
-
-    fn has_invalid_horizontal_connection(&self, grid: &ProgramGrid, coord: Coordinate) -> bool {
-        let left = Coordinate::new(coord.x - 1, coord.y);
-        let right = Coordinate::new(coord.x + 1, coord.y);
-
-        if self.strict_mode {
-            let has_left = grid.get(left).is_some();
-            let has_right = grid.get(right).is_some();
-            !has_left && !has_right
-        } else {
-            false
-        }
-    }
-
-    fn has_invalid_corner_placement(&self, grid: &ProgramGrid, coord: Coordinate, symbol: char) -> bool {
-        // Corner pipes need at least one valid connection in strict mode
-        if !self.strict_mode {
-            return false;
-        }
-
-        let valid_pairs = match symbol {
-            '/' => vec![
-                (Direction::Right, Direction::Up),
-                (Direction::Down, Direction::Left),
-            ],
-            '\\' => vec![
-                (Direction::Right, Direction::Down),
-                (Direction::Up, Direction::Left),
-            ],
-            _ => return false,
-        };
-
-        let mut has_valid_connection = false;
-
-        for (from_dir, to_dir) in valid_pairs {
-            let from_coord = coord - from_dir;
-            let to_coord = coord + to_dir;
-
-            if grid.get(from_coord).is_some() || grid.get(to_coord).is_some() {
-                has_valid_connection = true;
-                break;
-            }
-        }
-
-        !has_valid_connection
-    }
-
-    fn validate_symbols(&self, grid: &ProgramGrid) -> Result<()> {
-        let mut symbol_counts = HashMap::new();
-
-        for (_, cell) in grid.iter() {
-            *symbol_counts.entry(cell.symbol).or_insert(0) += 1;
-        }
-
-        // Validate start symbol count
-        let start_count = symbol_counts.get(&'@').unwrap_or(&0);
-        if *start_count != 1 {
-            return Err(InitError::NoStartSymbol.into());
-        }
-
-        // Check for potentially problematic symbol combinations
-        if self.strict_mode {
-            self.validate_symbol_combinations(&symbol_counts, grid)?;
-        }
-
-        Ok(())
-    }
-
-    fn validate_symbols_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        let mut symbol_counts = HashMap::new();
-
-        for (_, cell) in grid.iter() {
-            *symbol_counts.entry(cell.symbol).or_insert(0) += 1;
-        }
-
-        // Validate start symbol count
-        let start_count = symbol_counts.get(&'@').unwrap_or(&0);
-        if *start_count != 1 {
-            let error = if *start_count == 0 {
-                InterpreterError::enhanced(
-                    "No start symbol (@) found in program".to_string(),
-                    ErrorType::Initialization
-                ).with_suggestions(vec![
-                    "Add a start symbol '@' to your program".to_string(),
-                    "The start symbol should be placed where you want execution to begin".to_string(),
-                ]).with_help("Every Tubular program needs exactly one start symbol '@' to indicate where execution should begin.".to_string())
-            } else {
-                InterpreterError::enhanced(
-                    format!("Multiple start symbols (@) found: {} start symbols", start_count),
-                    ErrorType::Initialization
-                ).with_suggestions(vec![
-                    "Remove all but one start symbol '@'".to_string(),
-                    "Choose the location where you want execution to begin".to_string(),
-                ]).with_help("A Tubular program can only have one start symbol '@'. Multiple start symbols create ambiguity about where execution should begin.".to_string())
-            };
-
-            return Err(error);
-        }
-
-        // Check for potentially problematic symbol combinations
-        if self.strict_mode {
-            if let Err(e) = self.validate_symbol_combinations_with_context(grid) {
-                return Err(e);
-            }
-        }
-
-        Ok(InterpreterError::enhanced("Symbol validation passed".to_string(), ErrorType::Validation))
-    }
-
-    fn validate_symbol_combinations(&self, _symbol_counts: &HashMap<char, usize>, grid: &ProgramGrid) -> Result<()> {
-        // In strict mode, ensure certain operators have appropriate context
-        for (coord, cell) in grid.iter() {
-            match cell.symbol {
-                ':' | ';' | 'd' => {
-                    // Stack operations should have access to data
-                    if !self.has_data_access(grid, *coord) {
-                        return Err(InitError::InvalidCharacter(cell.symbol, *coord).into());
-                    }
-                }
-                'A' | 'S' | 'M' | 'D' => {
-                    // Arithmetic operations need at least one operand
-                    if !self.has_data_source_nearby(grid, *coord) {
-                        return Err(InitError::InvalidCharacter(cell.symbol, *coord).into());
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        Ok(())
-    }
-
-    fn validate_symbol_combinations_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        // In strict mode, ensure certain operators have appropriate context
-        for (coord, cell) in grid.iter() {
-            match cell.symbol {
-                ':' | ';' | 'd' => {
-                    // Stack operations should have access to data
-                    if !self.has_data_access(grid, *coord) {
-                        let context = self.create_error_context_for_coord(*coord);
-                        let error = InterpreterError::enhanced(
-                            format!("Stack operation '{}' has no access to data", cell.symbol),
-                            ErrorType::Semantic
-                        ).with_context(context)
-                        .with_suggestions(vec![
-                            "Add a data source nearby (numbers, input symbol '?', or other stack operations)".to_string(),
-                            "Move the operation closer to data flow".to_string(),
-                        ]).with_help("Stack operations need access to data to function properly. Place them near data sources or in the flow of operations.".to_string());
-                        return Err(error);
-                    }
-                }
-                'A' | 'S' | 'M' | 'D' => {
-                    // Arithmetic operations need at least one operand
-                    if !self.has_data_source_nearby(grid, *coord) {
-                        let context = self.create_error_context_for_coord(*coord);
-                        let operation_name = match cell.symbol {
-                            'A' => "Addition",
-                            'S' => "Subtraction",
-                            'M' => "Multiplication",
-                            'D' => "Division",
-                            _ => "Arithmetic",
-                        };
-                        let error = InterpreterError::enhanced(
-                            format!("{} operation '{}' has no operands available", operation_name, cell.symbol),
-                            ErrorType::Semantic
-                        ).with_context(context)
-                        .with_suggestions(vec![
-                            "Add numeric literals (0-9) nearby".to_string(),
-                            "Ensure data flow reaches this operation".to_string(),
-                            "Add input operations or other data sources upstream".to_string(),
-                        ]).with_help("Arithmetic operations need at least one operand to work with. Make sure there's data flowing into this operation.".to_string());
-                        return Err(error);
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        Ok(InterpreterError::enhanced("Symbol combination validation passed".to_string(), ErrorType::Validation))
-    }
-
-    fn has_data_access(&self, grid: &ProgramGrid, coord: Coordinate) -> bool {
-        // Check if there are data sources or stack operations nearby
-        let adjacent_coords = [
-            Coordinate::new(coord.x - 1, coord.y),
-            Coordinate::new(coord.x + 1, coord.y),
-            Coordinate::new(coord.x, coord.y - 1),
-            Coordinate::new(coord.x, coord.y + 1),
-        ];
-
-        for adjacent_coord in adjacent_coords {
-            if let Some(cell) = grid.get(adjacent_coord) {
-                if ProgramCell::is_data_source(cell.symbol) ||
-                   cell.symbol == ':' ||
-                   cell.symbol == '0' {
-                    return true;
-                }
-            }
-        }
-
-        false
-    }
-
-    fn has_data_source_nearby(&self, grid: &ProgramGrid, coord: Coordinate) -> bool {
-        // Check if there are numbers, input sources, or stack operations nearby
-        let adjacent_coords = [
-            Coordinate::new(coord.x - 1, coord.y),
-            Coordinate::new(coord.x + 1, coord.y),
-            Coordinate::new(coord.x, coord.y - 1),
-            Coordinate::new(coord.x, coord.y + 1),
-        ];
-
-        for adjacent_coord in adjacent_coords {
-            if let Some(cell) = grid.get(adjacent_coord) {
-                if cell.symbol.is_ascii_digit() ||
-                   ProgramCell::is_data_source(cell.symbol) ||
-                   cell.symbol == ':' ||
-                   cell.symbol == ';' {
-                    return true;
-                }
-            }
-        }
-
-        false
-    }
-
-    fn validate_reachable_code(&self, grid: &ProgramGrid) -> Result<()> {
-        if grid.start.is_none() {
-            return Ok(()); // Already caught by basic validation
-        }
-
-        let start_pos = grid.start.unwrap();
-        let mut visited = HashSet::new();
-        let mut to_visit = vec![start_pos];
-
-        while let Some(current_pos) = to_visit.pop() {
-            if visited.contains(&current_pos) {
-                continue;
-            }
-            visited.insert(current_pos);
-
-            if let Some(cell) = grid.get(current_pos) {
-                let next_positions = self.get_next_positions(grid, current_pos, cell);
-                for next_pos in next_positions {
-                    if !visited.contains(&next_pos) {
-                        to_visit.push(next_pos);
-                    }
-                }
-            }
-        }
-
-        // In strict mode, warn about unreachable code
-        if self.strict_mode {
-            let unreachable_count = grid.size() - visited.len();
-            if unreachable_count > 0 {
-                // For now, we'll just allow unreachable code
-                // In a stricter implementation, we might return an error
-            }
-        }
-
         Ok(())
     }
 
-    fn get_next_positions(&self, grid: &ProgramGrid, pos: Coordinate, cell: &ProgramCell) -> Vec<Coordinate> {
-        let mut positions = Vec::new();
-
-        if ProgramCell::is_flow_control_symbol(cell.symbol) {
-            // Follow flow control rules
-            let directions = self.get_flow_directions(cell.symbol);
-            for direction in directions {
-                positions.push(pos + direction);
-            }
-        } else if ProgramCell::is_operator_symbol(cell.symbol) {
-            // Operators allow flow through (default direction)
-            positions.push(Coordinate::new(pos.x, pos.y + 1)); // Default down
-        }
-
-        positions
-    }
-
-    fn get_flow_directions(&self, symbol: char) -> Vec<Direction> {
-        match symbol {
-            '|' => vec![Direction::Up, Direction::Down],
-            '-' => vec![Direction::Left, Direction::Right],
-            '^' => vec![Direction::Up],
-            'v' => vec![Direction::Down],
-            '<' => vec![Direction::Left],
-            '>' => vec![Direction::Right],
-            '/' => vec![Direction::Up, Direction::Left], // Simplified
-            '\\' => vec![Direction::Down, Direction::Left], // Simplified
-            _ => vec![],
-        }
-    }
-
-    fn validate_strict_rules(&self, grid: &ProgramGrid) -> Result<()> {
-        // Additional strict validation rules
-
-        // 1. Ensure no orphaned pipes
-        self.validate_no_orphaned_pipes(grid)?;
-
-        // 2. Ensure proper I/O placement
-        self.validate_io_placement(grid)?;
-
-        // 3. Ensure memory operations have valid coordinates
-        self.validate_memory_operations(grid)?;
-
-        Ok(())
-    }
-
-    fn validate_no_orphaned_pipes(&self, grid: &ProgramGrid) -> Result<()> {
-        for (coord, cell) in grid.iter() {
-            if ProgramCell::is_flow_control_symbol(cell.symbol) {
-                if self.is_orphaned_pipe(grid, *coord, cell.symbol) {
-                    return Err(InitError::InvalidCharacter(cell.symbol, *coord).into());
-                }
-            }
-        }
-        Ok(())
-    }
-
-    fn is_orphaned_pipe(&self, grid: &ProgramGrid, coord: Coordinate, symbol: char) -> bool {
-        let connections = self.count_connections(grid, coord, symbol);
-        connections == 0
-    }
-
-    fn count_connections(&self, grid: &ProgramGrid, coord: Coordinate, symbol: char) -> usize {
-        let mut connections = 0;
-        let adjacent_coords = [
-            (Coordinate::new(coord.x, coord.y - 1), Direction::Down),
-            (Coordinate::new(coord.x, coord.y + 1), Direction::Up),
-            (Coordinate::new(coord.x - 1, coord.y), Direction::Right),
-            (Coordinate::new(coord.x + 1, coord.y), Direction::Left),
-        ];
-
-        for (adj_coord, expected_dir) in adjacent_coords {
-            if let Some(adj_cell) = grid.get(adj_coord) {
-                if self.can_connect(symbol, adj_cell.symbol, expected_dir) {
-                    connections += 1;
-                }
-            }
+    fn validate_symbols_with_context(&self, grid: &ProgramGrid) -> Result<()> {
+        let start_count = grid.iter().filter(|(_, cell)| cell.symbol == '@').count();
+        if start_count > 1 {
+            return Err(InterpreterError::enhanced(
+                format!("Multiple start symbols (@) found: {} start symbols", start_count),
+                ErrorType::Initialization
+            ).with_suggestions(vec![
+                "Remove all but one start symbol '@'".to_string(),
+                "Choose the location where you want execution to begin".to_string(),
+            ]).with_help("A Tubular program can only have one start symbol '@'. Multiple start symbols create ambiguity about where execution should begin.".to_string()));
         }
 
-        connections
-    }
-
-    fn can_connect(&self, from_symbol: char, to_symbol: char, expected_dir: Direction) -> bool {
-        // Simplified connection logic
-        ProgramCell::is_flow_control_symbol(to_symbol) ||
-        ProgramCell::is_operator_symbol(to_symbol) ||
-        ProgramCell::is_start_symbol(to_symbol)
-    }
-
-    fn validate_io_placement(&self, grid: &ProgramGrid) -> Result<()> {
-        for (coord, cell) in grid.iter() {
-            match cell.symbol {
-                ',' | 'n' => {
-                    // Output operations should have flow control leading to them
-                    if !self.has_upstream_connection(grid, *coord) {
-                        return Err(InitError::InvalidCharacter(cell.symbol, *coord).into());
-                    }
-                }
-                '?' => {
-                    // Input operations should not be at dead ends
-                    if self.is_dead_end(grid, *coord) {
-                        return Err(InitError::InvalidCharacter(cell.symbol, *coord).into());
-                    }
-                }
-                _ => {}
-            }
-        }
         Ok(())
     }
+}
 
-    fn has_upstream_connection(&self, grid: &ProgramGrid, coord: Coordinate) -> bool {
-        let upstream = Coordinate::new(coord.x, coord.y - 1);
-        grid.get(upstream).is_some()
+impl Default for ProgramValidator {
+    fn default() -> Self {
+        Self::new()
     }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grid_parser::GridParser;
+
+    #[test]
+    fn test_undefined_labels_are_reported_at_their_call_site() {
+        let grid = GridParser::new()
+            .parse_string("@\nC\n!\n%call missing at (0, 1)\n%call missing at (0, 2)")
+            .unwrap();
+        assert_eq!(
+            grid.validate().unwrap_err(),
+            InterpreterError::Initialization(InitError::UndefinedLabel("missing".to_string(), Coordinate::new(0, 1)))
+        );
+
+        let mut validator = ProgramValidator::new().with_error_collection();
+        assert!(validator.validate_with_collection(&grid).is_err());
+        let errors = validator.get_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].context().unwrap().position.coordinate, Coordinate::new(0, 1));
+        assert!(errors[1].to_string().contains("does not point at a 'C' cell"));
+    }
+
+    #[test]
+    fn test_portals_must_come_in_pairs() {
+        let paired = "@  J\nJ\n%portal a at (0, 1)\n%portal a at (3, 0)";
+        assert!(ProgramValidator::new().validate(&GridParser::new().parse_string(paired).unwrap()).is_ok());
+
+        let grid = GridParser::new()
+            .parse_string("@  J\nJ\n|\n%portal a at (0, 1)\n%portal b at (3, 0)\n%portal b at (0, 2)")
+            .unwrap();
+        assert_eq!(
+            grid.validate().unwrap_err(),
+            InterpreterError::Initialization(InitError::UnpairedPortal("a".to_string(), 0, Coordinate::new(0, 1)))
+        );
+
+        let mut errors = ProgramValidator::new().validate_all(&grid);
+        errors.retain(|e| e.severity() == ErrorSeverity::Error);
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(messages[0].contains("needs exactly one other cell named 'a', but has 0"));
+        assert_eq!(errors[1].context().unwrap().position.coordinate, Coordinate::new(0, 2));
+        assert!(messages[1].contains("does not point at a 'J' cell"));
+    }
+
+    #[test]
+    fn test_each_strictness_level_adds_checks() {
+        // A reservoir write nothing reads is only suspicious; the stray pipe is a mistake
+        let grid = GridParser::new().parse_string("@\n1\n:\n:\n:\nP\n!\n\n  |").unwrap();
+        let validate = |strictness| {
+            let mut findings = ProgramValidator::new().with_strictness(strictness).validate_all(&grid);
+            findings.retain(|e| e.severity() == ErrorSeverity::Error);
+            findings
+        };
 
-    fn is_dead_end(&self, grid: &ProgramGrid, coord: Coordinate) -> bool {
-        let exits = self.count_exits(grid, coord);
-        exits == 0
+        assert!(validate(Strictness::Default).is_empty());
+        let strict = validate(Strictness::Strict);
+        assert!(strict.iter().any(|e| e.to_string().contains("pipe")));
+        assert!(!strict.iter().any(|e| e.to_string().contains("never read")));
+        let pedantic = validate(Strictness::Pedantic);
+        assert!(pedantic.len() > strict.len());
+        assert_eq!("pedantic".parse::<Strictness>(), Ok(Strictness::Pedantic));
+        assert!(ProgramValidator::strict().strictness() < Strictness::Pedantic);
     }
 
-    fn count_exits(&self, grid: &ProgramGrid, coord: Coordinate) -> usize {
-        let mut exits = 0;
-        let directions = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+    #[test]
+    fn test_advisory_findings_warn_unless_denied() {
+        // The pipe is never reached
+        let grid = GridParser::new().parse_string("@\n1\nn\n!\n\n  |").unwrap();
 
-        for direction in directions {
-            let next_coord = coord + direction;
-            if grid.get(next_coord).is_some() {
-                exits += 1;
-            }
-        }
+        let validator = ProgramValidator::new();
+        assert!(validator.validate(&grid).is_ok());
+        let warnings = validator.validate_all(&grid);
+        assert!(!warnings.is_empty());
+        assert!(warnings.iter().all(|w| w.severity() == ErrorSeverity::Warning));
+        assert!(warnings.iter().any(|w| w.to_string().contains("unreachable")));
 
-        exits
-    }
+        // Findings the strictness denies are errors, not warnings as well
+        let strict = ProgramValidator::strict().validate_all(&grid);
+        assert!(strict.iter().any(|e| e.severity() == ErrorSeverity::Error && e.to_string().contains("unreachable")));
+        assert!(!strict.iter().any(|e| e.severity() == ErrorSeverity::Warning && e.to_string().contains("unreachable")));
 
-    fn validate_memory_operations(&self, grid: &ProgramGrid) -> Result<()> {
-        for (coord, cell) in grid.iter() {
-            if cell.symbol == 'G' || cell.symbol == 'P' {
-                // Memory operations should have access to stack for coordinates
-                if !self.can_access_stack_coordinates(grid, *coord) {
-                    return Err(InitError::InvalidCharacter(cell.symbol, *coord).into());
-                }
-            }
-        }
-        Ok(())
+        let error = validator.with_deny_warnings(true).validate(&grid).unwrap_err();
+        assert_eq!(error.severity(), ErrorSeverity::Error);
     }
 
-    fn can_access_stack_coordinates(&self, grid: &ProgramGrid, coord: Coordinate) -> bool {
-        // Check if there are stack operations or data sources nearby
-        let nearby_coords = [
-            Coordinate::new(coord.x - 1, coord.y),
-            Coordinate::new(coord.x + 1, coord.y),
-            Coordinate::new(coord.x, coord.y - 1),
-            Coordinate::new(coord.x, coord.y + 1),
-        ];
-
-        for nearby_coord in nearby_coords {
-            if let Some(cell) = grid.get(nearby_coord) {
-                if matches!(cell.symbol, ':' | ';' | 'd' | '0'..='9') {
-                    return true;
-                }
-            }
-        }
+    struct NoOutput;
 
-        false
-    }
-
-    // Enhanced validation methods with context
-    fn validate_reachable_code_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        if grid.start.is_none() {
-            return Ok(InterpreterError::enhanced("Reachable code validation skipped - no start symbol".to_string(), ErrorType::Validation));
+    impl ValidationRule for NoOutput {
+        fn name(&self) -> &'static str {
+            "no-output"
         }
 
-        let start_pos = grid.start.unwrap();
-        let mut visited = HashSet::new();
-        let mut to_visit = vec![start_pos];
-
-        while let Some(current_pos) = to_visit.pop() {
-            if visited.contains(&current_pos) {
-                continue;
-            }
-            visited.insert(current_pos);
-
-            if let Some(cell) = grid.get(current_pos) {
-                let next_positions = self.get_next_positions(grid, current_pos, cell);
-                for next_pos in next_positions {
-                    if !visited.contains(&next_pos) {
-                        to_visit.push(next_pos);
-                    }
-                }
-            }
+        fn severity(&self) -> ErrorSeverity {
+            ErrorSeverity::Warning
         }
 
-        // In strict mode, warn about unreachable code
-        if self.strict_mode {
-            let unreachable_count = grid.size() - visited.len();
-            if unreachable_count > 0 {
-                let error = InterpreterError::enhanced(
-                    format!("Found {} unreachable code cells", unreachable_count),
-                    ErrorType::Validation
-                ).with_suggestions(vec![
-                    "Remove unused code cells".to_string(),
-                    "Connect unreachable code to the main flow".to_string(),
-                    "Add flow control to reach the isolated cells".to_string(),
-                ]).with_help("Unreachable code cannot be executed and may indicate a bug in your program logic or missing flow connections.".to_string());
-                return Err(error);
+        fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+            if grid.iter().any(|(_, cell)| matches!(cell.symbol, 'n' | ',')) {
+                return Vec::new();
             }
+            vec![LintFinding::new(self.name(), grid.start, "Program never prints".to_string())]
         }
-
-        Ok(InterpreterError::enhanced("Reachable code validation passed".to_string(), ErrorType::Validation))
     }
 
-    fn validate_strict_rules_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        // 1. Ensure no orphaned pipes
-        if let Err(e) = self.validate_no_orphaned_pipes_with_context(grid) {
-            return Err(e);
-        }
+    struct NoSevens;
 
-        // 2. Ensure proper I/O placement
-        if let Err(e) = self.validate_io_placement_with_context(grid) {
-            return Err(e);
+    impl ValidationRule for NoSevens {
+        fn name(&self) -> &'static str {
+            "no-sevens"
         }
 
-        // 3. Ensure memory operations have valid coordinates
-        if let Err(e) = self.validate_memory_operations_with_context(grid) {
-            return Err(e);
+        fn check(&self, grid: &ProgramGrid) -> Vec<LintFinding> {
+            grid.iter()
+                .filter(|(_, cell)| cell.symbol == '7')
+                .map(|(coord, _)| LintFinding::new(self.name(), Some(*coord), "Sevens are not allowed".to_string()))
+                .collect()
         }
-
-        Ok(InterpreterError::enhanced("Strict validation passed".to_string(), ErrorType::Validation))
     }
 
-    fn validate_no_orphaned_pipes_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        for (coord, cell) in grid.iter() {
-            if ProgramCell::is_flow_control_symbol(cell.symbol) {
-                if self.is_orphaned_pipe(grid, *coord, cell.symbol) {
-                    let context = self.create_error_context_for_coord(*coord);
-                    let error = InterpreterError::enhanced(
-                        format!("Orphaned flow control pipe '{}' has no connections", cell.symbol),
-                        ErrorType::Validation
-                    ).with_context(context)
-                    .with_suggestions(vec![
-                        "Add adjacent cells to connect this pipe".to_string(),
-                        "Remove the orphaned pipe if not needed".to_string(),
-                        "Check for gaps in your flow control network".to_string(),
-                    ]).with_help("Flow control pipes need to be connected to other cells to be useful. An orphaned pipe cannot guide droplets anywhere.".to_string());
-                    return Err(error);
-                }
-            }
-        }
-        Ok(InterpreterError::enhanced("Orphaned pipe validation passed".to_string(), ErrorType::Validation))
-    }
+    #[test]
+    fn test_custom_rules_run_at_their_severity() {
+        let silent = GridParser::new().parse_string("@\n7\n!").unwrap();
+        let printing = GridParser::new().parse_string("@\n1\nn\n!").unwrap();
 
-    fn validate_io_placement_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        for (coord, cell) in grid.iter() {
-            match cell.symbol {
-                ',' | 'n' => {
-                    // Output operations should have flow control leading to them
-                    if !self.has_upstream_connection(grid, *coord) {
-                        let context = self.create_error_context_for_coord(*coord);
-                        let error = InterpreterError::enhanced(
-                            format!("Output operation '{}' has no upstream connection", cell.symbol),
-                            ErrorType::Validation
-                        ).with_context(context)
-                        .with_suggestions(vec![
-                            "Add flow control leading to this output operation".to_string(),
-                            "Place the output operation in the main flow path".to_string(),
-                            "Connect this operation to upstream cells".to_string(),
-                        ]).with_help("Output operations need to be reachable by droplets to function. Ensure there's a path for droplets to reach this cell.".to_string());
-                        return Err(error);
-                    }
-                }
-                '?' => {
-                    // Input operations should not be at dead ends
-                    if self.is_dead_end(grid, *coord) {
-                        let context = self.create_error_context_for_coord(*coord);
-                        let error = InterpreterError::enhanced(
-                            "Input operation '?' is at a dead end".to_string(),
-                            ErrorType::Validation
-                        ).with_context(context)
-                        .with_suggestions(vec![
-                            "Add flow control after the input operation".to_string(),
-                            "Connect the input operation to downstream cells".to_string(),
-                            "Ensure input can flow to the rest of the program".to_string(),
-                        ]).with_help("Input operations should provide data to the rest of the program. If it's at a dead end, the input data won't be used.".to_string());
-                        return Err(error);
-                    }
-                }
-                _ => {}
-            }
-        }
-        Ok(InterpreterError::enhanced("I/O placement validation passed".to_string(), ErrorType::Validation))
-    }
+        let warning_only = ProgramValidator::new().with_rule(Box::new(NoOutput));
+        assert!(warning_only.validate(&silent).is_ok());
+        let warnings = warning_only.validate_all(&silent);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity(), ErrorSeverity::Warning);
 
-    fn validate_memory_operations_with_context(&self, grid: &ProgramGrid) -> Result<InterpreterError> {
-        for (coord, cell) in grid.iter() {
-            if cell.symbol == 'G' || cell.symbol == 'P' {
-                // Memory operations should have access to stack for coordinates
-                if !self.can_access_stack_coordinates(grid, *coord) {
-                    let context = self.create_error_context_for_coord(*coord);
-                    let operation_name = match cell.symbol {
-                        'G' => "Get (read)",
-                        'P' => "Put (write)",
-                        _ => "Memory",
-                    };
-                    let error = InterpreterError::enhanced(
-                        format!("{} memory operation '{}' cannot access stack coordinates", operation_name, cell.symbol),
-                        ErrorType::Validation
-                    ).with_context(context)
-                    .with_suggestions(vec![
-                        "Add stack operations nearby to provide coordinates".to_string(),
-                        "Place numeric literals for direct addressing".to_string(),
-                        "Move the operation closer to coordinate sources".to_string(),
-                    ]).with_help("Memory operations need coordinates from the stack or direct addressing. Ensure there's a way to provide coordinates to this operation.".to_string());
-                    return Err(error);
-                }
-            }
-        }
-        Ok(InterpreterError::enhanced("Memory operations validation passed".to_string(), ErrorType::Validation))
+        assert_eq!(NoSevens.check(&silent)[0].rule, "no-sevens");
+        let validator = warning_only.with_rule(Box::new(NoSevens));
+        assert_eq!(validator.rules().map(|rule| rule.name()).collect::<Vec<_>>(), ["no-output", "no-sevens"]);
+        let error = validator.validate(&silent).unwrap_err();
+        assert!(error.to_string().contains("Sevens are not allowed"));
+        assert_eq!(error.context().unwrap().position.coordinate, Coordinate::new(0, 1));
+        assert!(validator.validate(&printing).is_ok());
     }
 }
-
-impl Default for ProgramValidator {
-    fn default() -> Self {
-        Self::new()
-    }
-}
\ No newline at end of file
@@ -1,7 +1,7 @@
 use num_bigint::BigInt;
 use num_traits::{Zero, Signed, ToPrimitive};
-use std::ops::{Add, Sub, Mul, Div, Rem};
-use std::cmp::Ordering;
+use core::ops::{Add, Sub, Mul, Div, Rem, BitAnd, BitOr, BitXor, Not};
+use core::cmp::Ordering;
 
 #[derive(Debug, Clone)]
 pub struct TubularBigInt(pub BigInt);
@@ -61,6 +61,14 @@ impl TubularBigInt {
         TubularBigInt(self.0.abs())
     }
 
+    /// Number of decimal digits in the magnitude (estimated from the bit length for huge values)
+    pub fn decimal_digits(&self) -> usize {
+        match self.0.abs().to_u128() {
+            Some(value) => value.checked_ilog10().map_or(1, |log| log as usize + 1),
+            None => (self.0.bits() as f64 * core::f64::consts::LOG10_2) as usize + 1,
+        }
+    }
+
     pub fn to_i64(&self) -> Option<i64> {
         self.0.to_i64()
     }
@@ -70,7 +78,7 @@ impl TubularBigInt {
     }
 
     pub fn to_char(&self) -> Option<char> {
-        self.to_i64().and_then(|n| std::char::from_u32(n as u32))
+        self.to_i64().and_then(|n| core::char::from_u32(n as u32))
     }
 
     pub fn from_char(c: char) -> Self {
@@ -92,6 +100,26 @@ impl TubularBigInt {
             TubularBigInt(&self.0 % &other.0)
         }
     }
+
+    /// Bits in the magnitude; 0 for zero
+    pub fn bits(&self) -> u64 {
+        self.0.bits()
+    }
+
+    /// `self` raised to `exponent`
+    pub fn pow(&self, exponent: u32) -> Self {
+        TubularBigInt(self.0.pow(exponent))
+    }
+
+    /// `self * 2^bits`
+    pub fn shl(&self, bits: usize) -> Self {
+        TubularBigInt(&self.0 << bits)
+    }
+
+    /// `self / 2^bits`, rounded toward negative infinity as in two's complement
+    pub fn shr(&self, bits: usize) -> Self {
+        TubularBigInt(&self.0 >> bits)
+    }
 }
 
 impl Default for TubularBigInt {
@@ -122,7 +150,7 @@ impl Eq for TubularBigInt {}
 
 impl PartialOrd for TubularBigInt {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
@@ -132,12 +160,30 @@ impl Ord for TubularBigInt {
     }
 }
 
-impl std::fmt::Display for TubularBigInt {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for TubularBigInt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+/// Values are stored as decimal strings so they survive formats limited to 64-bit numbers
+#[cfg(feature = "serde")]
+impl serde::Serialize for TubularBigInt {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TubularBigInt {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = alloc::string::String::deserialize(deserializer)?;
+        text.parse::<BigInt>()
+            .map(TubularBigInt)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl Add for TubularBigInt {
     type Output = TubularBigInt;
 
@@ -176,4 +222,38 @@ impl Rem for TubularBigInt {
     fn rem(self, other: Self) -> Self::Output {
         self.safe_mod(&other)
     }
-}
\ No newline at end of file
+}
+
+// Bitwise operators treat values as two's complement with infinite sign extension
+
+impl BitAnd for TubularBigInt {
+    type Output = TubularBigInt;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        TubularBigInt(self.0 & other.0)
+    }
+}
+
+impl BitOr for TubularBigInt {
+    type Output = TubularBigInt;
+
+    fn bitor(self, other: Self) -> Self::Output {
+        TubularBigInt(self.0 | other.0)
+    }
+}
+
+impl BitXor for TubularBigInt {
+    type Output = TubularBigInt;
+
+    fn bitxor(self, other: Self) -> Self::Output {
+        TubularBigInt(self.0 ^ other.0)
+    }
+}
+
+impl Not for TubularBigInt {
+    type Output = TubularBigInt;
+
+    fn not(self) -> Self::Output {
+        TubularBigInt(!self.0)
+    }
+}
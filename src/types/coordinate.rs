@@ -1,7 +1,8 @@
-use std::cmp::{Eq, PartialEq};
-use std::fmt;
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use core::fmt;
 
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coordinate {
     pub x: isize,
     pub y: isize,
@@ -31,7 +32,7 @@ impl fmt::Display for Coordinate {
     }
 }
 
-impl std::ops::Add<Direction> for Coordinate {
+impl core::ops::Add<Direction> for Coordinate {
     type Output = Coordinate;
 
     fn add(self, direction: Direction) -> Coordinate {
@@ -39,7 +40,7 @@ impl std::ops::Add<Direction> for Coordinate {
     }
 }
 
-impl std::ops::Sub<Direction> for Coordinate {
+impl core::ops::Sub<Direction> for Coordinate {
     type Output = Coordinate;
 
     fn sub(self, direction: Direction) -> Coordinate {
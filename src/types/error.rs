@@ -1,22 +1,34 @@
-use thiserror::Error;
 use crate::types::coordinate::Coordinate;
+use crate::types::prelude::*;
+use core::fmt;
 
 /// Position information for error context
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub line: usize,
     pub column: usize,
     pub coordinate: Coordinate,
+    /// File the line and column are in, when it is not the program being
+    /// checked, as for a cell from an included file
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub file: Option<String>,
 }
 
 impl Position {
     pub fn new(line: usize, column: usize, coordinate: Coordinate) -> Self {
-        Self { line, column, coordinate }
+        Self { line, column, coordinate, file: None }
+    }
+
+    pub fn with_file(mut self, file: String) -> Self {
+        self.file = Some(file);
+        self
     }
 }
 
 /// Context information for errors
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ErrorContext {
     pub position: Position,
     pub source_line: String,
@@ -47,6 +59,7 @@ impl ErrorContext {
 
 /// Enhanced error information with context and suggestions
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ErrorInfo {
     pub message: String,
     pub error_type: ErrorType,
@@ -56,8 +69,8 @@ pub struct ErrorInfo {
     pub severity: ErrorSeverity,
 }
 
-impl std::fmt::Display for ErrorInfo {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for ErrorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {}", self.error_type, self.message)
     }
 }
@@ -97,6 +110,7 @@ impl ErrorInfo {
 
 /// Categories of errors for better organization
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ErrorType {
     Syntax,
     Validation,
@@ -107,8 +121,8 @@ pub enum ErrorType {
     Semantic,
 }
 
-impl std::fmt::Display for ErrorType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for ErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ErrorType::Syntax => write!(f, "Syntax Error"),
             ErrorType::Validation => write!(f, "Validation Error"),
@@ -123,6 +137,7 @@ impl std::fmt::Display for ErrorType {
 
 /// Error severity levels
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ErrorSeverity {
     Error,
     Warning,
@@ -130,26 +145,59 @@ pub enum ErrorSeverity {
     Hint,
 }
 
-#[derive(Error, Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterpreterError {
-    #[error("Initialization error: {0}")]
-    Initialization(#[from] InitError),
-
-    #[error("Execution error: {0}")]
-    Execution(#[from] ExecError),
-
-    #[error("System error: {0}")]
-    System(#[from] SystemError),
-
+    Initialization(InitError),
+    Execution(ExecError),
+    System(SystemError),
     /// Enhanced error with context and suggestions
-    #[error("{info}")]
     Enhanced {
         info: ErrorInfo,
-        #[source]
         source: Option<Box<InterpreterError>>,
     },
 }
 
+impl fmt::Display for InterpreterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpreterError::Initialization(error) => write!(f, "Initialization error: {error}"),
+            InterpreterError::Execution(error) => write!(f, "Execution error: {error}"),
+            InterpreterError::System(error) => write!(f, "System error: {error}"),
+            InterpreterError::Enhanced { info, .. } => write!(f, "{info}"),
+        }
+    }
+}
+
+impl core::error::Error for InterpreterError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            InterpreterError::Initialization(error) => Some(error),
+            InterpreterError::Execution(error) => Some(error),
+            InterpreterError::System(error) => Some(error),
+            InterpreterError::Enhanced { source, .. } => source.as_deref().map(|error| error as _),
+        }
+    }
+}
+
+impl From<InitError> for InterpreterError {
+    fn from(error: InitError) -> Self {
+        InterpreterError::Initialization(error)
+    }
+}
+
+impl From<ExecError> for InterpreterError {
+    fn from(error: ExecError) -> Self {
+        InterpreterError::Execution(error)
+    }
+}
+
+impl From<SystemError> for InterpreterError {
+    fn from(error: SystemError) -> Self {
+        InterpreterError::System(error)
+    }
+}
+
 impl InterpreterError {
     pub fn enhanced(message: String, error_type: ErrorType) -> Self {
         Self::Enhanced {
@@ -236,76 +284,131 @@ impl InterpreterError {
     }
 }
 
-#[derive(Error, Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InitError {
-    #[error("No start symbol (@) found in program")]
     NoStartSymbol,
-
-    #[error("Multiple start symbols (@) found in program")]
     MultipleStartSymbols,
-
-    #[error("Invalid character '{0}' at position {1}")]
     InvalidCharacter(char, Coordinate),
-
-    #[error("Grid size {0}x{1} exceeds maximum supported size of 1000x1000")]
     GridSizeExceeded(usize, usize),
+    SnapshotMismatch,
+    UndefinedLabel(String, Coordinate),
+    InvalidCallSite(Coordinate),
+    MissingLabelEntry(String, Coordinate),
+    InvalidPortalSite(Coordinate),
+    UnnamedPortal(Coordinate),
+    UnpairedPortal(String, usize, Coordinate),
 }
 
-#[derive(Error, Debug, Clone, PartialEq)]
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitError::NoStartSymbol => write!(f, "No start symbol (@) found in program"),
+            InitError::MultipleStartSymbols => write!(f, "Multiple start symbols (@) found in program"),
+            InitError::InvalidCharacter(symbol, position) => write!(f, "Invalid character '{symbol}' at position {position}"),
+            InitError::GridSizeExceeded(width, height) => write!(f, "Grid size {width}x{height} exceeds maximum supported size of 1000x1000"),
+            InitError::SnapshotMismatch => write!(f, "Snapshot was taken from a different program"),
+            InitError::UndefinedLabel(label, call) => write!(f, "Call at {call} refers to undefined label '{label}'"),
+            InitError::InvalidCallSite(position) => write!(f, "Call directive at {position} does not point at a 'C' cell"),
+            InitError::MissingLabelEntry(label, entry) => write!(f, "Label '{label}' enters at {entry}, which has no cell"),
+            InitError::InvalidPortalSite(position) => write!(f, "Portal directive at {position} does not point at a 'J' cell"),
+            InitError::UnnamedPortal(position) => write!(f, "Portal at {position} is not named by a %portal directive"),
+            InitError::UnpairedPortal(name, others, position) => write!(f, "Portal '{name}' at {position} needs exactly one other cell named '{name}', but has {others}"),
+        }
+    }
+}
+
+impl core::error::Error for InitError {}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExecError {
-    #[error("Stack underflow: attempted to pop from empty stack")]
     StackUnderflow,
-
-    #[error("Division by zero")]
     DivisionByZero,
-
-    #[error("Modulo by zero")]
     ModuloByZero,
-
-    #[error("Invalid memory access at {0}")]
     InvalidMemoryAccess(Coordinate),
-
-    #[error("Subroutine underflow: attempted to return with empty call stack")]
     SubroutineUnderflow,
-
-    #[error("Droplet collision at {0}")]
     DropletCollision(Coordinate),
-
-    #[error("Execution timeout: exceeded maximum tick limit of {0}")]
     ExecutionTimeout(u64),
-
-    #[error("Wall-clock timeout: exceeded maximum execution time of {0}ms")]
     WallClockTimeout(u64),
-
-    #[error("Soft tick limit warning: approaching maximum tick limit of {0}")]
     SoftTickLimitWarning(u64),
-
-    #[error("Soft time limit warning: approaching maximum execution time of {0}ms")]
     SoftTimeLimitWarning(u64),
-
-    #[error("Internal error: {0}")]
     InternalError(String),
-
-    #[error("Invalid operation '{0}'")]
     InvalidOperation(char),
+    CapabilityDenied(char, String),
+    DropletLimitExceeded(usize),
+    StackLimitExceeded(usize),
+    ReservoirLimitExceeded(usize),
+    LocalStackOverflow(u64, usize),
+    ShiftTooLarge(usize),
+    NegativeExponent,
+    PowerTooLarge(u64),
+    RegionTooLarge(u64),
+    InvalidCellWrite(String, Coordinate),
+    InvalidNumericInput(String),
+    EndOfInput,
 }
 
-#[derive(Error, Debug, Clone, PartialEq)]
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecError::StackUnderflow => write!(f, "Stack underflow: attempted to pop from empty stack"),
+            ExecError::DivisionByZero => write!(f, "Division by zero"),
+            ExecError::ModuloByZero => write!(f, "Modulo by zero"),
+            ExecError::InvalidMemoryAccess(position) => write!(f, "Invalid memory access at {position}"),
+            ExecError::SubroutineUnderflow => write!(f, "Subroutine underflow: attempted to return with empty call stack"),
+            ExecError::DropletCollision(position) => write!(f, "Droplet collision at {position}"),
+            ExecError::ExecutionTimeout(limit) => write!(f, "Execution timeout: exceeded maximum tick limit of {limit}"),
+            ExecError::WallClockTimeout(limit) => write!(f, "Wall-clock timeout: exceeded maximum execution time of {limit}ms"),
+            ExecError::SoftTickLimitWarning(limit) => write!(f, "Soft tick limit warning: approaching maximum tick limit of {limit}"),
+            ExecError::SoftTimeLimitWarning(limit) => write!(f, "Soft time limit warning: approaching maximum execution time of {limit}ms"),
+            ExecError::InternalError(message) => write!(f, "Internal error: {message}"),
+            ExecError::InvalidOperation(symbol) => write!(f, "Invalid operation '{symbol}'"),
+            ExecError::CapabilityDenied(symbol, flag) => write!(f, "Operation '{symbol}' is not allowed; run with {flag} to enable it"),
+            ExecError::DropletLimitExceeded(limit) => write!(f, "Droplet limit exceeded: more than {limit} droplets alive at once"),
+            ExecError::StackLimitExceeded(limit) => write!(f, "Stack limit exceeded: stack grew deeper than {limit} values"),
+            ExecError::ReservoirLimitExceeded(limit) => write!(f, "Reservoir limit exceeded: more than {limit} cells in use"),
+            ExecError::LocalStackOverflow(droplet, limit) => write!(f, "Local stack overflow: droplet {droplet} holds more than {limit} values on its own stack"),
+            ExecError::ShiftTooLarge(limit) => write!(f, "Shift too large: a non-zero value cannot be shifted left by more than {limit} bits"),
+            ExecError::NegativeExponent => write!(f, "Negative exponent: powers need an exponent of 0 or more"),
+            ExecError::PowerTooLarge(limit) => write!(f, "Power too large: the result would take more than {limit} bits"),
+            ExecError::RegionTooLarge(limit) => write!(f, "Reservoir region too large: more than {limit} cells"),
+            ExecError::InvalidCellWrite(value, position) => write!(f, "Cannot write {value} into the program at {position}: not a printable ASCII character code"),
+            ExecError::InvalidNumericInput(text) => write!(f, "Invalid numeric input: '{text}' is not an integer"),
+            ExecError::EndOfInput => write!(f, "End of input: `?` has nothing left to read"),
+        }
+    }
+}
+
+impl core::error::Error for ExecError {}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SystemError {
-    #[error("Out of memory")]
     OutOfMemory,
-
-    #[error("I/O error: {0}")]
     IoError(String),
-
-    #[error("Internal error: {0}")]
     InternalError(String),
+    InvalidSnapshot(String),
 }
 
+impl fmt::Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SystemError::OutOfMemory => write!(f, "Out of memory"),
+            SystemError::IoError(message) => write!(f, "I/O error: {message}"),
+            SystemError::InternalError(message) => write!(f, "Internal error: {message}"),
+            SystemError::InvalidSnapshot(message) => write!(f, "Invalid snapshot: {message}"),
+        }
+    }
+}
+
+impl core::error::Error for SystemError {}
+
+#[cfg(feature = "std")]
 impl From<std::io::Error> for InterpreterError {
     fn from(error: std::io::Error) -> Self {
         InterpreterError::System(SystemError::IoError(error.to_string()))
     }
 }
 
-pub type Result<T> = std::result::Result<T, InterpreterError>;
\ No newline at end of file
+pub type Result<T> = core::result::Result<T, InterpreterError>;
\ No newline at end of file
@@ -2,8 +2,13 @@ pub mod coordinate;
 pub mod direction;
 pub mod error;
 pub mod bigint;
+pub mod number;
+pub mod source_map;
+pub(crate) mod prelude;
 
 pub use coordinate::*;
 pub use direction::*;
 pub use error::*;
-pub use bigint::*;
\ No newline at end of file
+pub use bigint::*;
+pub use number::*;
+pub use source_map::*;
\ No newline at end of file
@@ -0,0 +1,416 @@
+use crate::types::bigint::TubularBigInt;
+use num_bigint::BigInt;
+#[cfg(feature = "rational")]
+use num_rational::BigRational;
+#[cfg(feature = "rational")]
+use num_traits::{Signed, Zero};
+use core::ops::{Add, Sub, Mul, Div, Rem, BitAnd, BitOr, BitXor, Not};
+use core::cmp::Ordering;
+use core::fmt;
+
+/// A droplet, stack or reservoir value
+///
+/// Values are integers unless a program asks for exact fractions with
+/// `%rational` (feature `rational`), where `D` divides without rounding. A
+/// fraction that reduces to a whole number is always stored as an
+/// [`Integer`](TubularNumber::Integer), so programs that never divide
+/// exactly only ever see integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TubularNumber {
+    Integer(TubularBigInt),
+    /// Always in lowest terms, with a denominator above 1
+    #[cfg(feature = "rational")]
+    Rational(BigRational),
+}
+
+use TubularNumber::Integer;
+
+impl TubularNumber {
+    pub fn new(value: i64) -> Self {
+        Integer(TubularBigInt::new(value))
+    }
+
+    pub fn zero() -> Self {
+        Integer(TubularBigInt::zero())
+    }
+
+    pub fn one() -> Self {
+        Integer(TubularBigInt::one())
+    }
+
+    pub fn from_bigint(value: BigInt) -> Self {
+        Integer(TubularBigInt::from_bigint(value))
+    }
+
+    pub fn from_char(c: char) -> Self {
+        Integer(TubularBigInt::from_char(c))
+    }
+
+    /// `numerator / denominator` in lowest terms; 0 if the denominator is 0
+    #[cfg(feature = "rational")]
+    pub fn fraction(numerator: BigInt, denominator: BigInt) -> Self {
+        if denominator.is_zero() {
+            return Self::zero();
+        }
+        Self::from_ratio(BigRational::new(numerator, denominator))
+    }
+
+    #[cfg(feature = "rational")]
+    fn from_ratio(ratio: BigRational) -> Self {
+        if ratio.is_integer() {
+            Integer(TubularBigInt::from_bigint(ratio.to_integer()))
+        } else {
+            TubularNumber::Rational(ratio)
+        }
+    }
+
+    #[cfg(feature = "rational")]
+    fn ratio(&self) -> BigRational {
+        match self {
+            Integer(value) => BigRational::from_integer(value.as_bigint().clone()),
+            TubularNumber::Rational(ratio) => ratio.clone(),
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Integer(_))
+    }
+
+    /// The whole part, rounded toward zero
+    pub fn to_integer(&self) -> TubularBigInt {
+        match self {
+            Integer(value) => value.clone(),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => TubularBigInt::from_bigint(ratio.to_integer()),
+        }
+    }
+
+    /// [`to_integer`](Self::to_integer) as a value
+    pub fn truncate(&self) -> Self {
+        Integer(self.to_integer())
+    }
+
+    /// Exact quotient, a fraction unless `other` divides `self`; 0 when dividing by 0
+    #[cfg(feature = "rational")]
+    pub fn exact_div(&self, other: &Self) -> Self {
+        if other.is_zero() {
+            return Self::zero();
+        }
+        Self::from_ratio(self.ratio() / other.ratio())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Integer(value) => value.is_zero(),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(_) => false,
+        }
+    }
+
+    pub fn is_positive(&self) -> bool {
+        match self {
+            Integer(value) => value.is_positive(),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => ratio.is_positive(),
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Integer(value) => value.is_negative(),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => ratio.is_negative(),
+        }
+    }
+
+    pub fn abs(&self) -> Self {
+        match self {
+            Integer(value) => Integer(value.abs()),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => TubularNumber::Rational(ratio.abs()),
+        }
+    }
+
+    /// Number of decimal digits, counting the numerator and denominator of a fraction
+    pub fn decimal_digits(&self) -> usize {
+        match self {
+            Integer(value) => value.decimal_digits(),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => {
+                TubularBigInt::from_bigint(ratio.numer().clone()).decimal_digits()
+                    + TubularBigInt::from_bigint(ratio.denom().clone()).decimal_digits()
+            }
+        }
+    }
+
+    /// The value as an `i64`; None for fractions and values out of range
+    pub fn to_i64(&self) -> Option<i64> {
+        self.as_integer()?.to_i64()
+    }
+
+    /// The value as a `usize`; None for fractions and values out of range
+    pub fn to_usize(&self) -> Option<usize> {
+        self.as_integer()?.to_usize()
+    }
+
+    pub fn to_char(&self) -> Option<char> {
+        self.as_integer()?.to_char()
+    }
+
+    /// The integer, or None for a fraction
+    pub fn as_integer(&self) -> Option<&TubularBigInt> {
+        match self {
+            Integer(value) => Some(value),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(_) => None,
+        }
+    }
+
+    /// Bits in the magnitude, or in the larger of a fraction's numerator and denominator
+    pub fn bits(&self) -> u64 {
+        match self {
+            Integer(value) => value.bits(),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => ratio.numer().bits().max(ratio.denom().bits()),
+        }
+    }
+
+    /// `self` raised to `exponent`
+    pub fn pow(&self, exponent: u32) -> Self {
+        match self {
+            Integer(value) => Integer(value.pow(exponent)),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => TubularNumber::from_ratio(BigRational::new(
+                num_traits::pow(ratio.numer().clone(), exponent as usize),
+                num_traits::pow(ratio.denom().clone(), exponent as usize),
+            )),
+        }
+    }
+
+    /// The whole part times `2^bits`
+    pub fn shl(&self, bits: usize) -> Self {
+        Integer(self.to_integer().shl(bits))
+    }
+
+    /// The whole part divided by `2^bits`, rounded toward negative infinity
+    pub fn shr(&self, bits: usize) -> Self {
+        Integer(self.to_integer().shr(bits))
+    }
+
+    pub fn safe_div(&self, other: &Self) -> Self {
+        self.clone() / other.clone()
+    }
+
+    pub fn safe_mod(&self, other: &Self) -> Self {
+        self.clone() % other.clone()
+    }
+}
+
+impl Default for TubularNumber {
+    fn default() -> Self {
+        TubularNumber::zero()
+    }
+}
+
+impl From<i64> for TubularNumber {
+    fn from(value: i64) -> Self {
+        TubularNumber::new(value)
+    }
+}
+
+impl From<BigInt> for TubularNumber {
+    fn from(value: BigInt) -> Self {
+        TubularNumber::from_bigint(value)
+    }
+}
+
+impl From<TubularBigInt> for TubularNumber {
+    fn from(value: TubularBigInt) -> Self {
+        Integer(value)
+    }
+}
+
+impl PartialOrd for TubularNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TubularNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Integer(a), Integer(b)) => a.cmp(b),
+            #[cfg(feature = "rational")]
+            _ => self.ratio().cmp(&other.ratio()),
+        }
+    }
+}
+
+/// Fractions print as `numerator/denominator`
+impl fmt::Display for TubularNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Integer(value) => write!(f, "{}", value),
+            #[cfg(feature = "rational")]
+            TubularNumber::Rational(ratio) => write!(f, "{}/{}", ratio.numer(), ratio.denom()),
+        }
+    }
+}
+
+/// Values are stored as decimal strings (`n/d` for fractions) so they
+/// survive formats limited to 64-bit numbers
+#[cfg(feature = "serde")]
+impl serde::Serialize for TubularNumber {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TubularNumber {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = alloc::string::String::deserialize(deserializer)?;
+        #[cfg(feature = "rational")]
+        if let Some((numerator, denominator)) = text.split_once('/') {
+            let parse = |part: &str| part.parse::<BigInt>().map_err(serde::de::Error::custom);
+            return Ok(TubularNumber::fraction(parse(numerator)?, parse(denominator)?));
+        }
+        text.parse::<BigInt>()
+            .map(TubularNumber::from_bigint)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Add for TubularNumber {
+    type Output = TubularNumber;
+
+    fn add(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Integer(a), Integer(b)) => Integer(a + b),
+            #[cfg(feature = "rational")]
+            (a, b) => TubularNumber::from_ratio(a.ratio() + b.ratio()),
+        }
+    }
+}
+
+impl Sub for TubularNumber {
+    type Output = TubularNumber;
+
+    fn sub(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Integer(a), Integer(b)) => Integer(a - b),
+            #[cfg(feature = "rational")]
+            (a, b) => TubularNumber::from_ratio(a.ratio() - b.ratio()),
+        }
+    }
+}
+
+impl Mul for TubularNumber {
+    type Output = TubularNumber;
+
+    fn mul(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Integer(a), Integer(b)) => Integer(a * b),
+            #[cfg(feature = "rational")]
+            (a, b) => TubularNumber::from_ratio(a.ratio() * b.ratio()),
+        }
+    }
+}
+
+/// Integers divide rounding toward zero, fractions exactly; dividing by zero gives 0
+impl Div for TubularNumber {
+    type Output = TubularNumber;
+
+    fn div(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Integer(a), Integer(b)) => Integer(a / b),
+            #[cfg(feature = "rational")]
+            (a, b) => a.exact_div(&b),
+        }
+    }
+}
+
+/// `a - b * trunc(a / b)`, so the result takes the sign of `a`; 0 for a zero divisor
+impl Rem for TubularNumber {
+    type Output = TubularNumber;
+
+    fn rem(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Integer(a), Integer(b)) => Integer(a % b),
+            #[cfg(feature = "rational")]
+            (_, b) if b.is_zero() => TubularNumber::zero(),
+            #[cfg(feature = "rational")]
+            (a, b) => {
+                let quotient = a.exact_div(&b).truncate();
+                a - b * quotient
+            }
+        }
+    }
+}
+
+// Bitwise operators work on the whole parts, as two's complement with infinite sign extension
+
+impl BitAnd for TubularNumber {
+    type Output = TubularNumber;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        Integer(self.to_integer() & other.to_integer())
+    }
+}
+
+impl BitOr for TubularNumber {
+    type Output = TubularNumber;
+
+    fn bitor(self, other: Self) -> Self::Output {
+        Integer(self.to_integer() | other.to_integer())
+    }
+}
+
+impl BitXor for TubularNumber {
+    type Output = TubularNumber;
+
+    fn bitxor(self, other: Self) -> Self::Output {
+        Integer(self.to_integer() ^ other.to_integer())
+    }
+}
+
+impl Not for TubularNumber {
+    type Output = TubularNumber;
+
+    fn not(self) -> Self::Output {
+        Integer(!self.to_integer())
+    }
+}
+
+#[cfg(all(test, feature = "rational"))]
+mod tests {
+    use super::*;
+
+    fn fraction(numerator: i64, denominator: i64) -> TubularNumber {
+        TubularNumber::fraction(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    #[test]
+    fn test_fractions_stay_exact_and_reduce_to_integers() {
+        let third = TubularNumber::new(1).exact_div(&TubularNumber::new(3));
+        assert_eq!(third, fraction(1, 3));
+        assert_eq!(third.to_string(), "1/3");
+        assert_eq!(third.clone() * TubularNumber::new(3), TubularNumber::one());
+        assert!((third.clone() + third.clone() + third.clone()).is_integer());
+        assert!(third < TubularNumber::one() && third > TubularNumber::zero());
+        assert_eq!(third.to_i64(), None);
+        assert_eq!(TubularNumber::new(4).exact_div(&TubularNumber::zero()), TubularNumber::zero());
+    }
+
+    #[test]
+    fn test_whole_parts_round_toward_zero() {
+        assert_eq!(fraction(7, 2).truncate(), TubularNumber::new(3));
+        assert_eq!(fraction(-7, 2).truncate(), TubularNumber::new(-3));
+        assert_eq!(fraction(7, 2) % TubularNumber::new(2), fraction(3, 2));
+        assert_eq!(fraction(-7, 2) % TubularNumber::new(2), fraction(-3, 2));
+        assert_eq!(fraction(2, 3).pow(2), fraction(4, 9));
+        assert_eq!(fraction(2, 3).pow(0), TubularNumber::one());
+        assert_eq!(fraction(7, 2) % TubularNumber::zero(), TubularNumber::zero());
+        assert_eq!(fraction(7, 2).safe_mod(&TubularNumber::zero()), TubularNumber::zero());
+    }
+}
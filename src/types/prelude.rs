@@ -0,0 +1,8 @@
+//! The parts of the std prelude that `alloc` provides, for modules that also
+//! build without std
+
+pub use alloc::boxed::Box;
+pub use alloc::format;
+pub use alloc::string::{String, ToString};
+pub use alloc::vec;
+pub use alloc::vec::Vec;
@@ -0,0 +1,90 @@
+use crate::types::coordinate::Coordinate;
+use crate::types::prelude::*;
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+/// Location of a grid cell in the original source text
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// Source file name (or a pseudo-name such as `<stdin>`)
+    pub file: String,
+    /// Zero-based line index in the source file
+    pub line: usize,
+    /// Zero-based column index in the source file
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn new(file: String, line: usize, column: usize) -> Self {
+        SourceLocation { file, line, column }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Rendered one-based, the way editors and compilers report positions
+        write!(f, "{}:{}:{}", self.file, self.line + 1, self.column + 1)
+    }
+}
+
+/// Mapping from grid coordinates back to source file locations
+///
+/// File names are interned so that large grids only store small per-cell entries.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<String>,
+    entries: BTreeMap<Coordinate, (usize, usize, usize)>,
+    /// Index of the program's own file, as opposed to files it includes
+    program_file: Option<usize>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name the file the program itself was read from
+    pub fn set_program_file(&mut self, file: &str) {
+        self.program_file = Some(self.intern(file));
+    }
+
+    pub fn program_file(&self) -> Option<&str> {
+        self.program_file.map(|index| self.files[index].as_str())
+    }
+
+    /// Record that the cell at `coord` came from `file` at (`line`, `column`)
+    pub fn insert(&mut self, coord: Coordinate, file: &str, line: usize, column: usize) {
+        let file_index = self.intern(file);
+        self.entries.insert(coord, (file_index, line, column));
+    }
+
+    fn intern(&mut self, file: &str) -> usize {
+        match self.files.iter().position(|f| f == file) {
+            Some(index) => index,
+            None => {
+                self.files.push(file.to_string());
+                self.files.len() - 1
+            }
+        }
+    }
+
+    /// Look up the original source location of a grid coordinate
+    pub fn lookup(&self, coord: Coordinate) -> Option<SourceLocation> {
+        self.entries.get(&coord).map(|&(file_index, line, column)| {
+            SourceLocation::new(self.files[file_index].clone(), line, column)
+        })
+    }
+
+    /// Names of all files that contributed cells to the grid
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod playground;
+
+pub use playground::*;
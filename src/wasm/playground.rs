@@ -0,0 +1,111 @@
+use crate::interpreter::execution::{ExecutionStatus, TubularInterpreter};
+use crate::operations::io::StringInput;
+use crate::parser::grid_parser::GridParser;
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+/// Parse and validate `source`, returning the program in canonical form
+#[wasm_bindgen]
+pub fn parse(source: &str) -> Result<String, JsError> {
+    let grid = GridParser::new().parse_string(source).map_err(to_js_error)?;
+    grid.validate().map_err(to_js_error)?;
+    Ok(grid.to_text())
+}
+
+/// Run `source` to completion with the default limits and return its output
+#[wasm_bindgen]
+pub fn run(source: &str, input: Option<String>) -> Result<String, JsError> {
+    let mut playground = Playground::new(source, input)?;
+    while playground.step()? {}
+    Ok(playground.output())
+}
+
+/// A program loaded in the browser, driven one tick or one batch at a time
+///
+/// ```js
+/// const program = new Playground(source, "42\n");
+/// program.onOutput(text => console.log(text));
+/// while (program.runFor(500)) await new Promise(requestAnimationFrame);
+/// ```
+#[wasm_bindgen]
+pub struct Playground {
+    interpreter: TubularInterpreter,
+    /// Called with each tick's output as it is produced
+    on_output: Option<Function>,
+}
+
+#[wasm_bindgen]
+impl Playground {
+    /// Load `source`, reading `?` and `??` input from `input` (empty when omitted)
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: &str, input: Option<String>) -> Result<Playground, JsError> {
+        let grid = GridParser::new().parse_string(source).map_err(to_js_error)?;
+        let interpreter = TubularInterpreter::new(grid).map_err(to_js_error)?
+            .with_input(StringInput::new(&input.unwrap_or_default()));
+        Ok(Playground { interpreter, on_output: None })
+    }
+
+    #[wasm_bindgen(js_name = onOutput)]
+    pub fn on_output(&mut self, callback: Function) {
+        self.on_output = Some(callback);
+    }
+
+    /// Execute one tick, returning whether the program is still running
+    pub fn step(&mut self) -> Result<bool, JsError> {
+        if !self.interpreter.is_running() {
+            return Ok(false);
+        }
+        let tick = self.interpreter.execute_tick().map_err(to_js_error)?;
+        if let (Some(text), Some(callback)) = (tick.output, &self.on_output) {
+            callback.call1(&JsValue::NULL, &JsValue::from_str(&text))
+                .map_err(|error| JsError::new(&format!("output callback failed: {:?}", error)))?;
+        }
+        Ok(self.interpreter.is_running())
+    }
+
+    /// Execute up to `ticks` ticks so the page can redraw between batches
+    #[wasm_bindgen(js_name = runFor)]
+    pub fn run_for(&mut self, ticks: u32) -> Result<bool, JsError> {
+        for _ in 0..ticks {
+            if !self.step()? {
+                return Ok(false);
+            }
+        }
+        Ok(self.interpreter.is_running())
+    }
+
+    pub fn pause(&mut self) {
+        self.interpreter.pause();
+    }
+
+    pub fn resume(&mut self) {
+        self.interpreter.resume();
+    }
+
+    /// Everything the program has output so far
+    pub fn output(&self) -> String {
+        self.interpreter.state().output.clone()
+    }
+
+    pub fn tick(&self) -> f64 {
+        self.interpreter.state().tick as f64
+    }
+
+    /// One of running, paused, completed, cancelled, tick limit, time limit, warning or error
+    pub fn status(&self) -> String {
+        match &self.interpreter.state().status {
+            ExecutionStatus::Running => "running".to_string(),
+            ExecutionStatus::Paused => "paused".to_string(),
+            ExecutionStatus::Completed => "completed".to_string(),
+            ExecutionStatus::Cancelled => "cancelled".to_string(),
+            ExecutionStatus::TickTimeout(_) => "tick limit".to_string(),
+            ExecutionStatus::WallClockTimeout(_) => "time limit".to_string(),
+            ExecutionStatus::Warning(_) => "warning".to_string(),
+            ExecutionStatus::Error(error) => format!("error: {}", error),
+        }
+    }
+}
+
+fn to_js_error(error: impl std::fmt::Display) -> JsError {
+    JsError::new(&error.to_string())
+}
@@ -0,0 +1,1180 @@
+//! End-to-end tests that run the built binary against example programs
+//! and exercise the CLI surface (trace formats, limits, validation modes)
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Run the tubular binary from the crate root with the given arguments
+fn tubular(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tubular"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Failed to run tubular")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+/// Write a throwaway program into the temp directory
+fn temp_program(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("tubular_e2e_{}_{}.tb", std::process::id(), name));
+    fs::write(&path, source).expect("Failed to write test program");
+    path
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().expect("Temp path is not valid UTF-8")
+}
+
+/// Curated examples with their expected output
+const EXAMPLES: &[(&str, &str)] = &[
+    ("examples/hello_world.tb", "2"),
+    ("examples/simple_working.tb", "2"),
+    ("examples/simple.tb", "-7"),
+    ("examples/countdown.tb", "51"),
+];
+
+#[test]
+fn test_examples_produce_expected_output() {
+    for (program, expected) in EXAMPLES {
+        let output = tubular(&[program]);
+        assert!(output.status.success(), "{} failed: {}", program, stderr(&output));
+        assert_eq!(stdout(&output), *expected, "Unexpected output from {}", program);
+    }
+}
+
+#[test]
+fn test_run_subcommand_matches_direct_execution() {
+    for (program, expected) in EXAMPLES {
+        let output = tubular(&["run", program]);
+        assert!(output.status.success(), "{} failed: {}", program, stderr(&output));
+        assert_eq!(stdout(&output), *expected);
+    }
+}
+
+#[test]
+fn test_validate_accepts_valid_program() {
+    let output = tubular(&["validate", "examples/hello_world.tb"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("[OK] Program validation passed"));
+}
+
+#[test]
+fn test_validate_rejects_invalid_character() {
+    let program = temp_program("invalid_char", "@\nQ\n!\n");
+    let output = tubular(&["validate", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Invalid character 'Q'"));
+    assert!(stderr(&output).contains("line 2, column 1"));
+}
+
+#[test]
+fn test_validate_all_errors_reports_every_problem() {
+    let program = temp_program("all_errors", "@\nQ\ny\nC\n!\n%call missing at (0, 3)\n");
+    let first = tubular(&["validate", path_str(&program)]);
+    let all = tubular(&["validate", "--all-errors", path_str(&program)]);
+    let json = tubular(&["validate", "--all-errors", "--format", "json", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(first.status.code(), Some(2));
+    assert!(!stderr(&first).contains("'y'"));
+
+    assert_eq!(all.status.code(), Some(2));
+    let report = stderr(&all);
+    assert!(report.contains("(3 errors)"));
+    assert!(report.contains("Invalid character 'Q'"));
+    assert!(report.contains("Invalid character 'y'"));
+    assert!(report.contains("undefined label 'missing'"));
+
+    let report: serde_json::Value = serde_json::from_str(&stdout(&json)).unwrap();
+    let errors: Vec<_> = report["diagnostics"].as_array().unwrap().iter()
+        .filter(|diagnostic| diagnostic["severity"] == "error")
+        .collect();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[1]["line"], 3);
+}
+
+#[test]
+fn test_validate_json_reports_diagnostics() {
+    let program = temp_program("invalid_json", "@\nQ\n!\n");
+    let invalid = tubular(&["validate", "--format", "json", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(invalid.status.code(), Some(2));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&invalid)).unwrap();
+    assert_eq!(report["valid"], false);
+    let diagnostic = &report["diagnostics"][0];
+    assert_eq!((diagnostic["code"].as_str(), diagnostic["severity"].as_str()), (Some("E001"), Some("error")));
+    assert_eq!((diagnostic["line"].as_u64(), diagnostic["column"].as_u64()), (Some(2), Some(1)));
+    assert!(diagnostic["message"].as_str().unwrap().contains("'Q'"));
+
+    let valid = tubular(&["validate", "--format", "json", "examples/hello_world.tb"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&valid)).unwrap();
+    assert_eq!(valid.status.code(), Some(0));
+    assert_eq!(report["diagnostics"], serde_json::json!([]));
+
+    // Every error is listed without --all-errors, and nothing is printed as text
+    let program = temp_program("invalid_json_twice", "@\nQ\nQ\n!\n");
+    let empty = temp_program("empty_json", "");
+    let both = tubular(&["validate", "--format", "json", path_str(&program)]);
+    let nothing = tubular(&["validate", "--format", "json", path_str(&empty)]);
+    let missing = tubular(&["validate", "--format", "sarif", "missing.tb"]);
+    fs::remove_file(&program).ok();
+    fs::remove_file(&empty).ok();
+
+    let report: serde_json::Value = serde_json::from_str(&stdout(&both)).unwrap();
+    let lines: Vec<_> = report["diagnostics"].as_array().unwrap().iter()
+        .filter(|diagnostic| diagnostic["severity"] == "error")
+        .map(|diagnostic| diagnostic["line"].as_u64())
+        .collect();
+    assert_eq!(lines, vec![Some(2), Some(3)]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&nothing)).unwrap();
+    assert_eq!(nothing.status.code(), Some(1));
+    assert_eq!(report["diagnostics"][0]["message"], "No input provided");
+    let sarif: serde_json::Value = serde_json::from_str(&stdout(&missing)).unwrap();
+    assert_eq!(missing.status.code(), Some(1));
+    assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], "E013");
+    for output in [&both, &nothing, &missing] {
+        assert_eq!(stderr(output), "");
+    }
+}
+
+#[test]
+fn test_validate_sarif_output() {
+    let program = temp_program("invalid_sarif", "@\nQ\n!\n");
+    let output = tubular(&["validate", "--format", "sarif", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(output.status.code(), Some(2));
+    let sarif: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "E001");
+    assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+}
+
+#[test]
+fn test_validation_warnings_fail_only_when_denied() {
+    let program = temp_program("warnings", "@\n1\nn\n!\n\n  |\n");
+    let relaxed = tubular(&["validate", path_str(&program)]);
+    let denied = tubular(&["validate", "--deny-warnings", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(relaxed.status.code(), Some(0));
+    assert!(stdout(&relaxed).contains("[OK] Program validation passed"));
+    assert!(stderr(&relaxed).contains(":6:3: warning: '|' is unreachable"));
+    assert_eq!(denied.status.code(), Some(2));
+    assert!(stderr(&denied).contains("Validation failed"));
+}
+
+#[test]
+fn test_deny_warnings_fails_runs_that_warn() {
+    let warned = tubular(&["--warn-digits", "3", "examples/gallery/fibonacci.tb"]);
+    let denied = tubular(&["--warn-digits", "3", "--deny-warnings", "examples/gallery/fibonacci.tb"]);
+
+    assert_eq!(warned.status.code(), Some(0));
+    assert!(stderr(&warned).contains("[WARNING]"));
+    assert_eq!(denied.status.code(), Some(3));
+    assert!(stdout(&denied).ends_with("89\n"));
+    assert!(stderr(&denied).contains("--deny-warnings"));
+}
+
+#[test]
+fn test_pedantic_validation_denies_suspicious_patterns() {
+    // The write is reachable and well-formed but never read back
+    let program = temp_program("pedantic", "\n @\n 1\n :\n :\n :\n P\n");
+    let strict = tubular(&["validate", "--strictness", "strict", path_str(&program)]);
+    let pedantic = tubular(&["validate", "--strictness", "pedantic", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(strict.status.code(), Some(0));
+    assert_eq!(pedantic.status.code(), Some(2));
+    assert!(stderr(&pedantic).contains("Reservoir write 'P' is never read"));
+}
+
+#[test]
+fn test_strict_validation_is_stricter_than_default() {
+    let program = temp_program("strict", "@\n7\n:\nA\nn\n!\n");
+    let relaxed = tubular(&["validate", path_str(&program)]);
+    let strict = tubular(&["validate", "--strict", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(relaxed.status.code(), Some(0));
+    assert_eq!(strict.status.code(), Some(2));
+}
+
+#[test]
+fn test_lint_rules_can_be_allowed_and_denied() {
+    let program = temp_program("lint", "@\n1\n!\n\n2\n");
+    let warned = tubular(&["lint", path_str(&program)]);
+    let denied = tubular(&["lint", "--deny", "unreachable-cell", "--format", "json", path_str(&program)]);
+    let allowed = tubular(&["lint", "--allow", "all", path_str(&program)]);
+    let unknown = tubular(&["lint", "--deny", "no-such-rule", path_str(&program)]);
+    // Later flags win, whether or not they name every rule
+    let allowed_last = tubular(&["lint", "--deny", "unreachable-cell", "--allow", "all", path_str(&program)]);
+    let denied_last = tubular(&["lint", "--allow", "all", "--deny", "unreachable-cell", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(warned.status.code(), Some(0));
+    assert!(stderr(&warned).contains(":5:1: warning[unreachable-cell]: '2' is unreachable"));
+
+    assert_eq!(denied.status.code(), Some(2));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&denied)).unwrap();
+    assert_eq!(report["diagnostics"][0]["rule"], "unreachable-cell");
+    assert_eq!(report["diagnostics"][0]["severity"], "error");
+
+    assert_eq!(allowed.status.code(), Some(0));
+    assert!(stdout(&allowed).contains("[OK] No lint findings"));
+    assert_eq!(unknown.status.code(), Some(1));
+    assert_eq!(allowed_last.status.code(), Some(0));
+    assert_eq!(denied_last.status.code(), Some(2));
+}
+
+#[test]
+fn test_lint_fix_rewrites_program() {
+    let program = temp_program("lint_fix", "@ Q\n1\nn\n!  \n\n  |\n");
+    let fixed = tubular(&["lint", "--fix", path_str(&program)]);
+    let content = fs::read_to_string(&program).unwrap();
+    fs::remove_file(&program).ok();
+
+    assert_eq!(fixed.status.code(), Some(0));
+    assert!(stderr(&fixed).contains("Fixed 2 cell(s)"));
+    assert_eq!(content, "@\n1\nn\n!\n");
+}
+
+#[test]
+fn test_tick_limit_stops_execution() {
+    let program = temp_program("tick_limit", "@\n9\n:\nz\nn\n!\n");
+    let limited = tubular(&["--ticks", "5", path_str(&program)]);
+    let unlimited = tubular(&[path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert!(stderr(&limited).contains("[TIMEOUT] Program execution timed out after 5 ticks"));
+    assert_eq!(limited.status.code(), Some(4));
+    assert_eq!(stdout(&limited), "");
+    assert_eq!(stdout(&unlimited), "9");
+}
+
+#[test]
+fn test_quiet_mode_prints_only_program_output() {
+    let program = temp_program("quiet", "@\n9\n:\nz\nn\n!\n");
+    let timeout = tubular(&["--quiet", "--ticks", "5", path_str(&program)]);
+    let validate = tubular(&["-q", "validate", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(timeout.status.code(), Some(4));
+    assert_eq!(stderr(&timeout), "");
+    assert_eq!(validate.status.code(), Some(0));
+    assert_eq!(stdout(&validate), "");
+}
+
+#[test]
+fn test_trace_formats() {
+    let compact = tubular(&["--trace-format", "compact", "examples/hello_world.tb"]);
+    assert!(stderr(&compact).contains("[00000] Movement D0"));
+    assert!(stderr(&compact).contains("[examples/hello_world.tb:2:1]"));
+
+    let detailed = tubular(&["--trace-format", "detailed", "examples/hello_world.tb"]);
+    assert!(stderr(&detailed).contains("║ Source: examples/hello_world.tb:2:1"));
+
+    let json = tubular(&["--trace-format", "json", "examples/hello_world.tb"]);
+    assert_eq!(stdout(&json), "2");
+    let json_out = stderr(&json);
+    let trace: serde_json::Value = serde_json::from_str(&json_out[json_out.find('{').unwrap()..=json_out.rfind('}').unwrap()]).unwrap();
+    assert_eq!(trace["version"], 1);
+    assert_eq!(trace["program"], "@\n|\n7\n2\n-\nn\n!\n");
+    assert!(trace["events"].as_array().unwrap().iter().any(|e| {
+        e["source"] == serde_json::json!({"file": "examples/hello_world.tb", "line": 2, "column": 1})
+    }));
+}
+
+#[test]
+fn test_replay_saved_trace() {
+    let trace_path = std::env::temp_dir().join(format!("tubular_e2e_{}_replay.json", std::process::id()));
+    tubular(&["--trace-format", "json", "--trace-output", path_str(&trace_path), "examples/hello_world.tb"]);
+
+    let compact = tubular(&["replay", path_str(&trace_path)]);
+    let animated = tubular(&["replay", "--format", "animate", "--delay", "0", path_str(&trace_path)]);
+    fs::remove_file(&trace_path).ok();
+
+    assert!(compact.status.success());
+    assert!(stdout(&compact).contains("[00000] Movement D0 @(0, 1) [examples/hello_world.tb:2:1]"));
+    assert!(stdout(&animated).starts_with("Tick 0  Droplets 1  [replay]\n\no\n|\n7"));
+    assert!(stdout(&animated).contains("[end of trace]"));
+    assert!(stdout(&animated).trim_end().ends_with("Output: 2"));
+
+    let invalid = tubular(&["replay", "examples/hello_world.tb"]);
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(stderr(&invalid).contains("Invalid trace file"));
+}
+
+#[test]
+fn test_trace_format_chrome() {
+    let trace_path = std::env::temp_dir().join(format!("tubular_e2e_{}_chrome.json", std::process::id()));
+    let output = tubular(&["--trace-format", "chrome", "--trace-output", path_str(&trace_path), "examples/hello_world.tb"]);
+    let contents = fs::read_to_string(&trace_path).unwrap_or_default();
+    fs::remove_file(&trace_path).ok();
+
+    assert_eq!(stdout(&output), "2");
+    let trace: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    let events = trace["traceEvents"].as_array().unwrap();
+
+    assert!(events.iter().any(|e| e["ph"] == "M" && e["args"]["name"] == "Droplet D0"));
+    assert!(events.iter().any(|e| e["name"] == "Tick 0" && e["tid"] == 1 && e["dur"] == 1000));
+    assert!(events.iter().any(|e| e["name"] == "Movement" && e["args"]["source"] == "examples/hello_world.tb:2:1"));
+}
+
+#[test]
+fn test_trace_output_file() {
+    let trace_path = std::env::temp_dir().join(format!("tubular_e2e_{}_trace.txt", std::process::id()));
+    let output = tubular(&["--trace-output", path_str(&trace_path), "examples/hello_world.tb"]);
+    let trace = fs::read_to_string(&trace_path).unwrap_or_default();
+    fs::remove_file(&trace_path).ok();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "2");
+    assert!(trace.contains("Movement"));
+}
+
+#[test]
+fn test_trace_watch() {
+    let program = temp_program("watch", "@\n7\n:\n2\n:\nA\nn\n!\n");
+    let output = tubular(&[
+        "--trace-watch", "stack depth > 1",
+        "--trace-operations", "watch",
+        path_str(&program),
+    ]);
+    fs::remove_file(&program).ok();
+
+    let text = stderr(&output);
+    assert!(text.contains("Watch stack depth > 1: 1 -> 2"), "{}", text);
+    assert!(!text.contains("Movement"));
+
+    let invalid = tubular(&["--trace-watch", "heap 3", "examples/hello_world.tb"]);
+    assert!(!invalid.status.success());
+    assert!(stderr(&invalid).contains("Unknown watch 'heap 3'"));
+}
+
+#[test]
+fn test_analyze_stack_check() {
+    let program = temp_program("analyze", "@\n7\n:\nA\nn\n!\n");
+    let flagged = tubular(&["analyze", "--stack-check", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(flagged.status.code(), Some(2));
+    assert!(stderr(&flagged).contains(":4:1: warning: Stack underflow: 'A'"));
+
+    let clean = tubular(&["analyze", "--stack-check", "examples/hello_world.tb"]);
+    assert_eq!(clean.status.code(), Some(0));
+}
+
+#[test]
+fn test_output_hexdump_modes() {
+    let text = tubular(&["--output-hexdump", "examples/simple.tb"]);
+    assert_eq!(stdout(&text), "00000000  2d 37                                             |-7|\n00000002\n");
+
+    let json = tubular(&["--output-hexdump=json", "examples/simple.tb"]);
+    assert_eq!(stdout(&json).trim(), "{\"length\": 2, \"base64\": \"LTc=\"}");
+}
+
+#[test]
+fn test_report_usage_json() {
+    let output = tubular(&["--ticks", "100", "--report-usage=json", "examples/hello_world.tb"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stderr(&output).is_empty());
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["output"], "2");
+    assert_eq!(report["resource_usage"][0], serde_json::json!({
+        "resource": "ticks", "unit": "ticks", "actual": 6, "limit": 100, "percent_used": 6.0,
+    }));
+
+    let table = tubular(&["--ticks", "100", "--report-usage", "examples/hello_world.tb"]);
+    assert_eq!(stdout(&table), "2");
+    assert!(stderr(&table).contains("ticks (ticks)"));
+}
+
+#[test]
+fn test_profile_reports_hottest_cells() {
+    let output = tubular(&["--profile=3", "examples/hello_world.tb"]);
+    assert_eq!(stdout(&output), "2");
+    let report = stderr(&output);
+    assert!(report.contains("Hottest cells (top 3):"));
+    assert!(report.contains("'@' Start"));
+}
+
+#[test]
+fn test_metrics_file_written_on_timeout() {
+    let metrics_path = std::env::temp_dir().join(format!("tubular_e2e_{}_metrics.prom", std::process::id()));
+    let output = tubular(&["--ticks", "3", "--metrics-file", path_str(&metrics_path), "examples/hello_world.tb"]);
+    let metrics = fs::read_to_string(&metrics_path).unwrap_or_default();
+    fs::remove_file(&metrics_path).ok();
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(metrics.contains("# TYPE tubular_ticks_total counter\ntubular_ticks_total 3\n"));
+    assert!(metrics.contains("tubular_droplets_created_total 1\n"));
+}
+
+#[test]
+fn test_buffered_output_flushed_on_timeout() {
+    let program = temp_program("buffering", "@\n7\nn\n!\n");
+    let buffered = tubular(&["--ticks", "3", "--output-buffering", "full", path_str(&program)]);
+    let unbuffered = tubular(&["--output-buffering", "none", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(buffered.status.code(), Some(4));
+    assert_eq!(stdout(&buffered), "7");
+    assert!(unbuffered.status.success());
+    assert_eq!(stdout(&unbuffered), "7");
+}
+
+#[test]
+fn test_run_reads_input_argument() {
+    let program = temp_program("input_arg", "@\n?\n,\n!\n");
+    let output = tubular(&["run", "--input", "Z", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Z");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ctrl_c_cancels_run_gracefully() {
+    use std::io::Read;
+
+    // The loop prints a 0 on every pass, so the first byte shows the run has started
+    let program = temp_program("ctrl_c", "\\\n@\nn\n\\\n");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+        .args(["--ticks", "100000000", "--output-buffering", "none", path_str(&program)])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to run tubular");
+    let mut stdout = child.stdout.take().unwrap();
+    let mut first = [0u8; 1];
+    stdout.read_exact(&mut first).expect("Failed to read the first output");
+    Command::new("kill").args(["-INT", &child.id().to_string()]).status().expect("Failed to send SIGINT");
+    // Keep draining stdout so the run never blocks on a full pipe
+    let mut printed = Vec::new();
+    stdout.read_to_end(&mut printed).expect("Failed to read output");
+    let output = child.wait_with_output().expect("Failed to wait for tubular");
+    fs::remove_file(&program).ok();
+
+    assert_eq!(&first, b"0");
+
+    assert_eq!(output.status.code(), Some(130));
+    assert!(stderr(&output).contains("[CANCELLED] Program interrupted after"));
+}
+
+#[test]
+fn test_pseudo_input_seed_is_reproducible() {
+    let program = temp_program("pseudo_input", "@\ni\n;\nn\n!\n");
+    let first = tubular(&["--pseudo-input-seed", "11", path_str(&program)]);
+    let second = tubular(&["--pseudo-input-seed", "11", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert!(first.status.success());
+    assert!(!stdout(&first).is_empty());
+    assert_eq!(stdout(&first), stdout(&second));
+}
+
+#[test]
+fn test_run_reads_program_from_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    for args in [&["run", "-"][..], &["-"][..]] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to start tubular");
+        child.stdin.take().unwrap().write_all(b"@\n7\nn\n!\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "7");
+    }
+}
+
+#[test]
+fn test_interactive_run_reads_piped_input_without_prompts() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let program = temp_program("interactive", "@\n?\n,\n?\n,\n!\n");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+        .args(["run", "--interactive", path_str(&program)])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start tubular");
+    child.stdin.take().unwrap().write_all(b"QR\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "QR");
+}
+
+#[test]
+fn test_input_file_feeds_program_input() {
+    let program = temp_program("input_file", "@\n?\n,\n?\n,\n!\n");
+    let input = std::env::temp_dir().join(format!("tubular_e2e_{}_input_file.input", std::process::id()));
+    fs::write(&input, "QR\n").unwrap();
+
+    let output = tubular(&["run", "--input-file", path_str(&input), path_str(&program)]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "QR");
+
+    let missing = tubular(&["run", "--input-file", "does_not_exist.input", path_str(&program)]);
+    assert!(!missing.status.success());
+    assert!(stderr(&missing).contains("does_not_exist.input"));
+}
+
+#[test]
+fn test_numbers_input_mode_rejects_non_integers() {
+    let program = temp_program("input_mode", "@\n?\n?\nn\n!\n");
+    let valid = tubular(&["--input-mode", "numbers", "run", "--input", " 42 ", path_str(&program)]);
+    let invalid = tubular(&["--input-mode", "numbers", "run", "--input", "4x2", path_str(&program)]);
+    let lenient = tubular(&["run", "--input", "4x2", path_str(&program)]);
+
+    assert!(valid.status.success(), "{}", stderr(&valid));
+    assert!(!invalid.status.success());
+    assert!(stderr(&invalid).contains("'4x2' is not an integer"), "{}", stderr(&invalid));
+    assert!(lenient.status.success());
+}
+
+#[test]
+fn test_eof_flag_picks_what_input_reads_at_the_end() {
+    let program = temp_program("eof", "@\n?\nn\n?\nn\n!\n");
+    let minus_one = tubular(&["--eof", "minus-one", "run", "--input", "A", path_str(&program)]);
+    let error = tubular(&["--eof", "error", "run", "--input", "A", path_str(&program)]);
+
+    assert!(minus_one.status.success(), "{}", stderr(&minus_one));
+    assert_eq!(stdout(&minus_one), "65-1");
+    assert!(!error.status.success());
+    assert!(stderr(&error).contains("End of input"), "{}", stderr(&error));
+}
+
+#[test]
+fn test_eval_runs_inline_program() {
+    let output = tubular(&["-e", r"@\n7\nn\n!"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "7");
+
+    let output = tubular(&["--eval", r"@\n7\nn\n!", "examples/hello_world.tb"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_missing_file_fails() {
+    let output = tubular(&["examples/does_not_exist.tb"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_repl_runs_and_reruns_after_edit() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start repl");
+    child.stdin.take().unwrap()
+        .write_all(b"@\n7\n:\n.run\n.stack\n.edit 2 9\n.run\n.stack\n.quit\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let lines: Vec<String> = stdout(&output).lines().map(String::from).collect();
+    assert_eq!(lines[1], "Stack (bottom to top): [7]");
+    assert_eq!(lines[3], "Stack (bottom to top): [9]");
+}
+
+/// Wait for `child`, killing it if it has not finished within ten seconds
+fn wait_or_kill(mut child: std::process::Child) -> Output {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while child.try_wait().unwrap().is_none() {
+        if std::time::Instant::now() > deadline {
+            child.kill().ok();
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_repl_programs_read_input_from_the_session() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start repl");
+    child.stdin.take().unwrap().write_all(b"@\n?\nn\n!\n.run\nA\n.list\n.quit\n").unwrap();
+    let output = wait_or_kill(child);
+
+    assert!(output.status.success(), "repl did not finish: {:?}", output.status);
+    let text = stdout(&output);
+    assert!(text.starts_with("65\n"), "{}", text);
+    // The input line is used by `?`, not added to the program
+    assert!(text.contains("  4 | !\n") && !text.contains("  5 |"), "{}", text);
+}
+
+fn debug_session(args: &[&str], commands: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+        .arg("debug")
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start debugger");
+    child.stdin.take().unwrap().write_all(commands.as_bytes()).unwrap();
+    wait_or_kill(child)
+}
+
+#[test]
+fn test_debugger_steps_over_input() {
+    let program = temp_program("debug_input", "@\n?\nn\n!\n");
+    // The line after the step that reaches `?` is the program's input
+    let output = debug_session(&[path_str(&program)], "step 2\nA\nstep\noutput\nquit\n");
+    fs::remove_file(&program).ok();
+
+    assert!(output.status.success(), "debugger did not finish: {:?}", output.status);
+    let text = stdout(&output);
+    assert!(text.contains("Tick 2: 1 droplet(s)\n  D0 at (0, 2) on 'n' heading v value 65"), "{}", text);
+    assert!(text.contains("Output: \"65\""), "{}", text);
+}
+
+#[test]
+fn test_debugger_steps_and_rewinds() {
+    let output = debug_session(
+        &["--history", "1", "examples/hello_world.tb"],
+        "step 2\nback\nback\nhistory\ncontinue\noutput\nquit\n",
+    );
+
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.contains("Tick 2: 1 droplet(s)\n  D0 at (0, 2) on '7' heading v value 0"));
+    assert!(text.contains("Tick 1: 1 droplet(s)\n  D0 at (0, 1) on '|'"));
+    assert!(text.contains("No earlier ticks in history"));
+    assert!(text.contains("0 tick(s) can be stepped back"));
+    assert!(text.contains("Program completed"));
+    assert!(text.contains("Output: \"2\""));
+}
+
+#[test]
+fn test_debugger_breakpoints() {
+    let output = debug_session(
+        &["examples/hello_world.tb"],
+        "break 0 3\nbreak tick 1\nbreakpoints\ndelete 1\ncontinue\ncontinue\nquit\n",
+    );
+
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.contains("Breakpoint 0: droplet at (0, 3)\nBreakpoint 1: tick 1\n"));
+    assert!(text.contains("Deleted breakpoint 1: tick 1"));
+    assert!(text.contains("Tick 3: 1 droplet(s)\n  D0 at (0, 3)"));
+    assert!(text.contains("Stopped at breakpoint: droplet at (0, 3)"));
+    assert!(text.contains("Program completed"));
+}
+
+#[test]
+fn test_debugger_watch() {
+    let program = temp_program("debug_watch", "@\n7\n:\n2\n:\nA\nn\n!\n");
+    let output = debug_session(&[path_str(&program)], "watch stack top\ncontinue\ncontinue\nquit\n");
+    fs::remove_file(&program).ok();
+
+    let text = stdout(&output);
+    assert!(text.contains("Watch 0: stack top"));
+    assert!(text.contains("Watch triggered: stack top: empty -> 7"), "{}", text);
+    assert!(text.contains("Watch triggered: stack top: 7 -> 2"));
+}
+
+#[test]
+fn test_fmt_check_and_stdout() {
+    let program = temp_program("fmt", "@  \n\t7\nn\n!\n\n");
+    let check = tubular(&["fmt", "--check", path_str(&program)]);
+    let printed = tubular(&["fmt", "--stdout", path_str(&program)]);
+    let unchanged = fs::read_to_string(&program).unwrap();
+    let rewrite = tubular(&["fmt", path_str(&program)]);
+    let recheck = tubular(&["fmt", "--check", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert_eq!(check.status.code(), Some(5));
+    assert_eq!(stdout(&printed), "@\n 7\nn\n!\n");
+    assert_eq!(unchanged, "@  \n\t7\nn\n!\n\n");
+    assert!(rewrite.status.success());
+    assert_eq!(recheck.status.code(), Some(0));
+}
+
+#[test]
+fn test_watch_prints_frames_when_piped() {
+    let output = tubular(&["watch", "examples/hello_world.tb"]);
+    let text = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(text.starts_with("Tick 0  Droplets 1  [running]\n\no\n|\n7\n"));
+    assert!(text.contains("Tick 1  Droplets 1  [running]\n\n@\no\n"));
+    assert!(text.contains("[completed]"));
+    assert!(text.trim_end().ends_with("Output: 2"));
+}
+
+#[test]
+fn test_watch_reads_program_input_from_the_input_flag() {
+    let program = temp_program("watch_input", "@\n?\nn\n!");
+    let given = tubular(&["watch", "--input", "A", path_str(&program)]);
+    let empty = tubular(&["watch", path_str(&program)]);
+    fs::remove_file(&program).ok();
+
+    assert!(given.status.success());
+    assert!(stdout(&given).trim_end().ends_with("Output: 65"));
+    // Without --input the program sees end of input rather than waiting on stdin
+    assert!(empty.status.success());
+    assert!(stdout(&empty).trim_end().ends_with("Output: 10"));
+}
+
+#[test]
+fn test_run_watch_reruns_after_edit() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let program = temp_program("run_watch", "@\n7\nn\n!");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+        .args(["run", "--watch", path_str(&program)])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start tubular");
+    // Pipes have no read timeout, so read on a thread and wait on a channel
+    // instead; a rerun that never comes then fails the test rather than
+    // hanging it
+    let mut output = child.stdout.take().unwrap();
+    let (bytes, received) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut byte = [0u8];
+        while output.read_exact(&mut byte).is_ok() && bytes.send(byte[0]).is_ok() {}
+    });
+    let mut next_byte = || received.recv_timeout(std::time::Duration::from_secs(10));
+
+    let first = next_byte();
+    if first.is_ok() {
+        fs::write(&program, "@\n9\nn\n!\n").unwrap();
+    }
+    let second = next_byte();
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    fs::remove_file(&program).ok();
+    assert_eq!(first, Ok(b'7'), "no output from the first run");
+    assert_eq!(second, Ok(b'9'), "no rerun after the edit");
+}
+
+#[test]
+fn test_symbol_map_remaps_characters() {
+    let dir = std::env::temp_dir().join(format!("tubular_dialect_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("tubular.toml"), "[symbols]\n\"Ø\" = \"!\"\n").unwrap();
+    fs::write(dir.join("seven.tb"), "@\n7\nn\nØ\n").unwrap();
+    let map = temp_program("conflicting_map", "[symbols]\n\"n\" = \"!\"\n");
+
+    let project = tubular(&["run", path_str(&dir.join("seven.tb"))]);
+    let conflicting = tubular(&["--symbol-map", path_str(&map), "run", path_str(&dir.join("seven.tb"))]);
+    let plain = tubular(&["--eval", "@\\n7\\nn\\nØ"]);
+    fs::remove_dir_all(&dir).ok();
+    fs::remove_file(&map).ok();
+
+    assert_eq!(stdout(&project), "7");
+    assert_ne!(conflicting.status.code(), Some(0));
+    assert!(stderr(&conflicting).contains("'n' is already a Tubular symbol"));
+    assert!(stderr(&plain).contains("Invalid character 'Ø'"));
+}
+
+#[test]
+fn test_example_gallery_lists_prints_and_runs() {
+    let listed = tubular(&["examples"]);
+    assert!(listed.status.success());
+    assert!(stdout(&listed).lines().any(|line| line.starts_with("fibonacci ")));
+
+    let printed = tubular(&["examples", "counter"]);
+    assert_eq!(stdout(&printed), fs::read_to_string("examples/gallery/counter.tb").unwrap());
+
+    let run = tubular(&["examples", "counter", "--run"]);
+    assert_eq!(stdout(&run), "1\n2\n3\n4\n5\n");
+    assert!(!tubular(&["examples", "missing"]).status.success());
+}
+
+#[test]
+fn test_inspect_reports_program_structure() {
+    let output = tubular(&["inspect", "examples/gallery/counter.tb"]);
+    let text = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(text.contains("Grid size: 10x9\n"));
+    assert!(text.contains("Reachable cells: 18 of 18 (100.0%)"));
+    assert!(text.contains("  count at (2, 0) heading down\n"));
+
+    let json = tubular(&["inspect", "--format", "json", "examples/memory_test_simple.tb"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&json)).unwrap();
+    assert_eq!(report["symbols"]["G"], 3);
+    assert_eq!(report["memory_operations"][0]["symbol"], "P");
+
+    let json = tubular(&["inspect", "--format", "json", "examples/gallery/counter.tb"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&json)).unwrap();
+    assert_eq!(report["labels"][0]["heading"], "down");
+}
+
+#[test]
+fn test_graph_exports_dot() {
+    let output = tubular(&["graph", "--format", "dot", "examples/gallery/counter.tb"]);
+    let dot = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(dot.starts_with("digraph program {\n"));
+    assert!(dot.contains("\"0,0\" -> \"0,1\";"));
+    assert!(dot.contains("\"2,7\" -> \"2,6\" [label=\"= 0\"];"));
+    assert!(dot.trim_end().ends_with('}'));
+}
+
+#[test]
+fn test_errors_in_included_files_report_the_included_file() {
+    let dir = std::env::temp_dir().join(format!("tubular_e2e_{}_source_map", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("main.tb"), "\n @\n%include \"lib.tb\" at (1, 2)\n").unwrap();
+    fs::write(dir.join("lib.tb"), "1\nX\n!\n").unwrap();
+    fs::write(dir.join("check.tb"), "\n @\n%include \"unreachable.tb\" at (1, 2)\n").unwrap();
+    fs::write(dir.join("unreachable.tb"), "!\n\n7\n").unwrap();
+
+    let run = tubular(&[path_str(&dir.join("main.tb"))]);
+    let validate = tubular(&["validate", "--strictness", "pedantic", "--format", "json", path_str(&dir.join("check.tb"))]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(run.status.code(), Some(3));
+    assert!(stderr(&run).contains("lib.tb:2:1"), "{}", stderr(&run));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&validate)).unwrap();
+    let diagnostic = &report["diagnostics"][0];
+    assert!(diagnostic["file"].as_str().unwrap().ends_with("unreachable.tb"), "{}", report);
+    assert_eq!(diagnostic["line"], 3);
+}
+
+#[test]
+fn test_minify_packs_programs_and_checks_golden_tests() {
+    let program = temp_program("minify", "\n\n   @    7\n   C    R\n   n\n   !\n%label seven at (8, 2)\n%call seven at (3, 3)\n");
+    fs::write(program.with_extension("expected"), "7").unwrap();
+    let minified = tubular(&["minify", "--test", path_str(&program)]);
+    fs::write(program.with_extension("expected"), "8").unwrap();
+    let failing = tubular(&["minify", "--test", path_str(&program)]);
+    fs::remove_file(program.with_extension("expected")).unwrap();
+
+    assert!(minified.status.success(), "{}", stderr(&minified));
+    assert_eq!(stdout(&minified), "@7\nCR\nn\n!\n%label seven at (1, 0) heading down\n%call seven at (0, 1)\n");
+    assert!(stderr(&minified).contains("9x6 -> 2x4"));
+    assert_eq!(failing.status.code(), Some(5));
+    assert!(stderr(&failing).contains("fails the golden test"));
+}
+
+#[test]
+fn test_transform_mirrors_programs_and_refuses_untranslatable_ones() {
+    let program = temp_program("transform", "@ 7\nC R\nn\n!\n%label seven at (2, 0)\n%call seven at (0, 1)\n");
+    let mirrored = tubular(&["transform", "--mirror-h", path_str(&program)]);
+    let mirrored_program = temp_program("transform_mirrored", &stdout(&mirrored));
+    let run = tubular(&[path_str(&mirrored_program)]);
+    let rotated = tubular(&["transform", "--rotate", "90", path_str(&program)]);
+    let rotated_program = temp_program("transform_rotated", &stdout(&rotated));
+    let rotated_run = tubular(&[path_str(&rotated_program)]);
+    let turning = temp_program("transform_turning", "@\n/7\n n\n !\n");
+    let refused = tubular(&["transform", "--rotate", "90", path_str(&turning)]);
+    for file in [&program, &mirrored_program, &rotated_program, &turning] {
+        fs::remove_file(file).ok();
+    }
+
+    assert!(mirrored.status.success());
+    assert!(stdout(&mirrored).starts_with("7 @\nR C\n  n\n  !\n"));
+    assert_eq!(stdout(&run), "7");
+    assert!(rotated.status.success(), "{}", stderr(&rotated));
+    assert!(stdout(&rotated).contains("%start heading left\n"));
+    assert_eq!(stdout(&rotated_run), "7");
+    assert!(!refused.status.success());
+    assert!(stderr(&refused).contains("cannot be rotated by 90 degrees"), "{}", stderr(&refused));
+    assert!(stderr(&refused).contains("'/' at (0, 1)"));
+}
+
+#[test]
+fn test_compile_emits_c_and_refuses_dynamic_programs() {
+    let program = temp_program("compile", "@ 7\nC R\nn\n:\n:\nM\nn\n!\n%label seven at (2, 0)\n%call seven at (0, 1)\n");
+    let source = program.with_extension("c");
+    let compiled = tubular(&["compile", "--emit", "c", "-o", path_str(&source), path_str(&program)]);
+    let dynamic = temp_program("compile_dynamic", "@\n9\n:\n1\n:\nr\nn\n!\n");
+    let refused = tubular(&["compile", path_str(&dynamic)]);
+
+    assert!(compiled.status.success(), "{}", stderr(&compiled));
+    let c = fs::read_to_string(&source).unwrap();
+    assert!(c.contains("int main(void)"));
+    assert!(c.contains("call(0);"));
+    assert!(!refused.status.success());
+    assert!(stderr(&refused).contains("'r' (random numbers) at (0, 5)"));
+
+    // Build and run the result where a C compiler is installed
+    let binary = program.with_extension("bin");
+    let built = Command::new("cc").args(["-O2", "-o", path_str(&binary), path_str(&source)]).status();
+    if built.is_ok_and(|status| status.success()) {
+        let run = Command::new(&binary).output().unwrap();
+        assert_eq!(stdout(&run), "749");
+    }
+}
+
+#[test]
+fn test_opt_report_lists_constant_folds() {
+    let program = temp_program("opt_report", "@\n7\n:\n3\n:\nA\nn\n!\n");
+    let plain = tubular(&[path_str(&program)]);
+    let optimized = tubular(&["--opt-report", path_str(&program)]);
+
+    assert!(optimized.status.success(), "{}", stderr(&optimized));
+    assert_eq!(stdout(&optimized), stdout(&plain));
+    assert!(stdout(&optimized).contains("10"));
+    assert!(stderr(&optimized).contains("Optimizations: 1 constant fold\n  (0, 1) heading v: 7:3:A -> 10 (5 cells)\n"), "{}", stderr(&optimized));
+}
+
+#[test]
+fn test_schedule_option_accepts_documented_orders() {
+    let program = temp_program("schedule", "@\n7\nn\n!\n");
+    for order in ["fifo", "id", "position"] {
+        let run = tubular(&["--schedule", order, path_str(&program)]);
+        assert!(run.status.success(), "{}", stderr(&run));
+        assert!(stdout(&run).contains('7'));
+    }
+    let unknown = tubular(&["--schedule", "random", path_str(&program)]);
+    assert!(!unknown.status.success());
+    let conflicting = tubular(&["--schedule", "id", "--deterministic", path_str(&program)]);
+    assert!(!conflicting.status.success());
+}
+
+#[test]
+fn test_local_stacks_need_their_flag() {
+    let program = temp_program("local_stacks", "@\n7\n{\n3\n}\nn\n!\n");
+    let enabled = tubular(&["--local-stacks", path_str(&program)]);
+    let disabled = tubular(&[path_str(&program)]);
+
+    assert!(enabled.status.success(), "{}", stderr(&enabled));
+    assert_eq!(stdout(&enabled).trim(), "7");
+    assert!(!disabled.status.success());
+    assert!(stderr(&disabled).contains("run with --local-stacks"), "{}", stderr(&disabled));
+}
+
+#[test]
+fn test_multi_digit_flag_joins_digits() {
+    let program = temp_program("multi_digit", "@\n2\n5\n6\nn\n!\n");
+    let joined = tubular(&["--multi-digit", path_str(&program)]);
+    let single = tubular(&[path_str(&program)]);
+
+    assert!(joined.status.success(), "{}", stderr(&joined));
+    assert_eq!(stdout(&joined).trim(), "256");
+    assert_eq!(stdout(&single).trim(), "6");
+}
+
+#[test]
+fn test_character_literals_print_text() {
+    let program = temp_program("quote", "@\n1\n\\'H,'i,' ,'!,!\n");
+    let run = tubular(&[path_str(&program)]);
+    let validate = tubular(&["validate", path_str(&program)]);
+
+    assert!(run.status.success(), "{}", stderr(&run));
+    assert_eq!(stdout(&run), "Hi !");
+    assert!(validate.status.success(), "{}", stderr(&validate));
+}
+
+#[test]
+fn test_region_fill_is_read_back_with_get() {
+    // Fills x=1, y=1, 3 wide and 2 high with 7, then reads (3, 2) and (4, 0)
+    let program = temp_program("region", "@\n1\n:\n:\n3\n:\n2\n:\n7\nF\n2\n:\n3\nG\nn\n0\n:\n4\nG\nn\n!\n");
+    let run = tubular(&[path_str(&program)]);
+
+    assert!(run.status.success(), "{}", stderr(&run));
+    assert_eq!(stdout(&run), "70");
+}
+
+#[test]
+fn test_self_modifying_programs_need_permission() {
+    // `p` writes an `n` into the blank cell below the `7` before the droplet reaches it
+    let program = temp_program("self_modify", "@\n0\n:\n9\n:\n'\nn\np\n7\n");
+    let allowed = tubular(&["--allow-self-modify", path_str(&program)]);
+    let denied = tubular(&[path_str(&program)]);
+
+    assert!(allowed.status.success(), "{}", stderr(&allowed));
+    assert_eq!(stdout(&allowed), "7");
+    assert!(!denied.status.success());
+    assert!(stderr(&denied).contains("--allow-self-modify"));
+}
+
+#[test]
+fn test_collision_policy_flag_decides_how_droplets_collide() {
+    // The start droplet picks up 3 and the other droplet 4; they meet on the `^`
+    let program = temp_program("collisions", "   !\n   n\n@3-^-4-\n%start heading right\n%droplet at (6, 2) heading left\n");
+    let run = |policy: &str| tubular(&["--collisions", policy, "--verbose", path_str(&program)]);
+
+    let destroyed = run("destroy-all");
+    assert!(destroyed.status.success(), "{}", stderr(&destroyed));
+    assert_eq!(stdout(&destroyed), "");
+    assert!(stderr(&destroyed).contains("Collision at (3, 2) (destroy-all) - 2 destroyed"), "{}", stderr(&destroyed));
+    let merged = run("merge-product");
+    assert_eq!(stdout(&merged), "12");
+    assert!(stderr(&merged).contains("droplet 0 carries on, 1 destroyed"), "{}", stderr(&merged));
+    assert_eq!(stdout(&run("merge-sum")), "7");
+    assert_eq!(stdout(&run("keep-oldest")), "3");
+    let bounced = run("bounce");
+    assert_eq!(stdout(&bounced), "");
+    assert!(stderr(&bounced).contains("(bounce) - 2 bounced"), "{}", stderr(&bounced));
+
+    let unknown = run("sideways");
+    assert!(!unknown.status.success());
+    assert!(stderr(&unknown).contains("merge-sum"));
+}
+
+#[test]
+fn test_tick_rate_flag_needs_a_positive_rate() {
+    let program = temp_program("tick_rate", "@\n7\nn\n!\n");
+    let paced = tubular(&["--tick-rate", "40", path_str(&program)]);
+    let stopped = tubular(&["--tick-rate", "0", path_str(&program)]);
+
+    assert!(paced.status.success(), "{}", stderr(&paced));
+    assert_eq!(stdout(&paced), "7");
+    assert!(!stopped.status.success());
+    assert!(stderr(&stopped).contains("positive number of ticks per second"));
+}
+
+#[test]
+fn test_benchmark_measurement_time_must_be_finite() {
+    for bad in ["inf", "NaN", "-1"] {
+        let flag = format!("--measurement-time={}", bad);
+        let output = tubular(&["benchmark", &flag, "examples/hello_world.tb"]);
+        assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+        assert!(stderr(&output).contains("non-negative number of seconds"), "{}", stderr(&output));
+    }
+}
+
+#[test]
+fn test_golden_test_runner() {
+    let dir = std::env::temp_dir().join(format!("tubular_e2e_{}_golden", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("pass.tb"), "@\n7\nn\n!\n").unwrap();
+    fs::write(dir.join("pass.expected"), "7\n").unwrap();
+    fs::write(dir.join("echo.tub"), "@\n?\n,\n!\n").unwrap();
+    fs::write(dir.join("echo.input"), "Q\n").unwrap();
+    fs::write(dir.join("echo.expected"), "Q").unwrap();
+    fs::write(dir.join("fail.tb"), "@\n3\nn\n!\n").unwrap();
+    fs::write(dir.join("fail.expected"), "4").unwrap();
+
+    let failing = tubular(&["test", path_str(&dir)]);
+    let update = tubular(&["test", "--update", path_str(&dir.join("fail.tb"))]);
+    let updated = fs::read_to_string(dir.join("fail.expected")).unwrap();
+    let passing = tubular(&["test", path_str(&dir)]);
+    fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(failing.status.code(), Some(5));
+    let report = stdout(&failing);
+    assert!(report.contains(&format!("PASS {}", path_str(&dir.join("echo.tub")))), "{}", report);
+    assert!(report.contains(&format!("PASS {}", path_str(&dir.join("pass.tb")))));
+    assert!(report.contains(&format!("FAIL {}\n       1 - 4\n       1 + 3\n", path_str(&dir.join("fail.tb")))));
+    assert!(report.ends_with("\n2 passed, 1 failed\n"));
+
+    assert!(update.status.success());
+    assert_eq!(updated, "3");
+    assert!(passing.status.success());
+    assert!(stdout(&passing).ends_with("\n3 passed, 0 failed\n"));
+}
+
+#[test]
+fn test_program_arguments_after_double_dash() {
+    // Reads argument 1 as a number, then the argument count
+    let number = temp_program("args_number", "@\n1\n:\n0\n:\na\nn\n!\n");
+    let count = temp_program("args_count", "@\n:\n:\na\nn\n!\n");
+
+    assert_eq!(stdout(&tubular(&[path_str(&number), "--", "42", "x"])).trim(), "42");
+    assert_eq!(stdout(&tubular(&["run", path_str(&count), "--", "a", "b", "c"])).trim(), "3");
+    assert_eq!(stdout(&tubular(&[path_str(&count)])).trim(), "0");
+
+    fs::remove_file(number).ok();
+    fs::remove_file(count).ok();
+}
+
+#[test]
+fn test_time_limit_and_soft_limit_flags() {
+    let looping = temp_program("time_limit_loop", "@\n|\n");
+
+    let timed = tubular(&["--wrap", "--ticks", "100000000", "--time-limit", "50", path_str(&looping)]);
+    assert_eq!(timed.status.code(), Some(4));
+    assert!(stderr(&timed).contains("50ms"));
+
+    let warned = tubular(&["--wrap", "--ticks", "10", "--soft-limits", path_str(&looping)]);
+    assert!(stderr(&warned).contains("80% of the tick limit"));
+    let quiet = tubular(&["--wrap", "--ticks", "10", path_str(&looping)]);
+    assert!(!stderr(&quiet).contains("80%"));
+
+    fs::remove_file(looping).ok();
+}
+
+#[test]
+fn test_json_execution_report() {
+    let output = tubular(&["--output-format", "json", "examples/hello_world.tb"]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).expect("report is JSON");
+    assert_eq!(report["status"], "completed");
+    assert_eq!(report["output"], "2");
+    assert_eq!(report["exit_code"], 0);
+    assert!(report["ticks"].as_u64().unwrap() > 0);
+    assert!(report["warnings"].as_array().unwrap().is_empty());
+
+    let failing = temp_program("json_report_error", "@\nX\n!\n");
+    let output = tubular(&["--output-format", "json", path_str(&failing)]);
+    assert_eq!(output.status.code(), Some(3));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).expect("report is JSON");
+    assert_eq!(report["status"], "error");
+    assert!(report["error"].as_str().unwrap().contains("--allow-fs"));
+    fs::remove_file(failing).ok();
+
+    // Programs that never start are reported the same way, with nothing on stderr
+    let unparsable = temp_program("json_report_parse_error", "@\n\u{e9}\n!\n");
+    let headless = temp_program("json_report_init_error", "7\nn\n!\n");
+    for (program, code) in [(unparsable.clone(), 2), (headless.clone(), 2), ("missing.tb".into(), 1)] {
+        let output = tubular(&["--output-format", "json", path_str(&program)]);
+        assert_eq!(output.status.code(), Some(code), "{}", stderr(&output));
+        let report: serde_json::Value = serde_json::from_str(&stdout(&output)).expect("report is JSON");
+        assert_eq!((report["status"].as_str(), report["exit_code"].as_i64()), (Some("error"), Some(code as i64)));
+        assert!(!report["error"].as_str().unwrap().is_empty());
+        assert_eq!(stderr(&output), "");
+    }
+    fs::remove_file(unparsable).ok();
+    fs::remove_file(headless).ok();
+}
+
+#[test]
+fn test_traces_and_diagnostics_stay_off_stdout() {
+    let output = tubular(&["--trace", "--verbose", "examples/hello_world.tb"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "2");
+    assert!(!stderr(&output).is_empty());
+
+    let quiet = tubular(&["--quiet", "--ticks", "2", "examples/hello_world.tb"]);
+    assert_eq!(quiet.status.code(), Some(4));
+    assert!(stdout(&quiet).is_empty() && stderr(&quiet).is_empty());
+}
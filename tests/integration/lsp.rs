@@ -0,0 +1,118 @@
+//! Drives `tubular lsp` over stdio with framed JSON-RPC messages
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tubular"))
+            .arg("lsp")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to start language server");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Client { child, stdin, stdout }
+    }
+
+    fn send(&mut self, body: &str) {
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> String {
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+            let header = header.trim();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+        }
+        let mut body = vec![0; length];
+        self.stdout.read_exact(&mut body).unwrap();
+        String::from_utf8(body).unwrap()
+    }
+
+    fn initialize(&mut self) {
+        self.send(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#);
+        let response = self.receive();
+        assert!(response.contains("\"hoverProvider\":true"), "{}", response);
+        self.send(r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#);
+    }
+
+    fn shutdown(mut self) {
+        self.send(r#"{"jsonrpc":"2.0","id":99,"method":"shutdown"}"#);
+        self.receive();
+        self.send(r#"{"jsonrpc":"2.0","method":"exit"}"#);
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+fn did_open(text: &str) -> String {
+    format!(
+        r#"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"file:///tmp/test.tb","languageId":"tubular","version":1,"text":"{}"}}}}}}"#,
+        text.replace('\n', "\\n")
+    )
+}
+
+#[test]
+fn test_lsp_publishes_diagnostics_for_invalid_character() {
+    let mut client = Client::start();
+    client.initialize();
+
+    client.send(&did_open("@\nQ\n!"));
+    let diagnostics = client.receive();
+    assert!(diagnostics.contains("textDocument/publishDiagnostics"));
+    assert!(diagnostics.contains("Invalid character 'Q'"));
+    assert!(diagnostics.contains(r#""start":{"character":0,"line":1}"#), "{}", diagnostics);
+
+    client.shutdown();
+}
+
+#[test]
+fn test_lsp_places_stack_check_warnings_on_their_cell() {
+    let mut client = Client::start();
+    client.initialize();
+
+    client.send(&did_open("  @\n  7\n  :\n  A\n  n\n  !"));
+    let diagnostics = client.receive();
+    assert!(diagnostics.contains("Stack underflow: 'A'"), "{}", diagnostics);
+    assert!(
+        diagnostics.contains(r#""range":{"end":{"character":3,"line":3},"start":{"character":2,"line":3}}"#),
+        "{}", diagnostics
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn test_lsp_hover_and_definition() {
+    let mut client = Client::start();
+    client.initialize();
+
+    client.send(&did_open(" @\n 7\n :\n n\n !"));
+    let diagnostics = client.receive();
+    assert!(diagnostics.contains(r#""diagnostics":[]"#), "{}", diagnostics);
+
+    client.send(r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/hover","params":{"textDocument":{"uri":"file:///tmp/test.tb"},"position":{"line":2,"character":1}}}"#);
+    let hover = client.receive();
+    assert!(hover.contains("Push: pushes the droplet value onto the stack"), "{}", hover);
+
+    client.send(r#"{"jsonrpc":"2.0","id":3,"method":"textDocument/definition","params":{"textDocument":{"uri":"file:///tmp/test.tb"},"position":{"line":4,"character":1}}}"#);
+    let definition = client.receive();
+    assert!(definition.contains(r#""start":{"character":1,"line":0}"#), "{}", definition);
+
+    client.shutdown();
+}
@@ -36,7 +36,7 @@ P
     // Verify the reservoir contains the stored value at (5, 10)
     let reservoir = &interpreter.state().reservoir;
     let coord = tubular::interpreter::memory::ReservoirCoordinate::new(5, 10);
-    assert_eq!(reservoir.get(coord), tubular::types::bigint::TubularBigInt::new(42));
+    assert_eq!(reservoir.get(coord), tubular::types::number::TubularNumber::new(42));
 }
 
 #[test]
@@ -74,7 +74,7 @@ G
     let droplets = &interpreter.state().droplets;
     assert!(!droplets.is_empty(), "Should have at least one droplet");
     let last_droplet = &droplets[droplets.len() - 1];
-    assert_eq!(last_droplet.value, tubular::types::bigint::TubularBigInt::new(42));
+    assert_eq!(last_droplet.value, tubular::types::number::TubularNumber::new(42));
 }
 
 #[test]
@@ -108,7 +108,7 @@ G
     let droplets = &interpreter.state().droplets;
     assert!(!droplets.is_empty(), "Should have at least one droplet");
     let last_droplet = &droplets[droplets.len() - 1];
-    assert_eq!(last_droplet.value, tubular::types::bigint::TubularBigInt::zero());
+    assert_eq!(last_droplet.value, tubular::types::number::TubularNumber::zero());
 }
 
 #[test]
@@ -152,12 +152,12 @@ G
     let droplets = &interpreter.state().droplets;
     assert!(!droplets.is_empty(), "Should have at least one droplet");
     let last_droplet = &droplets[droplets.len() - 1];
-    assert_eq!(last_droplet.value, tubular::types::bigint::TubularBigInt::new(99));
+    assert_eq!(last_droplet.value, tubular::types::number::TubularNumber::new(99));
 
     // Verify reservoir contains the overwritten value
     let reservoir = &interpreter.state().reservoir;
     let coord = tubular::interpreter::memory::ReservoirCoordinate::new(5, 10);
-    assert_eq!(reservoir.get(coord), tubular::types::bigint::TubularBigInt::new(99));
+    assert_eq!(reservoir.get(coord), tubular::types::number::TubularNumber::new(99));
 }
 
 #[test]
@@ -196,12 +196,12 @@ G
     let droplets = &interpreter.state().droplets;
     assert!(!droplets.is_empty(), "Should have at least one droplet");
     let last_droplet = &droplets[droplets.len() - 1];
-    assert_eq!(last_droplet.value, tubular::types::bigint::TubularBigInt::new(42));
+    assert_eq!(last_droplet.value, tubular::types::number::TubularNumber::new(42));
 
     // Verify reservoir contains the value at negative coordinates
     let reservoir = &interpreter.state().reservoir;
     let coord = tubular::interpreter::memory::ReservoirCoordinate::new(-5, -10);
-    assert_eq!(reservoir.get(coord), tubular::types::bigint::TubularBigInt::new(42));
+    assert_eq!(reservoir.get(coord), tubular::types::number::TubularNumber::new(42));
 }
 
 #[test]
@@ -240,7 +240,7 @@ G
     let droplets = &interpreter.state().droplets;
     assert!(!droplets.is_empty(), "Should have at least one droplet");
     let last_droplet = &droplets[droplets.len() - 1];
-    assert_eq!(last_droplet.value, tubular::types::bigint::TubularBigInt::new(12345));
+    assert_eq!(last_droplet.value, tubular::types::number::TubularNumber::new(12345));
 }
 
 #[test]
@@ -288,10 +288,10 @@ G
     let reservoir = &interpreter.state().reservoir;
 
     let coord1 = tubular::interpreter::memory::ReservoirCoordinate::new(5, 10);
-    assert_eq!(reservoir.get(coord1), tubular::types::bigint::TubularBigInt::new(42));
+    assert_eq!(reservoir.get(coord1), tubular::types::number::TubularNumber::new(42));
 
     let coord2 = tubular::interpreter::memory::ReservoirCoordinate::new(15, 20);
-    assert_eq!(reservoir.get(coord2), tubular::types::bigint::TubularBigInt::new(99));
+    assert_eq!(reservoir.get(coord2), tubular::types::number::TubularNumber::new(99));
 }
 
 #[test]
@@ -323,7 +323,7 @@ P
     // Verify the value was stored at (42, 0) due to stack underflow (y=0)
     let reservoir = &interpreter.state().reservoir;
     let coord = tubular::interpreter::memory::ReservoirCoordinate::new(42, 0);
-    assert_eq!(reservoir.get(coord), tubular::types::bigint::TubularBigInt::new(42));
+    assert_eq!(reservoir.get(coord), tubular::types::number::TubularNumber::new(42));
 }
 
 #[test]
@@ -7,4 +7,6 @@
 
 // Working integration tests
 pub mod basic_functionality;
-pub mod smoke_test;
\ No newline at end of file
+pub mod smoke_test;
+pub mod examples;
+pub mod lsp;
\ No newline at end of file
@@ -611,10 +611,10 @@ fn test_subroutine_operations_direction_conversion() {
     assert_eq!(SubroutineOperations::direction_to_value(Direction::Left).to_isize(), Some(3));
 
     // Test value to direction conversion
-    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::bigint::TubularBigInt::new(0)), Direction::Up);
-    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::bigint::TubularBigInt::new(1)), Direction::Right);
-    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::bigint::TubularBigInt::new(2)), Direction::Down);
-    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::bigint::TubularBigInt::new(3)), Direction::Left);
+    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::number::TubularNumber::new(0)), Direction::Up);
+    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::number::TubularNumber::new(1)), Direction::Right);
+    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::number::TubularNumber::new(2)), Direction::Down);
+    assert_eq!(SubroutineOperations::value_to_direction(&tubular::types::number::TubularNumber::new(3)), Direction::Left);
 
     // Test roundtrip conversion
     for direction in [Direction::Up, Direction::Right, Direction::Down, Direction::Left] {
@@ -7,11 +7,11 @@ use tubular::interpreter::stack::DataStack;
 use tubular::interpreter::droplet::Droplet;
 use tubular::types::coordinate::Coordinate;
 use tubular::types::direction::Direction;
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 
 fn create_test_droplet(id: u64, value: i64) -> Droplet {
     let mut droplet = Droplet::new(id, Coordinate::new(0, 0), Direction::Down);
-    droplet.set_value(TubularBigInt::new(value));
+    droplet.set_value(TubularNumber::new(value));
     droplet
 }
 
@@ -29,14 +29,14 @@ mod stack_properties {
             // Push the value
             ArithmeticOperations::push(&droplet, &mut stack).unwrap();
             assert_eq!(stack.depth(), 1);
-            assert_eq!(stack.peek(), TubularBigInt::new(value));
+            assert_eq!(stack.peek(), TubularNumber::new(value));
 
             // Pop the value
-            droplet.set_value(TubularBigInt::new(999)); // Change droplet value
+            droplet.set_value(TubularNumber::new(999)); // Change droplet value
             ArithmeticOperations::pop(&mut droplet, &mut stack).unwrap();
 
             // Should get original value back
-            assert_eq!(droplet.value, TubularBigInt::new(value));
+            assert_eq!(droplet.value, TubularNumber::new(value));
             assert_eq!(stack.depth(), 0);
         });
     }
@@ -49,7 +49,7 @@ mod stack_properties {
             let droplet = create_test_droplet(0, value);
 
             // Push a value
-            stack.push(TubularBigInt::new(value));
+            stack.push(TubularNumber::new(value));
             assert_eq!(stack.depth(), 1);
 
             // Duplicate it
@@ -57,13 +57,13 @@ mod stack_properties {
 
             // Should now have two identical values
             assert_eq!(stack.depth(), 2);
-            assert_eq!(stack.peek(), TubularBigInt::new(value));
+            assert_eq!(stack.peek(), TubularNumber::new(value));
 
             // Pop both values and verify they're the same
             let first = stack.pop_or_zero();
             let second = stack.pop_or_zero();
-            assert_eq!(first, TubularBigInt::new(value));
-            assert_eq!(second, TubularBigInt::new(value));
+            assert_eq!(first, TubularNumber::new(value));
+            assert_eq!(second, TubularNumber::new(value));
         });
     }
 
@@ -77,14 +77,14 @@ mod stack_properties {
             let mut droplet2 = create_test_droplet(1, 0);
 
             // Stack 1: a then b
-            stack1.push(TubularBigInt::new(a));
-            stack1.push(TubularBigInt::new(b));
+            stack1.push(TubularNumber::new(a));
+            stack1.push(TubularNumber::new(b));
             ArithmeticOperations::add(&mut droplet1, &mut stack1).unwrap();
             let result1 = droplet1.value.clone();
 
             // Stack 2: b then a
-            stack2.push(TubularBigInt::new(b));
-            stack2.push(TubularBigInt::new(a));
+            stack2.push(TubularNumber::new(b));
+            stack2.push(TubularNumber::new(a));
             ArithmeticOperations::add(&mut droplet2, &mut stack2).unwrap();
             let result2 = droplet2.value.clone();
 
@@ -102,14 +102,14 @@ mod stack_properties {
             let mut droplet2 = create_test_droplet(1, 0);
 
             // Stack 1: a then b
-            stack1.push(TubularBigInt::new(a));
-            stack1.push(TubularBigInt::new(b));
+            stack1.push(TubularNumber::new(a));
+            stack1.push(TubularNumber::new(b));
             ArithmeticOperations::multiply(&mut droplet1, &mut stack1).unwrap();
             let result1 = droplet1.value.clone();
 
             // Stack 2: b then a
-            stack2.push(TubularBigInt::new(b));
-            stack2.push(TubularBigInt::new(a));
+            stack2.push(TubularNumber::new(b));
+            stack2.push(TubularNumber::new(a));
             ArithmeticOperations::multiply(&mut droplet2, &mut stack2).unwrap();
             let result2 = droplet2.value.clone();
 
@@ -127,19 +127,19 @@ mod stack_properties {
             let mut droplet2 = create_test_droplet(1, 0);
 
             // Left associative: (A + B) + C
-            stack1.push(TubularBigInt::new(a));
-            stack1.push(TubularBigInt::new(b));
+            stack1.push(TubularNumber::new(a));
+            stack1.push(TubularNumber::new(b));
             ArithmeticOperations::add(&mut droplet1, &mut stack1).unwrap();
             stack1.push(droplet1.value.clone());
-            stack1.push(TubularBigInt::new(c));
+            stack1.push(TubularNumber::new(c));
             ArithmeticOperations::add(&mut droplet1, &mut stack1).unwrap();
             let left_result = droplet1.value.clone();
 
             // Right associative: A + (B + C)
-            stack2.push(TubularBigInt::new(b));
-            stack2.push(TubularBigInt::new(c));
+            stack2.push(TubularNumber::new(b));
+            stack2.push(TubularNumber::new(c));
             ArithmeticOperations::add(&mut droplet2, &mut stack2).unwrap();
-            stack2.push(TubularBigInt::new(a));
+            stack2.push(TubularNumber::new(a));
             stack2.push(droplet2.value.clone());
             ArithmeticOperations::add(&mut droplet2, &mut stack2).unwrap();
             let right_result = droplet2.value.clone();
@@ -156,17 +156,17 @@ mod stack_properties {
             let mut droplet = create_test_droplet(0, 0);
 
             // Test addition identity: A + 0 = A
-            stack.push(TubularBigInt::new(value));
-            stack.push(TubularBigInt::new(0));
+            stack.push(TubularNumber::new(value));
+            stack.push(TubularNumber::new(0));
             ArithmeticOperations::add(&mut droplet, &mut stack).unwrap();
-            assert_eq!(droplet.value, TubularBigInt::new(value));
+            assert_eq!(droplet.value, TubularNumber::new(value));
 
             // Test multiplication identity: A * 1 = A
             stack.clear();
-            stack.push(TubularBigInt::new(value));
-            stack.push(TubularBigInt::new(1));
+            stack.push(TubularNumber::new(value));
+            stack.push(TubularNumber::new(1));
             ArithmeticOperations::multiply(&mut droplet, &mut stack).unwrap();
-            assert_eq!(droplet.value, TubularBigInt::new(value));
+            assert_eq!(droplet.value, TubularNumber::new(value));
         });
     }
 
@@ -179,20 +179,20 @@ mod stack_properties {
             let mut droplet_gt = create_test_droplet(2, 0);
 
             // Test equality property: A = A should be true (1)
-            stack.push(TubularBigInt::new(a));
-            stack.push(TubularBigInt::new(a));
+            stack.push(TubularNumber::new(a));
+            stack.push(TubularNumber::new(a));
             ArithmeticOperations::equals(&mut droplet_eq, &mut stack).unwrap();
-            assert_eq!(droplet_eq.value, TubularBigInt::new(1));
+            assert_eq!(droplet_eq.value, TubularNumber::new(1));
 
             // Test less than property consistency
             stack.clear();
-            stack.push(TubularBigInt::new(a));
-            stack.push(TubularBigInt::new(b));
+            stack.push(TubularNumber::new(a));
+            stack.push(TubularNumber::new(b));
             ArithmeticOperations::less_than(&mut droplet_lt, &mut stack).unwrap();
 
             stack.clear();
-            stack.push(TubularBigInt::new(b));
-            stack.push(TubularBigInt::new(a));
+            stack.push(TubularNumber::new(b));
+            stack.push(TubularNumber::new(a));
             ArithmeticOperations::greater_than(&mut droplet_gt, &mut stack).unwrap();
 
             // A < B should equal B > A
@@ -209,7 +209,7 @@ mod stack_properties {
 
             // Push initial values
             for &value in &initial_values {
-                stack.push(TubularBigInt::new(value));
+                stack.push(TubularNumber::new(value));
             }
 
             // Try to pop one more value than was pushed
@@ -218,7 +218,7 @@ mod stack_properties {
             }
 
             // Should have droplet value as zero (safe default)
-            assert_eq!(droplet.value, TubularBigInt::zero());
+            assert_eq!(droplet.value, TubularNumber::zero());
             assert_eq!(stack.depth(), 0);
         });
     }
@@ -235,7 +235,7 @@ mod stack_properties {
                 match op.as_str() {
                     "push" => {
                         push_count += 1;
-                        stack.push(TubularBigInt::new(42));
+                        stack.push(TubularNumber::new(42));
                     }
                     "pop" => {
                         stack.pop_or_zero();
@@ -266,12 +266,12 @@ mod stack_properties {
             let mut droplet = create_test_droplet(0, 0);
 
             // Push numerator and zero divisor
-            stack.push(TubularBigInt::new(numerator));
-            stack.push(TubularBigInt::new(0));
+            stack.push(TubularNumber::new(numerator));
+            stack.push(TubularNumber::new(0));
 
             // Should not panic and should return zero
             ArithmeticOperations::divide(&mut droplet, &mut stack).unwrap();
-            assert_eq!(droplet.value, TubularBigInt::zero());
+            assert_eq!(droplet.value, TubularNumber::zero());
 
             // Stack should be empty after operation
             assert_eq!(stack.depth(), 0);
@@ -286,12 +286,12 @@ mod stack_properties {
             let mut droplet = create_test_droplet(0, 0);
 
             // Push numerator and zero divisor
-            stack.push(TubularBigInt::new(numerator));
-            stack.push(TubularBigInt::new(0));
+            stack.push(TubularNumber::new(numerator));
+            stack.push(TubularNumber::new(0));
 
             // Should not panic and should return zero
             ArithmeticOperations::modulo(&mut droplet, &mut stack).unwrap();
-            assert_eq!(droplet.value, TubularBigInt::zero());
+            assert_eq!(droplet.value, TubularNumber::zero());
 
             // Stack should be empty after operation
             assert_eq!(stack.depth(), 0);
@@ -306,18 +306,18 @@ mod stack_properties {
             let mut droplet = create_test_droplet(0, 0);
 
             // First: A - B
-            stack.push(TubularBigInt::new(a));
-            stack.push(TubularBigInt::new(b));
+            stack.push(TubularNumber::new(a));
+            stack.push(TubularNumber::new(b));
             ArithmeticOperations::subtract(&mut droplet, &mut stack).unwrap();
             let diff = droplet.value.clone();
 
             // Then: (A - B) + B
             stack.push(diff);
-            stack.push(TubularBigInt::new(b));
+            stack.push(TubularNumber::new(b));
             ArithmeticOperations::add(&mut droplet, &mut stack).unwrap();
 
             // Should equal original A (modulo overflow behavior)
-            let expected = TubularBigInt::new(a);
+            let expected = TubularNumber::new(a);
             assert_eq!(droplet.value, expected);
         });
     }
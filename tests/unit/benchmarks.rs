@@ -56,10 +56,10 @@ mod benchmarks {
     }
 
     fn benchmark_bigint_operations() {
-        use tubular::types::bigint::TubularBigInt;
+        use tubular::types::number::TubularNumber;
 
-        let a = TubularBigInt::new(1000);
-        let b = TubularBigInt::new(42);
+        let a = TubularNumber::new(1000);
+        let b = TubularNumber::new(42);
         let start = Instant::now();
 
         for _ in 0..1_000_000 {
@@ -77,13 +77,13 @@ mod benchmarks {
 
     fn benchmark_stack_operations() {
         use tubular::interpreter::stack::DataStack;
-        use tubular::types::bigint::TubularBigInt;
+        use tubular::types::number::TubularNumber;
 
         let mut stack = DataStack::new();
         let start = Instant::now();
 
         for i in 0..1_000_000 {
-            stack.push(TubularBigInt::new(i));
+            stack.push(TubularNumber::new(i));
             if i % 10 == 0 {
                 stack.pop();
             }
@@ -96,14 +96,14 @@ mod benchmarks {
 
     fn benchmark_memory_operations() {
         use tubular::interpreter::memory::{Reservoir, ReservoirCoordinate};
-        use tubular::types::bigint::TubularBigInt;
+        use tubular::types::number::TubularNumber;
 
         let mut reservoir = Reservoir::new();
         let start = Instant::now();
 
         for i in 0..100_000 {
             let coord = ReservoirCoordinate::new(i % 1000, i / 1000);
-            reservoir.put(coord, TubularBigInt::new(i));
+            reservoir.put(coord, TubularNumber::new(i));
         }
 
         for i in 0..100_000 {
@@ -119,7 +119,7 @@ mod benchmarks {
     fn benchmark_droplet_operations() {
         use tubular::interpreter::droplet::Droplet;
         use tubular::types::{Coordinate, Direction};
-        use tubular::types::bigint::TubularBigInt;
+        use tubular::types::number::TubularNumber;
 
         let mut droplets: Vec<Droplet> = (0..10_000)
             .map(|i| Droplet::new(
@@ -132,7 +132,7 @@ mod benchmarks {
         let start = Instant::now();
 
         for droplet in &mut droplets {
-            droplet.set_value(TubularBigInt::new(42));
+            droplet.set_value(TubularNumber::new(42));
             droplet.move_to(droplet.next_position());
             droplet.set_direction(Direction::Up);
         }
@@ -3,7 +3,7 @@
 use tubular::interpreter::droplet::{Droplet, DropletId};
 use tubular::types::Coordinate;
 use tubular::types::direction::Direction;
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use proptest::prelude::*;
 
 #[cfg(test)]
@@ -21,14 +21,14 @@ mod droplet_tests {
         assert_eq!(droplet.id, id);
         assert_eq!(droplet.position, position);
         assert_eq!(droplet.direction, direction);
-        assert_eq!(droplet.value, TubularBigInt::zero());
+        assert_eq!(droplet.value, TubularNumber::zero());
         assert!(droplet.is_active());
     }
 
     #[test]
     fn test_droplet_with_value() {
         let id = 2;
-        let value = TubularBigInt::new(42);
+        let value = TubularNumber::new(42);
         let position = Coordinate::new(0, 0);
         let direction = Direction::Down;
 
@@ -77,7 +77,7 @@ mod droplet_tests {
     #[test]
     fn test_droplet_set_value() {
         let mut droplet = Droplet::new(1, Coordinate::origin(), Direction::Up);
-        let new_value = TubularBigInt::new(123);
+        let new_value = TubularNumber::new(123);
 
         droplet.set_value(new_value.clone());
         assert_eq!(droplet.value, new_value);
@@ -217,7 +217,7 @@ mod droplet_tests {
         let mut droplet = Droplet::new(1, Coordinate::origin(), Direction::Up);
 
         // Test multiple mutations
-        droplet.set_value(TubularBigInt::new(100));
+        droplet.set_value(TubularNumber::new(100));
         droplet.set_direction(Direction::Right);
         droplet.move_to(Coordinate::new(10, 20));
 
@@ -233,7 +233,7 @@ mod droplet_tests {
 
     #[test]
     fn test_droplet_large_values() {
-        let large_value = TubularBigInt::new(i64::MAX);
+        let large_value = TubularNumber::new(i64::MAX);
         let droplet = Droplet::with_value(
             1,
             large_value.clone(),
@@ -429,7 +429,7 @@ mod benchmarks {
         let start = Instant::now();
 
         for droplet in &mut droplets {
-            droplet.set_value(TubularBigInt::new(42));
+            droplet.set_value(TubularNumber::new(42));
             droplet.move_to(droplet.next_position());
             droplet.set_direction(Direction::Up);
         }
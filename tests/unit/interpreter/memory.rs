@@ -3,7 +3,7 @@
 
 // use tubular::interpreter::memory::{Reservoir, ReservoirCoordinate};
 // use tubular::types::Coordinate;
-// use tubular::types::bigint::TubularBigInt;
+// use tubular::types::number::TubularNumber;
 // use std::collections::HashMap;
 // use proptest::prelude::*;
 
@@ -121,14 +121,14 @@ mod reservoir_tests {
         let coord = ReservoirCoordinate::new(5, 10);
 
         let value = reservoir.get(coord);
-        assert_eq!(value, TubularBigInt::zero());
+        assert_eq!(value, TubularNumber::zero());
     }
 
     #[test]
     fn test_reservoir_put_and_get() {
         let mut reservoir = Reservoir::new();
         let coord = ReservoirCoordinate::new(3, 7);
-        let value = TubularBigInt::new(42);
+        let value = TubularNumber::new(42);
 
         let returned = reservoir.put(coord, value.clone());
         assert_eq!(returned, value);
@@ -141,8 +141,8 @@ mod reservoir_tests {
     fn test_reservoir_put_overwrite() {
         let mut reservoir = Reservoir::new();
         let coord = ReservoirCoordinate::new(1, 2);
-        let value1 = TubularBigInt::new(10);
-        let value2 = TubularBigInt::new(20);
+        let value1 = TubularNumber::new(10);
+        let value2 = TubularNumber::new(20);
 
         reservoir.put(coord, value1);
         reservoir.put(coord, value2.clone());
@@ -157,10 +157,10 @@ mod reservoir_tests {
         let coord1 = ReservoirCoordinate::new(0, 0);
         let coord2 = ReservoirCoordinate::new(1, 1);
 
-        reservoir.put(coord1, TubularBigInt::new(5));
+        reservoir.put(coord1, TubularNumber::new(5));
 
-        assert_eq!(reservoir.get_or_zero(coord1), TubularBigInt::new(5));
-        assert_eq!(reservoir.get_or_zero(coord2), TubularBigInt::zero());
+        assert_eq!(reservoir.get_or_zero(coord1), TubularNumber::new(5));
+        assert_eq!(reservoir.get_or_zero(coord2), TubularNumber::zero());
     }
 
     #[test]
@@ -172,7 +172,7 @@ mod reservoir_tests {
         assert!(!reservoir.contains(&coord1));
         assert!(!reservoir.contains(&coord2));
 
-        reservoir.put(coord1, TubularBigInt::new(42));
+        reservoir.put(coord1, TubularNumber::new(42));
 
         assert!(reservoir.contains(&coord1));
         assert!(!reservoir.contains(&coord2));
@@ -182,7 +182,7 @@ mod reservoir_tests {
     fn test_reservoir_remove() {
         let mut reservoir = Reservoir::new();
         let coord = ReservoirCoordinate::new(2, 4);
-        let value = TubularBigInt::new(100);
+        let value = TubularNumber::new(100);
 
         reservoir.put(coord, value.clone());
         assert!(reservoir.contains(&coord));
@@ -203,7 +203,7 @@ mod reservoir_tests {
         for i in 0..10 {
             reservoir.put(
                 ReservoirCoordinate::new(i, i * 2),
-                TubularBigInt::new(i * 10)
+                TubularNumber::new(i * 10)
             );
         }
 
@@ -225,7 +225,7 @@ mod reservoir_tests {
         for i in 1..=5 {
             reservoir.put(
                 ReservoirCoordinate::new(i, i),
-                TubularBigInt::new(i)
+                TubularNumber::new(i)
             );
             assert_eq!(reservoir.len(), i);
             assert_eq!(reservoir.size(), i);
@@ -241,9 +241,9 @@ mod reservoir_tests {
             ReservoirCoordinate::new(2, 2),
         ];
         let values = vec![
-            TubularBigInt::new(10),
-            TubularBigInt::new(20),
-            TubularBigInt::new(30),
+            TubularNumber::new(10),
+            TubularNumber::new(20),
+            TubularNumber::new(30),
         ];
 
         for (coord, value) in coords.iter().zip(values.iter()) {
@@ -274,9 +274,9 @@ mod reservoir_tests {
     fn test_reservoir_keys_and_values() {
         let mut reservoir = Reservoir::new();
 
-        reservoir.put(ReservoirCoordinate::new(0, 0), TubularBigInt::new(1));
-        reservoir.put(ReservoirCoordinate::new(1, 1), TubularBigInt::new(2));
-        reservoir.put(ReservoirCoordinate::new(2, 2), TubularBigInt::new(3));
+        reservoir.put(ReservoirCoordinate::new(0, 0), TubularNumber::new(1));
+        reservoir.put(ReservoirCoordinate::new(1, 1), TubularNumber::new(2));
+        reservoir.put(ReservoirCoordinate::new(2, 2), TubularNumber::new(3));
 
         let keys: Vec<_> = reservoir.keys().cloned().collect();
         let values: Vec<_> = reservoir.values().cloned().collect();
@@ -288,9 +288,9 @@ mod reservoir_tests {
         assert!(keys.contains(&ReservoirCoordinate::new(1, 1)));
         assert!(keys.contains(&ReservoirCoordinate::new(2, 2)));
 
-        assert!(values.contains(&TubularBigInt::new(1)));
-        assert!(values.contains(&TubularBigInt::new(2)));
-        assert!(values.contains(&TubularBigInt::new(3)));
+        assert!(values.contains(&TubularNumber::new(1)));
+        assert!(values.contains(&TubularNumber::new(2)));
+        assert!(values.contains(&TubularNumber::new(3)));
     }
 
     #[test]
@@ -299,9 +299,9 @@ mod reservoir_tests {
         let center = ReservoirCoordinate::new(5, 5);
 
         // Put values in some adjacent cells
-        reservoir.put(ReservoirCoordinate::new(4, 4), TubularBigInt::new(1));
-        reservoir.put(ReservoirCoordinate::new(6, 5), TubularBigInt::new(2));
-        reservoir.put(ReservoirCoordinate::new(5, 6), TubularBigInt::new(3));
+        reservoir.put(ReservoirCoordinate::new(4, 4), TubularNumber::new(1));
+        reservoir.put(ReservoirCoordinate::new(6, 5), TubularNumber::new(2));
+        reservoir.put(ReservoirCoordinate::new(5, 6), TubularNumber::new(3));
 
         let adjacent = reservoir.get_adjacent(center);
 
@@ -314,15 +314,15 @@ mod reservoir_tests {
 
         for (value, coord) in &adjacent {
             if *coord == ReservoirCoordinate::new(4, 4) {
-                assert_eq!(value, &TubularBigInt::new(1));
+                assert_eq!(value, &TubularNumber::new(1));
                 found_1 = true;
             }
             if *coord == ReservoirCoordinate::new(6, 5) {
-                assert_eq!(value, &TubularBigInt::new(2));
+                assert_eq!(value, &TubularNumber::new(2));
                 found_2 = true;
             }
             if *coord == ReservoirCoordinate::new(5, 6) {
-                assert_eq!(value, &TubularBigInt::new(3));
+                assert_eq!(value, &TubularNumber::new(3));
                 found_3 = true;
             }
         }
@@ -336,7 +336,7 @@ mod reservoir_tests {
             if *coord != ReservoirCoordinate::new(4, 4) &&
                *coord != ReservoirCoordinate::new(6, 5) &&
                *coord != ReservoirCoordinate::new(5, 6) {
-                assert_eq!(value, &TubularBigInt::zero());
+                assert_eq!(value, &TubularNumber::zero());
             }
         }
     }
@@ -349,9 +349,9 @@ mod reservoir_tests {
         assert_eq!(reservoir.bounding_box(), None);
 
         // Add some cells
-        reservoir.put(ReservoirCoordinate::new(0, 0), TubularBigInt::new(1));
-        reservoir.put(ReservoirCoordinate::new(5, 3), TubularBigInt::new(2));
-        reservoir.put(ReservoirCoordinate::new(-2, 7), TubularBigInt::new(3));
+        reservoir.put(ReservoirCoordinate::new(0, 0), TubularNumber::new(1));
+        reservoir.put(ReservoirCoordinate::new(5, 3), TubularNumber::new(2));
+        reservoir.put(ReservoirCoordinate::new(-2, 7), TubularNumber::new(3));
 
         let bbox = reservoir.bounding_box().unwrap();
         assert_eq!(bbox.0, ReservoirCoordinate::new(-2, 0));
@@ -364,9 +364,9 @@ mod reservoir_tests {
 
         assert_eq!(reservoir.count_non_zero(), 0);
 
-        reservoir.put(ReservoirCoordinate::new(0, 0), TubularBigInt::new(1));
-        reservoir.put(ReservoirCoordinate::new(1, 0), TubularBigInt::zero());
-        reservoir.put(ReservoirCoordinate::new(2, 0), TubularBigInt::new(5));
+        reservoir.put(ReservoirCoordinate::new(0, 0), TubularNumber::new(1));
+        reservoir.put(ReservoirCoordinate::new(1, 0), TubularNumber::zero());
+        reservoir.put(ReservoirCoordinate::new(2, 0), TubularNumber::new(5));
 
         assert_eq!(reservoir.count_non_zero(), 2);
         assert_eq!(reservoir.len(), 3); // Still counts zero values
@@ -376,10 +376,10 @@ mod reservoir_tests {
     fn test_reservoir_filter_zero_values() {
         let mut reservoir = Reservoir::new();
 
-        reservoir.put(ReservoirCoordinate::new(0, 0), TubularBigInt::new(1));
-        reservoir.put(ReservoirCoordinate::new(1, 0), TubularBigInt::zero());
-        reservoir.put(ReservoirCoordinate::new(2, 0), TubularBigInt::new(5));
-        reservoir.put(ReservoirCoordinate::new(3, 0), TubularBigInt::zero());
+        reservoir.put(ReservoirCoordinate::new(0, 0), TubularNumber::new(1));
+        reservoir.put(ReservoirCoordinate::new(1, 0), TubularNumber::zero());
+        reservoir.put(ReservoirCoordinate::new(2, 0), TubularNumber::new(5));
+        reservoir.put(ReservoirCoordinate::new(3, 0), TubularNumber::zero());
 
         assert_eq!(reservoir.len(), 4);
         assert_eq!(reservoir.count_non_zero(), 2);
@@ -405,9 +405,9 @@ mod reservoir_tests {
     fn test_reservoir_display() {
         let mut reservoir = Reservoir::new();
 
-        reservoir.put(ReservoirCoordinate::new(0, 0), TubularBigInt::new(1));
-        reservoir.put(ReservoirCoordinate::new(1, 0), TubularBigInt::new(2));
-        reservoir.put(ReservoirCoordinate::new(0, 1), TubularBigInt::new(3));
+        reservoir.put(ReservoirCoordinate::new(0, 0), TubularNumber::new(1));
+        reservoir.put(ReservoirCoordinate::new(1, 0), TubularNumber::new(2));
+        reservoir.put(ReservoirCoordinate::new(0, 1), TubularNumber::new(3));
 
         let display = format!("{}", reservoir);
         assert!(display.contains("Reservoir"));
@@ -420,13 +420,13 @@ mod reservoir_tests {
     #[test]
     fn test_reservoir_from_hashmap() {
         let mut data = HashMap::new();
-        data.insert(ReservoirCoordinate::new(0, 0), TubularBigInt::new(10));
-        data.insert(ReservoirCoordinate::new(1, 1), TubularBigInt::new(20));
+        data.insert(ReservoirCoordinate::new(0, 0), TubularNumber::new(10));
+        data.insert(ReservoirCoordinate::new(1, 1), TubularNumber::new(20));
 
         let reservoir: Reservoir = data.into();
         assert_eq!(reservoir.len(), 2);
-        assert_eq!(reservoir.get(ReservoirCoordinate::new(0, 0)), TubularBigInt::new(10));
-        assert_eq!(reservoir.get(ReservoirCoordinate::new(1, 1)), TubularBigInt::new(20));
+        assert_eq!(reservoir.get(ReservoirCoordinate::new(0, 0)), TubularNumber::new(10));
+        assert_eq!(reservoir.get(ReservoirCoordinate::new(1, 1)), TubularNumber::new(20));
     }
 
     #[test]
@@ -436,11 +436,11 @@ mod reservoir_tests {
         let large_coord = ReservoirCoordinate::new(isize::MAX, isize::MIN);
         let small_coord = ReservoirCoordinate::new(isize::MIN, isize::MAX);
 
-        reservoir.put(large_coord, TubularBigInt::new(100));
-        reservoir.put(small_coord, TubularBigInt::new(200));
+        reservoir.put(large_coord, TubularNumber::new(100));
+        reservoir.put(small_coord, TubularNumber::new(200));
 
-        assert_eq!(reservoir.get(large_coord), TubularBigInt::new(100));
-        assert_eq!(reservoir.get(small_coord), TubularBigInt::new(200));
+        assert_eq!(reservoir.get(large_coord), TubularNumber::new(100));
+        assert_eq!(reservoir.get(small_coord), TubularNumber::new(200));
     }
 
     #[test]
@@ -448,11 +448,11 @@ mod reservoir_tests {
         let mut reservoir = Reservoir::new();
 
         // Test with negative coordinates
-        reservoir.put(ReservoirCoordinate::new(-5, -3), TubularBigInt::new(42));
-        reservoir.put(ReservoirCoordinate::new(5, 3), TubularBigInt::new(24));
+        reservoir.put(ReservoirCoordinate::new(-5, -3), TubularNumber::new(42));
+        reservoir.put(ReservoirCoordinate::new(5, 3), TubularNumber::new(24));
 
-        assert_eq!(reservoir.get(ReservoirCoordinate::new(-5, -3)), TubularBigInt::new(42));
-        assert_eq!(reservoir.get(ReservoirCoordinate::new(5, 3)), TubularBigInt::new(24));
+        assert_eq!(reservoir.get(ReservoirCoordinate::new(-5, -3)), TubularNumber::new(42));
+        assert_eq!(reservoir.get(ReservoirCoordinate::new(5, 3)), TubularNumber::new(24));
 
         // Test bounding box includes both
         let bbox = reservoir.bounding_box().unwrap();
@@ -466,7 +466,7 @@ mod reservoir_tests {
 
         // Test at coordinate boundaries
         let edge_coord = ReservoirCoordinate::new(isize::MAX, isize::MAX);
-        reservoir.put(edge_coord.clone(), TubularBigInt::new(999));
+        reservoir.put(edge_coord.clone(), TubularNumber::new(999));
 
         let adjacent = reservoir.get_adjacent(edge_coord.clone());
         // Should wrap around (isize overflow behavior)
@@ -475,7 +475,7 @@ mod reservoir_tests {
         // All adjacent cells should be zero since we only put one value
         for (value, coord) in &adjacent {
             if *coord != edge_coord {
-                assert_eq!(value, &TubularBigInt::zero());
+                assert_eq!(value, &TubularNumber::zero());
             }
         }
     }
@@ -507,7 +507,7 @@ proptest! {
 
         for (x, y, value) in coords {
             let coord = ReservoirCoordinate::new(x, y);
-            let big_value = TubularBigInt::new(value);
+            let big_value = TubularNumber::new(value);
             reservoir.put(coord.clone(), big_value.clone());
             test_data.push((coord, big_value));
         }
@@ -540,7 +540,7 @@ proptest! {
         ];
 
         for (i, &coord) in adjacent_coords.iter().enumerate() {
-            reservoir.put(coord.clone(), TubularBigInt::new(i as i64));
+            reservoir.put(coord.clone(), TubularNumber::new(i as i64));
         }
 
         let adjacent = reservoir.get_adjacent(center);
@@ -553,7 +553,7 @@ proptest! {
             let mut found = false;
             for (value, actual_coord) in &adjacent {
                 if actual_coord == &expected_coord {
-                    assert_eq!(value, &TubularBigInt::new(i as i64));
+                    assert_eq!(value, &TubularNumber::new(i as i64));
                     found = true;
                     break;
                 }
@@ -576,7 +576,7 @@ proptest! {
             .collect();
 
         for coord in &coordinate_list {
-            reservoir.put(coord.clone(), TubularBigInt::new(1));
+            reservoir.put(coord.clone(), TubularNumber::new(1));
         }
 
         let bbox = reservoir.bounding_box();
@@ -626,7 +626,7 @@ proptest! {
 
         for (x, y, value) in &data {
             let coord = ReservoirCoordinate::new(*x, *y);
-            let big_value = TubularBigInt::new(*value);
+            let big_value = TubularNumber::new(*value);
             if !big_value.is_zero() {
                 non_zero_count += 1;
             }
@@ -661,7 +661,7 @@ mod benchmarks {
         // Put operations
         for i in 0..100_000 {
             let coord = ReservoirCoordinate::new(i as isize, (i * 2) as isize);
-            let value = TubularBigInt::new(i);
+            let value = TubularNumber::new(i);
             reservoir.put(coord, value);
         }
 
@@ -688,7 +688,7 @@ mod benchmarks {
         for x in 0..100 {
             for y in 0..100 {
                 let coord = ReservoirCoordinate::new(x, y);
-                let value = TubularBigInt::new(x * 100 + y);
+                let value = TubularNumber::new(x * 100 + y);
                 reservoir.put(coord, value);
             }
         }
@@ -714,12 +714,12 @@ mod benchmarks {
                 (i % 1000) as isize,
                 (i / 1000) as isize
             );
-            let value = TubularBigInt::new(i);
+            let value = TubularNumber::new(i);
             reservoir.put(coord, value);
         }
 
         let start = Instant::now();
-        let mut sum = TubularBigInt::zero();
+        let mut sum = TubularNumber::zero();
         for (_coord, value) in reservoir.iter() {
             sum = sum + value.clone();
         }
@@ -739,7 +739,7 @@ mod benchmarks {
             let x = (i * 1009) % 2000 - 1000; // Spread across -1000 to 1000
             let y = (i * 2017) % 2000 - 1000;
             let coord = ReservoirCoordinate::new(x, y);
-            let value = TubularBigInt::new(i);
+            let value = TubularNumber::new(i);
             reservoir.put(coord, value);
         }
 
@@ -1,7 +1,7 @@
 //! Unit tests for the DataStack type
 
 use tubular::interpreter::stack::DataStack;
-use tubular::types::bigint::TubularBigInt;
+use tubular::types::number::TubularNumber;
 use proptest::prelude::*;
 
 #[cfg(test)]
@@ -28,7 +28,7 @@ mod stack_tests {
     #[test]
     fn test_data_stack_push() {
         let mut stack = DataStack::new();
-        let value = TubularBigInt::new(42);
+        let value = TubularNumber::new(42);
 
         stack.push(value.clone());
         assert!(!stack.is_empty());
@@ -43,19 +43,19 @@ mod stack_tests {
         let mut stack = DataStack::new();
 
         for i in 1..=5 {
-            stack.push(TubularBigInt::new(i));
+            stack.push(TubularNumber::new(i));
         }
 
         assert_eq!(stack.len(), 5);
         assert_eq!(stack.depth(), 5);
         assert_eq!(stack.max_depth_reached(), 5);
-        assert_eq!(stack.peek(), TubularBigInt::new(5));
+        assert_eq!(stack.peek(), TubularNumber::new(5));
     }
 
     #[test]
     fn test_data_stack_pop() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(42));
+        stack.push(TubularNumber::new(42));
 
         let popped = stack.pop();
         assert_eq!(popped.to_i64(), Some(42));
@@ -68,21 +68,21 @@ mod stack_tests {
         let mut stack = DataStack::new();
 
         let popped = stack.pop();
-        assert_eq!(popped, TubularBigInt::zero());
+        assert_eq!(popped, TubularNumber::zero());
         assert!(stack.is_empty());
     }
 
     #[test]
     fn test_data_stack_pop_multiple() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
 
         assert_eq!(stack.pop().to_i64(), Some(3));
         assert_eq!(stack.pop().to_i64(), Some(2));
         assert_eq!(stack.pop().to_i64(), Some(1));
-        assert_eq!(stack.pop(), TubularBigInt::zero()); // Empty now
+        assert_eq!(stack.pop(), TubularNumber::zero()); // Empty now
     }
 
     #[test]
@@ -90,48 +90,48 @@ mod stack_tests {
         let mut stack = DataStack::new();
 
         // Empty stack should return zero
-        assert_eq!(stack.peek(), TubularBigInt::zero());
+        assert_eq!(stack.peek(), TubularNumber::zero());
 
-        stack.push(TubularBigInt::new(42));
-        assert_eq!(stack.peek(), TubularBigInt::new(42));
+        stack.push(TubularNumber::new(42));
+        assert_eq!(stack.peek(), TubularNumber::new(42));
 
-        stack.push(TubularBigInt::new(100));
-        assert_eq!(stack.peek(), TubularBigInt::new(100));
+        stack.push(TubularNumber::new(100));
+        assert_eq!(stack.peek(), TubularNumber::new(100));
 
         // Peek shouldn't modify the stack
         assert_eq!(stack.len(), 2);
-        assert_eq!(stack.peek(), TubularBigInt::new(100));
+        assert_eq!(stack.peek(), TubularNumber::new(100));
     }
 
     #[test]
     fn test_data_stack_peek_depth() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
-        stack.push(TubularBigInt::new(4));
-        stack.push(TubularBigInt::new(5));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
+        stack.push(TubularNumber::new(4));
+        stack.push(TubularNumber::new(5));
 
-        assert_eq!(stack.peek_depth(0), TubularBigInt::new(5)); // Top
-        assert_eq!(stack.peek_depth(1), TubularBigInt::new(4));
-        assert_eq!(stack.peek_depth(2), TubularBigInt::new(3));
-        assert_eq!(stack.peek_depth(4), TubularBigInt::new(1)); // Bottom
+        assert_eq!(stack.peek_depth(0), TubularNumber::new(5)); // Top
+        assert_eq!(stack.peek_depth(1), TubularNumber::new(4));
+        assert_eq!(stack.peek_depth(2), TubularNumber::new(3));
+        assert_eq!(stack.peek_depth(4), TubularNumber::new(1)); // Bottom
 
         // Out of bounds should return zero
-        assert_eq!(stack.peek_depth(5), TubularBigInt::zero());
-        assert_eq!(stack.peek_depth(10), TubularBigInt::zero());
+        assert_eq!(stack.peek_depth(5), TubularNumber::zero());
+        assert_eq!(stack.peek_depth(10), TubularNumber::zero());
 
         // Empty stack
         let empty_stack = DataStack::new();
-        assert_eq!(empty_stack.peek_depth(0), TubularBigInt::zero());
+        assert_eq!(empty_stack.peek_depth(0), TubularNumber::zero());
     }
 
     #[test]
     fn test_data_stack_clear() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
 
         assert_eq!(stack.len(), 3);
 
@@ -145,15 +145,15 @@ mod stack_tests {
     #[test]
     fn test_data_stack_truncate() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
-        stack.push(TubularBigInt::new(4));
-        stack.push(TubularBigInt::new(5));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
+        stack.push(TubularNumber::new(4));
+        stack.push(TubularNumber::new(5));
 
         stack.truncate(3);
         assert_eq!(stack.len(), 3);
-        assert_eq!(stack.peek(), TubularBigInt::new(3));
+        assert_eq!(stack.peek(), TubularNumber::new(3));
 
         // Truncate to larger size (should do nothing)
         stack.truncate(10);
@@ -172,22 +172,22 @@ mod stack_tests {
         assert!(!stack.swap_top_two());
 
         // One element
-        stack.push(TubularBigInt::new(1));
+        stack.push(TubularNumber::new(1));
         assert!(!stack.swap_top_two());
         assert_eq!(stack.len(), 1);
 
         // Two elements
-        stack.push(TubularBigInt::new(2));
+        stack.push(TubularNumber::new(2));
         assert!(stack.swap_top_two());
-        assert_eq!(stack.peek(), TubularBigInt::new(1));
-        assert_eq!(stack.peek_depth(1), TubularBigInt::new(2));
+        assert_eq!(stack.peek(), TubularNumber::new(1));
+        assert_eq!(stack.peek_depth(1), TubularNumber::new(2));
 
         // More elements
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(3));
         assert!(stack.swap_top_two());
-        assert_eq!(stack.peek(), TubularBigInt::new(2));
-        assert_eq!(stack.peek_depth(1), TubularBigInt::new(3));
-        assert_eq!(stack.peek_depth(2), TubularBigInt::new(1));
+        assert_eq!(stack.peek(), TubularNumber::new(2));
+        assert_eq!(stack.peek_depth(1), TubularNumber::new(3));
+        assert_eq!(stack.peek_depth(2), TubularNumber::new(1));
     }
 
     #[test]
@@ -199,28 +199,28 @@ mod stack_tests {
         assert!(stack.is_empty());
 
         // One element
-        stack.push(TubularBigInt::new(42));
+        stack.push(TubularNumber::new(42));
         assert!(stack.duplicate());
         assert_eq!(stack.len(), 2);
-        assert_eq!(stack.peek(), TubularBigInt::new(42));
-        assert_eq!(stack.peek_depth(1), TubularBigInt::new(42));
+        assert_eq!(stack.peek(), TubularNumber::new(42));
+        assert_eq!(stack.peek_depth(1), TubularNumber::new(42));
 
         // Multiple elements
-        stack.push(TubularBigInt::new(100));
+        stack.push(TubularNumber::new(100));
         assert!(stack.duplicate());
         assert_eq!(stack.len(), 4);
-        assert_eq!(stack.peek(), TubularBigInt::new(100));
-        assert_eq!(stack.peek_depth(1), TubularBigInt::new(100));
+        assert_eq!(stack.peek(), TubularNumber::new(100));
+        assert_eq!(stack.peek_depth(1), TubularNumber::new(100));
     }
 
     #[test]
     fn test_data_stack_pop_n() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
-        stack.push(TubularBigInt::new(4));
-        stack.push(TubularBigInt::new(5));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
+        stack.push(TubularNumber::new(4));
+        stack.push(TubularNumber::new(5));
 
         let popped = stack.pop_n(3);
         assert_eq!(popped.len(), 3);
@@ -228,7 +228,7 @@ mod stack_tests {
         assert_eq!(popped[1].to_i64(), Some(4));
         assert_eq!(popped[2].to_i64(), Some(3));
         assert_eq!(stack.len(), 2);
-        assert_eq!(stack.peek(), TubularBigInt::new(2));
+        assert_eq!(stack.peek(), TubularNumber::new(2));
 
         // Pop more than available
         let more_popped = stack.pop_n(10);
@@ -237,7 +237,7 @@ mod stack_tests {
             assert!(!val.is_zero());
         }
         for val in &more_popped[2..] {
-            assert_eq!(val, &TubularBigInt::zero());
+            assert_eq!(val, &TubularNumber::zero());
         }
         assert!(stack.is_empty());
     }
@@ -246,16 +246,16 @@ mod stack_tests {
     fn test_data_stack_push_n() {
         let mut stack = DataStack::new();
         let values = vec![
-            TubularBigInt::new(1),
-            TubularBigInt::new(2),
-            TubularBigInt::new(3),
+            TubularNumber::new(1),
+            TubularNumber::new(2),
+            TubularNumber::new(3),
         ];
 
         stack.push_n(values.clone());
         assert_eq!(stack.len(), 3);
-        assert_eq!(stack.peek(), TubularBigInt::new(3));
-        assert_eq!(stack.peek_depth(1), TubularBigInt::new(2));
-        assert_eq!(stack.peek_depth(2), TubularBigInt::new(1));
+        assert_eq!(stack.peek(), TubularNumber::new(3));
+        assert_eq!(stack.peek_depth(1), TubularNumber::new(2));
+        assert_eq!(stack.peek_depth(2), TubularNumber::new(1));
 
         // Empty vector
         stack.push_n(Vec::new());
@@ -265,41 +265,41 @@ mod stack_tests {
     #[test]
     fn test_data_stack_get() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
 
-        assert_eq!(stack.get(0), Some(&TubularBigInt::new(1)));
-        assert_eq!(stack.get(1), Some(&TubularBigInt::new(2)));
-        assert_eq!(stack.get(2), Some(&TubularBigInt::new(3)));
+        assert_eq!(stack.get(0), Some(&TubularNumber::new(1)));
+        assert_eq!(stack.get(1), Some(&TubularNumber::new(2)));
+        assert_eq!(stack.get(2), Some(&TubularNumber::new(3)));
         assert_eq!(stack.get(3), None);
     }
 
     #[test]
     fn test_data_stack_get_from_top() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
 
-        assert_eq!(stack.get_from_top(0), Some(&TubularBigInt::new(3)));
-        assert_eq!(stack.get_from_top(1), Some(&TubularBigInt::new(2)));
-        assert_eq!(stack.get_from_top(2), Some(&TubularBigInt::new(1)));
+        assert_eq!(stack.get_from_top(0), Some(&TubularNumber::new(3)));
+        assert_eq!(stack.get_from_top(1), Some(&TubularNumber::new(2)));
+        assert_eq!(stack.get_from_top(2), Some(&TubularNumber::new(1)));
         assert_eq!(stack.get_from_top(3), None);
     }
 
     #[test]
     fn test_data_stack_as_slice() {
         let mut stack = DataStack::new();
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
 
         let slice = stack.as_slice();
         assert_eq!(slice.len(), 3);
-        assert_eq!(slice[0], TubularBigInt::new(1));
-        assert_eq!(slice[1], TubularBigInt::new(2));
-        assert_eq!(slice[2], TubularBigInt::new(3));
+        assert_eq!(slice[0], TubularNumber::new(1));
+        assert_eq!(slice[1], TubularNumber::new(2));
+        assert_eq!(slice[2], TubularNumber::new(3));
     }
 
     #[test]
@@ -309,13 +309,13 @@ mod stack_tests {
         assert!(stack.is_within_limit(0));
         assert!(stack.is_within_limit(10));
 
-        stack.push(TubularBigInt::new(1));
+        stack.push(TubularNumber::new(1));
         assert!(stack.is_within_limit(1));
         assert!(stack.is_within_limit(10));
         assert!(!stack.is_within_limit(0));
 
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
         assert!(stack.is_within_limit(3));
         assert!(!stack.is_within_limit(2));
     }
@@ -326,7 +326,7 @@ mod stack_tests {
 
         // Push some values
         for i in 1..=5 {
-            stack.push(TubularBigInt::new(i));
+            stack.push(TubularNumber::new(i));
         }
         assert_eq!(stack.max_depth_reached(), 5);
 
@@ -339,17 +339,17 @@ mod stack_tests {
         assert_eq!(stack.max_depth_reached(), 5);
 
         // Push more values (but don't exceed previous max)
-        stack.push(TubularBigInt::new(6));
-        stack.push(TubularBigInt::new(7));
+        stack.push(TubularNumber::new(6));
+        stack.push(TubularNumber::new(7));
         assert_eq!(stack.len(), 4);
         assert_eq!(stack.max_depth_reached(), 5);
 
         // Exceed previous max
-        stack.push(TubularBigInt::new(8));
+        stack.push(TubularNumber::new(8));
         assert_eq!(stack.len(), 5);
         assert_eq!(stack.max_depth_reached(), 5);
 
-        stack.push(TubularBigInt::new(9));
+        stack.push(TubularNumber::new(9));
         assert_eq!(stack.len(), 6);
         assert_eq!(stack.max_depth_reached(), 6);
     }
@@ -368,11 +368,11 @@ mod stack_tests {
 
         assert_eq!(format!("{}", stack), "[]");
 
-        stack.push(TubularBigInt::new(1));
+        stack.push(TubularNumber::new(1));
         assert_eq!(format!("{}", stack), "[1]");
 
-        stack.push(TubularBigInt::new(2));
-        stack.push(TubularBigInt::new(3));
+        stack.push(TubularNumber::new(2));
+        stack.push(TubularNumber::new(3));
         let display = format!("{}", stack);
         assert!(display.contains("["));
         assert!(display.contains("]"));
@@ -384,9 +384,9 @@ mod stack_tests {
     #[test]
     fn test_data_stack_from_vec_bigint() {
         let values = vec![
-            TubularBigInt::new(1),
-            TubularBigInt::new(2),
-            TubularBigInt::new(3),
+            TubularNumber::new(1),
+            TubularNumber::new(2),
+            TubularNumber::new(3),
         ];
 
         let stack: DataStack = values.clone().into();
@@ -402,20 +402,20 @@ mod stack_tests {
         let stack: DataStack = values.clone().into();
         assert_eq!(stack.len(), 5);
         assert_eq!(stack.max_depth_reached(), 5);
-        assert_eq!(stack.peek(), TubularBigInt::new(5));
-        assert_eq!(stack.get(0), Some(&TubularBigInt::new(1)));
+        assert_eq!(stack.peek(), TubularNumber::new(5));
+        assert_eq!(stack.get(0), Some(&TubularNumber::new(1)));
     }
 
     #[test]
     fn test_data_stack_large_values() {
         let mut stack = DataStack::new();
-        let large_value = TubularBigInt::new(i64::MAX);
+        let large_value = TubularNumber::new(i64::MAX);
 
         stack.push(large_value.clone());
         assert_eq!(stack.peek(), large_value);
 
-        stack.push(TubularBigInt::new(i64::MIN));
-        assert_eq!(stack.peek(), TubularBigInt::new(i64::MIN));
+        stack.push(TubularNumber::new(i64::MIN));
+        assert_eq!(stack.peek(), TubularNumber::new(i64::MIN));
     }
 
     #[test]
@@ -423,14 +423,14 @@ mod stack_tests {
         let mut stack = DataStack::new();
 
         // Test peek_depth 0 on empty stack
-        assert_eq!(stack.peek_depth(0), TubularBigInt::zero());
+        assert_eq!(stack.peek_depth(0), TubularNumber::zero());
 
         // Test swap_top_two with exactly two elements
-        stack.push(TubularBigInt::new(1));
-        stack.push(TubularBigInt::new(2));
+        stack.push(TubularNumber::new(1));
+        stack.push(TubularNumber::new(2));
         assert!(stack.swap_top_two());
-        assert_eq!(stack.peek(), TubularBigInt::new(1));
-        assert_eq!(stack.peek_depth(1), TubularBigInt::new(2));
+        assert_eq!(stack.peek(), TubularNumber::new(1));
+        assert_eq!(stack.peek_depth(1), TubularNumber::new(2));
 
         // Test truncate to current size
         stack.truncate(2);
@@ -450,9 +450,9 @@ proptest! {
         values in prop::collection::vec(any::<i64>(), 0..100)
     ) {
         let mut stack = DataStack::new();
-        let bigint_values: Vec<TubularBigInt> = values
+        let bigint_values: Vec<TubularNumber> = values
             .iter()
-            .map(|&v| TubularBigInt::new(v))
+            .map(|&v| TubularNumber::new(v))
             .collect();
 
         // Push all values
@@ -467,7 +467,7 @@ proptest! {
         }
 
         // Should be reverse of input
-        let expected: Vec<TubularBigInt> = bigint_values.iter().rev().cloned().collect();
+        let expected: Vec<TubularNumber> = bigint_values.iter().rev().cloned().collect();
         assert_eq!(popped_values, expected);
     }
 
@@ -476,9 +476,9 @@ proptest! {
         values in prop::collection::vec(any::<i64>(), 1..50)
     ) {
         let mut stack = DataStack::new();
-        let bigint_values: Vec<TubularBigInt> = values
+        let bigint_values: Vec<TubularNumber> = values
             .iter()
-            .map(|&v| TubularBigInt::new(v))
+            .map(|&v| TubularNumber::new(v))
             .collect();
 
         for value in &bigint_values {
@@ -494,7 +494,7 @@ proptest! {
 
         // Test invalid depths
         for depth in stack.len()..stack.len() + 5 {
-            assert_eq!(stack.peek_depth(depth), TubularBigInt::zero());
+            assert_eq!(stack.peek_depth(depth), TubularNumber::zero());
         }
     }
 
@@ -510,7 +510,7 @@ proptest! {
 
         for should_push in push_operations {
             if should_push {
-                stack.push(TubularBigInt::new(1));
+                stack.push(TubularNumber::new(1));
                 max_seen = max_seen.max(stack.len());
             } else {
                 stack.pop();
@@ -526,7 +526,7 @@ proptest! {
     ) {
         let mut stack = DataStack::new();
         for &value in &values {
-            stack.push(TubularBigInt::new(value));
+            stack.push(TubularNumber::new(value));
         }
 
         let original_len = stack.len();
@@ -553,7 +553,7 @@ proptest! {
     ) {
         let mut stack = DataStack::new();
         for &value in &values {
-            stack.push(TubularBigInt::new(value));
+            stack.push(TubularNumber::new(value));
         }
 
         let original_len = stack.len();
@@ -585,7 +585,7 @@ mod benchmarks {
         let start = Instant::now();
 
         for i in 0..1_000_000 {
-            stack.push(TubularBigInt::new(i));
+            stack.push(TubularNumber::new(i));
         }
 
         let push_duration = start.elapsed();
@@ -605,7 +605,7 @@ mod benchmarks {
     fn benchmark_stack_peek_operations() {
         let mut stack = DataStack::new();
         for i in 0..100_000 {
-            stack.push(TubularBigInt::new(i));
+            stack.push(TubularNumber::new(i));
         }
 
         let start = Instant::now();
@@ -620,8 +620,8 @@ mod benchmarks {
 
     #[test]
     fn benchmark_stack_bulk_operations() {
-        let values: Vec<TubularBigInt> = (0..10_000)
-            .map(TubularBigInt::new)
+        let values: Vec<TubularNumber> = (0..10_000)
+            .map(TubularNumber::new)
             .collect();
 
         let mut stack = DataStack::new();
@@ -643,7 +643,7 @@ mod benchmarks {
     fn benchmark_stack_access_methods() {
         let mut stack = DataStack::new();
         for i in 0..100_000 {
-            stack.push(TubularBigInt::new(i));
+            stack.push(TubularNumber::new(i));
         }
 
         let start = Instant::now();
@@ -661,7 +661,7 @@ mod benchmarks {
     fn benchmark_stack_complex_operations() {
         let mut stack = DataStack::new();
         for i in 0..10_000 {
-            stack.push(TubularBigInt::new(i));
+            stack.push(TubularNumber::new(i));
         }
 
         let start = Instant::now();
@@ -671,7 +671,7 @@ mod benchmarks {
             stack.truncate(stack.len() / 2);
             // Rebuild stack
             while stack.len() < 10_000 {
-                stack.push(TubularBigInt::new(42));
+                stack.push(TubularNumber::new(42));
             }
         }
         let duration = start.elapsed();
@@ -50,11 +50,11 @@ proptest! {
         b in any::<i64>(),
         c in any::<i64>()
     ) {
-        use tubular::types::bigint::TubularBigInt;
+        use tubular::types::number::TubularNumber;
 
-        let big_a = TubularBigInt::new(a);
-        let big_b = TubularBigInt::new(b);
-        let big_c = TubularBigInt::new(c);
+        let big_a = TubularNumber::new(a);
+        let big_b = TubularNumber::new(b);
+        let big_c = TubularNumber::new(c);
 
         // Test commutativity of addition and multiplication
         assert_eq!(big_a.clone() + big_b.clone(), big_b.clone() + big_a.clone());
@@ -82,12 +82,12 @@ proptest! {
         values in prop::collection::vec(any::<i64>(), 0..100)
     ) {
         use tubular::interpreter::stack::DataStack;
-        use tubular::types::bigint::TubularBigInt;
+        use tubular::types::number::TubularNumber;
 
         let mut stack = DataStack::new();
-        let bigint_values: Vec<TubularBigInt> = values
+        let bigint_values: Vec<TubularNumber> = values
             .iter()
-            .map(|&v| TubularBigInt::new(v))
+            .map(|&v| TubularNumber::new(v))
             .collect();
 
         // Push all values
@@ -101,7 +101,7 @@ proptest! {
             popped.push(stack.pop());
         }
 
-        let expected: Vec<TubularBigInt> = bigint_values.iter().rev().cloned().collect();
+        let expected: Vec<TubularNumber> = bigint_values.iter().rev().cloned().collect();
         assert_eq!(popped, expected);
     }
 
@@ -113,14 +113,14 @@ proptest! {
         )
     ) {
         use tubular::interpreter::memory::{Reservoir, ReservoirCoordinate};
-        use tubular::types::bigint::TubularBigInt;
+        use tubular::types::number::TubularNumber;
 
         let mut reservoir = Reservoir::new();
         let mut test_data = Vec::new();
 
         for (x, y, value) in operations {
             let coord = ReservoirCoordinate::new(x, y);
-            let big_value = TubularBigInt::new(value);
+            let big_value = TubularNumber::new(value);
             reservoir.put(coord.clone(), big_value.clone());
             test_data.push((coord, big_value));
         }